@@ -0,0 +1,51 @@
+//! Generates the emoji keyword table from the CLDR annotations in
+//! `assets/emoji/annotations-en.xml`.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+const ANNOTATIONS: &str = "assets/emoji/annotations-en.xml";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", ANNOTATIONS);
+
+    let xml = fs::read_to_string(ANNOTATIONS).expect("emoji annotations missing");
+    // Keyed by the emoji without variation selectors, sorted for binary search
+    let mut keywords: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for line in xml.lines() {
+        let line = line.trim();
+        // Text-to-speech entries repeat the name, which comes from the emojis crate
+        if !line.starts_with("<annotation cp=") || line.contains("type=\"tts\"") {
+            continue;
+        }
+        let Some((cp, rest)) = line["<annotation cp=\"".len()..].split_once("\">") else {
+            continue;
+        };
+        let Some(text) = rest.strip_suffix("</annotation>") else {
+            continue;
+        };
+        let cp = unescape(cp).replace('\u{FE0F}', "");
+        let text = unescape(text);
+        let words = text.split('|').map(|w| w.trim().to_lowercase()).filter(|w| !w.is_empty());
+        keywords.entry(cp).or_default().extend(words);
+    }
+
+    let mut table = String::from("static KEYWORDS: &[(&str, &[&str])] = &[\n");
+    for (cp, words) in &keywords {
+        table.push_str(&format!("    ({:?}, &{:?}),\n", cp, words));
+    }
+    table.push_str("];\n");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("emoji_keywords.rs");
+    fs::write(out, table).expect("failed to write the emoji keyword table");
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
@@ -1,7 +1,21 @@
+use crate::core::{Item, ItemType};
 use anyhow::Result;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Profile whose config and data this process uses, chosen at startup.
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Set by `--kiosk`, on top of the `kiosk` config option.
+static KIOSK: AtomicBool = AtomicBool::new(false);
+
+/// The profile stored directly in the config directory.
+pub const DEFAULT_PROFILE: &str = "default";
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
@@ -15,15 +29,830 @@ pub struct Config {
     pub clipboard_history_size: Option<usize>,
     #[serde(default)]
     pub max_recent_files: Option<usize>,
+    /// Drag source helper for Ctrl+D on files (default: ripdrag or dragon-drop)
+    #[serde(default)]
+    pub drag_tool: Option<String>,
+    /// Type copied clipboard entries, snippets and emojis into the previously
+    /// focused window (default: off)
+    #[serde(default)]
+    pub paste_on_select: Option<bool>,
+    /// Directories scanned for `.AppImage` files (`~` is expanded)
+    #[serde(default)]
+    pub appimage_dirs: Option<Vec<String>>,
+    /// Folder for notes, todos and snippets, such as a Syncthing, Dropbox or
+    /// Nextcloud folder (`~` is expanded)
+    #[serde(default)]
+    pub sync_dir: Option<String>,
+    /// Window border color while this profile is active (default: the
+    /// theme's accent, for profiles other than the default one)
+    #[serde(default)]
+    pub profile_color: Option<String>,
+    /// Search and launch only: no killing, deleting, connecting or
+    /// installing, and no clipboard, passwords or notes (default: off)
+    #[serde(default)]
+    pub kiosk: Option<bool>,
+    /// Record how long each mode's lookups take and how often they fail,
+    /// for the `stats` and `doctor` modes; kept locally (default: off)
+    #[serde(default)]
+    pub insights: Option<bool>,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    #[serde(default)]
+    pub accessibility: AccessibilityConfig,
+    #[serde(default)]
+    pub mouse: MouseConfig,
+    #[serde(default)]
+    pub main_search: MainSearchConfig,
+    #[serde(default)]
+    pub espanso: EspansoConfig,
+    #[serde(default)]
+    pub capture: CaptureConfig,
+    #[serde(default)]
+    pub todo_sync: TodoSyncConfig,
+    #[serde(default)]
+    pub ai: AiConfig,
+    #[serde(default)]
+    pub night_mode: NightModeConfig,
+    #[serde(default)]
+    pub alerts: AlertsConfig,
+    #[serde(default)]
+    pub focus: FocusConfig,
+    #[serde(default)]
+    pub currency: CurrencyConfig,
+    #[serde(default)]
+    pub numbers: NumberFormatConfig,
+    #[serde(default)]
+    pub web_search: WebSearchConfig,
+    #[serde(default)]
+    pub tabs: TabsConfig,
+    #[serde(default)]
+    pub projects: ProjectsConfig,
+    #[serde(default)]
+    pub git: GitConfig,
+    #[serde(default)]
+    pub files: FilesConfig,
+    #[serde(default)]
+    pub grep: GrepConfig,
+    #[serde(default)]
+    pub maps: MapsConfig,
+    /// Cities, abbreviations or IANA zones listed by the `time` mode
+    #[serde(default)]
+    pub world_clock: Option<Vec<String>>,
+    /// Per-mode result options, keyed by mode name
+    /// (`processes`, `files`, `docker`, `bitwarden`, `recent`)
+    #[serde(default)]
+    pub modes: HashMap<String, ModeConfig>,
+    /// Modes and results to favor depending on the window the launcher was
+    /// opened over; the first rule matching its class applies
+    #[serde(default)]
+    pub context_rules: Vec<ContextRule>,
+    /// Per-application launch tweaks, keyed by desktop file id
+    /// (`"code"`, `"org.mozilla.firefox"`) or application name
+    #[serde(default)]
+    pub app_overrides: HashMap<String, AppOverride>,
+    /// Colors and badges for result rows, e.g. to mark production hosts
+    #[serde(default)]
+    pub row_rules: Vec<RowRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ModeConfig {
+    #[serde(default)]
+    pub max_results: Option<usize>,
+    #[serde(default)]
+    pub sort: Option<SortOrder>,
+    /// Include hidden files, stopped containers and the like
+    #[serde(default)]
+    pub show_hidden: Option<bool>,
+}
+
+/// Result order; each mode supports the orders that make sense for it and
+/// falls back to its default otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Name,
+    Recent,
+    Cpu,
+    Memory,
+    Status,
+}
+
+/// Extra environment and flags merged into an application's exec at launch,
+/// so broken apps can be fixed without editing system .desktop files.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AppOverride {
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Appended to the exec line as-is (shell syntax)
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Workspace the app's new window is moved to once it appears: a
+    /// workspace name or number on i3/Sway and Hyprland, a desktop number
+    /// (from 0) elsewhere
+    #[serde(default)]
+    pub workspace: Option<String>,
+}
+
+/// Colors are hex strings (`"#1e1e1e"`) applied on top of the chosen preset.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ThemeConfig {
+    /// Built-in palette: dark, light, catppuccin, gruvbox or high-contrast
+    #[serde(default)]
+    pub preset: Option<String>,
+    #[serde(default)]
+    pub background: Option<String>,
+    #[serde(default)]
+    pub surface: Option<String>,
+    #[serde(default)]
+    pub accent: Option<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub text_secondary: Option<String>,
+    #[serde(default)]
+    pub border: Option<String>,
+    #[serde(default)]
+    pub selection: Option<String>,
+    #[serde(default)]
+    pub hover: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AccessibilityConfig {
+    /// Speak the selected item through speech-dispatcher (`spd-say`)
+    #[serde(default)]
+    pub announce: Option<bool>,
+    /// Don't auto-scroll or show a typing cursor while AI answers stream in
+    #[serde(default)]
+    pub reduced_motion: Option<bool>,
+    /// Opaque window and colors, ignoring alpha in theme colors
+    #[serde(default)]
+    pub reduced_transparency: Option<bool>,
+}
+
+impl AccessibilityConfig {
+    pub fn announce(&self) -> bool {
+        self.announce.unwrap_or(false)
+    }
+
+    pub fn reduced_motion(&self) -> bool {
+        self.reduced_motion.unwrap_or(false)
+    }
+
+    pub fn reduced_transparency(&self) -> bool {
+        self.reduced_transparency.unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MouseConfig {
+    /// Move the selection to the result under the pointer
+    #[serde(default)]
+    pub hover_select: Option<bool>,
+}
+
+impl MouseConfig {
+    pub fn hover_select(&self) -> bool {
+        self.hover_select.unwrap_or(true)
+    }
+}
+
+/// What besides applications the prefix-less search matches.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MainSearchConfig {
+    /// Saved connections and `~/.ssh/config` hosts
+    #[serde(default)]
+    pub ssh: Option<bool>,
+    /// Snippets pinned with `snip pin <name>`
+    #[serde(default)]
+    pub snippets: Option<bool>,
+    /// Lock screen and keep awake
+    #[serde(default)]
+    pub idle: Option<bool>,
+    /// Browser bookmarks; off by default, as reading every browser's
+    /// bookmarks holds up the first search
+    #[serde(default)]
+    pub bookmarks: Option<bool>,
+}
+
+impl MainSearchConfig {
+    pub fn ssh(&self) -> bool {
+        self.ssh.unwrap_or(true)
+    }
+
+    pub fn snippets(&self) -> bool {
+        self.snippets.unwrap_or(true)
+    }
+
+    pub fn idle(&self) -> bool {
+        self.idle.unwrap_or(true)
+    }
+
+    pub fn bookmarks(&self) -> bool {
+        self.bookmarks.unwrap_or(false)
+    }
+}
+
+/// What to favor when the launcher opens over a window of a given class.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ContextRule {
+    /// Regex matched against the whole window class, ignoring case
+    pub class: String,
+    /// Mode prefix to open in, e.g. `ssh`
+    #[serde(default)]
+    pub mode: Option<String>,
+    /// Shown first in the main search: `ssh`, `snippets`, `web` or
+    /// application names
+    #[serde(default)]
+    pub boost: Vec<String>,
+    /// `class` compiled on first use
+    #[serde(skip)]
+    regex: OnceLock<Option<Regex>>,
+}
+
+impl ContextRule {
+    pub fn matches(&self, class: &str) -> bool {
+        let regex = self.regex.get_or_init(|| match Regex::new(&format!("(?i)^(?:{})$", self.class)) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                log::warn!("Invalid context rule class {:?}: {}", self.class, e);
+                None
+            }
+        });
+        regex.as_ref().is_some_and(|regex| regex.is_match(class))
+    }
+
+    pub fn boosts(&self, name: &str) -> bool {
+        self.boost.iter().any(|b| b.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Colors or badges result rows matching every condition given, as a
+/// guard against acting on the wrong item.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RowRule {
+    /// Item types the rule applies to, e.g. `Process`, `SshConnection` or
+    /// `DockerContainer` (default: all)
+    #[serde(default)]
+    pub types: Vec<ItemType>,
+    /// Regex matched against the name and description, ignoring case
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// CPU usage above this percentage (processes)
+    #[serde(default)]
+    pub cpu_above: Option<f32>,
+    /// Memory use above this many MB (processes)
+    #[serde(default)]
+    pub memory_above: Option<f32>,
+    /// Hex color of the name and badge, e.g. `"#e01b24"`
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub badge: Option<String>,
+    /// `pattern` compiled on first use; rows are matched on every redraw
+    #[serde(skip)]
+    regex: OnceLock<Option<Regex>>,
+}
+
+impl RowRule {
+    pub fn matches(&self, item: &Item) -> bool {
+        if !self.types.is_empty() && !self.types.contains(&item.item_type) {
+            return false;
+        }
+        if let Some(pattern) = &self.pattern {
+            let regex = self.regex.get_or_init(|| match Regex::new(&format!("(?i){}", pattern)) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    log::warn!("Invalid row rule pattern {:?}: {}", pattern, e);
+                    None
+                }
+            });
+            let Some(regex) = regex else {
+                return false;
+            };
+            if !regex.is_match(&item.name) && !item.description.as_deref().is_some_and(|d| regex.is_match(d)) {
+                return false;
+            }
+        }
+        let above = |value: Option<f32>, limit: Option<f32>| limit.is_none_or(|limit| value.is_some_and(|v| v > limit));
+        above(item.metadata.cpu, self.cpu_above) && above(item.metadata.memory, self.memory_above)
+    }
+}
+
+/// Where `qc <text>` files the text when Enter is pressed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureTarget {
+    Inbox,
+    Note,
+    Todo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CaptureConfig {
+    /// Org or markdown file captured text is appended to (`~` is expanded)
+    #[serde(default)]
+    pub inbox: Option<String>,
+    /// Default target (default: `inbox` when one is set, else `note`)
+    #[serde(default)]
+    pub target: Option<CaptureTarget>,
+}
+
+impl CaptureConfig {
+    pub fn inbox(&self) -> Option<PathBuf> {
+        self.inbox.as_deref().map(Config::expand_home)
+    }
+
+    pub fn target(&self) -> CaptureTarget {
+        match self.target {
+            Some(CaptureTarget::Inbox) | None if self.inbox.is_some() => CaptureTarget::Inbox,
+            Some(CaptureTarget::Inbox) | None => CaptureTarget::Note,
+            Some(target) => target,
+        }
+    }
+}
+
+/// Service todos are kept in sync with.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TodoBackend {
+    Todoist,
+    Caldav,
+}
+
+/// Two-way sync of todos with Todoist or a CalDAV task list.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TodoSyncConfig {
+    #[serde(default)]
+    pub backend: Option<TodoBackend>,
+    /// Todoist API token, or the CalDAV password
+    #[serde(default)]
+    pub token: Option<String>,
+    /// CalDAV task list URL, e.g.
+    /// `https://cloud.example.com/remote.php/dav/calendars/me/tasks/`
+    #[serde(default)]
+    pub url: Option<String>,
+    /// CalDAV user name
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Minutes between syncs while the todos mode is open (default: 5)
+    #[serde(default)]
+    pub interval_minutes: Option<u64>,
+}
+
+impl TodoSyncConfig {
+    pub fn interval_minutes(&self) -> u64 {
+        self.interval_minutes.unwrap_or(5)
+    }
+}
+
+/// Registers snippets as Espanso matches so they also expand while typing.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct EspansoConfig {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// Match file to write (default: `~/.config/espanso/match/wlaunch.yml`)
+    #[serde(default)]
+    pub match_file: Option<String>,
+    /// Typed before the snippet name to expand it (default: `:`)
+    #[serde(default)]
+    pub trigger_prefix: Option<String>,
+}
+
+impl EspansoConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(false)
+    }
+
+    pub fn match_file(&self) -> PathBuf {
+        match &self.match_file {
+            Some(path) => Config::expand_home(path),
+            None => dirs::config_dir()
+                .unwrap_or_else(|| PathBuf::from("~/.config"))
+                .join("espanso/match/wlaunch.yml"),
+        }
+    }
+
+    pub fn trigger_prefix(&self) -> &str {
+        self.trigger_prefix.as_deref().unwrap_or(":")
+    }
+}
+
+/// AI backend selection. `gemini_api_key` is still honored for Gemini.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AiConfig {
+    /// gemini, openai, anthropic or ollama
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Override the API endpoint, e.g. for OpenAI-compatible servers
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Extra prompt presets; a preset with a built-in's name replaces it
+    #[serde(default)]
+    pub prompts: Vec<AiPrompt>,
+}
+
+/// A prompt template run on the clipboard. `{text}` is replaced with the
+/// clipboard contents and `{input}` with whatever follows the name in the
+/// query (`translate german`, defaulting to English).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiPrompt {
+    pub name: String,
+    pub template: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl AiConfig {
+    pub fn provider(&self) -> &str {
+        self.provider.as_deref().unwrap_or("gemini")
+    }
+}
+
+/// Separators the calculator and converter read and write; by default
+/// those of the locale.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NumberFormatConfig {
+    /// e.g. "de_DE"; LC_ALL, LC_NUMERIC or LANG when unset
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub decimal_separator: Option<String>,
+    /// Empty for no grouping
+    #[serde(default)]
+    pub group_separator: Option<String>,
+}
+
+/// Exchange rates for the converter's currency conversions.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CurrencyConfig {
+    /// Endpoint returning open.er-api.com style JSON
+    /// (`base_code`, `time_last_update_unix`, `rates`)
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// How old the cached rates may get before they are refetched
+    #[serde(default)]
+    pub refresh_hours: Option<u64>,
+}
+
+impl CurrencyConfig {
+    pub fn api_url(&self) -> &str {
+        self.api_url
+            .as_deref()
+            .unwrap_or("https://open.er-api.com/v6/latest/EUR")
+    }
+
+    pub fn refresh_hours(&self) -> u64 {
+        self.refresh_hours.unwrap_or(12)
+    }
+}
+
+/// Browsers web searches open in; `xdg-open` when none is set. Commands
+/// use shell syntax and get the URL appended.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WebSearchConfig {
+    /// Browser for engines without their own, e.g. `firefox`
+    #[serde(default)]
+    pub browser: Option<String>,
+    /// Per search engine (`google`, `github`, ...), e.g.
+    /// `"github": "google-chrome --profile-directory='Profile 1'"`
+    #[serde(default)]
+    pub browsers: HashMap<String, String>,
+    /// Flag opening a private window, for browsers it isn't known for
+    #[serde(default)]
+    pub private_flag: Option<String>,
+    /// Extra `!bangs`, keyed without the `!`: a search URL with `{query}`
+    /// where the search terms go
+    #[serde(default)]
+    pub bangs: HashMap<String, String>,
+}
+
+/// Browsers the `tabs` mode lists, by the port they were started with
+/// `--remote-debugging-port` on.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TabsConfig {
+    #[serde(default)]
+    pub debug_ports: Option<Vec<u16>>,
+}
+
+impl TabsConfig {
+    pub fn debug_ports(&self) -> Vec<u16> {
+        self.debug_ports.clone().unwrap_or_else(|| vec![9222])
+    }
+}
+
+/// Editors the `proj` mode opens projects in.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectsConfig {
+    /// Commands replacing the default ones (`code`, `idea`, `pycharm`, ...),
+    /// e.g. `"idea": "~/.local/share/JetBrains/Toolbox/scripts/idea"`
+    #[serde(default)]
+    pub editors: HashMap<String, String>,
+}
+
+/// Where the `git` mode looks for repositories and what it opens them with.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GitConfig {
+    /// Directories searched for repositories (`~` is expanded)
+    #[serde(default)]
+    pub roots: Option<Vec<String>>,
+    /// How many levels below a root repositories may be
+    #[serde(default)]
+    pub depth: Option<usize>,
+    /// Editor command the repository path is appended to
+    #[serde(default)]
+    pub editor: Option<String>,
+    /// Terminal command, started in the repository
+    #[serde(default)]
+    pub terminal: Option<String>,
+}
+
+impl GitConfig {
+    pub fn roots(&self) -> Vec<String> {
+        self.roots
+            .clone()
+            .unwrap_or_else(|| vec!["~/code".to_string(), "~/src".to_string(), "~/projects".to_string()])
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth.unwrap_or(3)
+    }
+
+    pub fn editor(&self) -> &str {
+        self.editor.as_deref().unwrap_or("code")
+    }
+
+    pub fn terminal(&self) -> &str {
+        self.terminal.as_deref().unwrap_or("x-terminal-emulator")
+    }
+}
+
+/// How the `f` mode finds files by name.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileSearchBackend {
+    /// Walks the folders on each search, four levels deep
+    Walk,
+    /// Runs `fd`
+    Fd,
+    /// Queries the `plocate` database
+    Plocate,
+    /// Reads a file list the daemon rebuilds every hour
+    Index,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FilesConfig {
+    #[serde(default)]
+    pub backend: Option<FileSearchBackend>,
+}
+
+impl FilesConfig {
+    pub fn backend(&self) -> FileSearchBackend {
+        self.backend.unwrap_or(FileSearchBackend::Walk)
+    }
+}
+
+/// File content search with ripgrep (the `grep` mode).
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GrepConfig {
+    /// Directories searched (`~` is expanded)
+    #[serde(default)]
+    pub roots: Option<Vec<String>>,
+    /// Editor a match is opened in, instead of `$EDITOR`
+    #[serde(default)]
+    pub editor: Option<String>,
+}
+
+impl GrepConfig {
+    pub fn roots(&self) -> Vec<String> {
+        self.roots.clone().unwrap_or_else(|| {
+            ["~/Documents", "~/code", "~/src", "~/projects"].into_iter().map(str::to_string).collect()
+        })
+    }
+
+    pub fn editor(&self) -> String {
+        self.editor
+            .clone()
+            .or_else(|| std::env::var("EDITOR").ok().filter(|editor| !editor.is_empty()))
+            .unwrap_or_else(|| "vi".to_string())
+    }
+}
+
+/// Where `map <place>` and `route <destination>` open.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MapsProvider {
+    Google,
+    Openstreetmap,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MapsConfig {
+    #[serde(default)]
+    pub provider: Option<MapsProvider>,
+    /// Address directions start from; no route item without one
+    #[serde(default)]
+    pub origin: Option<String>,
+    /// Google Maps travel mode: transit, driving, walking or bicycling
+    #[serde(default)]
+    pub travel_mode: Option<String>,
+}
+
+impl MapsConfig {
+    pub fn provider(&self) -> MapsProvider {
+        self.provider.unwrap_or(MapsProvider::Google)
+    }
+
+    pub fn travel_mode(&self) -> &str {
+        self.travel_mode.as_deref().unwrap_or("transit")
+    }
+}
+
+/// Automatic day/night switching, run by `wlaunch --daemon`.
+///
+/// With `latitude`/`longitude` set, night starts at sunset and ends at
+/// sunrise; otherwise the fixed `start`/`end` times (`"HH:MM"`) are used.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NightModeConfig {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub latitude: Option<f64>,
+    #[serde(default)]
+    pub longitude: Option<f64>,
+    #[serde(default)]
+    pub start: Option<String>,
+    #[serde(default)]
+    pub end: Option<String>,
+    /// Theme preset to switch to during the day / at night
+    #[serde(default)]
+    pub day_theme: Option<String>,
+    #[serde(default)]
+    pub night_theme: Option<String>,
+    /// Run gammastep at night
+    #[serde(default)]
+    pub night_light: Option<bool>,
+    /// gammastep color temperature in Kelvin
+    #[serde(default)]
+    pub temperature: Option<u32>,
+}
+
+impl NightModeConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(false)
+    }
+
+    pub fn start(&self) -> &str {
+        self.start.as_deref().unwrap_or("20:00")
+    }
+
+    pub fn end(&self) -> &str {
+        self.end.as_deref().unwrap_or("07:00")
+    }
+
+    pub fn night_light(&self) -> bool {
+        self.night_light.unwrap_or(true)
+    }
+
+    pub fn temperature(&self) -> u32 {
+        self.temperature.unwrap_or(4500)
+    }
+}
+
+/// Low battery, disk space and temperature notifications from
+/// `wlaunch --daemon`, each sent once until the value recovers.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AlertsConfig {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// Battery percent while discharging
+    #[serde(default)]
+    pub battery_below: Option<u8>,
+    /// Free space of any filesystem, in percent
+    #[serde(default)]
+    pub disk_free_below: Option<u8>,
+    /// Hottest sensor, in °C
+    #[serde(default)]
+    pub temperature_above: Option<f32>,
+    /// Components at or above this many °C are shown in the danger color
+    /// by `sys temps`
+    #[serde(default)]
+    pub temperature_warning: Option<f32>,
+}
+
+impl AlertsConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn battery_below(&self) -> u8 {
+        self.battery_below.unwrap_or(15)
+    }
+
+    pub fn disk_free_below(&self) -> u8 {
+        self.disk_free_below.unwrap_or(10)
+    }
+
+    pub fn temperature_above(&self) -> f32 {
+        self.temperature_above.unwrap_or(90.0)
+    }
+
+    pub fn temperature_warning(&self) -> f32 {
+        self.temperature_warning.unwrap_or(80.0)
+    }
+}
+
+/// Distracting sites the `focus` mode blocks in `/etc/hosts`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FocusConfig {
+    /// Domains such as `reddit.com`; their `www.` names are blocked too
+    #[serde(default)]
+    pub sites: Vec<String>,
+    /// Length of a focus session when none is typed
+    #[serde(default)]
+    pub minutes: Option<u64>,
+}
+
+impl FocusConfig {
+    pub fn minutes(&self) -> u64 {
+        self.minutes.unwrap_or(25)
+    }
 }
 
 impl Config {
-    pub fn config_dir() -> PathBuf {
+    /// Directory of the default profile; other profiles live in its
+    /// `profiles/` folder.
+    pub fn base_dir() -> PathBuf {
         dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("~/.config"))
             .join("wlaunch")
     }
 
+    /// Config and data directory of the active profile.
+    pub fn config_dir() -> PathBuf {
+        Self::profile_dir(Self::profile())
+    }
+
+    pub fn profile_dir(name: &str) -> PathBuf {
+        if name == DEFAULT_PROFILE {
+            Self::base_dir()
+        } else {
+            Self::base_dir().join("profiles").join(name)
+        }
+    }
+
+    /// Uses profile `name` for the rest of the process. Has no effect once
+    /// a config or data path was looked up.
+    pub fn set_profile(name: &str) {
+        let _ = PROFILE.set(name.to_string());
+    }
+
+    /// The profile given with `--profile`, else the one last switched to in
+    /// the profile mode.
+    pub fn profile() -> &'static str {
+        PROFILE.get_or_init(|| {
+            fs::read_to_string(Self::base_dir().join("active_profile"))
+                .ok()
+                .map(|name| name.trim().to_string())
+                .filter(|name| Self::is_profile_name(name))
+                .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+        })
+    }
+
+    /// Makes `name` the profile a plain `wlaunch` starts with, creating its
+    /// directory.
+    pub fn remember_profile(name: &str) -> Result<()> {
+        fs::create_dir_all(Self::profile_dir(name))?;
+        fs::write(Self::base_dir().join("active_profile"), format!("{}\n", name))?;
+        Ok(())
+    }
+
+    /// The default profile and those with a directory, sorted.
+    pub fn profiles() -> Vec<String> {
+        let mut profiles: Vec<String> = fs::read_dir(Self::base_dir().join("profiles"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| Self::is_profile_name(name) && name != DEFAULT_PROFILE)
+            .collect();
+        profiles.sort();
+        profiles.insert(0, DEFAULT_PROFILE.to_string());
+        profiles
+    }
+
+    /// Letters, digits, `-` and `_`, so a name is always a single directory.
+    pub fn is_profile_name(name: &str) -> bool {
+        !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    }
+
     pub fn config_path() -> PathBuf {
         Self::config_dir().join("config.json")
     }
@@ -51,10 +880,59 @@ impl Config {
         self.clipboard_history_size.unwrap_or(50)
     }
 
+    /// Turns on kiosk mode for this process, as `--kiosk` does.
+    pub fn set_kiosk() {
+        KIOSK.store(true, Ordering::Relaxed);
+    }
+
+    pub fn kiosk(&self) -> bool {
+        self.kiosk.unwrap_or(false) || KIOSK.load(Ordering::Relaxed)
+    }
+
+    pub fn insights(&self) -> bool {
+        self.insights.unwrap_or(false)
+    }
+
+    pub fn paste_on_select(&self) -> bool {
+        self.paste_on_select.unwrap_or(false)
+    }
+
     pub fn max_recent_files(&self) -> usize {
         self.max_recent_files.unwrap_or(100)
     }
 
+    pub fn mode(&self, name: &str) -> ModeConfig {
+        self.modes.get(name).cloned().unwrap_or_default()
+    }
+
+    /// Looks up the launch override for an application by desktop file id,
+    /// then by name (case-insensitive).
+    pub fn app_override(&self, item: &Item) -> Option<&AppOverride> {
+        let desktop_id = item
+            .metadata
+            .desktop_file
+            .as_ref()
+            .and_then(|path| path.file_stem())
+            .and_then(|stem| stem.to_str());
+
+        desktop_id
+            .and_then(|id| self.app_overrides.get(id))
+            .or_else(|| {
+                self.app_overrides
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(&item.name))
+                    .map(|(_, app)| app)
+            })
+    }
+
+    pub fn appimage_dirs(&self) -> Vec<PathBuf> {
+        let home = dirs::home_dir().unwrap_or_default();
+        match &self.appimage_dirs {
+            Some(dirs) => dirs.iter().map(|dir| Self::expand_home(dir)).collect(),
+            None => vec![home.join("Applications")],
+        }
+    }
+
     pub fn scripts_dir() -> PathBuf {
         Self::config_dir().join("scripts")
     }
@@ -62,4 +940,69 @@ impl Config {
     pub fn data_path(name: &str) -> PathBuf {
         Self::config_dir().join(name)
     }
+
+    /// `name` in `sync_dir` if one is set, else in the config directory.
+    pub fn synced_path(&self, name: &str) -> PathBuf {
+        match &self.sync_dir {
+            Some(dir) => Self::expand_home(dir).join(name),
+            None => Self::data_path(name),
+        }
+    }
+
+    pub fn context_rule(&self, class: &str) -> Option<&ContextRule> {
+        self.context_rules.iter().find(|rule| rule.matches(class))
+    }
+
+    /// `path` with a leading `~/` replaced by the home directory.
+    pub fn expand_home(path: &str) -> PathBuf {
+        match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+            None => PathBuf::from(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(class: &str, mode: &str) -> ContextRule {
+        ContextRule {
+            class: class.to_string(),
+            mode: Some(mode.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_context_rules() {
+        let terminal = rule("kitty|foot|alacritty", "ssh");
+        assert!(terminal.matches("Alacritty"));
+        assert!(terminal.matches("foot"));
+        // The whole class has to match
+        assert!(!terminal.matches("footclient"));
+        assert!(rule("org\\.gnome\\..*", "files").matches("org.gnome.Nautilus"));
+        // An invalid pattern never matches rather than failing
+        let broken = rule("code(", "projects");
+        assert!(!broken.matches("code("));
+        assert!(!broken.matches("code"));
+
+        let config = Config {
+            context_rules: vec![broken, terminal, rule(".*", "apps")],
+            ..Default::default()
+        };
+        assert_eq!(config.context_rule("kitty").and_then(|r| r.mode.as_deref()), Some("ssh"));
+        assert_eq!(config.context_rule("firefox").and_then(|r| r.mode.as_deref()), Some("apps"));
+    }
+
+    #[test]
+    fn test_main_search() {
+        let config: Config = serde_json::from_str("{}").unwrap();
+        assert!(config.main_search.ssh() && config.main_search.snippets());
+        assert!(!config.main_search.bookmarks());
+        let config: Config = serde_json::from_str(r#"{"main_search": {"ssh": false, "bookmarks": true}}"#).unwrap();
+        assert!(!config.main_search.ssh());
+        assert!(config.main_search.snippets());
+        assert!(config.main_search.bookmarks());
+    }
 }
@@ -1,11 +1,18 @@
 use crate::core::{Config, Item, ItemType};
+use crate::features::projects::percent_decode;
 use anyhow::Result;
 use std::collections::HashSet;
+use sha1::{Digest, Sha1};
 use std::fs;
-use std::path::PathBuf;
+use std::io::{Read, Seek, SeekFrom};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use walkdir::WalkDir;
 
 pub struct Indexer {
     apps: Vec<Item>,
+    places: Vec<Item>,
     scripts: Vec<Item>,
 }
 
@@ -13,38 +20,39 @@ impl Indexer {
     pub fn new() -> Self {
         Self {
             apps: Vec::new(),
+            places: Vec::new(),
             scripts: Vec::new(),
         }
     }
 
-    pub fn index(&mut self) -> Result<()> {
-        self.index_applications()?;
+    /// Indexes apps, places and scripts, reporting the fraction done (0.0
+    /// to 1.0) after each directory and source.
+    pub fn index_with_progress(&mut self, progress: &mut dyn FnMut(f32)) -> Result<()> {
+        self.index_applications(progress)?;
+        self.index_places();
         self.index_scripts()?;
+        progress(1.0);
         Ok(())
     }
 
-    pub fn apps(&self) -> &[Item] {
-        &self.apps
-    }
-
-    pub fn scripts(&self) -> &[Item] {
-        &self.scripts
-    }
-
     pub fn all_items(&self) -> Vec<Item> {
         let mut items = self.apps.clone();
+        items.extend(self.places.clone());
         items.extend(self.scripts.clone());
         items
     }
 
-    fn index_applications(&mut self) -> Result<()> {
+    fn index_applications(&mut self, progress: &mut dyn FnMut(f32)) -> Result<()> {
         let mut seen_names: HashSet<String> = HashSet::new();
         self.apps.clear();
 
         // Get XDG data directories
         let data_dirs = Self::get_xdg_data_dirs();
+        // One step per data directory, plus Wine, AppImages and scripts
+        let steps = (data_dirs.len() + 3) as f32;
 
-        for data_dir in data_dirs {
+        for (i, data_dir) in data_dirs.into_iter().enumerate() {
+            progress(i as f32 / steps);
             let apps_dir = data_dir.join("applications");
             if !apps_dir.exists() {
                 continue;
@@ -62,12 +70,220 @@ impl Indexer {
             }
         }
 
+        progress((steps - 3.0) / steps);
+        self.index_wine_applications(&mut seen_names);
+        progress((steps - 2.0) / steps);
+        self.index_appimages(&mut seen_names);
+        progress((steps - 1.0) / steps);
+
         // Sort by name
-        self.apps.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        self.apps.sort_by_key(|a| a.name.to_lowercase());
 
         Ok(())
     }
 
+    /// Wine puts its shortcuts in nested folders under
+    /// `applications/wine/Programs/`, which the flat scan above skips.
+    fn index_wine_applications(&mut self, seen_names: &mut HashSet<String>) {
+        let Some(wine_dir) = dirs::data_local_dir().map(|d| d.join("applications/wine")) else {
+            return;
+        };
+
+        for entry in WalkDir::new(&wine_dir).into_iter().flatten() {
+            let path = entry.path().to_path_buf();
+            if path.extension().map(|e| e == "desktop").unwrap_or(false) {
+                if let Some(item) = self.parse_desktop_file(&path, seen_names) {
+                    self.apps.push(item);
+                }
+            }
+        }
+    }
+
+    /// AppImages never install a desktop entry, so their embedded one (and
+    /// icon) is unpacked once into the cache and reused until the file changes.
+    fn index_appimages(&mut self, seen_names: &mut HashSet<String>) {
+        let config = Config::load().unwrap_or_default();
+        let mut cached: HashSet<String> = HashSet::new();
+
+        for dir in config.appimage_dirs() {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let is_appimage = path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .map(|e| e.eq_ignore_ascii_case("appimage"))
+                    .unwrap_or(false);
+                if !is_appimage || !path.is_file() {
+                    continue;
+                }
+
+                let key = Self::appimage_cache_key(&path);
+                if let Some(key) = &key {
+                    cached.insert(key.clone());
+                }
+                let extracted = key.and_then(|key| Self::extract_appimage(&path, &key));
+                let desktop = extracted.as_ref().and_then(|dir| Self::find_file(dir, "desktop"));
+
+                let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("AppImage").to_string();
+                let mut item = match desktop.and_then(|d| self.parse_desktop_file(&d, seen_names)) {
+                    Some(item) => item,
+                    None if seen_names.insert(stem.clone()) => {
+                        Item::new(format!("app:{}", stem), &stem, ItemType::Application)
+                            .with_icon("application-x-executable")
+                    }
+                    None => continue,
+                };
+
+                // Launch the AppImage itself rather than the embedded Exec
+                item.exec = Some(format!("'{}'", path.display().to_string().replace('\'', "'\\''")));
+                item.description = Some(match item.description.take() {
+                    Some(comment) => format!("{} | AppImage", comment),
+                    None => format!("AppImage | {}", Self::display_path(&path)),
+                });
+                item.keywords.push("appimage".to_string());
+                item.metadata.path = Some(path.clone());
+
+                let icon_name = item.icon.clone();
+                if let Some(icon) = extracted
+                    .as_ref()
+                    .and_then(|dir| Self::appimage_icon(dir, icon_name.as_deref()))
+                {
+                    item.icon_path = Some(icon);
+                }
+
+                self.apps.push(item);
+            }
+        }
+
+        if let Some(cache) = Self::appimage_cache() {
+            Self::prune_appimage_cache(&cache, &cached);
+        }
+    }
+
+    fn appimage_cache() -> Option<PathBuf> {
+        Some(dirs::cache_dir()?.join("wlaunch/appimages"))
+    }
+
+    /// `<name>-<path hash>-<mtime>`: a new entry once the file is replaced,
+    /// and separate ones for equally named AppImages in different folders.
+    fn appimage_cache_key(path: &Path) -> Option<String> {
+        let mtime = fs::metadata(path)
+            .ok()?
+            .modified()
+            .ok()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_secs();
+        let hash = Sha1::digest(path.as_os_str().as_bytes());
+        let hash: String = hash[..4].iter().map(|b| format!("{:02x}", b)).collect();
+        Some(format!("{}-{}-{}", path.file_name()?.to_str()?, hash, mtime))
+    }
+
+    /// Unpacks the desktop entry and icon of an AppImage into
+    /// `~/.cache/wlaunch/appimages/<key>/squashfs-root` with unsquashfs,
+    /// reading the filesystem image appended to the runtime instead of
+    /// running the AppImage. Each version is tried once, even if it fails.
+    fn extract_appimage(path: &Path, key: &str) -> Option<PathBuf> {
+        let cache_dir = Self::appimage_cache()?.join(key);
+        let root = cache_dir.join("squashfs-root");
+        if cache_dir.exists() {
+            return root.exists().then_some(root);
+        }
+
+        let offset = Self::squashfs_offset(path)?;
+        which::which("unsquashfs").ok()?;
+        fs::create_dir_all(&cache_dir).ok()?;
+        let _ = Command::new("unsquashfs")
+            .arg("-no-progress")
+            .arg("-o")
+            .arg(offset.to_string())
+            .arg("-d")
+            .arg(&root)
+            .arg(path)
+            .args(["*.desktop", ".DirIcon", "*.png", "*.svg"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+
+        root.exists().then_some(root)
+    }
+
+    /// Where the squashfs image starts in a type 2 AppImage: right after
+    /// the ELF runtime, whose section headers come last.
+    fn squashfs_offset(path: &Path) -> Option<u64> {
+        let mut file = fs::File::open(path).ok()?;
+        let mut header = [0u8; 64];
+        file.read_exact(&mut header).ok()?;
+        if header[..4] != *b"\x7fELF" {
+            return None;
+        }
+
+        let little_endian = header[5] == 1;
+        let read = |range: std::ops::Range<usize>| -> u64 {
+            let bytes = &header[range];
+            let fold = |value: u64, byte: &u8| (value << 8) | u64::from(*byte);
+            if little_endian {
+                bytes.iter().rev().fold(0, fold)
+            } else {
+                bytes.iter().fold(0, fold)
+            }
+        };
+        let (section_headers, entry_size, entries) = match header[4] {
+            1 => (read(32..36), read(46..48), read(48..50)),
+            2 => (read(40..48), read(58..60), read(60..62)),
+            _ => return None,
+        };
+        let offset = section_headers.checked_add(entry_size.checked_mul(entries)?)?;
+
+        let mut magic = [0u8; 4];
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        file.read_exact(&mut magic).ok()?;
+        (magic == *b"hsqs").then_some(offset)
+    }
+
+    /// Removes cached entries of AppImages that were deleted or replaced.
+    fn prune_appimage_cache(cache: &Path, keep: &HashSet<String>) {
+        let Ok(entries) = fs::read_dir(cache) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let stale = entry.file_name().to_str().is_none_or(|name| !keep.contains(name));
+            if stale {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+
+    /// The first top-level file in `dir` with the given extension.
+    fn find_file(dir: &Path, extension: &str) -> Option<PathBuf> {
+        fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .map(|e| e.path())
+            .find(|p| p.extension().map(|e| e == extension).unwrap_or(false))
+    }
+
+    fn appimage_icon(dir: &Path, icon_name: Option<&str>) -> Option<PathBuf> {
+        if let Some(name) = icon_name {
+            for ext in ["png", "svg"] {
+                let icon = dir.join(format!("{}.{}", name, ext));
+                if icon.exists() {
+                    return Some(icon);
+                }
+            }
+        }
+        // .DirIcon is usually a symlink; resolving it keeps the extension
+        // needed to tell SVG from raster. Dangling links fail here.
+        fs::canonicalize(dir.join(".DirIcon"))
+            .ok()
+            .or_else(|| Self::find_file(dir, "png"))
+            .or_else(|| Self::find_file(dir, "svg"))
+    }
+
     fn get_xdg_data_dirs() -> Vec<PathBuf> {
         let mut dirs = Vec::new();
 
@@ -108,6 +324,7 @@ impl Indexer {
         let mut no_display = false;
         let mut hidden = false;
         let mut terminal = false;
+        let mut wm_class: Option<String> = None;
         let mut keywords: Vec<String> = Vec::new();
 
         let mut in_desktop_entry = false;
@@ -136,6 +353,7 @@ impl Indexer {
                     "NoDisplay" => no_display = value.to_lowercase() == "true",
                     "Hidden" => hidden = value.to_lowercase() == "true",
                     "Terminal" => terminal = value.to_lowercase() == "true",
+                    "StartupWMClass" => wm_class = Some(value.to_string()),
                     "Keywords" => {
                         keywords = value.split(';').filter(|s| !s.is_empty()).map(|s| s.to_string()).collect();
                     }
@@ -178,13 +396,152 @@ impl Indexer {
             item = item.with_exec(exec);
         }
 
+        let wine_prefix = item.exec.as_deref().and_then(Self::wine_prefix);
+        if let Some((kind, prefix)) = &wine_prefix {
+            item.description = Some(format!("{} | {}", kind, Self::display_path(prefix)));
+            keywords.push(kind.to_lowercase());
+        }
+
         item = item.with_keywords(keywords);
         item.metadata.desktop_file = Some(path.clone());
         item.metadata.terminal = terminal;
+        item.metadata.wm_class = wm_class;
+        item.metadata.wine_prefix = wine_prefix.map(|(_, prefix)| prefix);
 
         Some(item)
     }
 
+    /// Detects Wine shortcuts (`env WINEPREFIX="..." wine ...`) and Steam
+    /// games running under Proton, returning the kind and the prefix.
+    fn wine_prefix(exec: &str) -> Option<(&'static str, PathBuf)> {
+        if let Some(app_id) = exec.split("steam://rungameid/").nth(1) {
+            let app_id: String = app_id.chars().take_while(|c| c.is_ascii_digit()).collect();
+            let prefix = dirs::home_dir()?
+                .join(".steam/steam/steamapps/compatdata")
+                .join(app_id)
+                .join("pfx");
+            return prefix.exists().then_some(("Proton", prefix));
+        }
+
+        let words: Vec<&str> = exec.split_whitespace().collect();
+        if !words.iter().any(|w| *w == "wine" || *w == "wine64" || w.ends_with("/wine")) {
+            return None;
+        }
+
+        let prefix = words
+            .iter()
+            .find_map(|w| w.strip_prefix("WINEPREFIX="))
+            .map(|p| PathBuf::from(p.trim_matches('"')))
+            .or_else(|| dirs::home_dir().map(|home| home.join(".wine")))?;
+        Some(("Wine", prefix))
+    }
+
+    /// Shortens paths under the home directory to `~/...`.
+    fn display_path(path: &Path) -> String {
+        match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+            Some(relative) => format!("~/{}", relative.display()),
+            None => path.display().to_string(),
+        }
+    }
+
+    /// The XDG user folders (Downloads, Documents, ...) and the GTK
+    /// file manager bookmarks, which open in the file manager.
+    fn index_places(&mut self) {
+        self.places.clear();
+        let (Some(home), Some(config_dir)) = (dirs::home_dir(), dirs::config_dir()) else {
+            return;
+        };
+        let mut seen: HashSet<String> = HashSet::new();
+
+        let user_dirs = fs::read_to_string(config_dir.join("user-dirs.dirs")).unwrap_or_default();
+        for (key, path) in Self::parse_user_dirs(&user_dirs, &home) {
+            // Unused folders are set to the home folder itself
+            if path == home || !path.is_dir() {
+                continue;
+            }
+            let icon = match key.as_str() {
+                "DESKTOP" => "user-desktop",
+                "DOWNLOAD" => "folder-download",
+                "DOCUMENTS" => "folder-documents",
+                "MUSIC" => "folder-music",
+                "PICTURES" => "folder-pictures",
+                "VIDEOS" => "folder-videos",
+                "TEMPLATES" => "folder-templates",
+                "PUBLICSHARE" => "folder-publicshare",
+                _ => "folder",
+            };
+            let uri = format!("file://{}", path.display());
+            seen.insert(uri.clone());
+            self.places.push(Self::place_item(uri, None, Some(path), icon));
+        }
+
+        let bookmarks = fs::read_to_string(config_dir.join("gtk-3.0").join("bookmarks")).unwrap_or_default();
+        for (uri, label) in Self::parse_gtk_bookmarks(&bookmarks) {
+            if !seen.insert(uri.clone()) {
+                continue;
+            }
+            match uri.strip_prefix("file://") {
+                Some(path) => {
+                    let path = PathBuf::from(percent_decode(path));
+                    if path.is_dir() {
+                        self.places.push(Self::place_item(uri, label, Some(path), "folder"));
+                    }
+                }
+                None => self.places.push(Self::place_item(uri, label, None, "folder-remote")),
+            }
+        }
+    }
+
+    fn place_item(uri: String, label: Option<String>, path: Option<PathBuf>, icon: &str) -> Item {
+        let name = label.unwrap_or_else(|| match &path {
+            Some(path) => path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().to_string()),
+            None => uri.clone(),
+        });
+        let description = match &path {
+            Some(path) => Self::display_path(path),
+            None => uri.clone(),
+        };
+        let mut item = Item::new(format!("place:{}", uri), name, ItemType::Place)
+            .with_description(description)
+            .with_icon(icon);
+        item.metadata.path = path;
+        item.metadata.uri = Some(uri);
+        item
+    }
+
+    /// `XDG_DOWNLOAD_DIR="$HOME/Downloads"` lines, as `("DOWNLOAD", path)`.
+    fn parse_user_dirs(content: &str, home: &Path) -> Vec<(String, PathBuf)> {
+        content
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .filter_map(|line| {
+                let (key, value) = line.trim().split_once('=')?;
+                let key = key.strip_prefix("XDG_")?.strip_suffix("_DIR")?;
+                let value = value.trim_matches('"');
+                let path = match value.strip_prefix("$HOME") {
+                    Some(rest) => home.join(rest.trim_start_matches('/')),
+                    None if value.starts_with('/') => PathBuf::from(value),
+                    None => return None,
+                };
+                Some((key.to_string(), path))
+            })
+            .collect()
+    }
+
+    /// `file:///home/me/My%20Code Code` lines: a URI and an optional label.
+    fn parse_gtk_bookmarks(content: &str) -> Vec<(String, Option<String>)> {
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| match line.split_once(' ') {
+                Some((uri, label)) if !label.trim().is_empty() => (uri.to_string(), Some(label.trim().to_string())),
+                Some((uri, _)) => (uri.to_string(), None),
+                None => (line.to_string(), None),
+            })
+            .collect()
+    }
+
     fn index_scripts(&mut self) -> Result<()> {
         self.scripts.clear();
         let scripts_dir = Config::scripts_dir();
@@ -219,7 +576,7 @@ impl Indexer {
             }
         }
 
-        self.scripts.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        self.scripts.sort_by_key(|a| a.name.to_lowercase());
 
         Ok(())
     }
@@ -262,3 +619,103 @@ impl Default for Indexer {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wine_prefix() {
+        let exec = r#"env WINEPREFIX="/home/user/.wine-games" wine C:\\\\windows\\\\command\\\\start.exe /Unix game.lnk"#;
+        assert_eq!(
+            Indexer::wine_prefix(exec),
+            Some(("Wine", PathBuf::from("/home/user/.wine-games")))
+        );
+        assert_eq!(Indexer::wine_prefix("firefox %u"), None);
+    }
+
+    /// An ELF header whose section headers end at `offset`, followed by
+    /// `tail` there, like the runtime and image of an AppImage.
+    fn appimage_fixture(path: &Path, offset: u64, tail: &[u8]) {
+        let mut bytes = vec![0u8; offset as usize];
+        bytes[..4].copy_from_slice(b"\x7fELF");
+        bytes[4] = 2;
+        bytes[5] = 1;
+        bytes[40..48].copy_from_slice(&(offset - 3 * 64).to_le_bytes());
+        bytes[58..60].copy_from_slice(&64u16.to_le_bytes());
+        bytes[60..62].copy_from_slice(&3u16.to_le_bytes());
+        bytes.extend_from_slice(tail);
+        fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_appimage() {
+        let dir = std::env::temp_dir().join(format!("wlaunch-appimage-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let appimage = dir.join("Tool.AppImage");
+        appimage_fixture(&appimage, 1024, b"hsqs\0\0\0\0");
+        assert_eq!(Indexer::squashfs_offset(&appimage), Some(1024));
+        // Not a type 2 AppImage: nothing where the image should start
+        let plain = dir.join("Plain.AppImage");
+        appimage_fixture(&plain, 1024, b"\0\0\0\0");
+        assert_eq!(Indexer::squashfs_offset(&plain), None);
+        fs::write(dir.join("Script.AppImage"), "#!/bin/sh\necho hi\n").unwrap();
+        assert_eq!(Indexer::squashfs_offset(&dir.join("Script.AppImage")), None);
+
+        let key = Indexer::appimage_cache_key(&appimage).unwrap();
+        assert!(key.starts_with("Tool.AppImage-"), "{}", key);
+        // Same name in another folder
+        fs::create_dir_all(dir.join("other")).unwrap();
+        fs::copy(&appimage, dir.join("other/Tool.AppImage")).unwrap();
+        assert_ne!(Indexer::appimage_cache_key(&dir.join("other/Tool.AppImage")).unwrap(), key);
+
+        // An unpacked entry, as unsquashfs leaves it
+        let root = dir.join("cache").join(&key).join("squashfs-root");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("tool.desktop"), "[Desktop Entry]\nName=Tool\nExec=AppRun\nIcon=tool\n").unwrap();
+        fs::write(root.join("logo.svg"), "<svg/>").unwrap();
+        std::os::unix::fs::symlink("logo.svg", root.join(".DirIcon")).unwrap();
+        let desktop = Indexer::find_file(&root, "desktop").unwrap();
+        let item = Indexer::new().parse_desktop_file(&desktop, &mut HashSet::new()).unwrap();
+        assert_eq!((item.name.as_str(), item.icon.as_deref()), ("Tool", Some("tool")));
+        assert_eq!(Indexer::appimage_icon(&root, Some("tool")), Some(root.join("logo.svg")));
+
+        fs::create_dir_all(dir.join("cache/Old.AppImage-0000-1")).unwrap();
+        Indexer::prune_appimage_cache(&dir.join("cache"), &HashSet::from([key.clone()]));
+        let left: Vec<_> = fs::read_dir(dir.join("cache")).unwrap().flatten().map(|e| e.file_name()).collect();
+        assert_eq!(left, [std::ffi::OsString::from(&key)]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_places() {
+        let home = Path::new("/home/me");
+        let user_dirs = "# written by xdg-user-dirs-update\nXDG_DESKTOP_DIR=\"$HOME/Desktop\"\nXDG_DOWNLOAD_DIR=\"$HOME/Téléchargements\"\nXDG_MUSIC_DIR=\"/mnt/music\"\nXDG_PUBLICSHARE_DIR=\"$HOME/\"\n";
+        assert_eq!(
+            Indexer::parse_user_dirs(user_dirs, home),
+            [
+                ("DESKTOP".to_string(), PathBuf::from("/home/me/Desktop")),
+                ("DOWNLOAD".to_string(), PathBuf::from("/home/me/Téléchargements")),
+                ("MUSIC".to_string(), PathBuf::from("/mnt/music")),
+                ("PUBLICSHARE".to_string(), PathBuf::from("/home/me")),
+            ]
+        );
+
+        let bookmarks = "file:///home/me/My%20Code Code\nfile:///srv/media\n\nsftp://nas.local/backups NAS backups\n";
+        assert_eq!(
+            Indexer::parse_gtk_bookmarks(bookmarks),
+            [
+                ("file:///home/me/My%20Code".to_string(), Some("Code".to_string())),
+                ("file:///srv/media".to_string(), None),
+                ("sftp://nas.local/backups".to_string(), Some("NAS backups".to_string())),
+            ]
+        );
+
+        let remote = Indexer::place_item("sftp://nas.local/backups".to_string(), None, None, "folder-remote");
+        assert_eq!((remote.name.as_str(), remote.item_type), ("sftp://nas.local/backups", ItemType::Place));
+        let media = Indexer::place_item("file:///srv/media".to_string(), None, Some(PathBuf::from("/srv/media")), "folder");
+        assert_eq!(media.name, "media");
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::process::Output;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ItemType {
@@ -9,6 +10,9 @@ pub enum ItemType {
 
     // Window management
     Window,
+    WindowGroup,
+    WindowAction,
+    SessionAction,
 
     // System
     WifiNetwork,
@@ -17,14 +21,23 @@ pub enum ItemType {
     BluetoothAction,
     AudioSink,
     AudioAction,
+    Display,
+    DisplayAction,
 
     // Files
     File,
     RecentFile,
+    GrepMatch,
     Folder,
+    FolderAction,
+    Place,
+    Project,
+    GitRepo,
+    GitAction,
 
     // Clipboard
     ClipboardEntry,
+    ClipboardAction,
 
     // Productivity
     Note,
@@ -33,34 +46,86 @@ pub enum ItemType {
     SnippetAction,
     Todo,
     TodoAction,
+    Capture,
 
     // Connections
     SshConnection,
     SshAction,
+    NetworkShare,
+    ShareAction,
+    TmuxSession,
+    TmuxAction,
     DockerContainer,
     DockerAction,
+    DockerImage,
+    DockerVolume,
+    DockerCompose,
+    KubePod,
+    KubeAction,
+    VirtualMachine,
+    VmAction,
+
+    // Software
+    Package,
+    PackageAction,
+    Flatpak,
+    FlatpakAction,
 
     // Utilities
     Process,
+    LogEntry,
+    LogAction,
     Emoji,
     Timer,
     TimerAction,
+    FocusAction,
+    IdleAction,
+    ScreenshotAction,
     Calculator,
     Converter,
+    WorldClock,
+    DateCalc,
+    CryptoTool,
+    HealthCheck,
+    SysInfo,
+    Disk,
+    DiskAction,
+    Battery,
+    PowerProfile,
+    Generator,
+    UnicodeChar,
 
     // Web
     WebSearch,
+    Bookmark,
+    BrowserTab,
 
     // Password
     BitwardenItem,
     BitwardenAction,
+    GpgKey,
+    GpgAction,
+    TotpCode,
+    TotpAction,
 
     // AI
     AiQuery,
     AiResponse,
 
+    // Wine
+    WinePrefix,
+    WineAction,
+
+    // Appearance
+    Theme,
+    Profile,
+
     // Commands
     Command,
+    ShellAction,
+
+    // Provider failures, with Retry and Copy error
+    Error,
 }
 
 #[derive(Debug, Clone)]
@@ -81,10 +146,14 @@ pub struct ItemMetadata {
     // Application
     pub desktop_file: Option<PathBuf>,
     pub terminal: bool,
+    /// WINEPREFIX of a Wine shortcut or Proton game
+    pub wine_prefix: Option<PathBuf>,
 
     // Window
     pub window_id: Option<i64>,
     pub workspace: Option<String>,
+    /// WM_CLASS / Wayland app_id of a window, or StartupWMClass of an application
+    pub wm_class: Option<String>,
 
     // WiFi
     pub ssid: Option<String>,
@@ -101,10 +170,12 @@ pub struct ItemMetadata {
     pub muted: bool,
     pub sink_id: Option<String>,
 
+    // Display
+    pub brightness: Option<u32>,
+
     // File
     pub path: Option<PathBuf>,
     pub size: Option<u64>,
-    pub modified: Option<String>,
     pub mime_type: Option<String>,
 
     // Process
@@ -125,16 +196,37 @@ pub struct ItemMetadata {
     pub container_id: Option<String>,
     pub container_status: Option<String>,
     pub image: Option<String>,
+    /// Port mappings as `docker ps` shows them, e.g. "0.0.0.0:8080->80/tcp"
+    pub ports: Option<String>,
+
+    // Virtual machine
+    /// As the hypervisor reports it, e.g. "running" or "shut off"
+    pub vm_state: Option<String>,
+
+    // Package
+    pub installed: bool,
 
     // Note/Snippet/Todo
     pub content: Option<String>,
     pub created: Option<String>,
     pub completed: bool,
+    /// Todo `#tags` and `@project`
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    /// Todo `!priority` and due date as shown, e.g. "high" and "overdue 2d"
+    pub priority: Option<String>,
+    pub due: Option<String>,
+    /// Repeating todo's recurrence, e.g. "every monday"
+    pub recurrence: Option<String>,
 
     // Timer
     pub duration: Option<u64>,
     pub remaining: Option<u64>,
 
+    // Sensor
+    /// Above its warning threshold, shown in the theme's danger color
+    pub warning: bool,
+
     // Bitwarden
     pub username: Option<String>,
     pub password: Option<String>,
@@ -145,9 +237,101 @@ pub struct ItemMetadata {
     pub search_engine: Option<String>,
     pub query: Option<String>,
     pub url: Option<String>,
+
+    // Error
+    /// Full error output of a failed provider, e.g. a command's stderr
+    pub error: Option<String>,
+}
+
+impl ItemType {
+    /// Whether activating the item only opens, focuses, copies or computes
+    /// something, so it's allowed in kiosk mode. Anything that changes the
+    /// system or the user's data, opens a shell or shows secrets isn't, and
+    /// neither are user scripts, which could do any of that.
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            ItemType::Application
+                | ItemType::Window
+                | ItemType::WindowGroup
+                | ItemType::File
+                | ItemType::RecentFile
+                | ItemType::Folder
+                | ItemType::Place
+                | ItemType::Project
+                | ItemType::GitRepo
+                | ItemType::Emoji
+                | ItemType::Timer
+                | ItemType::TimerAction
+                | ItemType::Calculator
+                | ItemType::Converter
+                | ItemType::WorldClock
+                | ItemType::DateCalc
+                | ItemType::CryptoTool
+                | ItemType::HealthCheck
+                | ItemType::SysInfo
+                | ItemType::Battery
+                | ItemType::Generator
+                | ItemType::UnicodeChar
+                | ItemType::WebSearch
+                | ItemType::Bookmark
+                | ItemType::BrowserTab
+                | ItemType::WinePrefix
+                | ItemType::Command
+                | ItemType::Error
+        )
+    }
+
+    /// Human readable name, e.g. "wifi network" for `WifiNetwork`.
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        for c in format!("{:?}", self).chars() {
+            if c.is_uppercase() && !label.is_empty() {
+                label.push(' ');
+            }
+            label.extend(c.to_lowercase());
+        }
+        label
+    }
 }
 
 impl Item {
+    /// Text a screen reader should say for this item.
+    pub fn accessible_label(&self) -> String {
+        match &self.description {
+            Some(description) => format!("{}, {}, {}", self.name.trim(), self.item_type.label(), description),
+            None => format!("{}, {}", self.name.trim(), self.item_type.label()),
+        }
+    }
+
+    /// What "copy" puts on the clipboard: the item's content, location or
+    /// command, falling back to its name.
+    pub fn copy_text(&self) -> String {
+        let m = &self.metadata;
+        if let Some(text) = m.clipboard_content.as_ref().or(m.content.as_ref()) {
+            return text.clone();
+        }
+        if let Some(url) = m.url.as_ref().or(m.uri.as_ref()) {
+            return url.clone();
+        }
+        if let Some(path) = &m.path {
+            return path.display().to_string();
+        }
+        if let Some(host) = &m.host {
+            return match &m.user {
+                Some(user) => format!("{}@{}", user, host),
+                None => host.clone(),
+            };
+        }
+        m.container_id
+            .clone()
+            .or_else(|| m.ssid.clone())
+            .or_else(|| m.mac_address.clone())
+            .or_else(|| m.pid.map(|pid| pid.to_string()))
+            .or_else(|| self.exec.clone())
+            .unwrap_or_else(|| self.name.trim().to_string())
+    }
+
     pub fn new(id: impl Into<String>, name: impl Into<String>, item_type: ItemType) -> Self {
         Self {
             id: id.into(),
@@ -162,6 +346,39 @@ impl Item {
         }
     }
 
+    /// What a provider shows instead of an empty list when it fails: the
+    /// error (Enter retries) and a "Copy error" action. `source` names the
+    /// provider in the ids, `detail` is shown in the details panel.
+    pub fn error_items(source: &str, title: impl Into<String>, detail: impl Into<String>) -> Vec<Item> {
+        let detail = detail.into();
+        let summary = detail.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim().to_string();
+
+        let mut error = Item::new(format!("error:retry:{}", source), title, ItemType::Error)
+            .with_description(format!("{} | Press Enter to retry", summary))
+            .with_icon("dialog-error");
+        error.metadata.error = Some(detail.clone());
+
+        let mut copy = Item::new(format!("error:copy:{}", source), "    Copy error", ItemType::Error)
+            .with_description(summary)
+            .with_icon("edit-copy");
+        copy.metadata.error = Some(detail.clone());
+        copy.metadata.content = Some(detail);
+
+        vec![error, copy]
+    }
+
+    /// Error items for a command that ran but failed, using its stderr
+    /// (or the exit status when stderr is empty).
+    pub fn command_error_items(source: &str, title: impl Into<String>, output: &Output) -> Vec<Item> {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let detail = if stderr.is_empty() {
+            format!("Command failed with {}", output.status)
+        } else {
+            stderr
+        };
+        Self::error_items(source, title, detail)
+    }
+
     pub fn with_description(mut self, desc: impl Into<String>) -> Self {
         self.description = Some(desc.into());
         self
@@ -187,29 +404,6 @@ impl Item {
         self
     }
 
-    pub fn matches(&self, query: &str) -> bool {
-        let query = query.to_lowercase();
-        let name_lower = self.name.to_lowercase();
-
-        if name_lower.contains(&query) {
-            return true;
-        }
-
-        if let Some(desc) = &self.description {
-            if desc.to_lowercase().contains(&query) {
-                return true;
-            }
-        }
-
-        for keyword in &self.keywords {
-            if keyword.to_lowercase().contains(&query) {
-                return true;
-            }
-        }
-
-        false
-    }
-
     pub fn fuzzy_score(&self, query: &str) -> i64 {
         use fuzzy_matcher::skim::SkimMatcherV2;
         use fuzzy_matcher::FuzzyMatcher;
@@ -236,3 +430,59 @@ impl Item {
         best_score
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_only_types() {
+        for item_type in [ItemType::Application, ItemType::File, ItemType::Calculator, ItemType::WebSearch] {
+            assert!(item_type.is_read_only(), "{:?} should be allowed in kiosk mode", item_type);
+        }
+        for item_type in [
+            ItemType::Script,
+            ItemType::Process,
+            ItemType::ClipboardEntry,
+            ItemType::ClipboardAction,
+            ItemType::BitwardenItem,
+            ItemType::BitwardenAction,
+            ItemType::TotpCode,
+            ItemType::Note,
+            ItemType::NoteAction,
+            ItemType::SshConnection,
+            ItemType::TmuxSession,
+            ItemType::ShellAction,
+            ItemType::Package,
+            ItemType::PackageAction,
+            ItemType::FlatpakAction,
+            ItemType::DockerAction,
+            ItemType::FolderAction,
+            ItemType::WifiAction,
+            ItemType::DiskAction,
+            ItemType::AiResponse,
+        ] {
+            assert!(!item_type.is_read_only(), "{:?} should be refused in kiosk mode", item_type);
+        }
+    }
+
+    #[test]
+    fn test_error_items() {
+        let items = Item::error_items("wifi", "Can't list WiFi networks", "\n  Error: NetworkManager is not running.\nmore\n");
+        assert_eq!(items[0].id, "error:retry:wifi");
+        assert_eq!(items[0].description.as_deref(), Some("Error: NetworkManager is not running. | Press Enter to retry"));
+        assert_eq!(items[1].id, "error:copy:wifi");
+        assert_eq!(items[1].description.as_deref(), Some("Error: NetworkManager is not running."));
+        // Copy error copies the whole output
+        assert_eq!(items[1].copy_text(), "\n  Error: NetworkManager is not running.\nmore\n");
+
+        use std::os::unix::process::ExitStatusExt;
+        let output = Output {
+            status: std::process::ExitStatus::from_raw(2 << 8),
+            stdout: Vec::new(),
+            stderr: b"  \n".to_vec(),
+        };
+        let items = Item::command_error_items("docker", "Can't list containers", &output);
+        assert_eq!(items[0].metadata.error.as_deref(), Some("Command failed with exit status: 2"));
+    }
+}
@@ -1,7 +1,14 @@
 pub mod config;
 pub mod indexer;
 pub mod item;
+pub mod number_format;
+pub mod runner;
+pub mod settings;
+pub mod store;
 
 pub use config::Config;
 pub use indexer::Indexer;
 pub use item::{Item, ItemType};
+pub use number_format::NumberFormat;
+pub use runner::{CommandRunner, SystemRunner};
+pub use store::{Keyed, SyncedStore};
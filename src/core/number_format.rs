@@ -0,0 +1,206 @@
+//! Decimal and thousands separators of the user's locale, for the
+//! calculator and converter: "3,14 * 2" in German, "1,234.5" in English.
+
+use crate::core::config::NumberFormatConfig;
+use regex::Regex;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberFormat {
+    decimal: char,
+    group: Option<char>,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimal: '.',
+            group: Some(','),
+        }
+    }
+}
+
+impl NumberFormat {
+    /// The configured separators, falling back to the configured or
+    /// environment locale (LC_ALL, LC_NUMERIC, LANG).
+    pub fn new(config: &NumberFormatConfig) -> Self {
+        let locale = config.locale.clone().or_else(|| {
+            ["LC_ALL", "LC_NUMERIC", "LANG"]
+                .iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .find(|value| !value.is_empty())
+        });
+        let mut format = locale.as_deref().map(Self::for_locale).unwrap_or_default();
+        if let Some(decimal) = config.decimal_separator.as_deref().and_then(|s| s.chars().next()) {
+            format.decimal = decimal;
+        }
+        if let Some(group) = &config.group_separator {
+            format.group = group.chars().next();
+        }
+        format
+    }
+
+    /// Separators for a POSIX locale name such as "de_DE.UTF-8" or "fr_CH".
+    pub fn for_locale(locale: &str) -> Self {
+        let name = locale.split(['.', '@']).next().unwrap_or("");
+        let (language, region) = name.split_once(['_', '-']).unwrap_or((name, ""));
+        let (decimal, group) = match (language.to_lowercase().as_str(), region.to_uppercase().as_str()) {
+            (_, "CH") | (_, "LI") => ('.', '\''),
+            ("de" | "nl" | "it" | "es" | "pt" | "id" | "tr" | "da" | "el" | "ro" | "hr" | "sl" | "sr", _) => (',', '.'),
+            ("fr", _) => (',', '\u{202f}'),
+            ("ru" | "uk" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "nn" | "no" | "hu" | "bg" | "lt" | "lv" | "et", _) => {
+                (',', '\u{a0}')
+            }
+            _ => ('.', ','),
+        };
+        Self {
+            decimal,
+            group: Some(group),
+        }
+    }
+
+    /// Rewrites the numbers in `expr` with '.' decimals and no grouping, so
+    /// the parsers only deal with one form.
+    ///
+    /// Either separator is understood: with both, the last one is the decimal
+    /// separator; a single one is grouping only when it isn't the locale's
+    /// decimal separator and is followed by exactly three digits.
+    pub fn normalize(&self, expr: &str) -> String {
+        static NUMBER: OnceLock<Regex> = OnceLock::new();
+        let number = NUMBER.get_or_init(|| Regex::new(r"\d+(?:[.,'\u{a0}\u{202f}]\d+)+").unwrap());
+        number
+            .replace_all(expr, |caps: &regex::Captures| self.canonical(&caps[0]).unwrap_or_else(|| caps[0].to_string()))
+            .into_owned()
+    }
+
+    /// Parses a single number typed in either form.
+    pub fn parse(&self, number: &str) -> Option<f64> {
+        self.normalize(number.trim()).parse().ok()
+    }
+
+    fn canonical(&self, token: &str) -> Option<String> {
+        let separators: Vec<(usize, char)> = token.char_indices().filter(|(_, c)| !c.is_ascii_digit()).collect();
+        let (last_index, last) = *separators.last()?;
+        let spaces = ['\'', '\u{a0}', '\u{202f}'];
+
+        let marks = separators.iter().filter(|(_, c)| !spaces.contains(c)).count();
+
+        let decimal = if spaces.contains(&last) {
+            None
+        } else if separators.iter().any(|(_, c)| *c != last && !spaces.contains(c)) {
+            // "1.234,5": the last of the two is the decimal separator
+            Some(last_index)
+        } else if marks > 1 {
+            // "1.234.567"
+            None
+        } else if last == self.decimal || token.len() - last_index - 1 != 3 {
+            Some(last_index)
+        } else {
+            None
+        };
+
+        let (integer, fraction) = match decimal {
+            Some(index) => (&token[..index], Some(&token[index + 1..])),
+            None => (token, None),
+        };
+        // Groups after the first have three digits
+        let mut groups = integer.split(|c: char| !c.is_ascii_digit());
+        groups.next();
+        if !groups.all(|group| group.len() == 3) {
+            return None;
+        }
+
+        let mut canonical: String = integer.chars().filter(char::is_ascii_digit).collect();
+        if let Some(fraction) = fraction {
+            canonical.push('.');
+            canonical.push_str(fraction);
+        }
+        Some(canonical)
+    }
+
+    /// `value` with up to `decimals` decimals (trailing zeros dropped) and
+    /// the locale's separators.
+    pub fn format(&self, value: f64, decimals: usize) -> String {
+        let plain = if value.fract() == 0.0 && value.abs() < 1e15 {
+            format!("{}", value as i64)
+        } else {
+            format!("{:.*}", decimals, value).trim_end_matches('0').trim_end_matches('.').to_string()
+        };
+        self.localize(&plain)
+    }
+
+    /// `value` with exactly `decimals` decimals, as amounts of money are shown.
+    pub fn format_fixed(&self, value: f64, decimals: usize) -> String {
+        self.localize(&format!("{:.*}", decimals, value))
+    }
+
+    /// A formatted number without its grouping, for copying.
+    pub fn ungrouped(&self, formatted: &str) -> String {
+        match self.group {
+            Some(group) => formatted.replace(group, ""),
+            None => formatted.to_string(),
+        }
+    }
+
+    fn localize(&self, plain: &str) -> String {
+        let (sign, digits) = match plain.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", plain),
+        };
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let mut result = sign.to_string();
+        for (i, c) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                if let Some(group) = self.group {
+                    result.push(group);
+                }
+            }
+            result.push(c);
+        }
+        if !fraction.is_empty() {
+            result.push(self.decimal);
+            result.push_str(fraction);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let de = NumberFormat::for_locale("de_DE.UTF-8");
+        assert_eq!(de.normalize("3,14 * 2"), "3.14 * 2");
+        assert_eq!(de.normalize("1.234,5 + 0.5"), "1234.5 + 0.5");
+        assert_eq!(de.normalize("1.234"), "1234");
+        assert_eq!(de.parse("1.000.000"), Some(1_000_000.0));
+
+        let en = NumberFormat::for_locale("en_US.UTF-8");
+        assert_eq!(en.normalize("1,234.5 - 3,14"), "1234.5 - 3.14");
+        assert_eq!(en.normalize("1,234"), "1234");
+        // Not valid grouping, left for the parser to reject
+        assert_eq!(en.normalize("1,23,4"), "1,23,4");
+
+        let ch = NumberFormat::for_locale("de_CH");
+        assert_eq!(ch.normalize("1'234.5"), "1234.5");
+    }
+
+    #[test]
+    fn test_format() {
+        let de = NumberFormat::for_locale("de_DE");
+        assert_eq!(de.format(1234567.891, 2), "1.234.567,89");
+        assert_eq!(de.format(-6.25, 6), "-6,25");
+        assert_eq!(de.format_fixed(1500.0, 2), "1.500,00");
+        assert_eq!(de.ungrouped("1.234,5"), "1234,5");
+
+        let fr = NumberFormat::for_locale("fr_FR");
+        assert_eq!(fr.format(12345.5, 2), "12\u{202f}345,5");
+
+        let en = NumberFormat::for_locale("C");
+        assert_eq!(en.format(999.0, 2), "999");
+        assert_eq!(en.format(1000.0, 2), "1,000");
+    }
+}
@@ -0,0 +1,76 @@
+//! External commands run by the system integrations (nmcli, wpctl, docker…),
+//! behind a trait so their output parsing can be tested with canned output.
+
+use std::io;
+use std::process::{Command, Output};
+use std::sync::Arc;
+
+pub trait CommandRunner: Send + Sync {
+    /// Runs `program` with `args` to completion, capturing its output.
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<Output>;
+}
+
+/// Runs commands for real.
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<Output> {
+        Command::new(program).args(args).output()
+    }
+}
+
+impl SystemRunner {
+    pub fn shared() -> Arc<dyn CommandRunner> {
+        Arc::new(SystemRunner)
+    }
+}
+
+/// Answers commands with canned output; anything not registered fails as if
+/// the program wasn't installed.
+#[cfg(test)]
+#[derive(Default)]
+pub struct FakeRunner {
+    outputs: std::collections::HashMap<String, (i32, String, String)>,
+}
+
+#[cfg(test)]
+impl FakeRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a successful run of `command` ("program arg arg…").
+    pub fn with(mut self, command: &str, stdout: &str) -> Self {
+        self.outputs.insert(command.to_string(), (0, stdout.to_string(), String::new()));
+        self
+    }
+
+    /// Registers a run of `command` exiting with `code`.
+    pub fn failing(mut self, command: &str, code: i32, stderr: &str) -> Self {
+        self.outputs.insert(command.to_string(), (code, String::new(), stderr.to_string()));
+        self
+    }
+
+    pub fn shared(self) -> Arc<dyn CommandRunner> {
+        Arc::new(self)
+    }
+}
+
+#[cfg(test)]
+impl CommandRunner for FakeRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<Output> {
+        use std::os::unix::process::ExitStatusExt;
+
+        let command = std::iter::once(program).chain(args.iter().copied()).collect::<Vec<_>>().join(" ");
+        let (code, stdout, stderr) = self
+            .outputs
+            .get(&command)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no fixture for `{}`", command)))?;
+        Ok(Output {
+            // Wait status: the exit code lives in the second byte
+            status: std::process::ExitStatus::from_raw(code << 8),
+            stdout: stdout.clone().into_bytes(),
+            stderr: stderr.clone().into_bytes(),
+        })
+    }
+}
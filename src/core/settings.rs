@@ -0,0 +1,188 @@
+//! `wlaunch config get|set|list`: single settings by their dotted path
+//! (`clipboard_history_size`, `theme.preset`, `app_overrides.Spotify.workspace`).
+//! Setting one edits config.json in place, keeping the order of its keys,
+//! and refuses values that would stop it from loading, or keys wlaunch
+//! doesn't know. API keys and tokens are only shown when asked for by name.
+
+use super::Config;
+use anyhow::{anyhow, Context, Result};
+use serde_json::{Map, Value};
+use std::fs;
+
+/// Settings holding credentials.
+const SECRETS: &[&str] = &["gemini_api_key", "ai.api_key", "todo_sync.token"];
+const HIDDEN: &str = "(hidden)";
+
+/// The effective value of `key`; `null` when unset, meaning the built-in
+/// default applies. Secrets inside a section are hidden.
+pub fn get(key: &str) -> Result<Value> {
+    let config = serde_json::to_value(Config::load()?)?;
+    let mut value = lookup(&config, key).cloned().ok_or_else(|| anyhow!("Unknown setting {}", key))?;
+    redact(key, &mut value);
+    Ok(value)
+}
+
+/// Every setting as `(dotted key, value)`, sorted by key, with secrets hidden.
+pub fn list() -> Result<Vec<(String, Value)>> {
+    let mut settings = Vec::new();
+    flatten("", &serde_json::to_value(Config::load()?)?, &mut settings);
+    for (key, value) in &mut settings {
+        if SECRETS.contains(&key.as_str()) && !value.is_null() {
+            *value = Value::String(HIDDEN.to_string());
+        }
+    }
+    settings.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(settings)
+}
+
+/// Hides the secrets below `key` in its `value`; a secret asked for by
+/// its own name is shown.
+fn redact(key: &str, value: &mut Value) {
+    for secret in SECRETS {
+        let Some(rest) = secret.strip_prefix(key).and_then(|rest| rest.strip_prefix('.')) else {
+            continue;
+        };
+        let target = rest.split('.').try_fold(&mut *value, |value, part| value.as_object_mut()?.get_mut(part));
+        if let Some(target) = target.filter(|target| target.is_string()) {
+            *target = Value::String(HIDDEN.to_string());
+        }
+    }
+}
+
+/// Sets `key` to `raw`, read as JSON (`200`, `true`, `["~/bin"]`, `null`
+/// to unset) or else as a string, and saves config.json. Returns the value
+/// stored, hidden for secrets.
+pub fn set(key: &str, raw: &str) -> Result<Value> {
+    let path = Config::config_path();
+    let mut file = if path.exists() {
+        serde_json::from_str(&fs::read_to_string(&path)?).with_context(|| format!("{} is invalid", path.display()))?
+    } else {
+        Value::Object(Map::new())
+    };
+    let value = updated(&mut file, key, raw)?;
+    fs::create_dir_all(Config::config_dir())?;
+    fs::write(&path, serde_json::to_string_pretty(&file)?)?;
+    if SECRETS.contains(&key) && value.is_string() {
+        return Ok(Value::String(HIDDEN.to_string()));
+    }
+    Ok(value)
+}
+
+/// Applies the change to the parsed config.json and checks that it loads.
+fn updated(file: &mut Value, key: &str, raw: &str) -> Result<Value> {
+    let parsed = serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+    let mut candidate = file.clone();
+    insert(&mut candidate, key, parsed.clone())?;
+    let result = match validate(&candidate, key) {
+        // `workspace 9` means the string "9"
+        Err(_) if !parsed.is_string() && !parsed.is_null() => {
+            candidate = file.clone();
+            insert(&mut candidate, key, Value::String(raw.to_string()))?;
+            validate(&candidate, key).map(|_| Value::String(raw.to_string()))
+        }
+        result => result.map(|_| parsed),
+    };
+    let value = result?;
+    *file = candidate;
+    Ok(value)
+}
+
+/// Whether `file` loads, and still has `key` once loaded: keys wlaunch
+/// doesn't know are dropped when loading.
+fn validate(file: &Value, key: &str) -> Result<()> {
+    let config: Config = serde_json::from_value(file.clone()).map_err(|e| anyhow!("Invalid value for {}: {}", key, e))?;
+    match lookup(&serde_json::to_value(config)?, key) {
+        Some(_) => Ok(()),
+        None => Err(anyhow!("Unknown setting {}", key)),
+    }
+}
+
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(value, |value, part| value.as_object()?.get(part))
+}
+
+/// Sets `key` in `value`, creating the objects on the way.
+fn insert(value: &mut Value, key: &str, new: Value) -> Result<()> {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let last = parts.pop().filter(|part| !part.is_empty()).ok_or_else(|| anyhow!("Empty setting name"))?;
+    let mut current = value;
+    for part in parts {
+        let object = current.as_object_mut().ok_or_else(|| anyhow!("{} isn't a section", key))?;
+        current = object.entry(part).or_insert_with(|| Value::Object(Map::new()));
+        if current.is_null() {
+            *current = Value::Object(Map::new());
+        }
+    }
+    current
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("{} isn't a section", key))?
+        .insert(last.to_string(), new);
+    Ok(())
+}
+
+fn flatten(prefix: &str, value: &Value, settings: &mut Vec<(String, Value)>) {
+    match value.as_object() {
+        Some(object) if !object.is_empty() => {
+            for (key, value) in object {
+                let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten(&key, value, settings);
+            }
+        }
+        _ => settings.push((prefix.to_string(), value.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_set() {
+        let mut file = json!({ "theme": { "preset": "nord" } });
+        assert_eq!(updated(&mut file, "clipboard_history_size", "200").unwrap(), json!(200));
+        assert_eq!(updated(&mut file, "files.backend", "fd").unwrap(), json!("fd"));
+        assert_eq!(updated(&mut file, "app_overrides.Spotify.workspace", "9").unwrap(), json!("9"));
+        assert_eq!(
+            file,
+            json!({
+                "theme": { "preset": "nord" },
+                "clipboard_history_size": 200,
+                "files": { "backend": "fd" },
+                "app_overrides": { "Spotify": { "workspace": "9" } }
+            })
+        );
+
+        let invalid = updated(&mut file, "clipboard_history_size", "lots").unwrap_err().to_string();
+        assert!(invalid.starts_with("Invalid value for clipboard_history_size"), "{}", invalid);
+        assert_eq!(updated(&mut file, "clipbaord_history_size", "10").unwrap_err().to_string(), "Unknown setting clipbaord_history_size");
+        assert_eq!(updated(&mut file, "theme.presett", "nord").unwrap_err().to_string(), "Unknown setting theme.presett");
+        // Rejected changes leave the file alone
+        assert_eq!(file["clipboard_history_size"], json!(200));
+
+        // The file keeps its order, new keys go last
+        let mut settings = Vec::new();
+        flatten("", &file, &mut settings);
+        let keys: Vec<&str> = settings.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["theme.preset", "clipboard_history_size", "files.backend", "app_overrides.Spotify.workspace"]);
+    }
+
+    #[test]
+    fn test_redact() {
+        let mut ai = json!({ "provider": "openai", "api_key": "sk-123", "prompts": [] });
+        redact("ai", &mut ai);
+        assert_eq!(ai, json!({ "provider": "openai", "api_key": "(hidden)", "prompts": [] }));
+
+        let mut config = json!({ "gemini_api_key": "AIza", "ai": { "api_key": null }, "todo_sync": { "token": "t0k" } });
+        redact("", &mut config);
+        // Only a key's own section is searched; the whole config isn't a key
+        assert_eq!(config["gemini_api_key"], json!("AIza"));
+        redact("todo_sync", &mut config["todo_sync"]);
+        assert_eq!(config["todo_sync"], json!({ "token": "(hidden)" }));
+
+        // Asked for by name
+        let mut key = json!("sk-123");
+        redact("ai.api_key", &mut key);
+        assert_eq!(key, json!("sk-123"));
+    }
+}
@@ -0,0 +1,295 @@
+//! JSON lists that may live in a folder synced by another program
+//! (Syncthing, Dropbox, Nextcloud): reloaded when the file changes on disk,
+//! and merged with the conflict copies sync tools leave behind.
+//!
+//! Saving merges with changes made elsewhere since the file was read, entry
+//! by entry against the copy read then: entries edited or deleted only on
+//! the other side take that side's version, and local edits win otherwise.
+
+use crate::core::Config;
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Entries are matched across copies of a file by this key.
+pub trait Keyed {
+    fn key(&self) -> &str;
+}
+
+pub struct SyncedStore {
+    path: PathBuf,
+    /// Modification time of the file when it was last read or written
+    modified: Option<SystemTime>,
+    /// Entries in the file when it was last read or written, by key, to
+    /// tell which side added, changed or deleted one
+    known: HashMap<String, Value>,
+}
+
+impl SyncedStore {
+    /// `name` in `sync_dir` if one is configured, copying over the local
+    /// file the first time.
+    pub fn new(config: &Config, name: &str) -> Self {
+        let path = config.synced_path(name);
+        let local = Config::data_path(name);
+        if path != local && !path.exists() && local.exists() {
+            if let Some(dir) = path.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            if let Err(e) = fs::copy(&local, &path) {
+                log::warn!("Could not copy {} to {}: {}", local.display(), path.display(), e);
+            }
+        }
+        Self::at(path)
+    }
+
+    /// A store at `path` as is, without the sync directory.
+    pub(crate) fn at(path: PathBuf) -> Self {
+        Self {
+            path,
+            modified: None,
+            known: HashMap::new(),
+        }
+    }
+
+    fn modified_on_disk(&self) -> Option<SystemTime> {
+        fs::metadata(&self.path).and_then(|m| m.modified()).ok()
+    }
+
+    /// Whether the file was changed by someone else since it was last read
+    /// or written.
+    pub fn changed(&self) -> bool {
+        self.modified_on_disk() != self.modified
+    }
+
+    /// Reads the file, folding in entries that only exist in conflict copies.
+    /// Merged copies are renamed to `*.merged` rather than deleted.
+    pub fn load<T: Serialize + DeserializeOwned + Keyed>(&mut self) -> Result<Vec<T>> {
+        let mut items: Vec<T> = if self.path.exists() { read(&self.path)? } else { Vec::new() };
+
+        let conflicts = self.conflict_copies();
+        if conflicts.is_empty() {
+            self.remember(&items);
+            return Ok(items);
+        }
+
+        for copy in conflicts {
+            match read::<T>(&copy) {
+                Ok(entries) => {
+                    for entry in entries {
+                        if !items.iter().any(|item| item.key() == entry.key()) {
+                            items.push(entry);
+                        }
+                    }
+                    let mut merged = copy.clone().into_os_string();
+                    merged.push(".merged");
+                    let _ = fs::rename(&copy, merged);
+                }
+                Err(e) => log::warn!("Skipping unreadable conflict copy {}: {}", copy.display(), e),
+            }
+        }
+        self.write(&items)?;
+        Ok(items)
+    }
+
+    /// Writes `items`, first merging in what another machine added, changed
+    /// or deleted since the file was read so a sync in between doesn't get
+    /// overwritten.
+    pub fn save<T: Serialize + DeserializeOwned + Keyed>(&mut self, items: &mut Vec<T>) -> Result<()> {
+        if self.changed() && self.path.exists() {
+            let on_disk: Vec<T> = read(&self.path)?;
+            self.merge(items, on_disk);
+        }
+        self.write(items)
+    }
+
+    /// Three-way merge of `items` with `on_disk`, using the entries last
+    /// read or written as the common ancestor.
+    fn merge<T: Serialize + Keyed>(&self, items: &mut Vec<T>, on_disk: Vec<T>) {
+        let unchanged_here = |item: &T| self.known.get(item.key()) == serde_json::to_value(item).ok().as_ref();
+
+        // Deleted elsewhere and not edited here
+        items.retain(|item| {
+            !self.known.contains_key(item.key())
+                || on_disk.iter().any(|entry| entry.key() == item.key())
+                || !unchanged_here(item)
+        });
+
+        for entry in on_disk {
+            let known = self.known.get(entry.key());
+            match items.iter().position(|item| item.key() == entry.key()) {
+                // Edited elsewhere only
+                Some(index) if unchanged_here(&items[index]) && known != serde_json::to_value(&entry).ok().as_ref() => {
+                    items[index] = entry;
+                }
+                Some(_) => {}
+                // Added elsewhere; a known one was deleted here
+                None if known.is_none() => items.push(entry),
+                None => {}
+            }
+        }
+    }
+
+    fn write<T: Serialize + Keyed>(&mut self, items: &[T]) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(items)?)?;
+        self.remember(items);
+        Ok(())
+    }
+
+    fn remember<T: Serialize + Keyed>(&mut self, items: &[T]) {
+        self.modified = self.modified_on_disk();
+        self.known = items
+            .iter()
+            .filter_map(|item| Some((item.key().to_string(), serde_json::to_value(item).ok()?)))
+            .collect();
+    }
+
+    /// `todos.sync-conflict-20240101-120000-ABC1234.json` (Syncthing) and
+    /// `todos (conflicted copy 2024-01-01).json` (Dropbox, Nextcloud).
+    fn conflict_copies(&self) -> Vec<PathBuf> {
+        let (Some(dir), Some(stem)) = (self.path.parent(), self.path.file_stem().and_then(|s| s.to_str())) else {
+            return Vec::new();
+        };
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut copies: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                name.starts_with(stem)
+                    && name.ends_with(".json")
+                    && name != format!("{}.json", stem)
+                    && name.to_lowercase().contains("conflict")
+            })
+            .collect();
+        copies.sort();
+        copies
+    }
+}
+
+fn read<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Entry {
+        id: String,
+        #[serde(default)]
+        text: String,
+    }
+
+    impl Keyed for Entry {
+        fn key(&self) -> &str {
+            &self.id
+        }
+    }
+
+    fn entries(ids: &[&str]) -> Vec<Entry> {
+        ids.iter()
+            .map(|id| Entry {
+                id: id.to_string(),
+                text: String::new(),
+            })
+            .collect()
+    }
+
+    fn ids(items: &[Entry]) -> Vec<&str> {
+        items.iter().map(|e| e.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_conflicts_and_external_changes() {
+        let dir = std::env::temp_dir().join(format!("wlaunch-store-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("todos.json");
+        fs::write(&path, serde_json::to_string(&entries(&["a", "b"])).unwrap()).unwrap();
+        let conflict = dir.join("todos.sync-conflict-20260101-120000-ABC1234.json");
+        fs::write(&conflict, serde_json::to_string(&entries(&["a", "c"])).unwrap()).unwrap();
+
+        let mut store = SyncedStore::at(path.clone());
+        let mut items: Vec<Entry> = store.load().unwrap();
+        assert_eq!(ids(&items), ["a", "b", "c"]);
+        assert!(!conflict.exists());
+        assert!(!store.changed());
+
+        // Another machine adds "d" while "b" is deleted here
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(&path, serde_json::to_string(&entries(&["a", "b", "c", "d"])).unwrap()).unwrap();
+        assert!(store.changed());
+        items.retain(|e| e.id != "b");
+        store.save(&mut items).unwrap();
+        assert_eq!(ids(&items), ["a", "c", "d"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    fn texts(items: &[Entry]) -> Vec<(&str, &str)> {
+        items.iter().map(|e| (e.id.as_str(), e.text.as_str())).collect()
+    }
+
+    fn edit(items: &mut [Entry], id: &str, text: &str) {
+        items.iter_mut().find(|e| e.id == id).unwrap().text = text.to_string();
+    }
+
+    #[test]
+    fn test_edits_and_deletes_across_stores() {
+        let dir = std::env::temp_dir().join(format!("wlaunch-store-merge-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("notes.json");
+        fs::write(&path, serde_json::to_string(&entries(&["a", "b", "c", "d"])).unwrap()).unwrap();
+
+        // Two machines sharing the file
+        let mut here = SyncedStore::at(path.clone());
+        let mut there = SyncedStore::at(path.clone());
+        let mut items: Vec<Entry> = here.load().unwrap();
+        let mut theirs: Vec<Entry> = there.load().unwrap();
+
+        // There: edit "a" and "c", delete "b" and "d"
+        edit(&mut theirs, "a", "from there");
+        edit(&mut theirs, "c", "from there");
+        theirs.retain(|e| e.id != "b" && e.id != "d");
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        there.save(&mut theirs).unwrap();
+
+        // Here: edit "c" and "d", add "e", without reloading
+        edit(&mut items, "c", "from here");
+        edit(&mut items, "d", "from here");
+        items.push(Entry {
+            id: "e".to_string(),
+            text: "new".to_string(),
+        });
+        here.save(&mut items).unwrap();
+
+        // "b" stays deleted, "d" is kept for its edit, "c" keeps the local edit
+        assert_eq!(
+            texts(&items),
+            [("a", "from there"), ("c", "from here"), ("d", "from here"), ("e", "new")]
+        );
+        let on_disk: Vec<Entry> = read(&path).unwrap();
+        assert_eq!(on_disk, items);
+
+        // Back there, deleting "a" sticks and the edit and additions come in
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        theirs.retain(|e| e.id != "a");
+        there.save(&mut theirs).unwrap();
+        assert_eq!(texts(&theirs), [("c", "from here"), ("d", "from here"), ("e", "new")]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
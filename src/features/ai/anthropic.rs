@@ -0,0 +1,133 @@
+use super::{forward_lines, sse_data, AiEvent, AiProvider, ChatMessage, Role};
+use crate::core::config::AiConfig;
+use anyhow::Result;
+use iced::futures::channel::mpsc;
+use iced::futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com/v1";
+const DEFAULT_MODEL: &str = "claude-3-5-haiku-latest";
+const API_VERSION: &str = "2023-06-01";
+const MAX_TOKENS: u32 = 1024;
+
+pub struct Anthropic {
+    api_key: Option<String>,
+    model: String,
+    base_url: String,
+}
+
+#[derive(Serialize)]
+struct MessagesRequest {
+    model: String,
+    max_tokens: u32,
+    messages: Vec<RequestMessage>,
+    stream: bool,
+}
+
+#[derive(Serialize)]
+struct RequestMessage {
+    role: &'static str,
+    content: String,
+}
+
+/// Only the event types that carry text or errors are of interest.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    ContentBlockDelta { delta: Delta },
+    Error { error: ApiError },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+struct Delta {
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+impl Anthropic {
+    pub fn new(config: &AiConfig) -> Self {
+        Self {
+            api_key: config.api_key.clone(),
+            model: config.model.clone().unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            base_url: config.base_url.clone().unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+        }
+    }
+
+    fn parse_line(line: &str) -> Result<Option<String>> {
+        let Some(data) = sse_data(line) else {
+            return Ok(None);
+        };
+        match serde_json::from_str(data)? {
+            StreamEvent::ContentBlockDelta { delta } => Ok(delta.text),
+            StreamEvent::Error { error } => Err(anyhow::anyhow!("API error: {}", error.message)),
+            StreamEvent::Other => Ok(None),
+        }
+    }
+}
+
+impl AiProvider for Anthropic {
+    fn name(&self) -> String {
+        format!("Anthropic ({})", self.model)
+    }
+
+    fn missing_config(&self) -> Option<&'static str> {
+        self.api_key
+            .is_none()
+            .then_some("Add ai.api_key to ~/.config/wlaunch/config.json")
+    }
+
+    fn stream(
+        &self,
+        messages: Vec<ChatMessage>,
+        output: mpsc::Sender<AiEvent>,
+    ) -> BoxFuture<'static, Result<()>> {
+        let url = format!("{}/messages", self.base_url);
+        let api_key = self.api_key.clone().unwrap_or_default();
+        let request = MessagesRequest {
+            model: self.model.clone(),
+            max_tokens: MAX_TOKENS,
+            messages: messages
+                .into_iter()
+                .map(|m| RequestMessage {
+                    role: match m.role {
+                        Role::User => "user",
+                        Role::Assistant => "assistant",
+                    },
+                    content: m.content,
+                })
+                .collect(),
+            stream: true,
+        };
+
+        Box::pin(async move {
+            let response = reqwest::Client::new()
+                .post(&url)
+                .header("x-api-key", api_key)
+                .header("anthropic-version", API_VERSION)
+                .json(&request)
+                .send()
+                .await?;
+            forward_lines(response, output, Self::parse_line).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let line = r#"data: {"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Hi"}}"#;
+        assert_eq!(Anthropic::parse_line(line).unwrap(), Some("Hi".to_string()));
+        assert_eq!(Anthropic::parse_line("event: ping").unwrap(), None);
+        assert_eq!(Anthropic::parse_line(r#"data: {"type": "ping"}"#).unwrap(), None);
+        assert!(Anthropic::parse_line(r#"data: {"type": "error", "error": {"message": "overloaded"}}"#).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use super::{forward_lines, sse_data, AiEvent, AiProvider, ChatMessage, Role};
+use crate::core::config::AiConfig;
+use anyhow::Result;
+use iced::futures::channel::mpsc;
+use iced::futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+const DEFAULT_MODEL: &str = "gemini-pro";
+
+pub struct Gemini {
+    api_key: Option<String>,
+    model: String,
+    base_url: String,
+}
+
+#[derive(Serialize)]
+struct GeminiRequest {
+    contents: Vec<GeminiContent>,
+}
+
+#[derive(Serialize)]
+struct GeminiContent {
+    role: &'static str,
+    parts: Vec<GeminiPart>,
+}
+
+#[derive(Serialize)]
+struct GeminiPart {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct GeminiResponse {
+    candidates: Option<Vec<GeminiCandidate>>,
+    error: Option<GeminiError>,
+}
+
+#[derive(Deserialize)]
+struct GeminiCandidate {
+    // The final streamed chunk may only carry a finish reason
+    #[serde(default)]
+    content: GeminiResponseContent,
+}
+
+#[derive(Deserialize, Default)]
+struct GeminiResponseContent {
+    #[serde(default)]
+    parts: Vec<GeminiResponsePart>,
+}
+
+#[derive(Deserialize)]
+struct GeminiResponsePart {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct GeminiError {
+    message: String,
+}
+
+impl Gemini {
+    pub fn new(config: &AiConfig, legacy_key: Option<String>) -> Self {
+        Self {
+            api_key: config.api_key.clone().or(legacy_key),
+            model: config.model.clone().unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            base_url: config.base_url.clone().unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+        }
+    }
+
+    fn parse_line(line: &str) -> Result<Option<String>> {
+        let Some(data) = sse_data(line) else {
+            return Ok(None);
+        };
+        let response: GeminiResponse = serde_json::from_str(data)?;
+        if let Some(error) = response.error {
+            return Err(anyhow::anyhow!("API error: {}", error.message));
+        }
+
+        let text = response
+            .candidates
+            .and_then(|c| c.into_iter().next())
+            .map(|c| c.content.parts.into_iter().map(|p| p.text).collect::<Vec<_>>().join(""));
+        Ok(text)
+    }
+}
+
+impl AiProvider for Gemini {
+    fn name(&self) -> String {
+        format!("Gemini ({})", self.model)
+    }
+
+    fn missing_config(&self) -> Option<&'static str> {
+        self.api_key
+            .is_none()
+            .then_some("Add ai.api_key (or gemini_api_key) to ~/.config/wlaunch/config.json")
+    }
+
+    fn stream(
+        &self,
+        messages: Vec<ChatMessage>,
+        output: mpsc::Sender<AiEvent>,
+    ) -> BoxFuture<'static, Result<()>> {
+        let url = format!(
+            "{}/models/{}:streamGenerateContent?alt=sse&key={}",
+            self.base_url,
+            self.model,
+            self.api_key.clone().unwrap_or_default()
+        );
+        let request = GeminiRequest {
+            contents: messages
+                .into_iter()
+                .map(|m| GeminiContent {
+                    role: match m.role {
+                        Role::User => "user",
+                        Role::Assistant => "model",
+                    },
+                    parts: vec![GeminiPart { text: m.content }],
+                })
+                .collect(),
+        };
+
+        Box::pin(async move {
+            let response = reqwest::Client::new().post(&url).json(&request).send().await?;
+            forward_lines(response, output, Self::parse_line).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let line = r#"data: {"candidates": [{"content": {"parts": [{"text": "Hel"}, {"text": "lo"}]}}]}"#;
+        assert_eq!(Gemini::parse_line(line).unwrap(), Some("Hello".to_string()));
+        assert_eq!(Gemini::parse_line(": keep-alive").unwrap(), None);
+        assert!(Gemini::parse_line(r#"data: {"error": {"message": "quota"}}"#).is_err());
+    }
+}
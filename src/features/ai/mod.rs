@@ -0,0 +1,311 @@
+//! AI assistant. The backend is chosen by `ai.provider` in the config; each
+//! provider streams its answer as [`AiEvent`]s.
+
+mod anthropic;
+mod gemini;
+mod ollama;
+mod openai;
+mod presets;
+
+use crate::core::config::AiPrompt;
+use crate::core::{Config, Item, ItemType};
+use anyhow::Result;
+use iced::futures::channel::mpsc;
+use iced::futures::future::BoxFuture;
+use iced::futures::{SinkExt, Stream};
+use std::sync::Arc;
+
+/// One question/answer pair of the current session.
+#[derive(Debug, Clone)]
+pub struct ChatTurn {
+    pub question: String,
+    pub answer: String,
+    pub streaming: bool,
+    pub error: Option<String>,
+}
+
+/// Progress of a streamed answer.
+#[derive(Debug, Clone)]
+pub enum AiEvent {
+    Chunk(String),
+    /// Carries the error message if the request failed
+    Done(Option<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatMessage {
+    pub role: Role,
+    pub content: String,
+}
+
+/// A chat backend.
+pub trait AiProvider: Send + Sync {
+    /// Display name, e.g. "Ollama (llama3.2)"
+    fn name(&self) -> String;
+
+    /// Explains what's missing from the config, if the provider can't be used.
+    fn missing_config(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Sends `messages` and forwards each piece of the answer to `output`.
+    fn stream(
+        &self,
+        messages: Vec<ChatMessage>,
+        output: mpsc::Sender<AiEvent>,
+    ) -> BoxFuture<'static, Result<()>>;
+}
+
+#[derive(Clone)]
+pub struct AiManager {
+    provider: Arc<dyn AiProvider>,
+    prompts: Vec<AiPrompt>,
+    conversation: Vec<ChatTurn>,
+}
+
+impl AiManager {
+    pub fn new(config: &Config) -> Self {
+        let ai = &config.ai;
+        let provider: Arc<dyn AiProvider> = match ai.provider() {
+            "openai" => Arc::new(openai::OpenAi::new(ai)),
+            "anthropic" | "claude" => Arc::new(anthropic::Anthropic::new(ai)),
+            "ollama" => Arc::new(ollama::Ollama::new(ai)),
+            _ => Arc::new(gemini::Gemini::new(ai, config.gemini_api_key.clone())),
+        };
+
+        Self {
+            provider,
+            prompts: presets::presets(&ai.prompts),
+            conversation: Vec::new(),
+        }
+    }
+
+    pub fn conversation(&self) -> &[ChatTurn] {
+        &self.conversation
+    }
+
+    /// The answer of the last finished turn, if it succeeded.
+    pub fn last_answer(&self) -> Option<&str> {
+        self.conversation
+            .last()
+            .filter(|turn| !turn.streaming && turn.error.is_none() && !turn.answer.is_empty())
+            .map(|turn| turn.answer.as_str())
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.conversation.last().is_some_and(|turn| turn.streaming)
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let mut items = Vec::new();
+
+        if let Some(missing) = self.provider.missing_config() {
+            items.push(
+                Item::new("ai:no_key", format!("{} is not configured", self.provider.name()), ItemType::AiQuery)
+                    .with_description(missing)
+                    .with_icon("dialog-warning"),
+            );
+            return items;
+        }
+
+        if query.is_empty() {
+            items.push(
+                Item::new("ai:hint", "Ask a question...", ItemType::AiQuery)
+                    .with_description(format!("Type your question to query {}", self.provider.name()))
+                    .with_icon("dialog-question"),
+            );
+        } else {
+            items.push(
+                Item::new(format!("ai:query:{}", query), format!("Ask: {}", query), ItemType::AiQuery)
+                    .with_description(format!("Press Enter to query {}", self.provider.name()))
+                    .with_icon("dialog-question"),
+            );
+        }
+
+        // Clipboard transforms: "translate german" matches the translate preset
+        let (word, input) = query.split_once(' ').unwrap_or((query, ""));
+        let word = word.to_lowercase();
+        for preset in &self.prompts {
+            if !query.is_empty() && !preset.name.starts_with(&word) {
+                continue;
+            }
+            let title = match input.trim() {
+                "" => format!("{} clipboard", capitalize(&preset.name)),
+                input => format!("{} clipboard: {}", capitalize(&preset.name), input),
+            };
+            items.push(
+                Item::new(format!("ai:preset:{}:{}", preset.name, input.trim()), title, ItemType::AiQuery)
+                    .with_description(
+                        preset
+                            .description
+                            .clone()
+                            .unwrap_or_else(|| "Runs on the clipboard".to_string())
+                            + " | result is copied back",
+                    )
+                    .with_icon("edit-paste"),
+            );
+        }
+
+        items
+    }
+
+    /// Runs the preset named in an `ai:preset:<name>:<input>` id on `text`,
+    /// outside of the conversation's context.
+    pub fn run_preset(
+        &mut self,
+        action_id: &str,
+        text: &str,
+    ) -> Option<impl Stream<Item = AiEvent> + Send + 'static> {
+        let (name, input) = action_id.strip_prefix("ai:preset:")?.split_once(':')?;
+        let preset = self.prompts.iter().find(|p| p.name == name)?;
+        let prompt = presets::render(preset, input, text);
+        let label = match input {
+            "" => format!("{} clipboard", capitalize(name)),
+            input => format!("{} clipboard: {}", capitalize(name), input),
+        };
+
+        let messages = vec![ChatMessage {
+            role: Role::User,
+            content: prompt,
+        }];
+        Some(self.start(label, messages))
+    }
+
+    /// Starts a new turn and returns a stream of its answer. Earlier turns
+    /// are sent along so follow-up questions keep their context.
+    pub fn ask(&mut self, prompt: &str) -> impl Stream<Item = AiEvent> + Send + 'static {
+        let mut messages = Vec::new();
+        for turn in self.conversation.iter().filter(|t| t.error.is_none()) {
+            messages.push(ChatMessage {
+                role: Role::User,
+                content: turn.question.clone(),
+            });
+            messages.push(ChatMessage {
+                role: Role::Assistant,
+                content: turn.answer.clone(),
+            });
+        }
+        messages.push(ChatMessage {
+            role: Role::User,
+            content: prompt.to_string(),
+        });
+
+        self.start(prompt.to_string(), messages)
+    }
+
+    fn start(
+        &mut self,
+        question: String,
+        messages: Vec<ChatMessage>,
+    ) -> impl Stream<Item = AiEvent> + Send + 'static {
+        self.conversation.push(ChatTurn {
+            question,
+            answer: String::new(),
+            streaming: true,
+            error: None,
+        });
+
+        let provider = self.provider.clone();
+        iced::stream::channel(32, move |mut output| async move {
+            let result = provider.stream(messages, output.clone()).await;
+            let _ = output.send(AiEvent::Done(result.err().map(|e| e.to_string()))).await;
+        })
+    }
+
+    pub fn push_chunk(&mut self, text: &str) {
+        if let Some(turn) = self.conversation.last_mut() {
+            turn.answer.push_str(text);
+        }
+    }
+
+    pub fn finish(&mut self, error: Option<String>) {
+        if let Some(turn) = self.conversation.last_mut() {
+            turn.streaming = false;
+            turn.error = error;
+        }
+    }
+}
+
+impl Default for AiManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Reads a streamed HTTP body line by line, passing each line to `parse` and
+/// forwarding the text it extracts. Lines may be split across chunks.
+async fn forward_lines(
+    mut response: reqwest::Response,
+    mut output: mpsc::Sender<AiEvent>,
+    parse: fn(&str) -> Result<Option<String>>,
+) -> Result<()> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow::anyhow!("API error ({}): {}", status, body.trim()));
+    }
+
+    let mut buffer = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        buffer.extend_from_slice(&chunk);
+        for line in complete_lines(&mut buffer) {
+            if let Some(text) = parse(line.trim())? {
+                let _ = output.send(AiEvent::Chunk(text)).await;
+            }
+        }
+    }
+    if let Some(text) = parse(String::from_utf8_lossy(&buffer).trim())? {
+        let _ = output.send(AiEvent::Chunk(text)).await;
+    }
+
+    Ok(())
+}
+
+/// Takes the complete lines off the front of `buffer`. They're decoded only
+/// once whole, as a character can be split across chunks.
+fn complete_lines(buffer: &mut Vec<u8>) -> Vec<String> {
+    let Some(end) = buffer.iter().rposition(|byte| *byte == b'\n') else {
+        return Vec::new();
+    };
+    let lines: Vec<u8> = buffer.drain(..=end).collect();
+    lines[..end]
+        .split(|byte| *byte == b'\n')
+        .map(|line| String::from_utf8_lossy(line).into_owned())
+        .collect()
+}
+
+/// The payload of a server-sent event line (`data: ...`).
+fn sse_data(line: &str) -> Option<&str> {
+    line.strip_prefix("data:").map(str::trim)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete_lines() {
+        // "é" is two bytes, split here across chunks
+        let stream = "data: caf\u{e9}\ndata: \u{1f600}\npartial".as_bytes();
+        let split = stream.iter().position(|byte| *byte == 0xc3).unwrap() + 1;
+        let mut buffer = stream[..split].to_vec();
+        assert!(complete_lines(&mut buffer).is_empty());
+        buffer.extend_from_slice(&stream[split..]);
+        assert_eq!(complete_lines(&mut buffer), ["data: caf\u{e9}", "data: \u{1f600}"]);
+        assert_eq!(buffer, b"partial");
+    }
+}
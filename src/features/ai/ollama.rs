@@ -0,0 +1,106 @@
+use super::{forward_lines, AiEvent, AiProvider, ChatMessage, Role};
+use crate::core::config::AiConfig;
+use anyhow::Result;
+use iced::futures::channel::mpsc;
+use iced::futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+const DEFAULT_MODEL: &str = "llama3.2";
+
+/// A local Ollama server; needs no API key.
+pub struct Ollama {
+    model: String,
+    base_url: String,
+}
+
+#[derive(Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<RequestMessage>,
+    stream: bool,
+}
+
+#[derive(Serialize)]
+struct RequestMessage {
+    role: &'static str,
+    content: String,
+}
+
+/// One line of the newline-delimited JSON response.
+#[derive(Deserialize)]
+struct ChatChunk {
+    message: Option<ResponseMessage>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ResponseMessage {
+    content: String,
+}
+
+impl Ollama {
+    pub fn new(config: &AiConfig) -> Self {
+        Self {
+            model: config.model.clone().unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            base_url: config.base_url.clone().unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+        }
+    }
+
+    fn parse_line(line: &str) -> Result<Option<String>> {
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let chunk: ChatChunk = serde_json::from_str(line)?;
+        if let Some(error) = chunk.error {
+            return Err(anyhow::anyhow!("Ollama error: {}", error));
+        }
+        Ok(chunk.message.map(|m| m.content).filter(|c| !c.is_empty()))
+    }
+}
+
+impl AiProvider for Ollama {
+    fn name(&self) -> String {
+        format!("Ollama ({})", self.model)
+    }
+
+    fn stream(
+        &self,
+        messages: Vec<ChatMessage>,
+        output: mpsc::Sender<AiEvent>,
+    ) -> BoxFuture<'static, Result<()>> {
+        let url = format!("{}/api/chat", self.base_url);
+        let request = ChatRequest {
+            model: self.model.clone(),
+            messages: messages
+                .into_iter()
+                .map(|m| RequestMessage {
+                    role: match m.role {
+                        Role::User => "user",
+                        Role::Assistant => "assistant",
+                    },
+                    content: m.content,
+                })
+                .collect(),
+            stream: true,
+        };
+
+        Box::pin(async move {
+            let response = reqwest::Client::new().post(&url).json(&request).send().await?;
+            forward_lines(response, output, Self::parse_line).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let line = r#"{"model": "llama3.2", "message": {"role": "assistant", "content": "Hi"}, "done": false}"#;
+        assert_eq!(Ollama::parse_line(line).unwrap(), Some("Hi".to_string()));
+        assert_eq!(Ollama::parse_line("").unwrap(), None);
+        assert!(Ollama::parse_line(r#"{"error": "model not found"}"#).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use super::{forward_lines, sse_data, AiEvent, AiProvider, ChatMessage, Role};
+use crate::core::config::AiConfig;
+use anyhow::Result;
+use iced::futures::channel::mpsc;
+use iced::futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_MODEL: &str = "gpt-4o-mini";
+
+/// OpenAI chat completions; also works with compatible servers via `base_url`.
+pub struct OpenAi {
+    api_key: Option<String>,
+    model: String,
+    base_url: String,
+}
+
+#[derive(Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<ChatRequestMessage>,
+    stream: bool,
+}
+
+#[derive(Serialize)]
+struct ChatRequestMessage {
+    role: &'static str,
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct ChatChunk {
+    #[serde(default)]
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    delta: ChatDelta,
+}
+
+#[derive(Deserialize)]
+struct ChatDelta {
+    content: Option<String>,
+}
+
+impl OpenAi {
+    pub fn new(config: &AiConfig) -> Self {
+        Self {
+            api_key: config.api_key.clone(),
+            model: config.model.clone().unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            base_url: config.base_url.clone().unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+        }
+    }
+
+    fn parse_line(line: &str) -> Result<Option<String>> {
+        match sse_data(line) {
+            None | Some("[DONE]") => Ok(None),
+            Some(data) => {
+                let chunk: ChatChunk = serde_json::from_str(data)?;
+                Ok(chunk.choices.into_iter().next().and_then(|c| c.delta.content))
+            }
+        }
+    }
+}
+
+impl AiProvider for OpenAi {
+    fn name(&self) -> String {
+        format!("OpenAI ({})", self.model)
+    }
+
+    fn missing_config(&self) -> Option<&'static str> {
+        self.api_key
+            .is_none()
+            .then_some("Add ai.api_key to ~/.config/wlaunch/config.json")
+    }
+
+    fn stream(
+        &self,
+        messages: Vec<ChatMessage>,
+        output: mpsc::Sender<AiEvent>,
+    ) -> BoxFuture<'static, Result<()>> {
+        let url = format!("{}/chat/completions", self.base_url);
+        let api_key = self.api_key.clone().unwrap_or_default();
+        let request = ChatRequest {
+            model: self.model.clone(),
+            messages: messages
+                .into_iter()
+                .map(|m| ChatRequestMessage {
+                    role: match m.role {
+                        Role::User => "user",
+                        Role::Assistant => "assistant",
+                    },
+                    content: m.content,
+                })
+                .collect(),
+            stream: true,
+        };
+
+        Box::pin(async move {
+            let response = reqwest::Client::new()
+                .post(&url)
+                .bearer_auth(api_key)
+                .json(&request)
+                .send()
+                .await?;
+            forward_lines(response, output, Self::parse_line).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let line = r#"data: {"choices": [{"delta": {"content": "Hi"}}]}"#;
+        assert_eq!(OpenAi::parse_line(line).unwrap(), Some("Hi".to_string()));
+        assert_eq!(OpenAi::parse_line(r#"data: {"choices": [{"delta": {}}]}"#).unwrap(), None);
+        assert_eq!(OpenAi::parse_line("data: [DONE]").unwrap(), None);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::core::config::AiPrompt;
+
+/// (name, description, template)
+const BUILTIN: &[(&str, &str, &str)] = &[
+    (
+        "summarize",
+        "Summarize the clipboard",
+        "Summarize the following text concisely:\n\n{text}",
+    ),
+    (
+        "translate",
+        "Translate the clipboard (translate <language>)",
+        "Translate the following text to {input}. Reply with the translation only:\n\n{text}",
+    ),
+    (
+        "grammar",
+        "Fix grammar and spelling in the clipboard",
+        "Fix the grammar and spelling of the following text. Reply with the corrected text only:\n\n{text}",
+    ),
+    (
+        "explain",
+        "Explain the code in the clipboard",
+        "Explain what the following code does:\n\n{text}",
+    ),
+];
+
+/// Built-in presets with the user's additions and replacements applied.
+pub fn presets(custom: &[AiPrompt]) -> Vec<AiPrompt> {
+    let mut presets: Vec<AiPrompt> = BUILTIN
+        .iter()
+        .filter(|(name, _, _)| !custom.iter().any(|p| p.name == *name))
+        .map(|(name, description, template)| AiPrompt {
+            name: name.to_string(),
+            template: template.to_string(),
+            description: Some(description.to_string()),
+        })
+        .collect();
+    presets.extend(custom.iter().cloned());
+    presets
+}
+
+/// Fills in a preset's template.
+pub fn render(preset: &AiPrompt, input: &str, text: &str) -> String {
+    let input = if input.trim().is_empty() { "English" } else { input.trim() };
+    preset.template.replace("{input}", input).replace("{text}", text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let presets = presets(&[]);
+        let translate = presets.iter().find(|p| p.name == "translate").unwrap();
+        let prompt = render(translate, "german", "hello");
+        assert!(prompt.contains("to german"));
+        assert!(prompt.ends_with("hello"));
+        assert!(render(translate, "", "hi").contains("to English"));
+    }
+
+    #[test]
+    fn test_custom_replaces_builtin() {
+        let custom = AiPrompt {
+            name: "summarize".to_string(),
+            template: "TL;DR: {text}".to_string(),
+            description: None,
+        };
+        let presets = presets(&[custom]);
+        assert_eq!(presets.iter().filter(|p| p.name == "summarize").count(), 1);
+        assert_eq!(presets.last().unwrap().template, "TL;DR: {text}");
+    }
+}
@@ -0,0 +1,106 @@
+//! System alerts sent by `wlaunch --daemon`: low battery, a filesystem
+//! running out of space and overheating, with thresholds under `alerts`.
+
+use super::sysinfo::{Snapshot, SysInfo};
+use crate::core::config::AlertsConfig;
+use crate::core::Config;
+use std::collections::HashSet;
+
+pub struct SystemAlerts {
+    config: AlertsConfig,
+    sysinfo: SysInfo,
+    /// Alerts already sent, so each fires once until its value recovers
+    active: HashSet<String>,
+}
+
+impl SystemAlerts {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            config: config.alerts.clone(),
+            sysinfo: SysInfo::new(config),
+            active: HashSet::new(),
+        }
+    }
+
+    /// Picks up edited thresholds, keeping the alerts already sent.
+    pub fn set_config(&mut self, config: &Config) {
+        self.config = config.alerts.clone();
+    }
+
+    /// Notifications (summary, body) for values that crossed a threshold
+    /// since the last check.
+    fn check(&mut self, snapshot: &Snapshot) -> Vec<(String, String)> {
+        let mut alerts = Vec::new();
+
+        if let Some((percent, status)) = &snapshot.battery {
+            let low = *percent < self.config.battery_below() && status == "Discharging";
+            if self.transition("battery", low) {
+                alerts.push(("Battery low".to_string(), format!("{}% left, plug in the charger", percent)));
+            }
+        }
+        for (mountpoint, available, total) in &snapshot.disks {
+            let free = (*available as f64 / *total as f64 * 100.0) as u8;
+            if self.transition(&format!("disk:{}", mountpoint), free < self.config.disk_free_below()) {
+                alerts.push((format!("{} is almost full", mountpoint), format!("{}% free", free)));
+            }
+        }
+        if let Some((sensor, celsius)) = &snapshot.temperature {
+            if self.transition("temperature", *celsius > self.config.temperature_above()) {
+                alerts.push(("System is overheating".to_string(), format!("{} at {:.0} °C", sensor, celsius)));
+            }
+        }
+        alerts
+    }
+
+    /// Whether `key` just started alerting.
+    fn transition(&mut self, key: &str, alerting: bool) -> bool {
+        if alerting {
+            self.active.insert(key.to_string())
+        } else {
+            self.active.remove(key);
+            false
+        }
+    }
+
+    pub fn tick(&mut self) {
+        if !self.config.enabled() {
+            return;
+        }
+        let snapshot = self.sysinfo.snapshot();
+        for (summary, body) in self.check(&snapshot) {
+            log::info!("{}: {}", summary, body);
+            let _ = notify_rust::Notification::new()
+                .summary(&summary)
+                .body(&body)
+                .icon("dialog-warning")
+                .urgency(notify_rust::Urgency::Critical)
+                .show();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let mut alerts = SystemAlerts::new(&Config::default());
+        let mut snapshot = Snapshot {
+            battery: Some((12, "Discharging".to_string())),
+            temperature: Some(("k10temp Tctl".to_string(), 60.0)),
+            disks: vec![("/".to_string(), 5, 100), ("/home".to_string(), 50, 100)],
+            ..Default::default()
+        };
+        let summaries = |alerts: Vec<(String, String)>| alerts.into_iter().map(|(summary, _)| summary).collect::<Vec<_>>();
+        assert_eq!(summaries(alerts.check(&snapshot)), ["Battery low", "/ is almost full"]);
+
+        // Once per crossing
+        assert!(alerts.check(&snapshot).is_empty());
+        snapshot.battery = Some((12, "Charging".to_string()));
+        snapshot.temperature = Some(("k10temp Tctl".to_string(), 95.0));
+        assert_eq!(summaries(alerts.check(&snapshot)), ["System is overheating"]);
+        snapshot.battery = Some((11, "Discharging".to_string()));
+        assert_eq!(summaries(alerts.check(&snapshot)), ["Battery low"]);
+    }
+}
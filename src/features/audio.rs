@@ -1,10 +1,13 @@
-use crate::core::{Item, ItemType};
-use std::process::Command;
+use crate::core::{CommandRunner, Item, ItemType, SystemRunner};
+use std::sync::Arc;
 
+#[derive(Clone)]
 pub struct AudioManager {
     backend: AudioBackend,
+    runner: Arc<dyn CommandRunner>,
 }
 
+#[derive(Clone)]
 enum AudioBackend {
     PipeWire,
     PulseAudio,
@@ -12,14 +15,18 @@ enum AudioBackend {
 
 impl AudioManager {
     pub fn new() -> Self {
+        Self::with_runner(SystemRunner::shared())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Self {
         // Detect backend
-        let backend = if Command::new("wpctl").arg("--version").output().is_ok() {
+        let backend = if runner.run("wpctl", &["--version"]).is_ok() {
             AudioBackend::PipeWire
         } else {
             AudioBackend::PulseAudio
         };
 
-        Self { backend }
+        Self { backend, runner }
     }
 
     pub fn get_items(&self, query: &str) -> Vec<Item> {
@@ -120,10 +127,7 @@ impl AudioManager {
     fn get_volume(&self) -> u32 {
         match self.backend {
             AudioBackend::PipeWire => {
-                if let Ok(output) = Command::new("wpctl")
-                    .args(["get-volume", "@DEFAULT_AUDIO_SINK@"])
-                    .output()
-                {
+                if let Ok(output) = self.runner.run("wpctl", &["get-volume", "@DEFAULT_AUDIO_SINK@"]) {
                     if output.status.success() {
                         let stdout = String::from_utf8_lossy(&output.stdout);
                         // Format: Volume: X.XX [MUTED]
@@ -136,10 +140,7 @@ impl AudioManager {
                 }
             }
             AudioBackend::PulseAudio => {
-                if let Ok(output) = Command::new("pactl")
-                    .args(["get-sink-volume", "@DEFAULT_SINK@"])
-                    .output()
-                {
+                if let Ok(output) = self.runner.run("pactl", &["get-sink-volume", "@DEFAULT_SINK@"]) {
                     if output.status.success() {
                         let stdout = String::from_utf8_lossy(&output.stdout);
                         // Parse percentage
@@ -159,10 +160,7 @@ impl AudioManager {
     fn is_muted(&self) -> bool {
         match self.backend {
             AudioBackend::PipeWire => {
-                if let Ok(output) = Command::new("wpctl")
-                    .args(["get-volume", "@DEFAULT_AUDIO_SINK@"])
-                    .output()
-                {
+                if let Ok(output) = self.runner.run("wpctl", &["get-volume", "@DEFAULT_AUDIO_SINK@"]) {
                     if output.status.success() {
                         let stdout = String::from_utf8_lossy(&output.stdout);
                         return stdout.contains("[MUTED]");
@@ -170,10 +168,7 @@ impl AudioManager {
                 }
             }
             AudioBackend::PulseAudio => {
-                if let Ok(output) = Command::new("pactl")
-                    .args(["get-sink-mute", "@DEFAULT_SINK@"])
-                    .output()
-                {
+                if let Ok(output) = self.runner.run("pactl", &["get-sink-mute", "@DEFAULT_SINK@"]) {
                     if output.status.success() {
                         let stdout = String::from_utf8_lossy(&output.stdout);
                         return stdout.contains("yes");
@@ -189,10 +184,7 @@ impl AudioManager {
 
         match self.backend {
             AudioBackend::PipeWire => {
-                if let Ok(output) = Command::new("wpctl")
-                    .args(["status"])
-                    .output()
-                {
+                if let Ok(output) = self.runner.run("wpctl", &["status"]) {
                     if output.status.success() {
                         let stdout = String::from_utf8_lossy(&output.stdout);
                         let mut in_sinks = false;
@@ -205,32 +197,15 @@ impl AudioManager {
                             if in_sinks && (line.contains("Sources:") || line.trim().is_empty()) {
                                 break;
                             }
-                            if in_sinks && line.contains('.') {
-                                // Parse sink line
-                                let default = line.contains('*');
-                                let parts: Vec<&str> = line.split_whitespace().collect();
-                                if parts.len() >= 2 {
-                                    let id = parts[0].trim_matches(|c| c == '*' || c == '.');
-                                    let name = parts[1..].join(" ");
-                                    sinks.push(AudioSink {
-                                        id: id.to_string(),
-                                        name: name.clone(),
-                                        description: name,
-                                        volume: 100,
-                                        muted: false,
-                                        default,
-                                    });
-                                }
+                            if in_sinks {
+                                sinks.extend(parse_wpctl_sink(line));
                             }
                         }
                     }
                 }
             }
             AudioBackend::PulseAudio => {
-                if let Ok(output) = Command::new("pactl")
-                    .args(["list", "sinks", "short"])
-                    .output()
-                {
+                if let Ok(output) = self.runner.run("pactl", &["list", "sinks", "short"]) {
                     if output.status.success() {
                         let stdout = String::from_utf8_lossy(&output.stdout);
                         for line in stdout.lines() {
@@ -258,14 +233,10 @@ impl AudioManager {
         let vol_str = format!("{}%", volume.min(150));
         match self.backend {
             AudioBackend::PipeWire => {
-                let _ = Command::new("wpctl")
-                    .args(["set-volume", "@DEFAULT_AUDIO_SINK@", &vol_str])
-                    .output();
+                let _ = self.runner.run("wpctl", &["set-volume", "@DEFAULT_AUDIO_SINK@", &vol_str]);
             }
             AudioBackend::PulseAudio => {
-                let _ = Command::new("pactl")
-                    .args(["set-sink-volume", "@DEFAULT_SINK@", &vol_str])
-                    .output();
+                let _ = self.runner.run("pactl", &["set-sink-volume", "@DEFAULT_SINK@", &vol_str]);
             }
         }
     }
@@ -273,14 +244,10 @@ impl AudioManager {
     pub fn toggle_mute(&self) {
         match self.backend {
             AudioBackend::PipeWire => {
-                let _ = Command::new("wpctl")
-                    .args(["set-mute", "@DEFAULT_AUDIO_SINK@", "toggle"])
-                    .output();
+                let _ = self.runner.run("wpctl", &["set-mute", "@DEFAULT_AUDIO_SINK@", "toggle"]);
             }
             AudioBackend::PulseAudio => {
-                let _ = Command::new("pactl")
-                    .args(["set-sink-mute", "@DEFAULT_SINK@", "toggle"])
-                    .output();
+                let _ = self.runner.run("pactl", &["set-sink-mute", "@DEFAULT_SINK@", "toggle"]);
             }
         }
     }
@@ -288,14 +255,10 @@ impl AudioManager {
     pub fn set_default_sink(&self, sink_id: &str) {
         match self.backend {
             AudioBackend::PipeWire => {
-                let _ = Command::new("wpctl")
-                    .args(["set-default", sink_id])
-                    .output();
+                let _ = self.runner.run("wpctl", &["set-default", sink_id]);
             }
             AudioBackend::PulseAudio => {
-                let _ = Command::new("pactl")
-                    .args(["set-default-sink", sink_id])
-                    .output();
+                let _ = self.runner.run("pactl", &["set-default-sink", sink_id]);
             }
         }
     }
@@ -330,6 +293,34 @@ impl AudioManager {
     }
 }
 
+/// A sink line of `wpctl status`:
+/// ` │  *   46. Built-in Audio Analog Stereo      [vol: 0.40 MUTED]`
+fn parse_wpctl_sink(line: &str) -> Option<AudioSink> {
+    let line = line.trim_start_matches(|c: char| c.is_whitespace() || "│├└─".contains(c));
+    let default = line.starts_with('*');
+    let (id, rest) = line.trim_start_matches('*').trim_start().split_once(". ")?;
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (name, props) = rest.split_once('[').unwrap_or((rest, ""));
+    let name = name.trim().to_string();
+    let volume = props
+        .strip_prefix("vol: ")
+        .and_then(|v| v.split([' ', ']']).next())
+        .and_then(|v| v.parse::<f32>().ok())
+        .map(|v| (v * 100.0).round() as u32)
+        .unwrap_or(100);
+
+    Some(AudioSink {
+        id: id.to_string(),
+        description: name.clone(),
+        name,
+        volume,
+        muted: props.contains("MUTED"),
+        default,
+    })
+}
+
 struct AudioSink {
     id: String,
     name: String,
@@ -344,3 +335,29 @@ impl Default for AudioManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    #[test]
+    fn test_wpctl_status() {
+        let runner = FakeRunner::new()
+            .with("wpctl --version", "wpctl\nCompiled with libpipewire 1.0.5\n")
+            .with("wpctl get-volume @DEFAULT_AUDIO_SINK@", "Volume: 0.40\n")
+            .with("wpctl status", include_str!("../../tests/fixtures/wpctl_status.txt"))
+            .shared();
+        let items = AudioManager::with_runner(runner).get_items("");
+        assert_eq!(items[0].name, "Volume: 40%");
+
+        let sinks: Vec<&Item> = items.iter().filter(|i| i.item_type == ItemType::AudioSink).collect();
+        assert_eq!(sinks.len(), 2);
+        assert_eq!(sinks[0].name, "Built-in Audio Analog Stereo");
+        assert_eq!(sinks[0].metadata.sink_id.as_deref(), Some("46"));
+        assert_eq!(sinks[0].description.as_deref(), Some("Built-in Audio Analog Stereo (Default)"));
+        assert_eq!(sinks[1].metadata.sink_id.as_deref(), Some("61"));
+        assert_eq!(sinks[1].metadata.volume, Some(75));
+        assert!(sinks[1].metadata.muted);
+    }
+}
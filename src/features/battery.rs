@@ -0,0 +1,250 @@
+//! Batteries from upower: the laptop's charge, health and time estimates,
+//! and peripherals such as Bluetooth mice and headsets; plus switching the
+//! power profile with powerprofilesctl. Without upower, the system battery
+//! is read from sysfs.
+
+use super::sysinfo::meter;
+use crate::core::{CommandRunner, Item, ItemType, SystemRunner};
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::sync::Arc;
+
+/// One `Device:` block of `upower --dump`.
+struct Device {
+    /// `battery`, `mouse`, `headset`, `keyboard`, ...
+    kind: String,
+    /// Whether it powers the computer, as opposed to a peripheral
+    power_supply: bool,
+    fields: HashMap<String, String>,
+}
+
+impl Device {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str).filter(|value| !value.is_empty())
+    }
+
+    fn percentage(&self) -> Option<f32> {
+        self.field("percentage")?.trim_end_matches('%').parse().ok()
+    }
+}
+
+/// Devices with a battery, without line power and the combined
+/// `DisplayDevice`.
+fn parse_dump(stdout: &str) -> Vec<Device> {
+    let mut devices = Vec::new();
+    for block in stdout.split("\n\n") {
+        let mut lines = block.lines();
+        let Some(path) = lines.next().and_then(|line| line.strip_prefix("Device: ")) else {
+            continue;
+        };
+        if path.ends_with("/DisplayDevice") {
+            continue;
+        }
+        let mut device = Device {
+            kind: String::new(),
+            power_supply: false,
+            fields: HashMap::new(),
+        };
+        for line in lines {
+            match line.split_once(':') {
+                Some((key, value)) => {
+                    device.fields.insert(key.trim().to_string(), value.trim().trim_matches('\'').to_string());
+                }
+                // The kind heads the device's own properties
+                None => device.kind = line.trim().to_string(),
+            }
+        }
+        device.power_supply = device.field("power supply") == Some("yes");
+        if device.kind != "line-power" && device.percentage().is_some() {
+            devices.push(device);
+        }
+    }
+    devices
+}
+
+/// Profiles of `powerprofilesctl list`, the active one marked with `*`.
+fn parse_profiles(stdout: &str) -> Vec<(String, bool)> {
+    stdout
+        .lines()
+        .filter(|line| !line.starts_with("    ") && line.trim_end().ends_with(':'))
+        .map(|line| {
+            let active = line.starts_with('*');
+            (line.trim_start_matches('*').trim().trim_end_matches(':').to_string(), active)
+        })
+        .collect()
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars.next().map_or_else(String::new, |first| first.to_uppercase().chain(chars).collect())
+}
+
+#[derive(Clone)]
+pub struct BatteryManager {
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl BatteryManager {
+    pub fn new() -> Self {
+        Self::with_runner(SystemRunner::shared())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Self {
+        Self { runner }
+    }
+
+    fn battery_items(&self) -> Vec<Item> {
+        let devices = match self.runner.run("upower", &["--dump"]) {
+            Ok(output) if output.status.success() => parse_dump(&String::from_utf8_lossy(&output.stdout)),
+            Ok(output) => return Item::command_error_items("battery", "upower failed", &output),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return super::sysinfo::battery()
+                    .map(|(percent, status)| {
+                        let description = format!("{} {}% | {} | Install upower for health and time left", meter(percent as f32 / 100.0), percent, status);
+                        vec![Self::item("battery:sysfs", "Battery", description, "battery")]
+                    })
+                    .unwrap_or_default();
+            }
+            Err(e) => return Item::error_items("battery", "Failed to run upower", e.to_string()),
+        };
+
+        let batteries = devices.iter().filter(|device| device.power_supply).count();
+        devices
+            .iter()
+            .map(|device| {
+                let percent = device.percentage().unwrap_or(0.0);
+                let state = capitalize(device.field("state").unwrap_or("unknown"));
+                let mut details = vec![format!("{} {:.0}%", meter(percent / 100.0), percent)];
+                let native_path = device.field("native-path").unwrap_or_default();
+
+                let name = if device.power_supply {
+                    let charging = state == "Charging";
+                    match device.field(if charging { "time to full" } else { "time to empty" }) {
+                        Some(time) if charging => details.push(format!("{}, full in {}", state, time)),
+                        Some(time) => details.push(format!("{}, {} left", state, time)),
+                        None => details.push(state),
+                    }
+                    if let Some(capacity) = device.field("capacity").and_then(|c| c.trim_end_matches('%').parse::<f32>().ok()) {
+                        details.push(format!("Health {:.0}%", capacity));
+                    }
+                    if let Some(cycles) = device.field("charge-cycles").filter(|cycles| *cycles != "N/A") {
+                        details.push(format!("{} cycles", cycles));
+                    }
+                    if let Some(rate) = device.field("energy-rate").and_then(|r| r.split(' ').next()?.parse::<f32>().ok()) {
+                        if rate > 0.0 {
+                            details.push(format!("{:.1} W", rate));
+                        }
+                    }
+                    // Tell batteries apart only on machines with several
+                    if batteries > 1 {
+                        format!("Battery {}", native_path)
+                    } else {
+                        "Battery".to_string()
+                    }
+                } else {
+                    details.push(capitalize(&device.kind));
+                    device.field("model").unwrap_or(&device.kind).to_string()
+                };
+
+                let icon = device.field("icon-name").unwrap_or("battery").to_string();
+                Self::item(&format!("battery:{}", native_path), &name, details.join(" | "), &icon)
+            })
+            .collect()
+    }
+
+    /// A row that copies itself on Enter, like the system info mode.
+    fn item(id: &str, name: &str, description: String, icon: &str) -> Item {
+        let mut item = Item::new(id, name, ItemType::Battery).with_icon(icon);
+        item.metadata.content = Some(format!("{}: {}", name, description));
+        item.with_description(description)
+    }
+
+    /// Batteries first, then the power profiles; `battery <text>` filters by
+    /// name.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let filter = query.trim().to_lowercase();
+        let mut items = self.battery_items();
+
+        if let Ok(output) = self.runner.run("powerprofilesctl", &["list"]) {
+            if output.status.success() {
+                for (profile, active) in parse_profiles(&String::from_utf8_lossy(&output.stdout)) {
+                    let name = format!("Power profile: {}", profile);
+                    let description = if active { "Active".to_string() } else { format!("Switch to {}", profile) };
+                    let mut item = Item::new(format!("power:profile:{}", profile), name, ItemType::PowerProfile)
+                        .with_description(description)
+                        .with_icon(format!("power-profile-{}", profile));
+                    item.metadata.connected = active;
+                    items.push(item);
+                }
+            }
+        }
+
+        items.retain(|item| item.item_type == ItemType::Error || item.name.to_lowercase().contains(&filter));
+        if items.is_empty() {
+            let title = if filter.is_empty() { "No batteries found".to_string() } else { format!("Nothing matches \"{}\"", query.trim()) };
+            items.push(Item::new("battery:none", title, ItemType::Command).with_icon("dialog-information"));
+        }
+        items
+    }
+
+    /// Switches to a power profile; the list then shows it as active.
+    pub fn execute_action(&self, action_id: &str) {
+        if let Some(profile) = action_id.strip_prefix("power:profile:") {
+            match self.runner.run("powerprofilesctl", &["set", profile]) {
+                Ok(output) if output.status.success() => {}
+                Ok(output) => log::error!(
+                    "powerprofilesctl set {} failed: {}",
+                    profile,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+                Err(e) => log::error!("Failed to run powerprofilesctl: {}", e),
+            }
+        }
+    }
+}
+
+impl Default for BatteryManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    fn manager() -> BatteryManager {
+        let runner = FakeRunner::new()
+            .with("upower --dump", include_str!("../../tests/fixtures/upower_dump.txt"))
+            .with("powerprofilesctl list", include_str!("../../tests/fixtures/powerprofilesctl_list.txt"))
+            .shared();
+        BatteryManager::with_runner(runner)
+    }
+
+    #[test]
+    fn test_batteries() {
+        let items = manager().get_items("");
+        let rows: Vec<String> = items.iter().map(|i| format!("{}: {}", i.name, i.description.as_deref().unwrap())).collect();
+        assert_eq!(
+            rows,
+            [
+                "Battery: ▮▮▮▮▮▮▮▮▯▯ 76% | Discharging, 4.7 hours left | Health 88% | 212 cycles | 8.2 W",
+                "MX Master 3: ▮▮▮▮▮▮▯▯▯▯ 55% | Mouse",
+                "WH-1000XM4: ▮▮▯▯▯▯▯▯▯▯ 20% | Headset",
+                "Power profile: performance: Switch to performance",
+                "Power profile: balanced: Active",
+                "Power profile: power-saver: Switch to power-saver",
+            ]
+        );
+        assert_eq!(items[4].id, "power:profile:balanced");
+        assert!(items[4].metadata.connected);
+    }
+
+    #[test]
+    fn test_filter() {
+        let items = manager().get_items("profile");
+        assert_eq!(items.len(), 3);
+        assert!(items.iter().all(|i| i.item_type == ItemType::PowerProfile));
+    }
+}
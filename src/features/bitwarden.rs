@@ -7,7 +7,6 @@ struct BwItem {
     id: String,
     name: String,
     login: Option<BwLogin>,
-    notes: Option<String>,
     #[serde(rename = "type")]
     item_type: i32,
 }
@@ -28,6 +27,7 @@ struct BwUri {
 pub struct BitwardenManager {
     session: Option<String>,
     server: Option<String>,
+    max_results: usize,
 }
 
 impl BitwardenManager {
@@ -35,6 +35,7 @@ impl BitwardenManager {
         Self {
             session: None,
             server: config.bitwarden_server.clone(),
+            max_results: config.mode("bitwarden").max_results.unwrap_or(20),
         }
     }
 
@@ -42,20 +43,23 @@ impl BitwardenManager {
         Command::new("bw").arg("--version").output().is_ok()
     }
 
-    fn get_status(&self) -> String {
-        if let Ok(output) = Command::new("bw").args(["status"]).output() {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                if stdout.contains("\"status\":\"unlocked\"") {
-                    return "unlocked".to_string();
-                } else if stdout.contains("\"status\":\"locked\"") {
-                    return "locked".to_string();
-                } else if stdout.contains("\"status\":\"unauthenticated\"") {
-                    return "unauthenticated".to_string();
-                }
-            }
+    /// The vault status, or error items if `bw status` failed.
+    fn get_status(&self) -> Result<String, Vec<Item>> {
+        let output = match Command::new("bw").args(["status"]).output() {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => return Err(Item::command_error_items("bitwarden", "Can't read vault status", &output)),
+            Err(e) => return Err(Item::error_items("bitwarden", "Failed to run bw", e.to_string())),
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.contains("\"status\":\"unlocked\"") {
+            Ok("unlocked".to_string())
+        } else if stdout.contains("\"status\":\"locked\"") {
+            Ok("locked".to_string())
+        } else if stdout.contains("\"status\":\"unauthenticated\"") {
+            Ok("unauthenticated".to_string())
+        } else {
+            Ok("unknown".to_string())
         }
-        "unknown".to_string()
     }
 
     pub fn get_items(&self, query: &str) -> Vec<Item> {
@@ -74,7 +78,10 @@ impl BitwardenManager {
             return items;
         }
 
-        let status = self.get_status();
+        let status = match self.get_status() {
+            Ok(status) => status,
+            Err(error_items) => return error_items,
+        };
 
         match status.as_str() {
             "unauthenticated" => {
@@ -135,49 +142,50 @@ impl BitwardenManager {
             vec!["list", "items", "--search", query]
         };
 
-        if let Ok(output) = Command::new("bw").args(&args).output() {
-            if output.status.success() {
-                if let Ok(stdout) = String::from_utf8(output.stdout) {
-                    if let Ok(bw_items) = serde_json::from_str::<Vec<BwItem>>(&stdout) {
-                        for bw_item in bw_items.iter().take(20) {
-                            let type_str = match bw_item.item_type {
-                                1 => "Login",
-                                2 => "Secure Note",
-                                3 => "Card",
-                                4 => "Identity",
-                                _ => "Item",
-                            };
-
-                            let description = if let Some(login) = &bw_item.login {
-                                login.username.clone().unwrap_or_default()
-                            } else {
-                                type_str.to_string()
-                            };
+        let output = match Command::new("bw").args(&args).output() {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => return Item::command_error_items("bitwarden", "Can't search the vault", &output),
+            Err(e) => return Item::error_items("bitwarden", "Failed to run bw", e.to_string()),
+        };
+        let bw_items = match serde_json::from_slice::<Vec<BwItem>>(&output.stdout) {
+            Ok(bw_items) => bw_items,
+            Err(e) => return Item::error_items("bitwarden", "Unexpected output from bw", e.to_string()),
+        };
+        for bw_item in bw_items.iter().take(self.max_results) {
+            let type_str = match bw_item.item_type {
+                1 => "Login",
+                2 => "Secure Note",
+                3 => "Card",
+                4 => "Identity",
+                _ => "Item",
+            };
 
-                            let mut item = Item::new(
-                                format!("bw:item:{}", bw_item.id),
-                                &bw_item.name,
-                                ItemType::BitwardenItem,
-                            )
-                            .with_description(description)
-                            .with_icon("dialog-password");
+            let description = if let Some(login) = &bw_item.login {
+                login.username.clone().unwrap_or_default()
+            } else {
+                type_str.to_string()
+            };
 
-                            if let Some(login) = &bw_item.login {
-                                item.metadata.username = login.username.clone();
-                                item.metadata.password = login.password.clone();
-                                item.metadata.totp = login.totp.clone();
-                                if let Some(uris) = &login.uris {
-                                    if let Some(first_uri) = uris.first() {
-                                        item.metadata.uri = first_uri.uri.clone();
-                                    }
-                                }
-                            }
+            let mut item = Item::new(
+                format!("bw:item:{}", bw_item.id),
+                &bw_item.name,
+                ItemType::BitwardenItem,
+            )
+            .with_description(description)
+            .with_icon("dialog-password");
 
-                            items.push(item);
-                        }
+            if let Some(login) = &bw_item.login {
+                item.metadata.username = login.username.clone();
+                item.metadata.password = login.password.clone();
+                item.metadata.totp = login.totp.clone();
+                if let Some(uris) = &login.uris {
+                    if let Some(first_uri) = uris.first() {
+                        item.metadata.uri = first_uri.uri.clone();
                     }
                 }
             }
+
+            items.push(item);
         }
 
         items
@@ -253,6 +261,7 @@ impl Clone for BitwardenManager {
         Self {
             session: self.session.clone(),
             server: self.server.clone(),
+            max_results: self.max_results,
         }
     }
 }
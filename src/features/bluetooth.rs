@@ -1,11 +1,18 @@
-use crate::core::{Item, ItemType};
-use std::process::Command;
+use crate::core::{CommandRunner, Item, ItemType, SystemRunner};
+use std::sync::Arc;
 
-pub struct BluetoothManager;
+#[derive(Clone)]
+pub struct BluetoothManager {
+    runner: Arc<dyn CommandRunner>,
+}
 
 impl BluetoothManager {
     pub fn new() -> Self {
-        Self
+        Self::with_runner(SystemRunner::shared())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Self {
+        Self { runner }
     }
 
     pub fn get_items(&self, query: &str) -> Vec<Item> {
@@ -25,10 +32,7 @@ impl BluetoothManager {
         );
 
         // Get paired devices
-        if let Ok(output) = Command::new("bluetoothctl")
-            .args(["devices", "Paired"])
-            .output()
-        {
+        if let Ok(output) = self.runner.run("bluetoothctl", &["devices", "Paired"]) {
             if output.status.success() {
                 if let Ok(stdout) = String::from_utf8(output.stdout) {
                     for line in stdout.lines() {
@@ -85,10 +89,7 @@ impl BluetoothManager {
     }
 
     fn is_connected(&self, mac: &str) -> bool {
-        if let Ok(output) = Command::new("bluetoothctl")
-            .args(["info", mac])
-            .output()
-        {
+        if let Ok(output) = self.runner.run("bluetoothctl", &["info", mac]) {
             if output.status.success() {
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 return stdout.contains("Connected: yes");
@@ -98,47 +99,28 @@ impl BluetoothManager {
     }
 
     pub fn connect(&self, mac: &str) {
-        let _ = Command::new("bluetoothctl")
-            .args(["connect", mac])
-            .output();
+        let _ = self.runner.run("bluetoothctl", &["connect", mac]);
     }
 
     pub fn disconnect(&self, mac: &str) {
-        let _ = Command::new("bluetoothctl")
-            .args(["disconnect", mac])
-            .output();
-    }
-
-    pub fn pair(&self, mac: &str) {
-        let _ = Command::new("bluetoothctl")
-            .args(["pair", mac])
-            .output();
+        let _ = self.runner.run("bluetoothctl", &["disconnect", mac]);
     }
 
     pub fn toggle_power(&self) {
-        if let Ok(output) = Command::new("bluetoothctl")
-            .args(["show"])
-            .output()
-        {
+        if let Ok(output) = self.runner.run("bluetoothctl", &["show"]) {
             if output.status.success() {
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 if stdout.contains("Powered: yes") {
-                    let _ = Command::new("bluetoothctl")
-                        .args(["power", "off"])
-                        .output();
+                    let _ = self.runner.run("bluetoothctl", &["power", "off"]);
                 } else {
-                    let _ = Command::new("bluetoothctl")
-                        .args(["power", "on"])
-                        .output();
+                    let _ = self.runner.run("bluetoothctl", &["power", "on"]);
                 }
             }
         }
     }
 
     pub fn scan_start(&self) {
-        let _ = Command::new("bluetoothctl")
-            .args(["scan", "on"])
-            .output();
+        let _ = self.runner.run("bluetoothctl", &["scan", "on"]);
     }
 
     pub fn execute_action(&self, action_id: &str) {
@@ -163,3 +145,33 @@ impl Default for BluetoothManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    #[test]
+    fn test_bluetoothctl_devices() {
+        let runner = FakeRunner::new()
+            .with("bluetoothctl devices Paired", include_str!("../../tests/fixtures/bluetoothctl_devices.txt"))
+            .with(
+                "bluetoothctl info 11:22:33:44:55:66",
+                include_str!("../../tests/fixtures/bluetoothctl_info_connected.txt"),
+            )
+            .with(
+                "bluetoothctl info AA:BB:CC:DD:EE:FF",
+                include_str!("../../tests/fixtures/bluetoothctl_info_disconnected.txt"),
+            )
+            .shared();
+        let items = BluetoothManager::with_runner(runner).get_items("");
+        let devices: Vec<&Item> = items.iter().filter(|i| i.item_type == ItemType::BluetoothDevice).collect();
+        assert_eq!(devices.len(), 2);
+
+        assert_eq!(devices[0].name, "WH-1000XM4");
+        assert_eq!(devices[0].metadata.mac_address.as_deref(), Some("11:22:33:44:55:66"));
+        assert!(devices[0].metadata.connected);
+        assert_eq!(devices[1].name, "MX Keys");
+        assert!(!devices[1].metadata.connected);
+    }
+}
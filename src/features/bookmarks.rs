@@ -0,0 +1,358 @@
+//! Browser bookmarks from every Firefox profile's `places.sqlite` and every
+//! Chromium-family profile's `Bookmarks` file, read the first time they are
+//! searched.
+
+use crate::core::config::{ModeConfig, SortOrder};
+use crate::core::{Config, Item, ItemType};
+use anyhow::Result;
+use rusqlite::Connection;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Seconds between 1601-01-01, where Chromium timestamps start, and the Unix epoch
+const CHROMIUM_EPOCH_OFFSET: i64 = 11_644_473_600;
+
+/// Config directories of Chromium-based browsers, relative to `~/.config`
+const CHROMIUM_BROWSERS: [(&str, &str); 5] = [
+    ("google-chrome", "Chrome"),
+    ("chromium", "Chromium"),
+    ("BraveSoftware/Brave-Browser", "Brave"),
+    ("microsoft-edge", "Edge"),
+    ("vivaldi", "Vivaldi"),
+];
+
+/// Firefox profile directories, relative to home: native, Snap and Flatpak
+const FIREFOX_ROOTS: [&str; 3] = [
+    ".mozilla/firefox",
+    "snap/firefox/common/.mozilla/firefox",
+    ".var/app/org.mozilla.firefox/.mozilla/firefox",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+struct Bookmark {
+    title: String,
+    url: String,
+    /// Folder it is filed in, e.g. "Bookmarks bar/Work"
+    folder: String,
+    /// Browser and profile, e.g. "Firefox (default-release)"
+    browser: String,
+    /// Unix seconds
+    added: i64,
+}
+
+pub struct BookmarksManager {
+    bookmarks: OnceLock<Vec<Bookmark>>,
+    options: ModeConfig,
+}
+
+impl BookmarksManager {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            bookmarks: OnceLock::new(),
+            options: config.mode("bookmarks"),
+        }
+    }
+
+    fn bookmarks(&self) -> &[Bookmark] {
+        self.bookmarks.get_or_init(Self::discover)
+    }
+
+    fn discover() -> Vec<Bookmark> {
+        let Some(home) = dirs::home_dir() else {
+            return Vec::new();
+        };
+        let mut bookmarks = Vec::new();
+
+        for root in FIREFOX_ROOTS.iter().map(|root| home.join(root)) {
+            let Ok(entries) = fs::read_dir(&root) else {
+                continue;
+            };
+            for dir in entries.flatten().map(|e| e.path()) {
+                let places = dir.join("places.sqlite");
+                if !places.exists() {
+                    continue;
+                }
+                let name = dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+                // Profile directories are "<salt>.<name>"
+                let profile = name.split_once('.').map_or(name.as_str(), |(_, profile)| profile);
+                match Self::read_firefox(&places, &format!("Firefox ({})", profile)) {
+                    Ok(found) => bookmarks.extend(found),
+                    Err(e) => log::warn!("Failed to read {}: {}", places.display(), e),
+                }
+            }
+        }
+
+        let config_dir = dirs::config_dir().unwrap_or_else(|| home.join(".config"));
+        for (dir, browser) in CHROMIUM_BROWSERS {
+            let root = config_dir.join(dir);
+            let names = Self::chromium_profile_names(&root);
+            let Ok(entries) = fs::read_dir(&root) else {
+                continue;
+            };
+            for profile_dir in entries.flatten().map(|e| e.path()) {
+                let file = profile_dir.join("Bookmarks");
+                let Ok(json) = fs::read_to_string(&file) else {
+                    continue;
+                };
+                let dir_name = profile_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+                let profile = names
+                    .iter()
+                    .find(|(dir, _)| *dir == dir_name)
+                    .map_or(dir_name.clone(), |(_, name)| name.clone());
+                match Self::parse_chromium(&json, &format!("{} ({})", browser, profile)) {
+                    Ok(found) => bookmarks.extend(found),
+                    Err(e) => log::warn!("Failed to read {}: {}", file.display(), e),
+                }
+            }
+        }
+
+        bookmarks
+    }
+
+    /// Directory and display name of each profile in a Chromium browser's
+    /// `Local State`, e.g. ("Profile 1", "Work").
+    fn chromium_profile_names(root: &Path) -> Vec<(String, String)> {
+        let Ok(content) = fs::read_to_string(root.join("Local State")) else {
+            return Vec::new();
+        };
+        let Ok(state) = serde_json::from_str::<Value>(&content) else {
+            return Vec::new();
+        };
+        let Some(profiles) = state["profile"]["info_cache"].as_object() else {
+            return Vec::new();
+        };
+        profiles
+            .iter()
+            .filter_map(|(dir, info)| Some((dir.clone(), info["name"].as_str()?.to_string())))
+            .collect()
+    }
+
+    /// Firefox keeps `places.sqlite` locked while running, so a copy is read.
+    fn read_firefox(places: &Path, browser: &str) -> Result<Vec<Bookmark>> {
+        let tmp = std::env::temp_dir().join(format!("wlaunch-places-{}", std::process::id()));
+        fs::create_dir_all(&tmp)?;
+        let copy = tmp.join("places.sqlite");
+        fs::copy(places, &copy)?;
+        let wal = PathBuf::from(format!("{}-wal", places.display()));
+        if wal.exists() {
+            fs::copy(&wal, tmp.join("places.sqlite-wal"))?;
+        }
+
+        let result = Self::query_places(&copy, browser);
+        let _ = fs::remove_dir_all(&tmp);
+        result
+    }
+
+    fn query_places(path: &Path, browser: &str) -> Result<Vec<Bookmark>> {
+        let conn = Connection::open(path)?;
+        let mut stmt = conn.prepare(
+            "SELECT b.title, p.url, parent.title, b.dateAdded
+             FROM moz_bookmarks b
+             JOIN moz_places p ON b.fk = p.id
+             LEFT JOIN moz_bookmarks parent ON b.parent = parent.id
+             WHERE b.type = 1 AND p.url NOT LIKE 'place:%'",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Bookmark {
+                title: row.get::<_, Option<String>>(0)?.unwrap_or_default(),
+                url: row.get(1)?,
+                folder: match row.get::<_, Option<String>>(2)?.unwrap_or_default().as_str() {
+                    // Built-in roots go by their internal names
+                    "toolbar" => "Bookmarks Toolbar".to_string(),
+                    "menu" => "Bookmarks Menu".to_string(),
+                    "unfiled" => "Other Bookmarks".to_string(),
+                    "mobile" => "Mobile Bookmarks".to_string(),
+                    folder => folder.to_string(),
+                },
+                browser: browser.to_string(),
+                // Microseconds
+                added: row.get::<_, Option<i64>>(3)?.unwrap_or(0) / 1_000_000,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    fn parse_chromium(json: &str, browser: &str) -> Result<Vec<Bookmark>> {
+        let root: Value = serde_json::from_str(json)?;
+        let mut bookmarks = Vec::new();
+        if let Some(roots) = root["roots"].as_object() {
+            for node in roots.values() {
+                Self::walk_chromium(node, "", browser, &mut bookmarks);
+            }
+        }
+        Ok(bookmarks)
+    }
+
+    fn walk_chromium(node: &Value, folder: &str, browser: &str, bookmarks: &mut Vec<Bookmark>) {
+        let name = node["name"].as_str().unwrap_or_default();
+        match node["type"].as_str() {
+            Some("url") => {
+                // Microseconds since 1601
+                let added = node["date_added"]
+                    .as_str()
+                    .and_then(|d| d.parse::<i64>().ok())
+                    .map_or(0, |d| d / 1_000_000 - CHROMIUM_EPOCH_OFFSET);
+                bookmarks.push(Bookmark {
+                    title: name.to_string(),
+                    url: node["url"].as_str().unwrap_or_default().to_string(),
+                    folder: folder.to_string(),
+                    browser: browser.to_string(),
+                    added,
+                });
+            }
+            Some("folder") => {
+                let path = if folder.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{}/{}", folder, name)
+                };
+                for child in node["children"].as_array().into_iter().flatten() {
+                    Self::walk_chromium(child, &path, browser, bookmarks);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn to_item(bookmark: &Bookmark) -> Item {
+        let title = if bookmark.title.is_empty() { &bookmark.url } else { &bookmark.title };
+        let mut description = bookmark.url.clone();
+        if !bookmark.folder.is_empty() {
+            description.push_str(&format!(" | {}", bookmark.folder));
+        }
+        description.push_str(&format!(" | {}", bookmark.browser));
+
+        let mut item = Item::new(format!("bookmark:{}:{}", bookmark.browser, bookmark.url), title, ItemType::Bookmark)
+            .with_description(description)
+            .with_icon("bookmark-new")
+            .with_keywords(vec![bookmark.url.clone(), bookmark.folder.clone()]);
+        item.metadata.url = Some(bookmark.url.clone());
+        item
+    }
+
+    /// Every bookmark, for the main search to match against.
+    pub fn all_items(&self) -> Vec<Item> {
+        self.bookmarks().iter().map(Self::to_item).collect()
+    }
+
+    /// Bookmarks fuzzy-matching `query` on title, URL or folder; all of them,
+    /// newest first, for an empty query.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let bookmarks = self.bookmarks();
+        if bookmarks.is_empty() {
+            return vec![Item::new("bookmark:none", "No bookmarks found", ItemType::Command)
+                .with_description("Looked in Firefox and Chromium-based browser profiles")
+                .with_icon("dialog-information")];
+        }
+
+        let query = query.trim();
+        let mut items: Vec<Item> = if query.is_empty() {
+            let mut sorted: Vec<&Bookmark> = bookmarks.iter().collect();
+            match self.options.sort {
+                Some(SortOrder::Name) => sorted.sort_by_key(|b| b.title.to_lowercase()),
+                _ => sorted.sort_by_key(|b| std::cmp::Reverse(b.added)),
+            }
+            sorted.into_iter().map(Self::to_item).collect()
+        } else {
+            let mut scored: Vec<(i64, Item)> = bookmarks
+                .iter()
+                .map(Self::to_item)
+                .map(|item| (item.fuzzy_score(query), item))
+                .filter(|(score, _)| *score > 0)
+                .collect();
+            scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            scored.into_iter().map(|(_, item)| item).collect()
+        };
+
+        items.truncate(self.options.max_results.unwrap_or(50));
+        items
+    }
+}
+
+impl Default for BookmarksManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chromium() {
+        let json = r#"{"roots": {
+            "bookmark_bar": {"type": "folder", "name": "Bookmarks bar", "children": [
+                {"type": "url", "name": "Rust", "url": "https://www.rust-lang.org/", "date_added": "13370000000000000"},
+                {"type": "folder", "name": "Work", "children": [
+                    {"type": "url", "name": "Tracker", "url": "https://tracker.example.com/"}
+                ]}
+            ]},
+            "other": {"type": "folder", "name": "Other bookmarks", "children": []}
+        }}"#;
+        let bookmarks = BookmarksManager::parse_chromium(json, "Chrome (Default)").unwrap();
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].title, "Rust");
+        assert_eq!(bookmarks[0].folder, "Bookmarks bar");
+        assert_eq!(bookmarks[0].added, 13_370_000_000 - CHROMIUM_EPOCH_OFFSET);
+        assert_eq!(bookmarks[1].folder, "Bookmarks bar/Work");
+        assert_eq!(bookmarks[1].browser, "Chrome (Default)");
+    }
+
+    #[test]
+    fn test_all_items() {
+        let manager = BookmarksManager {
+            bookmarks: OnceLock::from(BookmarksManager::parse_chromium(
+                r#"{"roots": {"bookmark_bar": {"type": "folder", "name": "Bookmarks bar", "children": [
+                    {"type": "url", "name": "Rust", "url": "https://www.rust-lang.org/"}
+                ]}}}"#,
+                "Chrome (Default)",
+            )
+            .unwrap()),
+            options: ModeConfig::default(),
+        };
+        let items = manager.all_items();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].item_type, ItemType::Bookmark);
+        assert_eq!(items[0].metadata.url.as_deref(), Some("https://www.rust-lang.org/"));
+
+        // No "No bookmarks found" placeholder in the main search
+        let empty = BookmarksManager {
+            bookmarks: OnceLock::from(Vec::new()),
+            options: ModeConfig::default(),
+        };
+        assert!(empty.all_items().is_empty());
+    }
+
+    #[test]
+    fn test_query_places() {
+        let path = std::env::temp_dir().join(format!("wlaunch-test-places-{}.sqlite", std::process::id()));
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT);
+             CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER,
+                                         parent INTEGER, title TEXT, dateAdded INTEGER);
+             INSERT INTO moz_places VALUES (1, 'https://docs.rs/'), (2, 'place:sort=8');
+             INSERT INTO moz_bookmarks VALUES (10, 2, NULL, 0, 'toolbar', 0),
+                                              (11, 1, 1, 10, 'Docs', 1700000000000000),
+                                              (12, 1, 2, 10, 'Most visited', 0);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let bookmarks = BookmarksManager::query_places(&path, "Firefox (default)").unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(
+            bookmarks,
+            [Bookmark {
+                title: "Docs".to_string(),
+                url: "https://docs.rs/".to_string(),
+                folder: "Bookmarks Toolbar".to_string(),
+                browser: "Firefox (default)".to_string(),
+                added: 1_700_000_000,
+            }]
+        );
+    }
+}
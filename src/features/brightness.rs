@@ -0,0 +1,297 @@
+//! Display brightness control.
+//!
+//! - Laptop panels through the kernel backlight (via `brightnessctl`)
+//! - External monitors through DDC/CI (via `ddcutil`), including contrast
+
+use crate::core::{Item, ItemType};
+use std::process::Command;
+
+/// VCP feature code for brightness.
+const VCP_BRIGHTNESS: &str = "10";
+/// VCP feature code for contrast.
+const VCP_CONTRAST: &str = "12";
+
+/// An external monitor detected by `ddcutil detect`.
+#[derive(Debug, Clone, PartialEq)]
+struct DdcMonitor {
+    /// ddcutil display number (`--display N`)
+    display: u32,
+    name: String,
+}
+
+#[derive(Clone)]
+pub struct BrightnessManager;
+
+impl BrightnessManager {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Lists the backlight and every DDC monitor. A numeric query
+    /// (`bri 60`, `bri contrast 70`) turns into "set" actions per display.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim().to_lowercase();
+        let (feature, value) = Self::parse_query(&query);
+        let mut items = Vec::new();
+
+        if let Some(level) = self.get_backlight() {
+            let mut item = match (feature, value) {
+                (VCP_BRIGHTNESS, Some(value)) => Item::new(
+                    format!("display:backlight:set:{}", value),
+                    format!("Set Built-in Display to {}%", value),
+                    ItemType::DisplayAction,
+                ),
+                _ => Item::new(
+                    "display:backlight",
+                    format!("Built-in Display: {}%", level),
+                    ItemType::Display,
+                ),
+            }
+            .with_description("Laptop backlight")
+            .with_icon("display-brightness");
+            item.metadata.brightness = Some(level);
+            if value.is_none() || feature == VCP_BRIGHTNESS {
+                items.push(item);
+            }
+        }
+
+        for monitor in self.get_monitors() {
+            let brightness = self.get_vcp(monitor.display, VCP_BRIGHTNESS);
+            let contrast = self.get_vcp(monitor.display, VCP_CONTRAST);
+
+            let mut item = match value {
+                Some(value) => Item::new(
+                    format!("display:ddc:{}:{}:{}", monitor.display, feature, value),
+                    format!(
+                        "Set {} {} to {}%",
+                        monitor.name,
+                        if feature == VCP_CONTRAST { "contrast" } else { "brightness" },
+                        value
+                    ),
+                    ItemType::DisplayAction,
+                ),
+                None => Item::new(
+                    format!("display:ddc:{}", monitor.display),
+                    format!(
+                        "{}: {}%",
+                        monitor.name,
+                        brightness.map(|b| b.to_string()).unwrap_or_else(|| "?".to_string())
+                    ),
+                    ItemType::Display,
+                ),
+            }
+            .with_description(format!(
+                "Display {} (DDC/CI) | Contrast: {}",
+                monitor.display,
+                contrast.map(|c| format!("{}%", c)).unwrap_or_else(|| "?".to_string())
+            ))
+            .with_icon("video-display");
+            item.metadata.brightness = brightness;
+            items.push(item);
+        }
+
+        if items.is_empty() {
+            items.push(
+                Item::new("display:none", "No adjustable displays found", ItemType::DisplayAction)
+                    .with_description("Install brightnessctl for laptops or ddcutil for external monitors")
+                    .with_icon("dialog-warning"),
+            );
+        }
+
+        items
+    }
+
+    /// Splits `"contrast 70"` / `"70"` into a VCP code and an optional value.
+    fn parse_query(query: &str) -> (&'static str, Option<u32>) {
+        let (feature, rest) = match query.split_once(' ') {
+            Some(("contrast", rest)) => (VCP_CONTRAST, rest),
+            Some(("brightness", rest)) => (VCP_BRIGHTNESS, rest),
+            _ => (VCP_BRIGHTNESS, query),
+        };
+        let value = rest.trim_end_matches('%').parse::<u32>().ok().map(|v| v.min(100));
+        (feature, value)
+    }
+
+    fn get_backlight(&self) -> Option<u32> {
+        // Format: device,class,current,percent%,max
+        let output = Command::new("brightnessctl").arg("-m").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let line = stdout.lines().next()?;
+        line.split(',').nth(3)?.trim_end_matches('%').parse().ok()
+    }
+
+    fn get_monitors(&self) -> Vec<DdcMonitor> {
+        match Command::new("ddcutil").args(["detect", "--brief"]).output() {
+            Ok(output) if output.status.success() => {
+                Self::parse_detect(&String::from_utf8_lossy(&output.stdout))
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn parse_detect(output: &str) -> Vec<DdcMonitor> {
+        // Display 1
+        //    I2C bus:  /dev/i2c-4
+        //    Monitor:  DEL:DELL U2720Q:ABC123
+        let mut monitors = Vec::new();
+        let mut current: Option<DdcMonitor> = None;
+
+        for line in output.lines() {
+            let trimmed = line.trim();
+            if let Some(number) = trimmed.strip_prefix("Display ") {
+                if let Some(monitor) = current.take() {
+                    monitors.push(monitor);
+                }
+                if let Ok(display) = number.trim().parse() {
+                    current = Some(DdcMonitor {
+                        display,
+                        name: format!("Display {}", display),
+                    });
+                }
+            } else if trimmed.starts_with("Invalid display") {
+                // Phantom displays can't be controlled
+                if let Some(monitor) = current.take() {
+                    monitors.push(monitor);
+                }
+            } else if let Some(model) = trimmed.strip_prefix("Monitor:") {
+                if let Some(monitor) = current.as_mut() {
+                    // mfg:model:serial
+                    let model = model.trim().split(':').nth(1).unwrap_or(model.trim());
+                    if !model.is_empty() {
+                        monitor.name = model.to_string();
+                    }
+                }
+            }
+        }
+
+        if let Some(monitor) = current {
+            monitors.push(monitor);
+        }
+        monitors
+    }
+
+    /// The current value of `code` as a percentage of its maximum.
+    fn get_vcp(&self, display: u32, code: &str) -> Option<u32> {
+        let (current, max) = self.read_vcp(display, code)?;
+        Some(current * 100 / max)
+    }
+
+    fn read_vcp(&self, display: u32, code: &str) -> Option<(u32, u32)> {
+        let output = Command::new("ddcutil")
+            .args(["--display", &display.to_string(), "--brief", "getvcp", code])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Self::parse_vcp(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Parses `VCP 10 C 50 100` into the current value and the maximum.
+    fn parse_vcp(output: &str) -> Option<(u32, u32)> {
+        let line = output.lines().find(|l| l.starts_with("VCP "))?;
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 5 || parts[2] != "C" {
+            return None;
+        }
+        let current: u32 = parts[3].parse().ok()?;
+        let max: u32 = parts[4].parse().ok()?;
+        if max == 0 {
+            return None;
+        }
+        Some((current, max))
+    }
+
+    /// The raw value for `percent` of a monitor's `max`, e.g. 30 for 50%
+    /// of 60.
+    fn vcp_value(percent: u32, max: u32) -> u32 {
+        (percent.min(100) * max + 50) / 100
+    }
+
+    pub fn set_backlight(&self, percent: u32) {
+        let _ = Command::new("brightnessctl")
+            .args(["set", &format!("{}%", percent.min(100))])
+            .output();
+    }
+
+    /// Sets `code` to `percent` of the monitor's maximum for it.
+    pub fn set_vcp(&self, display: u32, code: &str, percent: u32) {
+        let Some((_, max)) = self.read_vcp(display, code) else {
+            log::warn!("Can't read VCP {} of display {}, leaving it as is", code, display);
+            return;
+        };
+        let _ = Command::new("ddcutil")
+            .args([
+                "--display",
+                &display.to_string(),
+                "setvcp",
+                code,
+                &Self::vcp_value(percent, max).to_string(),
+            ])
+            .output();
+    }
+
+    pub fn execute_action(&self, action_id: &str) {
+        if let Some(value) = action_id.strip_prefix("display:backlight:set:") {
+            if let Ok(percent) = value.parse() {
+                self.set_backlight(percent);
+            }
+        } else if let Some(rest) = action_id.strip_prefix("display:ddc:") {
+            // display:ddc:<display>:<vcp code>:<percent>
+            let parts: Vec<&str> = rest.split(':').collect();
+            if let [display, code, value] = parts.as_slice() {
+                if let (Ok(display), Ok(percent)) = (display.parse(), value.parse()) {
+                    self.set_vcp(display, code, percent);
+                }
+            }
+        }
+    }
+}
+
+impl Default for BrightnessManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_detect() {
+        let output = "Display 1\n   I2C bus:  /dev/i2c-4\n   Monitor:  DEL:DELL U2720Q:ABC123\n\n\
+                      Invalid display\n   I2C bus:  /dev/i2c-6\n\n\
+                      Display 2\n   I2C bus:  /dev/i2c-7\n   Monitor:  GSM:LG ULTRAFINE:XYZ\n";
+        let monitors = BrightnessManager::parse_detect(output);
+        assert_eq!(monitors.len(), 2);
+        assert_eq!(monitors[0].display, 1);
+        assert_eq!(monitors[0].name, "DELL U2720Q");
+        assert_eq!(monitors[1].name, "LG ULTRAFINE");
+    }
+
+    #[test]
+    fn test_parse_vcp() {
+        assert_eq!(BrightnessManager::parse_vcp("VCP 10 C 50 100\n"), Some((50, 100)));
+        assert_eq!(BrightnessManager::parse_vcp("VCP 12 C 30 60\n"), Some((30, 60)));
+        assert_eq!(BrightnessManager::parse_vcp("VCP 10 ERR\n"), None);
+        assert_eq!(BrightnessManager::parse_vcp("VCP 10 C 0 0\n"), None);
+
+        // Writes are scaled back to the monitor's maximum
+        assert_eq!(BrightnessManager::vcp_value(50, 60), 30);
+        assert_eq!(BrightnessManager::vcp_value(100, 60), 60);
+        assert_eq!(BrightnessManager::vcp_value(150, 60), 60);
+        assert_eq!(BrightnessManager::vcp_value(48, 60), 29);
+        assert_eq!(BrightnessManager::vcp_value(70, 100), 70);
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(BrightnessManager::parse_query("60"), (VCP_BRIGHTNESS, Some(60)));
+        assert_eq!(BrightnessManager::parse_query("contrast 70%"), (VCP_CONTRAST, Some(70)));
+        assert_eq!(BrightnessManager::parse_query(""), (VCP_BRIGHTNESS, None));
+    }
+}
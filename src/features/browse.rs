@@ -0,0 +1,173 @@
+//! Browsing folders from the search field: `cd ~/code/` lists the folder's
+//! children, and whatever follows the last `/` filters them. The query is
+//! the breadcrumb, so opening a folder appends it and Backspace over the
+//! trailing `/` goes up a level.
+
+use super::FileManager;
+use crate::core::config::ModeConfig;
+use crate::core::{Config, Item, ItemType};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Clone)]
+pub struct DirectoryBrowser {
+    options: ModeConfig,
+}
+
+impl DirectoryBrowser {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            options: config.mode("cd"),
+        }
+    }
+
+    /// `path` as typed after `cd`, with the home folder as `~` and a
+    /// trailing `/`.
+    pub fn query_path(path: &Path) -> String {
+        let shown = match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+            Some(relative) if relative.as_os_str().is_empty() => "~".to_string(),
+            Some(relative) => format!("~/{}", relative.display()),
+            None => path.display().to_string(),
+        };
+        if shown.ends_with('/') {
+            shown
+        } else {
+            format!("{}/", shown)
+        }
+    }
+
+    /// The folder to list and the filter for its children; without a `/`
+    /// the home folder is listed.
+    fn split(query: &str) -> (PathBuf, &str) {
+        match query.rfind('/') {
+            Some(i) if query[..i].is_empty() => (PathBuf::from("/"), &query[i + 1..]),
+            Some(i) if &query[..i] == "~" => (dirs::home_dir().unwrap_or_default(), &query[i + 1..]),
+            Some(i) => (Config::expand_home(&query[..i]), &query[i + 1..]),
+            None => (dirs::home_dir().unwrap_or_default(), query),
+        }
+    }
+
+    /// The query one level up when Backspace removed the trailing `/` of a
+    /// `cd` query, e.g. `cd ~/code/wlaunch/` to `cd ~/code/`.
+    pub fn up_a_level(previous: &str, query: &str) -> Option<String> {
+        let path = previous.strip_prefix("cd ")?;
+        if !path.ends_with('/') || previous.strip_suffix('/') != Some(query) {
+            return None;
+        }
+        let (dir, _) = Self::split(path);
+        Some(format!("cd {}", Self::query_path(dir.parent()?)))
+    }
+
+    /// Folders first, then files; names starting with the filter come
+    /// before those only containing it. Dotfiles show once the filter
+    /// starts with a dot.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let (dir, filter) = Self::split(query);
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => return Item::error_items("cd", format!("Can't open {}", dir.display()), e.to_string()),
+        };
+        let filter_lower = filter.to_lowercase();
+        let show_hidden = filter.starts_with('.') || self.options.show_hidden.unwrap_or(false);
+
+        let mut children: Vec<(bool, bool, String, PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let lower = name.to_lowercase();
+                if (!show_hidden && name.starts_with('.')) || !lower.contains(&filter_lower) {
+                    return None;
+                }
+                let path = entry.path();
+                Some((!lower.starts_with(&filter_lower), !path.is_dir(), lower, path))
+            })
+            .collect();
+        children.sort();
+
+        let mut items: Vec<Item> = children
+            .into_iter()
+            .take(self.options.max_results.unwrap_or(200))
+            .map(|(_, _, _, path)| FileManager::file_item(&path))
+            .collect();
+
+        if filter.is_empty() {
+            let shown = dir.display().to_string();
+            items.push(
+                Item::new(format!("cd:terminal:{}", shown), "Open terminal here", ItemType::FolderAction)
+                    .with_description(shown.clone())
+                    .with_icon("utilities-terminal"),
+            );
+            items.push(
+                Item::new(format!("cd:open:{}", shown), "Open in file manager", ItemType::FolderAction)
+                    .with_description(shown)
+                    .with_icon("system-file-manager"),
+            );
+        } else if items.is_empty() {
+            items.push(
+                Item::new("cd:none", format!("Nothing in {} matches \"{}\"", dir.display(), filter), ItemType::Command)
+                    .with_description("Backspace to the / to see everything")
+                    .with_icon("dialog-information"),
+            );
+        }
+        items
+    }
+
+    /// Runs a folder action; returns whether it started.
+    pub fn execute_action(&self, action_id: &str) -> bool {
+        let result = if let Some(path) = action_id.strip_prefix("cd:terminal:") {
+            Command::new("x-terminal-emulator").current_dir(path).spawn()
+        } else if let Some(path) = action_id.strip_prefix("cd:open:") {
+            Command::new("xdg-open").arg(path).spawn()
+        } else {
+            return false;
+        };
+        match result {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("Failed to run {}: {}", action_id, e);
+                false
+            }
+        }
+    }
+}
+
+impl Default for DirectoryBrowser {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_up_a_level() {
+        assert_eq!(DirectoryBrowser::up_a_level("cd /usr/share/", "cd /usr/share").as_deref(), Some("cd /usr/"));
+        assert_eq!(DirectoryBrowser::up_a_level("cd /usr/", "cd /usr").as_deref(), Some("cd /"));
+        assert_eq!(DirectoryBrowser::up_a_level("cd /", "cd "), None);
+        // Only deleting the slash goes up; typing after it filters
+        assert_eq!(DirectoryBrowser::up_a_level("cd /usr/share/", "cd /usr/share/d"), None);
+        assert_eq!(DirectoryBrowser::up_a_level("cd /usr/sh", "cd /usr/s"), None);
+    }
+
+    #[test]
+    fn test_listing() {
+        let dir = std::env::temp_dir().join(format!("wlaunch-browse-{}", std::process::id()));
+        for folder in ["src", "docs", ".git"] {
+            fs::create_dir_all(dir.join(folder)).unwrap();
+        }
+        for file in ["README.md", "Cargo.toml", ".gitignore"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        let browser = DirectoryBrowser::default();
+        let names = |query: String| browser.get_items(&query).into_iter().map(|item| item.name).collect::<Vec<_>>();
+
+        let root = format!("{}/", dir.display());
+        assert_eq!(names(root.clone()), ["docs", "src", "Cargo.toml", "README.md", "Open terminal here", "Open in file manager"]);
+        assert_eq!(names(format!("{}s", root)), ["src", "docs"]);
+        assert_eq!(names(format!("{}.git", root)), [".git", ".gitignore"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,70 +1,386 @@
-use crate::core::{Item, ItemType};
+use crate::core::{Config, Item, ItemType, NumberFormat};
+use crate::features::converter::currency::Rates;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// A number followed by a unit, "20 usd"; compiled on first use as it's
+/// matched on every keystroke
+static AMOUNT: OnceLock<Regex> = OnceLock::new();
+
+type MathFn = fn(f64) -> f64;
+
+const FUNCTIONS: &[(&str, MathFn)] = &[
+    ("sqrt", f64::sqrt),
+    ("sin", f64::sin),
+    ("cos", f64::cos),
+    ("tan", f64::tan),
+    ("log", f64::log10),
+    ("ln", f64::ln),
+    ("abs", f64::abs),
+    ("floor", f64::floor),
+    ("ceil", f64::ceil),
+    ("round", f64::round),
+];
+
+/// Bases for programmer-style results ("0xff to dec", "255 in bin").
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Radix {
+    Hex,
+    Dec,
+    Oct,
+    Bin,
+}
 
-pub struct Calculator;
+const RADIXES: [Radix; 4] = [Radix::Hex, Radix::Dec, Radix::Oct, Radix::Bin];
 
-impl Calculator {
-    pub fn new() -> Self {
-        Self
+impl Radix {
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "hex" | "hexadecimal" => Some(Radix::Hex),
+            "dec" | "decimal" => Some(Radix::Dec),
+            "oct" | "octal" => Some(Radix::Oct),
+            "bin" | "binary" => Some(Radix::Bin),
+            _ => None,
+        }
     }
 
-    pub fn get_items(&self, query: &str) -> Vec<Item> {
-        if query.is_empty() {
-            return Vec::new();
+    fn name(&self) -> &'static str {
+        match self {
+            Radix::Hex => "hex",
+            Radix::Dec => "dec",
+            Radix::Oct => "oct",
+            Radix::Bin => "bin",
         }
+    }
 
-        match self.evaluate(query) {
-            Some(result) => {
-                let result_str = if result.fract() == 0.0 {
-                    format!("{}", result as i64)
-                } else {
-                    format!("{:.6}", result).trim_end_matches('0').trim_end_matches('.').to_string()
-                };
+    fn format(&self, value: i64) -> String {
+        match self {
+            Radix::Hex => format!("{:#x}", value),
+            Radix::Dec => value.to_string(),
+            Radix::Oct => format!("0o{:o}", value),
+            Radix::Bin => format!("{:#b}", value),
+        }
+    }
+}
+
+/// Evaluations kept for the session; `ans` is the last one.
+pub struct Calculator {
+    history: Vec<(String, f64)>,
+    variables: BTreeMap<String, f64>,
+    numbers: NumberFormat,
+}
+
+impl Calculator {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            history: Vec::new(),
+            variables: BTreeMap::new(),
+            numbers: NumberFormat::new(&config.numbers),
+        }
+    }
+
+    /// The result of `query` (if any), followed by the session's variables
+    /// and history, newest first. Currency amounts are converted with `rates`.
+    pub fn get_items(&self, query: &str, rates: &Rates) -> Vec<Item> {
+        let query = query.trim();
+        let mut items = Vec::new();
+
+        if !query.is_empty() {
+            let (variable, expr) = Self::split_assignment(query);
+            let expr = &self.numbers.normalize(expr);
+            let (radix_expr, target) = Self::split_radix(expr);
+            let description = match variable {
+                Some(_) => "Press Enter to store the variable",
+                None => "Press Enter to copy result and keep it as ans",
+            };
+            if let Some((amount, code)) = self.evaluate_currency(expr, rates) {
+                let result_str = self.numbers.format_fixed(amount, 2);
+                let mut item = Item::new(
+                    "calc:eval",
+                    format!("{} = {} {}", variable.unwrap_or(query), result_str, code),
+                    ItemType::Calculator,
+                )
+                .with_description(format!("{} | {}", rates.source(), description))
+                .with_icon("accessories-calculator");
 
+                item.metadata.content = Some(self.numbers.ungrouped(&result_str));
+                items.push(item);
+            } else if let Some(result) = self.evaluate(radix_expr) {
+                let integer = Self::as_integer(result);
+                let result_str = match (target, integer) {
+                    (Some(radix), Some(n)) => radix.format(n),
+                    _ => self.format(result),
+                };
                 let mut item = Item::new(
-                    format!("calc:{}", result_str),
-                    format!("{} = {}", query, result_str),
+                    "calc:eval",
+                    format!("{} = {}", variable.unwrap_or(query), result_str),
                     ItemType::Calculator,
                 )
-                .with_description("Press Enter to copy result")
+                .with_description(description)
                 .with_icon("accessories-calculator");
 
-                item.metadata.content = Some(result_str);
-                vec![item]
+                item.metadata.content = Some(self.numbers.ungrouped(&result_str));
+                items.push(item);
+
+                // The other bases, when the query is about bits
+                if let Some(n) = integer.filter(|_| target.is_some() || Self::is_programmer(radix_expr)) {
+                    for radix in RADIXES.into_iter().filter(|r| Some(*r) != target) {
+                        let mut item = Item::new(
+                            format!("calc:radix:{}", radix.name()),
+                            format!("{}: {}", radix.name(), radix.format(n)),
+                            ItemType::Calculator,
+                        )
+                        .with_description("Press Enter to copy")
+                        .with_icon("accessories-calculator");
+                        item.metadata.content = Some(radix.format(n));
+                        items.push(item);
+                    }
+                }
+            }
+        }
+
+        for (name, value) in &self.variables {
+            let mut item = Item::new(
+                format!("calc:var:{}", name),
+                format!("{} = {}", name, self.format(*value)),
+                ItemType::Calculator,
+            )
+            .with_description("Variable")
+            .with_icon("accessories-calculator");
+            item.metadata.content = Some(self.numbers.ungrouped(&self.format(*value)));
+            items.push(item);
+        }
+
+        for (i, (expr, value)) in self.history.iter().enumerate().rev() {
+            let mut item = Item::new(
+                format!("calc:history:{}", i),
+                format!("{} = {}", expr, self.format(*value)),
+                ItemType::Calculator,
+            )
+            .with_description(if i + 1 == self.history.len() { "ans" } else { "History" })
+            .with_icon("document-open-recent");
+            item.metadata.content = Some(self.numbers.ungrouped(&self.format(*value)));
+            items.push(item);
+        }
+
+        items
+    }
+
+    /// Evaluates `query` for real: stores assignments, otherwise appends to
+    /// the history so the result becomes `ans`.
+    pub fn commit(&mut self, query: &str, rates: &Rates) -> Option<f64> {
+        let query = query.trim();
+        let (variable, expr) = Self::split_assignment(query);
+        let expr = &self.numbers.normalize(expr);
+        let result = match self.evaluate_currency(expr, rates) {
+            Some((amount, _)) => amount,
+            None => self.evaluate(Self::split_radix(expr).0)?,
+        };
+        match variable {
+            Some(name) => {
+                self.variables.insert(name.to_string(), result);
+            }
+            None => self.history.push((query.to_string(), result)),
+        }
+        Some(result)
+    }
+
+    /// Whether `query` looks like `name = expression`.
+    pub fn is_assignment(query: &str) -> bool {
+        Self::split_assignment(query).0.is_some()
+    }
+
+    /// Whether `query` assigns a plain numeric expression, "rate = 0.2 * 3",
+    /// so it can switch to the calculator without a `=` prefix.
+    pub fn is_numeric_assignment(query: &str) -> bool {
+        match Self::split_assignment(query.trim()) {
+            (Some(_), expr) => Self::default().evaluate(Self::split_radix(expr).0).is_some(),
+            (None, _) => false,
+        }
+    }
+
+    fn split_assignment(query: &str) -> (Option<&str>, &str) {
+        if let Some((name, expr)) = query.split_once('=') {
+            let name = name.trim();
+            let is_identifier = name.starts_with(|c: char| c.is_alphabetic())
+                && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+            let reserved = name == "ans" || name == "pi" || name == "e"
+                || FUNCTIONS.iter().any(|(f, _)| *f == name);
+            if is_identifier && !reserved {
+                return (Some(name), expr.trim());
+            }
+        }
+        (None, query)
+    }
+
+    /// Whether `query` converts a number to another base ("255 to hex").
+    pub fn is_radix_query(query: &str) -> bool {
+        let (expr, target) = Self::split_radix(query.trim());
+        target.is_some() && Self::default().evaluate(expr).is_some()
+    }
+
+    fn split_radix(expr: &str) -> (&str, Option<Radix>) {
+        let split = expr.rsplit_once(" to ").or_else(|| expr.rsplit_once(" in "));
+        match split.and_then(|(expr, target)| Some((expr, Radix::parse(target)?))) {
+            Some((expr, radix)) => (expr, Some(radix)),
+            None => (expr, None),
+        }
+    }
+
+    /// Whether `query` has currency amounts, so it needs current rates.
+    pub fn uses_currency(&self, query: &str, rates: &Rates) -> bool {
+        let expr = self.numbers.normalize(Self::split_assignment(query.trim()).1);
+        self.evaluate_currency(&expr, rates).is_some()
+    }
+
+    /// Evaluates an expression mixing currency amounts, "(20 usd + 15 eur) *
+    /// 2 in inr", in the `in`/`to` currency, or else the first one used.
+    /// Amounts are converted to the rates' base currency before evaluating.
+    fn evaluate_currency(&self, expr: &str, rates: &Rates) -> Option<(f64, String)> {
+        let (expr, target) = match expr.rsplit_once(" in ").or_else(|| expr.rsplit_once(" to ")) {
+            Some((expr, target)) if rates.knows(target.trim()) => (expr, Some(Rates::code(target.trim()))),
+            _ => (expr, None),
+        };
+
+        let amount = AMOUNT.get_or_init(|| Regex::new(r"(\d+(?:\.\d+)?)\s*([A-Za-z]+)\b").unwrap());
+        let mut base_expr = String::new();
+        let mut first = None;
+        let mut last = 0;
+        for caps in amount.captures_iter(expr) {
+            let (whole, number, unit) = (caps.get(0)?, &caps[1], &caps[2]);
+            if !rates.knows(unit) {
+                continue;
             }
-            None => Vec::new(),
+            let code = Rates::code(unit);
+            base_expr.push_str(&expr[last..whole.start()]);
+            base_expr.push_str(&format!("({}/{})", number, rates.rate(&code)?));
+            last = whole.end();
+            first.get_or_insert(code);
         }
+        base_expr.push_str(&expr[last..]);
+
+        let target = target.or(first)?;
+        let value = self.evaluate(&base_expr)? * rates.rate(&target)?;
+        Some((value, target))
+    }
+
+    /// Base prefixes or bitwise operators in the expression.
+    fn is_programmer(expr: &str) -> bool {
+        let lower = expr.to_lowercase();
+        lower.contains("0x")
+            || lower.contains("0b")
+            || lower.contains("0o")
+            || lower.contains(['&', '|', '~', '<', '>'])
+    }
+
+    fn as_integer(value: f64) -> Option<i64> {
+        (value.fract() == 0.0 && value.abs() < 9.2e18).then_some(value as i64)
+    }
+
+    fn format(&self, value: f64) -> String {
+        self.numbers.format(value, 6)
     }
 
     fn evaluate(&self, expr: &str) -> Option<f64> {
         // Simple expression parser
-        // Supports: +, -, *, /, ^, %, ()
+        // Supports: +, -, *, /, ^, %, (), functions, constants, ans and variables
 
         let expr = expr
             .replace(" ", "")
-            .replace("x", "*")
             .replace("×", "*")
             .replace("÷", "/")
             .replace("^", "**");
 
-        Self::parse_expression(&expr)
+        // "2x3" means multiplication, but "x" alone may be a variable and
+        // "0x10" is hex
+        let chars: Vec<char> = expr.chars().collect();
+        let expr: String = chars
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                let between_numbers = i > 0
+                    && (chars[i - 1].is_ascii_digit() || chars[i - 1] == ')')
+                    && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit() || *n == '(');
+                let hex_prefix = i > 0
+                    && chars[i - 1] == '0'
+                    && (i == 1 || !(chars[i - 2].is_ascii_alphanumeric() || chars[i - 2] == '.'));
+                if c == 'x' && between_numbers && !hex_prefix { '*' } else { c }
+            })
+            .collect();
+
+        self.parse_expression(&expr)
+    }
+
+    fn parse_expression(&self, expr: &str) -> Option<f64> {
+        match self.parse_bitor(expr)? {
+            (result, "") => Some(result),
+            _ => None,
+        }
+    }
+
+    // Bitwise operators bind looser than arithmetic, as in C: | < & < << >>
+
+    fn parse_bitor<'a>(&self, expr: &'a str) -> Option<(f64, &'a str)> {
+        let (mut left, mut rest) = self.parse_bitand(expr)?;
+
+        while let Some(new_rest) = rest.strip_prefix('|') {
+            let (right, new_rest) = self.parse_bitand(new_rest)?;
+            left = (Self::as_integer(left)? | Self::as_integer(right)?) as f64;
+            rest = new_rest;
+        }
+
+        Some((left, rest))
+    }
+
+    fn parse_bitand<'a>(&self, expr: &'a str) -> Option<(f64, &'a str)> {
+        let (mut left, mut rest) = self.parse_shift(expr)?;
+
+        while let Some(new_rest) = rest.strip_prefix('&') {
+            let (right, new_rest) = self.parse_shift(new_rest)?;
+            left = (Self::as_integer(left)? & Self::as_integer(right)?) as f64;
+            rest = new_rest;
+        }
+
+        Some((left, rest))
     }
 
-    fn parse_expression(expr: &str) -> Option<f64> {
-        Self::parse_additive(expr).map(|(result, _)| result)
+    fn parse_shift<'a>(&self, expr: &'a str) -> Option<(f64, &'a str)> {
+        let (mut left, mut rest) = self.parse_additive(expr)?;
+
+        loop {
+            let (shift_left, new_rest) = if let Some(r) = rest.strip_prefix("<<") {
+                (true, r)
+            } else if let Some(r) = rest.strip_prefix(">>") {
+                (false, r)
+            } else {
+                break;
+            };
+            let (right, new_rest) = self.parse_additive(new_rest)?;
+            let (value, amount) = (Self::as_integer(left)?, u32::try_from(Self::as_integer(right)?).ok()?);
+            left = if shift_left {
+                value.checked_shl(amount)?
+            } else {
+                value.checked_shr(amount)?
+            } as f64;
+            rest = new_rest;
+        }
+
+        Some((left, rest))
     }
 
-    fn parse_additive(expr: &str) -> Option<(f64, &str)> {
-        let (mut left, mut rest) = Self::parse_multiplicative(expr)?;
+    fn parse_additive<'a>(&self, expr: &'a str) -> Option<(f64, &'a str)> {
+        let (mut left, mut rest) = self.parse_multiplicative(expr)?;
 
         while let Some(c) = rest.chars().next() {
             match c {
                 '+' => {
-                    let (right, new_rest) = Self::parse_multiplicative(&rest[1..])?;
+                    let (right, new_rest) = self.parse_multiplicative(&rest[1..])?;
                     left += right;
                     rest = new_rest;
                 }
                 '-' => {
-                    let (right, new_rest) = Self::parse_multiplicative(&rest[1..])?;
+                    let (right, new_rest) = self.parse_multiplicative(&rest[1..])?;
                     left -= right;
                     rest = new_rest;
                 }
@@ -75,8 +391,8 @@ impl Calculator {
         Some((left, rest))
     }
 
-    fn parse_multiplicative(expr: &str) -> Option<(f64, &str)> {
-        let (mut left, mut rest) = Self::parse_power(expr)?;
+    fn parse_multiplicative<'a>(&self, expr: &'a str) -> Option<(f64, &'a str)> {
+        let (mut left, mut rest) = self.parse_power(expr)?;
 
         while let Some(c) = rest.chars().next() {
             match c {
@@ -84,12 +400,12 @@ impl Calculator {
                     if rest.starts_with("**") {
                         break; // Power operator, handle elsewhere
                     }
-                    let (right, new_rest) = Self::parse_power(&rest[1..])?;
+                    let (right, new_rest) = self.parse_power(&rest[1..])?;
                     left *= right;
                     rest = new_rest;
                 }
                 '/' => {
-                    let (right, new_rest) = Self::parse_power(&rest[1..])?;
+                    let (right, new_rest) = self.parse_power(&rest[1..])?;
                     if right == 0.0 {
                         return None; // Division by zero
                     }
@@ -97,7 +413,7 @@ impl Calculator {
                     rest = new_rest;
                 }
                 '%' => {
-                    let (right, new_rest) = Self::parse_power(&rest[1..])?;
+                    let (right, new_rest) = self.parse_power(&rest[1..])?;
                     left %= right;
                     rest = new_rest;
                 }
@@ -108,82 +424,89 @@ impl Calculator {
         Some((left, rest))
     }
 
-    fn parse_power(expr: &str) -> Option<(f64, &str)> {
-        let (base, rest) = Self::parse_unary(expr)?;
+    fn parse_power<'a>(&self, expr: &'a str) -> Option<(f64, &'a str)> {
+        let (base, rest) = self.parse_unary(expr)?;
 
-        if rest.starts_with("**") {
-            let (exponent, new_rest) = Self::parse_power(&rest[2..])?;
+        if let Some(rest) = rest.strip_prefix("**") {
+            let (exponent, new_rest) = self.parse_power(rest)?;
             Some((base.powf(exponent), new_rest))
         } else {
             Some((base, rest))
         }
     }
 
-    fn parse_unary(expr: &str) -> Option<(f64, &str)> {
+    fn parse_unary<'a>(&self, expr: &'a str) -> Option<(f64, &'a str)> {
         let expr = expr.trim_start();
 
-        if expr.starts_with('-') {
-            let (value, rest) = Self::parse_primary(&expr[1..])?;
+        if let Some(expr) = expr.strip_prefix('-') {
+            let (value, rest) = self.parse_primary(expr)?;
             Some((-value, rest))
-        } else if expr.starts_with('+') {
-            Self::parse_primary(&expr[1..])
+        } else if let Some(expr) = expr.strip_prefix('~') {
+            let (value, rest) = self.parse_primary(expr)?;
+            Some(((!Self::as_integer(value)?) as f64, rest))
+        } else if let Some(expr) = expr.strip_prefix('+') {
+            self.parse_primary(expr)
         } else {
-            Self::parse_primary(expr)
+            self.parse_primary(expr)
         }
     }
 
-    fn parse_primary(expr: &str) -> Option<(f64, &str)> {
+    fn parse_primary<'a>(&self, expr: &'a str) -> Option<(f64, &'a str)> {
         let expr = expr.trim_start();
 
         // Parentheses
-        if expr.starts_with('(') {
-            let (value, rest) = Self::parse_additive(&expr[1..])?;
-            let rest = rest.trim_start();
-            if rest.starts_with(')') {
-                return Some((value, &rest[1..]));
+        if let Some(expr) = expr.strip_prefix('(') {
+            let (value, rest) = self.parse_bitor(expr)?;
+            if let Some(rest) = rest.trim_start().strip_prefix(')') {
+                return Some((value, rest));
             }
             return None;
         }
 
-        // Functions
-        for (func_name, func) in [
-            ("sqrt", f64::sqrt as fn(f64) -> f64),
-            ("sin", f64::sin),
-            ("cos", f64::cos),
-            ("tan", f64::tan),
-            ("log", f64::log10),
-            ("ln", f64::ln),
-            ("abs", f64::abs),
-            ("floor", f64::floor),
-            ("ceil", f64::ceil),
-            ("round", f64::round),
-        ] {
-            if expr.to_lowercase().starts_with(func_name) {
-                let rest = &expr[func_name.len()..].trim_start();
-                if rest.starts_with('(') {
-                    let (arg, rest) = Self::parse_additive(&rest[1..])?;
-                    let rest = rest.trim_start();
-                    if rest.starts_with(')') {
-                        return Some((func(arg), &rest[1..]));
-                    }
-                }
+        // Functions, constants and variables
+        let ident_len = expr
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(expr.len());
+        if ident_len > 0 && expr.starts_with(|c: char| c.is_alphabetic()) {
+            let (ident, rest) = expr.split_at(ident_len);
+            let lower = ident.to_lowercase();
+
+            if let Some((_, func)) = FUNCTIONS.iter().find(|(name, _)| *name == lower) {
+                let rest = rest.trim_start().strip_prefix('(')?;
+                let (arg, rest) = self.parse_bitor(rest)?;
+                let rest = rest.trim_start().strip_prefix(')')?;
+                return Some((func(arg), rest));
             }
-        }
 
-        // Constants
-        if expr.to_lowercase().starts_with("pi") {
-            return Some((std::f64::consts::PI, &expr[2..]));
-        }
-        if expr.to_lowercase().starts_with("e") && !expr[1..].starts_with(|c: char| c.is_alphabetic()) {
-            return Some((std::f64::consts::E, &expr[1..]));
+            let value = match lower.as_str() {
+                "pi" => std::f64::consts::PI,
+                "e" => std::f64::consts::E,
+                "ans" => self.history.last()?.1,
+                _ => *self.variables.get(ident)?,
+            };
+            return Some((value, rest));
         }
 
         // Number
-        Self::parse_number(expr)
+        self.parse_number(expr)
     }
 
-    fn parse_number(expr: &str) -> Option<(f64, &str)> {
+    fn parse_number<'a>(&self, expr: &'a str) -> Option<(f64, &'a str)> {
         let expr = expr.trim_start();
+
+        // 0xff, 0o17, 0b1010
+        let radix = match expr.get(..2).map(str::to_lowercase).as_deref() {
+            Some("0x") => Some(16),
+            Some("0o") => Some(8),
+            Some("0b") => Some(2),
+            _ => None,
+        };
+        if let Some(radix) = radix {
+            let digits = &expr[2..];
+            let end = digits.find(|c: char| !c.is_digit(radix)).unwrap_or(digits.len());
+            let value = i64::from_str_radix(&digits[..end], radix).ok()?;
+            return Some((value as f64, &digits[end..]));
+        }
         let mut end = 0;
         let mut has_dot = false;
 
@@ -210,7 +533,7 @@ impl Calculator {
 
 impl Default for Calculator {
     fn default() -> Self {
-        Self::new()
+        Self::new(&Config::default())
     }
 }
 
@@ -220,7 +543,7 @@ mod tests {
 
     #[test]
     fn test_basic_operations() {
-        let calc = Calculator::new();
+        let calc = Calculator::default();
         assert_eq!(calc.evaluate("2 + 2"), Some(4.0));
         assert_eq!(calc.evaluate("10 - 3"), Some(7.0));
         assert_eq!(calc.evaluate("4 * 5"), Some(20.0));
@@ -229,15 +552,69 @@ mod tests {
 
     #[test]
     fn test_order_of_operations() {
-        let calc = Calculator::new();
+        let calc = Calculator::default();
         assert_eq!(calc.evaluate("2 + 3 * 4"), Some(14.0));
         assert_eq!(calc.evaluate("(2 + 3) * 4"), Some(20.0));
     }
 
+    #[test]
+    fn test_ans_and_variables() {
+        let mut calc = Calculator::default();
+        let rates = Rates::builtin();
+        assert_eq!(calc.commit("2 + 3", &rates), Some(5.0));
+        assert_eq!(calc.evaluate("ans * 2"), Some(10.0));
+        assert_eq!(calc.commit("x = 4", &rates), Some(4.0));
+        assert_eq!(calc.evaluate("x*2"), Some(8.0));
+        assert_eq!(calc.evaluate("2x3"), Some(6.0));
+        assert_eq!(calc.evaluate("y + 1"), None);
+        assert!(Calculator::is_assignment("rate = 0.2"));
+        assert!(!Calculator::is_assignment("pi = 3"));
+        assert!(Calculator::is_numeric_assignment("rate = 0.2 * 3"));
+        assert!(Calculator::is_numeric_assignment("mask = 0xff to bin"));
+        assert!(!Calculator::is_numeric_assignment("foo=bar"));
+        assert!(!Calculator::is_numeric_assignment("name = John Smith"));
+        assert!(!Calculator::is_numeric_assignment("my file=notes.txt"));
+    }
+
+    #[test]
+    fn test_programmer() {
+        let calc = Calculator::default();
+        assert_eq!(calc.evaluate("0xff"), Some(255.0));
+        assert_eq!(calc.evaluate("0x10 + 1"), Some(17.0));
+        assert_eq!(calc.evaluate("0b1010 + 5"), Some(15.0));
+        assert_eq!(calc.evaluate("0xf0 | 0x0f"), Some(255.0));
+        assert_eq!(calc.evaluate("12 & 10"), Some(8.0));
+        assert_eq!(calc.evaluate("1 << 4 + 1"), Some(32.0));
+        assert_eq!(calc.evaluate("256 >> 4"), Some(16.0));
+        assert_eq!(calc.evaluate("~0"), Some(-1.0));
+        assert_eq!(calc.evaluate("1.5 | 1"), None);
+
+        assert_eq!(Calculator::split_radix("255 to hex"), ("255", Some(Radix::Hex)));
+        assert!(Calculator::is_radix_query("0xff to dec"));
+        assert!(!Calculator::is_radix_query("5 km to mi"));
+        assert_eq!(Radix::Bin.format(10), "0b1010");
+        assert_eq!(Radix::Oct.format(8), "0o10");
+    }
+
     #[test]
     fn test_power() {
-        let calc = Calculator::new();
+        let calc = Calculator::default();
         assert_eq!(calc.evaluate("2 ** 3"), Some(8.0));
         assert_eq!(calc.evaluate("2^3"), Some(8.0));
     }
+
+    #[test]
+    fn test_currency() {
+        let calc = Calculator::default();
+        let rates = Rates::builtin();
+        let (amount, code) = calc.evaluate_currency("(10.8 usd + 15 eur) * 2 in inr", &rates).unwrap();
+        assert_eq!(code, "INR");
+        assert!((amount - 4500.0).abs() < 1e-6);
+
+        // Without a target, in the first currency used
+        let (amount, code) = calc.evaluate_currency("20 euros - 5 eur", &rates).unwrap();
+        assert_eq!((amount, code.as_str()), (15.0, "EUR"));
+        assert_eq!(calc.evaluate_currency("2 + 3", &rates), None);
+        assert_eq!(calc.evaluate_currency("255 in hex", &rates), None);
+    }
 }
@@ -0,0 +1,108 @@
+//! Quick capture: `qc <text>` files the text in an inbox file, a note or a
+//! todo with a single Enter, then closes.
+
+use crate::core::config::CaptureTarget;
+use crate::core::{Config, Item, ItemType};
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub struct QuickCapture {
+    inbox: Option<PathBuf>,
+    target: CaptureTarget,
+}
+
+impl QuickCapture {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            inbox: config.capture.inbox(),
+            target: config.capture.target(),
+        }
+    }
+
+    /// One item per target, the configured one first so Enter picks it.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let text = query.trim();
+        if text.is_empty() {
+            return vec![Item::new("capture:hint", "Type a thought to capture", ItemType::Command)
+                .with_description("Enter files it and closes the launcher")
+                .with_icon("document-new")];
+        }
+
+        let mut targets = vec![CaptureTarget::Inbox, CaptureTarget::Note, CaptureTarget::Todo];
+        targets.retain(|t| *t != CaptureTarget::Inbox || self.inbox.is_some());
+        targets.sort_by_key(|t| *t != self.target);
+
+        targets
+            .into_iter()
+            .map(|target| {
+                let (id, title, icon) = match target {
+                    CaptureTarget::Inbox => {
+                        let name = self.inbox.as_ref().and_then(|p| p.file_name()).map(|n| n.to_string_lossy());
+                        ("inbox", format!("Append to {}", name.unwrap_or_default()), "mail-inbox")
+                    }
+                    CaptureTarget::Note => ("note", "Save as note".to_string(), "text-x-generic"),
+                    CaptureTarget::Todo => ("todo", "Add as todo".to_string(), "checkbox"),
+                };
+                let mut item = Item::new(format!("capture:{}", id), title, ItemType::Capture)
+                    .with_description(text.to_string())
+                    .with_icon(icon);
+                item.metadata.content = Some(text.to_string());
+                item
+            })
+            .collect()
+    }
+
+    /// Appends `text` to the inbox file as an org heading or a markdown list
+    /// item, depending on its extension.
+    pub fn append(&self, text: &str) -> Result<()> {
+        let path = self.inbox.as_ref().ok_or_else(|| anyhow::anyhow!("No capture inbox configured"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // Start on a fresh line if the file doesn't end with one
+        let needs_newline = fs::read(path).is_ok_and(|bytes| bytes.last().is_some_and(|b| *b != b'\n'));
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if needs_newline {
+            writeln!(file)?;
+        }
+        file.write_all(Self::entry(path, text, Local::now()).as_bytes())?;
+        Ok(())
+    }
+
+    fn entry(path: &Path, text: &str, now: DateTime<Local>) -> String {
+        if path.extension().is_some_and(|e| e == "org") {
+            format!("* {}\n  [{}]\n", text, now.format("%Y-%m-%d %a %H:%M"))
+        } else {
+            format!("- {} {}\n", now.format("%Y-%m-%d %H:%M"), text)
+        }
+    }
+}
+
+impl Default for QuickCapture {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_entry() {
+        let now = Local.with_ymd_and_hms(2026, 10, 16, 9, 5, 0).unwrap();
+        assert_eq!(
+            QuickCapture::entry(Path::new("inbox.org"), "call mom", now),
+            "* call mom\n  [2026-10-16 Fri 09:05]\n"
+        );
+        assert_eq!(
+            QuickCapture::entry(Path::new("inbox.md"), "call mom", now),
+            "- 2026-10-16 09:05 call mom\n"
+        );
+    }
+}
@@ -1,9 +1,26 @@
-use crate::core::{Config, Item, ItemType};
+use crate::core::{CommandRunner, Config, Item, ItemType};
 use anyhow::Result;
 use arboard::Clipboard;
 use chrono::Local;
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+/// Delay before typing, so focus is back on the previous window.
+const PASTE_DELAY: &str = "0.2";
+
+/// Entries merged by `merge` without a count.
+const DEFAULT_MERGE_COUNT: usize = 3;
+
+/// How often the daemon polls the clipboard while the session is in use,
+/// and while logind reports it idle.
+pub const ACTIVE_POLL: Duration = Duration::from_millis(500);
+pub const IDLE_POLL: Duration = Duration::from_secs(5);
+
+/// File formats `export` writes, by extension.
+const EXPORT_FORMATS: [(&str, &str); 3] = [("txt", "text"), ("csv", "CSV"), ("json", "JSON")];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ClipboardEntry {
@@ -80,23 +97,78 @@ impl ClipboardManager {
         self.clipboard.as_mut()?.get_text().ok()
     }
 
-    pub fn add_to_history(&mut self, text: &str) {
-        let entry = ClipboardEntry {
-            content: text.to_string(),
-            timestamp: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    /// Types `text` into whichever window is focused once the launcher has
+    /// closed: wtype or ydotool on Wayland, xdotool on X11.
+    pub fn type_into_focused(text: &str) -> Result<()> {
+        let tools: &[(&str, &[&str])] = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            &[("wtype", &["--"]), ("ydotool", &["type", "--"])]
+        } else {
+            &[("xdotool", &["type", "--clearmodifiers", "--"])]
         };
+        let (tool, args) = tools
+            .iter()
+            .find(|(tool, _)| which::which(tool).is_ok())
+            .ok_or_else(|| {
+                let names: Vec<&str> = tools.iter().map(|(tool, _)| *tool).collect();
+                anyhow::anyhow!("Install {} to paste on select", names.join(" or "))
+            })?;
+        // Runs on after we exit; the shell waits for the window to close
+        Command::new("sh")
+            .arg("-c")
+            .arg(format!("sleep {} && exec \"$0\" \"$@\"", PASTE_DELAY))
+            .arg(tool)
+            .args(*args)
+            .arg(text)
+            .spawn()?;
+        Ok(())
+    }
 
-        self.history.retain(|e| e.content != text);
-        self.history.insert(0, entry);
-
-        if self.history.len() > self.max_size {
-            self.history.truncate(self.max_size);
+    /// History entries; `merge [n]` offers to copy the last n as one, and
+    /// `export [txt|csv|json] [filter]` to save the entries matching filter.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let mut words = query.split_whitespace();
+        match words.next() {
+            Some("merge") => {
+                let count = words.next().and_then(|n| n.parse().ok()).unwrap_or(DEFAULT_MERGE_COUNT).max(1);
+                let count = count.min(self.history.len());
+                let mut items = vec![Item::new(format!("clipboard:merge:{}", count), format!("Copy last {} merged", count), ItemType::ClipboardAction)
+                    .with_description("Oldest first, one per line")
+                    .with_icon("edit-copy")];
+                items.extend(self.entry_items("").into_iter().take(count));
+                return items;
+            }
+            Some("export") => {
+                let rest: Vec<&str> = words.collect();
+                let (formats, filter) = match rest.split_first() {
+                    Some((format, filter)) if EXPORT_FORMATS.iter().any(|(ext, _)| ext == format) => {
+                        (EXPORT_FORMATS.iter().filter(|(ext, _)| ext == format).collect::<Vec<_>>(), filter.join(" "))
+                    }
+                    _ => (EXPORT_FORMATS.iter().collect(), rest.join(" ")),
+                };
+                let entries = self.entry_items(&filter);
+                let plural = if entries.len() == 1 { "y" } else { "ies" };
+                let what = if filter.is_empty() {
+                    format!("all {} entr{}", entries.len(), plural)
+                } else {
+                    format!("{} entr{} matching \"{}\"", entries.len(), plural, filter)
+                };
+                let mut items: Vec<Item> = formats
+                    .into_iter()
+                    .map(|(ext, name)| {
+                        Item::new(format!("clipboard:export:{}:{}", ext, filter), format!("Export {} as {}", what, name), ItemType::ClipboardAction)
+                            .with_description(format!("Saves them to {}", Self::export_dir().display()))
+                            .with_icon("document-save")
+                    })
+                    .collect();
+                items.extend(entries);
+                return items;
+            }
+            _ => {}
         }
-
-        let _ = self.save_history();
+        self.entry_items(query)
     }
 
-    pub fn get_items(&self, query: &str) -> Vec<Item> {
+    fn entry_items(&self, query: &str) -> Vec<Item> {
         let query = query.to_lowercase();
 
         self.history
@@ -127,10 +199,51 @@ impl ClipboardManager {
             .collect()
     }
 
-    pub fn clear_history(&mut self) {
-        self.history.clear();
-        let _ = self.save_history();
+    /// The last `count` entries, oldest first, one per line.
+    pub fn merged(&self, count: usize) -> Option<String> {
+        let entries: Vec<&str> = self.history.iter().take(count).rev().map(|e| e.content.as_str()).collect();
+        (!entries.is_empty()).then(|| entries.join("\n"))
     }
+
+    fn export_dir() -> PathBuf {
+        dirs::download_dir().or_else(dirs::home_dir).unwrap_or_default()
+    }
+
+    /// Runs `clipboard:export:<ext>:<filter>`, returning the file written.
+    pub fn export(&self, action_id: &str) -> Result<PathBuf> {
+        let (ext, filter) = action_id
+            .strip_prefix("clipboard:export:")
+            .and_then(|rest| rest.split_once(':'))
+            .ok_or_else(|| anyhow::anyhow!("Unknown export {}", action_id))?;
+        let filter = filter.to_lowercase();
+        let entries: Vec<&ClipboardEntry> = self
+            .history
+            .iter()
+            .filter(|entry| entry.content.to_lowercase().contains(&filter))
+            .collect();
+        let path = Self::export_dir().join(format!("clipboard-{}.{}", Local::now().format("%Y%m%d-%H%M%S"), ext));
+        fs::create_dir_all(Self::export_dir())?;
+        fs::write(&path, Self::render(&entries, ext)?)?;
+        Ok(path)
+    }
+
+    /// Plain text separates entries with a blank line; CSV and JSON keep
+    /// when each was copied.
+    fn render(entries: &[&ClipboardEntry], ext: &str) -> Result<String> {
+        Ok(match ext {
+            "txt" => entries.iter().map(|e| e.content.as_str()).collect::<Vec<_>>().join("\n\n") + "\n",
+            "csv" => {
+                let quote = |field: &str| format!("\"{}\"", field.replace('"', "\"\""));
+                std::iter::once("timestamp,content".to_string())
+                    .chain(entries.iter().map(|e| format!("{},{}", quote(&e.timestamp), quote(&e.content))))
+                    .map(|row| row + "\r\n")
+                    .collect()
+            }
+            "json" => serde_json::to_string_pretty(entries)?,
+            _ => anyhow::bail!("Unknown export format {}", ext),
+        })
+    }
+
 }
 
 impl Default for ClipboardManager {
@@ -138,3 +251,146 @@ impl Default for ClipboardManager {
         Self::new()
     }
 }
+
+/// What the daemon reports about itself in `daemon_status.json`, printed by
+/// `wlaunch daemon-status`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub started: String,
+    /// Clipboard entries added to the history since it started
+    pub captured: u64,
+    pub last_capture: Option<String>,
+    /// Whether logind's IdleHint was set at the last check
+    pub idle: bool,
+    pub poll_ms: u64,
+}
+
+impl DaemonStatus {
+    pub fn new() -> Self {
+        Self {
+            pid: std::process::id(),
+            started: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            poll_ms: ACTIVE_POLL.as_millis() as u64,
+            ..Self::default()
+        }
+    }
+
+    fn path() -> PathBuf {
+        Config::data_path("daemon_status.json")
+    }
+
+    pub fn load() -> Option<Self> {
+        serde_json::from_str(&fs::read_to_string(Self::path()).ok()?).ok()
+    }
+
+    pub fn save(&self) {
+        let path = Self::path();
+        let _ = fs::create_dir_all(path.parent().unwrap());
+        if let Ok(content) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, content);
+        }
+    }
+
+    /// Whether the daemon that wrote this is still running.
+    pub fn running(&self) -> bool {
+        fs::read_to_string(format!("/proc/{}/comm", self.pid)).is_ok_and(|comm| comm.trim() == "wlaunch")
+    }
+
+    pub fn summary(&self) -> String {
+        let last = match &self.last_capture {
+            Some(time) => format!("last at {}", time),
+            None => "none yet".to_string(),
+        };
+        let polling = if self.idle { "session idle" } else { "session in use" };
+        format!(
+            "Daemon running (pid {}) since {}\nClipboard: {} entries captured, {}\nPolling every {} ms ({})",
+            self.pid, self.started, self.captured, last, self.poll_ms, polling
+        )
+    }
+
+    /// Records a clipboard entry added to the history.
+    pub fn record_capture(&mut self) {
+        self.captured += 1;
+        self.last_capture = Some(Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+    }
+}
+
+/// logind's IdleHint for this session, set by the desktop or compositor
+/// (swayidle with `idlehint`) once the user is away; `None` when logind
+/// can't tell.
+pub fn session_idle(runner: &dyn CommandRunner) -> Option<bool> {
+    let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "auto".to_string());
+    let output = runner.run("loginctl", &["show-session", &session, "-p", "IdleHint", "--value"]).ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(contents: &[&str]) -> ClipboardManager {
+        ClipboardManager {
+            clipboard: None,
+            history: contents
+                .iter()
+                .map(|content| ClipboardEntry {
+                    content: content.to_string(),
+                    timestamp: "2026-10-17 09:30:00".to_string(),
+                })
+                .collect(),
+            max_size: 100,
+        }
+    }
+
+    #[test]
+    fn test_merge_and_export() {
+        let clipboard = manager(&["third", "second \"quoted\"", "first"]);
+        assert_eq!(clipboard.merged(2).as_deref(), Some("second \"quoted\"\nthird"));
+        assert_eq!(clipboard.get_items("merge")[0].name, "Copy last 3 merged");
+        assert_eq!(clipboard.get_items("merge 9")[0].id, "clipboard:merge:3");
+
+        let items = clipboard.get_items("export csv second");
+        assert_eq!(items[0].id, "clipboard:export:csv:second");
+        assert_eq!(items[0].name, "Export 1 entry matching \"second\" as CSV");
+        assert_eq!(clipboard.get_items("export")[2].name, "Export all 3 entries as JSON");
+
+        let entries: Vec<&ClipboardEntry> = clipboard.history.iter().skip(1).collect();
+        assert_eq!(
+            ClipboardManager::render(&entries, "csv").unwrap(),
+            "timestamp,content\r\n\"2026-10-17 09:30:00\",\"second \"\"quoted\"\"\"\r\n\"2026-10-17 09:30:00\",\"first\"\r\n"
+        );
+        assert_eq!(ClipboardManager::render(&entries, "txt").unwrap(), "second \"quoted\"\n\nfirst\n");
+    }
+
+    #[test]
+    fn test_daemon_status() {
+        use crate::core::runner::FakeRunner;
+        let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "auto".to_string());
+        let query = format!("loginctl show-session {} -p IdleHint --value", session);
+        assert_eq!(session_idle(&FakeRunner::new().with(&query, "yes\n")), Some(true));
+        assert_eq!(session_idle(&FakeRunner::new().failing(&query, 1, "Failed to get session")), None);
+
+        let mut status = DaemonStatus {
+            pid: 4242,
+            started: "2026-03-01 08:00:00".to_string(),
+            poll_ms: IDLE_POLL.as_millis() as u64,
+            idle: true,
+            ..DaemonStatus::default()
+        };
+        assert_eq!(
+            status.summary(),
+            "Daemon running (pid 4242) since 2026-03-01 08:00:00\nClipboard: 0 entries captured, none yet\nPolling every 5000 ms (session idle)"
+        );
+        status.record_capture();
+        assert_eq!(status.captured, 1);
+        assert!(status.last_capture.is_some());
+    }
+}
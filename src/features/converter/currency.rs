@@ -0,0 +1,192 @@
+//! Exchange rates for currency conversions. Rates are fetched in the
+//! background, cached in `currency_rates.json` and fall back to a built-in
+//! table while offline.
+
+use crate::core::Config;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+/// Approximate rates per euro, used until the first successful fetch.
+const BUILTIN_RATES: &[(&str, f64)] = &[
+    ("USD", 1.08),
+    ("GBP", 0.85),
+    ("JPY", 162.0),
+    ("CHF", 0.95),
+    ("CAD", 1.48),
+    ("AUD", 1.65),
+    ("NZD", 1.80),
+    ("CNY", 7.80),
+    ("INR", 90.0),
+    ("SEK", 11.4),
+    ("NOK", 11.6),
+    ("DKK", 7.46),
+    ("PLN", 4.30),
+    ("CZK", 25.0),
+    ("HUF", 395.0),
+    ("BRL", 5.90),
+    ("MXN", 19.5),
+    ("KRW", 1480.0),
+    ("SGD", 1.45),
+    ("HKD", 8.45),
+    ("ZAR", 20.0),
+    ("TRY", 36.0),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rates {
+    pub base: String,
+    /// When the provider last updated the rates (unix seconds); 0 for the
+    /// built-in table
+    pub updated: i64,
+    /// When the rates were downloaded (unix seconds)
+    pub fetched: i64,
+    pub rates: HashMap<String, f64>,
+}
+
+/// open.er-api.com response
+#[derive(Deserialize)]
+struct ApiResponse {
+    #[serde(default)]
+    result: Option<String>,
+    #[serde(rename = "error-type", default)]
+    error_type: Option<String>,
+    #[serde(default)]
+    base_code: String,
+    #[serde(default)]
+    time_last_update_unix: i64,
+    #[serde(default)]
+    rates: HashMap<String, f64>,
+}
+
+impl Rates {
+    fn cache_path() -> std::path::PathBuf {
+        Config::data_path("currency_rates.json")
+    }
+
+    /// Cached rates, or the built-in table if nothing was fetched yet.
+    pub fn load() -> Self {
+        fs::read_to_string(Self::cache_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_else(Self::builtin)
+    }
+
+    pub fn builtin() -> Self {
+        Self {
+            base: "EUR".to_string(),
+            updated: 0,
+            fetched: 0,
+            rates: BUILTIN_RATES
+                .iter()
+                .map(|(code, rate)| (code.to_string(), *rate))
+                .collect(),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::cache_path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn is_builtin(&self) -> bool {
+        self.updated == 0
+    }
+
+    pub fn is_stale(&self, refresh_hours: u64) -> bool {
+        let age = chrono::Utc::now().timestamp() - self.fetched;
+        age < 0 || age as u64 >= refresh_hours * 3600
+    }
+
+    /// Where the rates come from, e.g. "Rates from 2026-10-15".
+    pub fn source(&self) -> String {
+        if self.is_builtin() {
+            return "Offline estimate".to_string();
+        }
+        chrono::DateTime::from_timestamp(self.updated, 0)
+            .map(|date| format!("Rates from {}", date.format("%Y-%m-%d")))
+            .unwrap_or_else(|| "Cached rates".to_string())
+    }
+
+    /// Units of `code` per unit of the base currency.
+    pub fn rate(&self, code: &str) -> Option<f64> {
+        if code == self.base {
+            Some(1.0)
+        } else {
+            self.rates.get(code).copied()
+        }
+    }
+
+    /// Converts between currency codes or names ("usd", "euros").
+    pub fn convert(&self, value: f64, from: &str, to: &str) -> Option<f64> {
+        let from = self.rate(&Self::code(from))?;
+        let to = self.rate(&Self::code(to))?;
+        Some(value / from * to)
+    }
+
+    /// Whether `name` is a currency code or name with a rate.
+    pub fn knows(&self, name: &str) -> bool {
+        self.rate(&Self::code(name)).is_some()
+    }
+
+    /// Upper-cased ISO code for a currency code or common name.
+    pub fn code(name: &str) -> String {
+        match name.to_lowercase().as_str() {
+            "dollar" | "dollars" => "USD",
+            "euro" | "euros" => "EUR",
+            "yen" => "JPY",
+            "yuan" | "rmb" => "CNY",
+            "rupee" | "rupees" => "INR",
+            "franc" | "francs" => "CHF",
+            "won" => "KRW",
+            "zloty" => "PLN",
+            other => return other.to_uppercase(),
+        }
+        .to_string()
+    }
+
+    pub async fn fetch(url: String) -> Result<Self> {
+        let body = reqwest::get(&url).await?.error_for_status()?.text().await?;
+        Self::parse(&body)
+    }
+
+    fn parse(body: &str) -> Result<Self> {
+        let response: ApiResponse = serde_json::from_str(body)?;
+        if response.result.as_deref() == Some("error") || response.rates.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Exchange rate API error: {}",
+                response.error_type.as_deref().unwrap_or("no rates returned")
+            ));
+        }
+
+        Ok(Self {
+            base: response.base_code,
+            updated: response.time_last_update_unix,
+            fetched: chrono::Utc::now().timestamp(),
+            rates: response.rates,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_convert() {
+        let body = r#"{"result": "success", "base_code": "EUR", "time_last_update_unix": 1760486401,
+            "rates": {"EUR": 1, "USD": 1.25, "GBP": 0.8}}"#;
+        let rates = Rates::parse(body).unwrap();
+        assert!(!rates.is_builtin());
+        assert_eq!(rates.convert(100.0, "eur", "usd"), Some(125.0));
+        assert_eq!(rates.convert(125.0, "dollars", "euro"), Some(100.0));
+        assert_eq!(rates.convert(1.0, "usd", "xyz"), None);
+
+        assert!(Rates::parse(r#"{"result": "error", "error-type": "unsupported-code"}"#).is_err());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::core::{Item, ItemType};
+pub mod currency;
+
+use crate::core::{Config, Item, ItemType, NumberFormat};
+use currency::Rates;
 use regex::Regex;
 use std::collections::HashMap;
 
@@ -8,10 +11,15 @@ pub struct Converter {
     temperature_units: Vec<&'static str>,
     time_units: HashMap<&'static str, f64>,
     data_units: HashMap<&'static str, f64>,
+    rates: Rates,
+    rates_url: String,
+    refresh_hours: u64,
+    refresh_started: bool,
+    numbers: NumberFormat,
 }
 
 impl Converter {
-    pub fn new() -> Self {
+    pub fn new(config: &Config) -> Self {
         let mut length_units = HashMap::new();
         // Base unit: meters
         length_units.insert("m", 1.0);
@@ -113,49 +121,107 @@ impl Converter {
             temperature_units,
             time_units,
             data_units,
+            rates: Rates::load(),
+            rates_url: config.currency.api_url().to_string(),
+            refresh_hours: config.currency.refresh_hours(),
+            refresh_started: false,
+            numbers: NumberFormat::new(&config.numbers),
         }
     }
 
     pub fn get_items(&self, query: &str) -> Vec<Item> {
         if let Some((value, from, to)) = self.parse_conversion(query) {
             if let Some(result) = self.convert(value, &from, &to) {
-                let result_str = if result.fract() == 0.0 {
-                    format!("{}", result as i64)
-                } else {
-                    format!("{:.4}", result)
-                        .trim_end_matches('0')
-                        .trim_end_matches('.')
-                        .to_string()
-                };
+                let result_str = self.numbers.format(result, 4);
 
                 let mut item = Item::new(
                     format!("convert:{}", result_str),
-                    format!("{} {} = {} {}", value, from, result_str, to),
+                    format!("{} {} = {} {}", self.numbers.format(value, 6), from, result_str, to),
                     ItemType::Converter,
                 )
                 .with_description("Press Enter to copy result")
                 .with_icon("accessories-calculator");
 
-                item.metadata.content = Some(result_str);
+                item.metadata.content = Some(self.numbers.ungrouped(&result_str));
                 return vec![item];
             }
+
+            if let Some(result) = self.rates.convert(value, &from, &to) {
+                return vec![self.currency_item(value, &from, &to, result)];
+            }
         }
 
         Vec::new()
     }
 
+    fn currency_item(&self, value: f64, from: &str, to: &str, result: f64) -> Item {
+        let result_str = self.numbers.format_fixed(result, 2);
+
+        let mut item = Item::new(
+            format!("convert:{}", result_str),
+            format!(
+                "{} {} = {} {}",
+                self.numbers.format(value, 6),
+                Rates::code(from),
+                result_str,
+                Rates::code(to)
+            ),
+            ItemType::Converter,
+        )
+        .with_description(format!("{} | Press Enter to copy result", self.rates.source()))
+        .with_icon("accessories-calculator");
+
+        item.metadata.content = Some(self.numbers.ungrouped(&result_str));
+        item
+    }
+
+    /// Returns the rates URL to fetch if `query` converts currencies and the
+    /// cached rates are stale. Only asks once per session, so an offline
+    /// machine doesn't retry on every keystroke.
+    pub fn rates_refresh(&mut self, query: &str) -> Option<String> {
+        let (value, from, to) = self.parse_conversion(query)?;
+        if self.convert(value, &from, &to).is_some() {
+            return None;
+        }
+        if !self.rates.knows(&from) && !self.rates.knows(&to) {
+            return None;
+        }
+        self.refresh_rates()
+    }
+
+    /// Returns the rates URL to fetch if the cached rates are stale, once
+    /// per session.
+    pub fn refresh_rates(&mut self) -> Option<String> {
+        if self.refresh_started || !self.rates.is_stale(self.refresh_hours) {
+            return None;
+        }
+        self.refresh_started = true;
+        Some(self.rates_url.clone())
+    }
+
+    /// Exchange rates, shared with the calculator's currency amounts.
+    pub fn rates(&self) -> &Rates {
+        &self.rates
+    }
+
+    pub fn set_rates(&mut self, rates: Rates) {
+        let _ = rates.save();
+        self.rates = rates;
+    }
+
     fn parse_conversion(&self, query: &str) -> Option<(f64, String, String)> {
         // Patterns:
         // "100 km to mi"
         // "100km to mi"
         // "100 km in mi"
         // "100km in miles"
+        // "1.234,5 m to ft", in the locale's or English separators
 
         let query = query.to_lowercase();
-        let re = Regex::new(r"(\d+\.?\d*)\s*([a-z]+)\s+(?:to|in)\s+([a-z]+)").ok()?;
+        let re = Regex::new(r"(\d[\d.,'\u{a0}\u{202f}]*)\s*([a-z]+)\s+(?:to|in)\s+([a-z]+)").ok()?;
 
         if let Some(caps) = re.captures(&query) {
-            let value: f64 = caps.get(1)?.as_str().parse().ok()?;
+            let value = self.numbers.parse(caps.get(1)?.as_str())?;
             let from = caps.get(2)?.as_str().to_string();
             let to = caps.get(3)?.as_str().to_string();
             return Some((value, from, to));
@@ -232,7 +298,7 @@ impl Converter {
 
 impl Default for Converter {
     fn default() -> Self {
-        Self::new()
+        Self::new(&Config::default())
     }
 }
 
@@ -242,14 +308,14 @@ mod tests {
 
     #[test]
     fn test_length_conversion() {
-        let conv = Converter::new();
+        let conv = Converter::default();
         let result = conv.convert(1.0, "km", "m");
         assert_eq!(result, Some(1000.0));
     }
 
     #[test]
     fn test_temperature_conversion() {
-        let conv = Converter::new();
+        let conv = Converter::default();
         let result = conv.convert(100.0, "c", "f");
         assert!((result.unwrap() - 212.0).abs() < 0.001);
     }
@@ -0,0 +1,347 @@
+//! Date arithmetic in plain language: "days until dec 25", "today + 45 days",
+//! "weeks between 2024-01-01 and now", "3 weeks ago".
+
+use crate::core::{Item, ItemType};
+use chrono::{Datelike, Local, Months, NaiveDate, TimeDelta};
+use regex::Regex;
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unit {
+    Days,
+    Weeks,
+    Months,
+    Years,
+}
+
+impl Unit {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "d" | "day" | "days" => Some(Unit::Days),
+            "w" | "wk" | "wks" | "week" | "weeks" => Some(Unit::Weeks),
+            "m" | "mo" | "month" | "months" => Some(Unit::Months),
+            "y" | "yr" | "yrs" | "year" | "years" => Some(Unit::Years),
+            _ => None,
+        }
+    }
+
+    fn name(&self, count: i64) -> &'static str {
+        match (self, count.abs() == 1) {
+            (Unit::Days, true) => "day",
+            (Unit::Days, false) => "days",
+            (Unit::Weeks, true) => "week",
+            (Unit::Weeks, false) => "weeks",
+            (Unit::Months, true) => "month",
+            (Unit::Months, false) => "months",
+            (Unit::Years, true) => "year",
+            (Unit::Years, false) => "years",
+        }
+    }
+
+    /// `date` moved by `count` of this unit.
+    fn shift(&self, date: NaiveDate, count: i64) -> Option<NaiveDate> {
+        match self {
+            Unit::Days => date.checked_add_signed(TimeDelta::try_days(count)?),
+            Unit::Weeks => date.checked_add_signed(TimeDelta::try_weeks(count)?),
+            Unit::Months | Unit::Years => {
+                let months = if *self == Unit::Years { count.checked_mul(12)? } else { count };
+                let delta = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+                if months >= 0 {
+                    date.checked_add_months(delta)
+                } else {
+                    date.checked_sub_months(delta)
+                }
+            }
+        }
+    }
+
+    /// Whole units from `from` to `to` (negative if `to` is earlier).
+    fn between(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        let days = (to - from).num_days();
+        match self {
+            Unit::Days => days,
+            Unit::Weeks => days / 7,
+            Unit::Months | Unit::Years => {
+                let (start, end, sign) = if from <= to { (from, to, 1) } else { (to, from, -1) };
+                let mut months = (end.year() - start.year()) as i64 * 12
+                    + end.month() as i64
+                    - start.month() as i64;
+                if end.day() < start.day() {
+                    months -= 1;
+                }
+                let count = if *self == Unit::Years { months / 12 } else { months };
+                sign * count
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum DateResult {
+    /// A date and how it was reached, e.g. "45 days from today"
+    Date(NaiveDate, String),
+    /// A count of units between two dates
+    Span {
+        count: i64,
+        unit: Unit,
+        from: NaiveDate,
+        to: NaiveDate,
+    },
+}
+
+pub struct DateCalculator {
+    span: Regex,
+    between: Regex,
+    offset: Regex,
+    relative: Regex,
+    ago: Regex,
+}
+
+impl DateCalculator {
+    pub fn new() -> Self {
+        Self {
+            span: Regex::new(r"^(days|weeks|months|years)\s+(until|till|to|since|from)\s+(.+)$").unwrap(),
+            between: Regex::new(r"^(days|weeks|months|years)\s+between\s+(.+?)\s+and\s+(.+)$").unwrap(),
+            offset: Regex::new(r"^(.+?)\s*([+-])\s*(\d+)\s*([a-z]+)$").unwrap(),
+            relative: Regex::new(r"^(\d+)\s*([a-z]+)\s+(from|after|before)\s+(.+)$").unwrap(),
+            ago: Regex::new(r"^(\d+)\s*([a-z]+)\s+ago$").unwrap(),
+        }
+    }
+
+    /// Whether `query` is a date calculation, for mode detection.
+    pub fn is_date_query(query: &str) -> bool {
+        Self::new().evaluate(query, Local::now().date_naive()).is_some()
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let today = Local::now().date_naive();
+        let Some(result) = self.evaluate(query, today) else {
+            return Vec::new();
+        };
+
+        let (name, description, content) = match result {
+            DateResult::Date(date, how) => (
+                date.format("%A, %B %-d, %Y").to_string(),
+                how,
+                date.format("%Y-%m-%d").to_string(),
+            ),
+            DateResult::Span { count, unit, from, to } => {
+                let mut name = format!("{} {}", count, unit.name(count));
+                // Remainder for coarse units: "6 weeks, 3 days"
+                let rest = (to - unit.shift(from, count).unwrap_or(to)).num_days();
+                if unit != Unit::Days && rest != 0 {
+                    name.push_str(&format!(", {} {}", rest, Unit::Days.name(rest)));
+                }
+                (
+                    name,
+                    format!(
+                        "{} to {} ({} days)",
+                        from.format("%b %-d, %Y"),
+                        to.format("%b %-d, %Y"),
+                        (to - from).num_days()
+                    ),
+                    count.to_string(),
+                )
+            }
+        };
+
+        let mut item = Item::new(format!("date:{}", content), name, ItemType::DateCalc)
+            .with_description(format!("{} | Press Enter to copy", description))
+            .with_icon("x-office-calendar");
+        item.metadata.content = Some(content);
+        vec![item]
+    }
+
+    fn evaluate(&self, query: &str, today: NaiveDate) -> Option<DateResult> {
+        let query = query.trim().to_lowercase();
+
+        if let Some(caps) = self.between.captures(&query) {
+            let unit = Unit::parse(&caps[1])?;
+            let from = parse_date(&caps[2], today, false)?;
+            let to = parse_date(&caps[3], today, false)?;
+            let (from, to) = if from <= to { (from, to) } else { (to, from) };
+            return Some(DateResult::Span {
+                count: unit.between(from, to),
+                unit,
+                from,
+                to,
+            });
+        }
+
+        if let Some(caps) = self.span.captures(&query) {
+            let unit = Unit::parse(&caps[1])?;
+            let until = matches!(&caps[2], "until" | "till" | "to");
+            let date = parse_date(&caps[3], today, until)?;
+            let (from, to) = if until { (today, date) } else { (date, today) };
+            return Some(DateResult::Span {
+                count: unit.between(from, to),
+                unit,
+                from,
+                to,
+            });
+        }
+
+        if let Some(caps) = self.ago.captures(&query) {
+            let count: i64 = caps[1].parse().ok()?;
+            let unit = Unit::parse(&caps[2])?;
+            let date = unit.shift(today, -count)?;
+            return Some(DateResult::Date(date, format!("{} {} ago", count, unit.name(count))));
+        }
+
+        if let Some(caps) = self.relative.captures(&query) {
+            let count: i64 = caps[1].parse().ok()?;
+            let unit = Unit::parse(&caps[2])?;
+            let base = match &caps[4] {
+                "now" => today,
+                other => parse_date(other, today, false)?,
+            };
+            let signed = if &caps[3] == "before" { -count } else { count };
+            let date = unit.shift(base, signed)?;
+            return Some(DateResult::Date(
+                date,
+                format!("{} {} {} {}", count, unit.name(count), &caps[3], describe(base, today)),
+            ));
+        }
+
+        if let Some(caps) = self.offset.captures(&query) {
+            let base = parse_date(&caps[1], today, false)?;
+            let count: i64 = caps[3].parse().ok()?;
+            let unit = Unit::parse(&caps[4])?;
+            let (signed, direction) = if &caps[2] == "-" { (-count, "before") } else { (count, "from") };
+            let date = unit.shift(base, signed)?;
+            return Some(DateResult::Date(
+                date,
+                format!("{} {} {} {}", count, unit.name(count), direction, describe(base, today)),
+            ));
+        }
+
+        None
+    }
+}
+
+impl Default for DateCalculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// "today" for today, the date otherwise.
+fn describe(date: NaiveDate, today: NaiveDate) -> String {
+    if date == today {
+        "today".to_string()
+    } else {
+        date.format("%b %-d, %Y").to_string()
+    }
+}
+
+/// Parses "today", "tomorrow", "2024-01-01", "dec 25", "25 december 2026".
+/// Without a year, `future` picks the next occurrence instead of this year's.
+fn parse_date(s: &str, today: NaiveDate, future: bool) -> Option<NaiveDate> {
+    let s = s.trim().trim_end_matches('?');
+    match s {
+        "today" | "now" => return Some(today),
+        "tomorrow" => return today.succ_opt(),
+        "yesterday" => return today.pred_opt(),
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Some(date);
+    }
+
+    let words: Vec<&str> = s
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty())
+        .collect();
+    let month_of = |w: &str| {
+        let w = w.trim_end_matches('.');
+        (w.len() >= 3 && w.chars().all(|c| c.is_ascii_alphabetic()))
+            .then(|| MONTHS.iter().position(|m| w.starts_with(m)))
+            .flatten()
+            .map(|i| i as u32 + 1)
+    };
+    let day_of = |w: &str| {
+        w.trim_end_matches(|c: char| c.is_ascii_alphabetic())
+            .parse::<u32>()
+            .ok()
+    };
+
+    let (month, day, year) = match words.as_slice() {
+        [a, b] | [a, b, _] if month_of(a).is_some() => (month_of(a)?, day_of(b)?, words.get(2)),
+        [a, b] | [a, b, _] if month_of(b).is_some() => (month_of(b)?, day_of(a)?, words.get(2)),
+        _ => return None,
+    };
+
+    match year {
+        Some(year) => NaiveDate::from_ymd_opt(year.parse().ok()?, month, day),
+        None => {
+            let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+            if future && date < today {
+                NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+            } else {
+                Some(date)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let today = date(2026, 10, 16);
+        assert_eq!(parse_date("dec 25", today, true), Some(date(2026, 12, 25)));
+        assert_eq!(parse_date("jan 1", today, true), Some(date(2027, 1, 1)));
+        assert_eq!(parse_date("1st january 2024", today, false), Some(date(2024, 1, 1)));
+        assert_eq!(parse_date("2024-02-29", today, false), Some(date(2024, 2, 29)));
+        assert_eq!(parse_date("firefox", today, false), None);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let calc = DateCalculator::new();
+        let today = date(2026, 10, 16);
+
+        match calc.evaluate("days until dec 25", today) {
+            Some(DateResult::Span { count, .. }) => assert_eq!(count, 70),
+            other => panic!("unexpected {:?}", other),
+        }
+        match calc.evaluate("weeks between 2026-01-01 and now", today) {
+            Some(DateResult::Span { count, .. }) => assert_eq!(count, 41),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            calc.evaluate("today + 45 days", today),
+            Some(DateResult::Date(d, _)) if d == date(2026, 11, 30)
+        ));
+        assert!(matches!(
+            calc.evaluate("jan 31 2026 + 1 month", today),
+            Some(DateResult::Date(d, _)) if d == date(2026, 2, 28)
+        ));
+        assert!(matches!(
+            calc.evaluate("2 weeks ago", today),
+            Some(DateResult::Date(d, _)) if d == date(2026, 10, 2)
+        ));
+        assert_eq!(calc.evaluate("2 + 2", today), None);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let calc = DateCalculator::new();
+        let today = date(2026, 10, 16);
+        assert_eq!(calc.evaluate("today + 99999999999999999 days", today), None);
+        assert_eq!(calc.evaluate("today - 99999999999999999 weeks", today), None);
+        assert_eq!(calc.evaluate("today + 9223372036854775807 years", today), None);
+        assert_eq!(calc.evaluate("99999999999999999 days ago", today), None);
+        assert_eq!(calc.evaluate("99999999999999999999 days ago", today), None);
+        assert!(!DateCalculator::is_date_query("today + 99999999999999999 days"));
+    }
+}
@@ -0,0 +1,319 @@
+//! Block devices and their free space, with mount, unmount and eject for
+//! removable drives through udisks, which needs no root for them.
+
+use super::sysinfo::{gib, meter};
+use crate::core::config::ModeConfig;
+use crate::core::{CommandRunner, Config, Item, ItemType, SystemRunner};
+use serde_json::Value;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+
+/// Filesystems without files to browse
+const HIDDEN_FSTYPES: [&str; 3] = ["swap", "crypto_LUKS", "LVM2_member"];
+
+struct Volume {
+    path: String,
+    /// Whole disk the volume is on, powered off by Eject
+    disk: String,
+    label: Option<String>,
+    model: Option<String>,
+    size: u64,
+    fstype: String,
+    mountpoint: Option<String>,
+    used: u64,
+    available: u64,
+    removable: bool,
+    /// Loop devices, e.g. snaps
+    loop_device: bool,
+}
+
+impl Volume {
+    fn name(&self) -> &str {
+        self.label.as_deref().or(self.mountpoint.as_deref()).unwrap_or(&self.path)
+    }
+
+    fn matches(&self, filter: &str) -> bool {
+        [Some(self.path.as_str()), self.label.as_deref(), self.model.as_deref(), self.mountpoint.as_deref()]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(filter))
+    }
+}
+
+/// lsblk prints numbers and flags as strings in older versions
+fn number(value: &Value) -> u64 {
+    value.as_u64().or_else(|| value.as_str()?.parse().ok()).unwrap_or(0)
+}
+
+fn flag(value: &Value) -> bool {
+    value.as_bool().unwrap_or_else(|| value.as_str() == Some("1"))
+}
+
+fn string(value: &Value) -> Option<String> {
+    value.as_str().filter(|s| !s.is_empty()).map(str::to_string)
+}
+
+#[derive(Clone)]
+pub struct DisksManager {
+    options: ModeConfig,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl DisksManager {
+    pub fn new(config: &Config) -> Self {
+        Self::with_runner(config, SystemRunner::shared())
+    }
+
+    pub fn with_runner(config: &Config, runner: Arc<dyn CommandRunner>) -> Self {
+        Self {
+            options: config.mode("disks"),
+            runner,
+        }
+    }
+
+    /// Volumes with a filesystem, in lsblk order.
+    fn volumes(&self) -> Result<Vec<Volume>, Vec<Item>> {
+        let args = [
+            "--json",
+            "--bytes",
+            "--output",
+            "PATH,LABEL,MODEL,SIZE,TYPE,FSTYPE,MOUNTPOINT,FSUSED,FSAVAIL,RM,HOTPLUG",
+        ];
+        let stdout = match self.runner.run("lsblk", &args) {
+            Ok(output) if output.status.success() => output.stdout,
+            Ok(output) => return Err(Item::command_error_items("disks", "Can't list block devices", &output)),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Err(Item::error_items("disks", "lsblk not found", "The disks mode needs util-linux"))
+            }
+            Err(e) => return Err(Item::error_items("disks", "Failed to run lsblk", e.to_string())),
+        };
+        let tree: Value = match serde_json::from_slice(&stdout) {
+            Ok(tree) => tree,
+            Err(e) => return Err(Item::error_items("disks", "Unexpected lsblk output", e.to_string())),
+        };
+
+        fn walk(device: &Value, disk: &Value, volumes: &mut Vec<Volume>) {
+            if let Some(fstype) = string(&device["fstype"]) {
+                volumes.push(Volume {
+                    path: string(&device["path"]).unwrap_or_default(),
+                    disk: string(&disk["path"]).unwrap_or_default(),
+                    label: string(&device["label"]),
+                    model: string(&disk["model"]),
+                    size: number(&device["size"]),
+                    fstype,
+                    mountpoint: string(&device["mountpoint"]),
+                    used: number(&device["fsused"]),
+                    available: number(&device["fsavail"]),
+                    removable: flag(&disk["rm"]) || flag(&disk["hotplug"]),
+                    loop_device: device["type"] == "loop",
+                });
+            }
+            for child in device["children"].as_array().into_iter().flatten() {
+                walk(child, disk, volumes);
+            }
+        }
+
+        let mut volumes = Vec::new();
+        for disk in tree["blockdevices"].as_array().into_iter().flatten() {
+            walk(disk, disk, &mut volumes);
+        }
+        Ok(volumes)
+    }
+
+    /// Removable drives first, then the rest; `disks <text>` filters by
+    /// label, mountpoint, device or model.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let filter = query.trim().to_lowercase();
+        let mut volumes = match self.volumes() {
+            Ok(volumes) => volumes,
+            Err(items) => return items,
+        };
+        let show_hidden = self.options.show_hidden.unwrap_or(false);
+        volumes.retain(|volume| {
+            (show_hidden || (!volume.loop_device && !HIDDEN_FSTYPES.contains(&volume.fstype.as_str())))
+                && volume.matches(&filter)
+        });
+        volumes.sort_by_key(|volume| !volume.removable);
+
+        let mut items = Vec::new();
+        for volume in &volumes {
+            let description = match &volume.mountpoint {
+                Some(mountpoint) => {
+                    let total = volume.used + volume.available;
+                    let fraction = if total == 0 { 0.0 } else { volume.used as f32 / total as f32 };
+                    format!(
+                        "{} {} free of {} | {} | {}",
+                        meter(fraction),
+                        gib(volume.available),
+                        gib(total),
+                        mountpoint,
+                        volume.fstype
+                    )
+                }
+                None => format!("Not mounted | {} | {} | {}", gib(volume.size), volume.fstype, volume.path),
+            };
+            let icon = if volume.removable { "drive-removable-media" } else { "drive-harddisk" };
+            let mut item = Item::new(format!("disk:{}", volume.path), volume.name(), ItemType::Disk)
+                .with_description(description)
+                .with_icon(icon);
+            item.metadata.path = volume.mountpoint.as_ref().map(PathBuf::from);
+            item.metadata.size = Some(volume.size);
+            items.push(item);
+
+            // Actions for the best match only, like the git mode
+            if items.len() == 1 && !filter.is_empty() && volume.removable {
+                let mut actions = Vec::new();
+                if volume.mountpoint.is_some() {
+                    actions.push(("unmount", "    Unmount", volume.path.as_str(), "media-eject"));
+                } else {
+                    actions.push(("mount", "    Mount", volume.path.as_str(), "drive-removable-media"));
+                }
+                actions.push(("eject", "    Eject", volume.disk.as_str(), "media-eject"));
+                for (action, name, device, icon) in actions {
+                    let description = match action {
+                        "eject" => format!("Unmount and power off {}, then it is safe to remove", device),
+                        _ => device.to_string(),
+                    };
+                    items.push(
+                        Item::new(format!("disk:{}:{}", action, device), name, ItemType::DiskAction)
+                            .with_description(description)
+                            .with_icon(icon),
+                    );
+                }
+            }
+        }
+
+        if items.is_empty() {
+            items.push(
+                Item::new("disk:none", format!("No disks match \"{}\"", query.trim()), ItemType::Command)
+                    .with_icon("dialog-information"),
+            );
+        }
+        items
+    }
+
+    /// Stdout of `udisksctl <args>`, or why it failed.
+    fn udisksctl(&self, args: &[&str]) -> Result<String, String> {
+        match self.runner.run("udisksctl", args) {
+            Ok(output) if output.status.success() => Ok(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+            Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+            Err(e) => Err(format!("Failed to run udisksctl: {}", e)),
+        }
+    }
+
+    /// Enter: opens a mounted volume, or mounts a removable one. Returns
+    /// whether the launcher should close.
+    pub fn activate(&self, item: &Item) -> bool {
+        if let Some(mountpoint) = &item.metadata.path {
+            return match Command::new("xdg-open").arg(mountpoint).spawn() {
+                Ok(_) => true,
+                Err(e) => {
+                    log::error!("Failed to open {}: {}", mountpoint.display(), e);
+                    false
+                }
+            };
+        }
+        if let Some(device) = item.id.strip_prefix("disk:") {
+            self.execute_action(&format!("disk:mount:{}", device));
+        }
+        false
+    }
+
+    /// Mounts, unmounts or ejects, with a notification either way. The
+    /// launcher stays open to show the new state.
+    pub fn execute_action(&self, action_id: &str) {
+        let mut parts = action_id.splitn(3, ':');
+        let (summary, result) = match (parts.next(), parts.next(), parts.next()) {
+            (Some("disk"), Some("mount"), Some(device)) => {
+                ("Mounted".to_string(), self.udisksctl(&["mount", "--block-device", device]))
+            }
+            (Some("disk"), Some("unmount"), Some(device)) => {
+                (format!("Unmounted {}", device), self.udisksctl(&["unmount", "--block-device", device]))
+            }
+            (Some("disk"), Some("eject"), Some(disk)) => (format!("{} can be removed safely", disk), self.eject(disk)),
+            _ => return,
+        };
+        let (summary, body) = match result {
+            Ok(stdout) => (summary, stdout),
+            Err(e) => (format!("Can't {}", action_id.split(':').nth(1).unwrap_or_default()), e),
+        };
+        let _ = notify_rust::Notification::new().summary(&summary).body(&body).show();
+    }
+
+    /// Unmounts every mounted volume of `disk`, then powers it off.
+    fn eject(&self, disk: &str) -> Result<String, String> {
+        let volumes = self.volumes().map_err(|_| "Can't list block devices".to_string())?;
+        for volume in volumes.iter().filter(|volume| volume.disk == disk && volume.mountpoint.is_some()) {
+            self.udisksctl(&["unmount", "--block-device", &volume.path])?;
+        }
+        self.udisksctl(&["power-off", "--block-device", disk])
+    }
+}
+
+impl Default for DisksManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    fn manager() -> DisksManager {
+        let runner = FakeRunner::new()
+            .with(
+                "lsblk --json --bytes --output PATH,LABEL,MODEL,SIZE,TYPE,FSTYPE,MOUNTPOINT,FSUSED,FSAVAIL,RM,HOTPLUG",
+                include_str!("../../tests/fixtures/lsblk.json"),
+            )
+            .shared();
+        DisksManager::with_runner(&Config::default(), runner)
+    }
+
+    #[test]
+    fn test_volumes() {
+        let items = manager().get_items("");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        // Removable first; no swap or snaps
+        assert_eq!(names, ["BACKUP", "CAMERA", "/boot", "root"]);
+        assert_eq!(
+            items[0].description.as_deref(),
+            Some("▮▮▮▯▯▯▯▯▯▯ 10.7 GiB free of 14.3 GiB | /run/media/alex/BACKUP | exfat")
+        );
+        assert_eq!(items[1].description.as_deref(), Some("Not mounted | 59.5 GiB | vfat | /dev/sdb1"));
+        assert_eq!(items[1].metadata.path, None);
+        assert_eq!(items[3].icon.as_deref(), Some("drive-harddisk"));
+    }
+
+    #[test]
+    fn test_actions() {
+        let items = manager().get_items("sandisk");
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["disk:/dev/sda1", "disk:unmount:/dev/sda1", "disk:eject:/dev/sda"]);
+
+        let items = manager().get_items("camera");
+        assert_eq!(items[1].id, "disk:mount:/dev/sdb1");
+
+        // Internal drives can't be unmounted from here
+        assert_eq!(manager().get_items("root").len(), 1);
+    }
+
+    #[test]
+    fn test_eject() {
+        let runner = FakeRunner::new()
+            .with(
+                "lsblk --json --bytes --output PATH,LABEL,MODEL,SIZE,TYPE,FSTYPE,MOUNTPOINT,FSUSED,FSAVAIL,RM,HOTPLUG",
+                include_str!("../../tests/fixtures/lsblk.json"),
+            )
+            .with("udisksctl unmount --block-device /dev/sda1", "Unmounted /dev/sda1.")
+            .with("udisksctl power-off --block-device /dev/sda", "")
+            .shared();
+        let manager = DisksManager::with_runner(&Config::default(), runner);
+        assert_eq!(manager.eject("/dev/sda"), Ok(String::new()));
+        // sdb1 isn't mounted, but powering off sdb has no fixture
+        assert!(manager.eject("/dev/sdb").is_err());
+    }
+}
@@ -1,10 +1,27 @@
-use crate::core::{Item, ItemType};
+use crate::core::config::{ModeConfig, SortOrder};
+use crate::core::{CommandRunner, Config, Item, ItemType, SystemRunner};
 use std::process::Command;
+use std::sync::Arc;
 
+const COMPOSE_FORMAT: &str = "{{.Label \"com.docker.compose.project\"}}\t{{.Label \"com.docker.compose.project.working_dir\"}}\t{{.Label \"com.docker.compose.project.config_files\"}}\t{{.State}}";
+
+/// A compose project, pieced together from its containers' labels.
+struct ComposeProject {
+    name: String,
+    working_dir: String,
+    config_files: Vec<String>,
+    running: usize,
+    total: usize,
+}
+
+#[derive(Clone)]
 pub struct DockerManager {
     runtime: DockerRuntime,
+    options: ModeConfig,
+    runner: Arc<dyn CommandRunner>,
 }
 
+#[derive(Clone)]
 enum DockerRuntime {
     Docker,
     Podman,
@@ -12,17 +29,25 @@ enum DockerRuntime {
 }
 
 impl DockerManager {
-    pub fn new() -> Self {
+    pub fn new(config: &Config) -> Self {
+        Self::with_runner(config, SystemRunner::shared())
+    }
+
+    pub fn with_runner(config: &Config, runner: Arc<dyn CommandRunner>) -> Self {
         // Detect runtime
-        let runtime = if Command::new("docker").arg("--version").output().is_ok() {
+        let runtime = if runner.run("docker", &["--version"]).is_ok() {
             DockerRuntime::Docker
-        } else if Command::new("podman").arg("--version").output().is_ok() {
+        } else if runner.run("podman", &["--version"]).is_ok() {
             DockerRuntime::Podman
         } else {
             DockerRuntime::None
         };
 
-        Self { runtime }
+        Self {
+            runtime,
+            options: config.mode("docker"),
+            runner,
+        }
     }
 
     fn runtime_cmd(&self) -> Option<&str> {
@@ -34,69 +59,134 @@ impl DockerManager {
     }
 
     pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let Some(cmd) = self.runtime_cmd() else {
+            return vec![Item::new(
+                "docker:not_found",
+                "Docker/Podman not found",
+                ItemType::DockerAction,
+            )
+            .with_description("Install Docker or Podman to use this feature")
+            .with_icon("dialog-warning")];
+        };
+
+        let query = query.trim();
+        let section = |name: &str| {
+            query
+                .strip_prefix(name)
+                .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+                .map(|rest| rest.trim().to_lowercase())
+        };
+        if let Some(filter) = section("images") {
+            return self.image_items(cmd, &filter);
+        }
+        if let Some(filter) = section("volumes") {
+            return self.volume_items(cmd, &filter);
+        }
+        if let Some(filter) = section("compose") {
+            return self.compose_items(&filter);
+        }
+        self.container_items(cmd, query)
+    }
+
+    fn container_items(&self, cmd: &str, query: &str) -> Vec<Item> {
         let query_lower = query.to_lowercase();
         let mut items = Vec::new();
 
-        let Some(cmd) = self.runtime_cmd() else {
-            items.push(
-                Item::new(
-                    "docker:not_found",
-                    "Docker/Podman not found",
-                    ItemType::DockerAction,
-                )
-                .with_description("Install Docker or Podman to use this feature")
-                .with_icon("dialog-warning"),
-            );
-            return items;
+        // Get containers, including stopped ones unless hidden
+        let mut args = vec!["ps", "--format", "{{.ID}}\t{{.Names}}\t{{.Image}}\t{{.Status}}\t{{.Ports}}"];
+        if self.options.show_hidden.unwrap_or(true) {
+            args.push("-a");
+        }
+        let output = match self.runner.run(cmd, &args) {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                return Item::command_error_items("docker", format!("Can't list containers ({})", cmd), &output)
+            }
+            Err(e) => return Item::error_items("docker", format!("Failed to run {}", cmd), e.to_string()),
         };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for line in stdout.lines() {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() >= 4 {
+                let id = parts[0];
+                let name = parts[1];
+                let image = parts[2];
+                let status = parts[3];
 
-        // Get containers (all, including stopped)
-        if let Ok(output) = Command::new(cmd)
-            .args(["ps", "-a", "--format", "{{.ID}}\t{{.Names}}\t{{.Image}}\t{{.Status}}"])
-            .output()
-        {
-            if output.status.success() {
-                if let Ok(stdout) = String::from_utf8(output.stdout) {
-                    for line in stdout.lines() {
-                        let parts: Vec<&str> = line.split('\t').collect();
-                        if parts.len() >= 4 {
-                            let id = parts[0];
-                            let name = parts[1];
-                            let image = parts[2];
-                            let status = parts[3];
-
-                            let running = status.starts_with("Up");
-
-                            if query_lower.is_empty()
-                                || name.to_lowercase().contains(&query_lower)
-                                || image.to_lowercase().contains(&query_lower)
-                            {
-                                let mut item = Item::new(
-                                    format!("docker:{}", id),
-                                    name,
-                                    ItemType::DockerContainer,
-                                )
-                                .with_description(format!("{} | {}", image, status))
-                                .with_icon(if running {
-                                    "media-playback-start"
-                                } else {
-                                    "media-playback-stop"
-                                });
-
-                                item.metadata.container_id = Some(id.to_string());
-                                item.metadata.container_status = Some(status.to_string());
-                                item.metadata.image = Some(image.to_string());
-
-                                items.push(item);
-                            }
-                        }
-                    }
+                let running = status.starts_with("Up");
+
+                if query_lower.is_empty()
+                    || name.to_lowercase().contains(&query_lower)
+                    || image.to_lowercase().contains(&query_lower)
+                {
+                    let mut item = Item::new(
+                        format!("docker:{}", id),
+                        name,
+                        ItemType::DockerContainer,
+                    )
+                    .with_description(format!("{} | {}", image, status))
+                    .with_icon(if running {
+                        "media-playback-start"
+                    } else {
+                        "media-playback-stop"
+                    });
+
+                    item.metadata.container_id = Some(id.to_string());
+                    item.metadata.container_status = Some(status.to_string());
+                    item.metadata.image = Some(image.to_string());
+                    item.metadata.ports = parts.get(4).filter(|p| !p.is_empty()).map(|p| p.to_string());
+
+                    items.push(item);
                 }
             }
         }
 
+        match self.options.sort {
+            Some(SortOrder::Name) => items.sort_by_key(|item| item.name.to_lowercase()),
+            // Running containers first
+            Some(SortOrder::Status) => items.sort_by_key(|item| {
+                !item
+                    .metadata
+                    .container_status
+                    .as_deref()
+                    .is_some_and(|s| s.starts_with("Up"))
+            }),
+            _ => {}
+        }
+        if let Some(max) = self.options.max_results {
+            items.truncate(max);
+        }
+
+        // Actions for the best match only, like the git mode
+        if let Some(best) = items.first().filter(|_| !query_lower.is_empty()) {
+            let id = best.metadata.container_id.clone().unwrap_or_default();
+            let running = best.metadata.container_status.as_deref().is_some_and(|s| s.starts_with("Up"));
+            let mut actions = vec![Item::new(format!("docker:logs:{}", id), "    View logs", ItemType::DockerAction)
+                .with_description(format!("{} logs -f in a terminal", cmd))
+                .with_icon("text-x-generic")];
+            if running {
+                actions.push(
+                    Item::new(format!("docker:exec:{}", id), "    Open shell", ItemType::DockerAction)
+                        .with_description(format!("{} exec -it {} sh", cmd, best.name))
+                        .with_icon("utilities-terminal"),
+                );
+            }
+            items.splice(1..1, actions);
+        }
+
         // Add actions
         if query_lower.is_empty() {
+            for (section, name, icon) in [
+                ("images", "Images", "media-optical"),
+                ("volumes", "Volumes", "drive-harddisk"),
+                ("compose", "Compose Projects", "view-grid"),
+            ] {
+                items.push(
+                    Item::new(format!("docker:list:{}", section), name, ItemType::DockerAction)
+                        .with_description(format!("Type \"{}\" to list them", section))
+                        .with_icon(icon),
+                );
+            }
             items.push(
                 Item::new("docker:action:prune", "Prune Containers", ItemType::DockerAction)
                     .with_description("Remove stopped containers")
@@ -112,37 +202,295 @@ impl DockerManager {
         items
     }
 
+    /// Lines of a successful `cmd args…`, or the error items to show.
+    fn list(&self, cmd: &str, args: &[&str], what: &str) -> Result<String, Vec<Item>> {
+        match self.runner.run(cmd, args) {
+            Ok(output) if output.status.success() => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+            Ok(output) => Err(Item::command_error_items("docker", format!("Can't list {} ({})", what, cmd), &output)),
+            Err(e) => Err(Item::error_items("docker", format!("Failed to run {}", cmd), e.to_string())),
+        }
+    }
+
+    fn sort_and_truncate(&self, items: &mut Vec<Item>) {
+        if self.options.sort == Some(SortOrder::Name) {
+            items.sort_by_key(|item| item.name.to_lowercase());
+        }
+        if let Some(max) = self.options.max_results {
+            items.truncate(max);
+        }
+    }
+
+    fn image_items(&self, cmd: &str, filter: &str) -> Vec<Item> {
+        let stdout = match self.list(
+            cmd,
+            &["images", "--format", "{{.ID}}\t{{.Repository}}\t{{.Tag}}\t{{.Size}}\t{{.CreatedSince}}"],
+            "images",
+        ) {
+            Ok(stdout) => stdout,
+            Err(items) => return items,
+        };
+
+        let mut images = Vec::new();
+        for line in stdout.lines() {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() < 5 {
+                continue;
+            }
+            let (id, repository, tag, size, created) = (parts[0], parts[1], parts[2], parts[3], parts[4]);
+            // Dangling images have no name to show or pull
+            let reference = (repository != "<none>").then(|| format!("{}:{}", repository, tag));
+            let name = reference.clone().unwrap_or_else(|| format!("<none> ({})", id));
+            if !filter.is_empty() && !name.to_lowercase().contains(filter) {
+                continue;
+            }
+
+            let mut item = Item::new(format!("docker:image:{}", id), name, ItemType::DockerImage)
+                .with_description(format!("{} | {} | Enter runs it in a terminal", size, created))
+                .with_icon("media-optical");
+            item.metadata.container_id = Some(id.to_string());
+            item.metadata.image = reference;
+            images.push(item);
+        }
+        self.sort_and_truncate(&mut images);
+
+        let mut items = Vec::new();
+        for (i, image) in images.into_iter().enumerate() {
+            let id = image.metadata.container_id.clone().unwrap_or_default();
+            let reference = image.metadata.image.clone();
+            items.push(image);
+            // Actions for the best match only, like the git mode
+            if i == 0 && !filter.is_empty() {
+                if let Some(reference) = reference {
+                    items.push(
+                        Item::new(format!("docker:pull:{}", reference), "    Pull latest", ItemType::DockerAction)
+                            .with_description(reference)
+                            .with_icon("emblem-downloads"),
+                    );
+                }
+                items.push(
+                    Item::new(format!("docker:rmi:{}", id), "    Remove image", ItemType::DockerAction)
+                        .with_description(id)
+                        .with_icon("edit-delete"),
+                );
+            }
+        }
+        items
+    }
+
+    fn volume_items(&self, cmd: &str, filter: &str) -> Vec<Item> {
+        let stdout = match self.list(cmd, &["volume", "ls", "--format", "{{.Name}}\t{{.Driver}}"], "volumes") {
+            Ok(stdout) => stdout,
+            Err(items) => return items,
+        };
+
+        let mut volumes: Vec<Item> = stdout
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .filter(|(name, _)| filter.is_empty() || name.to_lowercase().contains(filter))
+            .map(|(name, driver)| {
+                Item::new(format!("docker:volume:{}", name), name, ItemType::DockerVolume)
+                    .with_description(format!("{} driver", driver))
+                    .with_icon("drive-harddisk")
+            })
+            .collect();
+        self.sort_and_truncate(&mut volumes);
+
+        let mut items = Vec::new();
+        for (i, mut volume) in volumes.into_iter().enumerate() {
+            if i == 0 && !filter.is_empty() {
+                let name = volume.name.clone();
+                // Inspecting is one more call, so only for the best match
+                if let Ok(json) = self.list(cmd, &["volume", "inspect", &name], "volumes") {
+                    let info: serde_json::Value = serde_json::from_str(&json).unwrap_or_default();
+                    if let Some(mountpoint) = info[0]["Mountpoint"].as_str() {
+                        volume.description = Some(format!("{} | Enter copies the inspect output", mountpoint));
+                        volume.metadata.path = Some(mountpoint.into());
+                    }
+                    volume.metadata.content = Some(json.trim().to_string());
+                }
+                items.push(volume);
+                items.push(
+                    Item::new(format!("docker:volume:rm:{}", name), "    Remove volume", ItemType::DockerAction)
+                        .with_description(name)
+                        .with_icon("edit-delete"),
+                );
+            } else {
+                items.push(volume);
+            }
+        }
+        items
+    }
+
+    fn compose_projects(&self) -> Result<Vec<ComposeProject>, Vec<Item>> {
+        let cmd = self.runtime_cmd().unwrap_or("docker");
+        let stdout = self.list(
+            cmd,
+            &["ps", "-a", "--filter", "label=com.docker.compose.project", "--format", COMPOSE_FORMAT],
+            "compose projects",
+        )?;
+
+        let mut projects: Vec<ComposeProject> = Vec::new();
+        for line in stdout.lines() {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() < 4 || parts[0].is_empty() {
+                continue;
+            }
+            let index = match projects.iter().position(|p| p.name == parts[0]) {
+                Some(index) => index,
+                None => {
+                    projects.push(ComposeProject {
+                        name: parts[0].to_string(),
+                        working_dir: parts[1].to_string(),
+                        config_files: parts[2].split(',').filter(|f| !f.is_empty()).map(str::to_string).collect(),
+                        running: 0,
+                        total: 0,
+                    });
+                    projects.len() - 1
+                }
+            };
+            projects[index].total += 1;
+            if parts[3] == "running" {
+                projects[index].running += 1;
+            }
+        }
+        Ok(projects)
+    }
+
+    fn compose_items(&self, filter: &str) -> Vec<Item> {
+        let projects = match self.compose_projects() {
+            Ok(projects) => projects,
+            Err(items) => return items,
+        };
+        let mut projects: Vec<ComposeProject> = projects
+            .into_iter()
+            .filter(|p| filter.is_empty() || p.name.to_lowercase().contains(filter))
+            .collect();
+        if projects.is_empty() {
+            return vec![Item::new("docker:compose:none", "No compose projects found", ItemType::Command)
+                .with_description("Projects are found through their containers' labels")
+                .with_icon("dialog-information")];
+        }
+        projects.sort_by_key(|p| p.name.to_lowercase());
+
+        let home = dirs::home_dir().map(|h| h.display().to_string()).unwrap_or_default();
+        let mut items = Vec::new();
+        for (i, project) in projects.iter().enumerate() {
+            let dir = match project.working_dir.strip_prefix(&home) {
+                Some(rest) if !home.is_empty() => format!("~{}", rest),
+                _ => project.working_dir.clone(),
+            };
+            let mut item = Item::new(format!("docker:compose:up:{}", project.name), &project.name, ItemType::DockerCompose)
+                .with_description(format!("{}/{} running | {}", project.running, project.total, dir))
+                .with_icon(if project.running > 0 { "media-playback-start" } else { "media-playback-stop" });
+            item.metadata.path = Some(project.working_dir.clone().into());
+            items.push(item);
+
+            if i == 0 {
+                for (action, name, icon) in [
+                    ("up", "    Up", "media-playback-start"),
+                    ("restart", "    Restart", "view-refresh"),
+                    ("down", "    Down", "media-playback-stop"),
+                ] {
+                    items.push(
+                        Item::new(format!("docker:compose:{}:{}", action, project.name), name, ItemType::DockerAction)
+                            .with_description(format!("{} compose {} ({})", self.runtime_cmd().unwrap_or("docker"), action, project.name))
+                            .with_icon(icon),
+                    );
+                }
+            }
+        }
+        items
+    }
+
+    /// Runs a slow command (pulls, compose up…) off the UI thread.
+    fn run_detached(&self, args: Vec<String>) {
+        let Some(cmd) = self.runtime_cmd() else {
+            return;
+        };
+        let cmd = cmd.to_string();
+        let runner = self.runner.clone();
+        std::thread::spawn(move || {
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            match runner.run(&cmd, &args) {
+                Ok(output) if !output.status.success() => {
+                    log::error!("{} {} failed: {}", cmd, args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+                }
+                Err(e) => log::error!("Failed to run {}: {}", cmd, e),
+                Ok(_) => {}
+            }
+        });
+    }
+
+    fn compose(&self, action: &str, project: &str) {
+        let projects = match self.compose_projects() {
+            Ok(projects) => projects,
+            Err(_) => return,
+        };
+        let Some(project) = projects.into_iter().find(|p| p.name == project) else {
+            return;
+        };
+
+        let mut args = vec!["compose".to_string(), "-p".to_string(), project.name];
+        if !project.working_dir.is_empty() {
+            args.extend(["--project-directory".to_string(), project.working_dir]);
+        }
+        for file in project.config_files {
+            args.extend(["-f".to_string(), file]);
+        }
+        args.push(action.to_string());
+        if action == "up" {
+            args.push("-d".to_string());
+        }
+        self.run_detached(args);
+    }
+
+    /// Runs the image interactively in a new terminal, removed on exit.
+    pub fn run_image(&self, item: &Item) {
+        let (Some(cmd), Some(id)) = (self.runtime_cmd(), &item.metadata.container_id) else {
+            return;
+        };
+        let image = item.metadata.image.as_deref().unwrap_or(id);
+        if let Err(e) = Command::new("x-terminal-emulator").args(["-e", cmd, "run", "--rm", "-it", image]).spawn() {
+            log::error!("Failed to open a terminal for {}: {}", image, e);
+        }
+    }
+
+    /// Runs `args` in a new terminal, e.g. to follow logs or open a shell.
+    fn open_terminal(&self, args: &[&str]) -> bool {
+        let Some(cmd) = self.runtime_cmd() else {
+            return false;
+        };
+        match Command::new("x-terminal-emulator").arg("-e").arg(cmd).args(args).spawn() {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("Failed to open a terminal for {} {}: {}", cmd, args.join(" "), e);
+                false
+            }
+        }
+    }
+
     pub fn start_container(&self, container_id: &str) {
         if let Some(cmd) = self.runtime_cmd() {
-            let _ = Command::new(cmd)
-                .args(["start", container_id])
-                .output();
+            let _ = self.runner.run(cmd, &["start", container_id]);
         }
     }
 
     pub fn stop_container(&self, container_id: &str) {
         if let Some(cmd) = self.runtime_cmd() {
-            let _ = Command::new(cmd)
-                .args(["stop", container_id])
-                .output();
+            let _ = self.runner.run(cmd, &["stop", container_id]);
         }
     }
 
     pub fn remove_container(&self, container_id: &str) {
         if let Some(cmd) = self.runtime_cmd() {
-            let _ = Command::new(cmd)
-                .args(["rm", "-f", container_id])
-                .output();
+            let _ = self.runner.run(cmd, &["rm", "-f", container_id]);
         }
     }
 
     pub fn toggle_container(&self, container_id: &str) {
         if let Some(cmd) = self.runtime_cmd() {
             // Check if running
-            if let Ok(output) = Command::new(cmd)
-                .args(["inspect", "-f", "{{.State.Running}}", container_id])
-                .output()
-            {
+            if let Ok(output) = self.runner.run(cmd, &["inspect", "-f", "{{.State.Running}}", container_id]) {
                 if output.status.success() {
                     let stdout = String::from_utf8_lossy(&output.stdout);
                     if stdout.trim() == "true" {
@@ -157,22 +505,25 @@ impl DockerManager {
 
     pub fn prune_containers(&self) {
         if let Some(cmd) = self.runtime_cmd() {
-            let _ = Command::new(cmd)
-                .args(["container", "prune", "-f"])
-                .output();
+            let _ = self.runner.run(cmd, &["container", "prune", "-f"]);
         }
     }
 
     pub fn prune_all(&self) {
         if let Some(cmd) = self.runtime_cmd() {
-            let _ = Command::new(cmd)
-                .args(["system", "prune", "-af"])
-                .output();
+            let _ = self.runner.run(cmd, &["system", "prune", "-af"]);
         }
     }
 
-    pub fn execute_action(&self, action_id: &str) {
+    /// Returns whether a terminal was opened, so the launcher can close.
+    pub fn execute_action(&self, action_id: &str) -> bool {
         match action_id {
+            id if id.starts_with("docker:logs:") => {
+                return self.open_terminal(&["logs", "-f", id.trim_start_matches("docker:logs:")]);
+            }
+            id if id.starts_with("docker:exec:") => {
+                return self.open_terminal(&["exec", "-it", id.trim_start_matches("docker:exec:"), "sh"]);
+            }
             "docker:action:prune" => self.prune_containers(),
             "docker:action:prune_all" => self.prune_all(),
             id if id.starts_with("docker:start:") => {
@@ -187,13 +538,148 @@ impl DockerManager {
                 let container_id = id.strip_prefix("docker:remove:").unwrap();
                 self.remove_container(container_id);
             }
+            id if id.starts_with("docker:pull:") => {
+                self.run_detached(vec!["pull".to_string(), id.trim_start_matches("docker:pull:").to_string()]);
+            }
+            id if id.starts_with("docker:rmi:") => {
+                if let Some(cmd) = self.runtime_cmd() {
+                    let _ = self.runner.run(cmd, &["rmi", id.trim_start_matches("docker:rmi:")]);
+                }
+            }
+            id if id.starts_with("docker:volume:rm:") => {
+                if let Some(cmd) = self.runtime_cmd() {
+                    let _ = self.runner.run(cmd, &["volume", "rm", id.trim_start_matches("docker:volume:rm:")]);
+                }
+            }
+            id if id.starts_with("docker:compose:") => {
+                if let Some((action, project)) = id.trim_start_matches("docker:compose:").split_once(':') {
+                    self.compose(action, project);
+                }
+            }
             _ => {}
         }
+        false
     }
 }
 
 impl Default for DockerManager {
     fn default() -> Self {
-        Self::new()
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    const PS: &str = "docker ps --format {{.ID}}\t{{.Names}}\t{{.Image}}\t{{.Status}}\t{{.Ports}} -a";
+
+    #[test]
+    fn test_docker_ps() {
+        let runner = FakeRunner::new()
+            .with("docker --version", "Docker version 27.3.1, build ce12230\n")
+            .with(PS, include_str!("../../tests/fixtures/docker_ps.txt"))
+            .shared();
+        let docker = DockerManager::with_runner(&Config::default(), runner);
+
+        let items = docker.get_items("");
+        let containers: Vec<&Item> = items.iter().filter(|i| i.item_type == ItemType::DockerContainer).collect();
+        assert_eq!(containers.len(), 3);
+        assert_eq!(containers[0].name, "web");
+        assert_eq!(containers[0].metadata.container_id.as_deref(), Some("2f1c7a9e3b44"));
+        assert_eq!(containers[1].metadata.image.as_deref(), Some("postgres:16"));
+        assert_eq!(containers[2].description.as_deref(), Some("redis:7 | Up 3 hours (healthy)"));
+        assert_eq!(containers[0].metadata.ports.as_deref(), Some("0.0.0.0:8080->80/tcp, [::]:8080->80/tcp"));
+        assert_eq!(containers[1].metadata.ports, None);
+
+        // A stopped container has logs but no shell
+        let items = docker.get_items("postgres");
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["docker:8d0e5b12aa07", "docker:logs:8d0e5b12aa07"]);
+        assert_eq!(items[0].name, "db");
+
+        let items = docker.get_items("web");
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["docker:2f1c7a9e3b44", "docker:logs:2f1c7a9e3b44", "docker:exec:2f1c7a9e3b44"]);
+    }
+
+    #[test]
+    fn test_container_actions() {
+        // A line without the Ports column
+        let runner = FakeRunner::new()
+            .with("docker --version", "Docker version 27.3.1, build ce12230\n")
+            .with(PS, "2f1c7a9e3b44\tweb\tnginx:1.25\tUp 3 hours\n")
+            .shared();
+        let docker = DockerManager::with_runner(&Config::default(), runner);
+
+        let items = docker.get_items("web");
+        assert_eq!(items[0].metadata.ports, None);
+        let actions: Vec<(&str, Option<&str>)> = items[1..]
+            .iter()
+            .map(|item| (item.name.as_str(), item.description.as_deref()))
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                ("    View logs", Some("docker logs -f in a terminal")),
+                ("    Open shell", Some("docker exec -it web sh")),
+            ]
+        );
+        // Without a query every container is listed, with no actions
+        assert!(docker.get_items("").iter().all(|item| !item.id.starts_with("docker:logs:")));
+    }
+
+    #[test]
+    fn test_images_volumes_and_compose() {
+        let runner = FakeRunner::new()
+            .with("docker --version", "Docker version 27.3.1, build ce12230\n")
+            .with(
+                "docker images --format {{.ID}}\t{{.Repository}}\t{{.Tag}}\t{{.Size}}\t{{.CreatedSince}}",
+                include_str!("../../tests/fixtures/docker_images.txt"),
+            )
+            .with("docker volume ls --format {{.Name}}\t{{.Driver}}", include_str!("../../tests/fixtures/docker_volume_ls.txt"))
+            .with("docker volume inspect shop_pgdata", include_str!("../../tests/fixtures/docker_volume_inspect.json"))
+            .with(
+                &format!("docker ps -a --filter label=com.docker.compose.project --format {}", COMPOSE_FORMAT),
+                include_str!("../../tests/fixtures/docker_compose_ps.txt"),
+            )
+            .shared();
+        let docker = DockerManager::with_runner(&Config::default(), runner);
+
+        let items = docker.get_items("images");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["nginx:1.25", "postgres:16", "<none> (5d1a0c7e9b3f)"]);
+        let items = docker.get_items("images post");
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["docker:image:b2c4d6e8f0a1", "docker:pull:postgres:16", "docker:rmi:b2c4d6e8f0a1"]);
+
+        let items = docker.get_items("volumes pg");
+        assert_eq!(items[0].name, "shop_pgdata");
+        assert_eq!(
+            items[0].metadata.path.as_deref(),
+            Some(std::path::Path::new("/var/lib/docker/volumes/shop_pgdata/_data"))
+        );
+        assert_eq!(items[1].id, "docker:volume:rm:shop_pgdata");
+
+        let items = docker.get_items("compose");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["blog", "    Up", "    Restart", "    Down", "shop"]);
+        assert!(items[0].description.as_deref().unwrap().starts_with("0/1 running | "));
+        assert!(items[4].description.as_deref().unwrap().starts_with("2/3 running | "));
+    }
+
+    #[test]
+    fn test_docker_ps_failure() {
+        let runner = FakeRunner::new()
+            .with("docker --version", "Docker version 27.3.1, build ce12230\n")
+            .failing(PS, 1, "permission denied while trying to connect to the Docker daemon socket\n")
+            .shared();
+        let items = DockerManager::with_runner(&Config::default(), runner).get_items("");
+        assert_eq!(items[0].item_type, ItemType::Error);
+        assert_eq!(
+            items[0].metadata.error.as_deref(),
+            Some("permission denied while trying to connect to the Docker daemon socket")
+        );
     }
 }
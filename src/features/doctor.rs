@@ -0,0 +1,368 @@
+//! Health check for the optional external tools wlaunch shells out to,
+//! shown by the `doctor` mode and printed by `wlaunch doctor`.
+
+use super::Insights;
+use crate::core::{Config, Item, ItemType};
+
+/// A feature and the commands that provide it; any one of them is enough.
+pub struct Check {
+    pub feature: &'static str,
+    pub commands: &'static [&'static str],
+    /// Package to install, as named by most distributions
+    pub package: &'static str,
+    /// What stops working without it
+    pub impact: &'static str,
+}
+
+const CHECKS: &[Check] = &[
+    Check {
+        feature: "WiFi",
+        commands: &["nmcli"],
+        package: "networkmanager",
+        impact: "wifi mode can't list or connect to networks",
+    },
+    Check {
+        feature: "Bluetooth",
+        commands: &["bluetoothctl"],
+        package: "bluez",
+        impact: "bt mode can't list or pair devices",
+    },
+    Check {
+        feature: "Audio",
+        commands: &["wpctl", "pactl"],
+        package: "wireplumber (or pulseaudio-utils)",
+        impact: "vol mode can't list outputs or change the volume",
+    },
+    Check {
+        feature: "Backlight",
+        commands: &["brightnessctl"],
+        package: "brightnessctl",
+        impact: "bri mode can't change the laptop backlight",
+    },
+    Check {
+        feature: "External monitors",
+        commands: &["ddcutil"],
+        package: "ddcutil",
+        impact: "bri mode can't change external monitor brightness",
+    },
+    Check {
+        feature: "Night light",
+        commands: &["gammastep"],
+        package: "gammastep",
+        impact: "night mode only switches themes",
+    },
+    Check {
+        feature: "Window switching",
+        commands: &["hyprctl", "i3-msg", "wmctrl"],
+        package: "wmctrl (X11; Hyprland and Sway ship their own)",
+        impact: "w mode can't list or focus windows",
+    },
+    Check {
+        feature: "Containers",
+        commands: &["docker", "podman"],
+        package: "docker (or podman)",
+        impact: "docker mode is empty",
+    },
+    Check {
+        feature: "Virtual machines",
+        commands: &["virsh", "VBoxManage"],
+        package: "libvirt (or virtualbox)",
+        impact: "vm mode is empty",
+    },
+    Check {
+        feature: "VM consoles",
+        commands: &["virt-viewer"],
+        package: "virt-viewer",
+        impact: "vm mode can't open the console of libvirt VMs",
+    },
+    Check {
+        feature: "Flatpak",
+        commands: &["flatpak"],
+        package: "flatpak",
+        impact: "fp mode is empty",
+    },
+    Check {
+        feature: "Removable drives",
+        commands: &["udisksctl"],
+        package: "udisks2",
+        impact: "disk mode can't mount or eject removable drives",
+    },
+    Check {
+        feature: "Battery details",
+        commands: &["upower"],
+        package: "upower",
+        impact: "battery mode shows only the charge, without health, time left or Bluetooth devices",
+    },
+    Check {
+        feature: "Power profiles",
+        commands: &["powerprofilesctl"],
+        package: "power-profiles-daemon",
+        impact: "battery mode can't switch power profiles",
+    },
+    Check {
+        feature: "Finding network shares",
+        commands: &["avahi-browse"],
+        package: "avahi",
+        impact: "share mode doesn't list servers on the LAN",
+    },
+    Check {
+        feature: "Listing SMB shares",
+        commands: &["smbclient"],
+        package: "smbclient",
+        impact: "share //host can't list a server's shares",
+    },
+    Check {
+        feature: "Mounting network shares",
+        commands: &["gio"],
+        package: "glib2 and gvfs-smb (or gvfs-nfs)",
+        impact: "share mode can't mount or open shares",
+    },
+    Check {
+        feature: "Fan speeds",
+        commands: &["sensors"],
+        package: "lm_sensors",
+        impact: "sys temps shows temperatures only",
+    },
+    Check {
+        feature: "Bitwarden",
+        commands: &["bw"],
+        package: "bitwarden-cli",
+        impact: "bw mode can't unlock or search the vault",
+    },
+    Check {
+        feature: "GPG",
+        commands: &["gpg"],
+        package: "gnupg",
+        impact: "gpg mode can't list keys, encrypt, decrypt or sign",
+    },
+    Check {
+        feature: "Keyring",
+        commands: &["secret-tool"],
+        package: "libsecret",
+        impact: "2fa mode can't keep secrets and share logins aren't remembered",
+    },
+    Check {
+        feature: "Focus sessions",
+        commands: &["pkexec"],
+        package: "polkit",
+        impact: "focus mode can't change /etc/hosts to block sites",
+    },
+    Check {
+        feature: "Media previews",
+        commands: &["ffprobe"],
+        package: "ffmpeg",
+        impact: "the details panel doesn't show how long audio and video files run",
+    },
+    Check {
+        feature: "PDF previews",
+        commands: &["pdfinfo"],
+        package: "poppler-utils",
+        impact: "the details panel doesn't show how many pages a PDF has",
+    },
+    Check {
+        feature: "Frecent folders",
+        commands: &["zoxide"],
+        package: "zoxide",
+        impact: "z mode can't list the folders you visit most",
+    },
+    Check {
+        feature: "Keep awake",
+        commands: &["systemd-inhibit"],
+        package: "systemd",
+        impact: "awake mode can't keep the screen from locking or the system from sleeping",
+    },
+    Check {
+        feature: "Terminal apps and SSH",
+        commands: &["x-terminal-emulator"],
+        package: "a terminal providing x-terminal-emulator",
+        impact: "terminal applications and ssh connections don't open",
+    },
+    Check {
+        feature: "Opening files and URLs",
+        commands: &["xdg-open"],
+        package: "xdg-utils",
+        impact: "files, folders and web searches don't open",
+    },
+    Check {
+        feature: "Wine",
+        commands: &["wine"],
+        package: "wine",
+        impact: "wine mode can't run winecfg or stop prefixes",
+    },
+    Check {
+        feature: "File contents",
+        commands: &["rg"],
+        package: "ripgrep",
+        impact: "grep mode can't search inside files",
+    },
+    Check {
+        feature: "Copying files",
+        commands: &["wl-copy", "xclip"],
+        package: "wl-clipboard (or xclip on X11)",
+        impact: "Copy file in the Alt+Enter panel doesn't work",
+    },
+    Check {
+        feature: "Trash",
+        commands: &["gio"],
+        package: "glib2",
+        impact: "Move to trash in the Alt+Enter panel doesn't work",
+    },
+    Check {
+        feature: "Dragging files",
+        commands: &["ripdrag", "dragon-drop", "dragon"],
+        package: "ripdrag (or dragon-drop)",
+        impact: "Ctrl+D can't drag files into other apps",
+    },
+    Check {
+        feature: "AppImage names and icons",
+        commands: &["unsquashfs"],
+        package: "squashfs-tools",
+        impact: "AppImages are listed by file name with a generic icon",
+    },
+    Check {
+        feature: "Screenshots",
+        commands: &["grim", "maim", "scrot"],
+        package: "grim and slurp (or maim on X11)",
+        impact: "shot mode can't take screenshots",
+    },
+    Check {
+        feature: "Capturing text",
+        commands: &["tesseract"],
+        package: "tesseract (and a language pack such as tesseract-data-eng)",
+        impact: "Capture text in shot mode can't read text from the screen",
+    },
+    Check {
+        feature: "Screen recording",
+        commands: &["wf-recorder"],
+        package: "wf-recorder",
+        impact: "shot mode can't record the screen (Wayland only)",
+    },
+    Check {
+        feature: "Spoken selection",
+        commands: &["spd-say"],
+        package: "speech-dispatcher",
+        impact: "accessibility.announce is silent",
+    },
+];
+
+pub struct CheckResult {
+    pub check: &'static Check,
+    /// The first command that was found
+    pub found: Option<&'static str>,
+}
+
+pub struct Doctor;
+
+impl Doctor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn run(&self) -> Vec<CheckResult> {
+        CHECKS
+            .iter()
+            .map(|check| CheckResult {
+                check,
+                found: check.commands.iter().copied().find(|cmd| which::which(cmd).is_ok()),
+            })
+            .collect()
+    }
+
+    /// Problems with the config file itself, if any.
+    pub fn config_error(&self) -> Option<String> {
+        Config::load().err().map(|e| format!("{} is invalid: {}", Config::config_path().display(), e))
+    }
+
+    /// Missing dependencies first, after features that failed today or are
+    /// slow when insights are on, then the ones that are fine.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.to_lowercase();
+        let mut items = Vec::new();
+
+        if let Some(error) = self.config_error() {
+            items.push(
+                Item::new("doctor:config", "Config file can't be read", ItemType::HealthCheck)
+                    .with_description(format!("{} | defaults are used instead", error))
+                    .with_icon("dialog-error"),
+            );
+        }
+
+        if Config::load().is_ok_and(|config| config.insights()) {
+            items.extend(
+                Insights::new()
+                    .problems()
+                    .into_iter()
+                    .filter(|item| item.name.to_lowercase().contains(&query)),
+            );
+        }
+
+        let mut results = self.run();
+        results.sort_by_key(|r| r.found.is_some());
+        for result in results {
+            let check = result.check;
+            if !query.is_empty() && !check.feature.to_lowercase().contains(&query) {
+                continue;
+            }
+            let mut item = match result.found {
+                Some(cmd) => Item::new(
+                    format!("doctor:{}", check.feature),
+                    format!("OK  {}", check.feature),
+                    ItemType::HealthCheck,
+                )
+                .with_description(format!("using {}", cmd))
+                .with_icon("emblem-ok"),
+                None => Item::new(
+                    format!("doctor:{}", check.feature),
+                    format!("Missing  {}", check.feature),
+                    ItemType::HealthCheck,
+                )
+                .with_description(format!(
+                    "{} | install {} | Enter copies the package name",
+                    check.impact, check.package
+                ))
+                .with_icon("dialog-warning"),
+            };
+            item.metadata.content = Some(check.package.to_string());
+            items.push(item);
+        }
+
+        items
+    }
+
+    /// Plain text report for `wlaunch doctor`.
+    pub fn report(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(error) = self.config_error() {
+            lines.push(format!("[!!] Config: {}", error));
+        }
+        let mut missing = 0;
+        for result in self.run() {
+            let check = result.check;
+            match result.found {
+                Some(cmd) => lines.push(format!("[ok] {} ({})", check.feature, cmd)),
+                None => {
+                    missing += 1;
+                    lines.push(format!(
+                        "[--] {}: {} not found, {}\n     install {}",
+                        check.feature,
+                        check.commands.join(" / "),
+                        check.impact,
+                        check.package
+                    ));
+                }
+            }
+        }
+        lines.push(String::new());
+        lines.push(match missing {
+            0 => "All optional dependencies found.".to_string(),
+            n => format!("{} feature(s) degraded.", n),
+        });
+        lines.join("\n")
+    }
+}
+
+impl Default for Doctor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,233 +1,159 @@
-use crate::core::{Item, ItemType};
+//! Every emoji from the `emojis` crate, searchable by name, GitHub shortcode
+//! and the CLDR keywords in `assets/emoji/annotations-en.xml`.
+
+use crate::core::{Config, Item, ItemType};
+use anyhow::Result;
+use emojis::{Emoji, SkinTone};
+use std::fs;
+
+// KEYWORDS: CLDR keywords by emoji (without variation selectors), generated by build.rs
+include!(concat!(env!("OUT_DIR"), "/emoji_keywords.rs"));
+
+/// Skin tones offered for the selected emoji, lightest first.
+pub const SKIN_TONES: [SkinTone; 5] = [
+    SkinTone::Light,
+    SkinTone::MediumLight,
+    SkinTone::Medium,
+    SkinTone::MediumDark,
+    SkinTone::Dark,
+];
+
+const MAX_RECENT: usize = 32;
+
+struct Entry {
+    emoji: &'static Emoji,
+    /// Name without punctuation, "flag: India" -> "flag india"
+    name: String,
+    keywords: Vec<&'static str>,
+}
+
+impl Entry {
+    /// Lower is better; None if some query word doesn't match at all.
+    fn rank(&self, query: &str, words: &[&str]) -> Option<u8> {
+        let matches = |word: &str| {
+            self.name.contains(word) || self.keywords.iter().any(|k| k.contains(word))
+        };
+        if !words.iter().all(|w| matches(w)) {
+            return None;
+        }
+        Some(if self.name == query {
+            0
+        } else if self.name.starts_with(query) {
+            1
+        } else if self.name.split(' ').any(|w| w.starts_with(query)) {
+            2
+        } else if self.keywords.contains(&query) {
+            3
+        } else {
+            4
+        })
+    }
+}
 
 pub struct EmojiManager {
-    emojis: Vec<(&'static str, &'static str, Vec<&'static str>)>,
+    emojis: Vec<Entry>,
+    /// Most recently used first, with the skin tone they were used with
+    recent: Vec<String>,
 }
 
 impl EmojiManager {
     pub fn new() -> Self {
+        let emojis = emojis::iter()
+            .map(|emoji| {
+                let mut keywords = Self::cldr_keywords(emoji.as_str()).to_vec();
+                keywords.extend(emoji.shortcodes());
+                Entry {
+                    emoji,
+                    name: normalize(emoji.name()),
+                    keywords,
+                }
+            })
+            .collect();
+
         Self {
-            emojis: vec![
-                // Smileys
-                ("😀", "grinning face", vec!["smile", "happy", "grin"]),
-                ("😃", "grinning face with big eyes", vec!["smile", "happy"]),
-                ("😄", "grinning face with smiling eyes", vec!["smile", "happy"]),
-                ("😁", "beaming face with smiling eyes", vec!["smile", "happy", "grin"]),
-                ("😆", "grinning squinting face", vec!["laugh", "happy"]),
-                ("😅", "grinning face with sweat", vec!["sweat", "nervous"]),
-                ("🤣", "rolling on the floor laughing", vec!["rofl", "laugh", "lol"]),
-                ("😂", "face with tears of joy", vec!["laugh", "cry", "lol", "joy"]),
-                ("🙂", "slightly smiling face", vec!["smile"]),
-                ("🙃", "upside-down face", vec!["silly", "sarcasm"]),
-                ("😉", "winking face", vec!["wink"]),
-                ("😊", "smiling face with smiling eyes", vec!["blush", "smile"]),
-                ("😇", "smiling face with halo", vec!["angel", "innocent"]),
-                ("🥰", "smiling face with hearts", vec!["love", "hearts"]),
-                ("😍", "smiling face with heart-eyes", vec!["love", "heart", "eyes"]),
-                ("🤩", "star-struck", vec!["star", "eyes", "wow"]),
-                ("😘", "face blowing a kiss", vec!["kiss", "love"]),
-                ("😗", "kissing face", vec!["kiss"]),
-                ("😚", "kissing face with closed eyes", vec!["kiss"]),
-                ("😙", "kissing face with smiling eyes", vec!["kiss"]),
-                ("🥲", "smiling face with tear", vec!["happy", "sad", "tear"]),
-                ("😋", "face savoring food", vec!["yum", "delicious"]),
-                ("😛", "face with tongue", vec!["tongue"]),
-                ("😜", "winking face with tongue", vec!["tongue", "wink", "silly"]),
-                ("🤪", "zany face", vec!["crazy", "silly", "wild"]),
-                ("😝", "squinting face with tongue", vec!["tongue"]),
-                ("🤑", "money-mouth face", vec!["money", "rich"]),
-                ("🤗", "hugging face", vec!["hug"]),
-                ("🤭", "face with hand over mouth", vec!["oops", "shy"]),
-                ("🤫", "shushing face", vec!["quiet", "shh", "secret"]),
-                ("🤔", "thinking face", vec!["think", "hmm"]),
-                ("🤐", "zipper-mouth face", vec!["quiet", "zip"]),
-                ("🤨", "face with raised eyebrow", vec!["skeptical", "doubt"]),
-                ("😐", "neutral face", vec!["neutral", "meh"]),
-                ("😑", "expressionless face", vec!["blank", "meh"]),
-                ("😶", "face without mouth", vec!["silent", "speechless"]),
-                ("😏", "smirking face", vec!["smirk"]),
-                ("😒", "unamused face", vec!["unamused", "meh"]),
-                ("🙄", "face with rolling eyes", vec!["eyeroll", "whatever"]),
-                ("😬", "grimacing face", vec!["grimace", "awkward"]),
-                ("🤥", "lying face", vec!["lie", "pinocchio"]),
-                ("😌", "relieved face", vec!["relieved", "peaceful"]),
-                ("😔", "pensive face", vec!["sad", "pensive"]),
-                ("😪", "sleepy face", vec!["sleepy", "tired"]),
-                ("🤤", "drooling face", vec!["drool", "yum"]),
-                ("😴", "sleeping face", vec!["sleep", "zzz"]),
-                ("😷", "face with medical mask", vec!["mask", "sick"]),
-                ("🤒", "face with thermometer", vec!["sick", "fever"]),
-                ("🤕", "face with head-bandage", vec!["hurt", "injured"]),
-                ("🤢", "nauseated face", vec!["sick", "green"]),
-                ("🤮", "face vomiting", vec!["sick", "vomit"]),
-                ("🤧", "sneezing face", vec!["sneeze", "sick"]),
-                ("🥵", "hot face", vec!["hot", "heat"]),
-                ("🥶", "cold face", vec!["cold", "freeze"]),
-                ("🥴", "woozy face", vec!["drunk", "dizzy"]),
-                ("😵", "dizzy face", vec!["dizzy"]),
-                ("🤯", "exploding head", vec!["mind blown", "shocked"]),
-                ("🤠", "cowboy hat face", vec!["cowboy"]),
-                ("🥳", "partying face", vec!["party", "celebrate"]),
-                ("🥸", "disguised face", vec!["disguise", "incognito"]),
-                ("😎", "smiling face with sunglasses", vec!["cool", "sunglasses"]),
-                ("🤓", "nerd face", vec!["nerd", "geek"]),
-                ("🧐", "face with monocle", vec!["monocle", "fancy"]),
-                ("😕", "confused face", vec!["confused"]),
-                ("😟", "worried face", vec!["worried"]),
-                ("🙁", "slightly frowning face", vec!["frown", "sad"]),
-                ("☹️", "frowning face", vec!["frown", "sad"]),
-                ("😮", "face with open mouth", vec!["surprised", "wow"]),
-                ("😯", "hushed face", vec!["surprised", "hushed"]),
-                ("😲", "astonished face", vec!["astonished", "shocked"]),
-                ("😳", "flushed face", vec!["blush", "embarrassed"]),
-                ("🥺", "pleading face", vec!["puppy", "please", "beg"]),
-                ("😦", "frowning face with open mouth", vec!["frown"]),
-                ("😧", "anguished face", vec!["anguished"]),
-                ("😨", "fearful face", vec!["fear", "scared"]),
-                ("😰", "anxious face with sweat", vec!["anxious", "nervous"]),
-                ("😥", "sad but relieved face", vec!["sad", "relieved"]),
-                ("😢", "crying face", vec!["cry", "sad"]),
-                ("😭", "loudly crying face", vec!["cry", "sob", "sad"]),
-                ("😱", "face screaming in fear", vec!["scream", "fear", "omg"]),
-                ("😖", "confounded face", vec!["confounded"]),
-                ("😣", "persevering face", vec!["persevere"]),
-                ("😞", "disappointed face", vec!["disappointed", "sad"]),
-                ("😓", "downcast face with sweat", vec!["sweat"]),
-                ("😩", "weary face", vec!["weary", "tired"]),
-                ("😫", "tired face", vec!["tired"]),
-                ("🥱", "yawning face", vec!["yawn", "tired", "bored"]),
-                ("😤", "face with steam from nose", vec!["angry", "frustrated"]),
-                ("😡", "pouting face", vec!["angry", "mad"]),
-                ("😠", "angry face", vec!["angry", "mad"]),
-                ("🤬", "face with symbols on mouth", vec!["swear", "angry", "curse"]),
-                ("😈", "smiling face with horns", vec!["devil", "evil"]),
-                ("👿", "angry face with horns", vec!["devil", "angry"]),
-                ("💀", "skull", vec!["death", "dead"]),
-                ("☠️", "skull and crossbones", vec!["death", "danger"]),
-                ("💩", "pile of poo", vec!["poop", "crap"]),
-                ("🤡", "clown face", vec!["clown"]),
-                ("👹", "ogre", vec!["monster", "ogre"]),
-                ("👺", "goblin", vec!["monster", "goblin"]),
-                ("👻", "ghost", vec!["ghost", "boo"]),
-                ("👽", "alien", vec!["alien", "ufo"]),
-                ("👾", "alien monster", vec!["alien", "game"]),
-                ("🤖", "robot", vec!["robot", "bot"]),
-
-                // Gestures
-                ("👋", "waving hand", vec!["wave", "hello", "bye"]),
-                ("🤚", "raised back of hand", vec!["hand"]),
-                ("🖐️", "hand with fingers splayed", vec!["hand", "five"]),
-                ("✋", "raised hand", vec!["stop", "hand", "high five"]),
-                ("🖖", "vulcan salute", vec!["spock", "vulcan"]),
-                ("👌", "OK hand", vec!["ok", "perfect"]),
-                ("🤌", "pinched fingers", vec!["italian", "chef"]),
-                ("🤏", "pinching hand", vec!["small", "tiny"]),
-                ("✌️", "victory hand", vec!["peace", "victory"]),
-                ("🤞", "crossed fingers", vec!["luck", "hope"]),
-                ("🤟", "love-you gesture", vec!["love", "rock"]),
-                ("🤘", "sign of the horns", vec!["rock", "metal"]),
-                ("🤙", "call me hand", vec!["call", "shaka"]),
-                ("👈", "backhand index pointing left", vec!["left", "point"]),
-                ("👉", "backhand index pointing right", vec!["right", "point"]),
-                ("👆", "backhand index pointing up", vec!["up", "point"]),
-                ("🖕", "middle finger", vec!["finger", "rude"]),
-                ("👇", "backhand index pointing down", vec!["down", "point"]),
-                ("☝️", "index pointing up", vec!["up", "point"]),
-                ("👍", "thumbs up", vec!["like", "yes", "good"]),
-                ("👎", "thumbs down", vec!["dislike", "no", "bad"]),
-                ("✊", "raised fist", vec!["fist", "power"]),
-                ("👊", "oncoming fist", vec!["punch", "fist"]),
-                ("🤛", "left-facing fist", vec!["fist"]),
-                ("🤜", "right-facing fist", vec!["fist"]),
-                ("👏", "clapping hands", vec!["clap", "applause"]),
-                ("🙌", "raising hands", vec!["celebrate", "hooray"]),
-                ("👐", "open hands", vec!["hands"]),
-                ("🤲", "palms up together", vec!["hands"]),
-                ("🤝", "handshake", vec!["deal", "agreement"]),
-                ("🙏", "folded hands", vec!["pray", "please", "thanks"]),
-                ("✍️", "writing hand", vec!["write"]),
-                ("💪", "flexed biceps", vec!["muscle", "strong"]),
-
-                // Hearts
-                ("❤️", "red heart", vec!["love", "heart"]),
-                ("🧡", "orange heart", vec!["heart"]),
-                ("💛", "yellow heart", vec!["heart"]),
-                ("💚", "green heart", vec!["heart"]),
-                ("💙", "blue heart", vec!["heart"]),
-                ("💜", "purple heart", vec!["heart"]),
-                ("🖤", "black heart", vec!["heart"]),
-                ("🤍", "white heart", vec!["heart"]),
-                ("🤎", "brown heart", vec!["heart"]),
-                ("💔", "broken heart", vec!["heartbreak", "sad"]),
-                ("💕", "two hearts", vec!["love", "hearts"]),
-                ("💞", "revolving hearts", vec!["love", "hearts"]),
-                ("💓", "beating heart", vec!["love", "heart"]),
-                ("💗", "growing heart", vec!["love", "heart"]),
-                ("💖", "sparkling heart", vec!["love", "heart"]),
-                ("💘", "heart with arrow", vec!["love", "cupid"]),
-                ("💝", "heart with ribbon", vec!["love", "gift"]),
-
-                // Objects & Symbols
-                ("🔥", "fire", vec!["hot", "lit", "flame"]),
-                ("✨", "sparkles", vec!["magic", "shine"]),
-                ("⭐", "star", vec!["star"]),
-                ("🌟", "glowing star", vec!["star", "shine"]),
-                ("💫", "dizzy", vec!["star", "dizzy"]),
-                ("💯", "hundred points", vec!["100", "perfect"]),
-                ("💢", "anger symbol", vec!["angry"]),
-                ("💥", "collision", vec!["boom", "explosion"]),
-                ("💦", "sweat droplets", vec!["water", "sweat"]),
-                ("💨", "dashing away", vec!["fast", "wind"]),
-                ("🕳️", "hole", vec!["hole"]),
-                ("💣", "bomb", vec!["bomb"]),
-                ("💬", "speech balloon", vec!["chat", "talk"]),
-                ("👁️‍🗨️", "eye in speech bubble", vec!["witness"]),
-                ("🗨️", "left speech bubble", vec!["talk"]),
-                ("🗯️", "right anger bubble", vec!["angry"]),
-                ("💭", "thought balloon", vec!["think"]),
-                ("💤", "zzz", vec!["sleep", "tired"]),
-
-                // Common symbols
-                ("✅", "check mark button", vec!["check", "done", "yes"]),
-                ("❌", "cross mark", vec!["no", "wrong", "x"]),
-                ("❓", "question mark", vec!["question", "what"]),
-                ("❗", "exclamation mark", vec!["exclamation", "important"]),
-                ("⚠️", "warning", vec!["warning", "caution"]),
-                ("🚫", "prohibited", vec!["no", "forbidden"]),
-                ("⛔", "no entry", vec!["stop", "no"]),
-                ("🔴", "red circle", vec!["red", "circle"]),
-                ("🟠", "orange circle", vec!["orange", "circle"]),
-                ("🟡", "yellow circle", vec!["yellow", "circle"]),
-                ("🟢", "green circle", vec!["green", "circle"]),
-                ("🔵", "blue circle", vec!["blue", "circle"]),
-                ("🟣", "purple circle", vec!["purple", "circle"]),
-                ("⚫", "black circle", vec!["black", "circle"]),
-                ("⚪", "white circle", vec!["white", "circle"]),
-            ],
+            emojis,
+            recent: Self::load_recent().unwrap_or_default(),
         }
     }
 
+    fn cldr_keywords(emoji: &str) -> &'static [&'static str] {
+        let key = emoji.replace('\u{FE0F}', "");
+        KEYWORDS
+            .binary_search_by(|(cp, _)| (*cp).cmp(key.as_str()))
+            .map_or(&[], |i| KEYWORDS[i].1)
+    }
+
+    fn recent_path() -> std::path::PathBuf {
+        Config::data_path("emoji_recent.json")
+    }
+
+    fn load_recent() -> Result<Vec<String>> {
+        let content = fs::read_to_string(Self::recent_path())?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn save_recent(&self) -> Result<()> {
+        let path = Self::recent_path();
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, serde_json::to_string(&self.recent)?)?;
+        Ok(())
+    }
+
+    /// Recently used emojis come first, in the tone they were last used with,
+    /// then the best name matches.
     pub fn get_items(&self, query: &str) -> Vec<Item> {
-        let query_lower = query.to_lowercase();
+        let query = normalize(query);
+        let words: Vec<&str> = query.split(' ').filter(|w| !w.is_empty()).collect();
 
-        self.emojis
+        let mut items: Vec<(usize, u8, Item)> = self
+            .emojis
             .iter()
-            .filter(|(_, name, keywords)| {
-                if query_lower.is_empty() {
-                    return true;
-                }
-                name.contains(&query_lower)
-                    || keywords.iter().any(|k| k.contains(&query_lower))
-            })
-            .map(|(emoji, name, _)| {
-                Item::new(
-                    format!("emoji:{}", emoji),
-                    *emoji,
-                    ItemType::Emoji,
-                )
-                .with_description(name.to_string())
+            .filter_map(|entry| {
+                let rank = if words.is_empty() { 0 } else { entry.rank(&query, &words)? };
+                let emoji = entry.emoji.as_str();
+                let used = self.recent.iter().position(|r| Self::base(r) == emoji);
+                let glyph = used.map_or(emoji, |i| self.recent[i].as_str());
+                let item = Item::new(format!("emoji:{}", emoji), glyph, ItemType::Emoji)
+                    .with_description(entry.emoji.name().to_string());
+                Some((used.unwrap_or(usize::MAX), rank, item))
             })
-            .collect()
+            .collect();
+
+        // Stable, so equal ranks keep the Unicode order
+        items.sort_by_key(|(recent, rank, _)| (*recent, *rank));
+        items.into_iter().map(|(_, _, item)| item).collect()
+    }
+
+    /// Moves `emoji` to the front of the recently used list.
+    pub fn record_use(&mut self, emoji: &str) {
+        let base = Self::base(emoji);
+        self.recent.retain(|r| Self::base(r) != base);
+        self.recent.insert(0, emoji.to_string());
+        self.recent.truncate(MAX_RECENT);
+        let _ = self.save_recent();
+    }
+
+    pub fn supports_tones(emoji: &str) -> bool {
+        emojis::get(emoji).is_some_and(|e| e.skin_tone().is_some())
+    }
+
+    /// `emoji` in the given tone (an index into `SKIN_TONES`), or without one
+    /// for `None`.
+    pub fn with_tone(emoji: &str, tone: Option<usize>) -> String {
+        let tone = match tone {
+            Some(i) => SKIN_TONES.get(i).copied().unwrap_or(SkinTone::Default),
+            None => SkinTone::Default,
+        };
+        emojis::get(emoji)
+            .and_then(|e| e.with_skin_tone(tone))
+            .map_or_else(|| emoji.to_string(), |e| e.as_str().to_string())
+    }
+
+    /// `emoji` in its default skin tone.
+    fn base(emoji: &str) -> &str {
+        emojis::get(emoji)
+            .and_then(|e| e.with_skin_tone(SkinTone::Default))
+            .map_or(emoji, Emoji::as_str)
     }
 }
 
@@ -236,3 +162,46 @@ impl Default for EmojiManager {
         Self::new()
     }
 }
+
+/// Lowercase with punctuation dropped, so "flag india" finds "flag: India".
+fn normalize(s: &str) -> String {
+    s.to_lowercase()
+        .chars()
+        .filter(|c| !matches!(c, ':' | ',' | '.' | '’' | '“' | '”'))
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skin_tones() {
+        assert!(EmojiManager::supports_tones("👍"));
+        assert!(EmojiManager::supports_tones("✌️"));
+        assert!(!EmojiManager::supports_tones("🔥"));
+        assert_eq!(EmojiManager::with_tone("👍", Some(2)), "👍\u{1F3FD}");
+        assert_eq!(EmojiManager::with_tone("✌️", Some(0)), "✌\u{1F3FB}");
+        assert_eq!(EmojiManager::with_tone("✌\u{1F3FB}", None), "✌\u{FE0F}");
+        assert_eq!(EmojiManager::with_tone("👍\u{1F3FF}", None), "👍");
+    }
+
+    #[test]
+    fn test_search() {
+        let manager = EmojiManager {
+            recent: Vec::new(),
+            ..EmojiManager::new()
+        };
+        let first = |query: &str| manager.get_items(query).first().map(|item| item.name.clone());
+
+        assert_eq!(first("avocado").as_deref(), Some("🥑"));
+        assert_eq!(first("flag india").as_deref(), Some("🇮🇳"));
+        assert!(manager.get_items("keycap").iter().any(|item| item.name == "#️⃣"));
+        // CLDR keywords and shortcodes, not just names
+        assert!(manager.get_items("theatre").iter().any(|item| item.name == "🎭"));
+        assert!(manager.get_items("thumbsup").iter().any(|item| item.name == "👍"));
+    }
+}
@@ -1,13 +1,138 @@
-use crate::core::{Item, ItemType};
-use std::path::PathBuf;
+use crate::core::config::{FileSearchBackend, ModeConfig, SortOrder};
+use crate::core::{CommandRunner, Config, Item, ItemType, SystemRunner};
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 use walkdir::WalkDir;
 
+/// Helpers that open a small window to drag files from. iced can't be a
+/// drag source itself, so dragging out of the launcher goes through one.
+const DRAG_TOOLS: &[&str] = &["ripdrag", "dragon-drop", "dragon"];
+
+/// Actions of the panel Alt+Enter opens on a file or folder, by id.
+pub const FILE_ACTIONS: [(&str, &str); 5] = [
+    ("copy-path", "Copy path"),
+    ("copy-file", "Copy file"),
+    ("move", "Move to folder…"),
+    ("rename", "Rename"),
+    ("trash", "Move to trash"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileInput {
+    Rename,
+    Move,
+}
+
+/// The action panel of a file or folder.
+#[derive(Debug, Clone)]
+pub struct FileActions {
+    pub item_id: String,
+    pub path: PathBuf,
+    /// Index into [`FILE_ACTIONS`]
+    pub selected: usize,
+    /// The new name or destination folder being typed
+    pub input: Option<(FileInput, String)>,
+    /// Folders completing the destination typed for a move
+    pub folders: Vec<PathBuf>,
+    /// Why the last rename or move failed
+    pub error: String,
+}
+
+impl FileActions {
+    pub fn new(item_id: &str, path: &Path) -> Self {
+        Self {
+            item_id: item_id.to_string(),
+            path: path.to_path_buf(),
+            selected: 0,
+            input: None,
+            folders: Vec::new(),
+            error: String::new(),
+        }
+    }
+
+    /// Starts typing a new name, or a destination starting from the
+    /// file's folder.
+    pub fn start_input(&mut self, kind: FileInput) {
+        let value = match kind {
+            FileInput::Rename => self.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+            FileInput::Move => self.path.parent().map(|dir| format!("{}/", dir.display())).unwrap_or_default(),
+        };
+        self.set_input(value);
+        if let Some(input) = &mut self.input {
+            input.0 = kind;
+        }
+    }
+
+    pub fn set_input(&mut self, value: String) {
+        let kind = self.input.as_ref().map_or(FileInput::Rename, |(kind, _)| *kind);
+        self.folders = if kind == FileInput::Move { folder_suggestions(&value) } else { Vec::new() };
+        self.input = Some((kind, value));
+        self.error.clear();
+    }
+}
+
+/// Folders in the typed folder starting with the rest of what was typed,
+/// e.g. `~/Documents/Pro` lists `~/Documents/Projects`. Hidden ones only
+/// once a `.` is typed.
+pub fn folder_suggestions(typed: &str) -> Vec<PathBuf> {
+    let (dir, prefix) = match typed.rfind('/') {
+        Some(i) => (Config::expand_home(&typed[..=i]), typed[i + 1..].to_lowercase()),
+        None => return Vec::new(),
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut folders: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            name.starts_with(&prefix) && (prefix.starts_with('.') || !name.starts_with('.'))
+        })
+        .map(|entry| entry.path())
+        .collect();
+    folders.sort();
+    folders.truncate(8);
+    folders
+}
+
+/// `file://` URI of `path`, as file managers paste it.
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// The index file's paths, and its modification time when they were read.
+type LoadedIndex = Option<(SystemTime, Arc<Vec<PathBuf>>)>;
+
+#[derive(Clone)]
 pub struct FileManager {
     search_paths: Vec<PathBuf>,
+    backend: FileSearchBackend,
+    options: ModeConfig,
+    runner: Arc<dyn CommandRunner>,
+    /// Kept while the index file's modification time stays the same
+    index: Arc<Mutex<LoadedIndex>>,
 }
 
 impl FileManager {
-    pub fn new() -> Self {
+    pub fn new(config: &Config) -> Self {
+        Self::with_runner(config, SystemRunner::shared())
+    }
+
+    pub fn with_runner(config: &Config, runner: Arc<dyn CommandRunner>) -> Self {
         let home = dirs::home_dir().unwrap_or_default();
         Self {
             search_paths: vec![
@@ -18,6 +143,10 @@ impl FileManager {
                 home.join("Music"),
                 home.join("Desktop"),
             ],
+            backend: config.files.backend(),
+            options: config.mode("files"),
+            runner,
+            index: Arc::default(),
         }
     }
 
@@ -32,68 +161,222 @@ impl FileManager {
             .with_icon("system-search")];
         }
 
+        let paths = match self.backend {
+            FileSearchBackend::Walk => Ok(self.walk(query)),
+            FileSearchBackend::Fd => self.fd(query),
+            FileSearchBackend::Plocate => self.plocate(query),
+            FileSearchBackend::Index => self.indexed(query),
+        };
+        let mut items: Vec<Item> = match paths {
+            Ok(paths) => paths.iter().map(|path| Self::file_item(path)).collect(),
+            Err(items) => return items,
+        };
+
+        if self.options.sort == Some(SortOrder::Name) {
+            items.sort_by_key(|item| item.name.to_lowercase());
+        }
+
+        items
+    }
+
+    fn max_results(&self) -> usize {
+        self.options.max_results.unwrap_or(50)
+    }
+
+    fn show_hidden(&self) -> bool {
+        self.options.show_hidden.unwrap_or(false)
+    }
+
+    /// Whether `name` contains the query, ignoring case.
+    fn name_matches(path: &Path, query_lower: &str) -> bool {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|name| name.to_lowercase().contains(query_lower))
+    }
+
+    /// Whether `path` lies in a search folder, and (unless hidden files are
+    /// shown) not under a dotfile inside it.
+    fn in_search_paths(&self, path: &Path) -> bool {
+        self.search_paths.iter().any(|root| {
+            path.strip_prefix(root).is_ok_and(|relative| {
+                self.show_hidden()
+                    || !relative.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+            })
+        })
+    }
+
+    fn walk_entries(&self, root: &Path, max_depth: usize) -> impl Iterator<Item = walkdir::DirEntry> {
+        let show_hidden = self.show_hidden();
+        WalkDir::new(root)
+            .max_depth(max_depth)
+            .into_iter()
+            .filter_entry(move |e| {
+                show_hidden || e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.')
+            })
+            .filter_map(|e| e.ok())
+    }
+
+    fn walk(&self, query: &str) -> Vec<PathBuf> {
         let query_lower = query.to_lowercase();
-        let mut items = Vec::new();
+        self.search_paths
+            .iter()
+            .filter(|search_path| search_path.exists())
+            .flat_map(|search_path| self.walk_entries(search_path, 4))
+            .map(walkdir::DirEntry::into_path)
+            .filter(|path| Self::name_matches(path, &query_lower))
+            .take(self.max_results())
+            .collect()
+    }
 
-        for search_path in &self.search_paths {
-            if !search_path.exists() {
-                continue;
+    /// Paths a search command printed one per line, or error items.
+    fn run_search(&self, program: &str, args: &[&str], missing: &str) -> Result<Vec<PathBuf>, Vec<Item>> {
+        let output = match self.runner.run(program, args) {
+            Ok(output) => output,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Err(Item::error_items("files", format!("{} not found", program), missing))
             }
+            Err(e) => return Err(Item::error_items("files", format!("Failed to run {}", program), e.to_string())),
+        };
+        // plocate exits with 1 when nothing matches, and fd when some
+        // folders can't be read
+        if !output.status.success() && output.stdout.is_empty() && !output.stderr.is_empty() {
+            return Err(Item::command_error_items("files", format!("{} failed", program), &output));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).lines().map(PathBuf::from).collect())
+    }
 
-            for entry in WalkDir::new(search_path)
-                .max_depth(4)
-                .into_iter()
-                .filter_map(|e| e.ok())
-            {
-                let path = entry.path();
-                let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-                if file_name.to_lowercase().contains(&query_lower) {
-                    let is_dir = path.is_dir();
-                    let size = if is_dir {
-                        None
-                    } else {
-                        path.metadata().ok().map(|m| m.len())
-                    };
-
-                    let mime = if is_dir {
-                        "directory".to_string()
-                    } else {
-                        mime_guess::from_path(path)
-                            .first()
-                            .map(|m| m.to_string())
-                            .unwrap_or_else(|| "application/octet-stream".to_string())
-                    };
-
-                    let icon = Self::get_icon_for_mime(&mime);
-
-                    let mut item = Item::new(
-                        format!("file:{}", path.display()),
-                        file_name,
-                        if is_dir { ItemType::Folder } else { ItemType::File },
-                    )
-                    .with_description(path.display().to_string())
-                    .with_icon(icon);
-
-                    item.metadata.path = Some(path.to_path_buf());
-                    item.metadata.size = size;
-                    item.metadata.mime_type = Some(mime);
-
-                    items.push(item);
-                }
+    fn fd(&self, query: &str) -> Result<Vec<PathBuf>, Vec<Item>> {
+        let max_results = self.max_results().to_string();
+        let mut args = vec!["--ignore-case", "--fixed-strings", "--absolute-path", "--max-results", &max_results];
+        if self.show_hidden() {
+            args.push("--hidden");
+        }
+        args.extend(["--", query]);
+        let roots: Vec<String> = self
+            .search_paths
+            .iter()
+            .filter(|root| root.exists())
+            .map(|root| root.display().to_string())
+            .collect();
+        if roots.is_empty() {
+            return Ok(Vec::new());
+        }
+        args.extend(roots.iter().map(String::as_str));
+        self.run_search("fd", &args, "Install fd, or set files.backend to \"walk\"")
+    }
 
-                // Limit results
-                if items.len() >= 50 {
-                    break;
-                }
+    /// The whole database matches, so results outside the search folders
+    /// are dropped here.
+    fn plocate(&self, query: &str) -> Result<Vec<PathBuf>, Vec<Item>> {
+        let paths = self.run_search(
+            "plocate",
+            &["--ignore-case", "--basename", "--", query],
+            "Install plocate, or set files.backend to \"walk\"",
+        )?;
+        Ok(paths
+            .into_iter()
+            .filter(|path| self.in_search_paths(path))
+            .take(self.max_results())
+            .collect())
+    }
+
+    fn index_path() -> PathBuf {
+        Config::data_path("file-index.txt")
+    }
+
+    fn indexed(&self, query: &str) -> Result<Vec<PathBuf>, Vec<Item>> {
+        let Some(paths) = self.load_index() else {
+            return Err(vec![Item::new("file:no-index", "The file index isn't built yet", ItemType::Command)
+                .with_description("wlaunch --daemon builds it, then updates it every hour")
+                .with_icon("dialog-information")]);
+        };
+        let query_lower = query.to_lowercase();
+        Ok(paths
+            .iter()
+            .filter(|path| Self::name_matches(path, &query_lower))
+            .take(self.max_results())
+            .cloned()
+            .collect())
+    }
+
+    fn load_index(&self) -> Option<Arc<Vec<PathBuf>>> {
+        let path = Self::index_path();
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+        let mut index = self.index.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((built, paths)) = index.as_ref() {
+            if *built == modified {
+                return Some(paths.clone());
             }
+        }
+        let paths: Arc<Vec<PathBuf>> = Arc::new(fs::read_to_string(&path).ok()?.lines().map(PathBuf::from).collect());
+        *index = Some((modified, paths.clone()));
+        Some(paths)
+    }
+
+    /// Whether the `index` backend is on and its index is missing or more
+    /// than an hour old.
+    pub fn index_stale(&self) -> bool {
+        self.backend == FileSearchBackend::Index
+            && fs::metadata(Self::index_path())
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_none_or(|age| age >= Duration::from_secs(3600))
+    }
 
-            if items.len() >= 50 {
-                break;
+    /// Lists every file in the search folders into the index, for the
+    /// daemon. The list is written aside and renamed, so searches never
+    /// read half of it.
+    pub fn build_index(&self) -> Result<usize> {
+        let mut list = String::new();
+        let mut count = 0;
+        for root in self.search_paths.iter().filter(|root| root.exists()) {
+            for entry in self.walk_entries(root, usize::MAX).filter(|e| e.depth() > 0) {
+                list.push_str(&entry.path().to_string_lossy());
+                list.push('\n');
+                count += 1;
             }
         }
+        let path = Self::index_path();
+        fs::create_dir_all(path.parent().unwrap())?;
+        let partial = path.with_extension("txt.partial");
+        fs::write(&partial, list)?;
+        fs::rename(&partial, &path)?;
+        Ok(count)
+    }
 
-        items
+    pub(crate) fn file_item(path: &Path) -> Item {
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let is_dir = path.is_dir();
+        let size = if is_dir {
+            None
+        } else {
+            path.metadata().ok().map(|m| m.len())
+        };
+
+        let mime = if is_dir {
+            "directory".to_string()
+        } else {
+            mime_guess::from_path(path)
+                .first()
+                .map(|m| m.to_string())
+                .unwrap_or_else(|| "application/octet-stream".to_string())
+        };
+
+        let icon = Self::get_icon_for_mime(&mime);
+
+        let mut item = Item::new(
+            format!("file:{}", path.display()),
+            file_name,
+            if is_dir { ItemType::Folder } else { ItemType::File },
+        )
+        .with_description(path.display().to_string())
+        .with_icon(icon);
+
+        item.metadata.path = Some(path.to_path_buf());
+        item.metadata.size = size;
+        item.metadata.mime_type = Some(mime);
+        item
     }
 
     fn get_icon_for_mime(mime: &str) -> &'static str {
@@ -127,8 +410,354 @@ impl FileManager {
     }
 }
 
+impl FileManager {
+    /// Offers `path` for dragging into another application. `tool` overrides
+    /// the helper; it's run as `<tool> -x <path>` (exit after one drop).
+    pub fn drag(path: &Path, tool: Option<&str>) -> Result<()> {
+        let tool = match tool {
+            Some(tool) => tool.to_string(),
+            None => DRAG_TOOLS
+                .iter()
+                .find(|tool| which::which(tool).is_ok())
+                .map(|tool| tool.to_string())
+                .ok_or_else(|| anyhow::anyhow!("Install ripdrag or dragon-drop to drag files"))?,
+        };
+        Command::new(&tool).arg("-x").arg(path).spawn()?;
+        Ok(())
+    }
+
+    /// Puts the file itself on the clipboard, so a file manager pastes a
+    /// copy: wl-copy on Wayland, xclip on X11.
+    pub fn copy_file(path: &Path) -> Result<()> {
+        let (tool, args): (&str, &[&str]) = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            ("wl-copy", &["--type", "text/uri-list"])
+        } else {
+            ("xclip", &["-selection", "clipboard", "-target", "text/uri-list"])
+        };
+        let mut child = Command::new(tool)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Install {} to copy files", tool))?;
+        // Both keep serving the clipboard after the launcher exits
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "{}", file_uri(path))?;
+        }
+        Ok(())
+    }
+
+    /// Renames `path` within its folder, returning the new path.
+    pub fn rename(path: &Path, name: &str) -> Result<PathBuf> {
+        let name = name.trim();
+        if name.is_empty() || name == "." || name == ".." || name.contains('/') {
+            bail!("\"{}\" isn't a valid name", name);
+        }
+        let target = path.with_file_name(name);
+        if target == path {
+            return Ok(target);
+        }
+        if target.exists() {
+            bail!("{} already exists", target.display());
+        }
+        fs::rename(path, &target).with_context(|| format!("Can't rename {}", path.display()))?;
+        Ok(target)
+    }
+
+    /// Moves `path` into `folder` (`~` allowed), across filesystems too.
+    pub fn move_to(path: &Path, folder: &str) -> Result<PathBuf> {
+        let folder = Config::expand_home(folder.trim());
+        if !folder.is_dir() {
+            bail!("{} isn't a folder", folder.display());
+        }
+        let target = folder.join(path.file_name().context("Can't move the root folder")?);
+        if target.exists() {
+            bail!("{} already exists", target.display());
+        }
+        if fs::rename(path, &target).is_err() {
+            // Another filesystem: mv copies and deletes
+            let status = Command::new("mv").arg("--no-clobber").arg("--").arg(path).arg(&folder).status()?;
+            if !status.success() {
+                bail!("Can't move {} to {}", path.display(), folder.display());
+            }
+        }
+        Ok(target)
+    }
+
+    /// Moves `path` to the desktop's trash, where it can be restored.
+    pub fn trash(path: &Path) -> Result<()> {
+        let output = Command::new("gio")
+            .arg("trash")
+            .arg(path)
+            .output()
+            .context("Install gio (glib2) to move files to the trash")?;
+        if !output.status.success() {
+            bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(())
+    }
+}
+
 impl Default for FileManager {
     fn default() -> Self {
-        Self::new()
+        Self::new(&Config::default())
+    }
+}
+
+/// One line of `rg --json`: a match or a line of context around one.
+struct RgLine {
+    path: PathBuf,
+    number: u64,
+    text: String,
+    is_match: bool,
+}
+
+impl RgLine {
+    /// Paths and lines that aren't UTF-8 come base64 encoded, and are skipped.
+    fn parse(line: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(line).ok()?;
+        let is_match = match value.get("type")?.as_str()? {
+            "match" => true,
+            "context" => false,
+            _ => return None,
+        };
+        let data = value.get("data")?;
+        Some(RgLine {
+            path: PathBuf::from(data.pointer("/path/text")?.as_str()?),
+            number: data.get("line_number")?.as_u64()?,
+            text: data.pointer("/lines/text")?.as_str()?.trim_end_matches(['\n', '\r']).to_string(),
+            is_match,
+        })
+    }
+}
+
+/// Command opening `path` at `line` in `editor`, and whether it has to run
+/// in a terminal. Editors that don't take `+<line>` are known by name.
+fn editor_command(editor: &str, path: &Path, line: u64) -> (Vec<String>, bool) {
+    let mut command: Vec<String> = editor.split_whitespace().map(str::to_string).collect();
+    let program = command
+        .first()
+        .and_then(|program| Path::new(program).file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file = path.display().to_string();
+    let terminal = match program.as_str() {
+        "code" | "code-oss" | "codium" | "cursor" => {
+            command.extend(["--goto".to_string(), format!("{}:{}", file, line)]);
+            false
+        }
+        "subl" | "zed" => {
+            command.push(format!("{}:{}", file, line));
+            false
+        }
+        "kate" => {
+            command.extend(["--line".to_string(), line.to_string(), file]);
+            false
+        }
+        "gedit" | "gnome-text-editor" | "mousepad" => {
+            command.extend([format!("+{}", line), file]);
+            false
+        }
+        "hx" | "helix" => {
+            command.push(format!("{}:{}", file, line));
+            true
+        }
+        // vi, vim, nvim, nano, micro, emacs -nw, kak
+        _ => {
+            command.extend([format!("+{}", line), file]);
+            true
+        }
+    };
+    (command, terminal)
+}
+
+/// File contents under `grep.roots`, searched with ripgrep. Matches show the
+/// lines around them in the details panel and open at their line.
+#[derive(Clone)]
+pub struct ContentSearch {
+    roots: Vec<String>,
+    editor: String,
+    options: ModeConfig,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl ContentSearch {
+    pub fn new(config: &Config) -> Self {
+        Self::with_runner(config, SystemRunner::shared())
+    }
+
+    pub fn with_runner(config: &Config, runner: Arc<dyn CommandRunner>) -> Self {
+        Self {
+            roots: config.grep.roots().iter().map(|root| Config::expand_home(root).display().to_string()).collect(),
+            editor: config.grep.editor(),
+            options: config.mode("grep"),
+            runner,
+        }
+    }
+
+    /// Regular expressions, case-insensitive unless the query has capitals,
+    /// skipping what `.gitignore` files and hidden folders hold.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim();
+        if query.chars().count() < 2 {
+            return vec![Item::new("grep:hint", "Type at least 2 characters to search", ItemType::Command)
+                .with_description(format!("Searches file contents in {}", self.roots.join(", ")))
+                .with_icon("system-search")];
+        }
+
+        let mut args = vec!["--json", "--context", "2", "--max-count", "5", "--smart-case"];
+        if self.options.show_hidden.unwrap_or(false) {
+            args.push("--hidden");
+        }
+        args.extend(["--", query]);
+        args.extend(self.roots.iter().map(String::as_str));
+        let output = match self.runner.run("rg", &args) {
+            Ok(output) => output,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Item::error_items("grep", "ripgrep not found", "Install ripgrep to search file contents")
+            }
+            Err(e) => return Item::error_items("grep", "Failed to run ripgrep", e.to_string()),
+        };
+
+        let lines: Vec<RgLine> = String::from_utf8_lossy(&output.stdout).lines().filter_map(RgLine::parse).collect();
+        let max_results = self.options.max_results.unwrap_or(50);
+        let mut items = Vec::new();
+        for line in lines.iter().filter(|line| line.is_match).take(max_results) {
+            // Context of the same file within two lines, as rg printed it
+            let preview: Vec<String> = lines
+                .iter()
+                .filter(|other| other.path == line.path && other.number.abs_diff(line.number) <= 2)
+                .map(|other| format!("{:>5}{} {}", other.number, if other.is_match { ":" } else { " " }, other.text))
+                .collect();
+            let location = match dirs::home_dir().and_then(|home| line.path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+                Some(relative) => format!("~/{}", relative.display()),
+                None => line.path.display().to_string(),
+            };
+            let name: String = line.text.trim().chars().take(120).collect();
+
+            let mut item = Item::new(format!("grep:{}:{}", line.path.display(), line.number), name, ItemType::GrepMatch)
+                .with_description(format!("{}:{}", location, line.number))
+                .with_icon("text-x-generic");
+            item.metadata.path = Some(line.path.clone());
+            item.metadata.content = Some(preview.join("\n"));
+            items.push(item);
+        }
+
+        if items.is_empty() {
+            // 1 is no match; 2 is an error, which may come with matches when
+            // only some roots are missing
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if output.status.code() == Some(2) && !stderr.contains("No such file or directory") {
+                return Item::command_error_items("grep", "ripgrep failed", &output);
+            }
+            items.push(
+                Item::new("grep:none", format!("Nothing matches \"{}\"", query), ItemType::Command)
+                    .with_description(format!("Searched {}", self.roots.join(", ")))
+                    .with_icon("dialog-information"),
+            );
+        }
+        items
+    }
+
+    /// Opens a match in the editor at its line; returns whether it started.
+    pub fn open(&self, item: &Item) -> bool {
+        let (Some(path), Some((_, line))) = (&item.metadata.path, item.id.rsplit_once(':')) else {
+            return false;
+        };
+        let (command, terminal) = editor_command(&self.editor, path, line.parse().unwrap_or(1));
+        let result = if terminal {
+            Command::new("x-terminal-emulator").arg("-e").args(&command).spawn()
+        } else {
+            Command::new(&command[0]).args(&command[1..]).spawn()
+        };
+        match result {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("Failed to open {} in {}: {}", path.display(), self.editor, e);
+                false
+            }
+        }
+    }
+}
+
+impl Default for ContentSearch {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    #[test]
+    fn test_content_search() {
+        let mut config = Config::default();
+        config.grep.roots = Some(vec!["/src".to_string()]);
+        let runner = FakeRunner::new()
+            .with(
+                "rg --json --context 2 --max-count 5 --smart-case -- Config /src",
+                include_str!("../../tests/fixtures/rg_json.txt"),
+            )
+            .shared();
+        let items = ContentSearch::with_runner(&config, runner).get_items("Config");
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["grep:/src/app/src/main.rs:2", "grep:/src/notes/config.md:1", "grep:/src/notes/config.md:3"]);
+        assert_eq!(items[0].name, "let config = Config::load().unwrap_or_default();");
+        assert_eq!(items[0].description.as_deref(), Some("/src/app/src/main.rs:2"));
+        assert_eq!(
+            items[0].metadata.content.as_deref(),
+            Some("    1  fn main() {\n    2:     let config = Config::load().unwrap_or_default();\n    3      run(&config);\n    4  }")
+        );
+        // Both matches of a file share their context
+        assert_eq!(items[2].metadata.content.as_deref().unwrap().lines().count(), 3);
+    }
+
+    #[test]
+    fn test_plocate_search() {
+        let runner = FakeRunner::new().with(
+            "plocate --ignore-case --basename -- report",
+            "/home/alice/Documents/report.pdf\n/home/alice/Documents/.cache/report.tmp\n/usr/share/doc/report.txt\n/home/alice/Downloads/Report 2024.odt\n",
+        );
+        let mut files = FileManager::with_runner(&Config::default(), runner.shared());
+        files.backend = FileSearchBackend::Plocate;
+        files.search_paths = vec![PathBuf::from("/home/alice/Documents"), PathBuf::from("/home/alice/Downloads")];
+        let names: Vec<String> = files.get_items("report").into_iter().map(|item| item.name).collect();
+        assert_eq!(names, ["report.pdf", "Report 2024.odt"]);
+    }
+
+    #[test]
+    fn test_editor_command() {
+        let path = Path::new("/src/main.rs");
+        assert_eq!(editor_command("nvim", path, 12), (vec!["nvim".into(), "+12".into(), "/src/main.rs".into()], true));
+        assert_eq!(
+            editor_command("/usr/bin/code --reuse-window", path, 12),
+            (vec!["/usr/bin/code".into(), "--reuse-window".into(), "--goto".into(), "/src/main.rs:12".into()], false)
+        );
+        assert_eq!(editor_command("hx", path, 3).0, ["hx", "/src/main.rs:3"]);
+    }
+
+    #[test]
+    fn test_rename_and_move() {
+        let root = std::env::temp_dir().join(format!("wlaunch-test-files-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("Projects")).unwrap();
+        fs::create_dir_all(root.join("Pictures")).unwrap();
+        fs::create_dir_all(root.join(".cache")).unwrap();
+        fs::write(root.join("notes.txt"), "").unwrap();
+
+        let typed = format!("{}/p", root.display());
+        assert_eq!(folder_suggestions(&typed), [root.join("Pictures"), root.join("Projects")]);
+        assert_eq!(folder_suggestions(&format!("{}/", root.display())).len(), 2);
+        assert_eq!(folder_suggestions(&format!("{}/.c", root.display())), [root.join(".cache")]);
+
+        let renamed = FileManager::rename(&root.join("notes.txt"), "todo list.txt").unwrap();
+        assert_eq!(renamed, root.join("todo list.txt"));
+        assert!(FileManager::rename(&renamed, "a/b").is_err());
+        let moved = FileManager::move_to(&renamed, &root.join("Projects").display().to_string()).unwrap();
+        assert!(moved.exists() && !renamed.exists());
+        assert_eq!(file_uri(&moved), format!("file://{}/Projects/todo%20list.txt", root.display()));
+
+        fs::remove_dir_all(&root).unwrap();
     }
 }
@@ -0,0 +1,269 @@
+//! Installed Flatpak applications (run, update, uninstall) and Flathub
+//! search for new ones. Changes run in a terminal, where flatpak asks for
+//! confirmation.
+
+use super::packages::run_in_terminal;
+use crate::core::config::{ModeConfig, SortOrder};
+use crate::core::{CommandRunner, Config, Item, ItemType, SystemRunner};
+use std::io::ErrorKind;
+use std::process::Command;
+use std::sync::Arc;
+
+struct App {
+    id: String,
+    name: String,
+    version: String,
+    /// Remote it was installed from, or the remotes offering it
+    origin: String,
+    description: String,
+}
+
+#[derive(Clone)]
+pub struct FlatpakManager {
+    options: ModeConfig,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl FlatpakManager {
+    pub fn new(config: &Config) -> Self {
+        Self::with_runner(config, SystemRunner::shared())
+    }
+
+    pub fn with_runner(config: &Config, runner: Arc<dyn CommandRunner>) -> Self {
+        Self {
+            options: config.mode("flatpak"),
+            runner,
+        }
+    }
+
+    /// Stdout of a successful command; `Ok(None)` when flatpak isn't installed.
+    fn run(&self, args: &[&str]) -> Result<Option<String>, Vec<Item>> {
+        match self.runner.run("flatpak", args) {
+            Ok(output) if output.status.success() => Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned())),
+            Ok(output) => Err(Item::command_error_items("flatpak", format!("flatpak {} failed", args[0]), &output)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Item::error_items("flatpak", "Failed to run flatpak", e.to_string())),
+        }
+    }
+
+    /// Tab separated `application name version origin` rows of `flatpak list`.
+    fn parse_installed(stdout: &str) -> Vec<App> {
+        stdout
+            .lines()
+            .filter_map(|line| {
+                let columns: Vec<&str> = line.split('\t').collect();
+                let [id, name, version, origin] = columns[..] else {
+                    return None;
+                };
+                Some(App {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    version: version.to_string(),
+                    origin: origin.to_string(),
+                    description: String::new(),
+                })
+            })
+            .collect()
+    }
+
+    /// Tab separated `application name description version remotes` rows of
+    /// `flatpak search`.
+    fn parse_search(stdout: &str) -> Vec<App> {
+        stdout
+            .lines()
+            .filter_map(|line| {
+                let columns: Vec<&str> = line.split('\t').collect();
+                let [id, name, description, version, remotes] = columns[..] else {
+                    return None;
+                };
+                Some(App {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    version: version.to_string(),
+                    origin: remotes.to_string(),
+                    description: description.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim();
+        let filter = query.to_lowercase();
+
+        let mut installed = match self.run(&["list", "--app", "--columns=application,name,version,origin"]) {
+            Ok(Some(stdout)) => Self::parse_installed(&stdout),
+            Ok(None) => {
+                return Item::error_items(
+                    "flatpak",
+                    "Flatpak is not installed",
+                    "Install flatpak and add Flathub to manage Flatpak applications",
+                )
+            }
+            Err(items) => return items,
+        };
+        installed.retain(|app| {
+            filter.is_empty() || app.name.to_lowercase().contains(&filter) || app.id.to_lowercase().contains(&filter)
+        });
+        if let Some(SortOrder::Name) = self.options.sort {
+            installed.sort_by_key(|app| app.name.to_lowercase());
+        }
+
+        // Flathub search once the query is long enough to be useful
+        let mut available = Vec::new();
+        if query.chars().count() >= 2 {
+            match self.run(&["search", "--columns=application,name,description,version,remotes", query]) {
+                Ok(stdout) => available = Self::parse_search(&stdout.unwrap_or_default()),
+                Err(items) => return items,
+            }
+            available.retain(|app| !installed.iter().any(|other| other.id == app.id));
+            available.truncate(self.options.max_results.unwrap_or(20));
+        }
+
+        let mut items = Vec::new();
+        for app in &installed {
+            let mut item = Item::new(format!("flatpak:{}", app.id), &app.name, ItemType::Flatpak)
+                .with_description(format!("{} | {} | {}", app.id, app.version, app.origin))
+                .with_icon(app.id.clone());
+            item.metadata.installed = true;
+            items.push(item);
+
+            // Actions for the best match only, like the git mode
+            if items.len() == 1 && !filter.is_empty() {
+                for (action, name, icon) in [
+                    ("update", "    Update", "software-update-available"),
+                    ("uninstall", "    Uninstall", "edit-delete"),
+                ] {
+                    items.push(
+                        Item::new(format!("flatpak:{}:{}", action, app.id), name, ItemType::FlatpakAction)
+                            .with_description(format!("{} in a terminal, asks before changing anything", app.name))
+                            .with_icon(icon),
+                    );
+                }
+            }
+        }
+        for app in &available {
+            let remote = app.origin.split(',').next().unwrap_or(&app.origin);
+            let mut details = vec![format!("Install from {}", remote)];
+            if !app.version.is_empty() {
+                details.push(app.version.clone());
+            }
+            if !app.description.is_empty() {
+                details.push(app.description.clone());
+            }
+            items.push(
+                Item::new(format!("flatpak:{}", app.id), &app.name, ItemType::Flatpak)
+                    .with_description(details.join(" | "))
+                    .with_icon("system-software-install"),
+            );
+        }
+
+        if filter.is_empty() && !installed.is_empty() {
+            items.push(
+                Item::new("flatpak:update-all", "Update all Flatpaks", ItemType::FlatpakAction)
+                    .with_description("flatpak update in a terminal, asks before changing anything")
+                    .with_icon("software-update-available"),
+            );
+        }
+        if items.is_empty() {
+            items.push(
+                Item::new("flatpak:none", format!("No Flatpaks match \"{}\"", query), ItemType::Command)
+                    .with_icon("dialog-information"),
+            );
+        }
+        items
+    }
+
+    /// Enter on an application: runs it if installed, else installs it in a
+    /// terminal. Returns whether the launcher should close.
+    pub fn activate(&self, item: &Item) -> bool {
+        let Some(id) = item.id.strip_prefix("flatpak:") else {
+            return false;
+        };
+        if !item.metadata.installed {
+            return self.execute_action(&format!("flatpak:install:{}", id));
+        }
+        match Command::new("flatpak").args(["run", id]).spawn() {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("Failed to run {}: {}", id, e);
+                false
+            }
+        }
+    }
+
+    /// Runs an install, update or uninstall action in a terminal; returns
+    /// whether one was opened.
+    pub fn execute_action(&self, action_id: &str) -> bool {
+        if action_id == "flatpak:update-all" {
+            return run_in_terminal(&["flatpak".into(), "update".into()]);
+        }
+        let mut parts = action_id.splitn(3, ':');
+        let (Some("flatpak"), Some(action @ ("install" | "update" | "uninstall")), Some(id)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return false;
+        };
+        run_in_terminal(&["flatpak".into(), action.into(), id.into()])
+    }
+}
+
+impl Default for FlatpakManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    fn manager() -> FlatpakManager {
+        let runner = FakeRunner::new()
+            .with(
+                "flatpak list --app --columns=application,name,version,origin",
+                include_str!("../../tests/fixtures/flatpak_list.txt"),
+            )
+            .with(
+                "flatpak search --columns=application,name,description,version,remotes fire",
+                "org.mozilla.firefox\tFirefox\tFast, Private & Safe Web Browser\t127.0.2\tflathub\n\
+                 io.github.firewall_config\tFirewall\tConfigure the firewall\t2.1\tflathub\n",
+            )
+            .shared();
+        FlatpakManager::with_runner(&Config::default(), runner)
+    }
+
+    #[test]
+    fn test_installed() {
+        let items = manager().get_items("");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["Firefox", "Calculator", "Flatseal", "Update all Flatpaks"]);
+        assert_eq!(items[0].description.as_deref(), Some("org.mozilla.firefox | 127.0.2 | flathub"));
+        assert!(items[0].metadata.installed);
+    }
+
+    #[test]
+    fn test_search() {
+        let items = manager().get_items("fire");
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "flatpak:org.mozilla.firefox",
+                "flatpak:update:org.mozilla.firefox",
+                "flatpak:uninstall:org.mozilla.firefox",
+                "flatpak:io.github.firewall_config",
+            ]
+        );
+        assert_eq!(items[3].description.as_deref(), Some("Install from flathub | 2.1 | Configure the firewall"));
+        assert!(!items[3].metadata.installed);
+    }
+
+    #[test]
+    fn test_not_installed() {
+        let items = FlatpakManager::with_runner(&Config::default(), FakeRunner::new().shared()).get_items("");
+        assert_eq!(items[0].item_type, ItemType::Error);
+        assert_eq!(items[0].name, "Flatpak is not installed");
+    }
+}
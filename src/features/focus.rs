@@ -0,0 +1,323 @@
+//! Focus sessions: blocks the distracting sites in `focus.sites` by
+//! pointing them at 0.0.0.0 in `/etc/hosts` (written with pkexec), for a
+//! while or until turned off. The daemon lifts the block once the session
+//! is over, even with the launcher closed.
+
+use super::TimerManager;
+use crate::core::config::FocusConfig;
+use crate::core::{Config, Item, ItemType};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+const HOSTS: &str = "/etc/hosts";
+const BEGIN: &str = "# wlaunch focus begin";
+const END: &str = "# wlaunch focus end";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct FocusState {
+    /// When the running session ends; `None` lasts until turned off
+    #[serde(default)]
+    until: Option<SystemTime>,
+}
+
+fn is_blocked(hosts: &str) -> bool {
+    hosts.lines().any(|line| line == BEGIN)
+}
+
+/// `hosts` without wlaunch's block. A block missing its end line is left
+/// alone rather than guessing where it stops.
+fn unblocked(hosts: &str) -> Result<String, String> {
+    let mut inside = false;
+    let mut lines = Vec::new();
+    for line in hosts.lines() {
+        match line {
+            BEGIN => inside = true,
+            END => inside = false,
+            _ if !inside => lines.push(line),
+            _ => {}
+        }
+    }
+    if inside {
+        return Err(format!("{} has \"{}\" without \"{}\"; fix it by hand", HOSTS, BEGIN, END));
+    }
+    let mut result = lines.join("\n");
+    result.push('\n');
+    Ok(result)
+}
+
+/// `hosts` with a block sending `sites` and their `www.` names nowhere,
+/// over IPv4 and IPv6.
+fn blocked(hosts: &str, sites: &[String]) -> Result<String, String> {
+    let mut result = unblocked(hosts)?;
+    result.push_str(BEGIN);
+    result.push('\n');
+    for site in sites {
+        let site = site.trim().trim_start_matches("www.");
+        if site.is_empty() {
+            continue;
+        }
+        for name in [site.to_string(), format!("www.{}", site)] {
+            result.push_str(&format!("0.0.0.0 {}\n:: {}\n", name, name));
+        }
+    }
+    result.push_str(END);
+    result.push('\n');
+    Ok(result)
+}
+
+/// `25 min`, `1 h 30 min`.
+pub(crate) fn format_minutes(duration: Duration) -> String {
+    let minutes = duration.as_secs().div_ceil(60);
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{} min", minutes),
+        (hours, 0) => format!("{} h", hours),
+        (hours, minutes) => format!("{} h {} min", hours, minutes),
+    }
+}
+
+#[derive(Clone)]
+pub struct FocusManager {
+    config: FocusConfig,
+}
+
+impl FocusManager {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            config: config.focus.clone(),
+        }
+    }
+
+    fn data_path() -> PathBuf {
+        Config::data_path("focus.json")
+    }
+
+    /// Read on every use: the daemon ends sessions behind the launcher's back.
+    fn load() -> Result<FocusState> {
+        let path = Self::data_path();
+        if path.exists() {
+            Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+        } else {
+            Ok(FocusState::default())
+        }
+    }
+
+    fn save(state: &FocusState) -> Result<()> {
+        let path = Self::data_path();
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    pub fn is_active(&self) -> bool {
+        is_blocked(&fs::read_to_string(HOSTS).unwrap_or_default())
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let remaining = Self::load()
+            .unwrap_or_default()
+            .until
+            .map(|until| until.duration_since(SystemTime::now()).unwrap_or_default());
+        self.items(query, self.is_active(), remaining)
+    }
+
+    /// `remaining` is the time left of the running session, if it has an end.
+    fn items(&self, query: &str, active: bool, remaining: Option<Duration>) -> Vec<Item> {
+        let sites = self.config.sites.join(", ");
+        if active {
+            let name = match remaining {
+                Some(remaining) => format!("Stop focus ({} left)", format_minutes(remaining)),
+                None => "Stop focus (on until turned off)".to_string(),
+            };
+            return vec![Item::new("focus:off", name, ItemType::FocusAction)
+                .with_description(format!("Unblocks {}", sites))
+                .with_icon("media-playback-stop")];
+        }
+        if self.config.sites.is_empty() {
+            return Item::error_items(
+                "focus",
+                "No distracting sites configured",
+                "List them in focus.sites in the config, e.g. [\"reddit.com\", \"youtube.com\"]",
+            );
+        }
+
+        let query = query.trim();
+        let durations: Vec<Option<Duration>> = if query.is_empty() {
+            let default = Duration::from_secs(self.config.minutes() * 60);
+            let mut durations = vec![Some(default)];
+            durations.extend([Some(Duration::from_secs(3600)), Some(Duration::from_secs(7200))].into_iter().filter(|d| *d != Some(default)));
+            durations.push(None);
+            durations
+        } else {
+            match TimerManager::parse_duration(query) {
+                Some(duration) => vec![Some(duration)],
+                None => {
+                    return vec![Item::new("focus:none", format!("\"{}\" isn't a duration", query), ItemType::Command)
+                        .with_description("e.g. 45, 90m or 1h30m")
+                        .with_icon("dialog-information")]
+                }
+            }
+        };
+
+        durations
+            .into_iter()
+            .map(|duration| {
+                let (id, name) = match duration {
+                    Some(duration) => (format!("focus:on:{}", duration.as_secs()), format!("Focus for {}", format_minutes(duration))),
+                    None => ("focus:on".to_string(), "Focus until turned off".to_string()),
+                };
+                Item::new(id, name, ItemType::FocusAction)
+                    .with_description(format!("Focus is off | blocks {}", sites))
+                    .with_icon("media-playback-start")
+            })
+            .collect()
+    }
+
+    /// Writes `/etc/hosts` as root, asking for the password through polkit.
+    fn write_hosts(content: &str) -> Result<(), String> {
+        let child = Command::new("pkexec")
+            .args(["tee", HOSTS])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn();
+        let status = child.and_then(|mut child| {
+            child.stdin.take().map(|mut stdin| stdin.write_all(content.as_bytes())).transpose()?;
+            child.wait()
+        });
+        match status {
+            Ok(status) if status.success() => Ok(()),
+            Ok(_) => Err(format!("{} wasn't changed", HOSTS)),
+            Err(e) => Err(format!("Failed to run pkexec: {}", e)),
+        }
+    }
+
+    pub fn start(&self, duration: Option<Duration>) -> Result<(), String> {
+        let hosts = fs::read_to_string(HOSTS).map_err(|e| format!("Can't read {}: {}", HOSTS, e))?;
+        Self::write_hosts(&blocked(&hosts, &self.config.sites)?)?;
+        let state = FocusState {
+            until: duration.map(|duration| SystemTime::now() + duration),
+        };
+        Self::save(&state).map_err(|e| format!("Failed to save the focus session: {}", e))
+    }
+
+    pub fn stop(&self) -> Result<(), String> {
+        let hosts = fs::read_to_string(HOSTS).map_err(|e| format!("Can't read {}: {}", HOSTS, e))?;
+        if is_blocked(&hosts) {
+            Self::write_hosts(&unblocked(&hosts)?)?;
+        }
+        Self::save(&FocusState::default()).map_err(|e| format!("Failed to save the focus session: {}", e))
+    }
+
+    /// Starts or stops a session; returns the notification to show.
+    pub fn execute_action(&self, action_id: &str) -> Result<String, String> {
+        if action_id == "focus:off" {
+            self.stop()?;
+            return Ok("Focus off, distracting sites are unblocked".to_string());
+        }
+        let duration = match action_id.strip_prefix("focus:on") {
+            Some("") => None,
+            Some(seconds) => Some(Duration::from_secs(seconds.trim_start_matches(':').parse().map_err(|_| "Invalid duration")?)),
+            None => return Err(format!("Unknown focus action {}", action_id)),
+        };
+        self.start(duration)?;
+        Ok(match duration {
+            Some(duration) => format!("Focus on for {}", format_minutes(duration)),
+            None => "Focus on until turned off".to_string(),
+        })
+    }
+
+    /// Called by the daemon: ends a session whose time is up. The session
+    /// is cleared before asking for the password, so a cancelled prompt
+    /// leaves the sites blocked until turned off instead of asking again
+    /// every minute; the hosts file is written on its own thread, as the
+    /// prompt would hold up the daemon.
+    pub fn tick(&self) {
+        let Ok(FocusState { until: Some(until) }) = Self::load() else {
+            return;
+        };
+        if until > SystemTime::now() {
+            return;
+        }
+        if let Err(e) = Self::save(&FocusState::default()) {
+            log::error!("Failed to end the focus session: {}", e);
+            return;
+        }
+        let manager = self.clone();
+        std::thread::spawn(move || {
+            let body = match manager.stop() {
+                Ok(()) => "Distracting sites are unblocked".to_string(),
+                Err(e) => {
+                    log::error!("Failed to end the focus session: {}", e);
+                    format!("Couldn't unblock the sites: {}. Stop focus from the launcher to retry", e)
+                }
+            };
+            let _ = notify_rust::Notification::new().summary("Focus session over").body(&body).show();
+        });
+    }
+}
+
+impl Default for FocusManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> FocusManager {
+        let mut config = Config::default();
+        config.focus.sites = vec!["reddit.com".to_string(), "www.youtube.com".to_string()];
+        FocusManager::new(&config)
+    }
+
+    #[test]
+    fn test_hosts() {
+        let hosts = "127.0.0.1 localhost\n::1 localhost\n";
+        let sites = manager().config.sites;
+        let with_block = blocked(hosts, &sites).unwrap();
+        assert!(is_blocked(&with_block));
+        assert!(with_block.contains("\n0.0.0.0 reddit.com\n:: reddit.com\n0.0.0.0 www.reddit.com\n"));
+        assert!(with_block.contains("\n0.0.0.0 youtube.com\n"));
+        // Blocking twice doesn't add a second block
+        assert_eq!(blocked(&with_block, &sites).unwrap(), with_block);
+        assert_eq!(unblocked(&with_block).unwrap(), hosts);
+    }
+
+    #[test]
+    fn test_hosts_edge_cases() {
+        // Without the end line, everything after the begin line would go
+        let broken = format!("127.0.0.1 localhost\n{}\n0.0.0.0 reddit.com\n192.168.1.5 nas\n", BEGIN);
+        assert!(unblocked(&broken).unwrap_err().contains("without"));
+        assert!(blocked(&broken, &manager().config.sites).is_err());
+
+        let sites = ["".to_string(), "  ".to_string(), "www.".to_string(), "news.ycombinator.com".to_string()];
+        let with_block = blocked("127.0.0.1 localhost\n", &sites).unwrap();
+        assert_eq!(
+            with_block,
+            format!(
+                "127.0.0.1 localhost\n{}\n0.0.0.0 news.ycombinator.com\n:: news.ycombinator.com\n0.0.0.0 www.news.ycombinator.com\n:: www.news.ycombinator.com\n{}\n",
+                BEGIN, END
+            )
+        );
+    }
+
+    #[test]
+    fn test_items() {
+        let names = |items: Vec<Item>| items.into_iter().map(|i| i.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(manager().items("", false, None)),
+            ["Focus for 25 min", "Focus for 1 h", "Focus for 2 h", "Focus until turned off"]
+        );
+        let items = manager().items("1h30m", false, None);
+        assert_eq!(items[0].id, "focus:on:5400");
+        assert_eq!(items[0].name, "Focus for 1 h 30 min");
+        assert_eq!(names(manager().items("", true, Some(Duration::from_secs(1361)))), ["Stop focus (23 min left)"]);
+        assert_eq!(FocusManager::default().items("", false, None)[0].item_type, ItemType::Error);
+    }
+}
@@ -0,0 +1,160 @@
+//! Random data on demand: UUIDs, hex tokens, lorem ipsum and numbers.
+//! Everything is generated fresh on each query; Enter copies the value.
+
+use crate::core::{Item, ItemType};
+use rand::Rng;
+use uuid::Uuid;
+
+const LOREM: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod \
+tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud \
+exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in \
+reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint \
+occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.";
+
+/// Words the later lorem paragraphs are shuffled from.
+const LOREM_WORDS: &[&str] = &[
+    "lorem", "ipsum", "dolor", "sit", "amet", "consectetur", "adipiscing", "elit", "sed", "do",
+    "eiusmod", "tempor", "incididunt", "ut", "labore", "et", "dolore", "magna", "aliqua", "enim",
+    "ad", "minim", "veniam", "quis", "nostrud", "exercitation", "ullamco", "laboris", "nisi",
+    "aliquip", "ex", "ea", "commodo", "consequat", "duis", "aute", "irure", "in", "voluptate",
+    "velit", "esse", "cillum", "fugiat", "nulla", "pariatur", "excepteur", "sint", "occaecat",
+];
+
+pub struct Generator;
+
+impl Generator {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// `uuid`, `hex [bytes]`, `lorem [paragraphs]`, `<min>-<max>`; anything
+    /// else lists one of each.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let words: Vec<&str> = query.split_whitespace().collect();
+        let count = |default: usize, max: usize| {
+            words
+                .get(1)
+                .and_then(|n| n.parse::<usize>().ok())
+                .unwrap_or(default)
+                .clamp(1, max)
+        };
+
+        match words.first().map(|w| w.to_lowercase()).as_deref() {
+            Some("uuid") => vec![Self::uuid_v4(), Self::uuid_v7()],
+            Some("hex" | "token") => vec![Self::hex_token(count(16, 256))],
+            Some("lorem" | "ipsum") => vec![Self::lorem(count(1, 20))],
+            Some("num" | "number" | "rand" | "random") => {
+                let range = words[1..].join(" ");
+                vec![Self::number(Self::parse_range(&range).unwrap_or((1, 100)))]
+            }
+            Some(_) if Self::parse_range(query).is_some() => {
+                vec![Self::number(Self::parse_range(query).unwrap_or((1, 100)))]
+            }
+            _ => vec![
+                Self::uuid_v4(),
+                Self::uuid_v7(),
+                Self::hex_token(16),
+                Self::lorem(1),
+                Self::number((1, 100)),
+            ],
+        }
+    }
+
+    fn item(kind: &str, title: String, value: String, description: &str) -> Item {
+        let mut item = Item::new(format!("gen:{}", kind), title, ItemType::Generator)
+            .with_description(format!("{} | Press Enter to copy", description))
+            .with_icon("media-playlist-shuffle");
+        item.metadata.content = Some(value);
+        item
+    }
+
+    fn uuid_v4() -> Item {
+        let id = Uuid::new_v4().to_string();
+        Self::item("uuid4", id.clone(), id, "UUID v4 (random)")
+    }
+
+    fn uuid_v7() -> Item {
+        let id = Uuid::now_v7().to_string();
+        Self::item("uuid7", id.clone(), id, "UUID v7 (time-ordered)")
+    }
+
+    fn hex_token(bytes: usize) -> Item {
+        let mut rng = rand::thread_rng();
+        let token: String = (0..bytes).map(|_| format!("{:02x}", rng.gen::<u8>())).collect();
+        let title = if token.len() > 64 {
+            format!("{}...", &token[..61])
+        } else {
+            token.clone()
+        };
+        Self::item("hex", title, token, &format!("{}-byte hex token (gen hex <bytes>)", bytes))
+    }
+
+    fn lorem(paragraphs: usize) -> Item {
+        let mut rng = rand::thread_rng();
+        let mut text = vec![LOREM.to_string()];
+        for _ in 1..paragraphs {
+            let sentences: Vec<String> = (0..rng.gen_range(4..8))
+                .map(|_| {
+                    let words: Vec<&str> = (0..rng.gen_range(6..14))
+                        .map(|_| LOREM_WORDS[rng.gen_range(0..LOREM_WORDS.len())])
+                        .collect();
+                    let sentence = words.join(" ");
+                    let mut chars = sentence.chars();
+                    let first = chars.next().map(|c| c.to_uppercase().collect::<String>()).unwrap_or_default();
+                    format!("{}{}.", first, chars.as_str())
+                })
+                .collect();
+            text.push(sentences.join(" "));
+        }
+
+        Self::item(
+            "lorem",
+            format!("{}...", &LOREM[..60]),
+            text.join("\n\n"),
+            &format!("Lorem ipsum, {} paragraph(s) (gen lorem <n>)", paragraphs),
+        )
+    }
+
+    fn number((min, max): (i64, i64)) -> Item {
+        let value = rand::thread_rng().gen_range(min..=max).to_string();
+        Self::item(
+            "number",
+            value.clone(),
+            value,
+            &format!("Random number from {} to {} (gen <min>-<max>)", min, max),
+        )
+    }
+
+    /// "1-6", "1..100", "1 to 10", "5 50"
+    fn parse_range(range: &str) -> Option<(i64, i64)> {
+        let range = range.trim();
+        let (min, max) = range
+            .split_once("..")
+            .or_else(|| range.split_once(" to "))
+            .or_else(|| range.split_once(' '))
+            // Skip the first char so "-5-5" splits after the sign
+            .or_else(|| range.get(1..)?.find('-').map(|i| (&range[..i + 1], &range[i + 2..])))?;
+        let (min, max) = (min.trim().parse::<i64>().ok()?, max.trim().parse::<i64>().ok()?);
+        Some((min.min(max), min.max(max)))
+    }
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(Generator::parse_range("1-6"), Some((1, 6)));
+        assert_eq!(Generator::parse_range("-5-5"), Some((-5, 5)));
+        assert_eq!(Generator::parse_range("1..100"), Some((1, 100)));
+        assert_eq!(Generator::parse_range("10 to 1"), Some((1, 10)));
+        assert_eq!(Generator::parse_range("uuid"), None);
+    }
+}
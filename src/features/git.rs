@@ -0,0 +1,300 @@
+//! Git repositories under the configured roots, with their branch and
+//! whether they have uncommitted changes. Enter opens a repository in the
+//! editor; the best match also gets terminal, browser and clone URL actions.
+
+use crate::core::config::GitConfig;
+use crate::core::{Config, Item, ItemType};
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, OnceLock};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Repo {
+    path: PathBuf,
+    /// Branch, or the short commit hash when detached
+    branch: Option<String>,
+    /// Files with uncommitted changes
+    changes: usize,
+    /// `origin`'s URL
+    remote: Option<String>,
+}
+
+/// Cloning is cheap: clones share the repositories found on first use.
+#[derive(Clone)]
+pub struct GitManager {
+    roots: Vec<PathBuf>,
+    depth: usize,
+    editor: String,
+    terminal: String,
+    repos: Arc<OnceLock<Vec<Repo>>>,
+}
+
+impl GitManager {
+    pub fn new(config: &Config) -> Self {
+        let git: &GitConfig = &config.git;
+        Self {
+            roots: git.roots().iter().map(|root| Config::expand_home(root)).collect(),
+            depth: git.depth(),
+            editor: git.editor().to_string(),
+            terminal: git.terminal().to_string(),
+            repos: Arc::new(OnceLock::new()),
+        }
+    }
+
+    fn repos(&self) -> &[Repo] {
+        self.repos.get_or_init(|| {
+            let mut paths = Vec::new();
+            for root in &self.roots {
+                Self::find_repos(root, self.depth, &mut paths);
+            }
+            paths.sort();
+            paths.dedup();
+            // `git status` is the slow part, so repositories are checked in parallel
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = paths.into_iter().map(|path| scope.spawn(move || Self::inspect(path))).collect();
+                handles.into_iter().filter_map(|handle| handle.join().ok()).collect()
+            })
+        })
+    }
+
+    /// Directories with a `.git` below `dir`, not looking inside repositories.
+    fn find_repos(dir: &Path, depth: usize, repos: &mut Vec<PathBuf>) {
+        if dir.join(".git").exists() {
+            repos.push(dir.to_path_buf());
+            return;
+        }
+        if depth == 0 {
+            return;
+        }
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if !hidden && entry.file_type().is_ok_and(|t| t.is_dir()) {
+                Self::find_repos(&entry.path(), depth - 1, repos);
+            }
+        }
+    }
+
+    fn inspect(path: PathBuf) -> Repo {
+        let git_dir = Self::git_dir(&path);
+        let branch = fs::read_to_string(git_dir.join("HEAD")).ok().and_then(|head| Self::parse_head(&head));
+        let remote = fs::read_to_string(Self::common_dir(&git_dir).join("config"))
+            .ok()
+            .and_then(|config| Self::parse_origin(&config));
+        let changes = Command::new("git")
+            .arg("-C")
+            .arg(&path)
+            .args(["status", "--porcelain"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map_or(0, |output| String::from_utf8_lossy(&output.stdout).lines().count());
+        Repo { path, branch, changes, remote }
+    }
+
+    /// `.git`, or the directory a worktree's `.git` file points to.
+    fn git_dir(path: &Path) -> PathBuf {
+        let dot_git = path.join(".git");
+        match fs::read_to_string(&dot_git) {
+            Ok(content) => match content.trim().strip_prefix("gitdir:") {
+                Some(dir) => path.join(dir.trim()),
+                None => dot_git,
+            },
+            Err(_) => dot_git,
+        }
+    }
+
+    /// The main repository's git directory, where a worktree's config lives.
+    fn common_dir(git_dir: &Path) -> PathBuf {
+        match fs::read_to_string(git_dir.join("commondir")) {
+            Ok(common) => git_dir.join(common.trim()),
+            Err(_) => git_dir.to_path_buf(),
+        }
+    }
+
+    fn parse_head(head: &str) -> Option<String> {
+        let head = head.trim();
+        match head.strip_prefix("ref: refs/heads/") {
+            Some(branch) => Some(branch.to_string()),
+            None => head.get(..7).map(str::to_string),
+        }
+    }
+
+    fn parse_origin(config: &str) -> Option<String> {
+        let section = Regex::new(r#"(?s)\[remote "origin"\](.*?)(?:\n\[|$)"#).ok()?;
+        let url = Regex::new(r"(?m)^\s*url\s*=\s*(\S+)").ok()?;
+        let origin = section.captures(config)?;
+        Some(url.captures(&origin[1])?[1].to_string())
+    }
+
+    /// The web page of a clone URL, for `git@github.com:user/repo.git` and
+    /// `https://` or `ssh://` URLs.
+    fn web_url(remote: &str) -> Option<String> {
+        let remote = remote.trim_end_matches('/').trim_end_matches(".git");
+        if remote.starts_with("file:") {
+            return None;
+        }
+        let (host, path) = if let Some(rest) = remote.strip_prefix("https://").or_else(|| remote.strip_prefix("http://")) {
+            rest.split_once('/')?
+        } else if let Some(rest) = remote.strip_prefix("ssh://").or_else(|| remote.strip_prefix("git://")) {
+            rest.split_once('/')?
+        } else {
+            // scp-like `user@host:path`
+            remote.split_once(':')?
+        };
+        let host = host.rsplit('@').next()?;
+        // ssh://host:2222/path
+        let host = host.split(':').next()?;
+        Some(format!("https://{}/{}", host, path))
+    }
+
+    fn to_item(repo: &Repo) -> Item {
+        let name = repo.path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let location = match dirs::home_dir().and_then(|home| repo.path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+            Some(relative) => format!("~/{}", relative.display()),
+            None => repo.path.display().to_string(),
+        };
+        let status = match repo.changes {
+            0 => "clean".to_string(),
+            1 => "1 change".to_string(),
+            n => format!("{} changes", n),
+        };
+        let branch = repo.branch.as_deref().unwrap_or("no branch");
+
+        let mut item = Item::new(format!("git:{}", repo.path.display()), name, ItemType::GitRepo)
+            .with_description(format!("{} | {} | {}", location, branch, status))
+            .with_icon(if repo.changes > 0 { "folder-important" } else { "folder" })
+            .with_keywords(vec![location, branch.to_string()]);
+        item.metadata.path = Some(repo.path.clone());
+        item
+    }
+
+    /// Terminal, browser and clone URL actions for `repo`.
+    fn action_items(repo: &Repo) -> Vec<Item> {
+        let mut items = Vec::new();
+        let action = |id: &str, name: &str, icon: &str| {
+            let mut item = Item::new(format!("git:{}:{}", id, repo.path.display()), name, ItemType::GitAction)
+                .with_icon(icon);
+            item.metadata.path = Some(repo.path.clone());
+            item
+        };
+
+        let terminal = action("terminal", "    Open in terminal", "utilities-terminal");
+        items.push(terminal.with_description(repo.path.display().to_string()));
+        if let Some(remote) = &repo.remote {
+            if let Some(url) = Self::web_url(remote) {
+                let mut item = action("browse", "    Open remote in browser", "web-browser").with_description(url.clone());
+                item.metadata.url = Some(url);
+                items.push(item);
+            }
+            let mut item = action("clone", "    Copy clone URL", "edit-copy").with_description(remote.clone());
+            item.metadata.content = Some(remote.clone());
+            items.push(item);
+        }
+        items
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let repos = self.repos();
+        if repos.is_empty() {
+            let roots: Vec<String> = self.roots.iter().map(|root| root.display().to_string()).collect();
+            return vec![Item::new("git:none", "No repositories found", ItemType::Command)
+                .with_description(format!("Looked in {}; set git.roots to change", roots.join(", ")))
+                .with_icon("dialog-information")];
+        }
+
+        let query = query.trim();
+        let mut matches: Vec<(i64, &Repo)> = repos
+            .iter()
+            .map(|repo| (if query.is_empty() { 1 } else { Self::to_item(repo).fuzzy_score(query) }, repo))
+            .filter(|(score, _)| *score > 0)
+            .collect();
+        // Stable, so an empty query keeps the path order
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+        let mut items = Vec::new();
+        for (i, (_, repo)) in matches.into_iter().enumerate() {
+            items.push(Self::to_item(repo));
+            if i == 0 {
+                items.extend(Self::action_items(repo));
+            }
+        }
+        items
+    }
+
+    /// Opens the repository in the editor.
+    pub fn open_in_editor(&self, path: &Path) {
+        // The path is passed as $1 so it isn't parsed by the shell
+        let result = Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$1\"", self.editor))
+            .arg("sh")
+            .arg(path)
+            .spawn();
+        if let Err(e) = result {
+            log::error!("Failed to open {} in {}: {}", path.display(), self.editor, e);
+        }
+    }
+
+    pub fn open_in_terminal(&self, path: &Path) {
+        if let Err(e) = Command::new("sh").arg("-c").arg(&self.terminal).current_dir(path).spawn() {
+            log::error!("Failed to open a terminal in {}: {}", path.display(), e);
+        }
+    }
+}
+
+impl Default for GitManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_web_url() {
+        let web_url = |remote| GitManager::web_url(remote);
+        assert_eq!(web_url("git@github.com:me/app.git").as_deref(), Some("https://github.com/me/app"));
+        assert_eq!(web_url("https://gitlab.com/group/sub/app.git").as_deref(), Some("https://gitlab.com/group/sub/app"));
+        assert_eq!(web_url("ssh://git@git.example.com:2222/team/app").as_deref(), Some("https://git.example.com/team/app"));
+        assert_eq!(web_url("https://user@bitbucket.org/team/app.git").as_deref(), Some("https://bitbucket.org/team/app"));
+        assert_eq!(web_url("/srv/git/app.git"), None);
+    }
+
+    #[test]
+    fn test_parse_git_files() {
+        assert_eq!(GitManager::parse_head("ref: refs/heads/feature/login\n").as_deref(), Some("feature/login"));
+        assert_eq!(GitManager::parse_head("3f2a9c1d8e7b6a5f4e3d2c1b0a9f8e7d6c5b4a39\n").as_deref(), Some("3f2a9c1"));
+
+        let config = r#"[core]
+	bare = false
+[remote "upstream"]
+	url = https://example.com/up.git
+[remote "origin"]
+	url = git@github.com:me/app.git
+	fetch = +refs/heads/*:refs/remotes/origin/*
+[branch "main"]
+	remote = origin
+"#;
+        assert_eq!(GitManager::parse_origin(config).as_deref(), Some("git@github.com:me/app.git"));
+    }
+
+    #[test]
+    fn test_find_repos() {
+        let root = std::env::temp_dir().join(format!("wlaunch-test-git-{}", std::process::id()));
+        for dir in ["app/.git", "app/vendor/lib/.git", "group/tool/.git", "notes", ".hidden/x/.git"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        let mut repos = Vec::new();
+        GitManager::find_repos(&root, 3, &mut repos);
+        repos.sort();
+        let _ = fs::remove_dir_all(&root);
+        assert_eq!(repos, [root.join("app"), root.join("group/tool")]);
+    }
+}
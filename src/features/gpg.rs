@@ -0,0 +1,333 @@
+//! GnuPG keys: copy a public key or fingerprint, encrypt the clipboard to a
+//! key, decrypt the clipboard and clear-sign a short text. gpg-agent asks
+//! for passphrases with its own pinentry.
+
+use crate::core::{CommandRunner, Item, ItemType, SystemRunner};
+use chrono::DateTime;
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+const PGP_MESSAGE: &str = "-----BEGIN PGP MESSAGE-----";
+
+#[derive(Debug, Default)]
+struct Key {
+    fingerprint: String,
+    /// Primary user id first, e.g. "Alex Doe <alex@example.com>"
+    uids: Vec<String>,
+    /// Validity, `e` expired and `r` revoked
+    validity: String,
+    /// Unix time, if the key expires
+    expires: Option<i64>,
+    /// Whether any (sub)key can encrypt
+    can_encrypt: bool,
+    /// Whether the secret key is in the keyring, so it can sign
+    secret: bool,
+}
+
+impl Key {
+    fn name(&self) -> &str {
+        self.uids.first().map_or(&self.fingerprint, String::as_str)
+    }
+
+    fn usable(&self) -> bool {
+        !matches!(self.validity.as_str(), "e" | "r")
+    }
+}
+
+/// `--with-colons` listing: a `pub` (or `sec`) record starts each key, and the first
+/// `fpr` after it is the primary key's fingerprint.
+fn parse_keys(stdout: &str) -> Vec<Key> {
+    let mut keys: Vec<Key> = Vec::new();
+    for line in stdout.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        let field = |i: usize| fields.get(i).copied().unwrap_or_default();
+        match field(0) {
+            "pub" | "sec" => keys.push(Key {
+                validity: field(1).to_string(),
+                expires: field(6).parse().ok(),
+                // Upper case letters are the capabilities of the whole key
+                can_encrypt: field(11).contains('E'),
+                ..Default::default()
+            }),
+            "fpr" => {
+                if let Some(key) = keys.last_mut().filter(|key| key.fingerprint.is_empty()) {
+                    key.fingerprint = field(9).to_string();
+                }
+            }
+            "uid" if field(1) != "r" => {
+                if let Some(key) = keys.last_mut() {
+                    key.uids.push(field(9).to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    keys
+}
+
+/// `0123 4567 89AB ...`, as gpg prints it.
+fn group(fingerprint: &str) -> String {
+    fingerprint
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// gpg's stderr as one line; the last line alone is often just "encryption
+/// failed", with the reason, e.g. an untrusted key, before it.
+fn error_message(stderr: &str) -> String {
+    let lines: Vec<&str> = stderr
+        .lines()
+        .map(|line| line.trim_start_matches("gpg: ").trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        "gpg failed".to_string()
+    } else {
+        lines.join("; ")
+    }
+}
+
+#[derive(Clone)]
+pub struct GpgManager {
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl GpgManager {
+    pub fn new() -> Self {
+        Self::with_runner(SystemRunner::shared())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Self {
+        Self { runner }
+    }
+
+    fn keys(&self) -> Result<Vec<Key>, Vec<Item>> {
+        let mut keys = match self.runner.run("gpg", &["--list-keys", "--with-colons"]) {
+            Ok(output) if output.status.success() => parse_keys(&String::from_utf8_lossy(&output.stdout)),
+            Ok(output) => return Err(Item::command_error_items("gpg", "Can't list GPG keys", &output)),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Err(Item::error_items("gpg", "gpg not found", "Install GnuPG to manage keys"))
+            }
+            Err(e) => return Err(Item::error_items("gpg", "Failed to run gpg", e.to_string())),
+        };
+        if let Ok(output) = self.runner.run("gpg", &["--list-secret-keys", "--with-colons"]) {
+            let secret = parse_keys(&String::from_utf8_lossy(&output.stdout));
+            for key in &mut keys {
+                key.secret = secret.iter().any(|other| other.fingerprint == key.fingerprint);
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Keys matching the query by user id or fingerprint, with actions for
+    /// the best match; `gpg sign <text>` lists the keys that can sign it.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim();
+        let mut keys = match self.keys() {
+            Ok(keys) => keys,
+            Err(items) => return items,
+        };
+
+        if let Some(text) = query.strip_prefix("sign ").map(str::trim).filter(|text| !text.is_empty()) {
+            keys.retain(|key| key.secret && key.usable());
+            if keys.is_empty() {
+                return vec![Item::new("gpg:none", "No secret keys to sign with", ItemType::Command).with_icon("dialog-information")];
+            }
+            return keys
+                .iter()
+                .map(|key| {
+                    Item::new(format!("gpg:sign:{}", key.fingerprint), format!("Sign \"{}\"", text), ItemType::GpgAction)
+                        .with_description(format!("As {}, copies the signed text", key.name()))
+                        .with_icon("document-edit")
+                })
+                .collect();
+        }
+
+        let filter = query.to_lowercase().replace(' ', "");
+        keys.retain(|key| {
+            key.fingerprint.to_lowercase().contains(&filter)
+                || key.uids.iter().any(|uid| uid.to_lowercase().replace(' ', "").contains(&filter))
+        });
+
+        let mut items = Vec::new();
+        if query.is_empty() {
+            items.push(
+                Item::new("gpg:decrypt", "Decrypt clipboard", ItemType::GpgAction)
+                    .with_description("Copies the decrypted message; type sign <text> to clear-sign a text")
+                    .with_icon("dialog-password"),
+            );
+        }
+        for key in &keys {
+            let mut details = vec![group(&key.fingerprint)];
+            match key.validity.as_str() {
+                "e" => details.push("expired".to_string()),
+                "r" => details.push("revoked".to_string()),
+                _ => {
+                    if let Some(expires) = key.expires.and_then(|secs| DateTime::from_timestamp(secs, 0)) {
+                        details.push(format!("expires {}", expires.format("%Y-%m-%d")));
+                    }
+                }
+            }
+            if key.secret {
+                details.push("secret key".to_string());
+            }
+            let mut item = Item::new(format!("gpg:key:{}", key.fingerprint), key.name(), ItemType::GpgKey)
+                .with_description(details.join(" | "))
+                .with_icon(if key.secret { "dialog-password" } else { "contact-new" });
+            item.metadata.content = Some(key.uids.join("\n"));
+            items.push(item);
+
+            // Actions for the best match only, like the git mode
+            if items.len() == 1 && !query.is_empty() {
+                if key.can_encrypt && key.usable() {
+                    items.push(
+                        Item::new(format!("gpg:encrypt:{}", key.fingerprint), "    Encrypt clipboard", ItemType::GpgAction)
+                            .with_description(format!("To {}, copies the armored message", key.name()))
+                            .with_icon("document-encrypt"),
+                    );
+                }
+                items.push(
+                    Item::new(format!("gpg:fingerprint:{}", key.fingerprint), "    Copy fingerprint", ItemType::GpgAction)
+                        .with_description(group(&key.fingerprint))
+                        .with_icon("edit-copy"),
+                );
+            }
+        }
+        if items.is_empty() {
+            items.push(Item::new("gpg:none", format!("No keys match \"{}\"", query), ItemType::Command).with_icon("dialog-information"));
+        }
+        items
+    }
+
+    /// Runs gpg with `input` on stdin, returning stdout.
+    fn pipe(&self, args: &[&str], input: &str) -> Result<String, String> {
+        let mut child = Command::new("gpg")
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run gpg: {}", e))?;
+        // Written from a thread, so gpg can't block on a full stdout pipe
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let input = input.to_string();
+        let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let output = child.wait_with_output().map_err(|e| format!("Failed to run gpg: {}", e))?;
+        let _ = writer.join();
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        } else {
+            Err(error_message(&String::from_utf8_lossy(&output.stderr)))
+        }
+    }
+
+    /// The text to copy for an action and a notification summary, or why
+    /// it failed. `query` is the mode query, for `sign <text>`.
+    pub fn execute_action(&self, action_id: &str, query: &str, clipboard: Option<&str>) -> Result<(String, String), String> {
+        let clipboard = clipboard.unwrap_or_default();
+        let mut parts = action_id.splitn(3, ':');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("gpg"), Some("decrypt"), None) => {
+                if !clipboard.trim_start().starts_with(PGP_MESSAGE) {
+                    return Err("The clipboard doesn't hold a PGP message".to_string());
+                }
+                Ok((self.pipe(&["--decrypt", "--quiet"], clipboard)?, "Decrypted message copied".to_string()))
+            }
+            (Some("gpg"), Some("encrypt"), Some(fingerprint)) => {
+                if clipboard.is_empty() {
+                    return Err("The clipboard is empty".to_string());
+                }
+                // In batch mode a key that isn't validated fails with gpg's
+                // trust error rather than a prompt there's no terminal for
+                let args = ["--batch", "--armor", "--encrypt", "--recipient", fingerprint];
+                Ok((self.pipe(&args, clipboard)?, "Encrypted message copied".to_string()))
+            }
+            (Some("gpg"), Some("sign"), Some(fingerprint)) => {
+                let text = query.trim().strip_prefix("sign").unwrap_or_default().trim();
+                Ok((self.pipe(&["--clearsign", "--local-user", fingerprint], text)?, "Signed text copied".to_string()))
+            }
+            (Some("gpg"), Some("key"), Some(fingerprint)) => match self.runner.run("gpg", &["--armor", "--export", fingerprint]) {
+                Ok(output) if output.status.success() => {
+                    Ok((String::from_utf8_lossy(&output.stdout).into_owned(), "Public key copied".to_string()))
+                }
+                Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+                Err(e) => Err(format!("Failed to run gpg: {}", e)),
+            },
+            (Some("gpg"), Some("fingerprint"), Some(fingerprint)) => Ok((fingerprint.to_string(), "Fingerprint copied".to_string())),
+            _ => Err(format!("Unknown action {}", action_id)),
+        }
+    }
+}
+
+impl Default for GpgManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    fn manager() -> GpgManager {
+        let keys = include_str!("../../tests/fixtures/gpg_list_keys.txt");
+        let runner = FakeRunner::new()
+            .with("gpg --list-keys --with-colons", keys)
+            .with("gpg --list-secret-keys --with-colons", &keys.lines().take(7).collect::<Vec<_>>().join("\n").replace("pub:", "sec:"))
+            .shared();
+        GpgManager::with_runner(runner)
+    }
+
+    #[test]
+    fn test_keys() {
+        let items = manager().get_items("");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["Decrypt clipboard", "Alex Doe <alex@example.com>", "Sam Lee <sam@example.org>", "Old Key <old@example.net>"]
+        );
+        assert_eq!(
+            items[1].description.as_deref(),
+            Some("0123 4567 89AB CDEF 0123 4567 5E6F 7081 92A3 B4C5 | expires 2027-11-12 | secret key")
+        );
+        assert!(items[3].description.as_deref().unwrap().ends_with(" | expired"));
+    }
+
+    #[test]
+    fn test_actions() {
+        let items = manager().get_items("alex@work");
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "gpg:key:0123456789ABCDEF012345675E6F708192A3B4C5",
+                "gpg:encrypt:0123456789ABCDEF012345675E6F708192A3B4C5",
+                "gpg:fingerprint:0123456789ABCDEF012345675E6F708192A3B4C5",
+            ]
+        );
+        // Expired keys can't be encrypted to
+        assert_eq!(manager().get_items("old").len(), 2);
+
+        // Only secret keys sign
+        let items = manager().get_items("sign hello there");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "Sign \"hello there\"");
+        assert_eq!(items[0].id, "gpg:sign:0123456789ABCDEF012345675E6F708192A3B4C5");
+    }
+
+    #[test]
+    fn test_error_message() {
+        let stderr = "gpg: 5E6F708192A3B4C5: There is no assurance this key belongs to the named user\n\
+                      gpg: [stdin]: encryption failed: Unusable public key\n";
+        assert_eq!(
+            error_message(stderr),
+            "5E6F708192A3B4C5: There is no assurance this key belongs to the named user; [stdin]: encryption failed: Unusable public key"
+        );
+        assert_eq!(error_message(""), "gpg failed");
+    }
+}
@@ -0,0 +1,174 @@
+//! Locking the screen, and keeping it awake: a `systemd-inhibit` process
+//! holds an idle and sleep inhibitor for a while or until turned off, and
+//! keeps running after the launcher closes.
+
+use super::focus::format_minutes;
+use super::TimerManager;
+use crate::core::{Config, Item, ItemType};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct InhibitState {
+    /// The systemd-inhibit process
+    #[serde(default)]
+    pid: Option<u32>,
+    /// When it lets go; `None` lasts until turned off
+    #[serde(default)]
+    until: Option<SystemTime>,
+}
+
+#[derive(Clone, Default)]
+pub struct IdleManager;
+
+impl IdleManager {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn data_path() -> PathBuf {
+        Config::data_path("inhibit.json")
+    }
+
+    fn load() -> Result<InhibitState> {
+        let path = Self::data_path();
+        if path.exists() {
+            Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+        } else {
+            Ok(InhibitState::default())
+        }
+    }
+
+    fn save(state: &InhibitState) -> Result<()> {
+        let path = Self::data_path();
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    /// The running inhibitor's pid; it exits by itself when its time is up.
+    fn running(state: &InhibitState) -> Option<u32> {
+        let pid = state.pid?;
+        let comm = fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+        (comm.trim() == "systemd-inhibit").then_some(pid)
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let state = Self::load().unwrap_or_default();
+        let inhibited = Self::running(&state)
+            .map(|_| state.until.map(|until| until.duration_since(SystemTime::now()).unwrap_or_default()));
+        Self::items(query, inhibited)
+    }
+
+    /// `inhibited` is `Some` while an inhibitor runs, with its time left if
+    /// it has an end.
+    fn items(query: &str, inhibited: Option<Option<Duration>>) -> Vec<Item> {
+        let query = query.trim();
+        let mut items = vec![Item::new("idle:lock", "Lock screen", ItemType::IdleAction)
+            .with_description("Locks the session now (loginctl lock-session)")
+            .with_icon("system-lock-screen")];
+
+        if let Some(remaining) = inhibited {
+            let name = match remaining {
+                Some(remaining) => format!("Stop keeping awake ({} left)", format_minutes(remaining)),
+                None => "Stop keeping awake (on until turned off)".to_string(),
+            };
+            items.push(
+                Item::new("idle:release", name, ItemType::IdleAction)
+                    .with_description("Lets the screen lock and the system sleep when idle again")
+                    .with_icon("media-playback-stop"),
+            );
+            return items;
+        }
+
+        let durations = match TimerManager::parse_duration(query) {
+            Some(duration) => vec![Some(duration)],
+            None => vec![Some(Duration::from_secs(3600)), None],
+        };
+        for duration in durations {
+            let (id, name) = match duration {
+                Some(duration) => (format!("idle:inhibit:{}", duration.as_secs()), format!("Keep awake for {}", format_minutes(duration))),
+                None => ("idle:inhibit".to_string(), "Keep awake until turned off".to_string()),
+            };
+            items.push(
+                Item::new(id, name, ItemType::IdleAction)
+                    .with_description("No screen lock or sleep when idle, e.g. for a presentation or a long download")
+                    .with_icon("preferences-desktop-screensaver"),
+            );
+        }
+        items
+    }
+
+    fn lock() -> Result<(), String> {
+        let locked = Command::new("loginctl").arg("lock-session").status().is_ok_and(|status| status.success());
+        // Desktops without logind locking, e.g. X11 with xscreensaver
+        if locked || Command::new("xdg-screensaver").arg("lock").status().is_ok_and(|status| status.success()) {
+            Ok(())
+        } else {
+            Err("Neither loginctl nor xdg-screensaver could lock the screen".to_string())
+        }
+    }
+
+    fn inhibit(duration: Option<Duration>) -> Result<(), String> {
+        Self::release();
+        let sleep = duration.map_or("infinity".to_string(), |duration| duration.as_secs().to_string());
+        let child = Command::new("systemd-inhibit")
+            .args(["--what=idle:sleep", "--who=wlaunch", "--why=Kept awake from wlaunch", "--mode=block", "sleep", &sleep])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to run systemd-inhibit: {}", e))?;
+        let state = InhibitState {
+            pid: Some(child.id()),
+            until: duration.map(|duration| SystemTime::now() + duration),
+        };
+        Self::save(&state).map_err(|e| format!("Failed to save the inhibitor: {}", e))
+    }
+
+    fn release() {
+        if let Some(pid) = Self::load().ok().as_ref().and_then(Self::running) {
+            if let Err(e) = Command::new("kill").arg(pid.to_string()).status() {
+                log::error!("Failed to stop systemd-inhibit ({}): {}", pid, e);
+            }
+        }
+        let _ = Self::save(&InhibitState::default());
+    }
+
+    /// Runs an action, returning what to notify.
+    pub fn execute_action(&self, action_id: &str) -> Result<Option<String>, String> {
+        match action_id {
+            "idle:lock" => Self::lock().map(|()| None),
+            "idle:release" => {
+                Self::release();
+                Ok(Some("The screen locks when idle again".to_string()))
+            }
+            "idle:inhibit" => Self::inhibit(None).map(|()| Some("Kept awake until turned off".to_string())),
+            id => {
+                let seconds = id.strip_prefix("idle:inhibit:").and_then(|s| s.parse().ok()).ok_or("Unknown action".to_string())?;
+                let duration = Duration::from_secs(seconds);
+                Self::inhibit(Some(duration)).map(|()| Some(format!("Kept awake for {}", format_minutes(duration))))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_items() {
+        let names = |items: Vec<Item>| items.into_iter().map(|i| i.name).collect::<Vec<_>>();
+        assert_eq!(names(IdleManager::items("", None)), ["Lock screen", "Keep awake for 1 h", "Keep awake until turned off"]);
+        assert_eq!(IdleManager::items("90m", None)[1].id, "idle:inhibit:5400");
+        assert_eq!(
+            names(IdleManager::items("", Some(Some(Duration::from_secs(600))))),
+            ["Lock screen", "Stop keeping awake (10 min left)"]
+        );
+        assert_eq!(IdleManager::items("", Some(None))[1].name, "Stop keeping awake (on until turned off)");
+    }
+}
@@ -0,0 +1,234 @@
+//! Usage insights, off unless `insights` is set: how long each mode's
+//! lookups take and how often they fail, per day, kept for a week in
+//! `insights.json`. Nothing is sent anywhere; the `stats` mode shows the
+//! numbers and `doctor` points out failing and slow features.
+
+use crate::core::{Config, Item, ItemType};
+use anyhow::Result;
+use chrono::{Duration as Days, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Days of history kept, today included.
+const KEEP_DAYS: i64 = 7;
+/// Lookups slower than this on average are pointed out by `doctor`.
+const SLOW_MS: u64 = 1000;
+
+/// Lookups run on several threads; each read-modify-write holds this.
+static WRITE: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Usage {
+    pub calls: u32,
+    pub errors: u32,
+    pub total_ms: u64,
+}
+
+impl Usage {
+    fn add(&mut self, other: &Usage) {
+        self.calls += other.calls;
+        self.errors += other.errors;
+        self.total_ms += other.total_ms;
+    }
+
+    fn average(&self) -> Duration {
+        Duration::from_millis(self.total_ms / u64::from(self.calls.max(1)))
+    }
+}
+
+/// Feature name to usage, per `YYYY-MM-DD` day.
+type History = BTreeMap<String, BTreeMap<String, Usage>>;
+
+#[derive(Clone, Default)]
+pub struct Insights;
+
+impl Insights {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn data_path() -> PathBuf {
+        Config::data_path("insights.json")
+    }
+
+    fn load() -> Result<History> {
+        let path = Self::data_path();
+        if path.exists() {
+            Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+        } else {
+            Ok(History::new())
+        }
+    }
+
+    fn store(history: &History) -> Result<()> {
+        let path = Self::data_path();
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, serde_json::to_string(history)?)?;
+        Ok(())
+    }
+
+    /// Records one lookup of `feature` that took `elapsed`.
+    pub fn record(feature: &str, elapsed: Duration, failed: bool) {
+        let _guard = WRITE.lock().unwrap_or_else(|e| e.into_inner());
+        let mut history = Self::load().unwrap_or_default();
+        Self::record_on(&mut history, Local::now().date_naive(), feature, elapsed, failed);
+        if let Err(e) = Self::store(&history) {
+            log::warn!("Failed to save usage insights: {}", e);
+        }
+    }
+
+    /// Adds the lookup to `today` and drops days older than a week.
+    fn record_on(history: &mut History, today: NaiveDate, feature: &str, elapsed: Duration, failed: bool) {
+        let usage = history
+            .entry(today.format("%Y-%m-%d").to_string())
+            .or_default()
+            .entry(feature.to_string())
+            .or_default();
+        usage.calls += 1;
+        usage.errors += u32::from(failed);
+        usage.total_ms += elapsed.as_millis() as u64;
+
+        let oldest = (today - Days::days(KEEP_DAYS - 1)).format("%Y-%m-%d").to_string();
+        history.retain(|day, _| *day >= oldest);
+    }
+
+    /// Each feature's usage today and over the kept week.
+    fn totals(history: &History, today: NaiveDate) -> BTreeMap<String, (Usage, Usage)> {
+        let today = today.format("%Y-%m-%d").to_string();
+        let mut totals: BTreeMap<String, (Usage, Usage)> = BTreeMap::new();
+        for (day, features) in history {
+            for (feature, usage) in features {
+                let (daily, weekly) = totals.entry(feature.clone()).or_default();
+                weekly.add(usage);
+                if *day == today {
+                    daily.add(usage);
+                }
+            }
+        }
+        totals
+    }
+
+    pub fn get_items(&self, query: &str, enabled: bool) -> Vec<Item> {
+        if !enabled {
+            return vec![Item::new("stats:off", "Usage insights are off", ItemType::Command)
+                .with_description("Set \"insights\": true in config.json to record lookup times and failures; nothing leaves this machine")
+                .with_icon("dialog-information")];
+        }
+        Self::items(query, &Self::load().unwrap_or_default(), Local::now().date_naive())
+    }
+
+    fn items(query: &str, history: &History, today: NaiveDate) -> Vec<Item> {
+        let query = query.trim().to_lowercase();
+        let mut totals: Vec<(String, (Usage, Usage))> = Self::totals(history, today)
+            .into_iter()
+            .filter(|(feature, _)| feature.to_lowercase().contains(&query))
+            .collect();
+        // Failing features first, then the slowest
+        totals.sort_by_key(|(_, (daily, weekly))| std::cmp::Reverse((daily.errors, weekly.average())));
+
+        let mut items: Vec<Item> = totals
+            .into_iter()
+            .map(|(feature, (daily, weekly))| {
+                let today = match (daily.calls, daily.errors) {
+                    (0, _) => "Not used today".to_string(),
+                    (calls, 0) => format!("{} lookups today", calls),
+                    (calls, errors) => format!("{} of {} lookups failed today", errors, calls),
+                };
+                Item::new(format!("stats:{}", feature), &feature, ItemType::SysInfo)
+                    .with_description(format!(
+                        "{} | average {} over {} lookups this week",
+                        today,
+                        Self::format_latency(weekly.average()),
+                        weekly.calls
+                    ))
+                    .with_icon(if daily.errors > 0 { "dialog-warning" } else { "utilities-system-monitor" })
+            })
+            .collect();
+        if items.is_empty() {
+            items.push(
+                Item::new("stats:empty", "Nothing recorded yet", ItemType::Command)
+                    .with_description("Lookups are recorded as modes like wifi, bw or docker are used")
+                    .with_icon("dialog-information"),
+            );
+        }
+        items
+    }
+
+    /// Features that failed today or are slow this week, for `doctor`.
+    pub fn problems(&self) -> Vec<Item> {
+        Self::problem_items(&Self::load().unwrap_or_default(), Local::now().date_naive())
+    }
+
+    fn problem_items(history: &History, today: NaiveDate) -> Vec<Item> {
+        let mut items = Vec::new();
+        for (feature, (daily, weekly)) in Self::totals(history, today) {
+            if daily.errors > 0 {
+                let times = if daily.errors == 1 { "once".to_string() } else { format!("{} times", daily.errors) };
+                items.push(
+                    Item::new(format!("doctor:failing:{}", feature), format!("Failing  {}", feature), ItemType::HealthCheck)
+                        .with_description(format!("{} lookups have failed {} today | see stats", feature.to_lowercase(), times))
+                        .with_icon("dialog-warning"),
+                );
+            } else if weekly.average() >= Duration::from_millis(SLOW_MS) {
+                items.push(
+                    Item::new(format!("doctor:slow:{}", feature), format!("Slow  {}", feature), ItemType::HealthCheck)
+                        .with_description(format!(
+                            "{} lookups average {} this week",
+                            feature.to_lowercase(),
+                            Self::format_latency(weekly.average())
+                        ))
+                        .with_icon("dialog-warning"),
+                );
+            }
+        }
+        items
+    }
+
+    /// `320 ms`, or `1.8 s` from a second on.
+    fn format_latency(duration: Duration) -> String {
+        if duration < Duration::from_secs(1) {
+            format!("{} ms", duration.as_millis())
+        } else {
+            format!("{:.1} s", duration.as_secs_f64())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insights() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let mut history = History::new();
+        Insights::record_on(&mut history, today - Days::days(7), "Wifi", Duration::from_millis(100), false);
+        for _ in 0..8 {
+            Insights::record_on(&mut history, today, "Wifi", Duration::from_millis(300), true);
+        }
+        Insights::record_on(&mut history, today - Days::days(1), "Bitwarden", Duration::from_millis(1500), false);
+        Insights::record_on(&mut history, today - Days::days(1), "Bitwarden", Duration::from_millis(2100), false);
+        Insights::record_on(&mut history, today, "Docker", Duration::from_millis(40), false);
+        // A week ago is dropped
+        assert_eq!(history.len(), 2);
+
+        let items = Insights::items("", &history, today);
+        let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, ["Wifi", "Bitwarden", "Docker"]);
+        assert_eq!(items[0].description.as_deref(), Some("8 of 8 lookups failed today | average 300 ms over 8 lookups this week"));
+        assert_eq!(items[1].description.as_deref(), Some("Not used today | average 1.8 s over 2 lookups this week"));
+
+        let problems: Vec<String> = Insights::problem_items(&history, today)
+            .into_iter()
+            .filter_map(|item| item.description)
+            .collect();
+        assert_eq!(
+            problems,
+            ["bitwarden lookups average 1.8 s this week", "wifi lookups have failed 8 times today | see stats"]
+        );
+    }
+}
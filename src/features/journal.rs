@@ -0,0 +1,235 @@
+//! Systemd journal: this boot's errors, a unit's recent log, and following
+//! a unit's log in a terminal.
+
+use crate::core::config::ModeConfig;
+use crate::core::{CommandRunner, Config, Item, ItemType, SystemRunner};
+use chrono::{DateTime, Local};
+use serde_json::Value;
+use std::io::ErrorKind;
+use std::process::Command;
+use std::sync::Arc;
+
+const PRIORITIES: [&str; 8] = ["emerg", "alert", "crit", "err", "warning", "notice", "info", "debug"];
+
+struct Entry {
+    cursor: String,
+    time: String,
+    /// journalctl flag selecting the entry's source, e.g. `--unit=nginx.service`
+    source_flag: String,
+    /// Unit, or the syslog identifier of messages outside units
+    source: String,
+    priority: Option<usize>,
+    message: String,
+}
+
+impl Entry {
+    /// One line of `journalctl -o json`.
+    fn parse(line: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(line).ok()?;
+        let field = |name: &str| value.get(name).and_then(Value::as_str);
+
+        // User services log to the system journal through user@<uid>.service
+        let (source_flag, source) = if let Some(unit) = field("_SYSTEMD_USER_UNIT") {
+            ("--user-unit", unit)
+        } else if let Some(unit) = field("_SYSTEMD_UNIT") {
+            ("--unit", unit)
+        } else {
+            ("--identifier", field("SYSLOG_IDENTIFIER").unwrap_or("kernel"))
+        };
+        let time = field("__REALTIME_TIMESTAMP")
+            .and_then(|usec| usec.parse::<i64>().ok())
+            .and_then(DateTime::from_timestamp_micros)
+            .map(|time| time.with_timezone(&Local).format("%b %d %H:%M:%S").to_string())
+            .unwrap_or_default();
+
+        Some(Entry {
+            cursor: field("__CURSOR")?.to_string(),
+            time,
+            source_flag: format!("{}={}", source_flag, source),
+            source: source.to_string(),
+            priority: field("PRIORITY").and_then(|p| p.parse().ok()),
+            // Binary messages are byte arrays
+            message: field("MESSAGE").unwrap_or("(binary message)").trim().to_string(),
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct JournalManager {
+    options: ModeConfig,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl JournalManager {
+    pub fn new(config: &Config) -> Self {
+        Self::with_runner(config, SystemRunner::shared())
+    }
+
+    pub fn with_runner(config: &Config, runner: Arc<dyn CommandRunner>) -> Self {
+        Self {
+            options: config.mode("logs"),
+            runner,
+        }
+    }
+
+    /// Entries of this boot, newest first.
+    fn entries(&self, filter: &[&str]) -> Result<Vec<Entry>, Vec<Item>> {
+        let limit = self.options.max_results.unwrap_or(100).to_string();
+        let mut args = vec!["--boot", "--reverse", "--output=json", "--no-pager", "--lines", &limit];
+        args.extend_from_slice(filter);
+        match self.runner.run("journalctl", &args) {
+            Ok(output) if output.status.success() => {
+                Ok(String::from_utf8_lossy(&output.stdout).lines().filter_map(Entry::parse).collect())
+            }
+            Ok(output) => Err(Item::command_error_items("logs", "Can't read the journal", &output)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(Item::error_items(
+                "logs",
+                "journalctl not found",
+                "The logs mode reads the systemd journal",
+            )),
+            Err(e) => Err(Item::error_items("logs", "Failed to run journalctl", e.to_string())),
+        }
+    }
+
+    /// `logs [text]` lists this boot's errors, `logs unit <name> [text]` all
+    /// recent messages of a unit.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim();
+        let (entries, filter) = match query.strip_prefix("unit ") {
+            Some(rest) => {
+                let (unit, filter) = rest.trim().split_once(' ').unwrap_or((rest.trim(), ""));
+                let system = self.entries(&[&format!("--unit={}", unit)]);
+                let entries = match system {
+                    // Not a system unit, so perhaps one of the user's
+                    Ok(entries) if entries.is_empty() => self.entries(&[&format!("--user-unit={}", unit)]),
+                    other => other,
+                };
+                (entries, filter.trim())
+            }
+            None => (self.entries(&["--priority=err"]), query),
+        };
+        let mut entries = match entries {
+            Ok(entries) => entries,
+            Err(items) => return items,
+        };
+
+        let filter = filter.to_lowercase();
+        entries.retain(|entry| {
+            entry.message.to_lowercase().contains(&filter) || entry.source.to_lowercase().contains(&filter)
+        });
+
+        let mut items = Vec::new();
+        for (i, entry) in entries.iter().enumerate() {
+            let priority = entry.priority.and_then(|p| PRIORITIES.get(p)).copied().unwrap_or("unknown");
+            items.push(
+                Item::new(format!("logs:open:{}:{}", entry.source_flag, entry.cursor), &entry.message, ItemType::LogEntry)
+                    .with_description(format!("{} | {} | {}", entry.time, entry.source, priority))
+                    .with_icon(if entry.priority.is_some_and(|p| p <= 3) { "dialog-error" } else { "text-x-generic" }),
+            );
+
+            // Actions for the best match only, like the git mode
+            if i == 0 && (!filter.is_empty() || query.starts_with("unit ")) {
+                items.push(
+                    Item::new(format!("logs:follow:{}", entry.source_flag), format!("    Follow {}", entry.source), ItemType::LogAction)
+                        .with_description("New messages as they arrive, in a terminal")
+                        .with_icon("utilities-terminal"),
+                );
+                if !query.starts_with("unit ") && entry.source_flag.contains("unit=") {
+                    items.push(
+                        Item::new(format!("logs:unit:{}", entry.source), format!("    All {} messages", entry.source), ItemType::LogAction)
+                            .with_description("Not only errors")
+                            .with_icon("view-list"),
+                    );
+                }
+            }
+        }
+
+        if items.is_empty() {
+            let title = if query.is_empty() { "No errors since boot".to_string() } else { format!("No messages match \"{}\"", query) };
+            items.push(Item::new("logs:none", title, ItemType::Command).with_icon("dialog-information"));
+        }
+        items
+    }
+
+    fn open_in_terminal(&self, args: &[&str]) -> bool {
+        match Command::new("x-terminal-emulator").arg("-e").arg("journalctl").args(args).spawn() {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("Failed to open a terminal for journalctl: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Opens an entry's source in the pager, at the end, or follows it.
+    /// Returns whether a terminal was opened, so the launcher can close.
+    pub fn execute_action(&self, action_id: &str) -> bool {
+        let mut parts = action_id.splitn(4, ':');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("logs"), Some("open"), Some(source)) => self.open_in_terminal(&["--boot", "--pager-end", source]),
+            (Some("logs"), Some("follow"), Some(source)) => self.open_in_terminal(&["--follow", source]),
+            _ => false,
+        }
+    }
+}
+
+impl Default for JournalManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    const ARGS: &str = "journalctl --boot --reverse --output=json --no-pager --lines 100";
+
+    fn manager() -> JournalManager {
+        let runner = FakeRunner::new()
+            .with(&format!("{} --priority=err", ARGS), include_str!("../../tests/fixtures/journalctl_errors.json"))
+            .with(&format!("{} --unit=foo.service", ARGS), "")
+            .with(
+                &format!("{} --user-unit=foo.service", ARGS),
+                r#"{"__CURSOR":"s=3","__REALTIME_TIMESTAMP":"1760000000000000","PRIORITY":"6","_SYSTEMD_USER_UNIT":"foo.service","MESSAGE":"Started foo"}"#,
+            )
+            .shared();
+        JournalManager::with_runner(&Config::default(), runner)
+    }
+
+    #[test]
+    fn test_errors() {
+        let items = manager().get_items("");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "nginx: [emerg] bind() to 0.0.0.0:80 failed (98: Address already in use)",
+                "(binary message)",
+                "usb 1-2: device descriptor read/64, error -71",
+            ]
+        );
+        assert!(items[0].description.as_deref().unwrap().ends_with(" | nginx.service | err"));
+        assert!(items[2].description.as_deref().unwrap().ends_with(" | kernel | err"));
+
+        let items = manager().get_items("nginx");
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "logs:open:--unit=nginx.service:s=1",
+                "logs:follow:--unit=nginx.service",
+                "logs:unit:nginx.service",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_user_unit() {
+        let items = manager().get_items("unit foo.service");
+        assert_eq!(items[0].name, "Started foo");
+        assert!(items[0].description.as_deref().unwrap().ends_with(" | foo.service | info"));
+        assert_eq!(items[1].id, "logs:follow:--user-unit=foo.service");
+    }
+}
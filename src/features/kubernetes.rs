@@ -0,0 +1,358 @@
+//! Kubernetes pods, contexts and namespaces via `kubectl`.
+
+use crate::core::config::{ModeConfig, SortOrder};
+use crate::core::{CommandRunner, Config, Item, ItemType, SystemRunner};
+use serde::Deserialize;
+use std::process::Command;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+struct PodList {
+    items: Vec<Pod>,
+}
+
+#[derive(Deserialize)]
+struct Pod {
+    metadata: PodMetadata,
+    #[serde(default)]
+    spec: PodSpec,
+    #[serde(default)]
+    status: PodStatus,
+}
+
+#[derive(Deserialize)]
+struct PodMetadata {
+    name: String,
+    namespace: String,
+}
+
+#[derive(Deserialize, Default)]
+struct PodSpec {
+    #[serde(default)]
+    containers: Vec<Container>,
+}
+
+#[derive(Deserialize)]
+struct Container {
+    #[serde(default)]
+    ports: Vec<ContainerPort>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContainerPort {
+    container_port: u16,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodStatus {
+    #[serde(default)]
+    phase: String,
+    #[serde(default)]
+    container_statuses: Vec<ContainerStatus>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContainerStatus {
+    #[serde(default)]
+    restart_count: u32,
+    #[serde(default)]
+    state: serde_json::Value,
+}
+
+impl Pod {
+    /// The phase, or why a container is stuck (CrashLoopBackOff,
+    /// ImagePullBackOff…), as `kubectl get pods` shows it.
+    fn status(&self) -> String {
+        self.status
+            .container_statuses
+            .iter()
+            .find_map(|c| c.state["waiting"]["reason"].as_str().or(c.state["terminated"]["reason"].as_str()))
+            .unwrap_or(&self.status.phase)
+            .to_string()
+    }
+
+    fn restarts(&self) -> u32 {
+        self.status.container_statuses.iter().map(|c| c.restart_count).sum()
+    }
+
+    fn ports(&self) -> Vec<u16> {
+        self.spec.containers.iter().flat_map(|c| c.ports.iter().map(|p| p.container_port)).collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct KubernetesManager {
+    options: ModeConfig,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl KubernetesManager {
+    pub fn new(config: &Config) -> Self {
+        Self::with_runner(config, SystemRunner::shared())
+    }
+
+    pub fn with_runner(config: &Config, runner: Arc<dyn CommandRunner>) -> Self {
+        Self {
+            options: config.mode("k8s"),
+            runner,
+        }
+    }
+
+    fn kubectl(&self, args: &[&str], what: &str) -> Result<String, Vec<Item>> {
+        match self.runner.run("kubectl", args) {
+            Ok(output) if output.status.success() => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+            Ok(output) => Err(Item::command_error_items("k8s", format!("Can't list {}", what), &output)),
+            Err(e) => Err(Item::error_items("k8s", "kubectl not found", format!("Failed to run kubectl: {}", e))),
+        }
+    }
+
+    fn current_context(&self) -> Option<String> {
+        self.kubectl(&["config", "current-context"], "contexts")
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    fn current_namespace(&self) -> String {
+        self.kubectl(&["config", "view", "--minify", "-o", "jsonpath={..namespace}"], "namespaces")
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "default".to_string())
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim();
+        if let Some(filter) = query.strip_prefix("ctx").filter(|r| r.is_empty() || r.starts_with(' ')) {
+            return self.context_items(filter.trim());
+        }
+        if let Some(filter) = query.strip_prefix("ns").filter(|r| r.is_empty() || r.starts_with(' ')) {
+            return self.namespace_items(filter.trim());
+        }
+        self.pod_items(query)
+    }
+
+    fn context_items(&self, filter: &str) -> Vec<Item> {
+        let stdout = match self.kubectl(&["config", "get-contexts", "-o", "name"], "contexts") {
+            Ok(stdout) => stdout,
+            Err(items) => return items,
+        };
+        let current = self.current_context();
+        let filter = filter.to_lowercase();
+        stdout
+            .lines()
+            .filter(|name| !name.is_empty() && name.to_lowercase().contains(&filter))
+            .map(|name| {
+                let active = current.as_deref() == Some(name);
+                Item::new(format!("k8s:context:{}", name), name, ItemType::KubeAction)
+                    .with_description(if active { "Current context" } else { "Switch to this context" })
+                    .with_icon(if active { "emblem-default" } else { "network-server" })
+            })
+            .collect()
+    }
+
+    fn namespace_items(&self, filter: &str) -> Vec<Item> {
+        let stdout = match self.kubectl(&["get", "namespaces", "-o", "name"], "namespaces") {
+            Ok(stdout) => stdout,
+            Err(items) => return items,
+        };
+        let current = self.current_namespace();
+        let filter = filter.to_lowercase();
+        stdout
+            .lines()
+            .map(|line| line.trim_start_matches("namespace/"))
+            .filter(|name| !name.is_empty() && name.to_lowercase().contains(&filter))
+            .map(|name| {
+                let active = current == name;
+                Item::new(format!("k8s:namespace:{}", name), name, ItemType::KubeAction)
+                    .with_description(if active { "Current namespace" } else { "Switch to this namespace" })
+                    .with_icon(if active { "emblem-default" } else { "folder" })
+            })
+            .collect()
+    }
+
+    fn pod_items(&self, query: &str) -> Vec<Item> {
+        let stdout = match self.kubectl(&["get", "pods", "-o", "json"], "pods") {
+            Ok(stdout) => stdout,
+            Err(items) => return items,
+        };
+        let mut pods = match serde_json::from_str::<PodList>(&stdout) {
+            Ok(list) => list.items,
+            Err(e) => return Item::error_items("k8s", "Can't read kubectl output", e.to_string()),
+        };
+
+        let query = query.to_lowercase();
+        pods.retain(|pod| query.is_empty() || pod.metadata.name.to_lowercase().contains(&query));
+        match self.options.sort {
+            Some(SortOrder::Name) => pods.sort_by(|a, b| a.metadata.name.cmp(&b.metadata.name)),
+            // Running pods first
+            Some(SortOrder::Status) => pods.sort_by_key(|pod| pod.status() != "Running"),
+            _ => {}
+        }
+        if let Some(max) = self.options.max_results {
+            pods.truncate(max);
+        }
+
+        let mut items = Vec::new();
+        if query.is_empty() {
+            let context = self.current_context().unwrap_or_else(|| "none".to_string());
+            items.push(
+                Item::new("k8s:list:ctx", format!("Context: {}", context), ItemType::KubeAction)
+                    .with_description("Switch context")
+                    .with_icon("network-server"),
+            );
+            items.push(
+                Item::new("k8s:list:ns", format!("Namespace: {}", self.current_namespace()), ItemType::KubeAction)
+                    .with_description("Switch namespace")
+                    .with_icon("folder"),
+            );
+        }
+        for (i, pod) in pods.iter().enumerate() {
+            let target = format!("{}/{}", pod.metadata.namespace, pod.metadata.name);
+            let status = pod.status();
+            let restarts = pod.restarts();
+            let mut item = Item::new(format!("k8s:logs:{}", target), &pod.metadata.name, ItemType::KubePod)
+                .with_description(format!(
+                    "{}{} | Enter shows logs",
+                    status,
+                    if restarts > 0 { format!(" | {} restarts", restarts) } else { String::new() }
+                ))
+                .with_icon(if status == "Running" || status == "Succeeded" {
+                    "media-playback-start"
+                } else {
+                    "dialog-warning"
+                });
+            item.metadata.container_status = Some(status);
+            items.push(item);
+
+            // Actions for the best match only, like the git mode
+            if i == 0 && !query.is_empty() {
+                for port in pod.ports() {
+                    items.push(
+                        Item::new(
+                            format!("k8s:forward:{}:{}", target, port),
+                            format!("    Port-forward {}", port),
+                            ItemType::KubeAction,
+                        )
+                        .with_description(format!("localhost:{} -> {}", port, pod.metadata.name))
+                        .with_icon("network-transmit-receive"),
+                    );
+                }
+                items.push(
+                    Item::new(format!("k8s:delete:{}", target), "    Delete pod", ItemType::KubeAction)
+                        .with_description(target.clone())
+                        .with_icon("edit-delete"),
+                );
+            }
+        }
+        items
+    }
+
+    fn open_in_terminal(&self, args: &[&str]) {
+        if let Err(e) = Command::new("x-terminal-emulator").arg("-e").arg("kubectl").args(args).spawn() {
+            log::error!("Failed to open a terminal for kubectl: {}", e);
+        }
+    }
+
+    /// Runs the action; returns whether a terminal was opened, so the
+    /// launcher can close.
+    pub fn execute_action(&self, action_id: &str) -> bool {
+        let Some((action, target)) = action_id.strip_prefix("k8s:").and_then(|id| id.split_once(':')) else {
+            return false;
+        };
+        match action {
+            "context" => {
+                let _ = self.runner.run("kubectl", &["config", "use-context", target]);
+            }
+            "namespace" => {
+                let namespace = format!("--namespace={}", target);
+                let _ = self.runner.run("kubectl", &["config", "set-context", "--current", &namespace]);
+            }
+            "logs" => {
+                if let Some((namespace, pod)) = target.split_once('/') {
+                    self.open_in_terminal(&["logs", "-f", "--all-containers", "-n", namespace, pod]);
+                    return true;
+                }
+            }
+            "delete" => {
+                if let Some((namespace, pod)) = target.split_once('/') {
+                    let _ = self.runner.run("kubectl", &["delete", "pod", "--wait=false", "-n", namespace, pod]);
+                }
+            }
+            "forward" => {
+                if let Some((namespace, rest)) = target.split_once('/') {
+                    if let Some((pod, port)) = rest.rsplit_once(':') {
+                        let pod = format!("pod/{}", pod);
+                        let ports = format!("{}:{}", port, port);
+                        self.open_in_terminal(&["port-forward", "-n", namespace, &pod, &ports]);
+                        return true;
+                    }
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+}
+
+impl Default for KubernetesManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    fn manager() -> KubernetesManager {
+        let runner = FakeRunner::new()
+            .with("kubectl get pods -o json", include_str!("../../tests/fixtures/kubectl_get_pods.json"))
+            .with("kubectl config current-context", "kind-dev\n")
+            .with("kubectl config view --minify -o jsonpath={..namespace}", "shop")
+            .with("kubectl config get-contexts -o name", "kind-dev\nprod-eu\n")
+            .with("kubectl get namespaces -o name", "namespace/default\nnamespace/kube-system\nnamespace/shop\n")
+            .shared();
+        KubernetesManager::with_runner(&Config::default(), runner)
+    }
+
+    #[test]
+    fn test_pods() {
+        let items = manager().get_items("");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["Context: kind-dev", "Namespace: shop", "api-7d9f8b6c5-x2kqp", "worker-5c7b9d4f8-m8zrt"]);
+        assert_eq!(items[2].description.as_deref(), Some("Running | Enter shows logs"));
+        assert_eq!(
+            items[3].description.as_deref(),
+            Some("CrashLoopBackOff | 7 restarts | Enter shows logs")
+        );
+
+        let items = manager().get_items("api");
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "k8s:logs:shop/api-7d9f8b6c5-x2kqp",
+                "k8s:forward:shop/api-7d9f8b6c5-x2kqp:8080",
+                "k8s:forward:shop/api-7d9f8b6c5-x2kqp:9090",
+                "k8s:delete:shop/api-7d9f8b6c5-x2kqp",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_contexts_and_namespaces() {
+        let items = manager().get_items("ctx");
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].description.as_deref(), Some("Current context"));
+        assert_eq!(items[1].id, "k8s:context:prod-eu");
+
+        let items = manager().get_items("ns kube");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "k8s:namespace:kube-system");
+    }
+}
@@ -1,41 +1,116 @@
 pub mod ai;
+pub mod alerts;
 pub mod audio;
+pub mod battery;
 pub mod bitwarden;
 pub mod bluetooth;
+pub mod bookmarks;
+pub mod brightness;
+pub mod browse;
 pub mod calculator;
+pub mod capture;
 pub mod clipboard;
 pub mod converter;
+pub mod crypto_tools;
+pub mod date_calc;
+pub mod disks;
+pub mod doctor;
+pub mod generator;
+pub mod git;
+pub mod gpg;
+pub mod idle;
+pub mod insights;
+pub mod journal;
+pub mod kubernetes;
 pub mod docker;
 pub mod emoji;
 pub mod files;
+pub mod flatpak;
+pub mod focus;
 pub mod network;
+pub mod night_mode;
 pub mod notes;
+pub mod packages;
+pub mod preview;
 pub mod processes;
+pub mod profiles;
+pub mod projects;
 pub mod recent_files;
+pub mod rofi;
+pub mod screenshot;
+pub mod session;
+pub mod shares;
+pub mod shell;
 pub mod snippets;
+pub mod sysinfo;
 pub mod ssh;
+pub mod tabs;
 pub mod timer;
+pub mod tmux;
 pub mod todos;
+pub mod totp;
+pub mod unicode;
+pub mod vms;
 pub mod websearch;
 pub mod windows;
+pub mod wine;
+pub mod world_clock;
+pub mod zoxide;
 
 pub use ai::AiManager;
+pub use alerts::SystemAlerts;
 pub use audio::AudioManager;
+pub use battery::BatteryManager;
 pub use bitwarden::BitwardenManager;
 pub use bluetooth::BluetoothManager;
+pub use bookmarks::BookmarksManager;
+pub use brightness::BrightnessManager;
+pub use browse::DirectoryBrowser;
 pub use calculator::Calculator;
-pub use clipboard::ClipboardManager;
+pub use capture::QuickCapture;
+pub use clipboard::{ClipboardManager, DaemonStatus};
 pub use converter::Converter;
+pub use crypto_tools::CryptoTools;
+pub use date_calc::DateCalculator;
+pub use disks::DisksManager;
+pub use doctor::Doctor;
+pub use generator::Generator;
+pub use git::GitManager;
+pub use gpg::GpgManager;
+pub use idle::IdleManager;
+pub use insights::Insights;
+pub use journal::JournalManager;
+pub use kubernetes::KubernetesManager;
 pub use docker::DockerManager;
 pub use emoji::EmojiManager;
-pub use files::FileManager;
+pub use files::{ContentSearch, FileActions, FileInput, FileManager, FILE_ACTIONS};
+pub use flatpak::FlatpakManager;
+pub use focus::FocusManager;
 pub use network::NetworkManager;
+pub use night_mode::NightModeManager;
 pub use notes::NotesManager;
+pub use packages::PackagesManager;
+pub use preview::{FilePreview, FilePreviewer};
 pub use processes::ProcessManager;
+pub use profiles::ProfileManager;
+pub use projects::ProjectsManager;
 pub use recent_files::RecentFilesManager;
-pub use snippets::SnippetsManager;
+pub use screenshot::ScreenshotManager;
+pub use session::SessionManager;
+pub use shares::{MountError, ShareLogin, SharesManager};
+pub use shell::{CaptureTarget, ShellManager};
+pub use snippets::{SnippetForm, SnippetsManager};
 pub use ssh::SshManager;
+pub use sysinfo::SysInfo;
+pub use tabs::TabsManager;
 pub use timer::TimerManager;
+pub use tmux::TmuxManager;
 pub use todos::TodosManager;
+pub use totp::TotpManager;
+pub use unicode::UnicodeSearch;
+pub use vms::VmManager;
 pub use websearch::WebSearchManager;
 pub use windows::WindowsManager;
+pub use wine::WineManager;
+pub use world_clock::WorldClock;
+pub use zoxide::ZoxideManager;
@@ -1,11 +1,18 @@
-use crate::core::{Item, ItemType};
-use std::process::Command;
+use crate::core::{CommandRunner, Item, ItemType, SystemRunner};
+use std::sync::Arc;
 
-pub struct NetworkManager;
+#[derive(Clone)]
+pub struct NetworkManager {
+    runner: Arc<dyn CommandRunner>,
+}
 
 impl NetworkManager {
     pub fn new() -> Self {
-        Self
+        Self::with_runner(SystemRunner::shared())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Self {
+        Self { runner }
     }
 
     pub fn get_items(&self, query: &str) -> Vec<Item> {
@@ -30,54 +37,53 @@ impl NetworkManager {
         );
 
         // Get available networks
-        if let Ok(output) = Command::new("nmcli")
-            .args(["-t", "-f", "SSID,SIGNAL,SECURITY,IN-USE", "device", "wifi", "list"])
-            .output()
-        {
-            if output.status.success() {
-                if let Ok(stdout) = String::from_utf8(output.stdout) {
-                    for line in stdout.lines() {
-                        let parts: Vec<&str> = line.split(':').collect();
-                        if parts.len() >= 4 {
-                            let ssid = parts[0];
-                            if ssid.is_empty() {
-                                continue;
-                            }
-
-                            let signal: i32 = parts[1].parse().unwrap_or(0);
-                            let security = parts[2];
-                            let in_use = parts[3] == "*";
-
-                            let mut item = Item::new(
-                                format!("wifi:{}", ssid),
-                                ssid,
-                                ItemType::WifiNetwork,
-                            )
-                            .with_description(format!(
-                                "Signal: {}% | {}{}",
-                                signal,
-                                security,
-                                if in_use { " (Connected)" } else { "" }
-                            ))
-                            .with_icon(if signal > 75 {
-                                "network-wireless-signal-excellent"
-                            } else if signal > 50 {
-                                "network-wireless-signal-good"
-                            } else if signal > 25 {
-                                "network-wireless-signal-ok"
-                            } else {
-                                "network-wireless-signal-weak"
-                            });
-
-                            item.metadata.ssid = Some(ssid.to_string());
-                            item.metadata.signal_strength = Some(signal);
-                            item.metadata.secured = !security.is_empty() && security != "--";
-                            item.metadata.connected = in_use;
-
-                            items.push(item);
-                        }
-                    }
+        let output = match self.runner.run("nmcli", &["-t", "-f", "SSID,SIGNAL,SECURITY,IN-USE", "device", "wifi", "list"]) {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => return Item::command_error_items("wifi", "Can't list WiFi networks", &output),
+            Err(e) => {
+                return Item::error_items("wifi", "nmcli is not available", format!("Failed to run nmcli: {}", e))
+            }
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for line in stdout.lines() {
+            let parts: Vec<&str> = line.split(':').collect();
+            if parts.len() >= 4 {
+                let ssid = parts[0];
+                if ssid.is_empty() {
+                    continue;
                 }
+
+                let signal: i32 = parts[1].parse().unwrap_or(0);
+                let security = parts[2];
+                let in_use = parts[3] == "*";
+
+                let mut item = Item::new(
+                    format!("wifi:{}", ssid),
+                    ssid,
+                    ItemType::WifiNetwork,
+                )
+                .with_description(format!(
+                    "Signal: {}% | {}{}",
+                    signal,
+                    security,
+                    if in_use { " (Connected)" } else { "" }
+                ))
+                .with_icon(if signal > 75 {
+                    "network-wireless-signal-excellent"
+                } else if signal > 50 {
+                    "network-wireless-signal-good"
+                } else if signal > 25 {
+                    "network-wireless-signal-ok"
+                } else {
+                    "network-wireless-signal-weak"
+                });
+
+                item.metadata.ssid = Some(ssid.to_string());
+                item.metadata.signal_strength = Some(signal);
+                item.metadata.secured = !security.is_empty() && security != "--";
+                item.metadata.connected = in_use;
+
+                items.push(item);
             }
         }
 
@@ -97,42 +103,29 @@ impl NetworkManager {
     }
 
     pub fn connect(&self, ssid: &str) {
-        let _ = Command::new("nmcli")
-            .args(["device", "wifi", "connect", ssid])
-            .output();
+        let _ = self.runner.run("nmcli", &["device", "wifi", "connect", ssid]);
     }
 
     pub fn disconnect(&self) {
-        let _ = Command::new("nmcli")
-            .args(["device", "disconnect", "wlan0"])
-            .output();
+        let _ = self.runner.run("nmcli", &["device", "disconnect", "wlan0"]);
     }
 
     pub fn toggle_wifi(&self) {
         // Check current state
-        if let Ok(output) = Command::new("nmcli")
-            .args(["radio", "wifi"])
-            .output()
-        {
+        if let Ok(output) = self.runner.run("nmcli", &["radio", "wifi"]) {
             if output.status.success() {
                 let state = String::from_utf8_lossy(&output.stdout);
                 if state.trim() == "enabled" {
-                    let _ = Command::new("nmcli")
-                        .args(["radio", "wifi", "off"])
-                        .output();
+                    let _ = self.runner.run("nmcli", &["radio", "wifi", "off"]);
                 } else {
-                    let _ = Command::new("nmcli")
-                        .args(["radio", "wifi", "on"])
-                        .output();
+                    let _ = self.runner.run("nmcli", &["radio", "wifi", "on"]);
                 }
             }
         }
     }
 
     pub fn scan(&self) {
-        let _ = Command::new("nmcli")
-            .args(["device", "wifi", "rescan"])
-            .output();
+        let _ = self.runner.run("nmcli", &["device", "wifi", "rescan"]);
     }
 
     pub fn execute_action(&self, action_id: &str) {
@@ -150,3 +143,40 @@ impl Default for NetworkManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    #[test]
+    fn test_nmcli_wifi_list() {
+        let runner = FakeRunner::new()
+            .with(
+                "nmcli -t -f SSID,SIGNAL,SECURITY,IN-USE device wifi list",
+                include_str!("../../tests/fixtures/nmcli_wifi_list.txt"),
+            )
+            .shared();
+        let items = NetworkManager::with_runner(runner).get_items("");
+        let networks: Vec<&Item> = items.iter().filter(|i| i.item_type == ItemType::WifiNetwork).collect();
+        // The hidden network without an SSID is skipped
+        assert_eq!(networks.len(), 3);
+
+        assert_eq!(networks[0].name, "HomeNet");
+        assert_eq!(networks[0].metadata.signal_strength, Some(82));
+        assert!(networks[0].metadata.secured && networks[0].metadata.connected);
+        assert!(!networks[1].metadata.secured && !networks[1].metadata.connected);
+        assert_eq!(networks[2].name, "Neighbour 5G");
+
+        let items = NetworkManager::with_runner(FakeRunner::new().shared()).get_items("");
+        assert_eq!(items.last().unwrap().item_type, ItemType::Error);
+
+        let runner = FakeRunner::new()
+            .failing("nmcli -t -f SSID,SIGNAL,SECURITY,IN-USE device wifi list", 8, "Error: NetworkManager is not running.\n")
+            .shared();
+        let items = NetworkManager::with_runner(runner).get_items("");
+        let error = items.iter().find(|i| i.id == "error:retry:wifi").unwrap();
+        assert_eq!(error.name, "Can't list WiFi networks");
+        assert_eq!(error.metadata.error.as_deref(), Some("Error: NetworkManager is not running."));
+    }
+}
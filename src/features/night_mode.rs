@@ -0,0 +1,318 @@
+//! Night mode: switches the theme preset and a gammastep night light on a
+//! schedule. The daemon calls [`NightModeManager::tick`] periodically; the
+//! display mode offers manual overrides that last until the next scheduled
+//! transition.
+
+use crate::core::config::NightModeConfig;
+use crate::core::{settings, Config, Item, ItemType};
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::process::Command;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct NightModeState {
+    /// Manually forced state, if any
+    #[serde(default)]
+    override_night: Option<bool>,
+    /// Scheduled state when the override was set; the override expires once
+    /// the schedule moves past it
+    #[serde(default)]
+    override_scheduled: bool,
+    /// Last state applied, so the daemon only acts on transitions
+    #[serde(default)]
+    applied: Option<bool>,
+}
+
+#[derive(Clone)]
+pub struct NightModeManager {
+    config: NightModeConfig,
+    state: NightModeState,
+}
+
+impl NightModeManager {
+    pub fn new() -> Self {
+        let config = Config::load().unwrap_or_default().night_mode;
+        let state = Self::load().unwrap_or_default();
+        Self { config, state }
+    }
+
+    fn data_path() -> std::path::PathBuf {
+        Config::data_path("night_mode.json")
+    }
+
+    fn load() -> Result<NightModeState> {
+        let path = Self::data_path();
+        if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            Ok(serde_json::from_str(&content)?)
+        } else {
+            Ok(NightModeState::default())
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = Self::data_path();
+        fs::create_dir_all(path.parent().unwrap())?;
+        let content = serde_json::to_string_pretty(&self.state)?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Whether the schedule says it's night at `now`.
+    pub fn is_scheduled_night(&self, now: DateTime<Local>) -> bool {
+        if let (Some(lat), Some(lon)) = (self.config.latitude, self.config.longitude) {
+            let today = now.with_timezone(&Utc).date_naive();
+            match sun_times(today.ordinal(), lat, lon) {
+                SunTimes::PolarNight => return true,
+                SunTimes::MidnightSun => return false,
+                SunTimes::Normal(..) => {}
+            }
+            // Away from Greenwich the UTC day cuts through daylight, so the
+            // day may have started yesterday or end tomorrow (UTC)
+            let daylight = [today.pred_opt(), Some(today), today.succ_opt()].into_iter().flatten().any(|date| {
+                let SunTimes::Normal(sunrise, sunset) = sun_times(date.ordinal(), lat, lon) else {
+                    return false;
+                };
+                let midnight = Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN));
+                let sunrise = midnight + Duration::seconds((sunrise * 60.0) as i64);
+                let sunset = midnight + Duration::seconds((sunset * 60.0) as i64);
+                now >= sunrise && now < sunset
+            });
+            return !daylight;
+        }
+
+        let parse = |s: &str| NaiveTime::parse_from_str(s, "%H:%M").ok();
+        match (parse(self.config.start()), parse(self.config.end())) {
+            (Some(start), Some(end)) => in_window(now.time(), start, end),
+            _ => false,
+        }
+    }
+
+    /// The state night mode should be in right now, dropping an override the
+    /// schedule has caught up with.
+    fn desired(&mut self, now: DateTime<Local>) -> bool {
+        let scheduled = self.is_scheduled_night(now);
+        match self.state.override_night {
+            Some(night) if self.state.override_scheduled == scheduled => night,
+            Some(_) => {
+                self.state.override_night = None;
+                scheduled
+            }
+            None => scheduled,
+        }
+    }
+
+    /// Applies the desired state if it changed since the last call.
+    pub fn tick(&mut self) {
+        if !self.config.enabled() && self.state.override_night.is_none() {
+            return;
+        }
+        let night = self.desired(Local::now());
+        if self.state.applied != Some(night) {
+            self.apply(night);
+        }
+        let _ = self.save();
+    }
+
+    fn apply(&mut self, night: bool) {
+        let preset = if night {
+            self.config.night_theme.clone()
+        } else {
+            self.config.day_theme.clone()
+        };
+        // Only the preset is written; a config.json that doesn't load is
+        // left alone rather than replaced with defaults
+        if let Some(preset) = preset {
+            if let Err(e) = settings::set("theme.preset", &serde_json::Value::from(preset).to_string()) {
+                log::warn!("Failed to switch the theme for night mode: {}", e);
+            }
+        }
+
+        if self.config.night_light() {
+            let _ = Command::new("pkill").args(["-x", "gammastep"]).output();
+            if night {
+                let _ = Command::new("gammastep")
+                    .args(["-P", "-O", &self.config.temperature().to_string()])
+                    .spawn();
+            } else {
+                let _ = Command::new("gammastep").arg("-x").output();
+            }
+        }
+
+        self.state.applied = Some(night);
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim().to_lowercase();
+        if !query.is_empty() && !"night mode".contains(&query) && !query.starts_with("night") {
+            return Vec::new();
+        }
+
+        let active = self.state.applied.unwrap_or(false);
+        let status = match self.state.override_night {
+            Some(_) => "manual override",
+            None if self.config.enabled() => "scheduled",
+            None => "schedule disabled",
+        };
+
+        let mut items = vec![Item::new(
+            "night:status",
+            format!("Night Mode: {}", if active { "On" } else { "Off" }),
+            ItemType::Display,
+        )
+        .with_description(format!("Currently {}", status))
+        .with_icon("weather-clear-night")];
+
+        if active {
+            items.push(
+                Item::new("night:off", "Turn Night Mode Off", ItemType::DisplayAction)
+                    .with_description("Until the next scheduled change")
+                    .with_icon("weather-clear"),
+            );
+        } else {
+            items.push(
+                Item::new("night:on", "Turn Night Mode On", ItemType::DisplayAction)
+                    .with_description("Until the next scheduled change")
+                    .with_icon("weather-clear-night"),
+            );
+        }
+
+        if self.state.override_night.is_some() {
+            items.push(
+                Item::new("night:auto", "Resume Night Mode Schedule", ItemType::DisplayAction)
+                    .with_description("Drop the manual override")
+                    .with_icon("appointment-soon"),
+            );
+        }
+
+        items
+    }
+
+    pub fn execute_action(&mut self, action_id: &str) {
+        let now = Local::now();
+        match action_id {
+            "night:on" | "night:off" => {
+                let night = action_id == "night:on";
+                self.state.override_night = Some(night);
+                self.state.override_scheduled = self.is_scheduled_night(now);
+                self.apply(night);
+            }
+            "night:auto" => {
+                self.state.override_night = None;
+                let night = self.is_scheduled_night(now);
+                self.apply(night);
+            }
+            _ => return,
+        }
+        let _ = self.save();
+    }
+}
+
+impl Default for NightModeManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether `time` falls in `[start, end)`, wrapping past midnight.
+fn in_window(time: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start <= end {
+        time >= start && time < end
+    } else {
+        time >= start || time < end
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum SunTimes {
+    /// Sunrise and sunset in minutes after UTC midnight
+    Normal(f64, f64),
+    PolarNight,
+    MidnightSun,
+}
+
+/// NOAA's approximate solar equations for a day of the year.
+fn sun_times(day_of_year: u32, lat: f64, lon: f64) -> SunTimes {
+    let gamma = 2.0 * std::f64::consts::PI / 365.0 * (day_of_year as f64 - 1.0);
+    let eqtime = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+    let decl = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+
+    let lat = lat.to_radians();
+    let cos_ha = 90.833_f64.to_radians().cos() / (lat.cos() * decl.cos()) - lat.tan() * decl.tan();
+    if cos_ha > 1.0 {
+        return SunTimes::PolarNight;
+    }
+    if cos_ha < -1.0 {
+        return SunTimes::MidnightSun;
+    }
+
+    let ha = cos_ha.acos().to_degrees();
+    SunTimes::Normal(
+        720.0 - 4.0 * (lon + ha) - eqtime,
+        720.0 - 4.0 * (lon - ha) - eqtime,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sun_times() {
+        // Equinox on the equator: roughly 06:00 to 18:00 UTC
+        let SunTimes::Normal(sunrise, sunset) = sun_times(80, 0.0, 0.0) else {
+            panic!("expected a normal day");
+        };
+        assert!((sunrise - 360.0).abs() < 15.0);
+        assert!((sunset - 1080.0).abs() < 15.0);
+
+        assert_eq!(sun_times(172, 80.0, 0.0), SunTimes::MidnightSun);
+        assert_eq!(sun_times(355, 80.0, 0.0), SunTimes::PolarNight);
+    }
+
+    #[test]
+    fn test_scheduled_night_away_from_greenwich() {
+        let at = |lat: f64, lon: f64, utc: &str| {
+            let config = NightModeConfig {
+                latitude: Some(lat),
+                longitude: Some(lon),
+                ..Default::default()
+            };
+            let manager = NightModeManager { config, state: NightModeState::default() };
+            let now = Utc.from_utc_datetime(&chrono::NaiveDateTime::parse_from_str(utc, "%Y-%m-%d %H:%M").unwrap());
+            manager.is_scheduled_night(now.with_timezone(&Local))
+        };
+
+        // Near the equinox at lon +120 the sun is up from about 22:00 to
+        // 10:00 UTC (06:00 to 18:00 local)
+        assert!(!at(30.0, 120.0, "2026-03-21 23:00"));
+        assert!(!at(30.0, 120.0, "2026-03-21 04:00"));
+        assert!(at(30.0, 120.0, "2026-03-21 12:00"));
+        assert!(at(30.0, 120.0, "2026-03-21 20:00"));
+
+        // At lon -120 it's up from about 14:00 to 02:00 UTC
+        assert!(!at(30.0, -120.0, "2026-03-21 01:00"));
+        assert!(!at(30.0, -120.0, "2026-03-21 20:00"));
+        assert!(at(30.0, -120.0, "2026-03-21 06:00"));
+        assert!(at(30.0, -120.0, "2026-03-21 13:00"));
+    }
+
+    #[test]
+    fn test_in_window() {
+        let t = |s| NaiveTime::parse_from_str(s, "%H:%M").unwrap();
+        assert!(in_window(t("23:00"), t("20:00"), t("07:00")));
+        assert!(in_window(t("06:59"), t("20:00"), t("07:00")));
+        assert!(!in_window(t("12:00"), t("20:00"), t("07:00")));
+        assert!(in_window(t("13:00"), t("12:00"), t("14:00")));
+    }
+}
@@ -1,8 +1,7 @@
-use crate::core::{Config, Item, ItemType};
+use crate::core::{Config, Item, ItemType, Keyed, SyncedStore};
 use anyhow::Result;
 use chrono::Local;
 use serde::{Deserialize, Serialize};
-use std::fs;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,37 +13,35 @@ struct Note {
     updated: String,
 }
 
+impl Keyed for Note {
+    fn key(&self) -> &str {
+        &self.id
+    }
+}
+
 pub struct NotesManager {
     notes: Vec<Note>,
+    store: SyncedStore,
 }
 
 impl NotesManager {
-    pub fn new() -> Self {
-        let notes = Self::load().unwrap_or_default();
-        Self { notes }
-    }
-
-    fn data_path() -> std::path::PathBuf {
-        Config::data_path("notes.json")
+    pub fn new(config: &Config) -> Self {
+        let mut store = SyncedStore::new(config, "notes.json");
+        let notes = store.load().unwrap_or_default();
+        Self { notes, store }
     }
 
-    fn load() -> Result<Vec<Note>> {
-        let path = Self::data_path();
-        if path.exists() {
-            let content = fs::read_to_string(&path)?;
-            let notes: Vec<Note> = serde_json::from_str(&content)?;
-            Ok(notes)
-        } else {
-            Ok(Vec::new())
+    /// Picks up changes synced from another machine since the last read.
+    pub fn reload_if_changed(&mut self) {
+        if self.store.changed() {
+            if let Ok(notes) = self.store.load() {
+                self.notes = notes;
+            }
         }
     }
 
-    fn save(&self) -> Result<()> {
-        let path = Self::data_path();
-        fs::create_dir_all(path.parent().unwrap())?;
-        let content = serde_json::to_string_pretty(&self.notes)?;
-        fs::write(path, content)?;
-        Ok(())
+    fn save(&mut self) -> Result<()> {
+        self.store.save(&mut self.notes)
     }
 
     pub fn get_items(&self, query: &str) -> Vec<Item> {
@@ -53,7 +50,7 @@ impl NotesManager {
 
         // Add action to create note
         if query.starts_with("add ") || query.starts_with("new ") {
-            let content = query.splitn(2, ' ').nth(1).unwrap_or("");
+            let content = query.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
             if !content.is_empty() {
                 items.push(
                     Item::new(
@@ -106,12 +103,18 @@ impl NotesManager {
         items
     }
 
-    pub fn add_note(&mut self, content: &str) {
-        let title = if content.len() > 30 {
-            format!("{}...", &content[..27])
+    /// The first line, shortened to fit the results list.
+    fn title(content: &str) -> String {
+        let line = content.lines().next().unwrap_or("");
+        if line.chars().count() > 30 {
+            format!("{}...", line.chars().take(27).collect::<String>())
         } else {
-            content.to_string()
-        };
+            line.to_string()
+        }
+    }
+
+    pub fn add_note(&mut self, content: &str) {
+        let title = Self::title(content);
 
         let now = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
         let note = Note {
@@ -126,6 +129,15 @@ impl NotesManager {
         let _ = self.save();
     }
 
+    pub fn update_note(&mut self, id: &str, content: &str) {
+        if let Some(note) = self.notes.iter_mut().find(|n| n.id == id) {
+            note.title = Self::title(content);
+            note.content = content.to_string();
+            note.updated = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+            let _ = self.save();
+        }
+    }
+
     pub fn delete_note(&mut self, id: &str) {
         self.notes.retain(|n| n.id != id);
         let _ = self.save();
@@ -146,7 +158,7 @@ impl NotesManager {
             let id = action_id.strip_prefix("note:delete:").unwrap();
             self.delete_note(id);
         } else if query.starts_with("add ") || query.starts_with("new ") {
-            let content = query.splitn(2, ' ').nth(1).unwrap_or("");
+            let content = query.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
             if !content.is_empty() {
                 self.add_note(content);
             }
@@ -156,6 +168,6 @@ impl NotesManager {
 
 impl Default for NotesManager {
     fn default() -> Self {
-        Self::new()
+        Self::new(&Config::default())
     }
 }
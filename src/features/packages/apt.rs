@@ -0,0 +1,70 @@
+use super::{run_search, Package, PackageBackend};
+use crate::core::{CommandRunner, Item};
+
+pub struct Apt;
+
+impl Apt {
+    /// `name/suite,now version arch [installed]` lines of `apt search`, each
+    /// followed by an indented description. Skips the "Sorting..." header.
+    fn parse(stdout: &str) -> Vec<Package> {
+        let mut packages: Vec<Package> = Vec::new();
+        for line in stdout.lines() {
+            if line.starts_with(char::is_whitespace) {
+                if let Some(package) = packages.last_mut() {
+                    package.description = line.trim().to_string();
+                }
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let (Some(qualified), Some(version)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            let Some((name, suites)) = qualified.split_once('/') else {
+                continue;
+            };
+            packages.push(Package {
+                name: name.to_string(),
+                repo: suites.split(',').next().unwrap_or(suites).to_string(),
+                version: version.to_string(),
+                description: String::new(),
+                installed: line.contains("[installed"),
+            });
+        }
+        packages
+    }
+}
+
+impl PackageBackend for Apt {
+    fn name(&self) -> &'static str {
+        "apt"
+    }
+
+    fn search(&self, runner: &dyn CommandRunner, query: &str) -> Result<Vec<Package>, Vec<Item>> {
+        run_search(runner, "apt", &["search", "--names-only", query], &[]).map(|stdout| Self::parse(&stdout))
+    }
+
+    fn install(&self, package: &str) -> Vec<String> {
+        vec!["sudo".into(), "apt".into(), "install".into(), package.into()]
+    }
+
+    fn remove(&self, package: &str) -> Vec<String> {
+        vec!["sudo".into(), "apt".into(), "remove".into(), package.into()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let stdout = "Sorting...\nFull Text Search...\nfirefox/jammy-updates,now 127.0+build1 amd64 [installed]\n  Safe and easy web browser from Mozilla\n\nfirefox-esr/jammy 115.12.0esr amd64\n  Mozilla Firefox ESR\n";
+        let packages = Apt::parse(stdout);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "firefox");
+        assert_eq!(packages[0].repo, "jammy-updates");
+        assert!(packages[0].installed);
+        assert_eq!(packages[1].description, "Mozilla Firefox ESR");
+        assert!(!packages[1].installed);
+    }
+}
@@ -0,0 +1,75 @@
+use super::{run_search, Package, PackageBackend};
+use crate::core::{CommandRunner, Item};
+
+pub struct Dnf;
+
+impl Dnf {
+    /// `dnf list` sections: "Installed Packages" then "Available Packages"
+    /// (lowercase in dnf5), with `name.arch version repo` rows. dnf has no
+    /// descriptions in this listing.
+    fn parse(stdout: &str) -> Vec<Package> {
+        let mut installed = false;
+        let mut packages = Vec::new();
+        for line in stdout.lines() {
+            let lower = line.to_lowercase();
+            if lower.starts_with("installed packages") {
+                installed = true;
+                continue;
+            }
+            if lower.starts_with("available packages") {
+                installed = false;
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [qualified, version, repo] = fields[..] else {
+                continue;
+            };
+            let name = qualified.rsplit_once('.').map_or(qualified, |(name, _arch)| name);
+            packages.push(Package {
+                name: name.to_string(),
+                repo: repo.trim_start_matches('@').to_string(),
+                version: version.to_string(),
+                description: String::new(),
+                installed,
+            });
+        }
+        packages
+    }
+}
+
+impl PackageBackend for Dnf {
+    fn name(&self) -> &'static str {
+        "dnf"
+    }
+
+    fn search(&self, runner: &dyn CommandRunner, query: &str) -> Result<Vec<Package>, Vec<Item>> {
+        // Exits with 1 when nothing matches
+        let pattern = format!("*{}*", query);
+        run_search(runner, "dnf", &["list", "-q", &pattern], &[1]).map(|stdout| Self::parse(&stdout))
+    }
+
+    fn install(&self, package: &str) -> Vec<String> {
+        vec!["sudo".into(), "dnf".into(), "install".into(), package.into()]
+    }
+
+    fn remove(&self, package: &str) -> Vec<String> {
+        vec!["sudo".into(), "dnf".into(), "remove".into(), package.into()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let stdout = "Installed Packages\nfirefox.x86_64      127.0-1.fc40      @updates\nAvailable Packages\nfirefox-langpacks.x86_64      127.0-1.fc40      updates\n";
+        let packages = Dnf::parse(stdout);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "firefox");
+        assert_eq!(packages[0].repo, "updates");
+        assert!(packages[0].installed);
+        assert_eq!(packages[1].name, "firefox-langpacks");
+        assert!(!packages[1].installed);
+    }
+}
@@ -0,0 +1,52 @@
+use super::{run_search, Package, PackageBackend};
+use crate::core::{CommandRunner, Item};
+
+pub struct Flatpak;
+
+impl Flatpak {
+    /// Tab separated `application name description version remotes` rows;
+    /// `installed` holds the application ids of `flatpak list`.
+    fn parse(stdout: &str, installed: &str) -> Vec<Package> {
+        stdout
+            .lines()
+            .filter_map(|line| {
+                let columns: Vec<&str> = line.split('\t').collect();
+                let [application, _name, description, version, remotes] = columns[..] else {
+                    return None;
+                };
+                Some(Package {
+                    name: application.to_string(),
+                    repo: remotes.split(',').next().unwrap_or(remotes).to_string(),
+                    version: version.to_string(),
+                    description: description.to_string(),
+                    installed: installed.lines().any(|id| id.trim() == application),
+                })
+            })
+            .collect()
+    }
+}
+
+impl PackageBackend for Flatpak {
+    fn name(&self) -> &'static str {
+        "flatpak"
+    }
+
+    fn search(&self, runner: &dyn CommandRunner, query: &str) -> Result<Vec<Package>, Vec<Item>> {
+        let stdout = run_search(
+            runner,
+            "flatpak",
+            &["search", "--columns=application,name,description,version,remotes", query],
+            &[],
+        )?;
+        let installed = run_search(runner, "flatpak", &["list", "--app", "--columns=application"], &[]).unwrap_or_default();
+        Ok(Self::parse(&stdout, &installed))
+    }
+
+    fn install(&self, package: &str) -> Vec<String> {
+        vec!["flatpak".into(), "install".into(), package.into()]
+    }
+
+    fn remove(&self, package: &str) -> Vec<String> {
+        vec!["flatpak".into(), "uninstall".into(), package.into()]
+    }
+}
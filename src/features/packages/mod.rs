@@ -0,0 +1,275 @@
+//! Package search across the system's package manager (yay or pacman, apt,
+//! dnf, zypper) and Flatpak. Installs and removals run in a terminal, where
+//! the package manager asks for confirmation.
+
+mod apt;
+mod dnf;
+mod flatpak;
+mod pacman;
+mod zypper;
+
+use crate::core::config::ModeConfig;
+use crate::core::{CommandRunner, Config, Item, ItemType, SystemRunner};
+use std::process::Command;
+use std::sync::{Arc, OnceLock};
+
+/// A search result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Package {
+    pub name: String,
+    /// Repository or remote, e.g. "extra" or "flathub", if known
+    pub repo: String,
+    pub version: String,
+    pub description: String,
+    pub installed: bool,
+}
+
+/// A package manager.
+pub trait PackageBackend: Send + Sync {
+    /// Name shown with results and used in item ids, e.g. "pacman"
+    fn name(&self) -> &'static str;
+
+    /// Packages matching `query`, or the error items to show.
+    fn search(&self, runner: &dyn CommandRunner, query: &str) -> Result<Vec<Package>, Vec<Item>>;
+
+    /// Command installing `package`, without skipping confirmation.
+    fn install(&self, package: &str) -> Vec<String>;
+
+    fn remove(&self, package: &str) -> Vec<String>;
+}
+
+/// Stdout of a search; the exit codes in `no_match` mean nothing was found.
+fn run_search(runner: &dyn CommandRunner, program: &str, args: &[&str], no_match: &[i32]) -> Result<String, Vec<Item>> {
+    match runner.run(program, args) {
+        Ok(output) if output.status.success() => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        Ok(output) if output.status.code().is_some_and(|code| no_match.contains(&code)) => Ok(String::new()),
+        Ok(output) => Err(Item::command_error_items("packages", format!("Can't search packages ({})", program), &output)),
+        Err(e) => Err(Item::error_items("packages", format!("Failed to run {}", program), e.to_string())),
+    }
+}
+
+#[derive(Clone)]
+pub struct PackagesManager {
+    options: ModeConfig,
+    runner: Arc<dyn CommandRunner>,
+    /// Detected on the first search, off the UI thread, and shared by clones
+    backends: Arc<OnceLock<Vec<Arc<dyn PackageBackend>>>>,
+}
+
+impl PackagesManager {
+    pub fn new(config: &Config) -> Self {
+        Self::with_runner(config, SystemRunner::shared())
+    }
+
+    pub fn with_runner(config: &Config, runner: Arc<dyn CommandRunner>) -> Self {
+        Self {
+            options: config.mode("pkg"),
+            runner,
+            backends: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// The first system package manager found (yay before pacman, as it
+    /// also searches the AUR), and Flatpak.
+    fn backends(&self) -> &[Arc<dyn PackageBackend>] {
+        self.backends.get_or_init(|| {
+            let installed = |program: &str| self.runner.run(program, &["--version"]).is_ok();
+            let system = ["yay", "pacman", "apt", "dnf", "zypper"].into_iter().find(|program| installed(program));
+
+            let mut backends: Vec<Arc<dyn PackageBackend>> = Vec::new();
+            match system {
+                Some(program @ ("yay" | "pacman")) => backends.push(Arc::new(pacman::Pacman::new(program))),
+                Some("apt") => backends.push(Arc::new(apt::Apt)),
+                Some("dnf") => backends.push(Arc::new(dnf::Dnf)),
+                Some("zypper") => backends.push(Arc::new(zypper::Zypper)),
+                _ => {}
+            }
+            if installed("flatpak") {
+                backends.push(Arc::new(flatpak::Flatpak));
+            }
+            backends
+        })
+    }
+
+    fn backend(&self, name: &str) -> Option<&Arc<dyn PackageBackend>> {
+        self.backends().iter().find(|backend| backend.name() == name)
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim();
+        if query.chars().count() < 2 {
+            return vec![Item::new("pkg:hint", "Type a package name to search", ItemType::Command)
+                .with_description("Searches the repositories; installs and removals ask for confirmation in a terminal")
+                .with_icon("system-software-install")];
+        }
+        if self.backends().is_empty() {
+            return Item::error_items(
+                "packages",
+                "No package manager found",
+                "Looked for yay, pacman, apt, dnf, zypper and flatpak",
+            );
+        }
+
+        let mut results = Vec::new();
+        for backend in self.backends() {
+            match backend.search(self.runner.as_ref(), query) {
+                Ok(packages) => results.extend(packages.into_iter().map(|package| (backend.name(), package))),
+                Err(items) => return items,
+            }
+        }
+
+        // Exact and prefix matches first, keeping the package manager's order otherwise
+        let lower = query.to_lowercase();
+        results.sort_by_key(|(_, package)| {
+            let name = package.name.to_lowercase();
+            (name != lower, !name.starts_with(&lower))
+        });
+        results.truncate(self.options.max_results.unwrap_or(50));
+
+        let mut items = Vec::new();
+        for (i, (backend, package)) in results.into_iter().enumerate() {
+            let id = format!("{}:{}", backend, package.name);
+            let mut details = Vec::new();
+            if !package.version.is_empty() {
+                details.push(package.version.clone());
+            }
+            details.push(match package.repo.as_str() {
+                "" => backend.to_string(),
+                repo => format!("{}/{}", backend, repo),
+            });
+            if package.installed {
+                details.push("installed".to_string());
+            }
+            if !package.description.is_empty() {
+                details.push(package.description.clone());
+            }
+            let mut item = Item::new(format!("pkg:{}", id), &package.name, ItemType::Package)
+                .with_description(details.join(" | "))
+                .with_icon(if package.installed { "package-x-generic" } else { "system-software-install" });
+            item.metadata.installed = package.installed;
+            items.push(item);
+
+            // Actions for the best match only, like the git mode
+            if i == 0 {
+                let (action, name, icon) = if package.installed {
+                    ("remove", "    Remove", "edit-delete")
+                } else {
+                    ("install", "    Install", "list-add")
+                };
+                items.push(
+                    Item::new(format!("pkg:{}:{}", action, id), name, ItemType::PackageAction)
+                        .with_description(format!("{} in a terminal, asks before changing anything", backend))
+                        .with_icon(icon),
+                );
+            }
+        }
+
+        if items.is_empty() {
+            items.push(
+                Item::new("pkg:none", format!("No packages match \"{}\"", query), ItemType::Command)
+                    .with_icon("dialog-information"),
+            );
+        }
+        items
+    }
+
+    /// Enter on a package: removes it if installed, else installs it.
+    pub fn activate(&self, item: &Item) -> bool {
+        let action = if item.metadata.installed { "remove" } else { "install" };
+        match item.id.strip_prefix("pkg:") {
+            Some(id) => self.execute_action(&format!("pkg:{}:{}", action, id)),
+            None => false,
+        }
+    }
+
+    /// Runs an install or remove action in a terminal; returns whether one
+    /// was opened, so the launcher can close.
+    pub fn execute_action(&self, action_id: &str) -> bool {
+        let mut parts = action_id.splitn(4, ':');
+        let (Some("pkg"), Some(action), Some(backend), Some(package)) = (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return false;
+        };
+        let Some(backend) = self.backend(backend) else {
+            return false;
+        };
+        let command = match action {
+            "install" => backend.install(package),
+            "remove" => backend.remove(package),
+            _ => return false,
+        };
+        run_in_terminal(&command)
+    }
+}
+
+/// Runs `command` in a terminal that stays open to show the outcome;
+/// returns whether one was opened. The command is passed as arguments, so
+/// package names are never parsed by the shell.
+pub fn run_in_terminal(command: &[String]) -> bool {
+    let script = "\"$@\"; printf '\\nPress Enter to close'; read -r _";
+    let result = Command::new("x-terminal-emulator")
+        .args(["-e", "sh", "-c", script, "sh"])
+        .args(command)
+        .spawn();
+    match result {
+        Ok(_) => true,
+        Err(e) => {
+            log::error!("Failed to open a terminal for {}: {}", command.join(" "), e);
+            false
+        }
+    }
+}
+
+impl Default for PackagesManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    #[test]
+    fn test_search() {
+        let runner = FakeRunner::new()
+            .with("pacman --version", "Pacman v6.1.0 - libalpm v14.0.0\n")
+            .with("flatpak --version", "Flatpak 1.14.6\n")
+            .with("pacman -Ss fire", include_str!("../../../tests/fixtures/pacman_ss.txt"))
+            .with(
+                "flatpak search --columns=application,name,description,version,remotes fire",
+                "org.mozilla.firefox\tFirefox\tFast, Private & Safe Web Browser\t127.0.2\tflathub\n",
+            )
+            .with("flatpak list --app --columns=application", "org.gnome.Calculator\n")
+            .shared();
+        let packages = PackagesManager::with_runner(&Config::default(), runner);
+
+        let items = packages.get_items("fire");
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "pkg:pacman:firefox",
+                "pkg:remove:pacman:firefox",
+                "pkg:pacman:firefox-i18n-de",
+                "pkg:pacman:firewalld",
+                "pkg:flatpak:org.mozilla.firefox",
+            ]
+        );
+        assert_eq!(
+            items[0].description.as_deref(),
+            Some("127.0.2-1 | pacman/extra | installed | Fast, Secure & Private Web Browser")
+        );
+        assert!(!items[2].metadata.installed);
+        assert!(!items[4].metadata.installed);
+
+        assert_eq!(packages.get_items("f")[0].id, "pkg:hint");
+    }
+
+    #[test]
+    fn test_no_package_manager() {
+        let packages = PackagesManager::with_runner(&Config::default(), FakeRunner::new().shared());
+        assert_eq!(packages.get_items("vim")[0].item_type, ItemType::Error);
+    }
+}
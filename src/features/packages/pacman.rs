@@ -0,0 +1,69 @@
+use super::{run_search, Package, PackageBackend};
+use crate::core::{CommandRunner, Item};
+
+/// pacman, or an AUR helper with the same flags such as yay.
+pub struct Pacman {
+    program: &'static str,
+}
+
+impl Pacman {
+    /// `program` is "pacman" or the AUR helper's name.
+    pub fn new(program: &'static str) -> Self {
+        Self { program }
+    }
+
+    /// `repo/name version (groups) [installed]` lines, each followed by an
+    /// indented description. yay marks installed packages `(Installed)`.
+    fn parse(stdout: &str) -> Vec<Package> {
+        let mut packages: Vec<Package> = Vec::new();
+        for line in stdout.lines() {
+            if line.starts_with(char::is_whitespace) {
+                if let Some(package) = packages.last_mut() {
+                    package.description = line.trim().to_string();
+                }
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let (Some(qualified), Some(version)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            let Some((repo, name)) = qualified.split_once('/') else {
+                continue;
+            };
+            packages.push(Package {
+                name: name.to_string(),
+                repo: repo.to_string(),
+                version: version.to_string(),
+                description: String::new(),
+                installed: line.contains("[installed") || line.contains("(Installed"),
+            });
+        }
+        packages
+    }
+}
+
+impl PackageBackend for Pacman {
+    fn name(&self) -> &'static str {
+        self.program
+    }
+
+    fn search(&self, runner: &dyn CommandRunner, query: &str) -> Result<Vec<Package>, Vec<Item>> {
+        // Exits with 1 when nothing matches
+        run_search(runner, self.program, &["-Ss", query], &[1]).map(|stdout| Self::parse(&stdout))
+    }
+
+    fn install(&self, package: &str) -> Vec<String> {
+        match self.program {
+            // AUR helpers call sudo themselves
+            "pacman" => vec!["sudo".into(), "pacman".into(), "-S".into(), package.into()],
+            program => vec![program.into(), "-S".into(), package.into()],
+        }
+    }
+
+    fn remove(&self, package: &str) -> Vec<String> {
+        match self.program {
+            "pacman" => vec!["sudo".into(), "pacman".into(), "-Rs".into(), package.into()],
+            program => vec![program.into(), "-Rs".into(), package.into()],
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use super::{run_search, Package, PackageBackend};
+use crate::core::{CommandRunner, Item};
+
+pub struct Zypper;
+
+impl Zypper {
+    /// The `S | Name | Summary | Type` table of `zypper search`; an `i` in
+    /// the status column marks installed packages.
+    fn parse(stdout: &str) -> Vec<Package> {
+        stdout
+            .lines()
+            .filter_map(|line| {
+                let columns: Vec<&str> = line.split('|').map(str::trim).collect();
+                let [status, name, summary, kind] = columns[..] else {
+                    return None;
+                };
+                if kind != "package" {
+                    return None;
+                }
+                Some(Package {
+                    name: name.to_string(),
+                    repo: String::new(),
+                    version: String::new(),
+                    description: summary.to_string(),
+                    installed: status.starts_with('i'),
+                })
+            })
+            .collect()
+    }
+}
+
+impl PackageBackend for Zypper {
+    fn name(&self) -> &'static str {
+        "zypper"
+    }
+
+    fn search(&self, runner: &dyn CommandRunner, query: &str) -> Result<Vec<Package>, Vec<Item>> {
+        // Exits with 104 when nothing matches
+        run_search(runner, "zypper", &["--quiet", "--no-refresh", "search", query], &[104]).map(|stdout| Self::parse(&stdout))
+    }
+
+    fn install(&self, package: &str) -> Vec<String> {
+        vec!["sudo".into(), "zypper".into(), "install".into(), package.into()]
+    }
+
+    fn remove(&self, package: &str) -> Vec<String> {
+        vec!["sudo".into(), "zypper".into(), "remove".into(), package.into()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let stdout = "S  | Name          | Summary                     | Type\n---+---------------+-----------------------------+--------\ni+ | MozillaFirefox | Mozilla Firefox Web Browser | package\n   | firefox-esr   | Mozilla Firefox ESR         | package\n   | firefox       | Firefox pattern             | pattern\n";
+        let packages = Zypper::parse(stdout);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "MozillaFirefox");
+        assert!(packages[0].installed);
+        assert_eq!(packages[1].description, "Mozilla Firefox ESR");
+        assert!(!packages[1].installed);
+    }
+}
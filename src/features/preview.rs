@@ -0,0 +1,142 @@
+//! What the details panel shows of the selected file: the image itself,
+//! the first lines of text, how long audio and video run (ffprobe) and how
+//! many pages a PDF has (pdfinfo). Loaded off the UI thread, once per
+//! selection.
+
+use super::TimerManager;
+use crate::core::{CommandRunner, SystemRunner};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Lines of a text file shown.
+const TEXT_LINES: usize = 20;
+/// Bytes read to find them; longer lines are cut.
+const TEXT_BYTES: u64 = 8 * 1024;
+const LINE_CHARS: usize = 120;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilePreview {
+    Image(PathBuf),
+    Text(String),
+    /// Running time, e.g. `03:25`
+    Media(String),
+    Pdf { pages: u32 },
+    None,
+}
+
+#[derive(Clone)]
+pub struct FilePreviewer {
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl FilePreviewer {
+    pub fn new() -> Self {
+        Self::with_runner(SystemRunner::shared())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Self {
+        Self { runner }
+    }
+
+    /// Files whose type isn't known from the extension are shown as text
+    /// when their start has no NUL bytes and is valid UTF-8.
+    pub fn load(&self, path: &Path, mime: Option<&str>) -> FilePreview {
+        let guessed = mime_guess::from_path(path).first().map(|mime| mime.to_string());
+        let mime = mime.or(guessed.as_deref()).unwrap_or("application/octet-stream");
+        let (kind, subtype) = mime.split_once('/').unwrap_or((mime, ""));
+        match (kind, subtype) {
+            ("image", _) => FilePreview::Image(path.to_path_buf()),
+            ("audio" | "video", _) => self.duration(path).map_or(FilePreview::None, FilePreview::Media),
+            (_, "pdf") => self.pages(path).map_or(FilePreview::None, |pages| FilePreview::Pdf { pages }),
+            ("text", _)
+            | ("application", "json" | "xml" | "javascript" | "toml" | "x-sh" | "x-yaml" | "octet-stream") => {
+                Self::text(path).map_or(FilePreview::None, FilePreview::Text)
+            }
+            _ => FilePreview::None,
+        }
+    }
+
+    fn text(path: &Path) -> Option<String> {
+        let mut start = Vec::new();
+        File::open(path).ok()?.take(TEXT_BYTES).read_to_end(&mut start).ok()?;
+        if start.is_empty() || start.contains(&0) {
+            return None;
+        }
+        // The read may end inside a character
+        let text = match std::str::from_utf8(&start) {
+            Ok(text) => text,
+            Err(e) if e.error_len().is_none() => std::str::from_utf8(&start[..e.valid_up_to()]).ok()?,
+            Err(_) => return None,
+        };
+        let lines: Vec<String> = text
+            .lines()
+            .take(TEXT_LINES)
+            .map(|line| match line.char_indices().nth(LINE_CHARS) {
+                Some((end, _)) => format!("{}…", &line[..end]),
+                None => line.to_string(),
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+
+    fn duration(&self, path: &Path) -> Option<String> {
+        let path = path.to_str()?;
+        let output = self
+            .runner
+            .run("ffprobe", &["-v", "error", "-show_entries", "format=duration", "-of", "default=noprint_wrappers=1:nokey=1", path])
+            .ok()
+            .filter(|output| output.status.success())?;
+        let seconds: f64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+        Some(TimerManager::format_duration(Duration::from_secs_f64(seconds.max(0.0).round())))
+    }
+
+    fn pages(&self, path: &Path) -> Option<u32> {
+        let path = path.to_str()?;
+        let output = self.runner.run("pdfinfo", &[path]).ok().filter(|output| output.status.success())?;
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.strip_prefix("Pages:"))
+            .and_then(|pages| pages.trim().parse().ok())
+    }
+}
+
+impl Default for FilePreviewer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    #[test]
+    fn test_previews() {
+        let runner = FakeRunner::new()
+            .with("ffprobe -v error -show_entries format=duration -of default=noprint_wrappers=1:nokey=1 /music/song.flac", "205.493878\n")
+            .with("pdfinfo /docs/paper.pdf", "Title:          Paper\nPages:          12\nEncrypted:      no\n");
+        let previewer = FilePreviewer::with_runner(runner.shared());
+        assert_eq!(previewer.load(Path::new("/music/song.flac"), Some("audio/flac")), FilePreview::Media("03:25".to_string()));
+        assert_eq!(previewer.load(Path::new("/docs/paper.pdf"), Some("application/pdf")), FilePreview::Pdf { pages: 12 });
+        assert_eq!(previewer.load(Path::new("/docs/missing.pdf"), Some("application/pdf")), FilePreview::None);
+
+        let dir = std::env::temp_dir().join(format!("wlaunch-preview-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let makefile = dir.join("Makefile");
+        let long = "x".repeat(200);
+        std::fs::write(&makefile, format!("all:\n\tcargo build\n{}\n", long)).unwrap();
+        let binary = dir.join("blob.bin");
+        std::fs::write(&binary, [0x7f, b'E', b'L', b'F', 0, 1]).unwrap();
+
+        let FilePreview::Text(text) = previewer.load(&makefile, None) else {
+            panic!("Makefile isn't previewed as text");
+        };
+        assert_eq!(text, format!("all:\n\tcargo build\n{}…", "x".repeat(120)));
+        assert_eq!(previewer.load(&binary, Some("application/octet-stream")), FilePreview::None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,28 +1,31 @@
-use crate::core::{Item, ItemType};
+use crate::core::config::{ModeConfig, SortOrder};
+use crate::core::{Config, Item, ItemType};
 use sysinfo::{System, ProcessesToUpdate};
 use std::process::Command;
+use std::sync::{Arc, Mutex};
 
+/// Cloning is cheap: clones share the same `System` snapshot, which keeps
+/// CPU usage deltas meaningful across refreshes.
+#[derive(Clone)]
 pub struct ProcessManager {
-    system: System,
+    system: Arc<Mutex<System>>,
+    options: ModeConfig,
 }
 
 impl ProcessManager {
-    pub fn new() -> Self {
+    pub fn new(config: &Config) -> Self {
         Self {
-            system: System::new_all(),
+            system: Arc::new(Mutex::new(System::new_all())),
+            options: config.mode("processes"),
         }
     }
 
-    pub fn refresh(&mut self) {
-        self.system.refresh_processes(ProcessesToUpdate::All, true);
-    }
-
-    pub fn get_items(&mut self, query: &str) -> Vec<Item> {
-        self.refresh();
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let mut system = self.system.lock().unwrap_or_else(|e| e.into_inner());
+        system.refresh_processes(ProcessesToUpdate::All, true);
 
         let query = query.to_lowercase();
-        let mut items: Vec<Item> = self
-            .system
+        let mut items: Vec<Item> = system
             .processes()
             .iter()
             .filter(|(_, process)| {
@@ -52,15 +55,22 @@ impl ProcessManager {
             })
             .collect();
 
-        // Sort by CPU usage
-        items.sort_by(|a, b| {
-            let cpu_a = a.metadata.cpu.unwrap_or(0.0);
-            let cpu_b = b.metadata.cpu.unwrap_or(0.0);
-            cpu_b.partial_cmp(&cpu_a).unwrap_or(std::cmp::Ordering::Equal)
-        });
+        // Sort by CPU usage unless configured otherwise
+        match self.options.sort {
+            Some(SortOrder::Name) => items.sort_by_key(|item| item.name.to_lowercase()),
+            Some(SortOrder::Memory) => items.sort_by(|a, b| {
+                let mem_a = a.metadata.memory.unwrap_or(0.0);
+                let mem_b = b.metadata.memory.unwrap_or(0.0);
+                mem_b.total_cmp(&mem_a)
+            }),
+            _ => items.sort_by(|a, b| {
+                let cpu_a = a.metadata.cpu.unwrap_or(0.0);
+                let cpu_b = b.metadata.cpu.unwrap_or(0.0);
+                cpu_b.total_cmp(&cpu_a)
+            }),
+        }
 
-        // Limit to top 50
-        items.truncate(50);
+        items.truncate(self.options.max_results.unwrap_or(50));
         items
     }
 
@@ -71,16 +81,10 @@ impl ProcessManager {
             .output();
     }
 
-    pub fn kill_process_graceful(&self, pid: u32) {
-        let _ = Command::new("kill")
-            .arg("-15")
-            .arg(pid.to_string())
-            .output();
-    }
 }
 
 impl Default for ProcessManager {
     fn default() -> Self {
-        Self::new()
+        Self::new(&Config::default())
     }
 }
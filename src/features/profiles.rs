@@ -0,0 +1,105 @@
+//! Profiles keep separate config and data (`--profile work`): pinned apps,
+//! notes, SSH hosts and API keys. The profile mode lists, creates and
+//! switches between them.
+
+use crate::core::{Config, Item, ItemType};
+use anyhow::Result;
+use std::process::Command;
+
+pub struct ProfileManager {
+    profiles: Vec<String>,
+    active: String,
+}
+
+impl ProfileManager {
+    pub fn new() -> Self {
+        Self::with_profiles(Config::profiles(), Config::profile())
+    }
+
+    pub fn with_profiles(profiles: Vec<String>, active: &str) -> Self {
+        Self {
+            profiles,
+            active: active.to_string(),
+        }
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim();
+        let filter = query.to_lowercase();
+
+        let mut items: Vec<Item> = self
+            .profiles
+            .iter()
+            .filter(|name| name.to_lowercase().contains(&filter))
+            .map(|name| {
+                let active = *name == self.active;
+                let mut item = Item::new(format!("profile:{}", name), name, ItemType::Profile)
+                    .with_description(if active {
+                        format!("Active | {}", Config::profile_dir(name).display())
+                    } else {
+                        "Press Enter to switch".to_string()
+                    })
+                    .with_icon(if active { "user-available" } else { "system-users" });
+                item.metadata.path = Some(Config::profile_dir(name));
+                item
+            })
+            .collect();
+
+        if Config::is_profile_name(query) && !self.profiles.iter().any(|name| name == query) {
+            items.push(
+                Item::new(format!("profile:{}", query), format!("Create profile \"{}\"", query), ItemType::Profile)
+                    .with_description("Starts with an empty config | Press Enter to create and switch")
+                    .with_icon("list-add"),
+            );
+        }
+
+        items
+    }
+
+    /// Makes `name` the active profile and starts a launcher with it.
+    pub fn switch(&self, name: &str) -> Result<()> {
+        Config::remember_profile(name)?;
+        let exe = std::env::current_exe()?;
+        Command::new(exe).args(["--profile", name]).spawn()?;
+        Ok(())
+    }
+
+    pub fn is_active(&self, name: &str) -> bool {
+        name == self.active
+    }
+}
+
+impl Default for ProfileManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::DEFAULT_PROFILE;
+
+    #[test]
+    fn test_profile_items() {
+        let profiles = ProfileManager::with_profiles(
+            vec![DEFAULT_PROFILE.to_string(), "personal".to_string(), "work".to_string()],
+            "work",
+        );
+
+        let items = profiles.get_items("");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["default", "personal", "work"]);
+        assert!(items[2].description.as_deref().unwrap().starts_with("Active"));
+
+        let items = profiles.get_items("client-a");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "profile:client-a");
+        assert_eq!(items[0].name, "Create profile \"client-a\"");
+
+        let items = profiles.get_items("wor");
+        assert_eq!(items[0].name, "work");
+        assert_eq!(items[1].id, "profile:wor");
+        assert!(profiles.get_items("../etc").is_empty());
+    }
+}
@@ -0,0 +1,329 @@
+//! Recently opened projects of VS Code (and its forks) and JetBrains IDEs,
+//! opened again in the editor they were last used in.
+
+use crate::core::{Config, Item, ItemType};
+use anyhow::Result;
+use regex::Regex;
+use rusqlite::{Connection, OpenFlags};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Config directories of VS Code builds, relative to `~/.config`, with the
+/// editor's name and command
+const VSCODE_BUILDS: [(&str, &str, &str); 5] = [
+    ("Code", "VS Code", "code"),
+    ("Code - Insiders", "VS Code Insiders", "code-insiders"),
+    ("Code - OSS", "Code - OSS", "code-oss"),
+    ("VSCodium", "VSCodium", "codium"),
+    ("Cursor", "Cursor", "cursor"),
+];
+
+/// JetBrains config directory prefixes, e.g. `PyCharm2024.1`, with the
+/// IDE's name and launcher script
+const JETBRAINS_IDES: [(&str, &str, &str); 11] = [
+    ("IntelliJIdea", "IntelliJ IDEA", "idea"),
+    ("IdeaIC", "IntelliJ IDEA", "idea"),
+    ("PyCharm", "PyCharm", "pycharm"),
+    ("WebStorm", "WebStorm", "webstorm"),
+    ("GoLand", "GoLand", "goland"),
+    ("CLion", "CLion", "clion"),
+    ("RustRover", "RustRover", "rustrover"),
+    ("PhpStorm", "PhpStorm", "phpstorm"),
+    ("Rider", "Rider", "rider"),
+    ("RubyMine", "RubyMine", "rubymine"),
+    ("DataGrip", "DataGrip", "datagrip"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+struct Project {
+    path: PathBuf,
+    /// e.g. "VS Code"
+    editor: String,
+    /// Command the project path is appended to
+    command: String,
+}
+
+pub struct ProjectsManager {
+    projects: OnceLock<Vec<Project>>,
+    /// Commands replacing the default ones, keyed by default command
+    editors: HashMap<String, String>,
+}
+
+impl ProjectsManager {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            projects: OnceLock::new(),
+            editors: config.projects.editors.clone(),
+        }
+    }
+
+    fn projects(&self) -> &[Project] {
+        self.projects.get_or_init(|| self.discover())
+    }
+
+    fn discover(&self) -> Vec<Project> {
+        let Some(config_dir) = dirs::config_dir() else {
+            return Vec::new();
+        };
+        let mut projects = Vec::new();
+        for (dir, editor, command) in VSCODE_BUILDS {
+            let user_dir = config_dir.join(dir).join("User");
+            let paths = match Self::read_vscode(&user_dir) {
+                Ok(paths) => paths,
+                Err(e) => {
+                    log::warn!("Failed to read {} history: {}", editor, e);
+                    continue;
+                }
+            };
+            projects.extend(paths.into_iter().map(|path| Project {
+                path,
+                editor: editor.to_string(),
+                command: self.command(command),
+            }));
+        }
+        projects.extend(self.jetbrains_projects(&config_dir.join("JetBrains")));
+        projects.retain(|p| p.path.exists());
+        projects
+    }
+
+    fn command(&self, default: &str) -> String {
+        self.editors.get(default).cloned().unwrap_or_else(|| default.to_string())
+    }
+
+    /// Recent folders and workspaces, newest first: from `state.vscdb` in
+    /// current versions, `storage.json` in older ones.
+    fn read_vscode(user_dir: &Path) -> Result<Vec<PathBuf>> {
+        let db = user_dir.join("globalStorage/state.vscdb");
+        let history = if db.exists() {
+            let conn = Connection::open_with_flags(&db, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+            let value: String = conn.query_row(
+                "SELECT value FROM ItemTable WHERE key = 'history.recentlyOpenedPathsList'",
+                [],
+                |row| row.get(0),
+            )?;
+            serde_json::from_str(&value)?
+        } else {
+            let storage = user_dir.join("globalStorage/storage.json");
+            let Ok(content) = fs::read_to_string(storage) else {
+                return Ok(Vec::new());
+            };
+            serde_json::from_str::<Value>(&content)?["openedPathsList"].clone()
+        };
+        Ok(Self::parse_vscode_history(&history))
+    }
+
+    fn parse_vscode_history(history: &Value) -> Vec<PathBuf> {
+        history["entries"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let uri = entry["folderUri"].as_str().or(entry["workspace"]["configPath"].as_str())?;
+                // Remote and virtual workspaces can't be opened by path
+                Some(PathBuf::from(percent_decode(uri.strip_prefix("file://")?)))
+            })
+            .collect()
+    }
+
+    /// Projects of the newest version of each IDE, newest first.
+    fn jetbrains_projects(&self, root: &Path) -> Vec<Project> {
+        let Ok(entries) = fs::read_dir(root) else {
+            return Vec::new();
+        };
+        let mut dirs: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        // Version suffixes sort, so the newest version of an IDE comes last
+        dirs.sort();
+
+        let mut newest: HashMap<&str, PathBuf> = HashMap::new();
+        for dir in dirs {
+            let name = dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+            if let Some((prefix, _, _)) = JETBRAINS_IDES.iter().find(|(prefix, _, _)| name.starts_with(prefix)) {
+                newest.insert(prefix, dir);
+            }
+        }
+
+        let home = dirs::home_dir().unwrap_or_default();
+        let mut projects: Vec<(i64, Project)> = Vec::new();
+        for (prefix, name, command) in JETBRAINS_IDES {
+            let Some(dir) = newest.get(prefix) else {
+                continue;
+            };
+            for file in ["recentProjects.xml", "recentSolutions.xml"] {
+                let Ok(xml) = fs::read_to_string(dir.join("options").join(file)) else {
+                    continue;
+                };
+                projects.extend(Self::parse_jetbrains(&xml, &home).into_iter().map(|(path, opened)| {
+                    let project = Project {
+                        path,
+                        editor: name.to_string(),
+                        command: self.command(command),
+                    };
+                    (opened, project)
+                }));
+            }
+        }
+        projects.sort_by_key(|(opened, _)| std::cmp::Reverse(*opened));
+        projects.into_iter().map(|(_, project)| project).collect()
+    }
+
+    /// Paths in `recentProjects.xml` with when they were last opened (unix
+    /// millis, 0 when missing).
+    fn parse_jetbrains(xml: &str, home: &Path) -> Vec<(PathBuf, i64)> {
+        let entry = Regex::new(r#"(?s)<entry key="([^"]+)">(.*?)</entry>"#).unwrap();
+        let activated = Regex::new(r#"name="activationTimestamp" value="(\d+)""#).unwrap();
+        entry
+            .captures_iter(xml)
+            .map(|caps| {
+                let path = caps[1].replace("$USER_HOME$", &home.to_string_lossy());
+                let opened = activated.captures(&caps[2]).and_then(|c| c[1].parse().ok()).unwrap_or(0);
+                (PathBuf::from(path), opened)
+            })
+            .collect()
+    }
+
+    fn to_item(project: &Project) -> Item {
+        let name = project.path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let location = match dirs::home_dir().and_then(|home| project.path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+            Some(relative) => format!("~/{}", relative.display()),
+            None => project.path.display().to_string(),
+        };
+        let mut item = Item::new(
+            format!("project:{}:{}", project.command, project.path.display()),
+            name,
+            ItemType::Project,
+        )
+        .with_description(format!("{} | {}", location, project.editor))
+        .with_icon("folder-development")
+        .with_exec(project.command.clone())
+        .with_keywords(vec![project.editor.clone()]);
+        item.metadata.path = Some(project.path.clone());
+        item
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let projects = self.projects();
+        if projects.is_empty() {
+            return vec![Item::new("project:none", "No recent projects found", ItemType::Command)
+                .with_description("Looked in VS Code, VSCodium, Cursor and JetBrains IDE history")
+                .with_icon("dialog-information")];
+        }
+
+        let query = query.trim();
+        let items = projects.iter().map(Self::to_item);
+        if query.is_empty() {
+            return items.collect();
+        }
+        let mut scored: Vec<(i64, Item)> = items
+            .map(|item| (item.fuzzy_score(query), item))
+            .filter(|(score, _)| *score > 0)
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, item)| item).collect()
+    }
+
+    /// Opens the project of `item` in its editor.
+    pub fn open(item: &Item) {
+        let (Some(command), Some(path)) = (&item.exec, &item.metadata.path) else {
+            return;
+        };
+        // The path is passed as $1 so it isn't parsed by the shell
+        let result = Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$1\"", command))
+            .arg("sh")
+            .arg(path)
+            .spawn();
+        if let Err(e) = result {
+            log::error!("Failed to open {}: {}", path.display(), e);
+        }
+    }
+}
+
+impl Default for ProjectsManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+/// Decodes `%20` and friends in a file URI path.
+pub(crate) fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vscode_history() {
+        let history: Value = serde_json::from_str(
+            r#"{"entries": [
+                {"folderUri": "file:///home/me/code/api-server"},
+                {"workspace": {"id": "1f", "configPath": "file:///home/me/work/all.code-workspace"}},
+                {"folderUri": "vscode-remote://ssh-remote%2Bbox/srv/app"},
+                {"fileUri": "file:///home/me/notes.md"},
+                {"folderUri": "file:///home/me/My%20Project"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            ProjectsManager::parse_vscode_history(&history),
+            [
+                PathBuf::from("/home/me/code/api-server"),
+                PathBuf::from("/home/me/work/all.code-workspace"),
+                PathBuf::from("/home/me/My Project"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_jetbrains() {
+        let xml = r#"<application>
+  <component name="RecentProjectsManager">
+    <option name="additionalInfo">
+      <map>
+        <entry key="$USER_HOME$/code/api-server">
+          <value>
+            <RecentProjectMetaInfo frameTitle="api-server">
+              <option name="activationTimestamp" value="1760000000000" />
+            </RecentProjectMetaInfo>
+          </value>
+        </entry>
+        <entry key="/opt/src/tool">
+          <value>
+            <RecentProjectMetaInfo />
+          </value>
+        </entry>
+      </map>
+    </option>
+  </component>
+</application>"#;
+        assert_eq!(
+            ProjectsManager::parse_jetbrains(xml, Path::new("/home/me")),
+            [
+                (PathBuf::from("/home/me/code/api-server"), 1_760_000_000_000),
+                (PathBuf::from("/opt/src/tool"), 0),
+            ]
+        );
+    }
+}
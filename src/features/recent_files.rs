@@ -1,6 +1,11 @@
+//! Recently opened files: the ones opened through wlaunch, merged with
+//! the documents other applications record in `recently-used.xbel`.
+
+use super::projects::percent_decode;
+use crate::core::config::{ModeConfig, SortOrder};
 use crate::core::{Config, Item, ItemType};
 use anyhow::Result;
-use chrono::Local;
+use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -15,6 +20,7 @@ struct RecentFile {
 pub struct RecentFilesManager {
     files: Vec<RecentFile>,
     max_files: usize,
+    options: ModeConfig,
 }
 
 impl RecentFilesManager {
@@ -25,6 +31,7 @@ impl RecentFilesManager {
         Self {
             files,
             max_files: config.max_recent_files(),
+            options: config.mode("recent"),
         }
     }
 
@@ -51,10 +58,64 @@ impl RecentFilesManager {
         Ok(())
     }
 
+    /// The desktop's list of recently used files, kept by GTK apps.
+    fn xbel_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("recently-used.xbel"))
+    }
+
+    /// Local files among the `<bookmark>` elements of a recently-used.xbel,
+    /// with the time they were last modified or visited.
+    fn parse_xbel(content: &str) -> Vec<RecentFile> {
+        let attribute = |tag: &str, name: &str| -> Option<String> {
+            let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+            let end = start + tag[start..].find('"')?;
+            Some(
+                tag[start..end]
+                    .replace("&quot;", "\"")
+                    .replace("&apos;", "'")
+                    .replace("&lt;", "<")
+                    .replace("&gt;", ">")
+                    .replace("&amp;", "&"),
+            )
+        };
+        content
+            .split("<bookmark ")
+            .skip(1)
+            .filter_map(|rest| {
+                let tag = format!(" {}", &rest[..rest.find('>')?]);
+                let path = PathBuf::from(percent_decode(attribute(&tag, "href")?.strip_prefix("file://")?));
+                let accessed = ["visited", "modified", "added"]
+                    .iter()
+                    .filter_map(|name| DateTime::parse_from_rfc3339(&attribute(&tag, name)?).ok())
+                    .max()?
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string();
+                let name = path.file_name()?.to_string_lossy().to_string();
+                Some(RecentFile { path, name, accessed })
+            })
+            .collect()
+    }
+
+    /// Both lists, newest first, each path once with its latest access.
+    fn merged(own: &[RecentFile], others: Vec<RecentFile>, max_files: usize) -> Vec<RecentFile> {
+        let mut files: Vec<RecentFile> = own.iter().cloned().chain(others).collect();
+        // Stable, so a tie keeps wlaunch's own entry first
+        files.sort_by(|a, b| b.accessed.cmp(&a.accessed));
+        let mut seen = std::collections::HashSet::new();
+        files.retain(|f| seen.insert(f.path.clone()));
+        files.truncate(max_files);
+        files
+    }
+
     pub fn get_items(&self, query: &str) -> Vec<Item> {
         let query_lower = query.to_lowercase();
+        let others = Self::xbel_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| Self::parse_xbel(&content))
+            .unwrap_or_default();
 
-        self.files
+        let mut items: Vec<Item> = Self::merged(&self.files, others, self.max_files)
             .iter()
             .filter(|f| {
                 query_lower.is_empty()
@@ -83,7 +144,13 @@ impl RecentFilesManager {
 
                 item
             })
-            .collect()
+            .take(self.options.max_results.unwrap_or(usize::MAX))
+            .collect();
+
+        if self.options.sort == Some(SortOrder::Name) {
+            items.sort_by_key(|item| item.name.to_lowercase());
+        }
+        items
     }
 
     pub fn add_file(&mut self, path: &PathBuf) {
@@ -114,11 +181,6 @@ impl RecentFilesManager {
         let _ = self.save();
     }
 
-    pub fn clear(&mut self) {
-        self.files.clear();
-        let _ = self.save();
-    }
-
     fn get_icon_for_mime(mime: &str) -> &'static str {
         let main_type = mime.split('/').next().unwrap_or("");
         match main_type {
@@ -145,3 +207,46 @@ impl Default for RecentFilesManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(utc: &str) -> String {
+        DateTime::parse_from_rfc3339(utc).unwrap().with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+
+    #[test]
+    fn test_xbel_merge() {
+        let xbel = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xbel version="1.0" xmlns:bookmark="http://www.freedesktop.org/standards/desktop-bookmarks">
+  <bookmark href="file:///home/me/Documents/Q3%20report.odt" added="2026-03-01T09:00:00Z" modified="2026-03-02T10:00:00.123456Z" visited="2026-03-01T09:00:00Z">
+    <info><metadata owner="http://freedesktop.org"><mime:mime-type type="application/vnd.oasis.opendocument.text"/></metadata></info>
+  </bookmark>
+  <bookmark href="file:///home/me/Pictures/cat&amp;dog.png" added="2026-03-03T08:00:00Z" modified="2026-03-03T08:00:00Z" visited="2026-03-03T08:00:00Z"/>
+  <bookmark href="sftp://nas/backup.tar" added="2026-03-04T08:00:00Z"/>
+</xbel>"#;
+        let others = RecentFilesManager::parse_xbel(xbel);
+        let paths: Vec<&str> = others.iter().map(|f| f.path.to_str().unwrap()).collect();
+        assert_eq!(paths, ["/home/me/Documents/Q3 report.odt", "/home/me/Pictures/cat&dog.png"]);
+        assert_eq!(others[0].accessed, local("2026-03-02T10:00:00Z"));
+
+        let own = vec![
+            RecentFile {
+                path: PathBuf::from("/home/me/Documents/Q3 report.odt"),
+                name: "Q3 report.odt".to_string(),
+                accessed: local("2026-03-05T12:00:00Z"),
+            },
+            RecentFile {
+                path: PathBuf::from("/home/me/notes.txt"),
+                name: "notes.txt".to_string(),
+                accessed: local("2026-02-20T12:00:00Z"),
+            },
+        ];
+        let merged = RecentFilesManager::merged(&own, others, 100);
+        let names: Vec<&str> = merged.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["Q3 report.odt", "cat&dog.png", "notes.txt"]);
+        assert_eq!(merged[0].accessed, local("2026-03-05T12:00:00Z"));
+        assert_eq!(RecentFilesManager::merged(&own, Vec::new(), 1).len(), 1);
+    }
+}
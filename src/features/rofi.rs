@@ -0,0 +1,272 @@
+//! `wlaunch migrate-rofi`: carries a rofi setup over. Modi become the
+//! matching wlaunch modes, script modi become scripts that run through
+//! `wlaunch --dmenu`, and the terminal, ssh and theme settings are mapped
+//! where wlaunch has an equivalent.
+
+use crate::core::Config;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The `configuration` of a rofi config file.
+#[derive(Debug, Default, PartialEq)]
+pub struct RofiConfig {
+    /// Settings in file order, e.g. `("modi", "drun,window,calc:~/calc.sh")`
+    pub settings: Vec<(String, String)>,
+    /// `@theme` (or the old `theme` setting), e.g. "gruvbox-dark"
+    pub theme: Option<String>,
+}
+
+impl RofiConfig {
+    /// `config.rasi` in the rofi config directory, or the old Xresources
+    /// style `config`.
+    pub fn default_path() -> Option<PathBuf> {
+        let dir = dirs::config_dir()?.join("rofi");
+        ["config.rasi", "config"].into_iter().map(|name| dir.join(name)).find(|path| path.exists())
+    }
+
+    /// Reads both formats: rasi's `configuration { modi: "drun,run"; }` and
+    /// the old `rofi.modi: drun,run` lines. Nested blocks are skipped.
+    pub fn parse(content: &str) -> Self {
+        let mut config = RofiConfig::default();
+        let mut depth = 0usize;
+        let mut in_configuration = false;
+
+        for line in strip_comments(content).lines() {
+            let line = line.trim();
+            if let Some(theme) = line.strip_prefix("@theme") {
+                config.theme = Some(unquote(theme.trim().trim_end_matches(';')));
+                continue;
+            }
+            if line.ends_with('{') {
+                if depth == 0 && line.trim_end_matches('{').trim() == "configuration" {
+                    in_configuration = true;
+                }
+                depth += 1;
+                continue;
+            }
+            if line.starts_with('}') {
+                depth = depth.saturating_sub(1);
+                in_configuration &= depth > 0;
+                continue;
+            }
+
+            let old_style = line.strip_prefix("rofi.").filter(|_| depth == 0);
+            if !(in_configuration && depth == 1) && old_style.is_none() {
+                continue;
+            }
+            let Some((key, value)) = old_style.unwrap_or(line).split_once(':') else {
+                continue;
+            };
+            let value = unquote(value.trim().trim_end_matches(';').trim());
+            match key.trim() {
+                "theme" => config.theme = Some(value),
+                key => config.settings.push((key.to_string(), value)),
+            }
+        }
+        config
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.settings.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    /// The modi of `modi` and `combi-modi`, without duplicates.
+    fn modi(&self) -> Vec<&str> {
+        let mut modi: Vec<&str> = Vec::new();
+        for key in ["modi", "combi-modi"] {
+            for mode in self.get(key).unwrap_or("").split(',').map(str::trim) {
+                if !mode.is_empty() && !modi.contains(&mode) {
+                    modi.push(mode);
+                }
+            }
+        }
+        modi
+    }
+
+    /// Script modi (`name:command`) as wlaunch scripts, by file name.
+    pub fn scripts(&self) -> Vec<(String, String)> {
+        self.modi()
+            .into_iter()
+            .filter_map(|mode| mode.split_once(':'))
+            .filter(|(name, _)| !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_'))
+            .map(|(name, command)| (name.to_string(), script_wrapper(name, command.trim())))
+            .collect()
+    }
+
+    /// Changes `config` to match, returning what happened to each setting.
+    pub fn apply(&self, config: &mut Config) -> Vec<String> {
+        let mut report = Vec::new();
+
+        for mode in self.modi() {
+            // Script modi are listed below
+            if mode.contains(':') {
+                continue;
+            }
+            let line = match mode {
+                "drun" | "run" => format!("{}: applications are the default search, without a prefix", mode),
+                "window" | "windowcd" => format!("{}: switch windows with the `w` prefix", mode),
+                "ssh" => {
+                    config.main_search.ssh = Some(true);
+                    "ssh: hosts from ~/.ssh/config show in the main search and with the `ssh` prefix".to_string()
+                }
+                "filebrowser" => "filebrowser: search files with the `f` prefix".to_string(),
+                "emoji" => "emoji: pick emoji with the `e` prefix".to_string(),
+                "calc" => "calc: math is calculated as you type".to_string(),
+                "combi" => "combi: the main search already combines apps, scripts, ssh hosts and snippets".to_string(),
+                "keys" => "keys: see the keyboard shortcuts in the README".to_string(),
+                other => format!("{}: no equivalent, skipped", other),
+            };
+            report.push(line);
+        }
+        for (name, _) in self.scripts() {
+            report.push(format!("{}: script mode, added as the `{}` script (runs through wlaunch --dmenu)", name, name));
+        }
+
+        if let Some(terminal) = self.get("terminal") {
+            config.git.terminal = Some(terminal.to_string());
+            report.push(format!("terminal: {} opens git repositories", terminal));
+        }
+        for key in ["ssh-client", "ssh-command", "parse-hosts", "parse-known-hosts"] {
+            if self.get(key).is_some() {
+                report.push(format!(
+                    "{}: skipped, SSH connections run `ssh -p <port> <user>@<host>` in x-terminal-emulator",
+                    key
+                ));
+            }
+        }
+        for key in ["window-format", "window-match-fields", "window-command", "window-thumbnail"] {
+            if self.get(key).is_some() {
+                report.push(format!("{}: skipped, the `w` mode groups windows by application instead", key));
+            }
+        }
+
+        if let Some(theme) = &self.theme {
+            let lower = theme.to_lowercase();
+            let preset = ["catppuccin", "gruvbox", "light"]
+                .into_iter()
+                .find(|preset| lower.contains(preset))
+                .or_else(|| lower.contains("dark").then_some("dark"));
+            match preset {
+                Some(preset) => {
+                    config.theme.preset = Some(preset.to_string());
+                    report.push(format!("theme {}: the {} preset", theme, preset));
+                }
+                None => report.push(format!("theme {}: no matching preset, pick one with the `theme` mode", theme)),
+            }
+        }
+        report
+    }
+}
+
+/// Runs a rofi script mode with wlaunch: the script lists entries, the
+/// chosen one is passed back, and it repeats while the script prints more.
+fn script_wrapper(name: &str, command: &str) -> String {
+    format!(
+        r#"#!/bin/sh
+# rofi script mode "{name}", migrated by wlaunch migrate-rofi
+run() {{ ROFI_RETV=$1; export ROFI_RETV; shift; {command} "$@"; }}
+entries=$(run 0) || exit
+while [ -n "$entries" ] && choice=$(printf '%s\n' "$entries" | wlaunch --dmenu -p '{name}'); do
+    entries=$(run 1 "$choice") || exit
+done
+"#
+    )
+}
+
+fn strip_comments(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        rest = rest[start..].find("*/").map_or("", |end| &rest[start + end + 2..]);
+    }
+    out.push_str(rest);
+    out.lines()
+        .map(|line| match line.find("//") {
+            // Not inside a quoted value, such as a URL
+            Some(i) if line[..i].matches('"').count() % 2 == 0 => &line[..i],
+            _ => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn unquote(value: &str) -> String {
+    value.trim_matches('"').to_string()
+}
+
+/// Migrates the rofi config at `path` (or the default one) into the active
+/// profile and returns a report for the terminal.
+pub fn migrate(path: Option<&Path>) -> Result<String> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => match RofiConfig::default_path() {
+            Some(path) => path,
+            None => bail!("No rofi config found in ~/.config/rofi"),
+        },
+    };
+    let content = fs::read_to_string(&path).with_context(|| format!("Can't read {}", path.display()))?;
+    let rofi = RofiConfig::parse(&content);
+
+    let mut config = Config::load()?;
+    let mut report = rofi.apply(&mut config);
+    config.save()?;
+
+    let scripts_dir = Config::scripts_dir();
+    for (name, script) in rofi.scripts() {
+        let target = scripts_dir.join(&name);
+        if target.exists() {
+            report.push(format!("{}: {} already exists, not overwritten", name, target.display()));
+            continue;
+        }
+        fs::create_dir_all(&scripts_dir)?;
+        fs::write(&target, script)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&target, fs::Permissions::from_mode(0o755))?;
+        }
+    }
+
+    report.insert(0, format!("Migrated {} to {}", path.display(), Config::config_path().display()));
+    report.push(String::new());
+    report.push("Scripts calling `rofi -dmenu` keep working once rofi points to wlaunch:".to_string());
+    report.push("    ln -s \"$(command -v wlaunch)\" ~/.local/bin/rofi".to_string());
+    Ok(report.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate() {
+        let rofi = RofiConfig::parse(include_str!("../../tests/fixtures/rofi_config.rasi"));
+        assert_eq!(rofi.theme.as_deref(), Some("gruvbox-dark"));
+        assert_eq!(rofi.get("terminal"), Some("alacritty"));
+        // Only the configuration block, not the nested timeout block
+        assert_eq!(rofi.get("action"), None);
+
+        let mut config = Config::default();
+        let report = rofi.apply(&mut config);
+        assert_eq!(config.theme.preset.as_deref(), Some("gruvbox"));
+        assert_eq!(config.git.terminal.as_deref(), Some("alacritty"));
+        assert_eq!(config.main_search.ssh, Some(true));
+        assert!(report.iter().any(|line| line.starts_with("window: ")));
+        assert!(report.iter().any(|line| line.starts_with("ssh-command: skipped")));
+
+        let scripts = rofi.scripts();
+        assert_eq!(scripts.len(), 1);
+        assert_eq!(scripts[0].0, "power");
+        assert!(scripts[0].1.contains("shift; ~/.config/rofi/power.sh \"$@\"; }"));
+        assert!(scripts[0].1.contains("wlaunch --dmenu -p 'power'"));
+    }
+
+    #[test]
+    fn test_old_format() {
+        let rofi = RofiConfig::parse("rofi.modi: window,run\nrofi.theme: /usr/share/rofi/themes/Arc-Dark.rasi\n");
+        assert_eq!(rofi.modi(), ["window", "run"]);
+        assert_eq!(rofi.theme.as_deref(), Some("/usr/share/rofi/themes/Arc-Dark.rasi"));
+    }
+}
@@ -0,0 +1,312 @@
+//! Screenshots of the screen, a selected region or the focused window,
+//! copied or saved to `~/Pictures/Screenshots`, text read from a region
+//! (tesseract), and screen recordings.
+//! Wayland uses grim, slurp and wf-recorder; X11 uses maim or scrot. The
+//! capture runs detached once the launcher has closed, so it isn't in the
+//! picture.
+
+use crate::core::{CommandRunner, Item, ItemType, SystemRunner};
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+/// Seconds to wait for the launcher window to disappear.
+const CLOSE_DELAY: &str = "0.3";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tool {
+    Grim,
+    Maim,
+    Scrot,
+}
+
+impl Tool {
+    /// The first installed tool for the session type.
+    fn detect() -> Option<Self> {
+        let candidates: &[(&str, Tool)] = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            &[("grim", Tool::Grim)]
+        } else {
+            &[("maim", Tool::Maim), ("scrot", Tool::Scrot)]
+        };
+        candidates.iter().find(|(name, _)| which::which(name).is_ok()).map(|(_, tool)| *tool)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Target {
+    Screen,
+    Region,
+    Window,
+}
+
+impl Target {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "screen" => Some(Target::Screen),
+            "region" => Some(Target::Region),
+            "window" => Some(Target::Window),
+            _ => None,
+        }
+    }
+}
+
+/// Creates `$file`, a private temp file removed when the script exits.
+const TEMP_FILE: &str = "file=$(mktemp --suffix=.png) || exit 1; trap 'rm -f \"$file\"' EXIT; ";
+
+#[derive(Clone)]
+pub struct ScreenshotManager {
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl ScreenshotManager {
+    pub fn new() -> Self {
+        Self::with_runner(SystemRunner::shared())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Self {
+        Self { runner }
+    }
+
+    fn recording(&self) -> bool {
+        self.runner
+            .run("pgrep", &["-x", "wf-recorder"])
+            .is_ok_and(|output| output.status.success())
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim().to_lowercase();
+        let mut items = vec![Item::new("shot:ocr", "Capture text", ItemType::ScreenshotAction)
+            .with_description("Copies the text in a selected region, read with tesseract")
+            .with_icon("insert-text")];
+        for (target, what, icon) in [
+            ("screen", "screen", "video-display"),
+            ("region", "region", "select-rectangular"),
+            ("window", "window", "window"),
+        ] {
+            items.push(
+                Item::new(format!("shot:{}:copy", target), format!("Copy {} screenshot", what), ItemType::ScreenshotAction)
+                    .with_description("Puts the image on the clipboard")
+                    .with_icon(icon),
+            );
+            items.push(
+                Item::new(format!("shot:{}:save", target), format!("Save {} screenshot", what), ItemType::ScreenshotAction)
+                    .with_description(format!("Saves it to {}", Self::display_dir(&Self::screenshots_dir())))
+                    .with_icon(icon),
+            );
+        }
+
+        if self.recording() {
+            items.insert(
+                0,
+                Item::new("shot:stop", "Stop recording", ItemType::ScreenshotAction)
+                    .with_description("Ends the wf-recorder recording and saves it")
+                    .with_icon("media-playback-stop"),
+            );
+        } else {
+            let videos = Self::display_dir(&Self::videos_dir());
+            items.push(
+                Item::new("shot:record:screen", "Record screen", ItemType::ScreenshotAction)
+                    .with_description(format!("Records with wf-recorder to {} until stopped here", videos))
+                    .with_icon("media-record"),
+            );
+            items.push(
+                Item::new("shot:record:region", "Record region", ItemType::ScreenshotAction)
+                    .with_description(format!("Records a selected region to {} until stopped here", videos))
+                    .with_icon("media-record"),
+            );
+        }
+
+        items.retain(|item| item.name.to_lowercase().contains(&query));
+        items
+    }
+
+    fn screenshots_dir() -> PathBuf {
+        dirs::picture_dir()
+            .or_else(|| dirs::home_dir().map(|home| home.join("Pictures")))
+            .unwrap_or_default()
+            .join("Screenshots")
+    }
+
+    fn videos_dir() -> PathBuf {
+        dirs::video_dir()
+            .or_else(|| dirs::home_dir().map(|home| home.join("Videos")))
+            .unwrap_or_default()
+    }
+
+    fn display_dir(dir: &Path) -> String {
+        match dirs::home_dir().and_then(|home| dir.strip_prefix(home).ok().map(Path::to_path_buf)) {
+            Some(relative) => format!("~/{}", relative.display()),
+            None => dir.display().to_string(),
+        }
+    }
+
+    /// Starts the capture or recording; returns once it's spawned.
+    pub fn execute_action(&self, action_id: &str) -> Result<()> {
+        let rest = action_id.strip_prefix("shot:").ok_or_else(|| anyhow!("Unknown screenshot action"))?;
+        if rest == "stop" {
+            // SIGINT lets wf-recorder finish the file
+            self.runner.run("pkill", &["-INT", "-x", "wf-recorder"])?;
+            return Ok(());
+        }
+
+        let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S");
+        let script = if rest == "ocr" {
+            let tool = Tool::detect().ok_or_else(|| anyhow!("Install grim and slurp (Wayland) or maim (X11) to capture text"))?;
+            if which::which("tesseract").is_err() {
+                return Err(anyhow!("Install tesseract to capture text"));
+            }
+            Self::ocr_script(tool)
+        } else if let Some(target) = rest.strip_prefix("record:") {
+            if which::which("wf-recorder").is_err() {
+                return Err(anyhow!("Install wf-recorder to record the screen"));
+            }
+            let dir = Self::videos_dir();
+            std::fs::create_dir_all(&dir)?;
+            Self::record_script(target == "region", &dir.join(format!("Recording_{}.mp4", timestamp)))
+        } else {
+            let (target, output) = rest.split_once(':').ok_or_else(|| anyhow!("Unknown screenshot action"))?;
+            let target = Target::parse(target).ok_or_else(|| anyhow!("Unknown screenshot target {}", target))?;
+            let tool = Tool::detect().ok_or_else(|| anyhow!("Install grim and slurp (Wayland) or maim (X11) to take screenshots"))?;
+            let save = if output == "copy" {
+                None
+            } else {
+                let dir = Self::screenshots_dir();
+                std::fs::create_dir_all(&dir)?;
+                Some(dir.join(format!("Screenshot_{}.png", timestamp)))
+            };
+            Self::capture_script(tool, target, save.as_deref(), Self::compositor())
+        };
+
+        Command::new("sh")
+            .arg("-c")
+            .arg(format!("sleep {}; {}", CLOSE_DELAY, script))
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        Ok(())
+    }
+
+    /// The Wayland compositor, for finding the focused window's geometry.
+    fn compositor() -> Option<&'static str> {
+        if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            Some("hyprland")
+        } else if std::env::var_os("SWAYSOCK").is_some() {
+            Some("sway")
+        } else {
+            None
+        }
+    }
+
+    /// Shell commands taking the screenshot and saving it to `save` and
+    /// announcing where, or else copying it through a private temp file.
+    fn capture_script(tool: Tool, target: Target, save: Option<&Path>, compositor: Option<&str>) -> String {
+        let Some(file) = save else {
+            let copy = match tool {
+                Tool::Grim => "wl-copy --type image/png < \"$file\"",
+                _ => "xclip -selection clipboard -t image/png -i \"$file\"",
+            };
+            return format!("{}{} && {}", TEMP_FILE, Self::capture_command(tool, target, "\"$file\"", compositor), copy);
+        };
+        let out = shell_quote(&file.display().to_string());
+        format!(
+            "{} && notify-send -i {1} 'Screenshot saved' {1}",
+            Self::capture_command(tool, target, &out, compositor),
+            out
+        )
+    }
+
+    /// Shell commands reading the text in a selected region with tesseract
+    /// and copying it; the region's image is kept in a private temp file
+    /// meanwhile.
+    fn ocr_script(tool: Tool) -> String {
+        let copy = if tool == Tool::Grim { "wl-copy" } else { "xclip -selection clipboard" };
+        format!(
+            "{}{} && text=$(tesseract \"$file\" - 2>/dev/null | tr -d '\\f'); \
+             if [ -n \"$text\" ]; then printf '%s' \"$text\" | {} && notify-send -- 'Text copied' \"$text\"; \
+             else notify-send 'No text found in the region'; fi",
+            TEMP_FILE,
+            Self::capture_command(tool, Target::Region, "\"$file\"", None),
+            copy
+        )
+    }
+
+    /// The command writing the screenshot to `out`, already quoted.
+    fn capture_command(tool: Tool, target: Target, out: &str, compositor: Option<&str>) -> String {
+        match (tool, target) {
+            (Tool::Grim, Target::Screen) => format!("grim {}", out),
+            (Tool::Grim, Target::Window) if compositor == Some("hyprland") => format!(
+                "grim -g \"$(hyprctl -j activewindow | jq -r '\"\\(.at[0]),\\(.at[1]) \\(.size[0])x\\(.size[1])\"')\" {}",
+                out
+            ),
+            (Tool::Grim, Target::Window) if compositor == Some("sway") => format!(
+                "grim -g \"$(swaymsg -t get_tree | jq -r '.. | select(.focused?) | .rect | \"\\(.x),\\(.y) \\(.width)x\\(.height)\"')\" {}",
+                out
+            ),
+            // Other compositors: pick the window like a region
+            (Tool::Grim, _) => format!("grim -g \"$(slurp)\" {}", out),
+            (Tool::Maim, Target::Screen) => format!("maim {}", out),
+            (Tool::Maim, Target::Region) => format!("maim -s {}", out),
+            (Tool::Maim, Target::Window) => format!("maim -i \"$(xdotool getactivewindow)\" {}", out),
+            (Tool::Scrot, Target::Screen) => format!("scrot -o {}", out),
+            (Tool::Scrot, Target::Region) => format!("scrot -o -s {}", out),
+            (Tool::Scrot, Target::Window) => format!("scrot -o -u {}", out),
+        }
+    }
+
+    /// wf-recorder runs until `shot:stop` interrupts it.
+    fn record_script(region: bool, file: &Path) -> String {
+        let out = shell_quote(&file.display().to_string());
+        let geometry = if region { " -g \"$(slurp)\"" } else { "" };
+        format!("wf-recorder{} -f {1}; notify-send 'Recording saved' {1}", geometry, out)
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+impl Default for ScreenshotManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    #[test]
+    fn test_capture_scripts() {
+        assert_eq!(
+            ScreenshotManager::capture_script(Tool::Grim, Target::Region, None, None),
+            r#"file=$(mktemp --suffix=.png) || exit 1; trap 'rm -f "$file"' EXIT; grim -g "$(slurp)" "$file" && wl-copy --type image/png < "$file""#
+        );
+        assert_eq!(
+            ScreenshotManager::capture_script(Tool::Maim, Target::Window, Some(Path::new("/p/shot's.png")), None),
+            r#"maim -i "$(xdotool getactivewindow)" '/p/shot'\''s.png' && notify-send -i '/p/shot'\''s.png' 'Screenshot saved' '/p/shot'\''s.png'"#
+        );
+        assert!(ScreenshotManager::capture_script(Tool::Grim, Target::Window, None, Some("hyprland")).contains("hyprctl -j activewindow"));
+        assert_eq!(
+            ScreenshotManager::record_script(true, Path::new("/v/r.mp4")),
+            r#"wf-recorder -g "$(slurp)" -f '/v/r.mp4'; notify-send 'Recording saved' '/v/r.mp4'"#
+        );
+
+        assert_eq!(
+            ScreenshotManager::ocr_script(Tool::Maim),
+            "file=$(mktemp --suffix=.png) || exit 1; trap 'rm -f \"$file\"' EXIT; \
+             maim -s \"$file\" && text=$(tesseract \"$file\" - 2>/dev/null | tr -d '\\f'); \
+             if [ -n \"$text\" ]; then printf '%s' \"$text\" | xclip -selection clipboard && notify-send -- 'Text copied' \"$text\"; \
+             else notify-send 'No text found in the region'; fi"
+        );
+
+        let idle = ScreenshotManager::with_runner(FakeRunner::new().failing("pgrep -x wf-recorder", 1, "").shared());
+        assert_eq!(idle.get_items("record").len(), 2);
+        let recording = ScreenshotManager::with_runner(FakeRunner::new().with("pgrep -x wf-recorder", "4242\n").shared());
+        assert_eq!(recording.get_items("")[0].id, "shot:stop");
+        let ids: Vec<String> = recording.get_items("record").into_iter().map(|item| item.id).collect();
+        assert_eq!(ids, ["shot:stop"]);
+    }
+}
@@ -0,0 +1,165 @@
+//! Named sessions for tiling WMs: `session save <name>` records which apps
+//! have windows on which workspace, and restoring launches them again and
+//! moves each new window to its workspace.
+
+use super::WindowsManager;
+use crate::core::{Config, Item, ItemType};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// An app to launch and the workspace its window goes to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionApp {
+    /// Id of the indexed application, e.g. `app:Firefox`
+    pub app: String,
+    pub workspace: String,
+}
+
+#[derive(Clone, Default)]
+pub struct SessionManager;
+
+impl SessionManager {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn data_path() -> PathBuf {
+        Config::data_path("sessions.json")
+    }
+
+    fn load() -> Result<BTreeMap<String, Vec<SessionApp>>> {
+        let path = Self::data_path();
+        if path.exists() {
+            Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+        } else {
+            Ok(BTreeMap::new())
+        }
+    }
+
+    fn store(sessions: &BTreeMap<String, Vec<SessionApp>>) -> Result<()> {
+        let path = Self::data_path();
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, serde_json::to_string_pretty(sessions)?)?;
+        Ok(())
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        Self::items(query, &Self::load().unwrap_or_default())
+    }
+
+    fn items(query: &str, sessions: &BTreeMap<String, Vec<SessionApp>>) -> Vec<Item> {
+        let query = query.trim();
+        if let Some(name) = query.strip_prefix("save").map(str::trim).filter(|name| !name.is_empty()) {
+            let verb = if sessions.contains_key(name) { "Replace" } else { "Save" };
+            return vec![Item::new(format!("session:save:{}", name), format!("{} session \"{}\"", verb, name), ItemType::SessionAction)
+                .with_description("Records the apps open on each workspace")
+                .with_icon("document-save")];
+        }
+
+        let filter = query.strip_prefix("restore").unwrap_or(query).trim().to_lowercase();
+        let mut items: Vec<Item> = sessions
+            .iter()
+            .filter(|(name, _)| name.to_lowercase().contains(&filter))
+            .map(|(name, apps)| {
+                let mut workspaces: Vec<&str> = apps.iter().map(|app| app.workspace.as_str()).collect();
+                workspaces.sort();
+                workspaces.dedup();
+                Item::new(format!("session:restore:{}", name), format!("Restore session \"{}\"", name), ItemType::SessionAction)
+                    .with_description(format!("{} apps on workspaces {}", apps.len(), workspaces.join(", ")))
+                    .with_icon("view-restore")
+            })
+            .collect();
+        if items.is_empty() {
+            items.push(
+                Item::new("session:hint", "Type save <name> to record the open apps", ItemType::Command)
+                    .with_description("Restoring the session launches them on the same workspaces")
+                    .with_icon("dialog-information"),
+            );
+        }
+        items
+    }
+
+    /// One entry per app and workspace among `windows`; windows that no
+    /// indexed app owns, or that aren't on a workspace, are left out.
+    fn snapshot(windows: &[Item], apps: &[Item]) -> Vec<SessionApp> {
+        let mut entries: Vec<SessionApp> = Vec::new();
+        for window in windows {
+            let Some(workspace) = window.metadata.workspace.as_deref().filter(|ws| *ws != "sticky") else {
+                continue;
+            };
+            let Some(app) = apps
+                .iter()
+                .filter(|app| app.item_type == ItemType::Application)
+                .find(|app| !WindowsManager::windows_for_app(app, std::slice::from_ref(window)).is_empty())
+            else {
+                continue;
+            };
+            let entry = SessionApp {
+                app: app.id.clone(),
+                workspace: workspace.to_string(),
+            };
+            if !entries.contains(&entry) {
+                entries.push(entry);
+            }
+        }
+        entries
+    }
+
+    /// Saves the apps of `windows` as `name`; returns how many there are.
+    pub fn save(&self, name: &str, windows: &[Item], apps: &[Item]) -> Result<usize> {
+        let entries = Self::snapshot(windows, apps);
+        if entries.is_empty() {
+            return Err(anyhow!("No open window belongs to an installed app"));
+        }
+        let count = entries.len();
+        let mut sessions = Self::load()?;
+        sessions.insert(name.to_string(), entries);
+        Self::store(&sessions)?;
+        Ok(count)
+    }
+
+    pub fn session(&self, name: &str) -> Vec<SessionApp> {
+        Self::load().unwrap_or_default().remove(name).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(class: &str, workspace: &str) -> Item {
+        let mut item = Item::new(format!("window:{}", class), class, ItemType::Window);
+        item.metadata.window_id = Some(1);
+        item.metadata.wm_class = Some(class.to_string());
+        item.metadata.workspace = Some(workspace.to_string());
+        item
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut firefox = Item::new("app:Firefox", "Firefox", ItemType::Application).with_exec("firefox %u");
+        firefox.metadata.wm_class = Some("firefox".to_string());
+        let kitty = Item::new("app:kitty", "kitty", ItemType::Application).with_exec("kitty");
+        let windows = [
+            window("firefox", "1: web"),
+            window("firefox", "1: web"),
+            window("kitty", "2"),
+            window("kitty", "3"),
+            window("Unknown", "3"),
+            window("kitty", "sticky"),
+        ];
+        let entries = SessionManager::snapshot(&windows, &[firefox, kitty]);
+        let saved: Vec<(&str, &str)> = entries.iter().map(|e| (e.app.as_str(), e.workspace.as_str())).collect();
+        assert_eq!(saved, [("app:Firefox", "1: web"), ("app:kitty", "2"), ("app:kitty", "3")]);
+
+        let sessions = BTreeMap::from([("work".to_string(), entries)]);
+        assert_eq!(SessionManager::items("save work", &sessions)[0].name, "Replace session \"work\"");
+        let restore = &SessionManager::items("restore wo", &sessions)[0];
+        assert_eq!(restore.id, "session:restore:work");
+        assert_eq!(restore.description.as_deref(), Some("3 apps on workspaces 1: web, 2, 3"));
+        assert_eq!(SessionManager::items("games", &sessions)[0].id, "session:hint");
+    }
+}
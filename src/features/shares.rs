@@ -0,0 +1,380 @@
+//! Network shares: SMB and NFS servers announced on the LAN (avahi), the
+//! shares of one server (`smbclient --list`, `showmount`), and mounting a
+//! share with gio so the file manager opens it. Logins are asked for in the
+//! details panel and can be kept in the keyring with secret-tool.
+
+use crate::core::{CommandRunner, Item, ItemType, SystemRunner};
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+/// A server announced over mDNS.
+#[derive(Debug, PartialEq)]
+struct Server {
+    /// `smb` or `nfs`
+    protocol: &'static str,
+    name: String,
+    host: String,
+    address: String,
+}
+
+/// Resolved (`=`) lines of `avahi-browse --parsable`, one per server even
+/// when it is announced on several interfaces and over IPv4 and IPv6.
+fn parse_avahi(stdout: &str, protocol: &'static str) -> Vec<Server> {
+    let mut servers: Vec<Server> = Vec::new();
+    for line in stdout.lines() {
+        let fields: Vec<&str> = line.split(';').collect();
+        let ["=", _, ip, name, _, _, host, address, ..] = fields[..] else {
+            continue;
+        };
+        match servers.iter_mut().find(|server| server.host == host) {
+            Some(server) if ip == "IPv4" => server.address = address.to_string(),
+            Some(_) => {}
+            None => servers.push(Server {
+                protocol,
+                name: unescape(name),
+                host: host.to_string(),
+                address: address.to_string(),
+            }),
+        }
+    }
+    servers
+}
+
+/// avahi writes special characters as `\DDD` decimal escapes, e.g.
+/// `My\032NAS`.
+fn unescape(name: &str) -> String {
+    let mut out = String::new();
+    let mut rest = name;
+    while let Some(i) = rest.find('\\') {
+        out.push_str(&rest[..i]);
+        let code = rest.get(i + 1..i + 4).and_then(|digits| digits.parse::<u8>().ok());
+        match code {
+            Some(code) => {
+                out.push(code as char);
+                rest = &rest[i + 4..];
+            }
+            None => {
+                out.push('\\');
+                rest = &rest[i + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The server of `share //host`, `share smb://host` or `share nfs://host`,
+/// and the rest of the query.
+fn parse_target(query: &str) -> Option<(&'static str, &str, &str)> {
+    let (target, filter) = query.split_once(' ').unwrap_or((query, ""));
+    let (protocol, host) = if let Some(host) = target.strip_prefix("nfs://") {
+        ("nfs", host)
+    } else {
+        ("smb", target.strip_prefix("smb://").or_else(|| target.strip_prefix("//"))?)
+    };
+    let host = host.trim_end_matches('/');
+    (!host.is_empty()).then_some((protocol, host, filter.trim()))
+}
+
+/// Why a share didn't mount.
+#[derive(Debug, PartialEq)]
+pub enum MountError {
+    /// Mounting without a login failed, so ask for one
+    NeedsLogin(String),
+    Failed(String),
+}
+
+/// User name and password typed into the details panel for a share.
+#[derive(Debug, Clone, Default)]
+pub struct ShareLogin {
+    pub item_id: String,
+    pub uri: String,
+    pub user: String,
+    pub password: String,
+    /// Store the login in the keyring after it worked
+    pub remember: bool,
+    /// Why the last attempt failed
+    pub error: String,
+}
+
+#[derive(Clone)]
+pub struct SharesManager {
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl SharesManager {
+    pub fn new() -> Self {
+        Self::with_runner(SystemRunner::shared())
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Self {
+        Self { runner }
+    }
+
+    fn stdout(&self, program: &str, args: &[&str]) -> Result<String, String> {
+        match self.runner.run(program, args) {
+            Ok(output) if output.status.success() => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+            Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(format!("{} is not installed", program)),
+            Err(e) => Err(format!("Failed to run {}: {}", program, e)),
+        }
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim();
+        match parse_target(query) {
+            Some((protocol, host, filter)) => self.share_items(protocol, host, filter),
+            None => self.server_items(query),
+        }
+    }
+
+    /// Servers announced on the LAN; Enter lists a server's shares.
+    fn server_items(&self, filter: &str) -> Vec<Item> {
+        let filter = filter.to_lowercase();
+        let mut servers = Vec::new();
+        for (protocol, service) in [("smb", "_smb._tcp"), ("nfs", "_nfs._tcp")] {
+            match self.stdout("avahi-browse", &["--terminate", "--resolve", "--parsable", service]) {
+                Ok(stdout) => servers.extend(parse_avahi(&stdout, protocol)),
+                Err(e) if e.ends_with("not installed") => {
+                    return Item::error_items(
+                        "shares",
+                        "avahi-browse not found",
+                        "Install avahi to find servers, or type share //host to list a server's shares",
+                    )
+                }
+                Err(e) => return Item::error_items("shares", "Can't browse the network", e),
+            }
+        }
+        servers.retain(|server| {
+            filter.is_empty() || server.name.to_lowercase().contains(&filter) || server.host.to_lowercase().contains(&filter)
+        });
+
+        let mut items: Vec<Item> = servers
+            .iter()
+            .map(|server| {
+                let target = match server.protocol {
+                    "smb" => format!("//{}", server.host),
+                    _ => format!("nfs://{}", server.host),
+                };
+                Item::new(format!("share:server:{}", target), &server.name, ItemType::ShareAction)
+                    .with_description(format!("{} | {} | {}", server.protocol.to_uppercase(), server.host, server.address))
+                    .with_icon("network-server")
+            })
+            .collect();
+        if items.is_empty() {
+            let title = if filter.is_empty() { "No servers found" } else { "No servers match" };
+            items.push(
+                Item::new("share:none", title, ItemType::Command)
+                    .with_description("Type share //host to list the shares of a server that isn't announced")
+                    .with_icon("dialog-information"),
+            );
+        }
+        items
+    }
+
+    /// Shares of one server, plus the typed share name in case the server
+    /// doesn't list it.
+    fn share_items(&self, protocol: &str, host: &str, filter: &str) -> Vec<Item> {
+        let listed = match protocol {
+            // `/srv/media 192.168.1.0/24`: the export and the clients allowed
+            "nfs" => self.stdout("showmount", &["--exports", "--no-headers", host]).map(|stdout| {
+                stdout
+                    .lines()
+                    .filter_map(|line| {
+                        let mut words = line.split_whitespace();
+                        let path = words.next()?.trim_start_matches('/').to_string();
+                        Some((path, format!("Allowed: {}", words.collect::<Vec<_>>().join(" "))))
+                    })
+                    .collect::<Vec<_>>()
+            }),
+            _ => self.stdout("smbclient", &["--list", &format!("//{}", host), "--no-pass", "--grepable"]).map(|stdout| {
+                stdout
+                    .lines()
+                    .filter_map(|line| {
+                        let mut fields = line.splitn(3, '|');
+                        match (fields.next(), fields.next(), fields.next()) {
+                            // Administrative shares such as C$ end with $
+                            (Some("Disk"), Some(name), comment) if !name.ends_with('$') => {
+                                Some((name.to_string(), comment.unwrap_or_default().to_string()))
+                            }
+                            _ => None,
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            }),
+        };
+
+        let lower = filter.to_lowercase();
+        let mut items = Vec::new();
+        let mut error = None;
+        match listed {
+            Ok(shares) => {
+                for (name, comment) in shares.into_iter().filter(|(name, _)| name.to_lowercase().contains(&lower)) {
+                    let uri = format!("{}://{}/{}", protocol, host, name);
+                    let description = if comment.is_empty() { uri.clone() } else { format!("{} | {}", comment, uri) };
+                    items.push(
+                        Item::new(format!("share:{}", uri), &name, ItemType::NetworkShare)
+                            .with_description(description)
+                            .with_icon("folder-remote"),
+                    );
+                }
+            }
+            // Servers that refuse guests still mount with a login
+            Err(e) => error = Some(e),
+        }
+
+        let typed = filter.trim_start_matches('/');
+        if !typed.is_empty() && !items.iter().any(|item| item.name == typed) {
+            let uri = format!("{}://{}/{}", protocol, host, typed);
+            items.push(
+                Item::new(format!("share:{}", uri), typed, ItemType::NetworkShare)
+                    .with_description(format!("Mount {}", uri))
+                    .with_icon("folder-remote"),
+            );
+        }
+        if items.is_empty() {
+            return match error {
+                Some(e) => Item::error_items(
+                    "shares",
+                    format!("Can't list the shares of {}", host),
+                    format!("{}\nType the share name to mount it anyway", e),
+                ),
+                None => vec![Item::new("share:none", format!("No shares on {}", host), ItemType::Command)
+                    .with_icon("dialog-information")],
+            };
+        }
+        items
+    }
+
+    /// The login kept for `uri` in the keyring.
+    fn stored_login(&self, uri: &str) -> Option<(String, String)> {
+        let secret = self.stdout("secret-tool", &["lookup", "application", "wlaunch", "share", uri]).ok()?;
+        let (user, password) = secret.split_once('\n')?;
+        Some((user.to_string(), password.trim_end_matches('\n').to_string()))
+    }
+
+    /// Keeps a login in the keyring for the next mount.
+    pub fn store_login(&self, uri: &str, user: &str, password: &str) {
+        let label = format!("--label=wlaunch: {}", uri);
+        let child = Command::new("secret-tool")
+            .args(["store", &label, "application", "wlaunch", "share", uri])
+            .stdin(Stdio::piped())
+            .spawn();
+        let result = child.and_then(|mut child| {
+            child.stdin.take().map(|mut stdin| write!(stdin, "{}\n{}", user, password)).transpose()?;
+            child.wait()
+        });
+        if let Err(e) = result {
+            log::error!("Failed to store the login for {}: {}", uri, e);
+        }
+    }
+
+    /// Mounts `uri` with gio, with the given login, the one in the keyring,
+    /// or as a guest.
+    pub fn mount(&self, uri: &str, login: Option<(&str, &str)>) -> Result<(), MountError> {
+        let stored = if login.is_none() { self.stored_login(uri) } else { None };
+        let login = login.or(stored.as_ref().map(|(user, password)| (user.as_str(), password.as_str())));
+
+        let mut command = Command::new("gio");
+        command.arg("mount");
+        if login.is_none() {
+            command.arg("--anonymous");
+        }
+        let child = command
+            .arg(uri)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let output = child.and_then(|mut child| {
+            // gio asks for the user, the domain and the password in turn
+            if let (Some(mut stdin), Some((user, password))) = (child.stdin.take(), login) {
+                let (domain, user) = user.split_once('\\').unwrap_or(("", user));
+                write!(stdin, "{}\n{}\n{}\n", user, domain, password)?;
+            }
+            child.wait_with_output()
+        });
+        let output = output.map_err(|e| MountError::Failed(format!("Failed to run gio: {}", e)))?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if output.status.success() || stderr.contains("already mounted") {
+            Ok(())
+        } else if login.is_none() && uri.starts_with("smb://") {
+            Err(MountError::NeedsLogin(stderr))
+        } else {
+            Err(MountError::Failed(stderr))
+        }
+    }
+
+    /// Opens a mounted share in the file manager.
+    pub fn open(&self, uri: &str) {
+        if let Err(e) = Command::new("gio").args(["open", uri]).spawn() {
+            log::error!("Failed to open {}: {}", uri, e);
+        }
+    }
+}
+
+impl Default for SharesManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    fn manager() -> SharesManager {
+        let runner = FakeRunner::new()
+            .with(
+                "avahi-browse --terminate --resolve --parsable _smb._tcp",
+                "+;wlp3s0;IPv6;My\\032NAS;_smb._tcp;local\n\
+                 =;wlp3s0;IPv6;My\\032NAS;_smb._tcp;local;nas.local;fe80::1;445;\n\
+                 =;wlp3s0;IPv4;My\\032NAS;_smb._tcp;local;nas.local;192.168.1.10;445;\n",
+            )
+            .with(
+                "avahi-browse --terminate --resolve --parsable _nfs._tcp",
+                "=;wlp3s0;IPv4;media;_nfs._tcp;local;files.local;192.168.1.20;2049;\"path=/srv/media\"\n",
+            )
+            .with(
+                "smbclient --list //nas.local --no-pass --grepable",
+                "Disk|media|Movies and music\nDisk|backup|\nIPC|IPC$|IPC Service (Samba)\nDisk|print$|Printer Drivers\n",
+            )
+            .with("showmount --exports --no-headers files.local", "/srv/media 192.168.1.0/24\n")
+            .shared();
+        SharesManager::with_runner(runner)
+    }
+
+    #[test]
+    fn test_servers() {
+        let items = manager().get_items("");
+        let rows: Vec<String> = items.iter().map(|i| format!("{}: {}", i.id, i.description.as_deref().unwrap())).collect();
+        assert_eq!(
+            rows,
+            [
+                "share:server://nas.local: SMB | nas.local | 192.168.1.10",
+                "share:server:nfs://files.local: NFS | files.local | 192.168.1.20",
+            ]
+        );
+        assert_eq!(items[0].name, "My NAS");
+    }
+
+    #[test]
+    fn test_shares() {
+        let items = manager().get_items("//nas.local");
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["share:smb://nas.local/media", "share:smb://nas.local/backup"]);
+        assert_eq!(items[0].description.as_deref(), Some("Movies and music | smb://nas.local/media"));
+
+        // A share the server doesn't list can be typed
+        let items = manager().get_items("smb://nas.local/ private");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "share:smb://nas.local/private");
+
+        let items = manager().get_items("nfs://files.local");
+        assert_eq!(items[0].id, "share:nfs://files.local/srv/media");
+        assert_eq!(items[0].description.as_deref(), Some("Allowed: 192.168.1.0/24 | nfs://files.local/srv/media"));
+    }
+}
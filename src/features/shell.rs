@@ -0,0 +1,121 @@
+//! One-off shell commands typed after `>` or `run`: run in a terminal, or
+//! run in the background with the output copied or saved as a note.
+
+use crate::core::{Item, ItemType};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Longest a captured command may run before it's killed.
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where a captured command's output goes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CaptureTarget {
+    Clipboard,
+    Note,
+}
+
+#[derive(Clone, Default)]
+pub struct ShellManager;
+
+impl ShellManager {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let command = query.trim();
+        if command.is_empty() {
+            return vec![Item::new("shell:hint", "Type a command, e.g. ip a | grep inet", ItemType::Command)
+                .with_description("Runs it with sh in a terminal, or copies or saves its output")
+                .with_icon("utilities-terminal")];
+        }
+        [
+            ("terminal", "Run in terminal", "Keeps the terminal open after it exits", "utilities-terminal"),
+            ("copy", "Run and copy output", "Copies what it prints, without a terminal", "edit-copy"),
+            ("note", "Run and save output as note", "Saves the command and what it prints as a note", "document-new"),
+        ]
+        .into_iter()
+        .map(|(action, name, description, icon)| {
+            Item::new(format!("shell:{}:{}", action, command), format!("{}: {}", name, command), ItemType::ShellAction)
+                .with_description(description)
+                .with_icon(icon)
+        })
+        .collect()
+    }
+
+    /// The command and where its output goes, for the capturing actions.
+    pub fn capture_target(action_id: &str) -> Option<(CaptureTarget, &str)> {
+        let rest = action_id.strip_prefix("shell:")?;
+        if let Some(command) = rest.strip_prefix("copy:") {
+            Some((CaptureTarget::Clipboard, command))
+        } else {
+            rest.strip_prefix("note:").map(|command| (CaptureTarget::Note, command))
+        }
+    }
+
+    /// Runs `shell:terminal:<command>`; returns whether the terminal started.
+    pub fn run_in_terminal(&self, action_id: &str) -> bool {
+        let Some(command) = action_id.strip_prefix("shell:terminal:") else {
+            return false;
+        };
+        // Waits for Enter so the output can be read
+        let script = format!("{}; printf '\\n[exited with %s, press Enter]' $?; read _", command);
+        match Command::new("x-terminal-emulator").args(["-e", "sh", "-c", &script]).spawn() {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("Failed to run {} in a terminal: {}", command, e);
+                false
+            }
+        }
+    }
+
+    /// Runs `command` with sh and returns what it printed, without the
+    /// trailing newline. Fails when it prints nothing and exits with an
+    /// error, or runs longer than 30 seconds.
+    pub async fn capture(command: String) -> Result<String, String> {
+        let child = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(CAPTURE_TIMEOUT, child)
+            .await
+            .map_err(|_| format!("{} was still running after {} seconds", command, CAPTURE_TIMEOUT.as_secs()))?
+            .map_err(|e| format!("Failed to run {}: {}", command, e))?;
+        Self::output_text(output.status.success(), &output.stdout, &output.stderr)
+    }
+
+    fn output_text(success: bool, stdout: &[u8], stderr: &[u8]) -> Result<String, String> {
+        let stdout = String::from_utf8_lossy(stdout).trim_end_matches('\n').to_string();
+        if !stdout.is_empty() {
+            return Ok(stdout);
+        }
+        let stderr = String::from_utf8_lossy(stderr).trim().to_string();
+        match (success, stderr.is_empty()) {
+            (true, _) => Err("The command printed nothing".to_string()),
+            (false, true) => Err("The command failed without output".to_string()),
+            (false, false) => Err(stderr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions() {
+        let items = ShellManager::new().get_items(" ip a | grep inet ");
+        assert_eq!(items[1].id, "shell:copy:ip a | grep inet");
+        assert_eq!(
+            ShellManager::capture_target(&items[2].id),
+            Some((CaptureTarget::Note, "ip a | grep inet"))
+        );
+        assert_eq!(ShellManager::capture_target(&items[0].id), None);
+
+        assert_eq!(ShellManager::output_text(true, b"inet 10.0.0.2/24\n\n", b""), Ok("inet 10.0.0.2/24".to_string()));
+        assert_eq!(ShellManager::output_text(false, b"", b"sh: 1: nope: not found\n"), Err("sh: 1: nope: not found".to_string()));
+    }
+}
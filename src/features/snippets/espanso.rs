@@ -0,0 +1,161 @@
+//! Snippets registered as Espanso matches, so they also expand inline while
+//! typing anywhere. The match file is ours alone and rewritten on each save.
+
+use super::{token_regex, Snippet};
+use crate::core::config::EspansoConfig;
+use anyhow::Result;
+use regex::Captures;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+const HEADER: &str = "# Generated by wlaunch from its snippets; changes here are overwritten.\n";
+
+#[derive(Serialize)]
+struct MatchFile {
+    matches: Vec<Match>,
+}
+
+#[derive(Serialize)]
+struct Match {
+    trigger: String,
+    replace: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    vars: Vec<Var>,
+}
+
+#[derive(Serialize)]
+struct Var {
+    name: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    params: BTreeMap<&'static str, String>,
+}
+
+pub struct EspansoSync {
+    path: PathBuf,
+    prefix: String,
+}
+
+impl EspansoSync {
+    /// None unless `espanso.enabled` is set.
+    pub fn new(config: &EspansoConfig) -> Option<Self> {
+        config.enabled().then(|| Self {
+            path: config.match_file(),
+            prefix: config.trigger_prefix().to_string(),
+        })
+    }
+
+    /// Rewrites the match file and restarts Espanso, unless the file is
+    /// already up to date.
+    pub fn write(&self, snippets: &[Snippet]) -> Result<()> {
+        let file = MatchFile {
+            matches: snippets.iter().map(|s| self.to_match(s)).collect(),
+        };
+        let content = format!("{}{}", HEADER, serde_yaml::to_string(&file)?);
+        if fs::read_to_string(&self.path).is_ok_and(|existing| existing == content) {
+            return Ok(());
+        }
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, content)?;
+
+        if which::which("espanso").is_ok() {
+            Command::new("espanso").arg("restart").spawn()?;
+        }
+        Ok(())
+    }
+
+    /// `:` and the snippet name in lowercase with dashes for spaces, or the
+    /// name itself for snippets imported from Espanso (`:sig`).
+    fn trigger(&self, name: &str) -> String {
+        if name.len() > self.prefix.len() && name.starts_with(&self.prefix) {
+            return name.to_string();
+        }
+        let name = name.to_lowercase().split_whitespace().collect::<Vec<_>>().join("-");
+        format!("{}{}", self.prefix, name)
+    }
+
+    /// Our placeholders as Espanso variables: `{cursor}` is `$|$`, and named
+    /// placeholders are fields of one form.
+    fn to_match(&self, snippet: &Snippet) -> Match {
+        let mut vars = Vec::new();
+        let mut fields: Vec<String> = Vec::new();
+
+        let replace = token_regex()
+            .replace_all(&snippet.content, |caps: &Captures| match &caps[1] {
+                "cursor" => "$|$".to_string(),
+                "clipboard" => {
+                    if !vars.iter().any(|v: &Var| v.kind == "clipboard") {
+                        vars.push(Var {
+                            name: "clipboard".to_string(),
+                            kind: "clipboard",
+                            params: BTreeMap::new(),
+                        });
+                    }
+                    "{{clipboard}}".to_string()
+                }
+                "date" => {
+                    let name = format!("date{}", vars.iter().filter(|v| v.kind == "date").count() + 1);
+                    let format = caps.get(2).map_or("%Y-%m-%d", |m| m.as_str());
+                    vars.push(Var {
+                        name: name.clone(),
+                        kind: "date",
+                        params: BTreeMap::from([("format", format.to_string())]),
+                    });
+                    format!("{{{{{}}}}}", name)
+                }
+                field => {
+                    if !fields.iter().any(|f| f == field) {
+                        fields.push(field.to_string());
+                    }
+                    format!("{{{{form1.{}}}}}", field)
+                }
+            })
+            .into_owned();
+
+        if !fields.is_empty() {
+            let layout = fields.iter().map(|f| format!("{}: [[{}]]", f, f)).collect::<Vec<_>>().join("\n");
+            vars.insert(
+                0,
+                Var {
+                    name: "form1".to_string(),
+                    kind: "form",
+                    params: BTreeMap::from([("layout", layout)]),
+                },
+            );
+        }
+
+        Match {
+            trigger: self.trigger(&snippet.name),
+            replace,
+            vars,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_match() {
+        let sync = EspansoSync {
+            path: PathBuf::new(),
+            prefix: ":".to_string(),
+        };
+        let snippet = Snippet::new("Status Mail", "Hi {name}, {date:%d.%m.} {clipboard} {name}{cursor}", None);
+        let m = sync.to_match(&snippet);
+
+        assert_eq!(m.trigger, ":status-mail");
+        assert_eq!(m.replace, "Hi {{form1.name}}, {{date1}} {{clipboard}} {{form1.name}}$|$");
+        let vars: Vec<(&str, &str)> = m.vars.iter().map(|v| (v.name.as_str(), v.kind)).collect();
+        assert_eq!(vars, [("form1", "form"), ("date1", "date"), ("clipboard", "clipboard")]);
+        assert_eq!(m.vars[0].params["layout"], "name: [[name]]");
+        assert_eq!(sync.trigger(":sig"), ":sig");
+    }
+}
@@ -0,0 +1,291 @@
+//! Snippets from other tools: Espanso match files, VS Code snippet files and
+//! plain text files, or a directory of them. Their placeholders are turned
+//! into ours (`{cursor}`, `{clipboard}`, `{date:...}`, `{name}`) on the way in.
+
+use anyhow::{bail, Result};
+use regex::{Captures, Regex};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+pub struct Imported {
+    pub name: String,
+    pub content: String,
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Espanso,
+    VsCode,
+    Text,
+}
+
+impl Format {
+    /// By extension; anything unknown is read as plain text.
+    pub fn of(path: &Path) -> Format {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("yml" | "yaml") => Format::Espanso,
+            Some("json" | "code-snippets") => Format::VsCode,
+            _ => Format::Text,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Format::Espanso => "Espanso matches",
+            Format::VsCode => "VS Code snippets",
+            Format::Text => "Text file",
+        }
+    }
+}
+
+/// Snippets in `path`. Directories are searched a few levels deep for
+/// `.yml`, `.json`, `.code-snippets`, `.txt` and `.md` files.
+pub fn from_path(path: &Path) -> Result<Vec<Imported>> {
+    if !path.is_dir() {
+        return from_file(path);
+    }
+    let mut imported = Vec::new();
+    for entry in WalkDir::new(path).max_depth(3).sort_by_file_name().into_iter().flatten() {
+        let file = entry.path();
+        let known = matches!(
+            file.extension().and_then(|e| e.to_str()),
+            Some("yml" | "yaml" | "json" | "code-snippets" | "txt" | "md")
+        );
+        if entry.file_type().is_file() && known {
+            match from_file(file) {
+                Ok(snippets) => imported.extend(snippets),
+                Err(e) => log::warn!("Skipping {}: {}", file.display(), e),
+            }
+        }
+    }
+    Ok(imported)
+}
+
+fn from_file(path: &Path) -> Result<Vec<Imported>> {
+    let content = fs::read_to_string(path)?;
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("snippet").to_string();
+    match Format::of(path) {
+        Format::Espanso => from_espanso(&content),
+        Format::VsCode => {
+            // Per-language files are named after the language, `rust.json`
+            let language = (path.extension().and_then(|e| e.to_str()) == Some("json")).then_some(stem);
+            from_vscode(&content, language)
+        }
+        Format::Text if content.trim().is_empty() => Ok(Vec::new()),
+        Format::Text => Ok(vec![Imported {
+            name: stem,
+            content: content.trim_end().to_string(),
+            language: None,
+        }]),
+    }
+}
+
+#[derive(Deserialize)]
+struct EspansoFile {
+    #[serde(default)]
+    matches: Vec<EspansoMatch>,
+}
+
+#[derive(Deserialize)]
+struct EspansoMatch {
+    trigger: Option<String>,
+    #[serde(default)]
+    triggers: Vec<String>,
+    label: Option<String>,
+    replace: Option<String>,
+    markdown: Option<String>,
+    form: Option<String>,
+    #[serde(default)]
+    vars: Vec<EspansoVar>,
+}
+
+#[derive(Deserialize)]
+struct EspansoVar {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    params: serde_yaml::Value,
+}
+
+/// Matches with a text replacement; regex triggers and images are skipped.
+fn from_espanso(yaml: &str) -> Result<Vec<Imported>> {
+    let file: EspansoFile = serde_yaml::from_str(yaml)?;
+    let variable = Regex::new(r"\{\{\s*([\w.-]+)\s*\}\}").unwrap();
+    let field = Regex::new(r"\[\[\s*([\w-]+)\s*\]\]").unwrap();
+
+    Ok(file
+        .matches
+        .into_iter()
+        .filter_map(|m| {
+            let name = m.label.clone().or(m.trigger.clone()).or_else(|| m.triggers.first().cloned())?;
+            let text = m.replace.or(m.markdown).or(m.form)?;
+            let text = text.replace("$|$", "{cursor}");
+            let text = field.replace_all(&text, "{$1}");
+            let content = variable.replace_all(&text, |caps: &Captures| {
+                let var = &caps[1];
+                match m.vars.iter().find(|v| v.name == var).map(|v| (v.kind.as_str(), &v.params)) {
+                    Some(("clipboard", _)) => "{clipboard}".to_string(),
+                    Some(("date", params)) => match params.get("format").and_then(|f| f.as_str()) {
+                        Some(format) => format!("{{date:{}}}", format),
+                        None => "{date}".to_string(),
+                    },
+                    // Form fields are referenced as `{{form1.field}}`
+                    _ => format!("{{{}}}", var.rsplit('.').next().unwrap_or(var)),
+                }
+            });
+            Some(Imported {
+                name,
+                content: content.into_owned(),
+                language: None,
+            })
+        })
+        .collect())
+}
+
+/// `{ "Name": { "prefix": ..., "body": [...] } }`, with `//` comment lines
+/// allowed as VS Code does.
+fn from_vscode(json: &str, language: Option<String>) -> Result<Vec<Imported>> {
+    let json: String = json
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let Value::Object(snippets) = serde_json::from_str(&json)? else {
+        bail!("Expected an object of snippets");
+    };
+
+    Ok(snippets
+        .into_iter()
+        .filter_map(|(name, snippet)| {
+            let body = match snippet.get("body")? {
+                Value::String(line) => line.clone(),
+                Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect::<Vec<_>>().join("\n"),
+                _ => return None,
+            };
+            // `.code-snippets` files name their languages in `scope`
+            let language = language.clone().or_else(|| {
+                let scope = snippet.get("scope")?.as_str()?.split(',').next()?.trim();
+                (!scope.is_empty()).then(|| scope.to_string())
+            });
+            Some(Imported {
+                name,
+                content: vscode_placeholders(&body),
+                language,
+            })
+        })
+        .collect())
+}
+
+/// `$0` becomes `{cursor}`, `${1:name}` and later `$1` become `{name}`, and
+/// `$CLIPBOARD` and the `$CURRENT_*` date variables are mapped to ours.
+fn vscode_placeholders(body: &str) -> String {
+    let tab_stop = Regex::new(r"\\\$|\$\{(\d+)(?::([^{}$]*)|\|[^|]*\|)?\}|\$(\d+)|\$\{?([A-Z_]+)\}?").unwrap();
+
+    // Tab stops are named after their first label
+    let mut names: HashMap<&str, String> = HashMap::new();
+    for caps in tab_stop.captures_iter(body) {
+        if let (Some(index), Some(label)) = (caps.get(1), caps.get(2)) {
+            let label: String = label
+                .as_str()
+                .trim()
+                .to_lowercase()
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect();
+            if label.starts_with(|c: char| c.is_alphabetic()) {
+                names.entry(index.as_str()).or_insert(label);
+            }
+        }
+    }
+
+    tab_stop
+        .replace_all(body, |caps: &Captures| {
+            if &caps[0] == "\\$" {
+                return "$".to_string();
+            }
+            if let Some(index) = caps.get(1).or(caps.get(3)).map(|m| m.as_str()) {
+                return match index {
+                    "0" => "{cursor}".to_string(),
+                    _ => format!("{{{}}}", names.get(index).cloned().unwrap_or(format!("field{}", index))),
+                };
+            }
+            let format = match &caps[4] {
+                "CLIPBOARD" => return "{clipboard}".to_string(),
+                "CURRENT_YEAR" => "%Y",
+                "CURRENT_YEAR_SHORT" => "%y",
+                "CURRENT_MONTH" => "%m",
+                "CURRENT_MONTH_NAME" => "%B",
+                "CURRENT_MONTH_NAME_SHORT" => "%b",
+                "CURRENT_DATE" => "%d",
+                "CURRENT_DAY_NAME" => "%A",
+                "CURRENT_DAY_NAME_SHORT" => "%a",
+                "CURRENT_HOUR" => "%H",
+                "CURRENT_MINUTE" => "%M",
+                "CURRENT_SECOND" => "%S",
+                _ => return caps[0].to_string(),
+            };
+            format!("{{date:{}}}", format)
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_espanso() {
+        let yaml = r#"
+matches:
+  - trigger: ":sig"
+    replace: "Best,\n$|$"
+  - triggers: [":today", ":td"]
+    replace: "{{today}} / {{clip}}"
+    vars:
+      - name: today
+        type: date
+        params:
+          format: "%d.%m.%Y"
+      - name: clip
+        type: clipboard
+  - trigger: ":hey"
+    form: "Hey [[name]]!"
+  - regex: ":x(?P<n>\\d+)"
+    replace: "{{n}}"
+"#;
+        let imported = from_espanso(yaml).unwrap();
+        let pairs: Vec<(&str, &str)> = imported.iter().map(|s| (s.name.as_str(), s.content.as_str())).collect();
+        assert_eq!(
+            pairs,
+            [
+                (":sig", "Best,\n{cursor}"),
+                (":today", "{date:%d.%m.%Y} / {clipboard}"),
+                (":hey", "Hey {name}!"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_vscode() {
+        let json = r#"{
+  // Place your snippets here
+  "Print": {
+    "prefix": "log",
+    "body": ["console.log('${1:Label}', $1);", "$0"]
+  },
+  "Header": { "body": "// ${1|a,b|} $CURRENT_YEAR \\$HOME $CLIPBOARD $2" }
+}"#;
+        let imported = from_vscode(json, Some("javascript".to_string())).unwrap();
+        // In the file's order
+        assert_eq!(imported[0].name, "Print");
+        assert_eq!(imported[0].content, "console.log('{label}', {label});\n{cursor}");
+        assert_eq!(imported[0].language.as_deref(), Some("javascript"));
+        assert_eq!(imported[1].content, "// {field1} {date:%Y} $HOME {clipboard} {field2}");
+    }
+}
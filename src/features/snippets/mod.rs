@@ -0,0 +1,398 @@
+mod espanso;
+pub mod import;
+
+use crate::core::{Config, Item, ItemType, Keyed, SyncedStore};
+use anyhow::Result;
+use chrono::Local;
+use espanso::EspansoSync;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Snippet {
+    id: String,
+    name: String,
+    content: String,
+    language: Option<String>,
+    created: String,
+    /// Also offered by the main search
+    #[serde(default)]
+    pinned: bool,
+}
+
+impl Snippet {
+    fn new(name: &str, content: &str, language: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            content: content.to_string(),
+            language,
+            created: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            pinned: false,
+        }
+    }
+}
+
+impl Keyed for Snippet {
+    fn key(&self) -> &str {
+        &self.id
+    }
+}
+
+/// `{name}` or `{name:argument}`; braces with spaces or quotes inside, as in
+/// code and JSON, are left alone.
+fn token_regex() -> &'static Regex {
+    static TOKEN: OnceLock<Regex> = OnceLock::new();
+    TOKEN.get_or_init(|| Regex::new(r"\{([A-Za-z_][\w-]*)(?::([^{}\n]*))?\}").unwrap())
+}
+
+/// Names of the fill-in placeholders in `content`, in order of first use.
+/// `{cursor}`, `{clipboard}` and `{date}` are filled in automatically.
+pub fn placeholders(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for caps in token_regex().captures_iter(content) {
+        let name = &caps[1];
+        if !matches!(name, "cursor" | "clipboard" | "date") && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// `content` with its placeholders replaced. `{date:%d.%m.%Y}` takes a chrono
+/// format (default `%Y-%m-%d`); `{cursor}` is dropped. Placeholders without a
+/// value, and `{clipboard}` when `clipboard` is `None`, are kept as typed.
+pub fn expand(content: &str, values: &[(String, String)], clipboard: Option<&str>) -> String {
+    token_regex()
+        .replace_all(content, |caps: &Captures| match &caps[1] {
+            "cursor" => String::new(),
+            "clipboard" => clipboard.map_or_else(|| caps[0].to_string(), str::to_string),
+            "date" => {
+                let format = caps.get(2).map_or("%Y-%m-%d", |m| m.as_str());
+                Local::now().format(format).to_string()
+            }
+            name => values
+                .iter()
+                .find(|(n, v)| n == name && !v.is_empty())
+                .map_or_else(|| caps[0].to_string(), |(_, v)| v.clone()),
+        })
+        .into_owned()
+}
+
+/// Values typed into the details panel for a snippet's placeholders.
+#[derive(Debug, Clone)]
+pub struct SnippetForm {
+    pub item_id: String,
+    pub content: String,
+    pub fields: Vec<(String, String)>,
+}
+
+impl SnippetForm {
+    /// None for snippets without fill-in placeholders.
+    pub fn new(item: &Item) -> Option<Self> {
+        let content = item.metadata.content.clone()?;
+        let names = placeholders(&content);
+        if names.is_empty() {
+            return None;
+        }
+        Some(Self {
+            item_id: item.id.clone(),
+            content,
+            fields: names.into_iter().map(|name| (name, String::new())).collect(),
+        })
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.fields.iter().all(|(_, value)| !value.is_empty())
+    }
+
+    pub fn expand(&self, clipboard: Option<&str>) -> String {
+        expand(&self.content, &self.fields, clipboard)
+    }
+}
+
+pub struct SnippetsManager {
+    snippets: Vec<Snippet>,
+    store: SyncedStore,
+    espanso: Option<EspansoSync>,
+}
+
+impl SnippetsManager {
+    pub fn new(config: &Config) -> Self {
+        let mut store = SyncedStore::new(config, "snippets.json");
+        let snippets = store.load().unwrap_or_default();
+        let manager = Self {
+            snippets,
+            store,
+            espanso: EspansoSync::new(&config.espanso),
+        };
+        manager.sync_espanso();
+        manager
+    }
+
+    /// Picks up changes synced from another machine since the last read.
+    pub fn reload_if_changed(&mut self) {
+        if self.store.changed() {
+            if let Ok(snippets) = self.store.load() {
+                self.snippets = snippets;
+                self.sync_espanso();
+            }
+        }
+    }
+
+    fn save(&mut self) -> Result<()> {
+        self.store.save(&mut self.snippets)?;
+        self.sync_espanso();
+        Ok(())
+    }
+
+    fn sync_espanso(&self) {
+        if let Some(espanso) = &self.espanso {
+            if let Err(e) = espanso.write(&self.snippets) {
+                log::warn!("Could not update the Espanso match file: {}", e);
+            }
+        }
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query_lower = query.to_lowercase();
+        let mut items = Vec::new();
+
+        // Add action to create snippet
+        if query.starts_with("add ") || query.starts_with("new ") {
+            let rest = query.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
+            if !rest.is_empty() {
+                // Parse "name: content" format
+                if let Some(idx) = rest.find(':') {
+                    let name = rest[..idx].trim();
+                    let content = rest[idx + 1..].trim();
+                    if !name.is_empty() && !content.is_empty() {
+                        items.push(
+                            Item::new(
+                                format!("snippet:add:{}:{}", name, content),
+                                format!("Add Snippet: {}", name),
+                                ItemType::SnippetAction,
+                            )
+                            .with_description(format!("Content: {}", content))
+                            .with_icon("document-new"),
+                        );
+                    }
+                }
+            }
+        }
+
+        // "import <path>" reads snippets from Espanso, VS Code or text files
+        if let Some(path) = query.strip_prefix("import ") {
+            let path = path.trim();
+            let item = if path.is_empty() {
+                Item::new("snippet:import:hint", "Type a file or directory to import", ItemType::Command)
+                    .with_description("Espanso .yml, VS Code .json/.code-snippets, or text files")
+            } else {
+                let expanded = Config::expand_home(path);
+                if expanded.exists() {
+                    let kind = if expanded.is_dir() { "Directory" } else { import::Format::of(&expanded).label() };
+                    Item::new(format!("snippet:import:{}", path), format!("Import Snippets: {}", path), ItemType::SnippetAction)
+                        .with_description(kind)
+                } else {
+                    Item::new("snippet:import:missing", format!("Not found: {}", path), ItemType::Command)
+                }
+            };
+            return vec![item.with_icon("document-import")];
+        }
+
+        // "pin <name>" toggles whether a snippet shows up in the main search
+        if let Some(name) = query_lower.strip_prefix("pin ") {
+            let name = name.trim();
+            for snippet in &self.snippets {
+                if name.is_empty() || snippet.name.to_lowercase().contains(name) {
+                    let (title, icon) = if snippet.pinned {
+                        (format!("Unpin: {}", snippet.name), "window-unpin")
+                    } else {
+                        (format!("Pin: {}", snippet.name), "window-pin")
+                    };
+                    items.push(
+                        Item::new(format!("snippet:pin:{}", snippet.id), title, ItemType::SnippetAction)
+                            .with_description("Pinned snippets show up in the main search")
+                            .with_icon(icon),
+                    );
+                }
+            }
+            return items;
+        }
+
+        // List existing snippets
+        for snippet in &self.snippets {
+            if query_lower.is_empty()
+                || snippet.name.to_lowercase().contains(&query_lower)
+                || snippet.content.to_lowercase().contains(&query_lower)
+            {
+                let preview = if snippet.content.len() > 50 {
+                    format!("{}...", &snippet.content[..47])
+                } else {
+                    snippet.content.clone()
+                };
+
+                items.push(Self::snippet_item(snippet, preview));
+            }
+        }
+
+        // Add clear action
+        if !self.snippets.is_empty() && query.is_empty() {
+            items.push(
+                Item::new("snippet:action:clear", "Clear All Snippets", ItemType::SnippetAction)
+                    .with_description("Delete all snippets")
+                    .with_icon("edit-delete"),
+            );
+        }
+
+        items
+    }
+
+    /// Pinned snippets, for the main search to match against.
+    pub fn pinned_items(&self) -> Vec<Item> {
+        self.snippets
+            .iter()
+            .filter(|s| s.pinned)
+            .map(|s| Self::snippet_item(s, s.content.clone()))
+            .collect()
+    }
+
+    fn snippet_item(snippet: &Snippet, description: String) -> Item {
+        let mut item = Item::new(format!("snippet:{}", snippet.id), &snippet.name, ItemType::Snippet)
+            .with_description(description)
+            .with_icon("text-x-script");
+
+        item.metadata.content = Some(snippet.content.clone());
+        item.metadata.created = Some(snippet.created.clone());
+        item
+    }
+
+    pub fn add_snippet(&mut self, name: &str, content: &str) {
+        self.snippets.insert(0, Snippet::new(name, content, None));
+        let _ = self.save();
+    }
+
+    /// Adds the snippets found at `path`, skipping ones already here with the
+    /// same name and content. Returns how many were added.
+    pub fn import(&mut self, path: &Path) -> Result<usize> {
+        let mut added = 0;
+        for imported in import::from_path(path)? {
+            let exists = self
+                .snippets
+                .iter()
+                .any(|s| s.name == imported.name && s.content == imported.content);
+            if !exists {
+                self.snippets.push(Snippet::new(&imported.name, &imported.content, imported.language));
+                added += 1;
+            }
+        }
+        if added > 0 {
+            self.save()?;
+        }
+        Ok(added)
+    }
+
+    pub fn delete_snippet(&mut self, id: &str) {
+        self.snippets.retain(|s| s.id != id);
+        let _ = self.save();
+    }
+
+    pub fn toggle_pinned(&mut self, id: &str) {
+        if let Some(snippet) = self.snippets.iter_mut().find(|s| s.id == id) {
+            snippet.pinned = !snippet.pinned;
+            let _ = self.save();
+        }
+    }
+
+    pub fn clear_snippets(&mut self) {
+        self.snippets.clear();
+        let _ = self.save();
+    }
+
+    pub fn execute_action(&mut self, action_id: &str, query: &str) {
+        if action_id.starts_with("snippet:add:") {
+            let rest = action_id.strip_prefix("snippet:add:").unwrap();
+            if let Some(idx) = rest.find(':') {
+                let name = &rest[..idx];
+                let content = &rest[idx + 1..];
+                self.add_snippet(name, content);
+            }
+        } else if action_id == "snippet:action:clear" {
+            self.clear_snippets();
+        } else if let Some(id) = action_id.strip_prefix("snippet:pin:") {
+            self.toggle_pinned(id);
+        } else if action_id.starts_with("snippet:delete:") {
+            let id = action_id.strip_prefix("snippet:delete:").unwrap();
+            self.delete_snippet(id);
+        } else if query.starts_with("add ") || query.starts_with("new ") {
+            let rest = query.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
+            if let Some(idx) = rest.find(':') {
+                let name = rest[..idx].trim();
+                let content = rest[idx + 1..].trim();
+                if !name.is_empty() && !content.is_empty() {
+                    self.add_snippet(name, content);
+                }
+            }
+        }
+    }
+}
+
+impl Default for SnippetsManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholders() {
+        let content = "Hi {name}, see {link} {cursor}- {name}\nfn main() { }\n{\"a\": 1}";
+        assert_eq!(placeholders(content), ["name", "link"]);
+
+        let values = vec![("name".to_string(), "Ada".to_string()), ("link".to_string(), String::new())];
+        assert_eq!(
+            expand(content, &values, None),
+            "Hi Ada, see {link} - Ada\nfn main() { }\n{\"a\": 1}"
+        );
+        assert_eq!(expand("{clipboard}!", &[], Some("copied")), "copied!");
+        assert_eq!(expand("{clipboard}!", &[], None), "{clipboard}!");
+        assert_eq!(expand("{date:%Y}", &[], None), Local::now().format("%Y").to_string());
+    }
+
+    #[test]
+    fn test_pinned_snippets() {
+        let dir = std::env::temp_dir().join(format!("wlaunch-snippets-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut manager = SnippetsManager {
+            snippets: vec![Snippet::new("Email signature", "Regards,\nAda", None), Snippet::new("Address", "1 Main St", None)],
+            store: SyncedStore::at(dir.join("snippets.json")),
+            espanso: None,
+        };
+        let id = manager.snippets[0].id.clone();
+        assert!(manager.pinned_items().is_empty());
+
+        let items = manager.get_items("pin email");
+        let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, ["Pin: Email signature"]);
+        manager.execute_action(&items[0].id, "pin email");
+
+        let pinned = manager.pinned_items();
+        assert_eq!(pinned.len(), 1);
+        assert_eq!(pinned[0].id, format!("snippet:{}", id));
+        assert_eq!(pinned[0].description.as_deref(), Some("Regards,\nAda"));
+        assert_eq!(manager.get_items("pin email")[0].name, "Unpin: Email signature");
+        // "pin" alone lists every snippet
+        assert_eq!(manager.get_items("pin ").len(), 2);
+
+        manager.execute_action(&format!("snippet:pin:{}", id), "");
+        assert!(manager.pinned_items().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -76,7 +76,7 @@ impl SshManager {
                 continue;
             }
 
-            let parts: Vec<&str> = line.splitn(2, |c| c == ' ' || c == '=').collect();
+            let parts: Vec<&str> = line.splitn(2, [' ', '=']).collect();
             if parts.len() != 2 {
                 continue;
             }
@@ -140,7 +140,7 @@ impl SshManager {
 
         // Add action to add new connection
         if query.starts_with("add ") {
-            let rest = query.splitn(2, ' ').nth(1).unwrap_or("");
+            let rest = query.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
             // Parse "user@host:port" format
             if !rest.is_empty() {
                 let (user, host_port): (&str, &str) = if rest.contains('@') {
@@ -239,7 +239,7 @@ impl SshManager {
             self.delete_connection(id);
         } else if query.starts_with("add ") {
             // Parse and add from query
-            let rest = query.splitn(2, ' ').nth(1).unwrap_or("");
+            let rest = query.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
             let (user, host_port): (&str, &str) = if rest.contains('@') {
                 let parts: Vec<&str> = rest.splitn(2, '@').collect();
                 (parts[0], parts.get(1).copied().unwrap_or(""))
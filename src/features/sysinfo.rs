@@ -0,0 +1,398 @@
+//! Read-only system dashboard: CPU load and per-core usage, memory and
+//! swap, battery, temperature, disk space, uptime, load average, kernel and
+//! OS; `sys temps` lists every temperature sensor and fan. The launcher
+//! refreshes it on a tick while the mode is open, and the daemon's alerts
+//! watch the same values.
+
+use crate::core::{Config, Item, ItemType};
+use ::sysinfo::{Components, Disks, System};
+use serde_json::Value;
+use std::fs;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+/// Filesystems that never fill up, or fill up by design
+const VIRTUAL_FILESYSTEMS: [&str; 6] = ["squashfs", "overlay", "tmpfs", "devtmpfs", "efivarfs", "ramfs"];
+
+/// Values shown by the dashboard, read in one refresh.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    pub cpu_brand: String,
+    /// Overall and per-core usage in percent
+    pub cpu_usage: f32,
+    pub core_usage: Vec<f32>,
+    /// Bytes
+    pub memory_used: u64,
+    pub memory_total: u64,
+    pub swap_used: u64,
+    pub swap_total: u64,
+    /// Seconds since boot
+    pub uptime: u64,
+    /// 1, 5 and 15 minute load averages
+    pub load_average: [f64; 3],
+    pub kernel: String,
+    pub os: String,
+    pub host: String,
+    /// Percent and status of the first battery, e.g. "Discharging"
+    pub battery: Option<(u8, String)>,
+    /// Hottest sensor and its temperature in °C
+    pub temperature: Option<(String, f32)>,
+    /// Mountpoint, available and total bytes of each real filesystem
+    pub disks: Vec<(String, u64, u64)>,
+}
+
+/// A temperature sensor or fan of `sys temps`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sensor {
+    pub label: String,
+    /// °C, or RPM for fans
+    pub value: f32,
+    pub fan: bool,
+    /// Temperature the hardware considers critical, if it reports one
+    pub critical: Option<f32>,
+}
+
+/// Fans of `sensors -j`, e.g. `"thinkpad-isa-0000": {"fan1": {"fan1_input": 2400}}`.
+/// Unconnected fan headers read 0 and are left out.
+fn parse_fans(json: &str) -> Vec<Sensor> {
+    let Ok(Value::Object(chips)) = serde_json::from_str::<Value>(json) else {
+        return Vec::new();
+    };
+    let mut fans = Vec::new();
+    for (chip, features) in &chips {
+        let chip = chip.split('-').next().unwrap_or(chip);
+        for (feature, readings) in features.as_object().into_iter().flatten() {
+            let rpm = readings
+                .as_object()
+                .and_then(|readings| readings.iter().find(|(key, _)| key.starts_with("fan") && key.ends_with("_input")))
+                .and_then(|(_, rpm)| rpm.as_f64());
+            if let Some(rpm) = rpm.filter(|rpm| *rpm > 0.0) {
+                fans.push(Sensor {
+                    label: format!("{} {}", chip, feature),
+                    value: rpm as f32,
+                    fan: true,
+                    critical: None,
+                });
+            }
+        }
+    }
+    fans
+}
+
+/// Cloning is cheap: clones share the same `System`, which keeps CPU usage
+/// deltas meaningful across refreshes, like the processes mode.
+#[derive(Clone)]
+pub struct SysInfo {
+    system: Arc<Mutex<System>>,
+    /// °C from which `sys temps` highlights a component
+    temperature_warning: f32,
+}
+
+impl SysInfo {
+    pub fn new(config: &Config) -> Self {
+        let mut system = System::new();
+        // CPU usage is measured between refreshes, so start the first interval now
+        system.refresh_cpu_usage();
+        Self {
+            system: Arc::new(Mutex::new(system)),
+            temperature_warning: config.alerts.temperature_warning(),
+        }
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        let mut system = self.system.lock().unwrap_or_else(|e| e.into_inner());
+        system.refresh_cpu_usage();
+        system.refresh_memory();
+        let load = System::load_average();
+
+        Snapshot {
+            cpu_brand: system.cpus().first().map(|cpu| cpu.brand().trim().to_string()).unwrap_or_default(),
+            cpu_usage: system.global_cpu_usage(),
+            core_usage: system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect(),
+            memory_used: system.used_memory(),
+            memory_total: system.total_memory(),
+            swap_used: system.used_swap(),
+            swap_total: system.total_swap(),
+            uptime: System::uptime(),
+            load_average: [load.one, load.five, load.fifteen],
+            kernel: System::kernel_version().unwrap_or_default(),
+            os: System::long_os_version().unwrap_or_default(),
+            host: System::host_name().unwrap_or_default(),
+            battery: battery(),
+            temperature: Components::new_with_refreshed_list()
+                .iter()
+                .filter(|component| component.temperature().is_finite())
+                .max_by(|a, b| a.temperature().total_cmp(&b.temperature()))
+                .map(|component| (component.label().to_string(), component.temperature())),
+            disks: Disks::new_with_refreshed_list()
+                .iter()
+                .filter(|disk| {
+                    disk.total_space() > 0 && !VIRTUAL_FILESYSTEMS.iter().any(|fs| disk.file_system() == *fs)
+                })
+                .map(|disk| (disk.mount_point().display().to_string(), disk.available_space(), disk.total_space()))
+                .collect(),
+        }
+    }
+
+    /// Temperatures (CPU, GPU, NVMe, ...) from hwmon, and fans from
+    /// lm-sensors when it is installed.
+    fn sensors() -> Vec<Sensor> {
+        let mut sensors: Vec<Sensor> = Components::new_with_refreshed_list()
+            .iter()
+            .filter(|component| component.temperature().is_finite())
+            .map(|component| Sensor {
+                label: component.label().to_string(),
+                value: component.temperature(),
+                fan: false,
+                critical: component.critical().filter(|critical| critical.is_finite() && *critical > 0.0),
+            })
+            .collect();
+        if let Ok(output) = Command::new("sensors").arg("-j").output() {
+            sensors.extend(parse_fans(&String::from_utf8_lossy(&output.stdout)));
+        }
+        sensors
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let (first, rest) = query.trim().split_once(' ').unwrap_or((query.trim(), ""));
+        if matches!(first, "temps" | "temp" | "sensors") {
+            return Self::sensor_items(&Self::sensors(), rest, self.temperature_warning);
+        }
+        Self::items(&self.snapshot(), query)
+    }
+
+    /// Temperatures, hottest first, then fans; those at the warning
+    /// threshold or their critical temperature are marked.
+    pub fn sensor_items(sensors: &[Sensor], query: &str, warning: f32) -> Vec<Item> {
+        let query = query.trim().to_lowercase();
+        let mut sensors: Vec<&Sensor> = sensors
+            .iter()
+            .filter(|sensor| query.is_empty() || sensor.label.to_lowercase().contains(&query))
+            .collect();
+        sensors.sort_by(|a, b| a.fan.cmp(&b.fan).then(b.value.total_cmp(&a.value)));
+
+        let mut items: Vec<Item> = sensors
+            .iter()
+            .map(|sensor| {
+                let (value, icon) = if sensor.fan {
+                    (format!("{:.0} RPM", sensor.value), "fan")
+                } else {
+                    match sensor.critical {
+                        Some(critical) => (format!("{:.0} °C | critical {:.0} °C", sensor.value, critical), "temperature"),
+                        None => (format!("{:.0} °C", sensor.value), "temperature"),
+                    }
+                };
+                let id = format!("sys:sensor:{}", sensor.label.to_lowercase().replace(' ', "-"));
+                let mut item = Item::new(id, &sensor.label, ItemType::SysInfo).with_description(&value).with_icon(icon);
+                item.metadata.content = Some(format!("{}: {}", sensor.label, value));
+                item.metadata.warning =
+                    !sensor.fan && (sensor.value >= warning || sensor.critical.is_some_and(|critical| sensor.value >= critical));
+                item
+            })
+            .collect();
+        if items.is_empty() {
+            let title = if query.is_empty() { "No sensors found" } else { "No sensors match" };
+            items.push(Item::new("sys:sensor:none", title, ItemType::Command).with_icon("dialog-information"));
+        }
+        items
+    }
+
+    /// Dashboard rows whose name contains the query; Enter copies a row.
+    pub fn items(snapshot: &Snapshot, query: &str) -> Vec<Item> {
+        let mut rows = vec![
+            (
+                "CPU".to_string(),
+                format!(
+                    "{} {:.0}% | {} cores | {}",
+                    meter(snapshot.cpu_usage / 100.0),
+                    snapshot.cpu_usage,
+                    snapshot.core_usage.len(),
+                    snapshot.cpu_brand
+                ),
+                "cpu",
+            ),
+            ("Memory".to_string(), usage(snapshot.memory_used, snapshot.memory_total), "memory"),
+            (
+                "Swap".to_string(),
+                if snapshot.swap_total == 0 { "No swap".to_string() } else { usage(snapshot.swap_used, snapshot.swap_total) },
+                "drive-harddisk",
+            ),
+            (
+                "Load average".to_string(),
+                format!(
+                    "{:.2} {:.2} {:.2} (1, 5, 15 min)",
+                    snapshot.load_average[0], snapshot.load_average[1], snapshot.load_average[2]
+                ),
+                "utilities-system-monitor",
+            ),
+            ("Uptime".to_string(), format_uptime(snapshot.uptime), "appointment-soon"),
+        ];
+        if let Some((percent, status)) = &snapshot.battery {
+            rows.push((
+                "Battery".to_string(),
+                format!("{} {}% | {}", meter(*percent as f32 / 100.0), percent, status),
+                "battery",
+            ));
+        }
+        if let Some((sensor, celsius)) = &snapshot.temperature {
+            rows.push(("Temperature".to_string(), format!("{:.0} °C | {}", celsius, sensor), "temperature"));
+        }
+        for (mountpoint, available, total) in &snapshot.disks {
+            let used = 1.0 - *available as f32 / *total as f32;
+            rows.push((
+                format!("Disk {}", mountpoint),
+                format!("{} {} free of {}", meter(used), gib(*available), gib(*total)),
+                "drive-harddisk",
+            ));
+        }
+        rows.extend([
+            ("Kernel".to_string(), snapshot.kernel.clone(), "system"),
+            ("OS".to_string(), snapshot.os.clone(), "system"),
+            ("Hostname".to_string(), snapshot.host.clone(), "computer"),
+        ]);
+        // Cores last, as there may be many
+        for (i, core) in snapshot.core_usage.iter().enumerate() {
+            rows.push((format!("Core {}", i), format!("{} {:.0}%", meter(core / 100.0), core), "cpu"));
+        }
+
+        let query = query.trim().to_lowercase();
+        rows.into_iter()
+            .filter(|(name, _, _)| query.is_empty() || name.to_lowercase().contains(&query))
+            .map(|(name, value, icon)| {
+                let id = format!("sys:{}", name.to_lowercase().replace(' ', "-"));
+                let mut item = Item::new(id, &name, ItemType::SysInfo).with_description(&value).with_icon(icon);
+                item.metadata.content = Some(format!("{}: {}", name, value));
+                item
+            })
+            .collect()
+    }
+}
+
+impl Default for SysInfo {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+/// `used of total (percent)` in GiB.
+fn usage(used: u64, total: u64) -> String {
+    let fraction = if total == 0 { 0.0 } else { used as f32 / total as f32 };
+    format!("{} {} of {} ({:.0}%)", meter(fraction), gib(used), gib(total), fraction * 100.0)
+}
+
+pub(crate) fn gib(bytes: u64) -> String {
+    format!("{:.1} GiB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+}
+
+/// A ten-cell bar, e.g. `▮▮▮▯▯▯▯▯▯▯` for 0.3.
+pub(crate) fn meter(fraction: f32) -> String {
+    let filled = (fraction.clamp(0.0, 1.0) * 10.0).round() as usize;
+    format!("{}{}", "▮".repeat(filled), "▯".repeat(10 - filled))
+}
+
+fn format_uptime(seconds: u64) -> String {
+    let (days, hours, minutes) = (seconds / 86_400, seconds % 86_400 / 3600, seconds % 3600 / 60);
+    match (days, hours) {
+        (0, 0) => format!("{} min", minutes),
+        (0, _) => format!("{} h {} min", hours, minutes),
+        (1, _) => format!("1 day, {} h", hours),
+        _ => format!("{} days, {} h", days, hours),
+    }
+}
+
+/// The system battery from sysfs, e.g. `(54, "Discharging")`; mice and
+/// headsets report batteries too, with a `Device` scope.
+pub(crate) fn battery() -> Option<(u8, String)> {
+    let read = |path: &std::path::Path, name: &str| fs::read_to_string(path.join(name)).map(|value| value.trim().to_string());
+    fs::read_dir("/sys/class/power_supply").ok()?.flatten().find_map(|entry| {
+        let path = entry.path();
+        if read(&path, "type").ok()? != "Battery" || read(&path, "scope").is_ok_and(|scope| scope == "Device") {
+            return None;
+        }
+        let capacity = read(&path, "capacity").ok()?.parse().ok()?;
+        Some((capacity, read(&path, "status").unwrap_or_default()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_items() {
+        let snapshot = Snapshot {
+            cpu_brand: "AMD Ryzen 7 5800U".to_string(),
+            cpu_usage: 31.0,
+            core_usage: vec![12.0, 50.0],
+            memory_used: 4 * 1024 * 1024 * 1024,
+            memory_total: 16 * 1024 * 1024 * 1024,
+            uptime: 2 * 86_400 + 5 * 3600 + 120,
+            load_average: [0.52, 0.61, 0.7],
+            kernel: "6.9.7-arch1-1".to_string(),
+            ..Default::default()
+        };
+        let items = SysInfo::items(&snapshot, "");
+        let rows: Vec<String> = items.iter().map(|i| format!("{}: {}", i.name, i.description.as_deref().unwrap())).collect();
+        assert_eq!(rows[0], "CPU: ▮▮▮▯▯▯▯▯▯▯ 31% | 2 cores | AMD Ryzen 7 5800U");
+        assert_eq!(rows[1], "Memory: ▮▮▮▯▯▯▯▯▯▯ 4.0 GiB of 16.0 GiB (25%)");
+        assert_eq!(rows[2], "Swap: No swap");
+        assert_eq!(rows[3], "Load average: 0.52 0.61 0.70 (1, 5, 15 min)");
+        assert_eq!(rows[4], "Uptime: 2 days, 5 h");
+        assert_eq!(rows[9], "Core 1: ▮▮▮▮▮▯▯▯▯▯ 50%");
+
+        let items = SysInfo::items(&snapshot, "core");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["Core 0", "Core 1"]);
+        assert_eq!(format_uptime(3 * 60), "3 min");
+
+        let snapshot = Snapshot {
+            battery: Some((54, "Discharging".to_string())),
+            temperature: Some(("k10temp Tctl".to_string(), 71.6)),
+            disks: vec![("/".to_string(), 100 * 1024 * 1024 * 1024, 400 * 1024 * 1024 * 1024)],
+            ..snapshot
+        };
+        let rows: Vec<String> = SysInfo::items(&snapshot, "")
+            .iter()
+            .map(|i| format!("{}: {}", i.name, i.description.as_deref().unwrap()))
+            .collect();
+        assert_eq!(rows[5], "Battery: ▮▮▮▮▮▯▯▯▯▯ 54% | Discharging");
+        assert_eq!(rows[6], "Temperature: 72 °C | k10temp Tctl");
+        assert_eq!(rows[7], "Disk /: ▮▮▮▮▮▮▮▮▯▯ 100.0 GiB free of 400.0 GiB");
+    }
+
+    #[test]
+    fn test_sensors() {
+        let temperature = |label: &str, value: f32, critical: Option<f32>| Sensor {
+            label: label.to_string(),
+            value,
+            fan: false,
+            critical,
+        };
+        let mut sensors = vec![
+            temperature("nvme Composite", 74.9, Some(84.8)),
+            temperature("k10temp Tctl", 81.0, None),
+            temperature("amdgpu edge", 48.0, Some(100.0)),
+        ];
+        sensors.extend(parse_fans(
+            r#"{"thinkpad-isa-0000": {"Adapter": "ISA adapter", "fan1": {"fan1_input": 2400.0}, "fan2": {"fan2_input": 0.0}}}"#,
+        ));
+
+        let items = SysInfo::sensor_items(&sensors, "", 80.0);
+        let rows: Vec<String> = items.iter().map(|i| format!("{}: {}", i.name, i.description.as_deref().unwrap())).collect();
+        assert_eq!(
+            rows,
+            [
+                "k10temp Tctl: 81 °C",
+                "nvme Composite: 75 °C | critical 85 °C",
+                "amdgpu edge: 48 °C | critical 100 °C",
+                "thinkpad fan1: 2400 RPM",
+            ]
+        );
+        let warnings: Vec<bool> = items.iter().map(|i| i.metadata.warning).collect();
+        assert_eq!(warnings, [true, false, false, false]);
+
+        let items = SysInfo::sensor_items(&sensors, "nvme", 70.0);
+        assert_eq!(items.len(), 1);
+        assert!(items[0].metadata.warning);
+    }
+}
@@ -0,0 +1,140 @@
+//! Open tabs of Chromium-based browsers started with
+//! `--remote-debugging-port`, listed and focused over the DevTools HTTP
+//! endpoints.
+
+use crate::core::{Config, Item, ItemType};
+use anyhow::{anyhow, Result};
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// A DevTools target from `/json/list`.
+#[derive(Debug, Deserialize)]
+struct Target {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+}
+
+#[derive(Clone)]
+pub struct TabsManager {
+    ports: Vec<u16>,
+}
+
+impl TabsManager {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            ports: config.tabs.debug_ports(),
+        }
+    }
+
+    fn client() -> Result<Client> {
+        Ok(Client::builder().timeout(Duration::from_secs(2)).build()?)
+    }
+
+    fn list(port: u16) -> Result<Vec<Target>> {
+        let targets: Vec<Target> = Self::client()?
+            .get(format!("http://127.0.0.1:{}/json/list", port))
+            .send()?
+            .error_for_status()?
+            .json()?;
+        Ok(Self::pages(targets))
+    }
+
+    /// Tabs only: no service workers, extension pages or DevTools windows.
+    fn pages(targets: Vec<Target>) -> Vec<Target> {
+        targets
+            .into_iter()
+            .filter(|t| t.kind == "page" && !t.url.starts_with("devtools://") && !t.url.starts_with("chrome-extension://"))
+            .collect()
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let mut items = Vec::new();
+        let mut errors = Vec::new();
+        for port in &self.ports {
+            match Self::list(*port) {
+                Ok(targets) => items.extend(targets.into_iter().map(|t| Self::to_item(*port, t))),
+                Err(e) => errors.push(format!("Port {}: {}", port, e)),
+            }
+        }
+        if items.is_empty() && !errors.is_empty() {
+            return Item::error_items(
+                "tabs",
+                "No browser with remote debugging found",
+                format!(
+                    "{}\n\nStart Chrome, Chromium, Brave or Edge with --remote-debugging-port={} to list its tabs",
+                    errors.join("\n"),
+                    self.ports.first().copied().unwrap_or(9222)
+                ),
+            );
+        }
+
+        let query = query.trim();
+        if query.is_empty() {
+            return items;
+        }
+        let mut scored: Vec<(i64, Item)> = items
+            .into_iter()
+            .map(|item| (item.fuzzy_score(query), item))
+            .filter(|(score, _)| *score > 0)
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, item)| item).collect()
+    }
+
+    fn to_item(port: u16, target: Target) -> Item {
+        let title = if target.title.is_empty() { target.url.clone() } else { target.title };
+        let mut item = Item::new(format!("tab:{}:{}", port, target.id), title, ItemType::BrowserTab)
+            .with_description(target.url.clone())
+            .with_icon("web-browser");
+        item.metadata.url = Some(target.url);
+        item
+    }
+
+    /// Brings the tab with item id `tab:<port>:<target id>` to the front.
+    pub fn focus_tab(&self, item_id: &str) -> Result<()> {
+        let (port, id) = item_id
+            .strip_prefix("tab:")
+            .and_then(|rest| rest.split_once(':'))
+            .ok_or_else(|| anyhow!("Not a tab: {}", item_id))?;
+        Self::client()?
+            .get(format!("http://127.0.0.1:{}/json/activate/{}", port, id))
+            .send()?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+impl Default for TabsManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages() {
+        let targets: Vec<Target> = serde_json::from_str(
+            r#"[
+                {"id": "A1", "type": "page", "title": "iced - Rust", "url": "https://docs.rs/iced"},
+                {"id": "B2", "type": "service_worker", "title": "", "url": "https://example.com/sw.js"},
+                {"id": "C3", "type": "page", "title": "DevTools", "url": "devtools://devtools/bundled/inspector.html"},
+                {"id": "D4", "type": "page", "title": "", "url": "https://example.com/"}
+            ]"#,
+        )
+        .unwrap();
+        let items: Vec<Item> = TabsManager::pages(targets).into_iter().map(|t| TabsManager::to_item(9222, t)).collect();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id, "tab:9222:A1");
+        assert_eq!(items[0].name, "iced - Rust");
+        assert_eq!(items[1].name, "https://example.com/");
+    }
+}
@@ -1,13 +1,19 @@
-use crate::core::{Item, ItemType};
-use chrono::{DateTime, Local};
-use std::time::Duration;
+//! Countdown timers and a stopwatch. They are saved on every change, so
+//! they keep running after the launcher closes: the daemon notifies when one
+//! finishes, and `wlaunch timer-status` shows them in a status bar.
 
-#[derive(Debug, Clone)]
+use crate::core::{Config, Item, ItemType};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Timer {
     id: String,
     name: String,
     duration: Duration,
-    started_at: DateTime<Local>,
+    started_at: SystemTime,
     paused: bool,
     paused_remaining: Option<Duration>,
 }
@@ -17,10 +23,7 @@ impl Timer {
         if self.paused {
             self.paused_remaining.unwrap_or(Duration::ZERO)
         } else {
-            let elapsed = Local::now()
-                .signed_duration_since(self.started_at)
-                .to_std()
-                .unwrap_or(Duration::ZERO);
+            let elapsed = self.started_at.elapsed().unwrap_or(Duration::ZERO);
 
             self.duration.saturating_sub(elapsed)
         }
@@ -31,20 +34,44 @@ impl Timer {
     }
 }
 
-pub struct TimerManager {
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct TimerState {
     timers: Vec<Timer>,
-    stopwatch: Option<DateTime<Local>>,
+    stopwatch: Option<SystemTime>,
     stopwatch_paused: bool,
     stopwatch_elapsed: Duration,
 }
 
+pub struct TimerManager {
+    state: TimerState,
+    path: PathBuf,
+}
+
 impl TimerManager {
     pub fn new() -> Self {
-        Self {
-            timers: Vec::new(),
-            stopwatch: None,
-            stopwatch_paused: false,
-            stopwatch_elapsed: Duration::ZERO,
+        let path = Config::data_path("timers.json");
+        let state = Self::load(&path);
+        Self { state, path }
+    }
+
+    fn load(path: &Path) -> TimerState {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Picks up changes made by another launcher or the daemon.
+    fn reload(&mut self) {
+        self.state = Self::load(&self.path);
+    }
+
+    fn save(&self) {
+        if let Some(dir) = self.path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(content) = serde_json::to_string(&self.state) {
+            let _ = fs::write(&self.path, content);
         }
     }
 
@@ -65,9 +92,9 @@ impl TimerManager {
         }
 
         // Stopwatch controls
-        if self.stopwatch.is_some() {
+        if self.state.stopwatch.is_some() {
             let elapsed = self.get_stopwatch_elapsed();
-            let status = if self.stopwatch_paused {
+            let status = if self.state.stopwatch_paused {
                 "Paused"
             } else {
                 "Running"
@@ -79,7 +106,7 @@ impl TimerManager {
                     format!("Stopwatch: {} ({})", Self::format_duration(elapsed), status),
                     ItemType::Timer,
                 )
-                .with_description(if self.stopwatch_paused {
+                .with_description(if self.state.stopwatch_paused {
                     "Click to resume"
                 } else {
                     "Click to pause"
@@ -101,7 +128,7 @@ impl TimerManager {
         }
 
         // Active timers
-        for timer in &self.timers {
+        for timer in &self.state.timers {
             let remaining = timer.remaining();
             let status = if timer.paused {
                 "Paused"
@@ -145,7 +172,7 @@ impl TimerManager {
         }
 
         // Common presets if no query
-        if query.is_empty() && self.timers.is_empty() {
+        if query.is_empty() && self.state.timers.is_empty() {
             items.push(
                 Item::new("timer:preset:5m", "5 minute timer", ItemType::TimerAction)
                     .with_description("Start a 5 minute countdown")
@@ -166,9 +193,18 @@ impl TimerManager {
         items
     }
 
+    pub fn is_running(&self) -> bool {
+        !self.state.timers.is_empty()
+    }
+
     pub fn tick(&mut self) {
+        self.reload();
+        if !self.state.timers.iter().any(Timer::is_finished) {
+            return;
+        }
+
         // Check for finished timers and send notifications
-        for timer in &self.timers {
+        for timer in &self.state.timers {
             if timer.is_finished() {
                 let _ = notify_rust::Notification::new()
                     .summary("Timer Finished")
@@ -178,7 +214,8 @@ impl TimerManager {
         }
 
         // Remove finished timers
-        self.timers.retain(|t| !t.is_finished());
+        self.state.timers.retain(|t| !t.is_finished());
+        self.save();
     }
 
     pub fn start_timer(&mut self, duration: Duration, name: Option<&str>) {
@@ -189,19 +226,19 @@ impl TimerManager {
                 .unwrap_or(&Self::format_duration(duration))
                 .to_string(),
             duration,
-            started_at: Local::now(),
+            started_at: SystemTime::now(),
             paused: false,
             paused_remaining: None,
         };
-        self.timers.push(timer);
+        self.state.timers.push(timer);
     }
 
     pub fn toggle_timer(&mut self, id: &str) {
-        if let Some(timer) = self.timers.iter_mut().find(|t| t.id == id) {
+        if let Some(timer) = self.state.timers.iter_mut().find(|t| t.id == id) {
             if timer.paused {
                 // Resume
                 let remaining = timer.paused_remaining.unwrap_or(Duration::ZERO);
-                timer.started_at = Local::now();
+                timer.started_at = SystemTime::now();
                 timer.duration = remaining;
                 timer.paused = false;
                 timer.paused_remaining = None;
@@ -214,48 +251,51 @@ impl TimerManager {
     }
 
     pub fn cancel_timer(&mut self, id: &str) {
-        self.timers.retain(|t| t.id != id);
+        self.state.timers.retain(|t| t.id != id);
     }
 
     pub fn start_stopwatch(&mut self) {
-        self.stopwatch = Some(Local::now());
-        self.stopwatch_paused = false;
-        self.stopwatch_elapsed = Duration::ZERO;
+        self.state.stopwatch = Some(SystemTime::now());
+        self.state.stopwatch_paused = false;
+        self.state.stopwatch_elapsed = Duration::ZERO;
     }
 
     pub fn toggle_stopwatch(&mut self) {
-        if self.stopwatch_paused {
+        if self.state.stopwatch_paused {
             // Resume
-            self.stopwatch = Some(Local::now());
-            self.stopwatch_paused = false;
-        } else if self.stopwatch.is_some() {
+            self.state.stopwatch = Some(SystemTime::now());
+            self.state.stopwatch_paused = false;
+        } else if self.state.stopwatch.is_some() {
             // Pause
-            self.stopwatch_elapsed = self.get_stopwatch_elapsed();
-            self.stopwatch_paused = true;
+            self.state.stopwatch_elapsed = self.get_stopwatch_elapsed();
+            self.state.stopwatch_paused = true;
         }
     }
 
     pub fn reset_stopwatch(&mut self) {
-        self.stopwatch = None;
-        self.stopwatch_paused = false;
-        self.stopwatch_elapsed = Duration::ZERO;
+        self.state.stopwatch = None;
+        self.state.stopwatch_paused = false;
+        self.state.stopwatch_elapsed = Duration::ZERO;
     }
 
     fn get_stopwatch_elapsed(&self) -> Duration {
-        if self.stopwatch_paused {
-            self.stopwatch_elapsed
-        } else if let Some(started) = self.stopwatch {
-            let current = Local::now()
-                .signed_duration_since(started)
-                .to_std()
-                .unwrap_or(Duration::ZERO);
-            self.stopwatch_elapsed + current
+        if self.state.stopwatch_paused {
+            self.state.stopwatch_elapsed
+        } else if let Some(started) = self.state.stopwatch {
+            let current = started.elapsed().unwrap_or(Duration::ZERO);
+            self.state.stopwatch_elapsed + current
         } else {
             Duration::ZERO
         }
     }
 
     pub fn execute_action(&mut self, action_id: &str) {
+        self.reload();
+        self.apply_action(action_id);
+        self.save();
+    }
+
+    fn apply_action(&mut self, action_id: &str) {
         if action_id.starts_with("timer:start:") {
             if let Ok(secs) = action_id.strip_prefix("timer:start:").unwrap().parse::<u64>() {
                 self.start_timer(Duration::from_secs(secs), None);
@@ -284,7 +324,59 @@ impl TimerManager {
         }
     }
 
-    fn parse_duration(input: &str) -> Option<Duration> {
+    /// Status bar text: the timer finishing first and the stopwatch, e.g.
+    /// "⏲ 04:32  ⏱ 12:03"; empty when nothing runs.
+    pub fn status_text(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(timer) = self.state.timers.iter().min_by_key(|t| (t.paused, t.remaining())) {
+            parts.push(format!("⏲ {}", Self::format_duration(timer.remaining())));
+        }
+        if self.state.stopwatch.is_some() {
+            parts.push(format!("⏱ {}", Self::format_duration(self.get_stopwatch_elapsed())));
+        }
+        parts.join("  ")
+    }
+
+    /// The status as a waybar custom module expects it (`return-type: json`):
+    /// text, a tooltip listing every timer, a class for styling and the
+    /// first timer's progress.
+    pub fn status_json(&self) -> String {
+        let mut tooltip: Vec<String> = self
+            .state
+            .timers
+            .iter()
+            .map(|t| {
+                let state = if t.paused { " (paused)" } else { "" };
+                format!("{}: {}{}", t.name, Self::format_duration(t.remaining()), state)
+            })
+            .collect();
+        if self.state.stopwatch.is_some() {
+            tooltip.push(format!("Stopwatch: {}", Self::format_duration(self.get_stopwatch_elapsed())));
+        }
+
+        let running = self.state.timers.iter().any(|t| !t.paused) || (self.state.stopwatch.is_some() && !self.state.stopwatch_paused);
+        let class = match (running, tooltip.is_empty()) {
+            (true, _) => "running",
+            (false, false) => "paused",
+            (false, true) => "idle",
+        };
+        let percentage = self
+            .state
+            .timers
+            .iter()
+            .min_by_key(|t| (t.paused, t.remaining()))
+            .map_or(0, |t| 100 - (t.remaining().as_secs() * 100 / t.duration.as_secs().max(1)).min(100));
+
+        serde_json::json!({
+            "text": self.status_text(),
+            "tooltip": tooltip.join("\n"),
+            "class": class,
+            "percentage": percentage,
+        })
+        .to_string()
+    }
+
+    pub(crate) fn parse_duration(input: &str) -> Option<Duration> {
         let input = input.trim().to_lowercase();
         if input.is_empty() {
             return None;
@@ -351,7 +443,7 @@ impl TimerManager {
         }
     }
 
-    fn format_duration(duration: Duration) -> String {
+    pub(crate) fn format_duration(duration: Duration) -> String {
         let total_secs = duration.as_secs();
         let hours = total_secs / 3600;
         let mins = (total_secs % 3600) / 60;
@@ -370,3 +462,43 @@ impl Default for TimerManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(state: TimerState) -> TimerManager {
+        TimerManager {
+            state,
+            path: PathBuf::from("/nonexistent/timers.json"),
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        let idle = manager(TimerState::default());
+        assert_eq!(idle.status_text(), "");
+        assert_eq!(idle.status_json(), r#"{"text":"","tooltip":"","class":"idle","percentage":0}"#);
+
+        let timer = |name: &str, minutes: u64, paused: bool| Timer {
+            id: name.to_string(),
+            name: name.to_string(),
+            duration: Duration::from_secs(minutes * 60),
+            // Ahead of now, so the running timer reads its full duration
+            started_at: SystemTime::now() + Duration::from_secs(60),
+            paused,
+            paused_remaining: paused.then(|| Duration::from_secs(minutes * 30)),
+        };
+        let busy = manager(TimerState {
+            timers: vec![timer("Tea", 4, true), timer("Pomodoro", 25, false)],
+            stopwatch: Some(SystemTime::now()),
+            stopwatch_paused: true,
+            stopwatch_elapsed: Duration::from_secs(90),
+        });
+        // The running timer, not the paused one finishing sooner
+        assert_eq!(busy.status_text(), "⏲ 25:00  ⏱ 01:30");
+        let status: serde_json::Value = serde_json::from_str(&busy.status_json()).unwrap();
+        assert_eq!(status["tooltip"], "Tea: 02:00 (paused)\nPomodoro: 25:00\nStopwatch: 01:30");
+        assert_eq!(status["class"], "running");
+    }
+}
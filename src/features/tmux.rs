@@ -0,0 +1,287 @@
+//! tmux sessions and their windows: attach in a terminal, create or kill
+//! sessions.
+
+use crate::core::config::{ModeConfig, SortOrder};
+use crate::core::{CommandRunner, Config, Item, ItemType, SystemRunner};
+use std::process::Command;
+use std::sync::Arc;
+
+// Free-text fields last: session names can't contain ':' but anything else
+// goes, and window names can contain anything.
+const SESSION_FORMAT: &str = "#{session_activity}|#{session_attached}|#{session_windows}|#{session_name}";
+const WINDOW_FORMAT: &str = "#{window_index}|#{window_active}|#{pane_current_command}|#{session_name}:#{window_name}";
+
+struct Session {
+    name: String,
+    windows: usize,
+    attached: bool,
+    activity: i64,
+}
+
+struct Window {
+    session: String,
+    index: u32,
+    name: String,
+    command: String,
+    active: bool,
+}
+
+#[derive(Clone)]
+pub struct TmuxManager {
+    options: ModeConfig,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl TmuxManager {
+    pub fn new(config: &Config) -> Self {
+        Self::with_runner(config, SystemRunner::shared())
+    }
+
+    pub fn with_runner(config: &Config, runner: Arc<dyn CommandRunner>) -> Self {
+        Self {
+            options: config.mode("tmux"),
+            runner,
+        }
+    }
+
+    /// Lists sessions; `Ok(None)` when no tmux server is running.
+    fn list(&self, args: &[&str]) -> Result<Option<String>, Vec<Item>> {
+        match self.runner.run("tmux", args) {
+            Ok(output) if output.status.success() => Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned())),
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                if stderr.contains("no server running") || stderr.contains("error connecting") {
+                    Ok(None)
+                } else {
+                    Err(Item::command_error_items("tmux", "Can't list tmux sessions", &output))
+                }
+            }
+            Err(e) => Err(Item::error_items("tmux", "tmux is not available", format!("Failed to run tmux: {}", e))),
+        }
+    }
+
+    fn parse_sessions(stdout: &str) -> Vec<Session> {
+        stdout
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(4, '|');
+                let activity = parts.next()?.parse().unwrap_or(0);
+                let attached = parts.next()? != "0";
+                let windows = parts.next()?.parse().unwrap_or(0);
+                let name = parts.next()?.to_string();
+                Some(Session {
+                    name,
+                    windows,
+                    attached,
+                    activity,
+                })
+            })
+            .collect()
+    }
+
+    fn parse_windows(stdout: &str) -> Vec<Window> {
+        stdout
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(4, '|');
+                let index = parts.next()?.parse().ok()?;
+                let active = parts.next()? == "1";
+                let command = parts.next()?.to_string();
+                let (session, name) = parts.next()?.split_once(':')?;
+                Some(Window {
+                    session: session.to_string(),
+                    index,
+                    name: name.to_string(),
+                    command,
+                    active,
+                })
+            })
+            .collect()
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim();
+        let (kill, filter) = match query.strip_prefix("kill ") {
+            Some(rest) => (true, rest.trim().to_lowercase()),
+            None => (false, query.to_lowercase()),
+        };
+
+        if let Some(name) = query.strip_prefix("new ").map(str::trim).filter(|n| !n.is_empty()) {
+            // tmux turns ':' and '.' into '_' in session names
+            let name = name.replace([':', '.'], "_");
+            return vec![Item::new(format!("tmux:new:{}", name), format!("New tmux session: {}", name), ItemType::TmuxAction)
+                .with_description("Create the session and attach in a terminal")
+                .with_icon("list-add")];
+        }
+
+        let mut sessions = match self.list(&["list-sessions", "-F", SESSION_FORMAT]) {
+            Ok(Some(stdout)) => Self::parse_sessions(&stdout),
+            Ok(None) => Vec::new(),
+            Err(items) => return items,
+        };
+        if sessions.is_empty() {
+            return vec![Item::new("tmux:none", "No tmux sessions", ItemType::Command)
+                .with_description("Type new <name> to create one")
+                .with_icon("dialog-information")];
+        }
+        let windows = match self.list(&["list-windows", "-a", "-F", WINDOW_FORMAT]) {
+            Ok(Some(stdout)) => Self::parse_windows(&stdout),
+            Ok(None) => Vec::new(),
+            Err(items) => return items,
+        };
+
+        match self.options.sort {
+            Some(SortOrder::Name) => sessions.sort_by_key(|s| s.name.to_lowercase()),
+            _ => sessions.sort_by_key(|s| std::cmp::Reverse(s.activity)),
+        }
+
+        let mut items = Vec::new();
+        let mut shown = 0;
+        for session in &sessions {
+            if self.options.max_results.is_some_and(|max| shown >= max) {
+                break;
+            }
+            let session_matches = filter.is_empty() || session.name.to_lowercase().contains(&filter);
+            let matching: Vec<&Window> = windows
+                .iter()
+                .filter(|w| w.session == session.name)
+                .filter(|w| {
+                    session_matches
+                        || w.name.to_lowercase().contains(&filter)
+                        || w.command.to_lowercase().contains(&filter)
+                })
+                .collect();
+            if !session_matches && matching.is_empty() {
+                continue;
+            }
+            shown += 1;
+
+            let description = format!(
+                "{} window{}{}",
+                session.windows,
+                if session.windows == 1 { "" } else { "s" },
+                if session.attached { " | attached" } else { "" }
+            );
+            if kill {
+                items.push(
+                    Item::new(format!("tmux:kill:{}", session.name), format!("Kill session: {}", session.name), ItemType::TmuxAction)
+                        .with_description(description)
+                        .with_icon("process-stop"),
+                );
+                continue;
+            }
+            items.push(
+                Item::new(format!("tmux:session:{}", session.name), &session.name, ItemType::TmuxSession)
+                    .with_description(description)
+                    .with_icon("utilities-terminal"),
+            );
+            for window in matching {
+                items.push(
+                    Item::new(
+                        format!("tmux:window:{}:{}", window.session, window.index),
+                        format!("    {}: {}", window.index, window.name),
+                        ItemType::TmuxSession,
+                    )
+                    .with_description(format!("{}{}", window.command, if window.active { " | active" } else { "" }))
+                    .with_icon("window"),
+                );
+            }
+        }
+
+        items
+    }
+
+    /// Attaches to the session (and window) of `item` in a new terminal.
+    pub fn attach(&self, item: &Item) {
+        let session = if let Some(target) = item.id.strip_prefix("tmux:window:") {
+            let _ = self.runner.run("tmux", &["select-window", "-t", &format!("={}", target)]);
+            target.rsplit_once(':').map_or(target, |(session, _)| session)
+        } else if let Some(session) = item.id.strip_prefix("tmux:session:") {
+            session
+        } else {
+            return;
+        };
+        let _ = Command::new("x-terminal-emulator")
+            .args(["-e", "tmux", "attach-session", "-t", &format!("={}", session)])
+            .spawn();
+    }
+
+    /// Runs a new/kill action; returns whether a terminal was opened, so the
+    /// launcher can close.
+    pub fn execute_action(&self, action_id: &str) -> bool {
+        if let Some(name) = action_id.strip_prefix("tmux:new:") {
+            if let Err(e) = self.runner.run("tmux", &["new-session", "-d", "-s", name]) {
+                log::error!("Failed to create tmux session {}: {}", name, e);
+                return false;
+            }
+            let session = Item::new(format!("tmux:session:{}", name), name, ItemType::TmuxSession);
+            self.attach(&session);
+            return true;
+        }
+        if let Some(name) = action_id.strip_prefix("tmux:kill:") {
+            let _ = self.runner.run("tmux", &["kill-session", "-t", &format!("={}", name)]);
+        }
+        false
+    }
+}
+
+impl Default for TmuxManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    fn manager() -> TmuxManager {
+        let runner = FakeRunner::new()
+            .with(
+                &format!("tmux list-sessions -F {}", SESSION_FORMAT),
+                include_str!("../../tests/fixtures/tmux_list_sessions.txt"),
+            )
+            .with(
+                &format!("tmux list-windows -a -F {}", WINDOW_FORMAT),
+                include_str!("../../tests/fixtures/tmux_list_windows.txt"),
+            )
+            .shared();
+        TmuxManager::with_runner(&Config::default(), runner)
+    }
+
+    #[test]
+    fn test_sessions() {
+        let items = manager().get_items("");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        // Most recently active session first, its windows below it
+        assert_eq!(names, ["work", "    1: editor", "    2: server", "dotfiles", "    0: zsh"]);
+        assert_eq!(items[0].description.as_deref(), Some("2 windows | attached"));
+        assert_eq!(items[2].id, "tmux:window:work:2");
+        assert_eq!(items[2].description.as_deref(), Some("cargo | active"));
+
+        // A window match keeps its session
+        let items = manager().get_items("cargo");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["work", "    2: server"]);
+
+        let items = manager().get_items("kill dot");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "tmux:kill:dotfiles");
+
+        assert_eq!(manager().get_items("new my.app")[0].id, "tmux:new:my_app");
+    }
+
+    #[test]
+    fn test_no_server() {
+        let runner = FakeRunner::new()
+            .failing(
+                &format!("tmux list-sessions -F {}", SESSION_FORMAT),
+                1,
+                "no server running on /tmp/tmux-1000/default\n",
+            )
+            .shared();
+        let items = TmuxManager::with_runner(&Config::default(), runner).get_items("");
+        assert_eq!(items[0].id, "tmux:none");
+    }
+}
@@ -0,0 +1,340 @@
+//! A CalDAV task list, as served by Nextcloud, Radicale or Fastmail. Each
+//! todo is one VTODO resource with the todo's id as its UID; `#tags` are its
+//! categories and repeating todos have an RRULE. Writing a todo back
+//! replaces the resource, so properties wlaunch doesn't know, like
+//! descriptions and alarms, are dropped on edit.
+
+use super::recurrence::Recurrence;
+use super::sync::RemoteTask;
+use super::{Priority, TodoItem};
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
+use regex::Regex;
+use reqwest::header::{CONTENT_TYPE, IF_NONE_MATCH};
+use reqwest::{Client, Method, StatusCode, Url};
+
+const REPORT: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><c:calendar-data/></d:prop>
+  <c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VTODO"/></c:comp-filter></c:filter>
+</c:calendar-query>"#;
+
+pub(super) struct Caldav {
+    url: Url,
+    username: String,
+    password: String,
+    client: Client,
+}
+
+impl Caldav {
+    pub fn new(url: &str, username: String, password: String) -> Result<Self> {
+        // Resources are joined onto the collection, which needs the slash
+        let url = if url.ends_with('/') { url.to_string() } else { format!("{}/", url) };
+        Ok(Self {
+            url: Url::parse(&url)?,
+            username,
+            password,
+            client: Client::new(),
+        })
+    }
+
+    fn request(&self, method: Method, url: Url) -> reqwest::RequestBuilder {
+        self.client.request(method, url).basic_auth(&self.username, Some(&self.password))
+    }
+
+    pub async fn list(&self) -> Result<Vec<RemoteTask>> {
+        let xml = self
+            .request(Method::from_bytes(b"REPORT")?, self.url.clone())
+            .header("Depth", "1")
+            .header(CONTENT_TYPE, "application/xml; charset=utf-8")
+            .body(REPORT)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        Ok(parse_multistatus(&xml))
+    }
+
+    /// Returns the new resource's path.
+    pub async fn create(&self, todo: &TodoItem) -> Result<String> {
+        let url = self.url.join(&format!("{}.ics", todo.id))?;
+        self.request(Method::PUT, url.clone())
+            .header(IF_NONE_MATCH, "*")
+            .header(CONTENT_TYPE, "text/calendar; charset=utf-8")
+            .body(to_ics(todo, Utc::now()))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(url.path().to_string())
+    }
+
+    pub async fn update(&self, path: &str, todo: &TodoItem) -> Result<()> {
+        self.request(Method::PUT, self.url.join(path)?)
+            .header(CONTENT_TYPE, "text/calendar; charset=utf-8")
+            .body(to_ics(todo, Utc::now()))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<()> {
+        let response = self.request(Method::DELETE, self.url.join(path)?).send().await?;
+        // Already deleted remotely
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        response.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// The VTODOs in a REPORT response. Namespace prefixes differ between
+/// servers, so elements are matched by local name.
+fn parse_multistatus(xml: &str) -> Vec<RemoteTask> {
+    let response = Regex::new(r"(?s)<(?:[\w-]+:)?response\b.*?</(?:[\w-]+:)?response>").unwrap();
+    let href = Regex::new(r"(?s)<(?:[\w-]+:)?href>\s*(.*?)\s*</").unwrap();
+    let data = Regex::new(r"(?s)<(?:[\w-]+:)?calendar-data\b[^>]*>(.*?)</(?:[\w-]+:)?calendar-data>").unwrap();
+
+    response
+        .find_iter(xml)
+        .filter_map(|m| {
+            let path = unescape_xml(href.captures(m.as_str())?.get(1)?.as_str());
+            let ics = unescape_xml(data.captures(m.as_str())?.get(1)?.as_str());
+            let mut task = parse_vtodo(&ics)?;
+            task.id = path;
+            Some(task)
+        })
+        .collect()
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#13;", "\r")
+        .replace("&#xD;", "\r")
+        .replace("&amp;", "&")
+}
+
+/// The first VTODO in an iCalendar text, without its resource path.
+fn parse_vtodo(ics: &str) -> Option<RemoteTask> {
+    // Folded lines continue after a line break and one space or tab
+    let unfolded = ics.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+    let mut lines = unfolded.lines().skip_while(|line| *line != "BEGIN:VTODO").skip(1);
+
+    let mut task = RemoteTask {
+        id: String::new(),
+        uid: None,
+        text: String::new(),
+        completed: false,
+        due: None,
+        priority: None,
+        tags: Vec::new(),
+        recur: None,
+    };
+    let mut found = false;
+    for line in lines.by_ref() {
+        if line == "END:VTODO" {
+            found = true;
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = name.split_once(';').unwrap_or((name, ""));
+        match name.to_uppercase().as_str() {
+            "UID" => task.uid = Some(value.to_string()),
+            "SUMMARY" => task.text = unescape_text(value),
+            "STATUS" => task.completed = value.eq_ignore_ascii_case("COMPLETED"),
+            "DUE" => task.due = parse_due(value, params.to_uppercase().contains("VALUE=DATE")),
+            "PRIORITY" => {
+                task.priority = match value.trim().parse::<u8>().unwrap_or(0) {
+                    1..=4 => Some(Priority::High),
+                    5 => Some(Priority::Medium),
+                    6..=9 => Some(Priority::Low),
+                    _ => None,
+                }
+            }
+            "RRULE" => task.recur = Recurrence::from_rrule(value).map(|recurrence| recurrence.label()),
+            "CATEGORIES" => task.tags.extend(
+                value
+                    .split(',')
+                    .map(|tag| unescape_text(tag).trim().to_lowercase())
+                    .filter(|tag| !tag.is_empty()),
+            ),
+            _ => {}
+        }
+    }
+    found.then_some(task)
+}
+
+/// `20261020` or `20261020T170000`, local unless it ends in `Z`; zones
+/// named by `TZID` are taken as local.
+fn parse_due(value: &str, date_only: bool) -> Option<String> {
+    if date_only || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some(date.format("%Y-%m-%d").to_string());
+    }
+    let local = match value.strip_suffix('Z') {
+        Some(utc) => NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .ok()?
+            .and_utc()
+            .with_timezone(&Local)
+            .naive_local(),
+        None => NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?,
+    };
+    Some(local.format("%Y-%m-%d %H:%M").to_string())
+}
+
+fn unescape_text(value: &str) -> String {
+    value
+        .replace("\\n", "\n")
+        .replace("\\N", "\n")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Splits a content line into lines of at most 75 bytes.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+fn to_ics(todo: &TodoItem, now: DateTime<Utc>) -> String {
+    let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//wlaunch//todos//EN".to_string(),
+        "BEGIN:VTODO".to_string(),
+        format!("UID:{}", todo.id),
+        format!("DTSTAMP:{}", stamp),
+        format!("SUMMARY:{}", escape_text(&todo.text)),
+    ];
+    if todo.completed {
+        lines.push("STATUS:COMPLETED".to_string());
+        lines.push(format!("COMPLETED:{}", stamp));
+    } else {
+        lines.push("STATUS:NEEDS-ACTION".to_string());
+    }
+    if let Some(due) = &todo.due {
+        match NaiveDateTime::parse_from_str(due, "%Y-%m-%d %H:%M") {
+            Ok(time) => lines.push(format!("DUE:{}", time.format("%Y%m%dT%H%M%S"))),
+            Err(_) => lines.push(format!("DUE;VALUE=DATE:{}", due.replace('-', ""))),
+        }
+    }
+    if let Some(priority) = todo.priority {
+        let value = match priority {
+            Priority::High => 1,
+            Priority::Medium => 5,
+            Priority::Low => 9,
+        };
+        lines.push(format!("PRIORITY:{}", value));
+    }
+    if let Some(recurrence) = todo.recur.as_deref().and_then(Recurrence::parse) {
+        lines.push(format!("RRULE:{}", recurrence.to_rrule()));
+    }
+    if !todo.tags.is_empty() {
+        let tags: Vec<String> = todo.tags.iter().map(|t| escape_text(t)).collect();
+        lines.push(format!("CATEGORIES:{}", tags.join(",")));
+    }
+    lines.push("END:VTODO".to_string());
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multistatus() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+  <d:response>
+    <d:href>/dav/calendars/me/tasks/a1.ics</d:href>
+    <d:propstat><d:prop><cal:calendar-data>BEGIN:VCALENDAR&#13;
+BEGIN:VTODO&#13;
+UID:a1&#13;
+SUMMARY:Pay rent\, then call &amp; re&#13;
+ lax&#13;
+DUE;VALUE=DATE:20261020&#13;
+PRIORITY:1&#13;
+CATEGORIES:Home,Money&#13;
+END:VTODO&#13;
+END:VCALENDAR</cal:calendar-data></d:prop></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/calendars/me/tasks/b2.ics</d:href>
+    <d:propstat><d:prop><cal:calendar-data>BEGIN:VCALENDAR
+BEGIN:VTODO
+UID:b2
+SUMMARY:Done
+STATUS:COMPLETED
+DUE:20261020T173000
+END:VTODO
+END:VCALENDAR</cal:calendar-data></d:prop></d:propstat>
+  </d:response>
+</d:multistatus>"#;
+        let tasks = parse_multistatus(xml);
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].id, "/dav/calendars/me/tasks/a1.ics");
+        assert_eq!(tasks[0].uid.as_deref(), Some("a1"));
+        assert_eq!(tasks[0].text, "Pay rent, then call & relax");
+        assert_eq!(tasks[0].due.as_deref(), Some("2026-10-20"));
+        assert_eq!(tasks[0].priority, Some(Priority::High));
+        assert_eq!(tasks[0].tags, ["home", "money"]);
+        assert!(tasks[1].completed);
+        assert_eq!(tasks[1].due.as_deref(), Some("2026-10-20 17:30"));
+    }
+
+    #[test]
+    fn test_to_ics_round_trip() {
+        let todo = TodoItem {
+            id: "a1".to_string(),
+            text: "Buy milk, eggs; and a very long list of other groceries for the whole week".to_string(),
+            completed: false,
+            created: String::new(),
+            tags: vec!["home".to_string()],
+            project: None,
+            priority: Some(Priority::Medium),
+            due: Some("2026-10-20 09:00".to_string()),
+            reminded: false,
+            recur: Some("every 2 weeks".to_string()),
+            remote_id: None,
+            modified: false,
+        };
+        let ics = to_ics(&todo, Utc::now());
+        assert!(ics.lines().all(|line| line.len() <= 76));
+
+        let task = parse_vtodo(&ics).unwrap();
+        assert_eq!(task.uid.as_deref(), Some("a1"));
+        assert_eq!(task.text, todo.text);
+        assert_eq!(task.due, todo.due);
+        assert_eq!(task.priority, todo.priority);
+        assert_eq!(task.tags, todo.tags);
+        assert_eq!(task.recur, todo.recur);
+    }
+}
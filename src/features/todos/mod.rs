@@ -0,0 +1,916 @@
+mod caldav;
+mod recurrence;
+mod sync;
+mod todoist;
+
+pub use sync::SyncOutcome;
+
+use crate::core::config::TodoSyncConfig;
+use crate::core::{Config, Item, ItemType, Keyed, SyncedStore};
+use anyhow::Result;
+use recurrence::Recurrence;
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::future::Future;
+use std::time::Instant;
+use uuid::Uuid;
+
+const WEEKDAYS: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "high" | "h" | "1" => Some(Priority::High),
+            "medium" | "med" | "m" | "2" => Some(Priority::Medium),
+            "low" | "l" | "3" => Some(Priority::Low),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Priority::High => "high",
+            Priority::Medium => "medium",
+            Priority::Low => "low",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct TodoItem {
+    id: String,
+    /// Text without the `#tag`, `@project`, `!priority` and `@due` tokens
+    text: String,
+    completed: bool,
+    created: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    project: Option<String>,
+    #[serde(default)]
+    priority: Option<Priority>,
+    /// `YYYY-MM-DD`, or `YYYY-MM-DD HH:MM` when a time was given
+    #[serde(default)]
+    due: Option<String>,
+    /// Set once the overdue notification has been shown
+    #[serde(default)]
+    reminded: bool,
+    /// "every monday"; completing the todo moves it to the next date
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    recur: Option<String>,
+    /// Todoist task id or CalDAV resource path once synced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    remote_id: Option<String>,
+    /// Changed since the last sync
+    #[serde(default)]
+    modified: bool,
+}
+
+impl TodoItem {
+    /// `#tag`, `@project` and `!priority` filters are matched exactly, a due
+    /// date against the day, and other words anywhere in the text.
+    fn matches(&self, filter: &Filter) -> bool {
+        filter.tags.iter().all(|t| self.tags.contains(t))
+            && filter.project.as_ref().is_none_or(|p| self.project.as_ref() == Some(p))
+            && filter.priority.is_none_or(|p| self.priority == Some(p))
+            && filter.recur.as_ref().is_none_or(|r| self.recur.as_ref() == Some(r))
+            && filter.due.as_ref().is_none_or(|d| self.due.as_ref().is_some_and(|due| due.starts_with(d.as_str())))
+            && (filter.text.is_empty() || self.text.to_lowercase().contains(&filter.text))
+    }
+
+    /// Moves a repeating todo to its next date after today, or after its
+    /// current date when done ahead of time, keeping the time of day.
+    fn advance(&mut self, recurrence: Recurrence, today: NaiveDate) {
+        let due = self.due.as_deref().unwrap_or_default();
+        let date = due.get(..10).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()).unwrap_or(today);
+        let next = recurrence.next(date.max(today)).format("%Y-%m-%d");
+        self.due = Some(match due.get(10..) {
+            Some(time) => format!("{}{}", next, time),
+            None => next.to_string(),
+        });
+        self.reminded = false;
+    }
+
+    /// When the todo becomes overdue: its due time, or the end of its due day.
+    fn due_at(&self) -> Option<NaiveDateTime> {
+        let due = self.due.as_deref()?;
+        NaiveDateTime::parse_from_str(due, "%Y-%m-%d %H:%M").ok().or_else(|| {
+            NaiveDate::parse_from_str(due, "%Y-%m-%d").ok()?.succ_opt()?.and_hms_opt(0, 0, 0)
+        })
+    }
+
+    fn is_overdue(&self, now: NaiveDateTime) -> bool {
+        !self.completed && self.due_at().is_some_and(|due| due <= now)
+    }
+
+    /// "due tomorrow", "due Fri 17:00" or "overdue Oct 14".
+    fn due_label(&self, now: NaiveDateTime) -> Option<String> {
+        let due = self.due.as_deref()?;
+        let date = NaiveDate::parse_from_str(due.get(..10)?, "%Y-%m-%d").ok()?;
+        let mut label = match (date - now.date()).num_days() {
+            -1 => "yesterday".to_string(),
+            0 => "today".to_string(),
+            1 => "tomorrow".to_string(),
+            2..=6 => date.format("%a").to_string(),
+            _ if date.year() == now.year() => date.format("%b %-d").to_string(),
+            _ => date.format("%b %-d %Y").to_string(),
+        };
+        if let Some(time) = due.get(11..) {
+            label = format!("{} {}", label, time);
+        }
+        let state = if self.is_overdue(now) { "overdue" } else { "due" };
+        Some(format!("{} {}", state, label))
+    }
+}
+
+/// A todo text or search query split into its parts.
+#[derive(Debug, Default, PartialEq)]
+struct Filter {
+    text: String,
+    tags: Vec<String>,
+    project: Option<String>,
+    priority: Option<Priority>,
+    due: Option<String>,
+    recur: Option<String>,
+}
+
+impl Filter {
+    fn parse(input: &str) -> Self {
+        Self::parse_at(input, Local::now().date_naive())
+    }
+
+    /// Pulls `#tag`, `@project`, `!priority`, `@due` and `every ...`
+    /// tokens out of `input`. Tags have to start with a letter, so "issue
+    /// #42" stays text, and `@` tokens that read as a day or time are due
+    /// dates rather than projects.
+    fn parse_at(input: &str, today: NaiveDate) -> Self {
+        let mut filter = Filter::default();
+        let mut words = Vec::new();
+        let mut due_date = None;
+        let mut due_time = None;
+        let tokens: Vec<&str> = input.split_whitespace().collect();
+        let mut i = 0;
+        while i < tokens.len() {
+            let word = tokens[i];
+            i += 1;
+            if word.eq_ignore_ascii_case("every") && filter.recur.is_none() {
+                if let Some((recurrence, used)) = Recurrence::parse_words(&tokens[i..]) {
+                    filter.recur = Some(recurrence.label());
+                    i += used;
+                    continue;
+                }
+            }
+            let token = |prefix: char| {
+                word.strip_prefix(prefix)
+                    .filter(|t| t.starts_with(|c: char| c.is_alphabetic()))
+                    .map(str::to_lowercase)
+            };
+            let at = word.strip_prefix('@').map(str::to_lowercase);
+            let priority = word
+                .strip_prefix('!')
+                .and_then(|p| Priority::parse(&p.to_lowercase()))
+                .filter(|_| filter.priority.is_none());
+
+            if let Some(tag) = token('#') {
+                if !filter.tags.contains(&tag) {
+                    filter.tags.push(tag);
+                }
+            } else if let Some(date) = at.as_deref().and_then(|at| parse_due_date(at, today)) {
+                due_date = Some(date);
+            } else if let Some(time) = at.as_deref().and_then(parse_due_time) {
+                due_time = Some(time);
+            } else if let Some(project) = token('@').filter(|_| filter.project.is_none()) {
+                filter.project = Some(project);
+            } else if priority.is_some() {
+                filter.priority = priority;
+            } else {
+                words.push(word);
+            }
+        }
+        filter.text = words.join(" ");
+        filter.due = match (due_date, due_time) {
+            (date, Some(time)) => Some(date.unwrap_or(today).and_time(time).format("%Y-%m-%d %H:%M").to_string()),
+            (Some(date), None) => Some(date.format("%Y-%m-%d").to_string()),
+            (None, None) => None,
+        };
+        filter
+    }
+
+    /// The due date, or for repeating todos without one their first date.
+    fn due_or_first(&self, today: NaiveDate) -> Option<String> {
+        let first = || Some(Recurrence::parse(self.recur.as_deref()?)?.first(today).format("%Y-%m-%d").to_string());
+        self.due.clone().or_else(first)
+    }
+}
+
+/// `today`, `tomorrow`, `2026-10-20`, or a weekday (`fri`, `friday`) for
+/// the next one, today included.
+fn parse_due_date(token: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match token {
+        "today" | "tod" => return Some(today),
+        "tomorrow" | "tmr" => return today.succ_opt(),
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(token, "%Y-%m-%d") {
+        return Some(date);
+    }
+    let weekday = WEEKDAYS.iter().position(|day| token.len() >= 3 && day.starts_with(token))?;
+    let ahead = (weekday as i64 - today.weekday().num_days_from_monday() as i64).rem_euclid(7);
+    today.checked_add_signed(Duration::days(ahead))
+}
+
+/// `17:00`, `5pm` or `5:30pm`.
+fn parse_due_time(token: &str) -> Option<NaiveTime> {
+    if let Ok(time) = NaiveTime::parse_from_str(token, "%H:%M") {
+        return Some(time);
+    }
+    let (clock, pm) = match token.strip_suffix("pm") {
+        Some(clock) => (clock, true),
+        None => (token.strip_suffix("am")?, false),
+    };
+    let (hour, minute) = clock.split_once(':').unwrap_or((clock, "0"));
+    let hour: u32 = hour.parse().ok().filter(|h| (1..=12).contains(h))?;
+    NaiveTime::from_hms_opt(hour % 12 + if pm { 12 } else { 0 }, minute.parse().ok()?, 0)
+}
+
+impl Keyed for TodoItem {
+    fn key(&self) -> &str {
+        &self.id
+    }
+}
+
+/// Remote ids of synced todos deleted here, until the deletion is pushed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    #[serde(default)]
+    deleted: Vec<String>,
+}
+
+pub struct TodosManager {
+    todos: Vec<TodoItem>,
+    store: SyncedStore,
+    /// Set when `todo_sync.backend` is
+    sync: Option<TodoSyncConfig>,
+    sync_state: SyncState,
+    syncing: bool,
+    last_sync: Option<Instant>,
+}
+
+impl TodosManager {
+    pub fn new(config: &Config) -> Self {
+        let mut store = SyncedStore::new(config, "todos.json");
+        let todos = Self::load(&mut store).unwrap_or_default();
+        let sync_state = fs::read_to_string(Config::data_path("todo_sync.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            todos,
+            store,
+            sync: config.todo_sync.backend.is_some().then(|| config.todo_sync.clone()),
+            sync_state,
+            syncing: false,
+            last_sync: None,
+        }
+    }
+
+    /// Picks up changes synced from another machine since the last read.
+    pub fn reload_if_changed(&mut self) {
+        if self.store.changed() {
+            if let Ok(todos) = Self::load(&mut self.store) {
+                self.todos = todos;
+            }
+        }
+    }
+
+    fn load(store: &mut SyncedStore) -> Result<Vec<TodoItem>> {
+        let mut todos: Vec<TodoItem> = store.load()?;
+        // Todos saved before tags were parsed keep them in the text
+        let untagged = |t: &&mut TodoItem| {
+            t.tags.is_empty()
+                && t.project.is_none()
+                && t.priority.is_none()
+                && t.due.is_none()
+                && t.recur.is_none()
+                && t.remote_id.is_none()
+        };
+        for todo in todos.iter_mut().filter(untagged) {
+            let parsed = Filter::parse(&todo.text);
+            if !parsed.text.is_empty() {
+                todo.text = parsed.text;
+                todo.tags = parsed.tags;
+                todo.project = parsed.project;
+                todo.priority = parsed.priority;
+                todo.due = parsed.due;
+                todo.recur = parsed.recur;
+            }
+        }
+        Ok(todos)
+    }
+
+    fn save(&mut self) -> Result<()> {
+        self.store.save(&mut self.todos)
+    }
+
+    fn save_sync_state(&self) -> Result<()> {
+        let path = Config::data_path("todo_sync.json");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&self.sync_state)?)?;
+        Ok(())
+    }
+
+    /// A sync with the configured backend, when there are local changes to
+    /// push or the last one is older than `todo_sync.interval_minutes`.
+    pub fn start_sync(&mut self) -> Option<impl Future<Output = Result<SyncOutcome>>> {
+        let config = self.sync.clone()?;
+        let interval = std::time::Duration::from_secs(config.interval_minutes() * 60);
+        let changed = !self.sync_state.deleted.is_empty()
+            || self.todos.iter().any(|t| t.modified || (t.remote_id.is_none() && !t.completed));
+        let due = self.last_sync.is_none_or(|t| t.elapsed() >= interval);
+        if self.syncing || !(changed || due) {
+            return None;
+        }
+        self.syncing = true;
+        Some(sync::run(config, self.todos.clone(), self.sync_state.deleted.clone()))
+    }
+
+    pub fn apply_sync(&mut self, result: Result<SyncOutcome, String>) {
+        self.syncing = false;
+        self.last_sync = Some(Instant::now());
+        match result {
+            Ok(outcome) => {
+                sync::merge(&mut self.todos, &mut self.sync_state.deleted, outcome);
+                let _ = self.save();
+                let _ = self.save_sync_state();
+            }
+            Err(e) => log::warn!("Todo sync failed: {}", e),
+        }
+    }
+
+    /// Removes todos, remembering synced open ones to delete them remotely.
+    fn remove_where(&mut self, remove: impl Fn(&TodoItem) -> bool) {
+        let synced = self.sync.is_some();
+        let deleted = &mut self.sync_state.deleted;
+        self.todos.retain(|t| {
+            if !remove(t) {
+                return true;
+            }
+            if let (true, false, Some(id)) = (synced, t.completed, &t.remote_id) {
+                deleted.push(id.clone());
+            }
+            false
+        });
+        let _ = self.save();
+        if synced {
+            let _ = self.save_sync_state();
+        }
+    }
+
+    fn todo_item(todo: &TodoItem, now: NaiveDateTime) -> Item {
+        let prefix = if todo.completed { "✓ " } else { "○ " };
+
+        let mut item = Item::new(
+            format!("todo:{}", todo.id),
+            format!("{}{}", prefix, todo.text),
+            ItemType::Todo,
+        )
+        .with_description(format!(
+            "Created: {} | {}",
+            todo.created,
+            if todo.completed {
+                "Completed"
+            } else {
+                "Pending"
+            }
+        ))
+        .with_icon(if todo.completed {
+            "checkbox-checked"
+        } else {
+            "checkbox"
+        });
+
+        item.metadata.content = Some(todo.text.clone());
+        item.metadata.completed = todo.completed;
+        item.metadata.created = Some(todo.created.clone());
+        item.metadata.tags = todo.tags.clone();
+        item.metadata.project = todo.project.clone();
+        item.metadata.priority = todo.priority.map(|p| p.label().to_string());
+        item.metadata.due = todo.due_label(now);
+        item.metadata.recurrence = todo.recur.clone();
+        item
+    }
+
+    /// Open todos due today or overdue, under a heading per day.
+    fn agenda_items(&self, now: NaiveDateTime) -> Vec<Item> {
+        let today = now.date();
+        let mut by_day: BTreeMap<NaiveDate, Vec<&TodoItem>> = BTreeMap::new();
+        for todo in self.todos.iter().filter(|t| !t.completed) {
+            let day = todo.due.as_deref().and_then(|due| NaiveDate::parse_from_str(due.get(..10)?, "%Y-%m-%d").ok());
+            if let Some(day) = day.filter(|day| *day <= today) {
+                by_day.entry(day).or_default().push(todo);
+            }
+        }
+        if by_day.is_empty() {
+            return vec![Item::new("todo:agenda:empty", "Nothing due today", ItemType::Command)
+                .with_description("Todos with a due date show up here on their day")
+                .with_icon("x-office-calendar")];
+        }
+
+        let mut items = Vec::new();
+        for (day, mut todos) in by_day {
+            let heading = match (today - day).num_days() {
+                0 => format!("Today, {}", day.format("%a %b %-d")),
+                1 => format!("Yesterday, {}", day.format("%a %b %-d")),
+                days => format!("{}, {} days ago", day.format("%a %b %-d"), days),
+            };
+            items.push(
+                Item::new(format!("todo:agenda:{}", day), heading, ItemType::Command)
+                    .with_description(format!("{} open", todos.len()))
+                    .with_icon("x-office-calendar"),
+            );
+            todos.sort_by_key(|t| (std::cmp::Reverse(t.priority), t.due_at()));
+            items.extend(todos.into_iter().map(|todo| Self::todo_item(todo, now)));
+        }
+        items
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        if query.trim().eq_ignore_ascii_case("agenda") {
+            return self.agenda_items(Local::now().naive_local());
+        }
+
+        let filter = Filter::parse(&query.to_lowercase());
+        let mut items = Vec::new();
+
+        // Add action to create todo
+        if query.starts_with("add ") || query.starts_with("new ") {
+            let text = query.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
+            if !text.is_empty() {
+                items.push(
+                    Item::new(
+                        format!("todo:add:{}", text),
+                        format!("Add Todo: {}", text),
+                        ItemType::TodoAction,
+                    )
+                    .with_description("Create a new todo item")
+                    .with_icon("checkbox"),
+                );
+            }
+        }
+
+        // "edit <id>: <text>" replaces a todo's text, see edit_query
+        if let Some((id, text)) = Self::parse_edit(query) {
+            if let Some(todo) = self.find(id) {
+                items.push(
+                    Item::new(format!("todo:edit:{}", todo.id), format!("Save: {}", text), ItemType::TodoAction)
+                        .with_description(format!("Was: {}", Self::full_text(todo)))
+                        .with_icon("document-save"),
+                );
+            }
+            return items;
+        }
+
+        // List existing todos (overdue first, then the other incomplete
+        // ones, then completed)
+        let now = Local::now().naive_local();
+        let mut incomplete: Vec<_> = self.todos.iter().filter(|t| !t.completed).collect();
+        incomplete.sort_by_key(|t| (!t.is_overdue(now), t.is_overdue(now).then(|| t.due_at())));
+        let completed: Vec<_> = self.todos.iter().filter(|t| t.completed).collect();
+
+        for todo in incomplete.iter().chain(completed.iter()) {
+            if todo.matches(&filter) {
+                items.push(Self::todo_item(todo, now));
+            }
+        }
+
+        // Open todos per project and tag, to jump to a filtered view
+        if query.is_empty() {
+            let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+            for todo in &incomplete {
+                let project = todo.project.iter().map(|p| format!("@{}", p));
+                let tags = todo.tags.iter().map(|t| format!("#{}", t));
+                for token in project.chain(tags) {
+                    *counts.entry(token).or_default() += 1;
+                }
+            }
+            for (token, count) in counts {
+                items.push(
+                    Item::new(format!("todo:filter:{}", token), token.clone(), ItemType::TodoAction)
+                        .with_description(format!("{} open | Press Enter to filter", count))
+                        .with_icon("tag"),
+                );
+            }
+        }
+
+        // Add clear actions
+        if !self.todos.is_empty() && query.is_empty() {
+            items.push(
+                Item::new(
+                    "todo:action:clear_completed",
+                    "Clear Completed",
+                    ItemType::TodoAction,
+                )
+                .with_description("Remove all completed todos")
+                .with_icon("edit-delete"),
+            );
+            items.push(
+                Item::new("todo:action:clear_all", "Clear All", ItemType::TodoAction)
+                    .with_description("Remove all todos")
+                    .with_icon("edit-delete"),
+            );
+        }
+
+        items
+    }
+
+    pub fn add_todo(&mut self, text: &str) {
+        let parsed = Filter::parse(text);
+        let due = parsed.due_or_first(Local::now().date_naive());
+        let todo = TodoItem {
+            id: Uuid::new_v4().to_string(),
+            text: parsed.text,
+            completed: false,
+            created: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            tags: parsed.tags,
+            project: parsed.project,
+            priority: parsed.priority,
+            due,
+            reminded: false,
+            recur: parsed.recur,
+            remote_id: None,
+            modified: false,
+        };
+
+        self.todos.insert(0, todo);
+        let _ = self.save();
+    }
+
+    /// Todo by full id or the short id used in edit queries.
+    fn find(&self, id: &str) -> Option<&TodoItem> {
+        let id = id.strip_prefix("todo:").unwrap_or(id);
+        self.todos.iter().find(|t| t.id == id || (id.len() >= 8 && t.id.starts_with(id)))
+    }
+
+    /// The text as typed, with its `@project`, `#tags`, `!priority`, due
+    /// date and recurrence.
+    fn full_text(todo: &TodoItem) -> String {
+        let mut text = todo.text.clone();
+        if let Some(project) = &todo.project {
+            text.push_str(&format!(" @{}", project));
+        }
+        if let Some(priority) = todo.priority {
+            text.push_str(&format!(" !{}", priority.label()));
+        }
+        if let Some(due) = &todo.due {
+            text.push_str(&format!(" @{}", due.replace(' ', " @")));
+        }
+        if let Some(recur) = &todo.recur {
+            text.push_str(&format!(" {}", recur));
+        }
+        for tag in &todo.tags {
+            text.push_str(&format!(" #{}", tag));
+        }
+        text
+    }
+
+    fn parse_edit(query: &str) -> Option<(&str, &str)> {
+        let (id, text) = query.strip_prefix("edit ")?.split_once(':')?;
+        Some((id.trim(), text.trim())).filter(|(_, text)| !text.is_empty())
+    }
+
+    /// Search text that edits the todo in place, e.g. for Ctrl+E.
+    pub fn edit_query(&self, id: &str) -> Option<String> {
+        let todo = self.find(id)?;
+        Some(format!("edit {}: {}", &todo.id[..8.min(todo.id.len())], Self::full_text(todo)))
+    }
+
+    pub fn edit_todo(&mut self, id: &str, text: &str) {
+        let id = id.strip_prefix("todo:").unwrap_or(id);
+        let parsed = Filter::parse(text);
+        let due = parsed.due_or_first(Local::now().date_naive());
+        if let Some(todo) = self.todos.iter_mut().find(|t| t.id == id) {
+            todo.text = parsed.text;
+            todo.tags = parsed.tags;
+            todo.project = parsed.project;
+            todo.priority = parsed.priority;
+            if todo.due != due {
+                todo.due = due;
+                todo.reminded = false;
+            }
+            todo.recur = parsed.recur;
+            todo.modified = true;
+            let _ = self.save();
+        }
+    }
+
+    /// Whether an open todo with a due date hasn't been reminded of yet.
+    pub fn has_pending_reminders(&self) -> bool {
+        self.todos.iter().any(|t| !t.completed && !t.reminded && t.due.is_some())
+    }
+
+    /// Notifies once for each open todo whose due date has passed.
+    pub fn tick(&mut self) {
+        for text in self.take_overdue(Local::now().naive_local()) {
+            let _ = notify_rust::Notification::new().summary("Todo Overdue").body(&text).show();
+        }
+    }
+
+    /// Marks the todos overdue at `now` as reminded and returns their text.
+    /// Both the launcher and the daemon tick, so the todos are re-read first
+    /// and saved before notifying, for the other not to remind again.
+    fn take_overdue(&mut self, now: NaiveDateTime) -> Vec<String> {
+        self.reload_if_changed();
+        let mut overdue = Vec::new();
+        for todo in self.todos.iter_mut().filter(|t| !t.reminded && t.is_overdue(now)) {
+            todo.reminded = true;
+            overdue.push(todo.text.clone());
+        }
+        if overdue.is_empty() {
+            return overdue;
+        }
+        if let Err(e) = self.save() {
+            log::error!("Failed to save todo reminders: {}", e);
+            return Vec::new();
+        }
+        overdue
+    }
+
+    /// Moves a todo past its neighbour with the same completion state,
+    /// up for negative `delta`. Returns whether it moved.
+    pub fn move_todo(&mut self, id: &str, delta: isize) -> bool {
+        let moved = self.reorder(id, delta);
+        if moved {
+            let _ = self.save();
+        }
+        moved
+    }
+
+    fn reorder(&mut self, id: &str, delta: isize) -> bool {
+        let id = id.strip_prefix("todo:").unwrap_or(id);
+        let Some(from) = self.todos.iter().position(|t| t.id == id) else {
+            return false;
+        };
+        let completed = self.todos[from].completed;
+        let neighbour = if delta < 0 {
+            self.todos[..from].iter().rposition(|t| t.completed == completed)
+        } else {
+            self.todos[from + 1..]
+                .iter()
+                .position(|t| t.completed == completed)
+                .map(|i| from + 1 + i)
+        };
+        match neighbour {
+            Some(to) => {
+                self.todos.swap(from, to);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn toggle_todo(&mut self, id: &str) {
+        let id = id.strip_prefix("todo:").unwrap_or(id);
+        if let Some(todo) = self.todos.iter_mut().find(|t| t.id == id) {
+            match todo.recur.as_deref().and_then(Recurrence::parse).filter(|_| !todo.completed) {
+                Some(recurrence) => todo.advance(recurrence, Local::now().date_naive()),
+                None => todo.completed = !todo.completed,
+            }
+            todo.modified = true;
+            let _ = self.save();
+        }
+    }
+
+    pub fn delete_todo(&mut self, id: &str) {
+        self.remove_where(|t| t.id == id);
+    }
+
+    pub fn clear_completed(&mut self) {
+        self.remove_where(|t| t.completed);
+    }
+
+    pub fn clear_all(&mut self) {
+        self.remove_where(|_| true);
+    }
+
+    pub fn execute_action(&mut self, action_id: &str, query: &str) {
+        if action_id.starts_with("todo:add:") {
+            let text = action_id.strip_prefix("todo:add:").unwrap();
+            self.add_todo(text);
+        } else if action_id == "todo:action:clear_completed" {
+            self.clear_completed();
+        } else if action_id == "todo:action:clear_all" {
+            self.clear_all();
+        } else if let Some(id) = action_id.strip_prefix("todo:edit:") {
+            if let Some((_, text)) = Self::parse_edit(query) {
+                self.edit_todo(id, text);
+            }
+        } else if action_id.starts_with("todo:delete:") {
+            let id = action_id.strip_prefix("todo:delete:").unwrap();
+            self.delete_todo(id);
+        } else if query.starts_with("add ") || query.starts_with("new ") {
+            let text = query.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
+            if !text.is_empty() {
+                self.add_todo(text);
+            }
+        }
+    }
+}
+
+impl Default for TodosManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tokens() {
+        let parsed = Filter::parse("Send invoice #Work #finance @acme");
+        assert_eq!(parsed.text, "Send invoice");
+        assert_eq!(parsed.tags, vec!["work", "finance"]);
+        assert_eq!(parsed.project.as_deref(), Some("acme"));
+
+        let parsed = Filter::parse("fix issue #42 for me@ @");
+        assert_eq!(parsed.text, "fix issue #42 for me@ @");
+        assert!(parsed.tags.is_empty());
+        assert_eq!(parsed.project, None);
+    }
+
+    #[test]
+    fn test_move_todo() {
+        let todo = |id: &str, completed: bool| TodoItem {
+            id: id.to_string(),
+            text: id.to_string(),
+            completed,
+            created: String::new(),
+            tags: Vec::new(),
+            project: None,
+            priority: None,
+            due: None,
+            reminded: false,
+            recur: None,
+            remote_id: None,
+            modified: false,
+        };
+        let mut manager = TodosManager {
+            todos: vec![todo("a", false), todo("b", true), todo("c", false)],
+            store: SyncedStore::new(&Config::default(), "todos.json"),
+            sync: None,
+            sync_state: SyncState::default(),
+            syncing: false,
+            last_sync: None,
+        };
+        let order = |m: &TodosManager| m.todos.iter().map(|t| t.id.as_str()).collect::<String>();
+
+        // Skips over the completed todo
+        assert!(manager.reorder("todo:c", -1));
+        assert_eq!(order(&manager), "cba");
+        assert!(!manager.reorder("c", -1));
+    }
+
+    #[test]
+    fn test_reminded_once() {
+        let dir = std::env::temp_dir().join(format!("wlaunch-todos-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("todos.json");
+        let todo = TodoItem {
+            id: "1".to_string(),
+            text: "Pay rent".to_string(),
+            completed: false,
+            created: String::new(),
+            tags: Vec::new(),
+            project: None,
+            priority: None,
+            due: Some("2026-10-01 09:00".to_string()),
+            reminded: false,
+            recur: None,
+            remote_id: None,
+            modified: false,
+        };
+        fs::write(&path, serde_json::to_string(&[todo]).unwrap()).unwrap();
+        let open = || {
+            let mut store = SyncedStore::at(path.clone());
+            TodosManager {
+                todos: TodosManager::load(&mut store).unwrap(),
+                store,
+                sync: None,
+                sync_state: SyncState::default(),
+                syncing: false,
+                last_sync: None,
+            }
+        };
+        // The launcher and the daemon both have the todo loaded
+        let (mut launcher, mut daemon) = (open(), open());
+        let now = NaiveDate::from_ymd_opt(2026, 10, 2).unwrap().and_hms_opt(8, 0, 0).unwrap();
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        assert_eq!(daemon.take_overdue(now), ["Pay rent"]);
+        assert!(launcher.take_overdue(now).is_empty());
+        assert!(daemon.take_overdue(now).is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_matches() {
+        let todo = TodoItem {
+            id: "1".to_string(),
+            text: "Send invoice".to_string(),
+            completed: false,
+            created: String::new(),
+            tags: vec!["work".to_string()],
+            project: Some("acme".to_string()),
+            priority: Some(Priority::High),
+            due: Some("2026-10-16".to_string()),
+            reminded: false,
+            recur: None,
+            remote_id: None,
+            modified: false,
+        };
+        assert!(todo.matches(&Filter::parse("#work")));
+        assert!(todo.matches(&Filter::parse("invoice @acme")));
+        assert!(!todo.matches(&Filter::parse("#home")));
+        assert!(!todo.matches(&Filter::parse("@other invoice")));
+        assert!(todo.matches(&Filter::parse("!high @2026-10-16")));
+        assert!(!todo.matches(&Filter::parse("!low")));
+    }
+
+    #[test]
+    fn test_parse_due() {
+        // A Friday
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let parsed = Filter::parse_at("pay rent !high @monday @home", today);
+        assert_eq!(parsed.text, "pay rent");
+        assert_eq!(parsed.priority, Some(Priority::High));
+        assert_eq!(parsed.due.as_deref(), Some("2026-10-19"));
+        assert_eq!(parsed.project.as_deref(), Some("home"));
+
+        assert_eq!(Filter::parse_at("call @fri @5:30pm", today).due.as_deref(), Some("2026-10-16 17:30"));
+        assert_eq!(Filter::parse_at("call @tomorrow", today).due.as_deref(), Some("2026-10-17"));
+        assert_eq!(Filter::parse_at("wow !!", today).text, "wow !!");
+
+        let todo = TodoItem {
+            id: "1".to_string(),
+            text: "pay rent".to_string(),
+            completed: false,
+            created: String::new(),
+            tags: Vec::new(),
+            project: None,
+            priority: None,
+            due: Some("2026-10-16".to_string()),
+            reminded: false,
+            recur: None,
+            remote_id: None,
+            modified: false,
+        };
+        let at = |h| today.and_hms_opt(h, 0, 0).unwrap();
+        assert!(!todo.is_overdue(at(23)));
+        assert_eq!(todo.due_label(at(9)).as_deref(), Some("due today"));
+        assert!(todo.is_overdue(today.succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_recurring() {
+        // A Friday
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let parsed = Filter::parse_at("water plants every monday @9am", today);
+        assert_eq!(parsed.text, "water plants");
+        assert_eq!(parsed.recur.as_deref(), Some("every monday"));
+        assert_eq!(Filter::parse_at("check every box", today).text, "check every box");
+        assert_eq!(Filter::parse_at("stretch every weekday", today).due_or_first(today).as_deref(), Some("2026-10-16"));
+
+        let mut todo = TodoItem {
+            id: "1".to_string(),
+            text: "water plants".to_string(),
+            completed: false,
+            created: String::new(),
+            tags: Vec::new(),
+            project: None,
+            priority: None,
+            due: Some("2026-10-12 09:00".to_string()),
+            reminded: true,
+            recur: parsed.recur,
+            remote_id: None,
+            modified: false,
+        };
+        // Overdue since Monday, done on Friday: next Monday
+        todo.advance(Recurrence::parse("every monday").unwrap(), today);
+        assert_eq!(todo.due.as_deref(), Some("2026-10-19 09:00"));
+        assert!(!todo.reminded && !todo.completed);
+    }
+}
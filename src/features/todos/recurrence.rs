@@ -0,0 +1,176 @@
+//! Repeating todos: "every day", "every 2 weeks", "every monday", "every
+//! weekday", "every month". Completing one moves it to its next date instead
+//! of checking it off.
+
+use super::WEEKDAYS;
+use chrono::{Datelike, Duration, Months, NaiveDate};
+
+const RRULE_DAYS: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Recurrence {
+    Days(u32),
+    Weeks(u32),
+    Months(u32),
+    Years(u32),
+    /// Weekly on one day, Monday being 0
+    Weekday(u32),
+    /// Monday to Friday
+    Workdays,
+}
+
+impl Recurrence {
+    /// The words after "every", as `["2", "weeks"]` or `["monday"]`, and how
+    /// many of them were used.
+    pub fn parse_words(words: &[&str]) -> Option<(Self, usize)> {
+        let (count, unit, used) = match words.first()?.parse::<u32>() {
+            Ok(count) if count > 0 => (count, *words.get(1)?, 2),
+            Ok(_) => return None,
+            Err(_) => (1, words[0], 1),
+        };
+        let recurrence = match unit.to_lowercase().as_str() {
+            "day" | "days" => Recurrence::Days(count),
+            "week" | "weeks" => Recurrence::Weeks(count),
+            "month" | "months" => Recurrence::Months(count),
+            "year" | "years" => Recurrence::Years(count),
+            "weekday" | "weekdays" | "workday" | "workdays" if count == 1 => Recurrence::Workdays,
+            day if count == 1 && day.len() >= 3 => {
+                let day = day.trim_end_matches('s');
+                Recurrence::Weekday(WEEKDAYS.iter().position(|d| d.starts_with(day))? as u32)
+            }
+            _ => return None,
+        };
+        Some((recurrence, used))
+    }
+
+    /// "every monday", also with the trailing "at 09:00" Todoist adds.
+    pub fn parse(text: &str) -> Option<Self> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let (first, rest) = words.split_first()?;
+        if !first.eq_ignore_ascii_case("every") {
+            return None;
+        }
+        let (recurrence, used) = Self::parse_words(rest)?;
+        rest.get(used).is_none_or(|w| w.eq_ignore_ascii_case("at")).then_some(recurrence)
+    }
+
+    pub fn label(&self) -> String {
+        let every = |count: u32, unit: &str| match count {
+            1 => format!("every {}", unit),
+            n => format!("every {} {}s", n, unit),
+        };
+        match self {
+            Recurrence::Days(n) => every(*n, "day"),
+            Recurrence::Weeks(n) => every(*n, "week"),
+            Recurrence::Months(n) => every(*n, "month"),
+            Recurrence::Years(n) => every(*n, "year"),
+            Recurrence::Weekday(day) => format!("every {}", WEEKDAYS[*day as usize]),
+            Recurrence::Workdays => "every weekday".to_string(),
+        }
+    }
+
+    /// The first date on or after `today`.
+    pub fn first(&self, today: NaiveDate) -> NaiveDate {
+        match self {
+            Recurrence::Weekday(_) | Recurrence::Workdays if !self.falls_on(today) => self.next(today),
+            _ => today,
+        }
+    }
+
+    /// The next date after `date`.
+    pub fn next(&self, date: NaiveDate) -> NaiveDate {
+        let shifted = match self {
+            Recurrence::Days(n) => date.checked_add_signed(Duration::days(*n as i64)),
+            Recurrence::Weeks(n) => date.checked_add_signed(Duration::weeks(*n as i64)),
+            Recurrence::Months(n) => date.checked_add_months(Months::new(*n)),
+            Recurrence::Years(n) => date.checked_add_months(Months::new(n * 12)),
+            Recurrence::Weekday(_) | Recurrence::Workdays => {
+                (1..=7).map(|d| date + Duration::days(d)).find(|d| self.falls_on(*d))
+            }
+        };
+        shifted.unwrap_or(date)
+    }
+
+    fn falls_on(&self, date: NaiveDate) -> bool {
+        let weekday = date.weekday().num_days_from_monday();
+        match self {
+            Recurrence::Weekday(day) => weekday == *day,
+            Recurrence::Workdays => weekday < 5,
+            _ => true,
+        }
+    }
+
+    /// An iCalendar RRULE value, `FREQ=WEEKLY;BYDAY=MO`.
+    pub fn to_rrule(self) -> String {
+        let freq = |freq: &str, n: u32| match n {
+            1 => format!("FREQ={}", freq),
+            n => format!("FREQ={};INTERVAL={}", freq, n),
+        };
+        match self {
+            Recurrence::Days(n) => freq("DAILY", n),
+            Recurrence::Weeks(n) => freq("WEEKLY", n),
+            Recurrence::Months(n) => freq("MONTHLY", n),
+            Recurrence::Years(n) => freq("YEARLY", n),
+            Recurrence::Weekday(day) => format!("FREQ=WEEKLY;BYDAY={}", RRULE_DAYS[day as usize]),
+            Recurrence::Workdays => "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR".to_string(),
+        }
+    }
+
+    /// The RRULEs `to_rrule` writes; anything more elaborate is ignored.
+    pub fn from_rrule(rule: &str) -> Option<Self> {
+        let mut freq = None;
+        let mut interval = 1;
+        let mut days: Vec<u32> = Vec::new();
+        for part in rule.split(';') {
+            match part.split_once('=')? {
+                ("FREQ", value) => freq = Some(value),
+                ("INTERVAL", value) => interval = value.parse().ok().filter(|n| *n > 0)?,
+                ("BYDAY", value) => {
+                    for day in value.split(',') {
+                        days.push(RRULE_DAYS.iter().position(|d| *d == day)? as u32);
+                    }
+                }
+                _ => {}
+            }
+        }
+        match (freq?, days.as_slice()) {
+            ("DAILY", []) => Some(Recurrence::Days(interval)),
+            ("WEEKLY", []) => Some(Recurrence::Weeks(interval)),
+            ("WEEKLY", [day]) if interval == 1 => Some(Recurrence::Weekday(*day)),
+            ("WEEKLY", [0, 1, 2, 3, 4]) if interval == 1 => Some(Recurrence::Workdays),
+            ("MONTHLY", []) => Some(Recurrence::Months(interval)),
+            ("YEARLY", []) => Some(Recurrence::Years(interval)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recurrence() {
+        // A Friday
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+
+        let monday = Recurrence::parse("every Monday").unwrap();
+        assert_eq!(monday.label(), "every monday");
+        assert_eq!(monday.first(today), date(19));
+        assert_eq!(monday.next(date(19)), date(26));
+
+        let workdays = Recurrence::parse("every weekday").unwrap();
+        assert_eq!(workdays.first(today), today);
+        assert_eq!(workdays.next(today), date(19));
+
+        assert_eq!(Recurrence::parse("every 2 weeks at 09:00"), Some(Recurrence::Weeks(2)));
+        assert_eq!(Recurrence::parse_words(&["month", "pay"]), Some((Recurrence::Months(1), 1)));
+        assert_eq!(Recurrence::parse("every monday and friday"), None);
+        assert_eq!(Recurrence::parse_words(&["body"]), None);
+
+        for recurrence in [monday, workdays, Recurrence::Days(3), Recurrence::Years(1)] {
+            assert_eq!(Recurrence::from_rrule(&recurrence.to_rrule()), Some(recurrence));
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Two-way sync of todos with Todoist or a CalDAV task list. Local changes
+//! are pushed first, then the remote list is pulled and merged: remote edits
+//! win for todos not changed locally since, and todos that vanished remotely
+//! were completed or deleted there.
+
+use super::caldav::Caldav;
+use super::todoist::Todoist;
+use super::{Priority, TodoItem};
+use crate::core::config::{TodoBackend, TodoSyncConfig};
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A task as the service has it.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct RemoteTask {
+    /// Todoist task id or CalDAV resource path
+    pub id: String,
+    /// CalDAV UID, kept as the local id so the task can be written back
+    pub uid: Option<String>,
+    pub text: String,
+    pub completed: bool,
+    pub due: Option<String>,
+    pub priority: Option<Priority>,
+    pub tags: Vec<String>,
+    pub recur: Option<String>,
+}
+
+impl RemoteTask {
+    fn apply_to(&self, todo: &mut TodoItem) {
+        if todo.due != self.due {
+            todo.due = self.due.clone();
+            todo.reminded = false;
+        }
+        todo.text = self.text.clone();
+        todo.completed = self.completed;
+        todo.priority = self.priority;
+        todo.tags = self.tags.clone();
+        todo.recur = self.recur.clone();
+    }
+
+    fn to_todo(&self) -> TodoItem {
+        TodoItem {
+            id: self.uid.clone().unwrap_or_else(|| Uuid::new_v4().to_string()),
+            text: self.text.clone(),
+            completed: self.completed,
+            created: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            tags: self.tags.clone(),
+            project: None,
+            priority: self.priority,
+            due: self.due.clone(),
+            reminded: false,
+            recur: self.recur.clone(),
+            remote_id: Some(self.id.clone()),
+            modified: false,
+        }
+    }
+}
+
+/// What a sync did, merged into the todos once it finishes.
+#[derive(Debug, Clone, Default)]
+pub struct SyncOutcome {
+    /// Local and remote ids of todos created remotely
+    created: Vec<(String, String)>,
+    /// Todos as they were pushed
+    pushed: Vec<TodoItem>,
+    /// Remote ids of todos deleted remotely
+    deleted: Vec<String>,
+    /// Every remote task, or None if they couldn't be listed
+    remote: Option<Vec<RemoteTask>>,
+}
+
+enum Remote {
+    Todoist(Todoist),
+    Caldav(Caldav),
+}
+
+impl Remote {
+    fn new(config: &TodoSyncConfig) -> Result<Self> {
+        let token = config.token.clone().ok_or_else(|| anyhow!("todo_sync.token is not set"))?;
+        match config.backend {
+            Some(TodoBackend::Todoist) => Ok(Remote::Todoist(Todoist::new(token))),
+            Some(TodoBackend::Caldav) => {
+                let url = config.url.as_deref().ok_or_else(|| anyhow!("todo_sync.url is not set"))?;
+                let username = config.username.clone().unwrap_or_default();
+                Ok(Remote::Caldav(Caldav::new(url, username, token)?))
+            }
+            None => Err(anyhow!("todo_sync.backend is not set")),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<RemoteTask>> {
+        match self {
+            Remote::Todoist(todoist) => todoist.list().await,
+            Remote::Caldav(caldav) => caldav.list().await,
+        }
+    }
+
+    /// Returns the new task's remote id.
+    async fn create(&self, todo: &TodoItem) -> Result<String> {
+        match self {
+            Remote::Todoist(todoist) => todoist.create(todo).await,
+            Remote::Caldav(caldav) => caldav.create(todo).await,
+        }
+    }
+
+    async fn update(&self, id: &str, todo: &TodoItem) -> Result<()> {
+        match self {
+            Remote::Todoist(todoist) => todoist.update(id, todo).await,
+            Remote::Caldav(caldav) => caldav.update(id, todo).await,
+        }
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        match self {
+            Remote::Todoist(todoist) => todoist.delete(id).await,
+            Remote::Caldav(caldav) => caldav.delete(id).await,
+        }
+    }
+}
+
+/// Pushes deletions, new todos and local edits, then lists the remote
+/// tasks. Failed requests are logged and retried on the next sync.
+pub(super) async fn run(config: TodoSyncConfig, todos: Vec<TodoItem>, deleted: Vec<String>) -> Result<SyncOutcome> {
+    let remote = Remote::new(&config)?;
+    let mut outcome = SyncOutcome::default();
+
+    for id in deleted {
+        match remote.delete(&id).await {
+            Ok(()) => outcome.deleted.push(id),
+            Err(e) => log::warn!("Failed to delete synced todo {}: {}", id, e),
+        }
+    }
+
+    // Todos completed before sync was set up stay local
+    let pending = todos
+        .into_iter()
+        .filter(|t| if t.remote_id.is_some() { t.modified } else { !t.completed });
+    for todo in pending {
+        let result = match &todo.remote_id {
+            Some(id) => remote.update(id, &todo).await,
+            None => remote.create(&todo).await.map(|id| outcome.created.push((todo.id.clone(), id))),
+        };
+        match result {
+            Ok(()) => outcome.pushed.push(todo),
+            Err(e) => log::warn!("Failed to sync todo '{}': {}", todo.text, e),
+        }
+    }
+
+    match remote.list().await {
+        Ok(tasks) => outcome.remote = Some(tasks),
+        Err(e) => log::warn!("Failed to list synced todos: {}", e),
+    }
+    Ok(outcome)
+}
+
+/// Applies a finished sync to the current todos, which may have changed
+/// while it ran.
+pub(super) fn merge(todos: &mut Vec<TodoItem>, deleted: &mut Vec<String>, outcome: SyncOutcome) {
+    deleted.retain(|id| !outcome.deleted.contains(id));
+
+    // Todos edited again while syncing are pushed again next time
+    for pushed in &outcome.pushed {
+        if let Some(todo) = todos.iter_mut().find(|t| t.id == pushed.id) {
+            if todo == pushed {
+                todo.modified = false;
+            }
+        }
+    }
+    for (local, remote) in outcome.created {
+        match todos.iter_mut().find(|t| t.id == local) {
+            Some(todo) => todo.remote_id = Some(remote),
+            None => deleted.push(remote),
+        }
+    }
+
+    let Some(tasks) = outcome.remote else {
+        return;
+    };
+    let by_id: HashMap<&str, &RemoteTask> = tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    for todo in todos.iter_mut().filter(|t| !t.modified) {
+        let Some(remote_id) = &todo.remote_id else {
+            continue;
+        };
+        match by_id.get(remote_id.as_str()) {
+            Some(task) => task.apply_to(todo),
+            None => todo.completed = true,
+        }
+    }
+
+    // Completed tasks only come along with a todo that is already here
+    for task in tasks.iter().filter(|t| !t.completed && !deleted.contains(&t.id)) {
+        if !todos.iter().any(|t| t.remote_id.as_deref() == Some(task.id.as_str())) {
+            todos.push(task.to_todo());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo(id: &str, remote_id: Option<&str>) -> TodoItem {
+        TodoItem {
+            id: id.to_string(),
+            text: id.to_string(),
+            completed: false,
+            created: String::new(),
+            tags: Vec::new(),
+            project: None,
+            priority: None,
+            due: None,
+            reminded: false,
+            recur: None,
+            remote_id: remote_id.map(str::to_string),
+            modified: false,
+        }
+    }
+
+    fn task(id: &str, text: &str) -> RemoteTask {
+        RemoteTask {
+            id: id.to_string(),
+            uid: None,
+            text: text.to_string(),
+            completed: false,
+            due: Some("2026-10-20".to_string()),
+            priority: Some(Priority::High),
+            tags: Vec::new(),
+            recur: None,
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut edited = todo("edited", Some("r2"));
+        edited.modified = true;
+        let mut todos = vec![todo("new", None), todo("synced", Some("r1")), edited.clone(), todo("gone", Some("r3"))];
+        let mut deleted = vec!["r9".to_string(), "r8".to_string()];
+
+        let outcome = SyncOutcome {
+            created: vec![("new".to_string(), "r4".to_string())],
+            pushed: vec![todo("new", None)],
+            deleted: vec!["r9".to_string()],
+            remote: Some(vec![
+                task("r1", "renamed remotely"),
+                task("r2", "overwritten locally"),
+                task("r4", "new"),
+                task("r5", "added remotely"),
+                task("r8", "deleted locally"),
+            ]),
+        };
+        merge(&mut todos, &mut deleted, outcome);
+
+        assert_eq!(deleted, ["r8"]);
+        assert_eq!(todos[0].remote_id.as_deref(), Some("r4"));
+        assert_eq!(todos[1].text, "renamed remotely");
+        assert_eq!(todos[1].priority, Some(Priority::High));
+        // Changed locally since, so it is pushed again instead
+        assert_eq!(todos[2], edited);
+        assert!(todos[3].completed);
+        let texts: Vec<&str> = todos.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, ["new", "renamed remotely", "edited", "gone", "added remotely"]);
+    }
+}
@@ -0,0 +1,227 @@
+//! Todoist's API. Only active tasks are listed, so a task missing from the
+//! list was completed or deleted, and completing a todo closes its task.
+//! Labels are the todo's `#tags`; projects stay local. Repeating todos use
+//! Todoist's own recurring due dates, which it moves on when closed.
+
+use super::recurrence::Recurrence;
+use super::sync::RemoteTask;
+use super::{Priority, TodoItem};
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDateTime, SecondsFormat, TimeZone};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+const API: &str = "https://api.todoist.com/api/v1";
+
+#[derive(Deserialize)]
+struct Page {
+    results: Vec<Task>,
+    #[serde(default)]
+    next_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Task {
+    id: String,
+    content: String,
+    #[serde(default)]
+    labels: Vec<String>,
+    /// 4 is the most urgent, 1 none
+    #[serde(default)]
+    priority: u8,
+    #[serde(default)]
+    due: Option<Due>,
+    #[serde(default)]
+    checked: bool,
+}
+
+#[derive(Deserialize)]
+struct Due {
+    /// `2026-10-20`, or with a time `2026-10-20T17:00:00`, `Z` for fixed zones
+    date: String,
+    #[serde(default)]
+    datetime: Option<String>,
+    /// As typed, "every monday" for recurring dates
+    #[serde(default)]
+    string: String,
+    #[serde(default)]
+    is_recurring: bool,
+}
+
+impl Task {
+    fn into_remote(self) -> RemoteTask {
+        RemoteTask {
+            id: self.id,
+            uid: None,
+            text: self.content,
+            completed: self.checked,
+            recur: self
+                .due
+                .as_ref()
+                .filter(|due| due.is_recurring)
+                .and_then(|due| Recurrence::parse(&due.string))
+                .map(|recurrence| recurrence.label()),
+            due: self.due.and_then(|due| parse_due(due.datetime.as_deref().unwrap_or(&due.date))),
+            priority: match self.priority {
+                4 => Some(Priority::High),
+                3 => Some(Priority::Medium),
+                2 => Some(Priority::Low),
+                _ => None,
+            },
+            tags: self.labels,
+        }
+    }
+}
+
+/// A Todoist due date as ours: `YYYY-MM-DD`, or `YYYY-MM-DD HH:MM` in local
+/// time.
+fn parse_due(date: &str) -> Option<String> {
+    if date.len() <= 10 {
+        return Some(date.to_string());
+    }
+    let local = match DateTime::parse_from_rfc3339(date) {
+        Ok(fixed) => fixed.with_timezone(&Local).naive_local(),
+        Err(_) => NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S%.f").ok()?,
+    };
+    Some(local.format("%Y-%m-%d %H:%M").to_string())
+}
+
+/// Content, labels, priority and due date of `todo` as a task body.
+fn body(todo: &TodoItem) -> Value {
+    let priority = match todo.priority {
+        Some(Priority::High) => 4,
+        Some(Priority::Medium) => 3,
+        Some(Priority::Low) => 2,
+        None => 1,
+    };
+    let mut body = json!({ "content": todo.text, "labels": todo.tags, "priority": priority });
+    let due_time = todo.due.as_deref().and_then(|due| NaiveDateTime::parse_from_str(due, "%Y-%m-%d %H:%M").ok());
+    if let Some(recur) = &todo.recur {
+        body["due_string"] = match due_time {
+            Some(time) => json!(format!("{} at {}", recur, time.format("%H:%M"))),
+            None => json!(recur),
+        };
+        return body;
+    }
+    match (&todo.due, due_time.and_then(|time| Local.from_local_datetime(&time).earliest())) {
+        (Some(_), Some(time)) => body["due_datetime"] = json!(time.to_utc().to_rfc3339_opts(SecondsFormat::Secs, true)),
+        (Some(date), None) => body["due_date"] = json!(date),
+        (None, _) => body["due_string"] = json!("no date"),
+    }
+    body
+}
+
+pub(super) struct Todoist {
+    token: String,
+    client: Client,
+}
+
+impl Todoist {
+    pub fn new(token: String) -> Self {
+        Self {
+            token,
+            client: Client::new(),
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<RemoteTask>> {
+        let mut tasks = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut request = self
+                .client
+                .get(format!("{}/tasks", API))
+                .bearer_auth(&self.token)
+                .query(&[("limit", "200")]);
+            if let Some(cursor) = &cursor {
+                request = request.query(&[("cursor", cursor)]);
+            }
+            let page: Page = request.send().await?.error_for_status()?.json().await?;
+            tasks.extend(page.results.into_iter().map(Task::into_remote));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(tasks),
+            }
+        }
+    }
+
+    pub async fn create(&self, todo: &TodoItem) -> Result<String> {
+        let response = self
+            .client
+            .post(format!("{}/tasks", API))
+            .bearer_auth(&self.token)
+            .json(&body(todo))
+            .send()
+            .await?
+            .error_for_status()?;
+        let created: Task = response.json().await?;
+        if todo.completed {
+            self.set_completed(&created.id, true).await?;
+        }
+        Ok(created.id)
+    }
+
+    pub async fn update(&self, id: &str, todo: &TodoItem) -> Result<()> {
+        self.client
+            .post(format!("{}/tasks/{}", API, id))
+            .bearer_auth(&self.token)
+            .json(&body(todo))
+            .send()
+            .await?
+            .error_for_status()?;
+        self.set_completed(id, todo.completed).await
+    }
+
+    async fn set_completed(&self, id: &str, completed: bool) -> Result<()> {
+        let action = if completed { "close" } else { "reopen" };
+        self.client
+            .post(format!("{}/tasks/{}/{}", API, id, action))
+            .bearer_auth(&self.token)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let response = self
+            .client
+            .delete(format!("{}/tasks/{}", API, id))
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
+        // Already deleted remotely
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        response.error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_remote() {
+        let page: Page = serde_json::from_str(
+            r#"{"results": [
+                {"id": "6X7", "content": "Pay rent", "labels": ["home"], "priority": 4,
+                 "due": {"date": "2026-10-20", "string": "every month", "is_recurring": true}},
+                {"id": "6X8", "content": "Call", "priority": 1, "due": {"date": "2026-10-20T17:30:00"}},
+                {"id": "6X9", "content": "Stretch", "due": {"date": "2026-10-19", "string": "every weekday", "is_recurring": true}}
+            ], "next_cursor": null}"#,
+        )
+        .unwrap();
+        let tasks: Vec<RemoteTask> = page.results.into_iter().map(Task::into_remote).collect();
+        assert_eq!(tasks[0].priority, Some(Priority::High));
+        assert_eq!(tasks[0].due.as_deref(), Some("2026-10-20"));
+        assert_eq!(tasks[0].tags, ["home"]);
+        assert_eq!(tasks[1].priority, None);
+        assert_eq!(tasks[1].due.as_deref(), Some("2026-10-20 17:30"));
+        assert_eq!(tasks[0].recur.as_deref(), Some("every month"));
+        assert_eq!(tasks[2].recur.as_deref(), Some("every weekday"));
+    }
+}
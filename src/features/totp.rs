@@ -0,0 +1,458 @@
+//! Two-factor codes (TOTP, RFC 6238) for accounts kept in wlaunch itself,
+//! for users who don't keep them in Bitwarden. Secrets live in the keyring
+//! (secret-tool); `totp.json` only lists the accounts.
+
+use super::projects::percent_decode;
+use crate::core::{CommandRunner, Config, Item, ItemType, SystemRunner};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha1::digest::core_api::BlockSizeUser;
+use sha1::{Digest, Sha1};
+use sha2::{Sha256, Sha512};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Algorithm {
+    #[default]
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+fn default_digits() -> u32 {
+    6
+}
+
+fn default_period() -> u64 {
+    30
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Account {
+    /// `Issuer:name` like authenticator apps show it, also the keyring key
+    pub label: String,
+    #[serde(default)]
+    pub algorithm: Algorithm,
+    #[serde(default = "default_digits")]
+    pub digits: u32,
+    #[serde(default = "default_period")]
+    pub period: u64,
+}
+
+impl Account {
+    fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            algorithm: Algorithm::default(),
+            digits: default_digits(),
+            period: default_period(),
+        }
+    }
+
+    /// Whether codes can be computed: 6 to 8 digits, a period of a second or more.
+    fn is_valid(&self) -> bool {
+        (6..=8).contains(&self.digits) && self.period > 0
+    }
+
+    /// The code valid at `time` (seconds since the epoch).
+    fn code(&self, secret: &[u8], time: u64) -> String {
+        totp(secret, self.algorithm, self.digits, time / self.period)
+    }
+}
+
+fn hmac<D: Digest + BlockSizeUser>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let block_size = D::block_size();
+    let mut key = if key.len() > block_size { D::digest(key).to_vec() } else { key.to_vec() };
+    key.resize(block_size, 0);
+    let pad = |byte: u8| key.iter().map(|k| k ^ byte).collect::<Vec<u8>>();
+    let inner = D::new().chain_update(pad(0x36)).chain_update(message).finalize();
+    D::new().chain_update(pad(0x5c)).chain_update(inner).finalize().to_vec()
+}
+
+/// HOTP (RFC 4226) of `counter`, which TOTP counts in periods.
+fn totp(secret: &[u8], algorithm: Algorithm, digits: u32, counter: u64) -> String {
+    let counter = counter.to_be_bytes();
+    let mac = match algorithm {
+        Algorithm::Sha1 => hmac::<Sha1>(secret, &counter),
+        Algorithm::Sha256 => hmac::<Sha256>(secret, &counter),
+        Algorithm::Sha512 => hmac::<Sha512>(secret, &counter),
+    };
+    let offset = (mac[mac.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([mac[offset], mac[offset + 1], mac[offset + 2], mac[offset + 3]]) & 0x7fff_ffff;
+    format!("{:0width$}", binary % 10u32.pow(digits), width = digits as usize)
+}
+
+/// RFC 4648 base32 as sites show secrets, ignoring case, spaces and padding.
+fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    let (mut bits, mut count) = (0u32, 0);
+    for c in text.chars().filter(|c| !c.is_whitespace() && *c != '-' && *c != '=') {
+        let value = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u32 - 'A' as u32,
+            c @ '2'..='7' => c as u32 - '2' as u32 + 26,
+            _ => return None,
+        };
+        bits = (bits << 5) | value;
+        count += 5;
+        if count >= 8 {
+            count -= 8;
+            decoded.push((bits >> count) as u8);
+            bits &= (1 << count) - 1;
+        }
+    }
+    (!decoded.is_empty()).then_some(decoded)
+}
+
+/// `<name> <secret>` (the secret may be grouped with spaces) or an
+/// `otpauth://totp/...` link, as the account and its base32 secret.
+fn parse_new(spec: &str) -> Result<(Account, String), String> {
+    let spec = spec.trim();
+    let (account, secret) = match spec.strip_prefix("otpauth://") {
+        Some(uri) => {
+            let (path, params) = uri.split_once('?').unwrap_or((uri, ""));
+            let Some(label) = path.strip_prefix("totp/") else {
+                return Err("Only time-based (totp) links are supported".to_string());
+            };
+            let mut account = Account::new(percent_decode(label));
+            let mut secret = String::new();
+            for (key, value) in params.split('&').filter_map(|param| param.split_once('=')) {
+                let value = percent_decode(value);
+                match key {
+                    "secret" => secret = value,
+                    "issuer" if !account.label.contains(':') => account.label = format!("{}:{}", value, account.label),
+                    "algorithm" => {
+                        account.algorithm = match value.to_uppercase().as_str() {
+                            "SHA1" => Algorithm::Sha1,
+                            "SHA256" => Algorithm::Sha256,
+                            "SHA512" => Algorithm::Sha512,
+                            _ => return Err(format!("Unsupported algorithm {}", value)),
+                        }
+                    }
+                    "digits" => account.digits = value.parse().map_err(|_| format!("Invalid digits {}", value))?,
+                    "period" => account.period = value.parse().map_err(|_| format!("Invalid period {}", value))?,
+                    _ => {}
+                }
+            }
+            (account, secret)
+        }
+        None => {
+            let (name, secret) = spec.split_once(' ').unwrap_or((spec, ""));
+            (Account::new(name), secret.to_string())
+        }
+    };
+
+    if account.label.is_empty() || secret.trim().is_empty() {
+        return Err("Type add <name> <secret>, or paste an otpauth:// link".to_string());
+    }
+    if !account.is_valid() {
+        return Err("Codes must have 6 to 8 digits and a period of a second or more".to_string());
+    }
+    if base32_decode(&secret).is_none() {
+        return Err("The secret isn't valid base32".to_string());
+    }
+    let secret: String = secret.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    Ok((account, secret.to_uppercase()))
+}
+
+/// `123 456`, `1234 5678`: easier to read off the screen.
+fn group(code: &str) -> String {
+    let (first, second) = code.split_at(code.len() / 2);
+    format!("{} {}", first, second)
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[derive(Clone)]
+pub struct TotpManager {
+    runner: Arc<dyn CommandRunner>,
+    accounts: Arc<Mutex<Vec<Account>>>,
+    /// Decoded secrets by label, `None` when the keyring has none, so it's
+    /// asked once per account rather than on every refresh
+    secrets: Arc<Mutex<HashMap<String, Option<Vec<u8>>>>>,
+}
+
+impl TotpManager {
+    pub fn new() -> Self {
+        let manager = Self::with_runner(SystemRunner::shared());
+        *manager.accounts.lock().unwrap() = Self::load().unwrap_or_default();
+        manager
+    }
+
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Self {
+        Self {
+            runner,
+            accounts: Arc::new(Mutex::new(Vec::new())),
+            secrets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn data_path() -> PathBuf {
+        Config::data_path("totp.json")
+    }
+
+    fn load() -> Result<Vec<Account>> {
+        let path = Self::data_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        Self::parse_accounts(&fs::read_to_string(path)?)
+    }
+
+    /// The accounts in `totp.json`, skipping edited ones codes can't be
+    /// computed for.
+    fn parse_accounts(content: &str) -> Result<Vec<Account>> {
+        let mut accounts: Vec<Account> = serde_json::from_str(content)?;
+        accounts.retain(|account| {
+            let valid = account.is_valid();
+            if !valid {
+                log::warn!(
+                    "Skipping 2FA account {}: {} digits every {}s isn't supported",
+                    account.label,
+                    account.digits,
+                    account.period
+                );
+            }
+            valid
+        });
+        Ok(accounts)
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = Self::data_path();
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, serde_json::to_string_pretty(&*self.accounts.lock().unwrap())?)?;
+        Ok(())
+    }
+
+    fn secret(&self, label: &str) -> Option<Vec<u8>> {
+        if let Some(secret) = self.secrets.lock().unwrap().get(label) {
+            return secret.clone();
+        }
+        let secret = self
+            .runner
+            .run("secret-tool", &["lookup", "application", "wlaunch", "totp", label])
+            .ok()
+            .and_then(|output| base32_decode(String::from_utf8_lossy(&output.stdout).trim()));
+        self.secrets.lock().unwrap().insert(label.to_string(), secret.clone());
+        secret
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        self.items_at(query, now())
+    }
+
+    fn items_at(&self, query: &str, time: u64) -> Vec<Item> {
+        let query = query.trim();
+        if query == "add" || query.starts_with("add ") {
+            let spec = query["add".len()..].trim();
+            return match parse_new(spec) {
+                Ok((account, _)) => vec![Item::new(format!("totp:add:{}", spec), format!("Add {}", account.label), ItemType::TotpAction)
+                    .with_description(format!("{} digits every {}s, the secret is kept in the keyring", account.digits, account.period))
+                    .with_icon("list-add")],
+                Err(error) => vec![Item::new("totp:none", error, ItemType::Command)
+                    .with_description("Secrets are base32, as sites show them next to the QR code")
+                    .with_icon("dialog-information")],
+            };
+        }
+
+        let filter = query.to_lowercase();
+        let accounts = self.accounts.lock().unwrap().clone();
+        let mut items = Vec::new();
+        for account in accounts.iter().filter(|a| a.label.to_lowercase().contains(&filter)) {
+            let description = match self.secret(&account.label) {
+                Some(secret) => {
+                    let left = account.period - time % account.period;
+                    format!("{} | {}s left", group(&account.code(&secret, time)), left)
+                }
+                None => "The secret isn't in the keyring".to_string(),
+            };
+            items.push(
+                Item::new(format!("totp:{}", account.label), &account.label, ItemType::TotpCode)
+                    .with_description(description)
+                    .with_icon("dialog-password"),
+            );
+
+            // Actions for the best match only, like the git mode
+            if items.len() == 1 && !filter.is_empty() {
+                items.push(
+                    Item::new(format!("totp:remove:{}", account.label), "    Remove", ItemType::TotpAction)
+                        .with_description("Forgets the account and deletes its secret from the keyring")
+                        .with_icon("edit-delete"),
+                );
+            }
+        }
+
+        if items.is_empty() {
+            let title = if accounts.is_empty() { "No 2FA accounts yet".to_string() } else { format!("No accounts match \"{}\"", query) };
+            items.push(
+                Item::new("totp:none", title, ItemType::Command)
+                    .with_description("Type add <name> <secret>, or paste an otpauth:// link after add")
+                    .with_icon("dialog-information"),
+            );
+        }
+        items
+    }
+
+    /// The code valid now, for Enter.
+    pub fn code(&self, label: &str) -> Option<String> {
+        let accounts = self.accounts.lock().unwrap().clone();
+        let account = accounts.iter().find(|a| a.label == label)?;
+        Some(account.code(&self.secret(label)?, now()))
+    }
+
+    fn add(&self, spec: &str) -> Result<(), String> {
+        let (account, secret) = parse_new(spec)?;
+        if self.accounts.lock().unwrap().iter().any(|a| a.label == account.label) {
+            return Err(format!("{} is already registered", account.label));
+        }
+
+        let label = format!("--label=wlaunch 2FA: {}", account.label);
+        let child = Command::new("secret-tool")
+            .args(["store", &label, "application", "wlaunch", "totp", &account.label])
+            .stdin(Stdio::piped())
+            .spawn();
+        let status = child.and_then(|mut child| {
+            child.stdin.take().map(|mut stdin| stdin.write_all(secret.as_bytes())).transpose()?;
+            child.wait()
+        });
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(_) => return Err("The keyring didn't store the secret".to_string()),
+            Err(e) => return Err(format!("Failed to run secret-tool: {}", e)),
+        }
+
+        self.secrets.lock().unwrap().remove(&account.label);
+        self.accounts.lock().unwrap().push(account);
+        self.save().map_err(|e| format!("Failed to save the account: {}", e))
+    }
+
+    /// Deletes the secret of `label` from the keyring.
+    fn clear_secret(&self, label: &str) -> Result<(), String> {
+        match self.runner.run("secret-tool", &["clear", "application", "wlaunch", "totp", label]) {
+            Ok(output) if output.status.success() => Ok(()),
+            Ok(output) => Err(format!(
+                "The keyring didn't delete the secret: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
+            Err(e) => Err(format!("Failed to run secret-tool: {}", e)),
+        }
+    }
+
+    fn remove(&self, label: &str) -> Result<(), String> {
+        self.clear_secret(label)?;
+        self.accounts.lock().unwrap().retain(|a| a.label != label);
+        self.secrets.lock().unwrap().remove(label);
+        self.save().map_err(|e| format!("Failed to save the accounts: {}", e))
+    }
+
+    /// Adds or removes an account.
+    pub fn execute_action(&self, action_id: &str) -> Result<(), String> {
+        if let Some(spec) = action_id.strip_prefix("totp:add:") {
+            self.add(spec)
+        } else if let Some(label) = action_id.strip_prefix("totp:remove:") {
+            self.remove(label)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Default for TotpManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    #[test]
+    fn test_rfc6238() {
+        // The RFC's test vectors, truncated to 8 digits
+        let sha1 = Account { digits: 8, ..Account::new("sha1") };
+        assert_eq!(sha1.code(b"12345678901234567890", 59), "94287082");
+        assert_eq!(sha1.code(b"12345678901234567890", 1111111109), "07081804");
+        let sha256 = Account { algorithm: Algorithm::Sha256, ..sha1.clone() };
+        assert_eq!(sha256.code(b"12345678901234567890123456789012", 59), "46119246");
+        let sha512 = Account { algorithm: Algorithm::Sha512, ..sha1 };
+        assert_eq!(sha512.code(b"1234567890123456789012345678901234567890123456789012345678901234", 59), "90693936");
+
+        assert_eq!(base32_decode("gezd gnbv gy3t qojq").as_deref(), Some(&b"1234567890"[..]));
+        assert_eq!(base32_decode("not base32!"), None);
+    }
+
+    #[test]
+    fn test_parse_new() {
+        let (account, secret) = parse_new("GitHub jbsw y3dp ehpk 3pxp").unwrap();
+        assert_eq!(account, Account::new("GitHub"));
+        assert_eq!(secret, "JBSWY3DPEHPK3PXP");
+
+        let (account, _) =
+            parse_new("otpauth://totp/alice%40example.com?secret=JBSWY3DPEHPK3PXP&issuer=Example&digits=8&algorithm=SHA256")
+                .unwrap();
+        assert_eq!(account.label, "Example:alice@example.com");
+        assert_eq!((account.algorithm, account.digits, account.period), (Algorithm::Sha256, 8, 30));
+
+        assert!(parse_new("GitHub").is_err());
+        assert!(parse_new("otpauth://hotp/x?secret=JBSWY3DPEHPK3PXP").is_err());
+    }
+
+    #[test]
+    fn test_items() {
+        let runner = FakeRunner::new()
+            .with("secret-tool lookup application wlaunch totp Example:alice", "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\n")
+            .shared();
+        let manager = TotpManager::with_runner(runner);
+        manager.accounts.lock().unwrap().extend([Account::new("Example:alice"), Account::new("GitLab")]);
+
+        let items = manager.items_at("", 59);
+        assert_eq!(items[0].description.as_deref(), Some("287 082 | 1s left"));
+        assert_eq!(items[1].description.as_deref(), Some("The secret isn't in the keyring"));
+
+        let items = manager.items_at("alice", 45);
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["totp:Example:alice", "totp:remove:Example:alice"]);
+        assert_eq!(items[0].description.as_deref(), Some("287 082 | 15s left"));
+
+        let items = manager.items_at("add GitHub JBSWY3DPEHPK3PXP", 0);
+        assert_eq!(items[0].name, "Add GitHub");
+        assert_eq!(items[0].id, "totp:add:GitHub JBSWY3DPEHPK3PXP");
+
+        // The failed lookup is remembered rather than run on every keystroke
+        assert_eq!(manager.secrets.lock().unwrap().get("GitLab"), Some(&None));
+    }
+
+    #[test]
+    fn test_invalid_accounts() {
+        let accounts = TotpManager::parse_accounts(
+            r#"[{"label": "GitHub"}, {"label": "Broken", "period": 0}, {"label": "Long", "digits": 12}]"#,
+        )
+        .unwrap();
+        assert_eq!(accounts, [Account::new("GitHub")]);
+    }
+
+    #[test]
+    fn test_clear_secret() {
+        let runner = FakeRunner::new()
+            .with("secret-tool clear application wlaunch totp GitHub", "")
+            .failing("secret-tool clear application wlaunch totp GitLab", 1, "Cannot unlock the keyring")
+            .shared();
+        let manager = TotpManager::with_runner(runner);
+        assert_eq!(manager.clear_secret("GitHub"), Ok(()));
+        assert_eq!(
+            manager.clear_secret("GitLab"),
+            Err("The keyring didn't delete the secret: Cannot unlock the keyring".to_string())
+        );
+    }
+}
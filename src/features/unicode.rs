@@ -0,0 +1,221 @@
+//! Unicode characters by name ("em dash", "right arrow", "greek lambda"),
+//! or the details of one character ("—", "U+2014").
+
+use crate::core::{Item, ItemType};
+use std::sync::OnceLock;
+
+const MAX_RESULTS: usize = 100;
+
+/// Named HTML entities for the characters people look them up for.
+const HTML_ENTITIES: &[(char, &str)] = &[
+    ('&', "amp"), ('<', "lt"), ('>', "gt"), ('"', "quot"), ('\'', "apos"),
+    ('\u{A0}', "nbsp"), ('¡', "iexcl"), ('¢', "cent"), ('£', "pound"), ('¥', "yen"),
+    ('§', "sect"), ('©', "copy"), ('«', "laquo"), ('®', "reg"), ('°', "deg"),
+    ('±', "plusmn"), ('²', "sup2"), ('³', "sup3"), ('µ', "micro"), ('¶', "para"),
+    ('·', "middot"), ('»', "raquo"), ('¼', "frac14"), ('½', "frac12"), ('¾', "frac34"),
+    ('¿', "iquest"), ('×', "times"), ('÷', "divide"), ('–', "ndash"), ('—', "mdash"),
+    ('‘', "lsquo"), ('’', "rsquo"), ('“', "ldquo"), ('”', "rdquo"), ('†', "dagger"),
+    ('‡', "Dagger"), ('•', "bull"), ('…', "hellip"), ('‰', "permil"), ('′', "prime"),
+    ('″', "Prime"), ('€', "euro"), ('™', "trade"), ('←', "larr"), ('↑', "uarr"),
+    ('→', "rarr"), ('↓', "darr"), ('↔', "harr"), ('⇐', "lArr"), ('⇒', "rArr"),
+    ('⇔', "hArr"), ('∀', "forall"), ('∂', "part"), ('∃', "exist"), ('∅', "empty"),
+    ('∇', "nabla"), ('∈', "isin"), ('∏', "prod"), ('∑', "sum"), ('−', "minus"),
+    ('√', "radic"), ('∞', "infin"), ('≈', "asymp"), ('≠', "ne"), ('≡', "equiv"),
+    ('≤', "le"), ('≥', "ge"), ('α', "alpha"), ('β', "beta"), ('γ', "gamma"),
+    ('δ', "delta"), ('ε', "epsilon"), ('θ', "theta"), ('λ', "lambda"), ('μ', "mu"),
+    ('π', "pi"), ('σ', "sigma"), ('τ', "tau"), ('φ', "phi"), ('ω', "omega"),
+    ('Δ', "Delta"), ('Σ', "Sigma"), ('Ω', "Omega"),
+];
+
+/// Everyday spellings of words Unicode names spell differently.
+const ALIASES: &[(&str, &str)] = &[("lambda", "lamda"), ("lamba", "lamda")];
+
+/// Blocks of algorithmically named characters ("CJK UNIFIED IDEOGRAPH-4E00")
+/// that would only bury real matches.
+const SKIPPED: &[(u32, u32)] = &[
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xAC00, 0xD7A3),
+    (0xE000, 0xF8FF),
+    (0x17000, 0x18CFF),
+    (0x1B170, 0x1B2FF),
+    (0x20000, 0x3FFFF),
+    (0xF0000, 0x10FFFF),
+];
+
+/// Lowercase names of all searchable characters, built on first use.
+fn names() -> &'static [(char, String)] {
+    static NAMES: OnceLock<Vec<(char, String)>> = OnceLock::new();
+    NAMES.get_or_init(|| {
+        (0..=0x10FFFF_u32)
+            .filter(|cp| !SKIPPED.iter().any(|(start, end)| (start..=end).contains(&cp)))
+            .filter_map(char::from_u32)
+            .filter_map(|c| Some((c, unicode_names2::name(c)?.to_string().to_lowercase())))
+            .collect()
+    })
+}
+
+pub struct UnicodeSearch;
+
+impl UnicodeSearch {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim();
+        if query.is_empty() {
+            return vec![Item::new("char:hint", "Type a character name", ItemType::Command)
+                .with_description("e.g. em dash, right arrow, greek lambda, or U+2014")
+                .with_icon("accessories-character-map")];
+        }
+
+        if let Some(c) = Self::parse_codepoint(query) {
+            return Self::details(c);
+        }
+        let mut chars = query.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if !c.is_ascii_alphanumeric() {
+                return Self::details(c);
+            }
+        }
+
+        Self::search(query)
+            .into_iter()
+            .take(MAX_RESULTS)
+            .map(|(c, name)| Self::char_item(c, name))
+            .collect()
+    }
+
+    /// Characters whose name has a word starting with each query word,
+    /// shortest names first.
+    fn search(query: &str) -> Vec<(char, &'static str)> {
+        let words: Vec<String> = query
+            .to_lowercase()
+            .split_whitespace()
+            .map(|word| {
+                ALIASES
+                    .iter()
+                    .find(|(alias, _)| *alias == word)
+                    .map_or(word.to_string(), |(_, name)| name.to_string())
+            })
+            .collect();
+
+        let mut matches: Vec<(char, &'static str)> = names()
+            .iter()
+            .filter(|(c, name)| {
+                let entity = Self::entity_name(*c);
+                words.iter().all(|word| {
+                    name.split([' ', '-']).any(|w| w.starts_with(word.as_str()))
+                        || entity.is_some_and(|e| e.eq_ignore_ascii_case(word))
+                })
+            })
+            .map(|(c, name)| (*c, name.as_str()))
+            .collect();
+        matches.sort_by_key(|(_, name)| name.len());
+        matches
+    }
+
+    fn char_item(c: char, name: &str) -> Item {
+        let mut item = Item::new(
+            format!("char:{:04X}", c as u32),
+            format!("{}   {}", Self::display(c), name),
+            ItemType::UnicodeChar,
+        )
+        .with_description(format!("{} | {} | Press Enter to copy", Self::codepoint(c), Self::entity(c)))
+        .with_icon("accessories-character-map");
+        item.metadata.content = Some(c.to_string());
+        item
+    }
+
+    /// The character, its codepoint, HTML entity and UTF-8 bytes, each
+    /// copyable on its own.
+    fn details(c: char) -> Vec<Item> {
+        let name = unicode_names2::name(c).map_or("unnamed character".to_string(), |n| n.to_string().to_lowercase());
+        let mut buf = [0; 4];
+        let utf8 = c
+            .encode_utf8(&mut buf)
+            .bytes()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut outputs = vec![
+            ("char", Self::display(c), c.to_string(), name.clone()),
+            ("codepoint", Self::codepoint(c), Self::codepoint(c), "Codepoint".to_string()),
+            ("entity", Self::entity(c), Self::entity(c), "HTML entity".to_string()),
+        ];
+        if Self::entity_name(c).is_some() {
+            let numeric = format!("&#x{:X};", c as u32);
+            outputs.push(("numeric", numeric.clone(), numeric, "Numeric HTML entity".to_string()));
+        }
+        outputs.push(("utf8", utf8.clone(), utf8, "UTF-8 bytes".to_string()));
+
+        outputs
+            .into_iter()
+            .map(|(kind, title, value, description)| {
+                let mut item = Item::new(format!("char:{}:{:04X}", kind, c as u32), title, ItemType::UnicodeChar)
+                    .with_description(format!("{} | Press Enter to copy", description))
+                    .with_icon("accessories-character-map");
+                item.metadata.content = Some(value);
+                item
+            })
+            .collect()
+    }
+
+    /// "U+2014", "u+1f600", "0x2014"
+    fn parse_codepoint(query: &str) -> Option<char> {
+        let lower = query.to_lowercase();
+        let hex = lower.strip_prefix("u+").or_else(|| lower.strip_prefix("0x"))?;
+        char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+    }
+
+    fn codepoint(c: char) -> String {
+        format!("U+{:04X}", c as u32)
+    }
+
+    fn entity_name(c: char) -> Option<&'static str> {
+        HTML_ENTITIES.iter().find(|(e, _)| *e == c).map(|(_, name)| *name)
+    }
+
+    fn entity(c: char) -> String {
+        match Self::entity_name(c) {
+            Some(name) => format!("&{};", name),
+            None => format!("&#x{:X};", c as u32),
+        }
+    }
+
+    /// Combining marks are shown on a dotted circle, as in charts.
+    fn display(c: char) -> String {
+        let name = unicode_names2::name(c).map(|n| n.to_string()).unwrap_or_default();
+        if name.starts_with("COMBINING ") {
+            format!("\u{25CC}{}", c)
+        } else {
+            c.to_string()
+        }
+    }
+}
+
+impl Default for UnicodeSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search() {
+        let first = |query: &str| UnicodeSearch::search(query).first().map(|(c, _)| *c);
+        assert_eq!(first("em dash"), Some('—'));
+        assert_eq!(first("greek lambda"), Some('λ'));
+        assert!(UnicodeSearch::search("right arrow").iter().any(|(c, _)| *c == '→'));
+        assert_eq!(UnicodeSearch::parse_codepoint("U+2014"), Some('—'));
+        assert_eq!(UnicodeSearch::entity('—'), "&mdash;");
+        assert_eq!(UnicodeSearch::entity('→'), "&rarr;");
+        assert_eq!(UnicodeSearch::entity('λ'), "&lambda;");
+        assert_eq!(UnicodeSearch::entity('☃'), "&#x2603;");
+    }
+}
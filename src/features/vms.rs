@@ -0,0 +1,349 @@
+//! Virtual machines of libvirt (`virsh`) and VirtualBox (`VBoxManage`):
+//! start, shut down or force off, and open the graphical console.
+
+use crate::core::config::{ModeConfig, SortOrder};
+use crate::core::{CommandRunner, Config, Item, ItemType, SystemRunner};
+use std::io::ErrorKind;
+use std::process::Command;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Backend {
+    Libvirt,
+    VirtualBox,
+}
+
+impl Backend {
+    fn key(self) -> &'static str {
+        match self {
+            Backend::Libvirt => "libvirt",
+            Backend::VirtualBox => "vbox",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Backend::Libvirt => "libvirt",
+            Backend::VirtualBox => "VirtualBox",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "libvirt" => Some(Backend::Libvirt),
+            "vbox" => Some(Backend::VirtualBox),
+            _ => None,
+        }
+    }
+}
+
+struct Vm {
+    backend: Backend,
+    /// Domain name for libvirt, UUID for VirtualBox
+    id: String,
+    name: String,
+    state: String,
+}
+
+impl Vm {
+    fn running(&self) -> bool {
+        self.state == "running"
+    }
+}
+
+#[derive(Clone)]
+pub struct VmManager {
+    options: ModeConfig,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl VmManager {
+    pub fn new(config: &Config) -> Self {
+        Self::with_runner(config, SystemRunner::shared())
+    }
+
+    pub fn with_runner(config: &Config, runner: Arc<dyn CommandRunner>) -> Self {
+        Self {
+            options: config.mode("vms"),
+            runner,
+        }
+    }
+
+    /// Stdout of a successful command; `Ok(None)` when the tool isn't
+    /// installed, so the other backend still shows.
+    fn run(&self, program: &str, args: &[&str]) -> Result<Option<String>, Vec<Item>> {
+        match self.runner.run(program, args) {
+            Ok(output) if output.status.success() => Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned())),
+            Ok(output) => Err(Item::command_error_items("vms", format!("Can't list VMs ({})", program), &output)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Item::error_items("vms", format!("Failed to run {}", program), e.to_string())),
+        }
+    }
+
+    /// The table of `virsh list --all`; columns are cut at the header's
+    /// positions, as names and states ("shut off") may contain spaces.
+    fn parse_virsh(stdout: &str) -> Vec<Vm> {
+        let mut lines = stdout.lines();
+        let Some(header) = lines.next() else {
+            return Vec::new();
+        };
+        let (Some(name_at), Some(state_at)) = (header.find("Name"), header.find("State")) else {
+            return Vec::new();
+        };
+
+        lines
+            .filter(|line| !line.starts_with("---") && !line.trim().is_empty())
+            .filter_map(|line| {
+                let name = line.get(name_at..state_at.min(line.len()))?.trim().to_string();
+                let state = line.get(state_at..).unwrap_or("").trim().to_string();
+                (!name.is_empty()).then(|| Vm {
+                    backend: Backend::Libvirt,
+                    id: name.clone(),
+                    name,
+                    state,
+                })
+            })
+            .collect()
+    }
+
+    /// `"Name" {uuid}` lines of `VBoxManage list vms`, with the UUIDs of
+    /// `list runningvms` marked as running.
+    fn parse_vbox(vms: &str, running: &str) -> Vec<Vm> {
+        vms.lines()
+            .filter_map(|line| {
+                let (name, uuid) = line.trim().rsplit_once(" {")?;
+                let uuid = uuid.trim_end_matches('}').to_string();
+                let state = if running.contains(&uuid) { "running" } else { "powered off" };
+                Some(Vm {
+                    backend: Backend::VirtualBox,
+                    name: name.trim_matches('"').to_string(),
+                    id: uuid,
+                    state: state.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let filter = query.trim().to_lowercase();
+
+        let mut vms = Vec::new();
+        let mut found_tool = false;
+        match self.run("virsh", &["list", "--all"]) {
+            Ok(Some(stdout)) => {
+                found_tool = true;
+                vms.extend(Self::parse_virsh(&stdout));
+            }
+            Ok(None) => {}
+            Err(items) => return items,
+        }
+        match self.run("VBoxManage", &["list", "vms"]) {
+            Ok(Some(stdout)) => {
+                found_tool = true;
+                let running = self.run("VBoxManage", &["list", "runningvms"]).ok().flatten().unwrap_or_default();
+                vms.extend(Self::parse_vbox(&stdout, &running));
+            }
+            Ok(None) => {}
+            Err(items) => return items,
+        }
+        if !found_tool {
+            return Item::error_items(
+                "vms",
+                "No virtualization tools found",
+                "Install libvirt (virsh) or VirtualBox (VBoxManage) to manage virtual machines",
+            );
+        }
+
+        vms.retain(|vm| filter.is_empty() || vm.name.to_lowercase().contains(&filter));
+        match self.options.sort {
+            Some(SortOrder::Name) => vms.sort_by_key(|vm| vm.name.to_lowercase()),
+            Some(SortOrder::Status) => vms.sort_by_key(|vm| !vm.running()),
+            _ => {}
+        }
+        if let Some(max) = self.options.max_results {
+            vms.truncate(max);
+        }
+
+        let mut items = Vec::new();
+        for (i, vm) in vms.iter().enumerate() {
+            let id = format!("{}:{}", vm.backend.key(), vm.id);
+            let mut description = format!("{} | {}", vm.backend.label(), vm.state);
+            match (vm.running(), vm.backend) {
+                (false, _) => description.push_str(" | Enter starts it"),
+                (true, Backend::Libvirt) => description.push_str(" | Enter opens the console"),
+                (true, Backend::VirtualBox) => {}
+            }
+            let mut item = Item::new(format!("vm:{}", id), &vm.name, ItemType::VirtualMachine)
+                .with_description(description)
+                .with_icon(if vm.running() { "computer" } else { "computer-symbolic" });
+            item.metadata.vm_state = Some(vm.state.clone());
+            items.push(item);
+
+            // Actions for the best match only, like the git mode
+            if i == 0 && !filter.is_empty() {
+                let actions: &[(&str, &str, &str)] = if vm.running() {
+                    &[
+                        ("console", "    Open console", "video-display"),
+                        ("shutdown", "    Shut down", "system-shutdown"),
+                        ("off", "    Force off", "process-stop"),
+                    ]
+                } else {
+                    &[("start", "    Start", "media-playback-start")]
+                };
+                for (action, name, icon) in actions {
+                    // VirtualBox VMs show their own window
+                    if *action == "console" && vm.backend == Backend::VirtualBox {
+                        continue;
+                    }
+                    items.push(
+                        Item::new(format!("vm:{}:{}", action, id), *name, ItemType::VmAction)
+                            .with_description(vm.name.clone())
+                            .with_icon(*icon),
+                    );
+                }
+            }
+        }
+        items
+    }
+
+    /// Runs a slow command (booting, ACPI shutdown) off the UI thread.
+    fn run_detached(&self, program: &'static str, args: Vec<String>) {
+        let runner = self.runner.clone();
+        std::thread::spawn(move || {
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            match runner.run(program, &args) {
+                Ok(output) if !output.status.success() => {
+                    log::error!("{} {} failed: {}", program, args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+                }
+                Err(e) => log::error!("Failed to run {}: {}", program, e),
+                Ok(_) => {}
+            }
+        });
+    }
+
+    fn start(&self, backend: Backend, id: &str) {
+        match backend {
+            Backend::Libvirt => self.run_detached("virsh", vec!["start".to_string(), id.to_string()]),
+            Backend::VirtualBox => {
+                self.run_detached("VBoxManage", vec!["startvm".to_string(), id.to_string(), "--type".to_string(), "gui".to_string()])
+            }
+        }
+    }
+
+    fn console(&self, id: &str) -> bool {
+        // --wait keeps the viewer open while a just started VM boots
+        match Command::new("virt-viewer").args(["--wait", id]).spawn() {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("Failed to open the console of {}: {}", id, e);
+                false
+            }
+        }
+    }
+
+    /// Enter on a VM: starts it, or opens the console of a running libvirt
+    /// VM. Returns whether a console was opened, so the launcher can close.
+    pub fn activate(&self, item: &Item) -> bool {
+        let Some((backend, id)) = item
+            .id
+            .strip_prefix("vm:")
+            .and_then(|rest| rest.split_once(':'))
+            .and_then(|(backend, id)| Some((Backend::from_key(backend)?, id)))
+        else {
+            return false;
+        };
+        if item.metadata.vm_state.as_deref() != Some("running") {
+            self.start(backend, id);
+            false
+        } else {
+            backend == Backend::Libvirt && self.console(id)
+        }
+    }
+
+    /// Runs a start/shutdown/force-off/console action; returns whether a
+    /// console was opened.
+    pub fn execute_action(&self, action_id: &str) -> bool {
+        let mut parts = action_id.splitn(4, ':');
+        let (Some("vm"), Some(action), Some(backend), Some(id)) = (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return false;
+        };
+        let Some(backend) = Backend::from_key(backend) else {
+            return false;
+        };
+
+        let args: Vec<String> = match (action, backend) {
+            ("start", _) => {
+                self.start(backend, id);
+                return false;
+            }
+            ("console", Backend::Libvirt) => return self.console(id),
+            ("shutdown", Backend::Libvirt) => vec!["shutdown".into(), id.into()],
+            ("off", Backend::Libvirt) => vec!["destroy".into(), id.into()],
+            ("shutdown", Backend::VirtualBox) => vec!["controlvm".into(), id.into(), "acpipowerbutton".into()],
+            ("off", Backend::VirtualBox) => vec!["controlvm".into(), id.into(), "poweroff".into()],
+            _ => return false,
+        };
+        let program = match backend {
+            Backend::Libvirt => "virsh",
+            Backend::VirtualBox => "VBoxManage",
+        };
+        self.run_detached(program, args);
+        false
+    }
+}
+
+impl Default for VmManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    fn manager() -> VmManager {
+        let runner = FakeRunner::new()
+            .with("virsh list --all", include_str!("../../tests/fixtures/virsh_list.txt"))
+            .with("VBoxManage list vms", include_str!("../../tests/fixtures/vboxmanage_list_vms.txt"))
+            .with("VBoxManage list runningvms", "\"Ubuntu Dev\" {7b1c2d3e-4f50-4a6b-8c9d-0e1f2a3b4c5d}\n")
+            .shared();
+        VmManager::with_runner(&Config::default(), runner)
+    }
+
+    #[test]
+    fn test_vms() {
+        let items = manager().get_items("");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["win11", "arch linux", "Ubuntu Dev", "Windows XP"]);
+        assert_eq!(items[0].description.as_deref(), Some("libvirt | running | Enter opens the console"));
+        assert_eq!(items[1].id, "vm:libvirt:arch linux");
+        assert_eq!(items[1].metadata.vm_state.as_deref(), Some("shut off"));
+        assert_eq!(items[2].id, "vm:vbox:7b1c2d3e-4f50-4a6b-8c9d-0e1f2a3b4c5d");
+        assert_eq!(items[3].metadata.vm_state.as_deref(), Some("powered off"));
+
+        let items = manager().get_items("win11");
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["vm:libvirt:win11", "vm:console:libvirt:win11", "vm:shutdown:libvirt:win11", "vm:off:libvirt:win11"]);
+
+        let items = manager().get_items("ubuntu");
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "vm:vbox:7b1c2d3e-4f50-4a6b-8c9d-0e1f2a3b4c5d",
+                "vm:shutdown:vbox:7b1c2d3e-4f50-4a6b-8c9d-0e1f2a3b4c5d",
+                "vm:off:vbox:7b1c2d3e-4f50-4a6b-8c9d-0e1f2a3b4c5d",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_tools() {
+        let items = VmManager::with_runner(&Config::default(), FakeRunner::new().shared()).get_items("");
+        assert_eq!(items[0].item_type, ItemType::Error);
+        assert_eq!(items[0].name, "No virtualization tools found");
+    }
+}
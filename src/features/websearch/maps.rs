@@ -0,0 +1,110 @@
+//! `map <place>` and `route <destination>`: places and directions opened in
+//! the configured maps provider.
+
+use super::urlencoding;
+use crate::core::config::{MapsConfig, MapsProvider};
+use crate::core::{Item, ItemType};
+
+pub struct Maps {
+    config: MapsConfig,
+}
+
+impl Maps {
+    pub fn new(config: &MapsConfig) -> Self {
+        Self { config: config.clone() }
+    }
+
+    fn search_url(&self, place: &str) -> String {
+        let place = urlencoding::encode(place);
+        match self.config.provider() {
+            MapsProvider::Google => format!("https://www.google.com/maps/search/?api=1&query={}", place),
+            MapsProvider::Openstreetmap => format!("https://www.openstreetmap.org/search?query={}", place),
+        }
+    }
+
+    fn route_url(&self, origin: &str, destination: &str) -> String {
+        let (origin, destination) = (urlencoding::encode(origin), urlencoding::encode(destination));
+        match self.config.provider() {
+            MapsProvider::Google => format!(
+                "https://www.google.com/maps/dir/?api=1&origin={}&destination={}&travelmode={}",
+                origin,
+                destination,
+                self.config.travel_mode()
+            ),
+            MapsProvider::Openstreetmap => {
+                format!("https://www.openstreetmap.org/directions?from={}&to={}", origin, destination)
+            }
+        }
+    }
+
+    /// The place on the map, and directions to it from the configured
+    /// origin; directions first for a `route <destination>` query.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim();
+        let (route_first, place) = match query.strip_prefix("route ") {
+            Some(destination) => (true, destination.trim()),
+            None => (false, query),
+        };
+        if place.is_empty() {
+            return Vec::new();
+        }
+
+        let mut search = Item::new(format!("maps:search:{}", place), format!("Show on map: {}", place), ItemType::WebSearch)
+            .with_icon("find-location");
+        let url = self.search_url(place);
+        search = search.with_description(format!("Open {} in browser", url));
+        search.metadata.url = Some(url);
+
+        let route = match self.config.origin.as_deref() {
+            Some(origin) => {
+                let url = self.route_url(origin, place);
+                let mut route = Item::new(format!("maps:route:{}", place), format!("Route to {}", place), ItemType::WebSearch)
+                    .with_description(format!("From {} | Open directions in browser", origin))
+                    .with_icon("mark-location");
+                route.metadata.url = Some(url);
+                route
+            }
+            None => Item::new("maps:no-origin", "Set maps.origin for directions", ItemType::Command)
+                .with_description("e.g. \"maps\": { \"origin\": \"Alexanderplatz, Berlin\" }")
+                .with_icon("dialog-information"),
+        };
+
+        let mut items = if route_first { vec![route, search] } else { vec![search, route] };
+        for item in &mut items {
+            item.metadata.search_engine = Some("maps".to_string());
+            item.metadata.query = Some(place.to_string());
+        }
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_items() {
+        let config = MapsConfig {
+            origin: Some("Home Street 1".to_string()),
+            ..MapsConfig::default()
+        };
+        let items = Maps::new(&config).get_items("route central station");
+        assert_eq!(items[0].name, "Route to central station");
+        assert_eq!(
+            items[0].metadata.url.as_deref(),
+            Some("https://www.google.com/maps/dir/?api=1&origin=Home+Street+1&destination=central+station&travelmode=transit")
+        );
+        assert_eq!(items[1].name, "Show on map: central station");
+
+        let config = MapsConfig {
+            provider: Some(MapsProvider::Openstreetmap),
+            ..MapsConfig::default()
+        };
+        let items = Maps::new(&config).get_items("Brandenburger Tor");
+        assert_eq!(
+            items[0].metadata.url.as_deref(),
+            Some("https://www.openstreetmap.org/search?query=Brandenburger+Tor")
+        );
+        assert_eq!(items[1].id, "maps:no-origin");
+    }
+}
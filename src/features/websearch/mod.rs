@@ -0,0 +1,345 @@
+pub mod maps;
+pub mod wikipedia;
+
+use crate::core::config::WebSearchConfig;
+use crate::core::{Config, Item, ItemType};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Built-in `!bangs` and the engines they search
+const BANGS: &[(&str, &str)] = &[
+    ("g", "google"),
+    ("gh", "github"),
+    ("yt", "youtube"),
+    ("ddg", "duckduckgo"),
+    ("w", "wikipedia"),
+    ("wiki", "wikipedia"),
+    ("so", "stackoverflow"),
+    ("r", "reddit"),
+    ("a", "amazon"),
+    ("npm", "npm"),
+    ("crates", "crates"),
+    ("pypi", "pypi"),
+];
+
+pub struct WebSearchManager {
+    config: WebSearchConfig,
+    maps: maps::Maps,
+    /// Command of the desktop's default browser, for private windows when
+    /// no browser is configured
+    default_browser: OnceLock<Option<String>>,
+}
+
+impl WebSearchManager {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            config: config.web_search.clone(),
+            maps: maps::Maps::new(&config.maps),
+            default_browser: OnceLock::new(),
+        }
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let mut items = Vec::new();
+
+        if query.is_empty() {
+            // Show available search engines
+            items.push(
+                Item::new("websearch:google", "Google Search", ItemType::WebSearch)
+                    .with_description("Search with Google (prefix: g)")
+                    .with_icon("web-browser"),
+            );
+            items.push(
+                Item::new("websearch:github", "GitHub Search", ItemType::WebSearch)
+                    .with_description("Search GitHub (prefix: gh)")
+                    .with_icon("web-browser"),
+            );
+            items.push(
+                Item::new("websearch:youtube", "YouTube Search", ItemType::WebSearch)
+                    .with_description("Search YouTube (prefix: yt)")
+                    .with_icon("web-browser"),
+            );
+            items.push(
+                Item::new("websearch:duckduckgo", "DuckDuckGo Search", ItemType::WebSearch)
+                    .with_description("Search with DuckDuckGo (prefix: ddg)")
+                    .with_icon("web-browser"),
+            );
+            items.push(
+                Item::new("websearch:wikipedia", "Wikipedia Search", ItemType::WebSearch)
+                    .with_description("Search Wikipedia (prefix: wiki)")
+                    .with_icon("web-browser"),
+            );
+            return items;
+        }
+
+        // Parse search engine and query
+        let (engine, search_query) = self.parse_bang(query).unwrap_or_else(|| self.parse_query(query));
+
+        if !search_query.is_empty() {
+            let (name, url) = self.get_search_url(&engine, &search_query);
+
+            let mut item = Item::new(
+                format!("websearch:{}:{}", engine, search_query),
+                format!("Search {}: {}", name, search_query),
+                ItemType::WebSearch,
+            )
+            .with_description(format!("Open {} in browser", url))
+            .with_icon("web-browser");
+
+            item.metadata.search_engine = Some(engine.clone());
+            item.metadata.query = Some(search_query.clone());
+            item.metadata.url = Some(url.clone());
+
+            if self.browser_command(Some(&engine), true).is_some() {
+                let mut private = item.clone();
+                private.id = format!("websearch:private:{}:{}", engine, search_query);
+                private.name = "    Open in private window".to_string();
+                private.icon = Some("security-high".to_string());
+                items.push(item);
+                items.push(private);
+            } else {
+                items.push(item);
+            }
+        }
+
+        items
+    }
+
+    /// Places and directions for the `map` mode.
+    pub fn map_items(&self, query: &str) -> Vec<Item> {
+        self.maps.get_items(query)
+    }
+
+    /// Opens `url` in the browser configured for `engine`, or the default
+    /// one.
+    pub fn open(&self, url: &str, engine: Option<&str>, private: bool) {
+        let result = match self.browser_command(engine, private) {
+            // The URL is passed as $1 so it isn't parsed by the shell
+            Some(command) => Command::new("sh")
+                .arg("-c")
+                .arg(format!("{} \"$1\"", command))
+                .arg("sh")
+                .arg(url)
+                .spawn(),
+            None => Command::new("xdg-open").arg(url).spawn(),
+        };
+        if let Err(e) = result {
+            log::error!("Failed to open {}: {}", url, e);
+        }
+    }
+
+    /// Shell command opening a URL appended to it, None for `xdg-open`. A
+    /// private window needs a browser whose private flag is known.
+    fn browser_command(&self, engine: Option<&str>, private: bool) -> Option<String> {
+        let configured = engine
+            .and_then(|engine| self.config.browsers.get(engine))
+            .or(self.config.browser.as_ref())
+            .cloned();
+        if !private {
+            return configured;
+        }
+        let command = configured.or_else(|| self.default_browser().clone())?;
+        let flag = Self::private_flag(&command).map(str::to_string).or_else(|| self.config.private_flag.clone())?;
+        Some(format!("{} {}", command, flag))
+    }
+
+    fn private_flag(command: &str) -> Option<&'static str> {
+        let command = command.to_lowercase();
+        if ["firefox", "librewolf", "waterfox", "zen"].iter().any(|b| command.contains(b)) {
+            Some("--private-window")
+        } else if command.contains("edge") {
+            Some("--inprivate")
+        } else if ["chrom", "brave", "vivaldi"].iter().any(|b| command.contains(b)) {
+            Some("--incognito")
+        } else {
+            None
+        }
+    }
+
+    /// `firefox` for `firefox.desktop`, as `xdg-settings` reports it.
+    fn default_browser(&self) -> &Option<String> {
+        self.default_browser.get_or_init(|| {
+            let output = Command::new("xdg-settings").args(["get", "default-web-browser"]).output().ok()?;
+            let desktop_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            let command = desktop_id.strip_suffix(".desktop")?;
+            (!command.is_empty()).then(|| command.to_string())
+        })
+    }
+
+    /// Whether `query` has a word like `!gh`, which makes it a web search.
+    pub fn has_bang(query: &str) -> bool {
+        query.split_whitespace().any(|word| Self::bang(word).is_some())
+    }
+
+    fn bang(word: &str) -> Option<&str> {
+        let bang = word.strip_prefix('!')?;
+        (!bang.is_empty() && bang.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')).then_some(bang)
+    }
+
+    /// The engine of the first known bang anywhere in `query`, and the query
+    /// without it. Bangs from the config win over the built-in ones.
+    fn parse_bang(&self, query: &str) -> Option<(String, String)> {
+        let words: Vec<&str> = query.split_whitespace().collect();
+        let (index, engine) = words.iter().enumerate().find_map(|(i, word)| {
+            let bang = Self::bang(word)?.to_lowercase();
+            if self.config.bangs.contains_key(&bang) {
+                return Some((i, bang));
+            }
+            let (_, engine) = BANGS.iter().find(|(b, _)| *b == bang)?;
+            Some((i, engine.to_string()))
+        })?;
+        let rest: Vec<&str> = words.iter().enumerate().filter(|(i, _)| *i != index).map(|(_, w)| *w).collect();
+        Some((engine, rest.join(" ")))
+    }
+
+    fn parse_query(&self, query: &str) -> (String, String) {
+        let parts: Vec<&str> = query.splitn(2, ' ').collect();
+        let prefix = parts.first().unwrap_or(&"").to_lowercase();
+        let remainder = parts.get(1).unwrap_or(&"").to_string();
+
+        match prefix.as_str() {
+            "google" => ("google".to_string(), remainder),
+            "github" => ("github".to_string(), remainder),
+            "youtube" => ("youtube".to_string(), remainder),
+            "ddg" | "duckduckgo" => ("duckduckgo".to_string(), remainder),
+            "wiki" | "wikipedia" => ("wikipedia".to_string(), remainder),
+            "stackoverflow" | "so" => ("stackoverflow".to_string(), remainder),
+            "reddit" => ("reddit".to_string(), remainder),
+            "amazon" => ("amazon".to_string(), remainder),
+            "npm" => ("npm".to_string(), remainder),
+            "crates" | "cratesio" => ("crates".to_string(), remainder),
+            "pypi" => ("pypi".to_string(), remainder),
+            _ => ("google".to_string(), query.to_string()),
+        }
+    }
+
+    fn get_search_url(&self, engine: &str, query: &str) -> (String, String) {
+        let encoded_query = urlencoding::encode(query);
+
+        if let Some(template) = self.config.bangs.get(engine) {
+            return (format!("!{}", engine), template.replace("{query}", &encoded_query));
+        }
+
+        match engine {
+            "google" => (
+                "Google".to_string(),
+                format!("https://www.google.com/search?q={}", encoded_query),
+            ),
+            "github" => (
+                "GitHub".to_string(),
+                format!("https://github.com/search?q={}", encoded_query),
+            ),
+            "youtube" => (
+                "YouTube".to_string(),
+                format!("https://www.youtube.com/results?search_query={}", encoded_query),
+            ),
+            "duckduckgo" => (
+                "DuckDuckGo".to_string(),
+                format!("https://duckduckgo.com/?q={}", encoded_query),
+            ),
+            "wikipedia" => (
+                "Wikipedia".to_string(),
+                format!("https://en.wikipedia.org/wiki/Special:Search?search={}", encoded_query),
+            ),
+            "stackoverflow" => (
+                "Stack Overflow".to_string(),
+                format!("https://stackoverflow.com/search?q={}", encoded_query),
+            ),
+            "reddit" => (
+                "Reddit".to_string(),
+                format!("https://www.reddit.com/search/?q={}", encoded_query),
+            ),
+            "amazon" => (
+                "Amazon".to_string(),
+                format!("https://www.amazon.com/s?k={}", encoded_query),
+            ),
+            "npm" => (
+                "npm".to_string(),
+                format!("https://www.npmjs.com/search?q={}", encoded_query),
+            ),
+            "crates" => (
+                "crates.io".to_string(),
+                format!("https://crates.io/search?q={}", encoded_query),
+            ),
+            "pypi" => (
+                "PyPI".to_string(),
+                format!("https://pypi.org/search/?q={}", encoded_query),
+            ),
+            _ => (
+                "Google".to_string(),
+                format!("https://www.google.com/search?q={}", encoded_query),
+            ),
+        }
+    }
+}
+
+impl Default for WebSearchManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+// Simple URL encoding
+mod urlencoding {
+    pub fn encode(s: &str) -> String {
+        let mut result = String::new();
+        for c in s.chars() {
+            match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' | '~' => {
+                    result.push(c);
+                }
+                ' ' => result.push('+'),
+                _ => {
+                    for byte in c.to_string().as_bytes() {
+                        result.push_str(&format!("%{:02X}", byte));
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browser_command() {
+        let mut config = Config::default();
+        config.web_search.browser = Some("firefox -P default".to_string());
+        let chrome = "google-chrome --profile-directory='Profile 1'".to_string();
+        config.web_search.browsers.insert("github".to_string(), chrome);
+        let manager = WebSearchManager::new(&config);
+
+        assert_eq!(manager.browser_command(Some("google"), false).as_deref(), Some("firefox -P default"));
+        assert_eq!(
+            manager.browser_command(Some("github"), true).as_deref(),
+            Some("google-chrome --profile-directory='Profile 1' --incognito")
+        );
+        assert_eq!(manager.browser_command(None, true).as_deref(), Some("firefox -P default --private-window"));
+        assert_eq!(WebSearchManager::private_flag("microsoft-edge"), Some("--inprivate"));
+        assert_eq!(WebSearchManager::private_flag("qutebrowser"), None);
+    }
+
+    #[test]
+    fn test_bangs() {
+        let mut config = Config::default();
+        let jira = "https://jira.example.com/issues/?jql=text~{query}".to_string();
+        config.web_search.bangs.insert("jira".to_string(), jira);
+        let manager = WebSearchManager::new(&config);
+
+        assert_eq!(manager.parse_bang("!gh iced widgets"), Some(("github".to_string(), "iced widgets".to_string())));
+        assert_eq!(manager.parse_bang("lofi !YT"), Some(("youtube".to_string(), "lofi".to_string())));
+        assert_eq!(manager.parse_bang("login bug !jira"), Some(("jira".to_string(), "login bug".to_string())));
+        assert_eq!(manager.parse_bang("!nope rust"), None);
+        assert_eq!(
+            manager.get_search_url("jira", "login bug").1,
+            "https://jira.example.com/issues/?jql=text~login+bug"
+        );
+
+        assert!(WebSearchManager::has_bang("iced !gh"));
+        assert!(!WebSearchManager::has_bang("3 != 4"));
+        assert!(!WebSearchManager::has_bang("wow!"));
+    }
+}
@@ -0,0 +1,128 @@
+//! Instant answers for `wiki <term>`: the best matching article's summary
+//! and thumbnail from Wikipedia's REST API.
+
+use super::urlencoding;
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+
+const API: &str = "https://en.wikipedia.org";
+
+/// How long typing should pause before asking
+pub const DEBOUNCE: Duration = Duration::from_millis(400);
+
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub title: String,
+    /// The article's first paragraph
+    pub extract: String,
+    pub url: String,
+    /// Encoded image bytes
+    pub thumbnail: Option<Vec<u8>>,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    pages: Vec<SearchPage>,
+}
+
+#[derive(Deserialize)]
+struct SearchPage {
+    key: String,
+}
+
+#[derive(Deserialize)]
+struct SummaryResponse {
+    title: String,
+    #[serde(default)]
+    extract: String,
+    #[serde(default)]
+    thumbnail: Option<Thumbnail>,
+    content_urls: ContentUrls,
+}
+
+#[derive(Deserialize)]
+struct Thumbnail {
+    source: String,
+}
+
+#[derive(Deserialize)]
+struct ContentUrls {
+    desktop: PageUrl,
+}
+
+#[derive(Deserialize)]
+struct PageUrl {
+    page: String,
+}
+
+impl SummaryResponse {
+    fn first_paragraph(&self) -> String {
+        self.extract.split('\n').next().unwrap_or_default().trim().to_string()
+    }
+}
+
+/// The summary of the article best matching `term`.
+pub async fn fetch(term: String) -> Result<Summary> {
+    // Wikimedia asks API clients to identify themselves
+    let client = Client::builder()
+        .user_agent(concat!("wlaunch/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(10))
+        .build()?;
+
+    let search: SearchResponse = client
+        .get(format!("{}/w/rest.php/v1/search/title", API))
+        .query(&[("q", term.as_str()), ("limit", "1")])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let key = search.pages.into_iter().next().ok_or_else(|| anyhow!("No article for '{}'", term))?.key;
+
+    let summary: SummaryResponse = client
+        .get(format!("{}/api/rest_v1/page/summary/{}", API, urlencoding::encode(&key)))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    // An answer without its picture beats no answer
+    let thumbnail = match &summary.thumbnail {
+        Some(thumbnail) => match client.get(&thumbnail.source).send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response.bytes().await.ok().map(|bytes| bytes.to_vec()),
+            Err(e) => {
+                log::debug!("Failed to fetch Wikipedia thumbnail: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    Ok(Summary {
+        extract: summary.first_paragraph(),
+        title: summary.title,
+        url: summary.content_urls.desktop.page,
+        thumbnail,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_response() {
+        let summary: SummaryResponse = serde_json::from_str(
+            r#"{"type": "standard", "title": "Rust (programming language)",
+                "extract": "Rust is a general-purpose programming language.\nIt emphasizes performance.",
+                "thumbnail": {"source": "https://upload.wikimedia.org/rust.png", "width": 320, "height": 320},
+                "content_urls": {"desktop": {"page": "https://en.wikipedia.org/wiki/Rust_(programming_language)"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(summary.first_paragraph(), "Rust is a general-purpose programming language.");
+        assert_eq!(summary.content_urls.desktop.page, "https://en.wikipedia.org/wiki/Rust_(programming_language)");
+    }
+}
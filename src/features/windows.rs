@@ -5,9 +5,11 @@
 //! - Hyprland (via `hyprctl`)
 //! - X11 WMs like GNOME, KDE, XFCE (via `wmctrl`)
 
-use crate::core::{Item, ItemType};
+use crate::core::{CommandRunner, Item, ItemType, SystemRunner};
 use serde::Deserialize;
-use std::process::Command;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Supported window manager types.
 #[derive(Debug, Clone, PartialEq)]
@@ -29,13 +31,15 @@ struct I3Node {
     name: Option<String>,
     #[serde(rename = "type")]
     node_type: String,
-    #[allow(dead_code)]
     focused: bool,
     #[serde(default)]
     nodes: Vec<I3Node>,
     #[serde(default)]
     floating_nodes: Vec<I3Node>,
     window_properties: Option<I3WindowProperties>,
+    /// Wayland app_id (Sway native windows have no window_properties)
+    #[serde(default)]
+    app_id: Option<String>,
     #[allow(dead_code)]
     #[serde(default)]
     num: Option<i32>,
@@ -65,12 +69,20 @@ struct HyprlandWorkspace {
     name: String,
 }
 
+/// Scratchpads are `special:<name>` workspaces in Hyprland; plain
+/// `special` is the one `togglespecialworkspace` shows without a name.
+const HYPRLAND_SCRATCHPAD: &str = "special";
+
 /// Manager for window listing and manipulation across different window managers.
 ///
 /// Automatically detects the running window manager at initialization and uses
 /// the appropriate backend for window operations.
+#[derive(Clone)]
 pub struct WindowsManager {
     wm_type: WMType,
+    /// Application classes whose window group is currently expanded
+    expanded_groups: HashSet<String>,
+    runner: Arc<dyn CommandRunner>,
 }
 
 impl WindowsManager {
@@ -81,15 +93,24 @@ impl WindowsManager {
     /// 2. i3/Sway (checks `i3-msg -t get_version`)
     /// 3. wmctrl (fallback for X11 window managers)
     pub fn new() -> Self {
-        let wm_type = Self::detect_wm();
+        Self::with_runner(SystemRunner::shared())
+    }
+
+    /// Creates a WindowsManager that runs the WM's commands through `runner`.
+    pub fn with_runner(runner: Arc<dyn CommandRunner>) -> Self {
+        let wm_type = Self::detect_wm(runner.as_ref());
         log::debug!("Detected window manager: {:?}", wm_type);
-        Self { wm_type }
+        Self {
+            wm_type,
+            expanded_groups: HashSet::new(),
+            runner,
+        }
     }
 
-    fn detect_wm() -> WMType {
+    fn detect_wm(runner: &dyn CommandRunner) -> WMType {
         // Check for Hyprland first (via HYPRLAND_INSTANCE_SIGNATURE env var)
         if std::env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok() {
-            if let Ok(output) = Command::new("hyprctl").arg("version").output() {
+            if let Ok(output) = runner.run("hyprctl", &["version"]) {
                 if output.status.success() {
                     return WMType::Hyprland;
                 }
@@ -97,14 +118,14 @@ impl WindowsManager {
         }
 
         // Check for i3/Sway
-        if let Ok(output) = Command::new("i3-msg").args(["-t", "get_version"]).output() {
+        if let Ok(output) = runner.run("i3-msg", &["-t", "get_version"]) {
             if output.status.success() {
                 return WMType::I3Sway;
             }
         }
 
         // Check for wmctrl (works with most X11 WMs)
-        if let Ok(output) = Command::new("wmctrl").arg("--version").output() {
+        if let Ok(output) = runner.run("wmctrl", &["--version"]) {
             if output.status.success() {
                 return WMType::X11Wmctrl;
             }
@@ -113,14 +134,270 @@ impl WindowsManager {
         WMType::Unknown
     }
 
+    /// Class (or Wayland app_id) of the focused window, read before the
+    /// launcher's own window opens.
+    pub fn focused_class(&self) -> Option<String> {
+        match self.wm_type {
+            WMType::Hyprland => {
+                let output = self.runner.run("hyprctl", &["activewindow", "-j"]).ok()?;
+                let client: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+                client["class"].as_str().filter(|c| !c.is_empty()).map(str::to_string)
+            }
+            WMType::I3Sway => {
+                let output = self.runner.run("i3-msg", &["-t", "get_tree"]).ok()?;
+                let tree: I3Node = serde_json::from_slice(&output.stdout).ok()?;
+                Self::focused_i3_class(&tree)
+            }
+            WMType::X11Wmctrl => {
+                // _NET_ACTIVE_WINDOW(WINDOW): window id # 0x4000003
+                let output = self.runner.run("xprop", &["-root", "_NET_ACTIVE_WINDOW"]).ok()?;
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let id = stdout.split_whitespace().last()?.to_string();
+                // WM_CLASS(STRING) = "Navigator", "firefox"
+                let output = self.runner.run("xprop", &["-id", &id, "WM_CLASS"]).ok()?;
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                stdout.rsplit('"').nth(1).map(str::to_string)
+            }
+            WMType::Unknown => None,
+        }
+    }
+
+    fn focused_i3_class(node: &I3Node) -> Option<String> {
+        if node.focused {
+            return node
+                .app_id
+                .clone()
+                .or_else(|| node.window_properties.as_ref()?.class.clone());
+        }
+        node.nodes
+            .iter()
+            .chain(&node.floating_nodes)
+            .find_map(Self::focused_i3_class)
+    }
+
     /// Returns a list of open windows matching the query.
     ///
+    /// Windows belonging to the same application are grouped under a single
+    /// entry with a window count. Groups are expanded when toggled or when a
+    /// query is typed, and an expanded group offers a "close all" action.
+    ///
     /// # Arguments
     /// * `query` - Filter string to match against window titles and classes
     ///
     /// # Returns
-    /// Vector of `Item` representing each matching window
+    /// Vector of `Item` representing each matching window or window group
     pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let windows = self.list_windows(query);
+
+        // Group by class, keeping the order in which each class first appears
+        let mut groups: Vec<(String, Vec<Item>)> = Vec::new();
+        for window in windows {
+            let class = window.metadata.wm_class.clone().unwrap_or_default();
+            match groups.iter_mut().find(|(c, _)| *c == class) {
+                Some((_, members)) => members.push(window),
+                None => groups.push((class, vec![window])),
+            }
+        }
+
+        let mut items = Vec::new();
+        let best = (!query.is_empty()).then(|| groups.first().map(|(_, members)| members[0].id.clone())).flatten();
+        for (class, members) in groups {
+            if members.len() < 2 || class.is_empty() {
+                for window in members {
+                    let actions = if best.as_ref() == Some(&window.id) { self.window_actions(&window) } else { Vec::new() };
+                    items.push(window);
+                    items.extend(actions);
+                }
+                continue;
+            }
+
+            let expanded = !query.is_empty() || self.expanded_groups.contains(&class);
+            let mut group = Item::new(
+                format!("window:group:{}", class),
+                format!("{} ({} windows)", class, members.len()),
+                ItemType::WindowGroup,
+            )
+            .with_description(if expanded {
+                "Press Enter to collapse"
+            } else {
+                "Press Enter to show all windows"
+            })
+            .with_icon("window");
+            group.metadata.wm_class = Some(class.clone());
+            items.push(group);
+
+            if expanded {
+                let count = members.len();
+                for mut window in members {
+                    let actions = if best.as_ref() == Some(&window.id) { self.window_actions(&window) } else { Vec::new() };
+                    window.name = format!("  {}", window.name);
+                    items.push(window);
+                    items.extend(actions);
+                }
+                let mut close_all = Item::new(
+                    format!("window:closeall:{}", class),
+                    format!("  Close all {} {} windows", count, class),
+                    ItemType::WindowAction,
+                )
+                .with_description("Close every window of this application")
+                .with_icon("window-close");
+                close_all.metadata.wm_class = Some(class.clone());
+                items.push(close_all);
+            }
+        }
+
+        items.extend(self.scratchpad_items(query));
+        items
+    }
+
+    /// Floating, sticky and scratchpad actions for the best match, as far as
+    /// the window manager has them.
+    fn window_actions(&self, window: &Item) -> Vec<Item> {
+        let Some(window_id) = window.metadata.window_id else {
+            return Vec::new();
+        };
+        let actions: &[(&str, &str, &str)] = match self.wm_type {
+            WMType::I3Sway => &[
+                ("float", "Toggle floating", "Tiles a floating window, or floats a tiled one"),
+                ("sticky", "Toggle sticky", "Keeps a floating window on every workspace"),
+                ("scratch", "Move to scratchpad", "Hides it until the scratchpad is shown"),
+            ],
+            WMType::Hyprland => &[
+                ("float", "Toggle floating", "Tiles a floating window, or floats a tiled one"),
+                ("sticky", "Toggle pinned", "Keeps a floating window on every workspace"),
+                ("scratch", "Move to scratchpad", "Moves it to the special workspace"),
+            ],
+            WMType::X11Wmctrl => &[("sticky", "Toggle sticky", "Keeps it on every desktop")],
+            WMType::Unknown => &[],
+        };
+        actions
+            .iter()
+            .map(|(action, name, description)| {
+                Item::new(format!("window:{}:{}", action, window_id), format!("    {}", name), ItemType::WindowAction)
+                    .with_description(*description)
+                    .with_icon("window")
+            })
+            .collect()
+    }
+
+    /// Items showing the scratchpad: i3/Sway's one, or each of Hyprland's
+    /// special workspaces that has windows.
+    fn scratchpad_items(&self, query: &str) -> Vec<Item> {
+        let names: Vec<String> = match self.wm_type {
+            WMType::I3Sway => vec![String::new()],
+            WMType::Hyprland => self
+                .runner
+                .run("hyprctl", &["workspaces", "-j"])
+                .ok()
+                .and_then(|output| serde_json::from_slice::<Vec<HyprlandWorkspace>>(&output.stdout).ok())
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|workspace| workspace.name.strip_prefix("special:").map(str::to_string))
+                .collect(),
+            WMType::X11Wmctrl | WMType::Unknown => Vec::new(),
+        };
+        let query = query.to_lowercase();
+        names
+            .into_iter()
+            .map(|name| {
+                let title = if name.is_empty() || name == HYPRLAND_SCRATCHPAD {
+                    "Show scratchpad".to_string()
+                } else {
+                    format!("Show scratchpad {}", name)
+                };
+                Item::new(format!("window:summon:{}", name), title, ItemType::WindowAction)
+                    .with_description("Shows the hidden windows, or hides them again")
+                    .with_icon("window")
+            })
+            .filter(|item| item.name.to_lowercase().contains(&query))
+            .collect()
+    }
+
+    /// Runs a window manager command, logging failures.
+    fn run_logged(&self, program: &str, args: &[&str]) {
+        match self.runner.run(program, args) {
+            Ok(output) if !output.status.success() => {
+                log::debug!("{} {:?} failed: {}", program, args, String::from_utf8_lossy(&output.stderr))
+            }
+            Err(e) => log::debug!("Failed to run {}: {}", program, e),
+            Ok(_) => {}
+        }
+    }
+
+    /// Toggles floating or sticky on a window, or moves it to the scratchpad.
+    fn window_command(&self, action: &str, window_id: i64) {
+        match self.wm_type {
+            WMType::I3Sway => {
+                let command = match action {
+                    "float" => "floating toggle",
+                    "sticky" => "sticky toggle",
+                    _ => "move scratchpad",
+                };
+                self.run_logged("i3-msg", &[&format!("[con_id={}] {}", window_id, command)]);
+            }
+            WMType::Hyprland => {
+                let address = format!("address:0x{:x}", window_id);
+                match action {
+                    "float" => self.run_logged("hyprctl", &["dispatch", "togglefloating", &address]),
+                    "sticky" => self.run_logged("hyprctl", &["dispatch", "pin", &address]),
+                    _ => self.run_logged("hyprctl", &["dispatch", "movetoworkspacesilent", &format!("{},{}", HYPRLAND_SCRATCHPAD, address)]),
+                }
+            }
+            WMType::X11Wmctrl if action == "sticky" => {
+                self.run_logged("wmctrl", &["-i", "-r", &format!("0x{:08x}", window_id), "-b", "toggle,sticky"]);
+            }
+            WMType::X11Wmctrl | WMType::Unknown => {}
+        }
+    }
+
+    /// Shows or hides a scratchpad; `name` is empty for i3/Sway's.
+    fn summon(&self, name: &str) {
+        match self.wm_type {
+            WMType::I3Sway => self.run_logged("i3-msg", &["scratchpad show"]),
+            WMType::Hyprland if name == HYPRLAND_SCRATCHPAD => self.run_logged("hyprctl", &["dispatch", "togglespecialworkspace"]),
+            WMType::Hyprland => self.run_logged("hyprctl", &["dispatch", "togglespecialworkspace", name]),
+            WMType::X11Wmctrl | WMType::Unknown => {}
+        }
+    }
+
+    /// Expands or collapses the window group of an application class.
+    pub fn toggle_group(&mut self, class: &str) {
+        if !self.expanded_groups.remove(class) {
+            self.expanded_groups.insert(class.to_string());
+        }
+    }
+
+    /// Closes every open window whose class matches `class`.
+    pub fn close_all(&self, class: &str) {
+        for window in self.list_windows("") {
+            if window.metadata.wm_class.as_deref() == Some(class) {
+                if let Some(window_id) = window.metadata.window_id {
+                    self.close_window(window_id);
+                }
+            }
+        }
+    }
+
+    /// Runs a window action; returns whether the launcher should close to
+    /// reveal what it showed.
+    pub fn execute_action(&mut self, action_id: &str) -> bool {
+        if let Some(class) = action_id.strip_prefix("window:group:") {
+            self.toggle_group(class);
+        } else if let Some(class) = action_id.strip_prefix("window:closeall:") {
+            self.close_all(class);
+        } else if let Some(name) = action_id.strip_prefix("window:summon:") {
+            self.summon(name);
+            return true;
+        } else if let Some((action, window_id)) = action_id.strip_prefix("window:").and_then(|rest| rest.split_once(':')) {
+            if let Ok(window_id) = window_id.parse() {
+                self.window_command(action, window_id);
+            }
+        }
+        false
+    }
+
+    /// Returns the flat, ungrouped list of open windows matching the query.
+    pub fn list_windows(&self, query: &str) -> Vec<Item> {
         let query = query.to_lowercase();
         let mut items = match self.wm_type {
             WMType::I3Sway => self.get_i3_windows(),
@@ -156,7 +433,7 @@ impl WindowsManager {
     fn get_i3_windows(&self) -> Vec<Item> {
         let mut items = Vec::new();
 
-        if let Ok(output) = Command::new("i3-msg").args(["-t", "get_tree"]).output() {
+        if let Ok(output) = self.runner.run("i3-msg", &["-t", "get_tree"]) {
             if output.status.success() {
                 if let Ok(stdout) = String::from_utf8(output.stdout) {
                     if let Ok(tree) = serde_json::from_str::<I3Node>(&stdout) {
@@ -176,28 +453,30 @@ impl WindowsManager {
             workspace
         };
 
-        if node.node_type == "con" && node.window_properties.is_some() {
-            if let Some(props) = &node.window_properties {
-                let title = props
-                    .title
-                    .as_deref()
-                    .or(node.name.as_deref())
-                    .unwrap_or("Unknown");
-                let class = props.class.as_deref().unwrap_or("Unknown");
-
-                let mut item = Item::new(
-                    format!("window:{}", node.id),
-                    title,
-                    ItemType::Window,
-                )
-                .with_description(format!("{} ({})", class, current_workspace.unwrap_or("?")))
-                .with_icon("window");
+        if node.node_type == "con" && (node.window_properties.is_some() || node.app_id.is_some()) {
+            let props = node.window_properties.as_ref();
+            let title = props
+                .and_then(|p| p.title.as_deref())
+                .or(node.name.as_deref())
+                .unwrap_or("Unknown");
+            let class = props
+                .and_then(|p| p.class.as_deref())
+                .or(node.app_id.as_deref())
+                .unwrap_or("Unknown");
 
-                item.metadata.window_id = Some(node.id);
-                item.metadata.workspace = current_workspace.map(String::from);
+            let mut item = Item::new(
+                format!("window:{}", node.id),
+                title,
+                ItemType::Window,
+            )
+            .with_description(format!("{} ({})", class, current_workspace.unwrap_or("?")))
+            .with_icon("window");
 
-                items.push(item);
-            }
+            item.metadata.window_id = Some(node.id);
+            item.metadata.workspace = current_workspace.map(String::from);
+            item.metadata.wm_class = Some(class.to_string());
+
+            items.push(item);
         }
 
         for child in &node.nodes {
@@ -212,7 +491,7 @@ impl WindowsManager {
     fn get_hyprland_windows(&self) -> Vec<Item> {
         let mut items = Vec::new();
 
-        if let Ok(output) = Command::new("hyprctl").args(["clients", "-j"]).output() {
+        if let Ok(output) = self.runner.run("hyprctl", &["clients", "-j"]) {
             if output.status.success() {
                 if let Ok(stdout) = String::from_utf8(output.stdout) {
                     if let Ok(clients) = serde_json::from_str::<Vec<HyprlandClient>>(&stdout) {
@@ -231,6 +510,7 @@ impl WindowsManager {
                             // Store address as string in metadata for Hyprland
                             // We'll parse it back when focusing
                             item.metadata.workspace = Some(client.workspace.name);
+                            item.metadata.wm_class = Some(client.class.clone());
                             // Convert hex address to i64 for window_id
                             if let Some(addr) = client.address.strip_prefix("0x") {
                                 if let Ok(id) = i64::from_str_radix(addr, 16) {
@@ -254,17 +534,28 @@ impl WindowsManager {
 
         // wmctrl -l -x output format:
         // 0x04000003  0 instance.class  hostname Window Title
-        if let Ok(output) = Command::new("wmctrl").args(["-l", "-x"]).output() {
+        if let Ok(output) = self.runner.run("wmctrl", &["-l", "-x"]) {
             if output.status.success() {
                 if let Ok(stdout) = String::from_utf8(output.stdout) {
                     for line in stdout.lines() {
-                        let parts: Vec<&str> = line.splitn(5, ' ').filter(|s| !s.is_empty()).collect();
-                        if parts.len() >= 5 {
+                        // Columns are padded with runs of spaces; the title is the rest
+                        let mut parts: Vec<&str> = Vec::new();
+                        let mut rest = line;
+                        while parts.len() < 4 {
+                            rest = rest.trim_start();
+                            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                            if end == 0 {
+                                break;
+                            }
+                            parts.push(&rest[..end]);
+                            rest = &rest[end..];
+                        }
+                        if parts.len() == 4 {
                             let window_id_hex = parts[0];
                             let desktop = parts[1];
                             let class = parts[2];
-                            // parts[3] is hostname, parts[4..] is title
-                            let title = parts[4..].join(" ");
+                            // parts[3] is hostname, the rest is the title
+                            let title = rest.trim().to_string();
                             let title = if title.is_empty() {
                                 class.to_string()
                             } else {
@@ -283,7 +574,7 @@ impl WindowsManager {
                             }
 
                             // Parse class (format: instance.class)
-                            let class_name = class.split('.').last().unwrap_or(class);
+                            let class_name = class.split('.').next_back().unwrap_or(class);
 
                             let workspace = if desktop == "-1" {
                                 "sticky".to_string()
@@ -301,6 +592,7 @@ impl WindowsManager {
 
                             item.metadata.window_id = Some(window_id);
                             item.metadata.workspace = Some(workspace);
+                            item.metadata.wm_class = Some(class_name.to_string());
 
                             items.push(item);
                         }
@@ -312,6 +604,105 @@ impl WindowsManager {
         items
     }
 
+    /// Returns the open windows that belong to an indexed application.
+    ///
+    /// Windows are correlated by comparing their class/app_id against the
+    /// application's `StartupWMClass`, falling back to the desktop file id
+    /// (Wayland app_ids usually match it) and the executable name.
+    pub fn windows_for_app<'a>(app: &Item, windows: &'a [Item]) -> Vec<&'a Item> {
+        let candidates = Self::app_classes(app);
+        windows
+            .iter()
+            .filter(|w| w.metadata.window_id.is_some())
+            .filter(|w| {
+                w.metadata
+                    .wm_class
+                    .as_ref()
+                    .map(|c| candidates.contains(&c.to_lowercase()))
+                    .unwrap_or(false)
+            })
+            .collect()
+    }
+
+    /// `items` with a "switch to running instance" entry before each app
+    /// that has windows among `windows`; launching again stays available
+    /// right after it.
+    pub fn with_running_instances(items: Vec<Item>, windows: &[Item]) -> Vec<Item> {
+        let mut result = Vec::with_capacity(items.len());
+        for mut item in items {
+            if item.item_type == ItemType::Application {
+                let windows = Self::windows_for_app(&item, windows);
+                if let Some(window) = windows.first() {
+                    let mut switch = (*window).clone();
+                    switch.name = item.name.clone();
+                    switch.icon = item.icon.clone();
+                    switch.icon_path = item.icon_path.clone();
+                    switch.description = Some(if windows.len() > 1 {
+                        format!("Switch to running instance ({} windows)", windows.len())
+                    } else {
+                        format!("Switch to running instance: {}", window.name)
+                    });
+                    result.push(switch);
+                    item.description = Some("Launch new instance".to_string());
+                }
+            }
+            result.push(item);
+        }
+        result
+    }
+
+    /// Lowercased classes an application's windows may have.
+    pub fn app_classes(app: &Item) -> Vec<String> {
+        let mut candidates: Vec<String> = Vec::new();
+        if let Some(class) = &app.metadata.wm_class {
+            candidates.push(class.to_lowercase());
+        }
+        if let Some(stem) = app
+            .metadata
+            .desktop_file
+            .as_ref()
+            .and_then(|p| p.file_stem())
+            .and_then(|s| s.to_str())
+        {
+            candidates.push(stem.to_lowercase());
+            // "org.gnome.Nautilus" -> "nautilus"
+            if let Some(last) = stem.rsplit('.').next() {
+                candidates.push(last.to_lowercase());
+            }
+        }
+        if let Some(binary) = app
+            .exec
+            .as_deref()
+            .and_then(|e| e.split_whitespace().next())
+            .and_then(|b| b.rsplit('/').next())
+        {
+            candidates.push(binary.to_lowercase());
+        }
+        candidates
+    }
+
+    /// A window of one of `classes` (lowercased) that isn't in `known`.
+    fn new_window(&self, classes: &[String], known: &[i64]) -> Option<i64> {
+        self.list_windows("")
+            .into_iter()
+            .filter(|w| w.metadata.wm_class.as_ref().is_some_and(|c| classes.contains(&c.to_lowercase())))
+            .find_map(|w| w.metadata.window_id.filter(|id| !known.contains(id)))
+    }
+
+    /// Waits up to `timeout` for a new window of one of `classes`, then
+    /// moves it to `workspace`. Returns whether one appeared.
+    pub fn place_new_window(&self, classes: &[String], known: &[i64], workspace: &str, timeout: Duration) -> bool {
+        let start = Instant::now();
+        while start.elapsed() < timeout {
+            if let Some(window_id) = self.new_window(classes, known) {
+                self.move_to_workspace(window_id, workspace);
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(250));
+        }
+        false
+    }
+
     /// Focuses a window by its ID.
     ///
     /// # Arguments
@@ -324,20 +715,14 @@ impl WindowsManager {
     pub fn focus_window(&self, window_id: i64) {
         let result = match self.wm_type {
             WMType::I3Sway => {
-                Command::new("i3-msg")
-                    .arg(format!("[con_id={}] focus", window_id))
-                    .output()
+                self.runner.run("i3-msg", &[&format!("[con_id={}] focus", window_id)])
             }
             WMType::Hyprland => {
                 let address = format!("0x{:x}", window_id);
-                Command::new("hyprctl")
-                    .args(["dispatch", "focuswindow", &format!("address:{}", address)])
-                    .output()
+                self.runner.run("hyprctl", &["dispatch", "focuswindow", &format!("address:{}", address)])
             }
             WMType::X11Wmctrl => {
-                Command::new("wmctrl")
-                    .args(["-i", "-a", &format!("0x{:08x}", window_id)])
-                    .output()
+                self.runner.run("wmctrl", &["-i", "-a", &format!("0x{:08x}", window_id)])
             }
             WMType::Unknown => return,
         };
@@ -355,6 +740,45 @@ impl WindowsManager {
         }
     }
 
+    /// Moves a window to a workspace, then focuses it there.
+    ///
+    /// Uses the appropriate command for the detected window manager:
+    /// - i3/Sway: `i3-msg [con_id=<id>] move container to workspace [number] <ws>`
+    /// - Hyprland: `hyprctl dispatch movetoworkspacesilent <ws>,address:<addr>`
+    /// - wmctrl: `wmctrl -i -r <id> -t <desktop>`
+    pub fn move_to_workspace(&self, window_id: i64, workspace: &str) {
+        let numbered = workspace.parse::<u32>().is_ok();
+        let result = match self.wm_type {
+            WMType::I3Sway => {
+                // `number 9` also matches a workspace named "9: music"
+                let target = if numbered { format!("number {}", workspace) } else { workspace.to_string() };
+                self.runner.run("i3-msg", &[&format!("[con_id={}] move container to workspace {}", window_id, target)])
+            }
+            WMType::Hyprland => {
+                let address = format!("0x{:x}", window_id);
+                self.runner.run("hyprctl", &["dispatch", "movetoworkspacesilent", &format!("{},address:{}", workspace, address)])
+            }
+            WMType::X11Wmctrl if numbered => {
+                self.runner.run("wmctrl", &["-i", "-r", &format!("0x{:08x}", window_id), "-t", workspace])
+            }
+            WMType::X11Wmctrl => {
+                log::warn!("wmctrl desktops are numbers, not {:?}", workspace);
+                return;
+            }
+            WMType::Unknown => return,
+        };
+
+        match result {
+            Ok(output) if output.status.success() => self.focus_window(window_id),
+            Ok(output) => log::debug!(
+                "Move window command failed for {}: {}",
+                window_id,
+                String::from_utf8_lossy(&output.stderr)
+            ),
+            Err(e) => log::debug!("Failed to move window {}: {}", window_id, e),
+        }
+    }
+
     /// Closes a window by its ID.
     ///
     /// # Arguments
@@ -364,24 +788,17 @@ impl WindowsManager {
     /// - i3/Sway: `i3-msg [con_id=<id>] kill`
     /// - Hyprland: `hyprctl dispatch closewindow address:<addr>`
     /// - wmctrl: `wmctrl -i -c <id>`
-    #[allow(dead_code)]
     pub fn close_window(&self, window_id: i64) {
         let result = match self.wm_type {
             WMType::I3Sway => {
-                Command::new("i3-msg")
-                    .arg(format!("[con_id={}] kill", window_id))
-                    .output()
+                self.runner.run("i3-msg", &[&format!("[con_id={}] kill", window_id)])
             }
             WMType::Hyprland => {
                 let address = format!("0x{:x}", window_id);
-                Command::new("hyprctl")
-                    .args(["dispatch", "closewindow", &format!("address:{}", address)])
-                    .output()
+                self.runner.run("hyprctl", &["dispatch", "closewindow", &format!("address:{}", address)])
             }
             WMType::X11Wmctrl => {
-                Command::new("wmctrl")
-                    .args(["-i", "-c", &format!("0x{:08x}", window_id)])
-                    .output()
+                self.runner.run("wmctrl", &["-i", "-c", &format!("0x{:08x}", window_id)])
             }
             WMType::Unknown => return,
         };
@@ -405,3 +822,211 @@ impl Default for WindowsManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    fn manager(wm_type: WMType, runner: FakeRunner) -> WindowsManager {
+        WindowsManager {
+            wm_type,
+            expanded_groups: HashSet::new(),
+            runner: runner.shared(),
+        }
+    }
+
+    #[test]
+    fn test_i3_tree() {
+        let runner = FakeRunner::new()
+            .with("i3-msg -t get_version", r#"{"major":4,"minor":23,"patch":0}"#)
+            .with("i3-msg -t get_tree", include_str!("../../tests/fixtures/i3_tree.json"))
+            .shared();
+        let windows = WindowsManager::with_runner(runner);
+        assert_eq!(windows.wm_type, WMType::I3Sway);
+        assert_eq!(windows.focused_class().as_deref(), Some("foot"));
+
+        let items = windows.list_windows("");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["GitHub - Mozilla Firefox", "~/code", "Calculator"]);
+        assert_eq!(items[0].metadata.window_id, Some(94251));
+        assert_eq!(items[0].metadata.wm_class.as_deref(), Some("firefox"));
+        assert_eq!(items[2].metadata.workspace.as_deref(), Some("2: term"));
+        assert_eq!(items[2].metadata.wm_class.as_deref(), Some("org.gnome.Calculator"));
+    }
+
+    #[test]
+    fn test_focused_i3_class() {
+        let tree: I3Node = serde_json::from_str(include_str!("../../tests/fixtures/i3_tree.json")).unwrap();
+        assert_eq!(WindowsManager::focused_i3_class(&tree).as_deref(), Some("foot"));
+
+        // An X11 window floating on a workspace, found by its class
+        let tree: I3Node = serde_json::from_str(
+            r#"{"id": 1, "type": "root", "focused": false, "nodes": [
+                {"id": 2, "type": "workspace", "focused": false, "nodes": [], "floating_nodes": [
+                    {"id": 3, "type": "con", "focused": true, "window_properties": {"class": "Gimp", "title": "GIMP"}}
+                ]}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(WindowsManager::focused_i3_class(&tree).as_deref(), Some("Gimp"));
+
+        // Nothing focused, e.g. an empty workspace
+        let tree: I3Node = serde_json::from_str(r#"{"id": 1, "type": "root", "focused": false}"#).unwrap();
+        assert_eq!(WindowsManager::focused_i3_class(&tree), None);
+    }
+
+    #[test]
+    fn test_hyprctl_clients() {
+        let runner = FakeRunner::new().with("hyprctl clients -j", include_str!("../../tests/fixtures/hyprctl_clients.json"));
+        let items = manager(WMType::Hyprland, runner).list_windows("");
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].name, "nvim main.rs");
+        assert_eq!(items[1].metadata.window_id, Some(0x55d1c8b41a20));
+        assert_eq!(items[1].metadata.workspace.as_deref(), Some("code"));
+        assert_eq!(items[1].metadata.wm_class.as_deref(), Some("kitty"));
+    }
+
+    #[test]
+    fn test_tiling_actions() {
+        let runner = FakeRunner::new()
+            .with("hyprctl clients -j", include_str!("../../tests/fixtures/hyprctl_clients.json"))
+            .with("hyprctl workspaces -j", r#"[{"id":1,"name":"1"},{"id":-98,"name":"special:term"},{"id":-99,"name":"special:special"}]"#);
+        let windows = manager(WMType::Hyprland, runner);
+
+        let names: Vec<String> = windows.get_items("").into_iter().map(|item| item.name).collect();
+        assert_eq!(names, ["Mozilla Firefox", "nvim main.rs", "Show scratchpad term", "Show scratchpad"]);
+
+        let items = windows.get_items("nvim");
+        let ids: Vec<&str> = items.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "window:0x55d1c8b41a20",
+                "window:float:94359503772192",
+                "window:sticky:94359503772192",
+                "window:scratch:94359503772192",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_window_groups() {
+        let runner = FakeRunner::new().with(
+            "wmctrl -l -x",
+            "0x03a00003  0 Navigator.firefox  laptop Inbox - Mozilla Firefox\n\
+             0x04400004  1 gnome-terminal-server.Gnome-terminal  laptop alice@laptop: ~/code\n\
+             0x03a00010  1 Navigator.firefox  laptop Docs - Mozilla Firefox\n",
+        );
+        let mut windows = manager(WMType::X11Wmctrl, runner);
+
+        // Collapsed: one entry per application with several windows
+        let rows: Vec<(String, String)> = windows.get_items("").into_iter().map(|item| (item.id, item.name)).collect();
+        assert_eq!(
+            rows,
+            [
+                ("window:group:firefox".to_string(), "firefox (2 windows)".to_string()),
+                ("window:71303172".to_string(), "alice@laptop: ~/code".to_string()),
+            ]
+        );
+
+        // Enter expands the group, with its windows and a close all action
+        windows.execute_action("window:group:firefox");
+        let names: Vec<String> = windows.get_items("").into_iter().map(|item| item.name).collect();
+        assert_eq!(
+            names,
+            [
+                "firefox (2 windows)",
+                "  Inbox - Mozilla Firefox",
+                "  Docs - Mozilla Firefox",
+                "  Close all 2 firefox windows",
+                "alice@laptop: ~/code",
+            ]
+        );
+        windows.execute_action("window:group:firefox");
+        assert_eq!(windows.get_items("").len(), 2);
+
+        // A query always shows the matching windows
+        let items = windows.get_items("mozilla");
+        assert_eq!(items[0].description.as_deref(), Some("Press Enter to collapse"));
+        assert_eq!(items[1].name, "  Inbox - Mozilla Firefox");
+        assert_eq!(items.last().map(|item| item.id.as_str()), Some("window:closeall:firefox"));
+    }
+
+    #[test]
+    fn test_new_window() {
+        let runner = FakeRunner::new().with("hyprctl clients -j", include_str!("../../tests/fixtures/hyprctl_clients.json"));
+        let windows = manager(WMType::Hyprland, runner);
+        let classes = ["kitty".to_string()];
+        assert_eq!(windows.new_window(&classes, &[]), Some(0x55d1c8b41a20));
+        assert_eq!(windows.new_window(&classes, &[0x55d1c8b41a20]), None);
+        assert_eq!(windows.new_window(&["spotify".to_string()], &[]), None);
+    }
+
+    fn app(name: &str, exec: &str, desktop_file: &str) -> Item {
+        let mut item = Item::new(format!("app:{}", name), name, ItemType::Application).with_exec(exec);
+        item.metadata.desktop_file = Some(std::path::PathBuf::from(desktop_file));
+        item
+    }
+
+    #[test]
+    fn test_windows_for_app() {
+        let runner = FakeRunner::new().with("hyprctl clients -j", include_str!("../../tests/fixtures/hyprctl_clients.json"));
+        let windows = manager(WMType::Hyprland, runner).list_windows("");
+
+        let firefox = app("Firefox", "/usr/lib/firefox/firefox %u", "/usr/share/applications/firefox.desktop");
+        assert_eq!(WindowsManager::windows_for_app(&firefox, &windows)[0].name, "Mozilla Firefox");
+        // Matched by StartupWMClass although nothing else fits
+        let mut terminal = app("Terminal", "term-launcher", "/usr/share/applications/my-term.desktop");
+        terminal.metadata.wm_class = Some("Kitty".to_string());
+        assert_eq!(WindowsManager::windows_for_app(&terminal, &windows)[0].name, "nvim main.rs");
+        let spotify = app("Spotify", "spotify %U", "/usr/share/applications/com.spotify.Client.desktop");
+        assert!(WindowsManager::windows_for_app(&spotify, &windows).is_empty());
+        assert_eq!(WindowsManager::app_classes(&spotify), ["com.spotify.client", "client", "spotify"]);
+    }
+
+    #[test]
+    fn test_with_running_instances() {
+        let runner = FakeRunner::new().with("hyprctl clients -j", include_str!("../../tests/fixtures/hyprctl_clients.json"));
+        let windows = manager(WMType::Hyprland, runner).list_windows("");
+        let items = vec![
+            app("Firefox", "firefox %u", "/usr/share/applications/firefox.desktop"),
+            app("Spotify", "spotify %U", "/usr/share/applications/spotify.desktop"),
+            Item::new("script:kitty", "kitty", ItemType::Script).with_exec("kitty"),
+        ];
+
+        let result = WindowsManager::with_running_instances(items, &windows);
+        let rows: Vec<(&str, &ItemType, Option<&str>)> = result
+            .iter()
+            .map(|item| (item.name.as_str(), &item.item_type, item.description.as_deref()))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("Firefox", &ItemType::Window, Some("Switch to running instance: Mozilla Firefox")),
+                ("Firefox", &ItemType::Application, Some("Launch new instance")),
+                ("Spotify", &ItemType::Application, None),
+                ("kitty", &ItemType::Script, None),
+            ]
+        );
+        assert_eq!(result[0].metadata.window_id, windows[0].metadata.window_id);
+
+        // Several windows are counted
+        let twice = [windows[0].clone(), windows[0].clone()];
+        let result = WindowsManager::with_running_instances(vec![app("Firefox", "firefox", "firefox.desktop")], &twice);
+        assert_eq!(result[0].description.as_deref(), Some("Switch to running instance (2 windows)"));
+    }
+
+    #[test]
+    fn test_wmctrl_list() {
+        let runner = FakeRunner::new().with("wmctrl -l -x", include_str!("../../tests/fixtures/wmctrl_list.txt"));
+        let items = manager(WMType::X11Wmctrl, runner).list_windows("");
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].metadata.workspace.as_deref(), Some("sticky"));
+        assert_eq!(items[1].name, "Inbox - Mozilla Thunderbird and more");
+        assert_eq!(items[1].metadata.window_id, Some(0x03a00003));
+        assert_eq!(items[1].metadata.wm_class.as_deref(), Some("firefox"));
+        assert_eq!(items[2].name, "alice@laptop: ~/code");
+        assert_eq!(items[2].metadata.workspace.as_deref(), Some("Desktop 1"));
+    }
+}
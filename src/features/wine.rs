@@ -0,0 +1,126 @@
+//! Wine prefixes discovered from indexed Wine shortcuts and Proton games,
+//! with per-prefix maintenance actions.
+
+use crate::core::{Item, ItemType};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Clone)]
+pub struct WineManager;
+
+impl WineManager {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Lists every prefix used by `apps` (plus `~/.wine`), each followed by
+    /// its winecfg and kill-wineserver actions.
+    pub fn get_items(&self, query: &str, apps: &[Item]) -> Vec<Item> {
+        let query = query.to_lowercase();
+
+        // prefix -> names of the apps installed in it
+        let mut prefixes: BTreeMap<PathBuf, Vec<&str>> = BTreeMap::new();
+        if let Some(default) = dirs::home_dir().map(|home| home.join(".wine")) {
+            if default.exists() {
+                prefixes.entry(default).or_default();
+            }
+        }
+        for app in apps {
+            if let Some(prefix) = &app.metadata.wine_prefix {
+                prefixes.entry(prefix.clone()).or_default().push(&app.name);
+            }
+        }
+
+        let mut items = Vec::new();
+        for (prefix, app_names) in prefixes {
+            let display = prefix.display().to_string();
+            if !query.is_empty()
+                && !display.to_lowercase().contains(&query)
+                && !app_names.iter().any(|name| name.to_lowercase().contains(&query))
+            {
+                continue;
+            }
+
+            let mut item = Item::new(
+                format!("wine:prefix:{}", display),
+                Self::prefix_name(&prefix),
+                ItemType::WinePrefix,
+            )
+            .with_description(if app_names.is_empty() {
+                display.clone()
+            } else {
+                format!("{} | {}", display, app_names.join(", "))
+            })
+            .with_icon("wine");
+            item.metadata.wine_prefix = Some(prefix.clone());
+            items.push(item);
+
+            let mut winecfg = Item::new(
+                format!("wine:winecfg:{}", display),
+                "    Configure (winecfg)",
+                ItemType::WineAction,
+            )
+            .with_description(display.clone())
+            .with_icon("preferences-system");
+            winecfg.metadata.wine_prefix = Some(prefix.clone());
+            items.push(winecfg);
+
+            let mut kill = Item::new(
+                format!("wine:kill:{}", display),
+                "    Kill wineserver",
+                ItemType::WineAction,
+            )
+            .with_description(display)
+            .with_icon("process-stop");
+            kill.metadata.wine_prefix = Some(prefix);
+            items.push(kill);
+        }
+
+        if items.is_empty() {
+            items.push(
+                Item::new("wine:none", "No Wine prefixes found", ItemType::WineAction)
+                    .with_description("Wine shortcuts and Proton games show up here once installed")
+                    .with_icon("dialog-information"),
+            );
+        }
+
+        items
+    }
+
+    /// `~/.wine` -> "wine", `.../compatdata/1234/pfx` -> "Proton 1234"
+    fn prefix_name(prefix: &Path) -> String {
+        let name = prefix.file_name().and_then(|n| n.to_str()).unwrap_or("prefix");
+        if name == "pfx" {
+            if let Some(app_id) = prefix.parent().and_then(|p| p.file_name()).and_then(|n| n.to_str()) {
+                return format!("Proton {}", app_id);
+            }
+        }
+        name.trim_start_matches('.').to_string()
+    }
+
+    pub fn winecfg(&self, prefix: &Path) {
+        let _ = Command::new("winecfg").env("WINEPREFIX", prefix).spawn();
+    }
+
+    pub fn kill_wineserver(&self, prefix: &Path) {
+        let _ = Command::new("wineserver")
+            .arg("-k")
+            .env("WINEPREFIX", prefix)
+            .output();
+    }
+
+    pub fn execute_action(&self, action_id: &str) {
+        if let Some(prefix) = action_id.strip_prefix("wine:winecfg:") {
+            self.winecfg(Path::new(prefix));
+        } else if let Some(prefix) = action_id.strip_prefix("wine:kill:") {
+            self.kill_wineserver(Path::new(prefix));
+        }
+    }
+}
+
+impl Default for WineManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
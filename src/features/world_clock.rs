@@ -0,0 +1,337 @@
+//! World clock: current time in other cities ("time in tokyo") and time
+//! zone conversions ("3pm PST to IST").
+
+use crate::core::{Config, Item, ItemType};
+use chrono::{DateTime, FixedOffset, Local, NaiveTime, TimeZone, Utc};
+use chrono_tz::{Tz, TZ_VARIANTS};
+use regex::Regex;
+
+/// Common abbreviations, mapped to a zone of their region so daylight
+/// saving time is applied (PST in July is really PDT).
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("utc", "UTC"),
+    ("gmt", "UTC"),
+    ("pst", "America/Los_Angeles"),
+    ("pdt", "America/Los_Angeles"),
+    ("pt", "America/Los_Angeles"),
+    ("mst", "America/Denver"),
+    ("mdt", "America/Denver"),
+    ("cst", "America/Chicago"),
+    ("cdt", "America/Chicago"),
+    ("est", "America/New_York"),
+    ("edt", "America/New_York"),
+    ("et", "America/New_York"),
+    ("bst", "Europe/London"),
+    ("cet", "Europe/Berlin"),
+    ("cest", "Europe/Berlin"),
+    ("eet", "Europe/Athens"),
+    ("msk", "Europe/Moscow"),
+    ("ist", "Asia/Kolkata"),
+    ("sgt", "Asia/Singapore"),
+    ("hkt", "Asia/Hong_Kong"),
+    ("jst", "Asia/Tokyo"),
+    ("kst", "Asia/Seoul"),
+    ("aest", "Australia/Sydney"),
+    ("aedt", "Australia/Sydney"),
+    ("nzst", "Pacific/Auckland"),
+];
+
+/// Cities that aren't the name of their zone.
+const CITIES: &[(&str, &str)] = &[
+    ("san francisco", "America/Los_Angeles"),
+    ("seattle", "America/Los_Angeles"),
+    ("nyc", "America/New_York"),
+    ("boston", "America/New_York"),
+    ("washington", "America/New_York"),
+    ("miami", "America/New_York"),
+    ("austin", "America/Chicago"),
+    ("dallas", "America/Chicago"),
+    ("montreal", "America/Toronto"),
+    ("munich", "Europe/Berlin"),
+    ("frankfurt", "Europe/Berlin"),
+    ("hamburg", "Europe/Berlin"),
+    ("barcelona", "Europe/Madrid"),
+    ("milan", "Europe/Rome"),
+    ("geneva", "Europe/Zurich"),
+    ("delhi", "Asia/Kolkata"),
+    ("new delhi", "Asia/Kolkata"),
+    ("mumbai", "Asia/Kolkata"),
+    ("bangalore", "Asia/Kolkata"),
+    ("bengaluru", "Asia/Kolkata"),
+    ("chennai", "Asia/Kolkata"),
+    ("beijing", "Asia/Shanghai"),
+    ("osaka", "Asia/Tokyo"),
+    ("dubai", "Asia/Dubai"),
+    ("melbourne", "Australia/Melbourne"),
+];
+
+const DEFAULT_CITIES: &[&str] = &["UTC", "London", "New York", "Los Angeles", "Tokyo"];
+
+/// A resolved place: what the user typed, tidied up, and its zone.
+struct Place {
+    label: String,
+    /// None for the system's local zone
+    zone: Option<Tz>,
+}
+
+impl Place {
+    fn time_at(&self, instant: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self.zone {
+            Some(tz) => instant.with_timezone(&tz).fixed_offset(),
+            None => instant.with_timezone(&Local).fixed_offset(),
+        }
+    }
+}
+
+pub struct WorldClock {
+    cities: Vec<String>,
+    time_in: Regex,
+    conversion: Regex,
+}
+
+impl WorldClock {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            cities: config
+                .world_clock
+                .clone()
+                .unwrap_or_else(|| DEFAULT_CITIES.iter().map(|c| c.to_string()).collect()),
+            time_in: Regex::new(r"^(?:time|now)\s+(?:in\s+)?(.+)$|^(.+?)\s+time$").unwrap(),
+            conversion: Regex::new(
+                r"^(noon|midnight|now|\d{1,2}(?::\d{2})?\s*(?:am|pm)?)\s+([a-z_/ ]+?)\s+(?:to|in)\s+([a-z_/ ]+)$",
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Whether `query` should go to the world clock rather than the
+    /// converter ("3pm pst to ist", "time in tokyo").
+    pub fn is_time_query(query: &str) -> bool {
+        let clock = Self::default();
+        let query = query.trim().to_lowercase();
+        if let Some(caps) = clock.time_in.captures(&query) {
+            let place = caps.get(1).or(caps.get(2)).map_or("", |m| m.as_str());
+            return Self::resolve(place).is_some();
+        }
+        clock
+            .conversion
+            .captures(&query)
+            .is_some_and(|caps| Self::resolve(&caps[2]).is_some() && Self::resolve(&caps[3]).is_some())
+    }
+
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let query = query.trim().to_lowercase();
+        // "time in tokyo" arrives here as "in tokyo"
+        let query = query.strip_prefix("in ").unwrap_or(&query).to_string();
+        let now = Utc::now();
+
+        if query.is_empty() {
+            return self
+                .cities
+                .iter()
+                .filter_map(|city| Self::resolve(city))
+                .map(|place| Self::clock_item(&place, now))
+                .collect();
+        }
+
+        if let Some(caps) = self.conversion.captures(&query) {
+            if let (Some(from), Some(to)) = (Self::resolve(&caps[2]), Self::resolve(&caps[3])) {
+                if let Some(item) = Self::conversion_item(&caps[1], &from, &to) {
+                    return vec![item];
+                }
+            }
+        }
+
+        let place = match self.time_in.captures(&query) {
+            Some(caps) => caps.get(1).or(caps.get(2)).map_or("", |m| m.as_str()).to_string(),
+            None => query,
+        };
+        if let Some(place) = Self::resolve(&place) {
+            return vec![Self::clock_item(&place, now)];
+        }
+
+        // Partial city names from the zone database
+        TZ_VARIANTS
+            .iter()
+            .filter(|tz| Self::city_name(tz.name()).to_lowercase().contains(&place))
+            .take(10)
+            .map(|tz| {
+                let place = Place {
+                    label: Self::city_name(tz.name()),
+                    zone: Some(*tz),
+                };
+                Self::clock_item(&place, now)
+            })
+            .collect()
+    }
+
+    fn clock_item(place: &Place, now: DateTime<Utc>) -> Item {
+        let local = place.time_at(now);
+        let time = local.format("%H:%M").to_string();
+        let zone = match place.zone {
+            Some(tz) => tz.name().to_string(),
+            None => "Local time".to_string(),
+        };
+
+        let mut item = Item::new(
+            format!("clock:{}", place.label),
+            format!("{}: {}", place.label, time),
+            ItemType::WorldClock,
+        )
+        .with_description(format!(
+            "{} | {} (UTC{}) | Press Enter to copy",
+            local.format("%a, %b %-d"),
+            zone,
+            local.format("%:z")
+        ))
+        .with_icon("preferences-system-time");
+        item.metadata.content = Some(time);
+        item
+    }
+
+    fn conversion_item(time: &str, from: &Place, to: &Place) -> Option<Item> {
+        let now = Utc::now();
+        let instant = match from.zone {
+            Some(tz) => Self::at(&tz, now, time)?.with_timezone(&Utc),
+            None => Self::at(&Local, now, time)?.with_timezone(&Utc),
+        };
+        let source = from.time_at(instant);
+        let target = to.time_at(instant);
+        let target_24h = target.format("%H:%M").to_string();
+
+        let day_shift = match (target.date_naive() - source.date_naive()).num_days() {
+            0 => String::new(),
+            1 => " (next day)".to_string(),
+            -1 => " (previous day)".to_string(),
+            n => format!(" ({:+} days)", n),
+        };
+
+        let mut item = Item::new(
+            format!("clock:convert:{}", target_24h),
+            format!(
+                "{} {} = {} {}{}",
+                source.format("%-I:%M %p"),
+                from.label,
+                target.format("%-I:%M %p"),
+                to.label,
+                day_shift
+            ),
+            ItemType::WorldClock,
+        )
+        .with_description(format!("{} | Press Enter to copy", target_24h))
+        .with_icon("preferences-system-time");
+        item.metadata.content = Some(target_24h);
+        Some(item)
+    }
+
+    /// Today's date in `tz` at the given clock time ("3pm", "15:30", "noon").
+    fn at<Z: TimeZone>(tz: &Z, now: DateTime<Utc>, time: &str) -> Option<DateTime<Z>> {
+        let today = now.with_timezone(tz);
+        if time == "now" {
+            return Some(today);
+        }
+        let naive = Self::parse_time(time)?;
+        tz.from_local_datetime(&today.date_naive().and_time(naive)).earliest()
+    }
+
+    fn parse_time(time: &str) -> Option<NaiveTime> {
+        let time = time.replace(' ', "");
+        match time.as_str() {
+            "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+            "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+            _ => {}
+        }
+
+        let (clock, meridiem) = if let Some(t) = time.strip_suffix("am") {
+            (t, Some(false))
+        } else if let Some(t) = time.strip_suffix("pm") {
+            (t, Some(true))
+        } else {
+            (time.as_str(), None)
+        };
+        let (hour, minute) = match clock.split_once(':') {
+            Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+            None => (clock.parse::<u32>().ok()?, 0),
+        };
+        let hour = match meridiem {
+            Some(_) if hour == 0 || hour > 12 => return None,
+            Some(false) => hour % 12,
+            Some(true) => hour % 12 + 12,
+            None => hour,
+        };
+        NaiveTime::from_hms_opt(hour, minute, 0)
+    }
+
+    /// Resolves an abbreviation, city or IANA zone name.
+    fn resolve(name: &str) -> Option<Place> {
+        let key = name.trim().to_lowercase();
+        if key.is_empty() {
+            return None;
+        }
+        if matches!(key.as_str(), "local" | "here") {
+            return Some(Place {
+                label: "Local".to_string(),
+                zone: None,
+            });
+        }
+
+        let zone_name = ABBREVIATIONS
+            .iter()
+            .chain(CITIES)
+            .find(|(alias, _)| *alias == key)
+            .map(|(_, zone)| *zone);
+        let zone = match zone_name {
+            Some(zone) => zone.parse::<Tz>().ok(),
+            None => TZ_VARIANTS.iter().copied().find(|tz| {
+                tz.name().to_lowercase() == key || Self::city_name(tz.name()).to_lowercase() == key
+            }),
+        }?;
+
+        let label = if key.len() <= 4 && zone_name.is_some() {
+            key.to_uppercase()
+        } else {
+            key.split(' ')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars.next().map_or(String::new(), |c| c.to_uppercase().chain(chars).collect())
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        Some(Place { label, zone: Some(zone) })
+    }
+
+    /// "America/New_York" -> "New York"
+    fn city_name(zone: &str) -> String {
+        zone.rsplit('/').next().unwrap_or(zone).replace('_', " ")
+    }
+}
+
+impl Default for WorldClock {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(WorldClock::parse_time("3pm"), NaiveTime::from_hms_opt(15, 0, 0));
+        assert_eq!(WorldClock::parse_time("12am"), NaiveTime::from_hms_opt(0, 0, 0));
+        assert_eq!(WorldClock::parse_time("9:30 am"), NaiveTime::from_hms_opt(9, 30, 0));
+        assert_eq!(WorldClock::parse_time("17:45"), NaiveTime::from_hms_opt(17, 45, 0));
+        assert_eq!(WorldClock::parse_time("13pm"), None);
+    }
+
+    #[test]
+    fn test_time_queries() {
+        assert!(WorldClock::is_time_query("3pm PST to IST"));
+        assert!(WorldClock::is_time_query("time in tokyo"));
+        assert!(WorldClock::is_time_query("new york time"));
+        assert!(!WorldClock::is_time_query("100 km to mi"));
+        assert!(!WorldClock::is_time_query("5 usd to eur"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Frecent directories from zoxide's database, ranked as `z` ranks them in
+//! the shell. Enter browses the folder; the best match can also open in a
+//! terminal or the file manager.
+
+use super::DirectoryBrowser;
+use crate::core::config::ModeConfig;
+use crate::core::{CommandRunner, Config, Item, ItemType, SystemRunner};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct ZoxideManager {
+    options: ModeConfig,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl ZoxideManager {
+    pub fn new(config: &Config) -> Self {
+        Self::with_runner(config, SystemRunner::shared())
+    }
+
+    pub fn with_runner(config: &Config, runner: Arc<dyn CommandRunner>) -> Self {
+        Self {
+            options: config.mode("z"),
+            runner,
+        }
+    }
+
+    /// Each word has to match the path in order, the last one its final
+    /// component, like `z foo bar` in the shell.
+    pub fn get_items(&self, query: &str) -> Vec<Item> {
+        let mut args = vec!["query", "--list", "--score", "--"];
+        args.extend(query.split_whitespace());
+        let output = match self.runner.run("zoxide", &args) {
+            Ok(output) => output,
+            Err(e) => return Item::error_items("z", "zoxide is not available", format!("Failed to run zoxide: {}", e)),
+        };
+        // Exits with 1 and "no match found" when nothing matches
+        if !output.status.success() && !String::from_utf8_lossy(&output.stderr).contains("no match") {
+            return Item::command_error_items("z", "Can't query zoxide", &output);
+        }
+
+        let mut items: Vec<Item> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                // "  24.5 /home/alice/code/wlaunch"
+                let (score, path) = line.trim_start().split_once(' ')?;
+                let path = PathBuf::from(path);
+                let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().to_string());
+                let mut item = Item::new(format!("z:{}", path.display()), name, ItemType::Folder)
+                    .with_description(format!("{} (score {})", DirectoryBrowser::query_path(&path), score))
+                    .with_icon("folder");
+                item.metadata.path = Some(path);
+                Some(item)
+            })
+            .take(self.options.max_results.unwrap_or(50))
+            .collect();
+
+        let Some(best) = items.first().and_then(|item| item.metadata.path.clone()) else {
+            return vec![Item::new("z:none", format!("zoxide knows no folder matching \"{}\"", query.trim()), ItemType::Command)
+                .with_description("Folders are added as you cd into them in a shell with zoxide set up")
+                .with_icon("dialog-information")];
+        };
+        let shown = best.display().to_string();
+        items.insert(
+            1,
+            Item::new(format!("cd:terminal:{}", shown), "    Open terminal here", ItemType::FolderAction)
+                .with_description(shown.clone())
+                .with_icon("utilities-terminal"),
+        );
+        items.insert(
+            2,
+            Item::new(format!("cd:open:{}", shown), "    Open in file manager", ItemType::FolderAction)
+                .with_description(shown)
+                .with_icon("system-file-manager"),
+        );
+        items
+    }
+}
+
+impl Default for ZoxideManager {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::runner::FakeRunner;
+
+    #[test]
+    fn test_query() {
+        let runner = FakeRunner::new()
+            .with("zoxide query --list --score -- code", "  48.0 /srv/code/wlaunch\n  12.5 /srv/code\n   0.3 /srv/old code\n")
+            .failing("zoxide query --list --score -- nope", 1, "zoxide: no match found\n");
+        let zoxide = ZoxideManager::with_runner(&Config::default(), runner.shared());
+
+        let items = zoxide.get_items("code");
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["wlaunch", "    Open terminal here", "    Open in file manager", "code", "old code"]);
+        assert_eq!(items[1].id, "cd:terminal:/srv/code/wlaunch");
+        assert_eq!(items[4].metadata.path, Some(PathBuf::from("/srv/old code")));
+        assert_eq!(items[0].description.as_deref(), Some("/srv/code/wlaunch/ (score 48.0)"));
+
+        assert_eq!(zoxide.get_items("nope")[0].id, "z:none");
+    }
+}
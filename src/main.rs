@@ -2,14 +2,61 @@ mod core;
 mod features;
 mod ui;
 
+use core::Config;
 use iced::{window, Size};
 use std::env;
+use std::path::Path;
+use ui::dmenu::{Dmenu, DmenuOptions};
 use ui::WLaunch;
 
 fn main() -> iced::Result {
     env_logger::init();
 
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    // --profile <name> switches config and data to another directory, for
+    // every mode below
+    if let Some(i) = args.iter().position(|arg| arg == "--profile" || arg.starts_with("--profile=")) {
+        let name = match args[i].strip_prefix("--profile=") {
+            Some(name) => name.to_string(),
+            None if i + 1 < args.len() => args.remove(i + 1),
+            None => String::new(),
+        };
+        args.remove(i);
+        if !Config::is_profile_name(&name) {
+            eprintln!("Invalid profile name {:?}: use letters, digits, - and _", name);
+            std::process::exit(2);
+        }
+        Config::set_profile(&name);
+    }
+
+    // --kiosk allows searching and launching only, e.g. on a shared machine
+    if let Some(i) = args.iter().position(|arg| arg == "--kiosk") {
+        args.remove(i);
+        Config::set_kiosk();
+    }
+
+    // --dmenu, or `rofi -dmenu` and `dmenu` through a symlink named after
+    // them, so existing scripts keep working
+    let program = Path::new(&args[0]).file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    if args.iter().any(|arg| arg == "--dmenu")
+        || (program == "rofi" && args.iter().any(|arg| arg == "-dmenu"))
+        || program == "dmenu"
+    {
+        return run_dmenu(&args[1..]);
+    }
+
+    // Import rofi's modi, script modes and settings
+    if args.len() > 1 && args[1] == "migrate-rofi" {
+        match features::rofi::migrate(args.get(2).map(Path::new)) {
+            Ok(report) => println!("{}", report),
+            Err(e) => {
+                eprintln!("{:#}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
 
     // Check for daemon mode
     if args.len() > 1 && (args[1] == "--daemon" || args[1] == "-d") {
@@ -17,36 +64,136 @@ fn main() -> iced::Result {
         return Ok(());
     }
 
+    // Running timers for waybar (JSON) or polybar (--text)
+    if args.len() > 1 && args[1] == "timer-status" {
+        let timers = features::TimerManager::new();
+        if args.iter().any(|arg| arg == "--text") {
+            println!("{}", timers.status_text());
+        } else {
+            println!("{}", timers.status_json());
+        }
+        return Ok(());
+    }
+
+    // What the daemon is doing, read from the status file it keeps
+    if args.len() > 1 && args[1] == "daemon-status" {
+        match features::DaemonStatus::load().filter(|status| status.running()) {
+            Some(status) if args.iter().any(|arg| arg == "--json") => {
+                println!("{}", serde_json::to_string(&status).unwrap_or_default())
+            }
+            Some(status) => println!("{}", status.summary()),
+            None => {
+                eprintln!("The daemon isn't running (start it with wlaunch --daemon)");
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    // Spawned by the launcher, which exits before the launched app's window
+    // appears: place-window <workspace> <classes> [known window ids]
+    if args.len() > 3 && args[1] == "place-window" {
+        let list = |arg: Option<&String>| arg.map(|arg| arg.split(',').filter(|s| !s.is_empty()).map(str::to_string).collect::<Vec<_>>()).unwrap_or_default();
+        let known: Vec<i64> = list(args.get(4)).iter().filter_map(|id| id.parse().ok()).collect();
+        let windows = features::WindowsManager::new();
+        if !windows.place_new_window(&list(args.get(3)), &known, &args[2], std::time::Duration::from_secs(30)) {
+            log::debug!("No new {} window appeared", args[3]);
+        }
+        return Ok(());
+    }
+
+    // Read and change settings without editing config.json by hand:
+    // config get <key> | config set <key> <value> | config list
+    if args.len() > 1 && args[1] == "config" {
+        let result = match (args.get(2).map(String::as_str), args.get(3)) {
+            (Some("get"), Some(key)) if args.len() == 4 => core::settings::get(key).map(|value| match value {
+                serde_json::Value::String(text) => println!("{}", text),
+                value => println!("{}", value),
+            }),
+            (Some("set"), Some(key)) if args.len() > 4 => {
+                core::settings::set(key, &args[4..].join(" ")).map(|value| println!("{} = {}", key, value))
+            }
+            (Some("list"), None) => core::settings::list().map(|settings| {
+                use std::io::Write;
+                // Stops quietly when piped into head
+                let mut out = std::io::stdout().lock();
+                for (key, value) in settings {
+                    if writeln!(out, "{} = {}", key, value).is_err() {
+                        break;
+                    }
+                }
+            }),
+            _ => Err(anyhow::anyhow!("Usage: wlaunch config get <key> | set <key> <value> | list")),
+        };
+        if let Err(e) = result {
+            eprintln!("{:#}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Check optional dependencies
+    if args.len() > 1 && args[1] == "doctor" {
+        println!("{}", features::Doctor::new().report());
+        return Ok(());
+    }
+
     // Show help
     if args.len() > 1 && (args[1] == "--help" || args[1] == "-h") {
         print_help();
         return Ok(());
     }
 
+    let config = Config::load().unwrap_or_default();
+
     // Run the GUI launcher
     iced::application("WLaunch", WLaunch::update, WLaunch::view)
         .subscription(WLaunch::subscription)
         .theme(WLaunch::theme)
-        .window(window::Settings {
-            size: Size::new(800.0, 500.0),
-            position: window::Position::Centered,
-            resizable: false,
-            decorations: false,
-            transparent: true,
-            level: window::Level::AlwaysOnTop,
-            exit_on_close_request: true,
-            #[cfg(target_os = "linux")]
-            platform_specific: window::settings::PlatformSpecific {
-                application_id: "wlaunch".to_string(),
-                ..Default::default()
-            },
-            #[cfg(not(target_os = "linux"))]
-            platform_specific: Default::default(),
-            ..Default::default()
-        })
+        .window(window_settings(&config))
         .run_with(WLaunch::new)
 }
 
+fn window_settings(config: &Config) -> window::Settings {
+    window::Settings {
+        size: Size::new(800.0, 500.0),
+        position: window::Position::Centered,
+        resizable: false,
+        decorations: false,
+        transparent: !config.accessibility.reduced_transparency(),
+        level: window::Level::AlwaysOnTop,
+        exit_on_close_request: true,
+        #[cfg(target_os = "linux")]
+        platform_specific: window::settings::PlatformSpecific {
+            application_id: "wlaunch".to_string(),
+            ..Default::default()
+        },
+        #[cfg(not(target_os = "linux"))]
+        platform_specific: Default::default(),
+        ..Default::default()
+    }
+}
+
+/// Reads the entries from stdin, then shows the picker.
+fn run_dmenu(args: &[String]) -> iced::Result {
+    use std::io::Read;
+
+    let options = DmenuOptions::parse(args);
+    let mut input = String::new();
+    if let Err(e) = std::io::stdin().read_to_string(&mut input) {
+        eprintln!("Failed to read stdin: {}", e);
+    }
+    let entries = options.entries(&input);
+
+    let config = Config::load().unwrap_or_default();
+    ui::theme::apply_config(&config);
+    iced::application("WLaunch", Dmenu::update, Dmenu::view)
+        .subscription(Dmenu::subscription)
+        .theme(Dmenu::theme)
+        .window(window_settings(&config))
+        .run_with(move || Dmenu::new(options, entries))
+}
+
 fn print_help() {
     println!("WLaunch - A Raycast-like application launcher for Linux");
     println!();
@@ -54,22 +201,31 @@ fn print_help() {
     println!("    wlaunch [OPTIONS]");
     println!();
     println!("OPTIONS:");
-    println!("    -d, --daemon    Run clipboard monitor daemon in background");
-    println!("    -h, --help      Print this help message");
+    println!("    -d, --daemon        Run clipboard monitor, night mode scheduler and system alerts in background");
+    println!("    --profile <name>    Use a separate config and data directory (e.g. work, personal)");
+    println!("    --kiosk             Search and launch only: no changes, no clipboard or passwords");
+    println!("    --dmenu [flags]     Pick a line from stdin and print it, like rofi -dmenu (-p, -mesg, -format, ...)");
+    println!("    -h, --help          Print this help message");
     println!();
     println!("MODES:");
     println!("    (no args)       Launch the GUI application launcher");
-    println!("    --daemon        Monitor clipboard and save history");
+    println!("    --daemon        Monitor clipboard, save history, apply night mode, remind of due todos, finish timers, end focus sessions, index files and send system alerts");
+    println!("    config          Read and change settings: get <key>, set <key> <value>, list (keys like theme.preset)");
+    println!("    doctor          Report missing optional dependencies");
+    println!("    migrate-rofi    Import rofi's modi, script modes, terminal and theme [path to config.rasi]");
+    println!("    timer-status    Print running timers as waybar JSON, or plain with --text for polybar");
+    println!("    daemon-status   Print the daemon's clipboard captures and polling interval (--json for JSON)");
 }
 
 fn run_clipboard_daemon() {
     use arboard::Clipboard;
     use chrono::Local;
-    use core::Config;
+    use features::clipboard::{session_idle, ACTIVE_POLL, IDLE_POLL};
+    use features::{DaemonStatus, FileManager, FocusManager, NightModeManager, SystemAlerts, TimerManager, TodosManager};
     use serde::{Deserialize, Serialize};
     use std::fs;
     use std::thread;
-    use std::time::Duration;
+    use std::time::{Duration, Instant};
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
     struct ClipboardEntry {
@@ -100,8 +256,7 @@ fn run_clipboard_daemon() {
     println!("WLaunch clipboard daemon started");
     println!("Monitoring clipboard changes...");
 
-    let config = Config::load().unwrap_or_default();
-    let max_size = config.clipboard_history_size();
+    let mut config = Config::load().unwrap_or_default();
 
     let mut clipboard = match Clipboard::new() {
         Ok(c) => c,
@@ -113,8 +268,65 @@ fn run_clipboard_daemon() {
 
     let mut last_content = String::new();
     let mut history = load_history();
+    let mut last_night_check: Option<Instant> = None;
+    let mut timers = TimerManager::new();
+    let mut alerts = SystemAlerts::new(&config);
+    let mut focus = FocusManager::new(&config);
+    let mut files = FileManager::new(&config);
+    let mut index_build: Option<thread::JoinHandle<()>> = None;
+    let runner = core::SystemRunner::shared();
+    let mut status = DaemonStatus::new();
+    let mut last_idle_check: Option<Instant> = None;
+    status.save();
 
     loop {
+        // Polls less often while logind says the session is idle, to spare
+        // the battery. While idle it's re-checked on every poll, so copies
+        // are picked up promptly once the user is back
+        if status.idle || last_idle_check.is_none_or(|t| t.elapsed() >= Duration::from_secs(30)) {
+            let idle = session_idle(runner.as_ref()).unwrap_or(false);
+            if idle != status.idle {
+                log::debug!("Session {}, polling every {:?}", if idle { "idle" } else { "active" }, if idle { IDLE_POLL } else { ACTIVE_POLL });
+                status.idle = idle;
+                status.poll_ms = if idle { IDLE_POLL } else { ACTIVE_POLL }.as_millis() as u64;
+                status.save();
+            }
+            last_idle_check = Some(Instant::now());
+        }
+
+        // The config, night mode and todos are re-read every minute to pick
+        // up edits, overrides and todos added from the launcher; battery,
+        // disk space and temperature are checked as often, and focus
+        // sessions whose time is up are ended
+        if last_night_check.is_none_or(|t| t.elapsed() >= Duration::from_secs(60)) {
+            // A config.json that doesn't parse keeps the last good settings
+            match Config::load() {
+                Ok(loaded) => {
+                    config = loaded;
+                    alerts.set_config(&config);
+                    focus = FocusManager::new(&config);
+                    files = FileManager::new(&config);
+                }
+                Err(e) => log::warn!("Failed to reload the config, keeping the previous one: {}", e),
+            }
+            NightModeManager::new().tick();
+            TodosManager::new(&config).tick();
+            alerts.tick();
+            focus.tick();
+            // The file index is rebuilt in the background, as walking the
+            // folders can take a while
+            if files.index_stale() && index_build.as_ref().is_none_or(|build| build.is_finished()) {
+                let files = files.clone();
+                index_build = Some(thread::spawn(move || match files.build_index() {
+                    Ok(count) => log::debug!("Indexed {} files", count),
+                    Err(e) => log::error!("Failed to build the file index: {}", e),
+                }));
+            }
+            last_night_check = Some(Instant::now());
+        }
+        // Timers started in the launcher finish here once it has closed
+        timers.tick();
+
         if let Ok(content) = clipboard.get_text() {
             if !content.is_empty() && content != last_content {
                 last_content = content.clone();
@@ -130,15 +342,18 @@ fn run_clipboard_daemon() {
                 history.insert(0, entry);
 
                 // Trim to max size
+                let max_size = config.clipboard_history_size();
                 if history.len() > max_size {
                     history.truncate(max_size);
                 }
 
                 save_history(&history);
+                status.record_capture();
+                status.save();
                 log::debug!("Clipboard updated: {}", &content[..content.len().min(50)]);
             }
         }
 
-        thread::sleep(Duration::from_millis(500));
+        thread::sleep(Duration::from_millis(status.poll_ms));
     }
 }
@@ -0,0 +1,303 @@
+//! `wlaunch --dmenu`: picks one of the lines read from stdin and prints it,
+//! like `rofi -dmenu` and `dmenu`, so existing scripts can switch over.
+//! Exits with 0 after a choice and 1 when cancelled.
+
+use crate::ui::theme;
+use iced::keyboard;
+use iced::widget::{button, column, container, row, scrollable, text, text_input, Column};
+use iced::{Element, Length, Subscription, Task};
+
+/// rofi's dmenu flags; unknown ones (and their values) are ignored, so
+/// theming and layout flags don't break a script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DmenuOptions {
+    /// `-p`, shown before the input
+    pub prompt: Option<String>,
+    /// `-mesg`, shown below the input
+    pub message: Option<String>,
+    /// `-filter`, the initial query
+    pub filter: String,
+    /// `-sep`, the character between entries on stdin
+    pub separator: char,
+    /// `-format`: `s` entry, `i` index, `d` 1-based index, `q` quoted
+    /// entry, `f` query, `F` quoted query
+    pub format: String,
+    /// `-no-custom` (or `-only-match`): Enter only accepts listed entries
+    pub no_custom: bool,
+    /// `-password`: hides what is typed
+    pub password: bool,
+    /// `-selected-row`
+    pub selected_row: usize,
+}
+
+impl Default for DmenuOptions {
+    fn default() -> Self {
+        Self {
+            prompt: None,
+            message: None,
+            filter: String::new(),
+            separator: '\n',
+            format: "s".to_string(),
+            no_custom: false,
+            password: false,
+            selected_row: 0,
+        }
+    }
+}
+
+impl DmenuOptions {
+    /// Parses the flags after `--dmenu` (or rofi's `-dmenu`). dmenu's own
+    /// `-p`, `-i` and `-l` mean the same.
+    pub fn parse(args: &[String]) -> Self {
+        let mut options = Self::default();
+        let mut args = args.iter().peekable();
+        while let Some(arg) = args.next() {
+            // Every value-taking flag, known or not, consumes the next word
+            let mut value = || args.next_if(|next| !next.starts_with('-') || next.len() == 1).cloned();
+            match arg.as_str() {
+                "-dmenu" | "--dmenu" | "-i" | "-markup-rows" | "-sync" => {}
+                "-no-custom" | "-only-match" => options.no_custom = true,
+                "-password" => options.password = true,
+                "-p" => options.prompt = value(),
+                "-mesg" => options.message = value(),
+                "-filter" => options.filter = value().unwrap_or_default(),
+                "-format" => options.format = value().unwrap_or_else(|| "s".to_string()),
+                "-selected-row" => options.selected_row = value().and_then(|v| v.parse().ok()).unwrap_or(0),
+                "-sep" => {
+                    options.separator = match value().as_deref() {
+                        Some("\\0") => '\0',
+                        Some("\\n") | None => '\n',
+                        Some(sep) => sep.chars().next().unwrap_or('\n'),
+                    }
+                }
+                _ => {
+                    value();
+                }
+            }
+        }
+        options
+    }
+
+    /// Splits stdin into entries, dropping the empty last one.
+    pub fn entries(&self, input: &str) -> Vec<String> {
+        let mut entries: Vec<String> = input.split(self.separator).map(str::to_string).collect();
+        if entries.last().is_some_and(|last| last.is_empty()) {
+            entries.pop();
+        }
+        entries
+    }
+}
+
+/// Indices of the entries containing every word of `query`, ignoring case,
+/// in input order as rofi keeps them.
+pub fn matching(entries: &[String], query: &str) -> Vec<usize> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| {
+            let entry = entry.to_lowercase();
+            words.iter().all(|word| entry.contains(word))
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// What to print for the chosen entry (`index` into the entries), or for
+/// the typed query when nothing matched.
+pub fn output(format: &str, entries: &[String], index: Option<usize>, query: &str) -> String {
+    let entry = index.map_or(query, |i| entries[i].as_str());
+    format
+        .chars()
+        .map(|c| match c {
+            's' => entry.to_string(),
+            'i' => index.map_or("-1".to_string(), |i| i.to_string()),
+            'd' => index.map_or("0".to_string(), |i| (i + 1).to_string()),
+            'q' => shell_quote(entry),
+            'f' => query.to_string(),
+            'F' => shell_quote(query),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    QueryChanged(String),
+    Submit,
+    Choose(usize),
+    KeyPressed(keyboard::Key, keyboard::Modifiers),
+}
+
+pub struct Dmenu {
+    options: DmenuOptions,
+    entries: Vec<String>,
+    query: String,
+    /// Indices into `entries` of the rows shown
+    matches: Vec<usize>,
+    selected: usize,
+}
+
+impl Dmenu {
+    pub fn new(options: DmenuOptions, entries: Vec<String>) -> (Self, Task<Message>) {
+        let query = options.filter.clone();
+        let matches = matching(&entries, &query);
+        let selected = options.selected_row.min(matches.len().saturating_sub(1));
+        let dmenu = Self {
+            options,
+            entries,
+            query,
+            matches,
+            selected,
+        };
+        (dmenu, text_input::focus(text_input::Id::new("dmenu")))
+    }
+
+    pub fn theme(&self) -> iced::Theme {
+        theme::Theme::custom()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        keyboard::on_key_press(|key, modifiers| Some(Message::KeyPressed(key, modifiers)))
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::QueryChanged(query) => {
+                self.query = query;
+                self.matches = matching(&self.entries, &self.query);
+                self.selected = 0;
+                Task::none()
+            }
+            Message::Submit => self.choose(self.matches.get(self.selected).copied()),
+            Message::Choose(row) => self.choose(self.matches.get(row).copied()),
+            Message::KeyPressed(key, modifiers) => match key.as_ref() {
+                keyboard::Key::Named(keyboard::key::Named::Escape) => std::process::exit(1),
+                keyboard::Key::Named(keyboard::key::Named::ArrowDown) => self.move_selection(1),
+                keyboard::Key::Named(keyboard::key::Named::ArrowUp) => self.move_selection(-1),
+                keyboard::Key::Named(keyboard::key::Named::Tab) => {
+                    self.move_selection(if modifiers.shift() { -1 } else { 1 })
+                }
+                _ => Task::none(),
+            },
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) -> Task<Message> {
+        if self.matches.is_empty() {
+            return Task::none();
+        }
+        let len = self.matches.len() as isize;
+        self.selected = (self.selected as isize + delta).rem_euclid(len) as usize;
+        // Each row is approximately 36px (34px height + 2px spacing)
+        scrollable::scroll_to(
+            scrollable::Id::new("dmenu-results"),
+            scrollable::AbsoluteOffset { x: 0.0, y: self.selected as f32 * 36.0 },
+        )
+    }
+
+    /// Prints the entry, or the typed text unless `-no-custom`, and exits.
+    fn choose(&self, index: Option<usize>) -> Task<Message> {
+        if index.is_none() && (self.options.no_custom || self.query.is_empty()) {
+            return Task::none();
+        }
+        println!("{}", output(&self.options.format, &self.entries, index, &self.query));
+        std::process::exit(0)
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let input = text_input("Search...", &self.query)
+            .on_input(Message::QueryChanged)
+            .on_submit(Message::Submit)
+            .secure(self.options.password)
+            .padding(15)
+            .size(18)
+            .id(text_input::Id::new("dmenu"))
+            .style(theme::search_input);
+
+        let search: Element<Message> = match &self.options.prompt {
+            Some(prompt) => row![text(prompt.clone()).size(16).style(theme::accent_text), input]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+                .into(),
+            None => input.into(),
+        };
+        let mut header = column![search].spacing(8);
+        if let Some(message) = &self.options.message {
+            header = header.push(text(message.clone()).size(13).style(theme::secondary_text));
+        }
+
+        let rows: Vec<Element<Message>> = self
+            .matches
+            .iter()
+            .enumerate()
+            .map(|(row, &index)| {
+                let selected = row == self.selected;
+                button(text(self.entries[index].clone()).size(14))
+                    .width(Length::Fill)
+                    .padding([8, 12])
+                    .on_press(Message::Choose(row))
+                    .style(move |theme, status| match status {
+                        button::Status::Hovered => theme::item_button_hover(theme),
+                        _ => theme::item_button(theme, selected),
+                    })
+                    .into()
+            })
+            .collect();
+        let results = scrollable(Column::with_children(rows).spacing(2))
+            .id(scrollable::Id::new("dmenu-results"))
+            .height(Length::Fill)
+            .style(theme::scrollable_style);
+
+        let content = column![
+            container(header).width(Length::Fill).padding(10).style(theme::search_container),
+            container(results).width(Length::Fill).height(Length::Fill).padding(5).style(theme::results_container),
+        ]
+        .spacing(10)
+        .padding(10);
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme::main_container)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split(' ').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_options() {
+        let options = DmenuOptions::parse(&args("-dmenu -i -p Run: -theme-str window{width:50%;} -l 10 -no-custom -format i"));
+        assert_eq!(options.prompt.as_deref(), Some("Run:"));
+        assert!(options.no_custom);
+        assert_eq!(options.format, "i");
+
+        let options = DmenuOptions::parse(&args("-sep | -selected-row 2"));
+        assert_eq!(options.entries("a|b|c"), ["a", "b", "c"]);
+        assert_eq!(options.selected_row, 2);
+    }
+
+    #[test]
+    fn test_matching() {
+        let entries = DmenuOptions::default().entries("Firefox\nFiles\nthunderbird\n");
+        assert_eq!(matching(&entries, ""), [0, 1, 2]);
+        assert_eq!(matching(&entries, "fi"), [0, 1]);
+        assert_eq!(matching(&entries, "BIRD thun"), [2]);
+
+        assert_eq!(output("s", &entries, Some(1), "fi"), "Files");
+        assert_eq!(output("d:f", &entries, Some(1), "fi"), "2:fi");
+        assert_eq!(output("i", &entries, None, "it's new"), "-1");
+        assert_eq!(output("q", &entries, None, "it's new"), "'it'\\''s new'");
+    }
+}
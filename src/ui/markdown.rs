@@ -0,0 +1,250 @@
+//! Just enough markdown for notes, snippets and AI answers: headings, lists,
+//! quotes, rules, fenced code blocks, and bold, italic and inline code.
+//! Anything else is shown as typed, and line breaks are kept since notes are
+//! written line by line.
+
+use crate::ui::theme;
+use iced::font::{Style, Weight};
+use iced::widget::{column, container, horizontal_rule, rich_text, row, span, text, Column};
+use iced::{Border, Element, Font};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    /// Marker ("•" or "1."), nesting depth and text
+    ListItem(String, usize, String),
+    Quote(String),
+    Code(String),
+    Rule,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Inline {
+    Plain,
+    Bold,
+    Italic,
+    Code,
+}
+
+fn parse(source: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<Vec<&str>> = None;
+
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(paragraph.join("\n")));
+            paragraph.clear();
+        }
+    };
+
+    for line in source.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            match code.take() {
+                Some(lines) => blocks.push(Block::Code(lines.join("\n"))),
+                None => {
+                    flush(&mut paragraph, &mut blocks);
+                    code = Some(Vec::new());
+                }
+            }
+            continue;
+        }
+        if let Some(lines) = &mut code {
+            lines.push(line);
+            continue;
+        }
+
+        let indent = (line.len() - trimmed.len()) / 2;
+        let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+        let numbered = trimmed
+            .split_once(". ")
+            .filter(|(number, _)| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()));
+
+        let block = if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+            continue;
+        } else if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            Block::Heading(hashes, trimmed[hashes..].trim().to_string())
+        } else if ["---", "***", "___"].contains(&trimmed.trim_end()) {
+            Block::Rule
+        } else if let Some(item) = ["- ", "* ", "+ "].iter().find_map(|m| trimmed.strip_prefix(m)) {
+            Block::ListItem("•".to_string(), indent, item.to_string())
+        } else if let Some((number, item)) = numbered {
+            Block::ListItem(format!("{}.", number), indent, item.to_string())
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            Block::Quote(quote.trim_start().to_string())
+        } else {
+            paragraph.push(line);
+            continue;
+        };
+        flush(&mut paragraph, &mut blocks);
+        blocks.push(block);
+    }
+
+    // An unclosed fence runs to the end, as while an answer is streaming
+    if let Some(lines) = code {
+        blocks.push(Block::Code(lines.join("\n")));
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+/// `**bold**`, `*italic*` or `_italic_`, and `` `code` ``. Markers only count
+/// next to text, so `2 * 3 * 4` stays as it is.
+fn parse_inline(text: &str) -> Vec<(String, Inline)> {
+    let mut spans: Vec<(String, Inline)> = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let marker = match c {
+            '`' => Some(("`", Inline::Code)),
+            '*' if rest.starts_with("**") => Some(("**", Inline::Bold)),
+            '_' if rest.starts_with("__") => Some(("__", Inline::Bold)),
+            '*' => Some(("*", Inline::Italic)),
+            '_' if !plain.ends_with(|c: char| c.is_alphanumeric()) => Some(("_", Inline::Italic)),
+            _ => None,
+        };
+        let closed = marker.and_then(|(marker, kind)| {
+            let inner = &rest[marker.len()..];
+            let end = inner.find(marker)?;
+            let content = &inner[..end];
+            let padded = content.starts_with(' ') || content.ends_with(' ');
+            (!content.is_empty() && (kind == Inline::Code || !padded)).then_some((marker, kind, content))
+        });
+
+        match closed {
+            Some((marker, kind, content)) => {
+                if !plain.is_empty() {
+                    spans.push((std::mem::take(&mut plain), Inline::Plain));
+                }
+                spans.push((content.to_string(), kind));
+                rest = &rest[marker.len() * 2 + content.len()..];
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !plain.is_empty() {
+        spans.push((plain, Inline::Plain));
+    }
+    spans
+}
+
+fn inline<'a, Message: Clone + 'static>(source: &str, size: f32, base: Font) -> Element<'a, Message> {
+    let palette = theme::palette();
+    let spans: Vec<_> = parse_inline(source)
+        .into_iter()
+        .map(|(content, kind)| {
+            let span = span(content).size(size);
+            match kind {
+                Inline::Plain => span.font(base),
+                Inline::Bold => span.font(Font {
+                    weight: Weight::Bold,
+                    ..base
+                }),
+                Inline::Italic => span.font(Font {
+                    style: Style::Italic,
+                    ..base
+                }),
+                Inline::Code => span.font(Font::MONOSPACE).background(palette.surface).padding([0, 3]),
+            }
+        })
+        .collect();
+    rich_text(spans).color(palette.text_primary).into()
+}
+
+/// `source` rendered as a column of blocks, with body text at `size`.
+pub fn view<'a, Message: Clone + 'static>(source: &str, size: f32) -> Element<'a, Message> {
+    let palette = theme::palette();
+    let mut blocks = Column::new().spacing(8);
+
+    for block in parse(source) {
+        let element: Element<'a, Message> = match block {
+            Block::Heading(level, heading) => {
+                let size = size + (8.0 - 2.0 * level as f32).max(0.0) + 2.0;
+                let bold = Font {
+                    weight: Weight::Bold,
+                    ..Font::default()
+                };
+                inline(&heading, size, bold)
+            }
+            Block::Paragraph(paragraph) => inline(&paragraph, size, Font::default()),
+            Block::ListItem(marker, depth, item) => row![
+                text(marker).size(size).style(theme::accent_text),
+                inline(&item, size, Font::default()),
+            ]
+            .spacing(6)
+            .padding(iced::Padding::ZERO.left(depth as f32 * 16.0))
+            .into(),
+            Block::Quote(quote) => row![
+                text("│").size(size).style(theme::accent_text),
+                text(quote).size(size).font(Font { style: Style::Italic, ..Font::default() }).style(theme::secondary_text),
+            ]
+            .spacing(8)
+            .into(),
+            Block::Code(code) => container(text(code).size(size - 1.0).font(Font::MONOSPACE))
+                .padding(8)
+                .width(iced::Length::Fill)
+                .style(move |_| container::Style {
+                    background: Some(palette.surface.into()),
+                    border: Border {
+                        color: palette.border,
+                        width: 1.0,
+                        radius: 6.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into(),
+            Block::Rule => column![horizontal_rule(1)].into(),
+        };
+        blocks = blocks.push(element);
+    }
+    blocks.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let blocks = parse("# Title\nfirst\nsecond\n\n- one\n  - nested\n2. two\n> quoted\n```rust\nlet x = 1;\n```\n---");
+        assert_eq!(
+            blocks,
+            [
+                Block::Heading(1, "Title".to_string()),
+                Block::Paragraph("first\nsecond".to_string()),
+                Block::ListItem("•".to_string(), 0, "one".to_string()),
+                Block::ListItem("•".to_string(), 1, "nested".to_string()),
+                Block::ListItem("2.".to_string(), 0, "two".to_string()),
+                Block::Quote("quoted".to_string()),
+                Block::Code("let x = 1;".to_string()),
+                Block::Rule,
+            ]
+        );
+        assert_eq!(parse("```\nstreaming"), [Block::Code("streaming".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_inline() {
+        let spans = parse_inline("a **bold** *it* `x * y` 2 * 3 * 4 snake_case_name");
+        let kinds: Vec<(&str, Inline)> = spans.iter().map(|(s, k)| (s.as_str(), *k)).collect();
+        assert_eq!(
+            kinds,
+            [
+                ("a ", Inline::Plain),
+                ("bold", Inline::Bold),
+                (" ", Inline::Plain),
+                ("it", Inline::Italic),
+                (" ", Inline::Plain),
+                ("x * y", Inline::Code),
+                (" 2 * 3 * 4 snake_case_name", Inline::Plain),
+            ]
+        );
+    }
+}
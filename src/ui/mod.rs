@@ -1,3 +1,5 @@
+pub mod dmenu;
+pub mod markdown;
 pub mod theme;
 pub mod window;
 
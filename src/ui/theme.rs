@@ -1,28 +1,212 @@
-use iced::widget::{button, container, scrollable, text, text_input};
+use crate::core::config::{ThemeConfig, DEFAULT_PROFILE};
+use crate::core::Config;
+use iced::widget::{button, container, progress_bar, scrollable, text, text_editor, text_input};
 use iced::{Background, Border, Color, Theme as IcedTheme};
+use std::sync::RwLock;
 
-// Colors matching the original wlaunch dark theme
-pub const BACKGROUND: Color = Color::from_rgb(0.118, 0.118, 0.118); // #1e1e1e
-pub const SURFACE: Color = Color::from_rgb(0.157, 0.157, 0.157); // #282828
-pub const ACCENT: Color = Color::from_rgb(0.8, 0.4, 0.2); // #cc6633
-pub const TEXT_PRIMARY: Color = Color::from_rgb(0.933, 0.933, 0.933); // #eeeeee
-pub const TEXT_SECONDARY: Color = Color::from_rgb(0.6, 0.6, 0.6); // #999999
-pub const BORDER: Color = Color::from_rgb(0.25, 0.25, 0.25); // #404040
-pub const SELECTED: Color = Color::from_rgb(0.8, 0.4, 0.2); // #cc6633
-pub const HOVER: Color = Color::from_rgb(0.2, 0.2, 0.2); // #333333
+/// Colors used by every widget style in the launcher.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub background: Color,
+    pub surface: Color,
+    pub accent: Color,
+    pub text_primary: Color,
+    pub text_secondary: Color,
+    pub border: Color,
+    pub selected: Color,
+    pub hover: Color,
+    pub success: Color,
+    pub danger: Color,
+}
+
+const fn hex(rgb: u32) -> Color {
+    Color::from_rgb(
+        ((rgb >> 16) & 0xff) as f32 / 255.0,
+        ((rgb >> 8) & 0xff) as f32 / 255.0,
+        (rgb & 0xff) as f32 / 255.0,
+    )
+}
+
+impl Palette {
+    // Colors matching the original wlaunch dark theme
+    pub const DARK: Palette = Palette {
+        background: hex(0x1e1e1e),
+        surface: hex(0x282828),
+        accent: hex(0xcc6633),
+        text_primary: hex(0xeeeeee),
+        text_secondary: hex(0x999999),
+        border: hex(0x404040),
+        selected: hex(0xcc6633),
+        hover: hex(0x333333),
+        success: hex(0x66cc66),
+        danger: hex(0xcc4d4d),
+    };
+
+    pub const LIGHT: Palette = Palette {
+        background: hex(0xf5f5f5),
+        surface: hex(0xffffff),
+        accent: hex(0xb3541e),
+        text_primary: hex(0x1e1e1e),
+        text_secondary: hex(0x666666),
+        border: hex(0xd0d0d0),
+        selected: hex(0xf0c8b0),
+        hover: hex(0xe6e6e6),
+        success: hex(0x2e8b3e),
+        danger: hex(0xc0392b),
+    };
+
+    // Catppuccin Mocha
+    pub const CATPPUCCIN: Palette = Palette {
+        background: hex(0x1e1e2e),
+        surface: hex(0x313244),
+        accent: hex(0xcba6f7),
+        text_primary: hex(0xcdd6f4),
+        text_secondary: hex(0xa6adc8),
+        border: hex(0x45475a),
+        selected: hex(0x585b70),
+        hover: hex(0x45475a),
+        success: hex(0xa6e3a1),
+        danger: hex(0xf38ba8),
+    };
+
+    // Gruvbox dark
+    pub const GRUVBOX: Palette = Palette {
+        background: hex(0x282828),
+        surface: hex(0x3c3836),
+        accent: hex(0xfe8019),
+        text_primary: hex(0xebdbb2),
+        text_secondary: hex(0xa89984),
+        border: hex(0x504945),
+        selected: hex(0xd65d0e),
+        hover: hex(0x32302f),
+        success: hex(0xb8bb26),
+        danger: hex(0xfb4934),
+    };
+
+    // Pure black/white/yellow, WCAG AAA contrast for all text
+    pub const HIGH_CONTRAST: Palette = Palette {
+        background: hex(0x000000),
+        surface: hex(0x000000),
+        accent: hex(0xffff00),
+        text_primary: hex(0xffffff),
+        text_secondary: hex(0xe0e0e0),
+        border: hex(0xffffff),
+        selected: hex(0x0000c0),
+        hover: hex(0x303030),
+        success: hex(0x00ff00),
+        danger: hex(0xff6060),
+    };
+
+    /// Built-in presets selectable by name.
+    pub const PRESETS: &'static [(&'static str, Palette)] = &[
+        ("dark", Palette::DARK),
+        ("light", Palette::LIGHT),
+        ("catppuccin", Palette::CATPPUCCIN),
+        ("gruvbox", Palette::GRUVBOX),
+        ("high-contrast", Palette::HIGH_CONTRAST),
+    ];
+
+    pub fn preset(name: &str) -> Option<Palette> {
+        let name = name.to_lowercase();
+        Self::PRESETS
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .map(|(_, palette)| *palette)
+    }
+
+    /// Builds a palette from the `theme` config section: the named preset
+    /// (dark by default) with any individually configured colors on top.
+    pub fn from_config(config: &ThemeConfig) -> Palette {
+        let mut palette = config
+            .preset
+            .as_deref()
+            .and_then(Self::preset)
+            .unwrap_or(Self::DARK);
+
+        let overrides = [
+            (&config.background, &mut palette.background),
+            (&config.surface, &mut palette.surface),
+            (&config.accent, &mut palette.accent),
+            (&config.text, &mut palette.text_primary),
+            (&config.text_secondary, &mut palette.text_secondary),
+            (&config.border, &mut palette.border),
+            (&config.selection, &mut palette.selected),
+            (&config.hover, &mut palette.hover),
+        ];
+        for (value, color) in overrides {
+            if let Some(parsed) = value.as_deref().and_then(Color::parse) {
+                *color = parsed;
+            }
+        }
+
+        palette
+    }
+
+    /// Drops any alpha from the configured colors.
+    pub fn opaque(mut self) -> Palette {
+        for color in [
+            &mut self.background,
+            &mut self.surface,
+            &mut self.accent,
+            &mut self.text_primary,
+            &mut self.text_secondary,
+            &mut self.border,
+            &mut self.selected,
+            &mut self.hover,
+            &mut self.success,
+            &mut self.danger,
+        ] {
+            color.a = 1.0;
+        }
+        self
+    }
+}
+
+static PALETTE: RwLock<Palette> = RwLock::new(Palette::DARK);
+
+/// Window border color marking the active profile, if not the default one.
+static PROFILE_COLOR: RwLock<Option<Color>> = RwLock::new(None);
+
+/// Returns the palette currently used by the widget styles.
+pub fn palette() -> Palette {
+    *PALETTE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Switches the active palette; takes effect on the next redraw.
+pub fn set_palette(palette: Palette) {
+    *PALETTE.write().unwrap_or_else(|e| e.into_inner()) = palette;
+}
+
+/// Applies the theme and accessibility settings from `config`.
+pub fn apply_config(config: &Config) {
+    let palette = Palette::from_config(&config.theme);
+    let profile_color = match config.profile_color.as_deref() {
+        Some(color) => Color::parse(color),
+        None if Config::profile() != DEFAULT_PROFILE => Some(palette.accent),
+        None => None,
+    };
+    *PROFILE_COLOR.write().unwrap_or_else(|e| e.into_inner()) = profile_color;
+
+    if config.accessibility.reduced_transparency() {
+        set_palette(palette.opaque());
+    } else {
+        set_palette(palette);
+    }
+}
 
 pub struct Theme;
 
 impl Theme {
     pub fn custom() -> IcedTheme {
+        let palette = palette();
         IcedTheme::custom(
             "WLaunch".to_string(),
             iced::theme::Palette {
-                background: BACKGROUND,
-                text: TEXT_PRIMARY,
-                primary: ACCENT,
-                success: Color::from_rgb(0.4, 0.8, 0.4),
-                danger: Color::from_rgb(0.8, 0.3, 0.3),
+                background: palette.background,
+                text: palette.text_primary,
+                primary: palette.accent,
+                success: palette.success,
+                danger: palette.danger,
             },
         )
     }
@@ -31,11 +215,13 @@ impl Theme {
 // Container styles
 pub fn main_container(theme: &IcedTheme) -> container::Style {
     let _ = theme;
+    let palette = palette();
+    let profile_color = *PROFILE_COLOR.read().unwrap_or_else(|e| e.into_inner());
     container::Style {
-        background: Some(Background::Color(BACKGROUND)),
+        background: Some(Background::Color(palette.background)),
         border: Border {
-            color: BORDER,
-            width: 1.0,
+            color: profile_color.unwrap_or(palette.border),
+            width: if profile_color.is_some() { 2.0 } else { 1.0 },
             radius: 12.0.into(),
         },
         ..Default::default()
@@ -44,10 +230,11 @@ pub fn main_container(theme: &IcedTheme) -> container::Style {
 
 pub fn search_container(theme: &IcedTheme) -> container::Style {
     let _ = theme;
+    let palette = palette();
     container::Style {
-        background: Some(Background::Color(SURFACE)),
+        background: Some(Background::Color(palette.surface)),
         border: Border {
-            color: BORDER,
+            color: palette.border,
             width: 1.0,
             radius: 8.0.into(),
         },
@@ -57,18 +244,34 @@ pub fn search_container(theme: &IcedTheme) -> container::Style {
 
 pub fn results_container(theme: &IcedTheme) -> container::Style {
     let _ = theme;
+    let palette = palette();
     container::Style {
-        background: Some(Background::Color(BACKGROUND)),
+        background: Some(Background::Color(palette.background)),
         ..Default::default()
     }
 }
 
 pub fn details_container(theme: &IcedTheme) -> container::Style {
     let _ = theme;
+    let palette = palette();
     container::Style {
-        background: Some(Background::Color(SURFACE)),
+        background: Some(Background::Color(palette.surface)),
         border: Border {
-            color: BORDER,
+            color: palette.border,
+            width: 1.0,
+            radius: 8.0.into(),
+        },
+        ..Default::default()
+    }
+}
+
+/// Outlined pill around a short label, e.g. result type badges.
+pub fn badge(theme: &IcedTheme) -> container::Style {
+    let _ = theme;
+    let palette = palette();
+    container::Style {
+        border: Border {
+            color: palette.accent,
             width: 1.0,
             radius: 8.0.into(),
         },
@@ -79,10 +282,11 @@ pub fn details_container(theme: &IcedTheme) -> container::Style {
 // Button styles
 pub fn item_button(theme: &IcedTheme, selected: bool) -> button::Style {
     let _ = theme;
+    let palette = palette();
     if selected {
         button::Style {
-            background: Some(Background::Color(SELECTED)),
-            text_color: TEXT_PRIMARY,
+            background: Some(Background::Color(palette.selected)),
+            text_color: palette.text_primary,
             border: Border {
                 radius: 6.0.into(),
                 ..Default::default()
@@ -92,7 +296,7 @@ pub fn item_button(theme: &IcedTheme, selected: bool) -> button::Style {
     } else {
         button::Style {
             background: Some(Background::Color(Color::TRANSPARENT)),
-            text_color: TEXT_PRIMARY,
+            text_color: palette.text_primary,
             border: Border {
                 radius: 6.0.into(),
                 ..Default::default()
@@ -104,9 +308,10 @@ pub fn item_button(theme: &IcedTheme, selected: bool) -> button::Style {
 
 pub fn item_button_hover(theme: &IcedTheme) -> button::Style {
     let _ = theme;
+    let palette = palette();
     button::Style {
-        background: Some(Background::Color(HOVER)),
-        text_color: TEXT_PRIMARY,
+        background: Some(Background::Color(palette.hover)),
+        text_color: palette.text_primary,
         border: Border {
             radius: 6.0.into(),
             ..Default::default()
@@ -115,33 +320,66 @@ pub fn item_button_hover(theme: &IcedTheme) -> button::Style {
     }
 }
 
+// Progress bar style
+pub fn progress_bar_style(theme: &IcedTheme) -> progress_bar::Style {
+    let _ = theme;
+    let palette = palette();
+    progress_bar::Style {
+        background: Background::Color(palette.border),
+        bar: Background::Color(palette.accent),
+        border: Border {
+            radius: 2.0.into(),
+            ..Default::default()
+        },
+    }
+}
+
 // Text input style
 pub fn search_input(theme: &IcedTheme, _status: text_input::Status) -> text_input::Style {
     let _ = theme;
+    let palette = palette();
     text_input::Style {
-        background: Background::Color(SURFACE),
+        background: Background::Color(palette.surface),
         border: Border {
-            color: BORDER,
+            color: palette.border,
             width: 1.0,
             radius: 8.0.into(),
         },
-        icon: TEXT_SECONDARY,
-        placeholder: TEXT_SECONDARY,
-        value: TEXT_PRIMARY,
-        selection: ACCENT,
+        icon: palette.text_secondary,
+        placeholder: palette.text_secondary,
+        value: palette.text_primary,
+        selection: palette.accent,
+    }
+}
+
+pub fn note_editor(theme: &IcedTheme, _status: text_editor::Status) -> text_editor::Style {
+    let _ = theme;
+    let palette = palette();
+    text_editor::Style {
+        background: Background::Color(palette.background),
+        border: Border {
+            color: palette.border,
+            width: 1.0,
+            radius: 8.0.into(),
+        },
+        icon: palette.text_secondary,
+        placeholder: palette.text_secondary,
+        value: palette.text_primary,
+        selection: palette.accent,
     }
 }
 
 // Scrollable style
 pub fn scrollable_style(theme: &IcedTheme, _status: scrollable::Status) -> scrollable::Style {
     let _ = theme;
+    let palette = palette();
     scrollable::Style {
         container: container::Style::default(),
         vertical_rail: scrollable::Rail {
-            background: Some(Background::Color(SURFACE)),
+            background: Some(Background::Color(palette.surface)),
             border: Border::default(),
             scroller: scrollable::Scroller {
-                color: BORDER,
+                color: palette.border,
                 border: Border {
                     radius: 4.0.into(),
                     ..Default::default()
@@ -149,10 +387,10 @@ pub fn scrollable_style(theme: &IcedTheme, _status: scrollable::Status) -> scrol
             },
         },
         horizontal_rail: scrollable::Rail {
-            background: Some(Background::Color(SURFACE)),
+            background: Some(Background::Color(palette.surface)),
             border: Border::default(),
             scroller: scrollable::Scroller {
-                color: BORDER,
+                color: palette.border,
                 border: Border {
                     radius: 4.0.into(),
                     ..Default::default()
@@ -164,20 +402,15 @@ pub fn scrollable_style(theme: &IcedTheme, _status: scrollable::Status) -> scrol
 }
 
 // Text styles
-pub fn primary_text(_theme: &IcedTheme) -> text::Style {
-    text::Style {
-        color: Some(TEXT_PRIMARY),
-    }
-}
 
 pub fn secondary_text(_theme: &IcedTheme) -> text::Style {
     text::Style {
-        color: Some(TEXT_SECONDARY),
+        color: Some(palette().text_secondary),
     }
 }
 
 pub fn accent_text(_theme: &IcedTheme) -> text::Style {
     text::Style {
-        color: Some(ACCENT),
+        color: Some(palette().accent),
     }
 }
@@ -1,22 +1,119 @@
-use crate::core::{Config, Indexer, Item, ItemType};
+use crate::core::config::{ContextRule, RowRule};
+use crate::core::{settings, Config, Indexer, Item, ItemType};
 use crate::features::*;
-use crate::ui::theme;
+use crate::ui::{markdown, theme, Theme};
 use iced::widget::{
-    button, column, container, horizontal_space, image, row, scrollable, svg, text, text_input,
-    Column, Row,
+    button, checkbox, column, container, horizontal_space, image, mouse_area, progress_bar, row, scrollable, svg, text, text_editor,
+    text_input, Column, Row,
 };
 
 // Scrollable ID for auto-scrolling
 fn results_scrollable_id() -> scrollable::Id {
     scrollable::Id::new("results_list")
 }
+
+fn ai_chat_scrollable_id() -> scrollable::Id {
+    scrollable::Id::new("ai_chat")
+}
+
+fn details_scrollable_id() -> scrollable::Id {
+    scrollable::Id::new("details")
+}
+
+fn snippet_field_id(index: usize) -> text_input::Id {
+    text_input::Id::new(format!("snippet_field_{}", index))
+}
+
+fn share_login_id(field: &str) -> text_input::Id {
+    text_input::Id::new(format!("share_login_{}", field))
+}
+
+fn file_input_id() -> text_input::Id {
+    text_input::Id::new("file_input")
+}
 use iced::{
-    event, keyboard, window, Element, Event, Length, Subscription, Task,
+    event, keyboard, window, Color, Element, Event, Length, Subscription, Task,
 };
-use std::process::Command;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
+/// How long typing has to pause before slow modes shell out again.
+const FETCH_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Descriptions longer than this get "Copy all" and "Select text".
+const LONG_DESCRIPTION: usize = 120;
+
+/// Item shown at the top of the app list until indexing finishes.
+const INDEXING_ID: &str = "index:apps";
+
+/// The selected note's text while it is being edited.
+struct NoteEditor {
+    item_id: String,
+    content: text_editor::Content,
+    /// Whether the editor is shown instead of the rendered note
+    editing: bool,
+    dirty: bool,
+}
+
+/// The selected item's text in a read-only editor, so it can be selected
+/// and copied with the mouse.
+struct DetailsText {
+    item_id: String,
+    source: String,
+    content: text_editor::Content,
+    /// Whether the editor is shown instead of the rendered text
+    selecting: bool,
+}
+
+impl DetailsText {
+    /// Brings `details` in step with the selected item's id and text,
+    /// rebuilding the editor only when either has changed.
+    fn sync(details: &mut Option<DetailsText>, selected: Option<(&str, &str)>) {
+        let Some((item_id, source)) = selected else {
+            *details = None;
+            return;
+        };
+        match details.as_mut().filter(|details| details.item_id == item_id) {
+            Some(details) if details.source == source => {}
+            Some(details) => {
+                details.content = text_editor::Content::with_text(source);
+                details.source = source.to_string();
+            }
+            None => {
+                *details = Some(DetailsText {
+                    item_id: item_id.to_string(),
+                    source: source.to_string(),
+                    content: text_editor::Content::with_text(source),
+                    selecting: false,
+                });
+            }
+        }
+    }
+
+    /// Read-only: selecting, moving and copying, but no edits.
+    fn perform(&mut self, action: text_editor::Action) {
+        if !action.is_edit() {
+            self.content.perform(action);
+        }
+    }
+}
+
+/// The instant answer for a `wiki <term>` search.
+struct WikiAnswer {
+    term: String,
+    summary: websearch::wikipedia::Summary,
+    thumbnail: Option<image::Handle>,
+}
+
+/// Emoji grid cell size and the gap between cells.
+const EMOJI_CELL: f32 = 56.0;
+const EMOJI_SPACING: f32 = 4.0;
+
 // Message types for the application
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -25,42 +122,86 @@ pub enum Message {
     SearchSubmit,
 
     // Navigation
-    SelectNext,
-    SelectPrevious,
-    SelectItem(usize),
-    ExecuteSelected,
     ExecuteItem(usize),
+    HoverItem(usize),
+    CopyItem(usize),
+    /// Copy the selected emoji in a skin tone (`None` for the default)
+    EmojiTone(Option<usize>),
+    // Placeholder fields of the selected snippet
+    SnippetField(usize, String),
+    SnippetFieldSubmit(usize),
+    // Login for a network share that refused guests
+    ShareLoginUser(String),
+    ShareLoginPassword(String),
+    ShareLoginRemember(bool),
+    ShareLoginSubmit,
+    FileAction(usize),
+    FileInputChanged(String),
+    FileFolderChosen(PathBuf),
+    FileInputSubmit,
+    // Editor for the selected note
+    NoteEdit,
+    NoteEdited(text_editor::Action),
+    NoteSave,
+    NoteDelete,
+    // Selecting the details panel's text
+    DetailsSelect(bool),
+    DetailsAction(text_editor::Action),
 
     // Indexing
     IndexingComplete(Vec<Item>),
 
+    // Progress of a long-running item, 0.0 to 1.0 (negative while the
+    // total is unknown)
+    ItemProgress(String, f32),
+    ProgressTick,
+
     // Feature messages
-    ClipboardUpdated(Vec<Item>),
     ProcessesUpdated(Vec<Item>),
     WindowsUpdated(Vec<Item>),
+    RunningWindowsUpdated(Vec<Item>),
     NetworkUpdated(Vec<Item>),
     BluetoothUpdated(Vec<Item>),
     AudioUpdated(Vec<Item>),
-    NotesUpdated(Vec<Item>),
-    TodosUpdated(Vec<Item>),
-    SnippetsUpdated(Vec<Item>),
-    SshUpdated(Vec<Item>),
+    DisplayUpdated(Vec<Item>),
+    TodosSynced(Result<todos::SyncOutcome, String>),
     DockerUpdated(Vec<Item>),
-    RecentFilesUpdated(Vec<Item>),
+    BitwardenUpdated(Vec<Item>),
+    GpgUpdated(Vec<Item>),
+    TotpUpdated(Vec<Item>),
+    TabsUpdated(Vec<Item>),
+    GitUpdated(Vec<Item>),
+    TmuxUpdated(Vec<Item>),
+    KubernetesUpdated(Vec<Item>),
+    VmsUpdated(Vec<Item>),
+    PackagesUpdated(Vec<Item>),
+    FlatpakUpdated(Vec<Item>),
+    LogsUpdated(Vec<Item>),
+    SysInfoUpdated(Vec<Item>),
+    DisksUpdated(Vec<Item>),
+    SharesUpdated(Vec<Item>),
+    BatteryUpdated(Vec<Item>),
+    ScreenshotUpdated(Vec<Item>),
     FilesSearchResult(Vec<Item>),
-    AiResponse(String),
+    GrepUpdated(Vec<Item>),
+    ZoxideUpdated(Vec<Item>),
+    PreviewLoaded(String, FilePreview),
+    AiResponse(ai::AiEvent),
+    CurrencyRatesUpdated(Result<converter::currency::Rates, String>),
+    WikiAnswer(String, Result<websearch::wikipedia::Summary, String>),
+    CommandCaptured(CaptureTarget, String, Result<String, String>),
+    /// A focus session started or stopped, with the notification to show
+    FocusChanged(Result<String, String>),
+    /// A gpg action finished, with the text to copy and the notification
+    GpgDone(Result<(String, String), String>),
     TimerTick,
+    SysInfoTick,
+    TotpTick,
 
     // Actions
     CopyToClipboard(String),
-    OpenUrl(String),
     ShowNotification(String),
 
-    // Window
-    CloseWindow,
-    Escape,
-    WindowUnfocused,
-
     // Keyboard
     KeyPressed(keyboard::Key, keyboard::Modifiers),
 
@@ -69,7 +210,7 @@ pub enum Message {
 }
 
 // Application mode based on search prefix
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Mode {
     Apps,
     Windows,
@@ -77,21 +218,58 @@ pub enum Mode {
     Wifi,
     Bluetooth,
     Audio,
+    Display,
     Clipboard,
     Notes,
     Snippets,
     Todos,
     Ssh,
+    Shares,
+    Tmux,
     Docker,
+    Kubernetes,
+    Vms,
+    Packages,
+    Flatpak,
+    Logs,
+    SysInfo,
+    Disks,
+    Battery,
     Timer,
     Emoji,
     Files,
+    Browse,
+    Zoxide,
+    Run,
     RecentFiles,
     Bitwarden,
+    Gpg,
+    Totp,
+    Grep,
+    Idle,
+    Screenshot,
+    Session,
+    Stats,
+    Focus,
     Ai,
     WebSearch,
     Calculator,
     Converter,
+    DateCalc,
+    WorldClock,
+    Hash,
+    Doctor,
+    Generator,
+    Unicode,
+    Capture,
+    Bookmarks,
+    Tabs,
+    Projects,
+    Git,
+    Maps,
+    Wine,
+    Theme,
+    Profile,
 }
 
 impl Mode {
@@ -107,25 +285,81 @@ impl Mode {
             "wifi" | "network" => (Mode::Wifi, remainder),
             "bt" | "bluetooth" => (Mode::Bluetooth, remainder),
             "vol" | "volume" | "audio" => (Mode::Audio, remainder),
+            "bri" | "brightness" | "display" | "monitor" => (Mode::Display, remainder),
             "cb" | "clip" | "clipboard" => (Mode::Clipboard, remainder),
             "note" | "notes" => (Mode::Notes, remainder),
             "snip" | "snippet" | "snippets" => (Mode::Snippets, remainder),
             "todo" | "todos" | "task" | "tasks" => (Mode::Todos, remainder),
+            "agenda" => (Mode::Todos, "agenda".to_string()),
             "ssh" => (Mode::Ssh, remainder),
+            "share" | "shares" | "smb" | "nfs" => (Mode::Shares, remainder),
+            "tmux" | "tm" => (Mode::Tmux, remainder),
+            "k8s" | "kube" | "kubectl" => (Mode::Kubernetes, remainder),
             "docker" | "container" | "containers" => (Mode::Docker, remainder),
+            "vm" | "vms" | "virsh" => (Mode::Vms, remainder),
+            "pkg" | "package" | "packages" => (Mode::Packages, remainder),
+            "flatpak" | "fp" => (Mode::Flatpak, remainder),
+            "logs" | "log" | "journal" => (Mode::Logs, remainder),
+            "sys" | "sysinfo" => (Mode::SysInfo, remainder.trim_start_matches("info").trim().to_string()),
+            "temps" | "sensors" => (Mode::SysInfo, format!("temps {}", remainder)),
+            "disk" | "disks" | "df" | "mount" => (Mode::Disks, remainder),
+            "battery" | "bat" | "power" => (Mode::Battery, remainder),
             "timer" | "stopwatch" => (Mode::Timer, remainder),
+            "focus" => (Mode::Focus, remainder),
+            "awake" | "caffeine" | "inhibit" => (Mode::Idle, remainder),
+            "session" | "sessions" => (Mode::Session, remainder),
+            "shot" | "screenshot" => (Mode::Screenshot, remainder),
+            "record" => (Mode::Screenshot, format!("record {}", remainder)),
             "e" | "emoji" => (Mode::Emoji, remainder),
             "f" | "find" | "file" | "files" => (Mode::Files, remainder),
+            "cd" => (Mode::Browse, remainder),
+            "z" | "zoxide" => (Mode::Zoxide, remainder),
+            "run" => (Mode::Run, remainder),
+            "grep" | "rg" => (Mode::Grep, remainder),
             "r" | "recent" => (Mode::RecentFiles, remainder),
             "bw" | "bitwarden" | "pass" | "password" => (Mode::Bitwarden, remainder),
+            "gpg" | "pgp" => (Mode::Gpg, remainder),
+            "2fa" | "totp" | "otp" => (Mode::Totp, remainder),
             "ask" | "ai" | "?" => (Mode::Ai, remainder),
             "g" | "google" => (Mode::WebSearch, format!("google {}", remainder)),
             "gh" | "github" => (Mode::WebSearch, format!("github {}", remainder)),
             "yt" | "youtube" => (Mode::WebSearch, format!("youtube {}", remainder)),
+            "=" | "calc" => (Mode::Calculator, remainder),
+            "date" | "dates" => (Mode::DateCalc, remainder),
+            "hash" => (Mode::Hash, remainder),
+            "doctor" => (Mode::Doctor, remainder),
+            "stats" | "insights" => (Mode::Stats, remainder),
+            "gen" | "generate" => (Mode::Generator, remainder),
+            "u" | "char" | "unicode" => (Mode::Unicode, remainder),
+            "qc" | "capture" | "inbox" => (Mode::Capture, remainder),
+            "bm" | "bookmark" | "bookmarks" => (Mode::Bookmarks, remainder),
+            "tab" | "tabs" => (Mode::Tabs, remainder),
+            "wiki" | "wikipedia" => (Mode::WebSearch, format!("wikipedia {}", remainder)),
+            "proj" | "project" | "projects" => (Mode::Projects, remainder),
+            "git" | "repo" | "repos" => (Mode::Git, remainder),
+            "map" | "maps" => (Mode::Maps, remainder),
+            "route" | "directions" => (Mode::Maps, format!("route {}", remainder)),
+            "time" | "tz" | "clock" => (Mode::WorldClock, remainder),
+            "wine" | "proton" => (Mode::Wine, remainder),
+            "theme" | "themes" => (Mode::Theme, remainder),
+            "profile" | "profiles" => (Mode::Profile, remainder),
             _ => {
                 // Check for calculator or converter
-                if is_math_expression(query) {
+                if let Some(expr) = query.strip_prefix('=') {
+                    (Mode::Calculator, expr.to_string())
+                } else if let Some(command) = query.strip_prefix('>') {
+                    (Mode::Run, command.to_string())
+                } else if WebSearchManager::has_bang(query) {
+                    (Mode::WebSearch, query.to_string())
+                } else if DateCalculator::is_date_query(query) {
+                    (Mode::DateCalc, query.to_string())
+                } else if is_math_expression(query)
+                    || Calculator::is_numeric_assignment(query)
+                    || Calculator::is_radix_query(query)
+                {
                     (Mode::Calculator, query.to_string())
+                } else if WorldClock::is_time_query(query) {
+                    (Mode::WorldClock, query.to_string())
                 } else if is_conversion(query) {
                     (Mode::Converter, query.to_string())
                 } else {
@@ -136,8 +370,177 @@ impl Mode {
     }
 }
 
+impl Mode {
+    /// Modes that only search and launch, the ones kiosk mode keeps.
+    fn available_in_kiosk(&self) -> bool {
+        matches!(
+            self,
+            Mode::Apps
+                | Mode::Windows
+                | Mode::Files
+                | Mode::RecentFiles
+                | Mode::Emoji
+                | Mode::Timer
+                | Mode::WebSearch
+                | Mode::Calculator
+                | Mode::Converter
+                | Mode::DateCalc
+                | Mode::WorldClock
+                | Mode::Hash
+                | Mode::Doctor
+                | Mode::SysInfo
+                | Mode::Battery
+                | Mode::Generator
+                | Mode::Unicode
+                | Mode::Bookmarks
+                | Mode::Tabs
+                | Mode::Projects
+                | Mode::Git
+                | Mode::Maps
+                | Mode::Wine
+        )
+    }
+
+    /// What to type after the mode's prefix.
+    fn placeholder(&self) -> &'static str {
+        match self {
+            Mode::Apps => "Search apps, commands, files...",
+            Mode::Windows => "Search open windows...",
+            Mode::Processes => "Search processes by name...",
+            Mode::Wifi => "Search WiFi networks...",
+            Mode::Bluetooth => "Search Bluetooth devices...",
+            Mode::Audio => "Search outputs, or a volume like 50",
+            Mode::Display => "Search displays, or a brightness like 70",
+            Mode::Clipboard => "Search clipboard history...",
+            Mode::Notes => "Search notes, or add <text>",
+            Mode::Snippets => "Search snippets, add <name>: <content>, pin <name> or import <path>",
+            Mode::Todos => "Search todos, #tag, @project or add <task>",
+            Mode::Ssh => "user@host or add user@host:port",
+            Mode::Tmux => "Search sessions, new <name> or kill <name>",
+            Mode::Docker => "Search containers, or images, volumes, compose...",
+            Mode::Kubernetes => "Search pods, ctx or ns...",
+            Mode::Vms => "Search virtual machines...",
+            Mode::Packages => "Search packages to install or remove...",
+            Mode::Flatpak => "Search installed Flatpaks and Flathub...",
+            Mode::Logs => "Search errors since boot, or unit <name>...",
+            Mode::SysInfo => "CPU, memory, uptime, kernel...",
+            Mode::Disks => "Search drives and mountpoints...",
+            Mode::Shares => "Search servers, or //host for its shares...",
+            Mode::Battery => "Batteries and power profiles...",
+            Mode::Timer => "Duration, e.g. 5m or 1h30m",
+            Mode::Focus => "Focus for how long, e.g. 45 or 1h30m",
+            Mode::Idle => "Keep awake for how long, e.g. 2h...",
+            Mode::Session => "Restore a session, or save <name>...",
+            Mode::Screenshot => "Screenshot the screen, a region or a window...",
+            Mode::Emoji => "Search emojis by name or keyword...",
+            Mode::Files => "Search files by name...",
+            Mode::Browse => "Folder, e.g. ~/code/...",
+            Mode::Zoxide => "Jump to a frecent folder...",
+            Mode::Run => "Shell command...",
+            Mode::Grep => "Search file contents...",
+            Mode::RecentFiles => "Search recent files...",
+            Mode::Bitwarden => "Search the vault...",
+            Mode::Gpg => "Search keys, or sign <text>...",
+            Mode::Totp => "Search accounts, or add <name> <secret>...",
+            Mode::Ai => "Ask anything...",
+            Mode::WebSearch => "Search the web...",
+            Mode::Calculator => "Expression, e.g. 2*(3+4)",
+            Mode::Converter => "Conversion, e.g. 10 km to mi",
+            Mode::DateCalc => "Date, e.g. days until dec 25",
+            Mode::WorldClock => "City or zone, e.g. tokyo or 3pm pst to ist",
+            Mode::Hash => "Text to hash, encode or spell out (empty uses the clipboard)",
+            Mode::Doctor => "Filter checks by feature...",
+            Mode::Stats => "Filter usage by feature...",
+            Mode::Generator => "uuid, hex 32, lorem 3 or 1-100",
+            Mode::Unicode => "Character name, e.g. em dash, or U+2014",
+            Mode::Capture => "Thought to capture...",
+            Mode::Bookmarks => "Search bookmarks...",
+            Mode::Tabs => "Search open browser tabs...",
+            Mode::Projects => "Search recent projects...",
+            Mode::Git => "Search repositories...",
+            Mode::Maps => "Place or address...",
+            Mode::Wine => "Search Wine prefixes...",
+            Mode::Theme => "Search themes...",
+            Mode::Profile => "Search profiles, or a name to create one",
+        }
+    }
+
+    /// Syntax help shown when the mode has no results.
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            Mode::Clipboard => Some("merge 3 copies the last three entries as one; export [txt|csv|json] [filter] saves the matching entries to Downloads"),
+            Mode::Notes => Some("add <text> saves a note; edit the selected note in the details panel"),
+            Mode::Snippets => Some("add <name>: <content> saves a snippet, pin <name> shows it in the main search, import <path> reads Espanso, VS Code or text files"),
+            Mode::Todos => Some("add <task> #tag @project !high @friday @5pm every monday creates a todo; the same tokens filter the list; agenda lists what is due"),
+            Mode::Ssh => Some("add user@host:port saves a connection; ~/.ssh/config hosts are listed too"),
+            Mode::Windows => Some("Enter focuses a window; the best match can float, stick or go to the scratchpad on i3, Sway and Hyprland"),
+            Mode::Session => Some("save <name> records the apps open on each workspace; restoring launches them and moves their windows back"),
+            Mode::Docker => Some("Enter starts or stops a container; images, volumes and compose list those, with actions under the best match"),
+            Mode::Kubernetes => Some("Enter follows pod logs in a terminal; the best match offers port-forward and delete; ctx and ns switch context and namespace"),
+            Mode::Vms => Some("Enter starts a VM or opens the console of a running one; the best match offers shut down and force off"),
+            Mode::Packages => Some("Enter installs a package, or removes an installed one, in a terminal that asks for confirmation"),
+            Mode::Flatpak => Some("Enter runs an installed app, or installs one from Flathub in a terminal that asks for confirmation"),
+            Mode::Logs => Some("Enter opens the message's unit log in a terminal; the best match can follow the unit"),
+            Mode::SysInfo => Some("sys temps lists temperatures and fan speeds, highlighting hot components"),
+            Mode::Gpg => Some("Enter copies a public key; the best match can encrypt the clipboard to it; sign <text> clear-signs a text"),
+            Mode::Totp => Some("Enter copies the current code; add <name> <secret> or add otpauth://... registers an account"),
+            Mode::Shares => Some("Enter lists a server's shares, then mounts one and opens it; share //host lists any server"),
+            Mode::Disks => Some("Enter opens a mounted drive or mounts a removable one; the best match can unmount or eject it"),
+            Mode::Battery => Some("Enter copies a battery's details or switches to a power profile"),
+            Mode::Tmux => Some("Enter attaches in a terminal; new <name> creates a session, kill <name> ends one"),
+            Mode::Timer => Some("Durations: 90s, 5m, 1h30m"),
+            Mode::Run => Some("Runs with sh; the output can be copied or saved as a note instead of opening a terminal"),
+            Mode::Zoxide => Some("Folders ranked by zoxide; Enter browses one, the best match opens in a terminal or the file manager"),
+            Mode::Browse => Some("Enter opens a folder, Backspace over the last / goes up a level; text after it filters"),
+            Mode::Grep => Some("Regular expressions, ignoring case unless the query has capitals; Enter opens the file at the line"),
+            Mode::Focus => Some("Blocks focus.sites in /etc/hosts until the time is up; the daemon unblocks them"),
+            Mode::Calculator => Some("Operators: + - * / ^ %, & | << >> ~, hex 0x1f, x = 5 to assign, 255 in hex"),
+            Mode::Converter => Some("<amount> <unit> to <unit>: 10 km to mi, 72 f to c, 100 usd to eur"),
+            Mode::DateCalc => Some("days until dec 25, today + 45 days, weeks between 2024-01-01 and now, 3 weeks ago"),
+            Mode::WorldClock => Some("time in <city>, <city> time, 3pm pst to ist"),
+            Mode::Generator => Some("uuid, hex <bytes>, lorem <paragraphs>, <min>-<max>"),
+            Mode::Unicode => Some("Every word has to start a word of the name: right arrow, greek lambda; a single character shows its codes"),
+            Mode::Git => Some("Enter opens the repository in the editor; the best match also offers terminal, browser and clone URL actions"),
+            Mode::Tabs => Some("Tabs of Chromium-based browsers started with --remote-debugging-port; Enter switches to the tab"),
+            Mode::Profile => Some("Each profile has its own config, notes, snippets and SSH hosts; start one with wlaunch --profile <name>"),
+            Mode::Capture => Some("Enter files the text in the inbox file, or as a note or todo; the configured target comes first"),
+            Mode::Apps => Some("Prefixes switch modes: w windows, cb clipboard, e emoji, f files, = calculator, ? AI"),
+            _ => None,
+        }
+    }
+}
+
+/// Small outlined label next to a result's name.
+fn badge(label: String) -> Element<'static, Message> {
+    container(text(label).size(10).style(theme::accent_text))
+        .padding([1, 6])
+        .style(theme::badge)
+        .into()
+}
+
+/// Name color of the first matching row rule with one, and the badges of
+/// all matching rules.
+fn row_style<'a>(rules: &'a [RowRule], item: &Item) -> (Option<Color>, Vec<&'a str>) {
+    let matching: Vec<&RowRule> = rules.iter().filter(|rule| rule.matches(item)).collect();
+    let color = matching.iter().find_map(|rule| rule.color.as_deref().and_then(Color::parse));
+    let badges = matching.iter().filter_map(|rule| rule.badge.as_deref()).collect();
+    (color, badges)
+}
+
+/// Main search ranking: fuzzy matches with boosted items first, then by
+/// score; ties keep the candidates' order.
+fn rank_items<'a>(candidates: impl IntoIterator<Item = &'a Item>, query: &str, boosted: impl Fn(&Item) -> bool) -> Vec<Item> {
+    let mut items: Vec<(&Item, i64)> = candidates
+        .into_iter()
+        .map(|item| (item, item.fuzzy_score(query)))
+        .filter(|(_, score)| *score > 0)
+        .collect();
+    items.sort_by_key(|(item, score)| (!boosted(item), std::cmp::Reverse(*score)));
+    items.into_iter().map(|(item, _)| item.clone()).collect()
+}
+
 fn is_math_expression(query: &str) -> bool {
-    let has_operators = query.chars().any(|c| "+-*/^%()".contains(c));
+    let has_operators = query.chars().any(|c| "+-*/^%()&|<>~".contains(c));
     let has_numbers = query.chars().any(|c| c.is_ascii_digit());
     has_operators && has_numbers
 }
@@ -157,227 +560,700 @@ pub struct WLaunch {
     all_items: Vec<Item>,
     filtered_items: Vec<Item>,
     selected_index: usize,
+    // Tracked to fit the emoji grid's columns to the window
+    window_width: f32,
+    // Tracked because the search field takes Enter before Alt+Enter can be
+    // seen as a key press
+    modifiers: keyboard::Modifiers,
+
+    // Last results of modes that are fetched in the background, shown while
+    // a refresh is in flight
+    cached_results: HashMap<Mode, Vec<Item>>,
+    fetch_generation: Arc<AtomicU64>,
+    loading: bool,
+
+    // Windows open when the launcher started, used for "raise or launch"
+    open_windows: Vec<Item>,
+
+    // Progress bars by item id, and the phase of the indeterminate animation
+    progress: HashMap<String, f32>,
+    progress_phase: f32,
 
     // Managers
-    indexer: Arc<Mutex<Indexer>>,
     clipboard_manager: ClipboardManager,
     process_manager: ProcessManager,
     windows_manager: WindowsManager,
     network_manager: NetworkManager,
     bluetooth_manager: BluetoothManager,
     audio_manager: AudioManager,
+    brightness_manager: BrightnessManager,
+    night_mode_manager: NightModeManager,
     notes_manager: NotesManager,
     todos_manager: TodosManager,
     snippets_manager: SnippetsManager,
+    // Values typed for the selected snippet's placeholders
+    snippet_form: Option<SnippetForm>,
+    share_login: Option<ShareLogin>,
+    // Alt+Enter actions of the selected file or folder
+    file_actions: Option<FileActions>,
+    note_editor: Option<NoteEditor>,
+    details_text: Option<DetailsText>,
+    wiki_answer: Option<WikiAnswer>,
+    // Bumped per lookup, so only the latest one is sent
+    wiki_generation: Arc<AtomicU64>,
     ssh_manager: SshManager,
+    tmux_manager: TmuxManager,
+    vm_manager: VmManager,
+    packages_manager: PackagesManager,
+    flatpak_manager: FlatpakManager,
+    journal_manager: JournalManager,
+    sysinfo: SysInfo,
+    disks_manager: DisksManager,
+    shares_manager: SharesManager,
+    battery_manager: BatteryManager,
     docker_manager: DockerManager,
+    kubernetes_manager: KubernetesManager,
     emoji_manager: EmojiManager,
     file_manager: FileManager,
+    content_search: ContentSearch,
+    directory_browser: DirectoryBrowser,
+    zoxide_manager: ZoxideManager,
+    file_previewer: FilePreviewer,
+    /// Preview of the selected file, by item id
+    file_preview: Option<(String, FilePreview)>,
+    shell_manager: ShellManager,
     recent_files_manager: RecentFilesManager,
     bitwarden_manager: BitwardenManager,
+    gpg_manager: GpgManager,
+    totp_manager: TotpManager,
+    focus_manager: FocusManager,
+    idle_manager: IdleManager,
+    session_manager: SessionManager,
+    screenshot_manager: ScreenshotManager,
     ai_manager: AiManager,
     websearch_manager: WebSearchManager,
     calculator: Calculator,
     converter: Converter,
+    date_calculator: DateCalculator,
+    world_clock: WorldClock,
+    crypto_tools: CryptoTools,
+    doctor: Doctor,
+    insights: Insights,
+    generator: Generator,
+    unicode_search: UnicodeSearch,
+    quick_capture: QuickCapture,
+    bookmarks_manager: BookmarksManager,
+    tabs_manager: TabsManager,
+    projects_manager: ProjectsManager,
+    git_manager: GitManager,
     timer_manager: TimerManager,
+    wine_manager: WineManager,
+
+    // Copy the streamed AI answer to the clipboard once it completes
+    copy_ai_answer: bool,
 
     // Config
     config: Config,
+    // Context rule for the window the launcher was opened over
+    context: Option<ContextRule>,
 }
 
 impl WLaunch {
     pub fn new() -> (Self, Task<Message>) {
         let config = Config::load().unwrap_or_default();
         let indexer = Arc::new(Mutex::new(Indexer::new()));
+        theme::apply_config(&config);
 
-        let app = Self {
+        let windows_manager = WindowsManager::new();
+        let context = if config.context_rules.is_empty() {
+            None
+        } else {
+            windows_manager
+                .focused_class()
+                .and_then(|class| config.context_rule(&class).cloned())
+        };
+
+        let mut app = Self {
             search_query: String::new(),
             mode: Mode::Apps,
             mode_query: String::new(),
             all_items: Vec::new(),
             filtered_items: Vec::new(),
             selected_index: 0,
-            indexer: indexer.clone(),
+            cached_results: HashMap::new(),
+            fetch_generation: Arc::new(AtomicU64::new(0)),
+            loading: false,
+            open_windows: Vec::new(),
+            progress: HashMap::from([(INDEXING_ID.to_string(), -1.0)]),
+            progress_phase: 0.0,
+            // The initial size set in main.rs
+            window_width: 800.0,
+            modifiers: keyboard::Modifiers::default(),
             clipboard_manager: ClipboardManager::new(),
-            process_manager: ProcessManager::new(),
-            windows_manager: WindowsManager::new(),
+            process_manager: ProcessManager::new(&config),
+            windows_manager,
             network_manager: NetworkManager::new(),
             bluetooth_manager: BluetoothManager::new(),
             audio_manager: AudioManager::new(),
-            notes_manager: NotesManager::new(),
-            todos_manager: TodosManager::new(),
-            snippets_manager: SnippetsManager::new(),
+            brightness_manager: BrightnessManager::new(),
+            night_mode_manager: NightModeManager::new(),
+            notes_manager: NotesManager::new(&config),
+            todos_manager: TodosManager::new(&config),
+            snippets_manager: SnippetsManager::new(&config),
+            snippet_form: None,
+            share_login: None,
+            file_actions: None,
+            note_editor: None,
+            details_text: None,
+            wiki_answer: None,
+            wiki_generation: Arc::new(AtomicU64::new(0)),
             ssh_manager: SshManager::new(),
-            docker_manager: DockerManager::new(),
+            tmux_manager: TmuxManager::new(&config),
+            vm_manager: VmManager::new(&config),
+            packages_manager: PackagesManager::new(&config),
+            flatpak_manager: FlatpakManager::new(&config),
+            journal_manager: JournalManager::new(&config),
+            sysinfo: SysInfo::new(&config),
+            disks_manager: DisksManager::new(&config),
+            shares_manager: SharesManager::new(),
+            battery_manager: BatteryManager::new(),
+            docker_manager: DockerManager::new(&config),
+            kubernetes_manager: KubernetesManager::new(&config),
             emoji_manager: EmojiManager::new(),
-            file_manager: FileManager::new(),
+            file_manager: FileManager::new(&config),
+            content_search: ContentSearch::new(&config),
+            directory_browser: DirectoryBrowser::new(&config),
+            zoxide_manager: ZoxideManager::new(&config),
+            file_previewer: FilePreviewer::new(),
+            file_preview: None,
+            shell_manager: ShellManager::new(),
             recent_files_manager: RecentFilesManager::new(),
             bitwarden_manager: BitwardenManager::new(&config),
+            gpg_manager: GpgManager::new(),
+            totp_manager: TotpManager::new(),
+            focus_manager: FocusManager::new(&config),
+            idle_manager: IdleManager::new(),
+            session_manager: SessionManager::new(),
+            screenshot_manager: ScreenshotManager::new(),
             ai_manager: AiManager::new(&config),
-            websearch_manager: WebSearchManager::new(),
-            calculator: Calculator::new(),
-            converter: Converter::new(),
+            websearch_manager: WebSearchManager::new(&config),
+            calculator: Calculator::new(&config),
+            converter: Converter::new(&config),
+            date_calculator: DateCalculator::new(),
+            world_clock: WorldClock::new(&config),
+            crypto_tools: CryptoTools::new(),
+            doctor: Doctor::new(),
+            insights: Insights::new(),
+            generator: Generator::new(),
+            unicode_search: UnicodeSearch::new(),
+            quick_capture: QuickCapture::new(&config),
+            bookmarks_manager: BookmarksManager::new(&config),
+            tabs_manager: TabsManager::new(&config),
+            projects_manager: ProjectsManager::new(&config),
+            git_manager: GitManager::new(&config),
             timer_manager: TimerManager::new(),
+            wine_manager: WineManager::new(),
+            copy_ai_answer: false,
             config,
+            context,
         };
 
         // Start indexing in background
-        let task = Task::perform(
+        let index_task = Task::run(
+            iced::stream::channel(16, move |mut output| async move {
+                use iced::futures::SinkExt;
+
+                let mut sender = output.clone();
+                let items = tokio::task::spawn_blocking(move || {
+                    let mut indexer = indexer.blocking_lock();
+                    let _ = indexer.index_with_progress(&mut |fraction| {
+                        let _ = sender.try_send(Message::ItemProgress(INDEXING_ID.to_string(), fraction));
+                    });
+                    indexer.all_items()
+                })
+                .await
+                .unwrap_or_default();
+                let _ = output.send(Message::IndexingComplete(items)).await;
+            }),
+            |message| message,
+        );
+
+        if let Some(prefix) = app.context.as_ref().and_then(|rule| rule.mode.clone()) {
+            app.search_query = format!("{} ", prefix);
+            (app.mode, app.mode_query) = Mode::from_query(&app.search_query);
+        }
+
+        // Snapshot open windows so apps can offer to switch to a running instance
+        let windows_manager = app.windows_manager.clone();
+        let windows_task = Task::perform(
             async move {
-                let mut indexer = indexer.lock().await;
-                let _ = indexer.index();
-                indexer.all_items()
+                tokio::task::spawn_blocking(move || windows_manager.list_windows(""))
+                    .await
+                    .unwrap_or_default()
             },
-            Message::IndexingComplete,
+            Message::RunningWindowsUpdated,
         );
 
-        (app, task)
-    }
-
-    pub fn title(&self) -> String {
-        "WLaunch".to_string()
+        (app, Task::batch([index_task, windows_task]))
     }
 
     pub fn theme(&self) -> iced::Theme {
-        theme::Theme::custom()
+        Theme::custom()
     }
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
+        let selected_before = self.selected_item_id();
+        let mut task = self.handle_message(message);
+        self.sync_note_editor();
+        self.sync_details_text();
+
+        if self.selected_item_id() != selected_before {
+            if self.config.accessibility.announce() {
+                self.announce_selection();
+            }
+            let to_top = scrollable::snap_to(details_scrollable_id(), scrollable::RelativeOffset::START);
+            task = Task::batch([task, self.load_preview(), to_top]);
+        }
+        task
+    }
+
+    /// Loads the selected file's preview in the background.
+    fn load_preview(&mut self) -> Task<Message> {
+        let Some(item) = self
+            .filtered_items
+            .get(self.selected_index)
+            .filter(|item| matches!(item.item_type, ItemType::File | ItemType::RecentFile))
+        else {
+            return Task::none();
+        };
+        let Some(path) = item.metadata.path.clone() else {
+            return Task::none();
+        };
+        if self.file_preview.as_ref().is_some_and(|(id, _)| *id == item.id) {
+            return Task::none();
+        }
+        let (item_id, mime) = (item.id.clone(), item.metadata.mime_type.clone());
+        let previewer = self.file_previewer.clone();
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || previewer.load(&path, mime.as_deref()))
+                    .await
+                    .unwrap_or(FilePreview::None)
+            },
+            move |preview| Message::PreviewLoaded(item_id.clone(), preview),
+        )
+    }
+
+    fn render_preview(preview: &FilePreview) -> Option<Element<'_, Message>> {
+        Some(match preview {
+            FilePreview::Image(path) if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("svg")) => {
+                svg(svg::Handle::from_path(path)).height(Length::Fixed(180.0)).into()
+            }
+            FilePreview::Image(path) => image(image::Handle::from_path(path)).height(Length::Fixed(180.0)).into(),
+            FilePreview::Text(lines) => container(text(lines.clone()).size(12).font(iced::Font::MONOSPACE))
+                .padding(8)
+                .width(Length::Fill)
+                .style(theme::search_container)
+                .into(),
+            FilePreview::Media(duration) => text(format!("Duration: {}", duration)).size(12).into(),
+            FilePreview::Pdf { pages } => text(format!("Pages: {}", pages)).size(12).into(),
+            FilePreview::None => return None,
+        })
+    }
+
+    fn selected_item_id(&self) -> Option<String> {
+        self.filtered_items.get(self.selected_index).map(|item| item.id.clone())
+    }
+
+    /// Speaks the selected item, standing in for a live region since iced
+    /// has no accessibility tree. `-C` cuts off the previous announcement.
+    fn announce_selection(&self) {
+        let message = match self.filtered_items.get(self.selected_index) {
+            Some(item) => format!(
+                "{}. {} of {}",
+                item.accessible_label(),
+                self.selected_index + 1,
+                self.filtered_items.len()
+            ),
+            None => "No results".to_string(),
+        };
+        // Cancel the previous announcement before queuing this one, or the
+        // cancel can race it and silence it. Both run off the UI thread;
+        // `--` keeps a title starting with "-" from being read as an option
+        std::thread::spawn(move || {
+            if let Ok(mut cancel) = Command::new("spd-say").arg("-C").spawn() {
+                let _ = cancel.wait();
+            }
+            let _ = Command::new("spd-say").arg("--").arg(message).spawn();
+        });
+    }
+
+    fn handle_message(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::SearchChanged(query) => {
+                self.file_actions = None;
+                if let Some(up) = DirectoryBrowser::up_a_level(&self.search_query, &query) {
+                    return self.set_query(up);
+                }
                 self.search_query = query.clone();
                 let (mode, mode_query) = Mode::from_query(&query);
                 self.mode = mode;
                 self.mode_query = mode_query;
-                self.filter_items();
                 self.selected_index = 0;
-                Task::none()
+                self.filter_items()
             }
-            Message::SearchSubmit | Message::ExecuteSelected => {
+            Message::SearchSubmit if self.modifiers.alt() => self.open_file_actions(),
+            Message::SearchSubmit if self.selected_file_actions().is_some() => {
+                let selected = self.selected_file_actions().map_or(0, |actions| actions.selected);
+                self.run_file_action(selected)
+            }
+            Message::SearchSubmit => {
                 self.execute_selected()
             }
-            Message::SelectNext => {
-                if !self.filtered_items.is_empty() {
-                    self.selected_index = (self.selected_index + 1) % self.filtered_items.len();
+            Message::ExecuteItem(index) => {
+                self.selected_index = index;
+                self.execute_selected()
+            }
+            Message::HoverItem(index) => {
+                if self.config.mouse.hover_select() && index < self.filtered_items.len() {
+                    self.selected_index = index;
                 }
                 Task::none()
             }
-            Message::SelectPrevious => {
-                if !self.filtered_items.is_empty() {
-                    if self.selected_index == 0 {
-                        self.selected_index = self.filtered_items.len() - 1;
-                    } else {
-                        self.selected_index -= 1;
-                    }
+            Message::CopyItem(index) => match self.filtered_items.get(index) {
+                Some(item) => Task::done(Message::CopyToClipboard(item.copy_text())),
+                None => Task::none(),
+            },
+            Message::EmojiTone(tone) => match self.filtered_items.get(self.selected_index) {
+                Some(item) if item.item_type == ItemType::Emoji => {
+                    let emoji = EmojiManager::with_tone(&item.name, tone);
+                    self.emoji_manager.record_use(&emoji);
+                    self.paste(&emoji)
+                }
+                _ => Task::none(),
+            },
+            Message::SnippetField(index, value) => {
+                if let Some((_, field)) = self.selected_snippet_form().and_then(|form| form.fields.get_mut(index)) {
+                    *field = value;
                 }
                 Task::none()
             }
-            Message::SelectItem(index) => {
-                self.selected_index = index;
+            Message::SnippetFieldSubmit(index) => match self.selected_snippet_form() {
+                Some(form) if index + 1 < form.fields.len() => text_input::focus(snippet_field_id(index + 1)),
+                Some(form) if form.is_complete() => self.copy_snippet_form(),
+                Some(form) => match form.fields.iter().position(|(_, value)| value.is_empty()) {
+                    Some(empty) => text_input::focus(snippet_field_id(empty)),
+                    None => Task::none(),
+                },
+                None => Task::none(),
+            },
+            Message::ShareLoginUser(value) => {
+                if let Some(login) = &mut self.share_login {
+                    login.user = value;
+                }
                 Task::none()
             }
-            Message::ExecuteItem(index) => {
-                self.selected_index = index;
-                self.execute_selected()
+            Message::ShareLoginPassword(value) => {
+                if let Some(login) = &mut self.share_login {
+                    login.password = value;
+                }
+                Task::none()
             }
-            Message::IndexingComplete(items) => {
-                self.all_items = items;
-                self.filter_items();
+            Message::ShareLoginRemember(remember) => {
+                if let Some(login) = &mut self.share_login {
+                    login.remember = remember;
+                }
                 Task::none()
             }
-            Message::ClipboardUpdated(items) => {
-                if self.mode == Mode::Clipboard {
-                    self.filtered_items = items;
+            Message::ShareLoginSubmit => self.submit_share_login(),
+            Message::FileAction(index) => self.run_file_action(index),
+            Message::FileInputChanged(value) => {
+                if let Some(actions) = &mut self.file_actions {
+                    actions.set_input(value);
                 }
                 Task::none()
             }
-            Message::ProcessesUpdated(items) => {
-                if self.mode == Mode::Processes {
-                    self.filtered_items = items;
+            Message::FileFolderChosen(folder) => {
+                if let Some(actions) = &mut self.file_actions {
+                    actions.set_input(format!("{}/", folder.display()));
+                }
+                text_input::focus(file_input_id())
+            }
+            Message::FileInputSubmit => self.submit_file_input(),
+            Message::NoteEdited(action) => {
+                if let Some(editor) = &mut self.note_editor {
+                    editor.dirty |= action.is_edit();
+                    editor.content.perform(action);
                 }
                 Task::none()
             }
-            Message::WindowsUpdated(items) => {
-                if self.mode == Mode::Windows {
-                    self.filtered_items = items;
+            Message::DetailsSelect(selecting) => {
+                if let Some(details) = &mut self.details_text {
+                    details.selecting = selecting;
                 }
                 Task::none()
             }
-            Message::NetworkUpdated(items) => {
-                if self.mode == Mode::Wifi {
-                    self.filtered_items = items;
+            Message::DetailsAction(action) => {
+                if let Some(details) = &mut self.details_text {
+                    details.perform(action);
                 }
                 Task::none()
             }
-            Message::BluetoothUpdated(items) => {
-                if self.mode == Mode::Bluetooth {
-                    self.filtered_items = items;
+            Message::NoteEdit => {
+                if let Some(editor) = &mut self.note_editor {
+                    editor.editing = true;
                 }
                 Task::none()
             }
-            Message::AudioUpdated(items) => {
-                if self.mode == Mode::Audio {
-                    self.filtered_items = items;
+            Message::NoteSave => {
+                self.save_note_editor();
+                if let Some(editor) = &mut self.note_editor {
+                    editor.editing = false;
+                }
+                self.filter_items()
+            }
+            Message::NoteDelete => {
+                if let Some(editor) = self.note_editor.take() {
+                    self.notes_manager.delete_note(editor.item_id.trim_start_matches("note:"));
+                }
+                self.filter_items()
+            }
+            Message::IndexingComplete(mut items) => {
+                // User scripts can do anything, so kiosk mode doesn't list them
+                if self.config.kiosk() {
+                    items.retain(|item| item.item_type.is_read_only());
                 }
+                self.all_items = items;
+                self.progress.remove(INDEXING_ID);
+                self.filter_items()
+            }
+            Message::ItemProgress(id, fraction) => {
+                self.progress.insert(id, fraction.min(1.0));
                 Task::none()
             }
-            Message::NotesUpdated(items) => {
-                if self.mode == Mode::Notes {
-                    self.filtered_items = items;
+            Message::ProgressTick => {
+                self.progress_phase = (self.progress_phase + 0.04) % 1.0;
+                Task::none()
+            }
+            Message::ProcessesUpdated(items) => {
+                self.apply_fetched(Mode::Processes, items);
+                Task::none()
+            }
+            Message::WindowsUpdated(items) => {
+                self.apply_fetched(Mode::Windows, items);
+                Task::none()
+            }
+            Message::RunningWindowsUpdated(items) => {
+                self.open_windows = items;
+                if self.mode == Mode::Apps {
+                    self.filter_items()
+                } else {
+                    Task::none()
                 }
+            }
+            Message::NetworkUpdated(items) => {
+                self.apply_fetched(Mode::Wifi, items);
                 Task::none()
             }
-            Message::TodosUpdated(items) => {
-                if self.mode == Mode::Todos {
-                    self.filtered_items = items;
+            Message::BluetoothUpdated(items) => {
+                self.apply_fetched(Mode::Bluetooth, items);
+                Task::none()
+            }
+            Message::AudioUpdated(items) => {
+                self.apply_fetched(Mode::Audio, items);
+                Task::none()
+            }
+            Message::DisplayUpdated(items) => {
+                self.apply_fetched(Mode::Display, items);
+                Task::none()
+            }
+            Message::CurrencyRatesUpdated(Ok(rates)) => {
+                self.converter.set_rates(rates);
+                match self.mode {
+                    Mode::Converter => self.filtered_items = self.converter.get_items(&self.mode_query),
+                    Mode::Calculator => {
+                        self.filtered_items = self.calculator.get_items(&self.mode_query, self.converter.rates())
+                    }
+                    _ => {}
                 }
                 Task::none()
             }
-            Message::SnippetsUpdated(items) => {
-                if self.mode == Mode::Snippets {
-                    self.filtered_items = items;
+            Message::WikiAnswer(term, Ok(summary)) => {
+                let thumbnail = summary.thumbnail.clone().map(image::Handle::from_bytes);
+                self.wiki_answer = Some(WikiAnswer { term, summary, thumbnail });
+                if self.mode == Mode::WebSearch {
+                    self.apply_wiki_answer();
                 }
                 Task::none()
             }
-            Message::SshUpdated(items) => {
-                if self.mode == Mode::Ssh {
-                    self.filtered_items = items;
+            Message::WikiAnswer(term, Err(e)) => {
+                log::debug!("No Wikipedia answer for '{}': {}", term, e);
+                Task::none()
+            }
+            Message::CurrencyRatesUpdated(Err(e)) => {
+                log::debug!("Failed to fetch exchange rates: {}", e);
+                Task::none()
+            }
+            Message::TodosSynced(result) => {
+                self.todos_manager.apply_sync(result);
+                if self.mode == Mode::Todos {
+                    self.filtered_items = self.todos_manager.get_items(&self.mode_query);
                 }
                 Task::none()
             }
             Message::DockerUpdated(items) => {
-                if self.mode == Mode::Docker {
-                    self.filtered_items = items;
-                }
+                self.apply_fetched(Mode::Docker, items);
                 Task::none()
             }
-            Message::RecentFilesUpdated(items) => {
-                if self.mode == Mode::RecentFiles {
-                    self.filtered_items = items;
-                }
+            Message::BitwardenUpdated(items) => {
+                self.apply_fetched(Mode::Bitwarden, items);
                 Task::none()
             }
-            Message::FilesSearchResult(items) => {
-                if self.mode == Mode::Files {
-                    self.filtered_items = items;
-                }
+            Message::TabsUpdated(items) => {
+                self.apply_fetched(Mode::Tabs, items);
+                Task::none()
+            }
+            Message::GitUpdated(items) => {
+                self.apply_fetched(Mode::Git, items);
+                Task::none()
+            }
+            Message::TmuxUpdated(items) => {
+                self.apply_fetched(Mode::Tmux, items);
+                Task::none()
+            }
+            Message::KubernetesUpdated(items) => {
+                self.apply_fetched(Mode::Kubernetes, items);
+                Task::none()
+            }
+            Message::VmsUpdated(items) => {
+                self.apply_fetched(Mode::Vms, items);
+                Task::none()
+            }
+            Message::PackagesUpdated(items) => {
+                self.apply_fetched(Mode::Packages, items);
+                Task::none()
+            }
+            Message::FlatpakUpdated(items) => {
+                self.apply_fetched(Mode::Flatpak, items);
+                Task::none()
+            }
+            Message::LogsUpdated(items) => {
+                self.apply_fetched(Mode::Logs, items);
+                Task::none()
+            }
+            Message::SysInfoUpdated(items) => {
+                self.apply_fetched(Mode::SysInfo, items);
+                Task::none()
+            }
+            Message::DisksUpdated(items) => {
+                self.apply_fetched(Mode::Disks, items);
+                Task::none()
+            }
+            Message::GpgUpdated(items) => {
+                self.apply_fetched(Mode::Gpg, items);
+                Task::none()
+            }
+            Message::TotpUpdated(items) => {
+                self.apply_fetched(Mode::Totp, items);
+                Task::none()
+            }
+            Message::SharesUpdated(items) => {
+                self.apply_fetched(Mode::Shares, items);
+                Task::none()
+            }
+            Message::BatteryUpdated(items) => {
+                self.apply_fetched(Mode::Battery, items);
+                Task::none()
+            }
+            Message::ScreenshotUpdated(items) => {
+                self.apply_fetched(Mode::Screenshot, items);
+                Task::none()
+            }
+            Message::GrepUpdated(items) => {
+                self.apply_fetched(Mode::Grep, items);
+                Task::none()
+            }
+            Message::ZoxideUpdated(items) => {
+                self.apply_fetched(Mode::Zoxide, items);
+                Task::none()
+            }
+            Message::PreviewLoaded(item_id, preview) => {
+                self.file_preview = Some((item_id, preview));
                 Task::none()
             }
-            Message::AiResponse(_response) => {
-                // Handle AI response - could show in details panel
+            Message::FilesSearchResult(items) => {
+                self.apply_fetched(Mode::Files, items);
                 Task::none()
             }
+            Message::AiResponse(ai::AiEvent::Chunk(text)) => {
+                self.ai_manager.push_chunk(&text);
+                if self.config.accessibility.reduced_motion() {
+                    Task::none()
+                } else {
+                    scrollable::snap_to(ai_chat_scrollable_id(), scrollable::RelativeOffset::END)
+                }
+            }
+            Message::AiResponse(ai::AiEvent::Done(error)) => {
+                self.ai_manager.finish(error);
+                if std::mem::take(&mut self.copy_ai_answer) {
+                    if let Some(answer) = self.ai_manager.last_answer().map(str::to_string) {
+                        let _ = self.clipboard_manager.copy(&answer);
+                    }
+                }
+                if self.mode == Mode::Ai {
+                    self.filter_items()
+                } else {
+                    Task::none()
+                }
+            }
             Message::TimerTick => {
                 self.timer_manager.tick();
+                self.todos_manager.tick();
                 Task::none()
             }
+            Message::SysInfoTick if self.mode == Mode::SysInfo => self.filter_items(),
+            Message::SysInfoTick => Task::none(),
+            Message::TotpTick if self.mode == Mode::Totp => self.filter_items(),
+            Message::TotpTick => Task::none(),
             Message::CopyToClipboard(content) => {
                 let _ = self.clipboard_manager.copy(&content);
                 window::get_latest().and_then(window::close)
             }
-            Message::OpenUrl(url) => {
-                let _ = Command::new("xdg-open").arg(&url).spawn();
-                window::get_latest().and_then(window::close)
+            Message::CommandCaptured(target, command, result) => {
+                self.loading = false;
+                let output = match result {
+                    Ok(output) => output,
+                    Err(error) => return Task::done(Message::ShowNotification(error)),
+                };
+                match target {
+                    CaptureTarget::Clipboard => self.paste(&output),
+                    CaptureTarget::Note => {
+                        self.notes_manager.add_note(&format!("$ {}\n{}", command, output));
+                        let _ = notify_rust::Notification::new().summary("Output saved as a note").body(&command).show();
+                        window::get_latest().and_then(window::close)
+                    }
+                }
+            }
+            Message::FocusChanged(result) => {
+                self.loading = false;
+                match result {
+                    Ok(summary) => {
+                        let _ = notify_rust::Notification::new().summary(&summary).show();
+                        window::get_latest().and_then(window::close)
+                    }
+                    Err(error) => Task::done(Message::ShowNotification(error)),
+                }
+            }
+            Message::GpgDone(result) => {
+                self.loading = false;
+                match result {
+                    Ok((text, summary)) => {
+                        let _ = self.clipboard_manager.copy(&text);
+                        let _ = notify_rust::Notification::new().summary(&summary).show();
+                        window::get_latest().and_then(window::close)
+                    }
+                    Err(error) => Task::done(Message::ShowNotification(error)),
+                }
             }
             Message::ShowNotification(msg) => {
                 let _ = notify_rust::Notification::new()
@@ -386,12 +1262,17 @@ impl WLaunch {
                     .show();
                 Task::none()
             }
-            Message::CloseWindow | Message::Escape | Message::WindowUnfocused => {
-                window::get_latest().and_then(window::close)
-            }
             Message::KeyPressed(key, modifiers) => {
                 self.handle_key(key, modifiers)
             }
+            Message::EventOccurred(Event::Window(window::Event::Resized(size))) => {
+                self.window_width = size.width;
+                Task::none()
+            }
+            Message::EventOccurred(Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers))) => {
+                self.modifiers = modifiers;
+                Task::none()
+            }
             Message::EventOccurred(_event) => {
                 // Focus handling disabled - was causing immediate close
                 Task::none()
@@ -399,8 +1280,8 @@ impl WLaunch {
         }
     }
 
-    pub fn view(&self) -> Element<Message> {
-        let search_input = text_input("Search apps, commands, files...", &self.search_query)
+    pub fn view(&self) -> Element<'_, Message> {
+        let search_input = text_input(Mode::Apps.placeholder(), &self.search_query)
             .on_input(Message::SearchChanged)
             .on_submit(Message::SearchSubmit)
             .padding(15)
@@ -408,21 +1289,41 @@ impl WLaunch {
             .id(text_input::Id::new("search"))
             .style(theme::search_input);
 
-        let search_bar = container(search_input)
+        // The input is never empty once a prefix selects a mode, so the
+        // mode's placeholder is shown next to it instead
+        let search_row: Element<Message> = if self.mode != Mode::Apps && self.mode_query.is_empty() {
+            row![
+                search_input,
+                text(self.mode.placeholder()).size(14).style(theme::secondary_text)
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            .into()
+        } else {
+            search_input.into()
+        };
+
+        let search_bar = container(search_row)
             .width(Length::Fill)
             .padding(10)
             .style(theme::search_container);
 
         // Results list
         let results: Element<Message> = if self.filtered_items.is_empty() {
-            container(
-                text("No results found")
-                    .size(14)
-                    .style(theme::secondary_text),
-            )
+            let mut empty = column![text(if self.loading { "Loading…" } else { "No results found" })
+                .size(14)
+                .style(theme::secondary_text)]
+            .spacing(8)
+            .align_x(iced::Alignment::Center);
+            if let Some(hint) = self.mode.hint().filter(|_| !self.loading) {
+                empty = empty.push(text(hint).size(12).style(theme::secondary_text));
+            }
+            container(empty)
             .padding(20)
             .center_x(Length::Fill)
             .into()
+        } else if self.mode == Mode::Emoji {
+            self.render_emoji_grid()
         } else {
             let items: Vec<Element<Message>> = self
                 .filtered_items
@@ -466,12 +1367,29 @@ impl WLaunch {
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
-        Subscription::batch([
+        let mut subscriptions = vec![
             keyboard::on_key_press(|key, modifiers| {
                 Some(Message::KeyPressed(key, modifiers))
             }),
             event::listen().map(Message::EventOccurred),
-        ])
+        ];
+        // Only tick while something needs animating
+        if self.progress.values().any(|p| *p < 0.0) && !self.config.accessibility.reduced_motion() {
+            subscriptions.push(iced::time::every(Duration::from_millis(40)).map(|_| Message::ProgressTick));
+        }
+        // Finished timers and overdue todos are checked every second
+        if self.timer_manager.is_running() || self.todos_manager.has_pending_reminders() {
+            subscriptions.push(iced::time::every(Duration::from_secs(1)).map(|_| Message::TimerTick));
+        }
+        // The system dashboard is live while open
+        if self.mode == Mode::SysInfo {
+            subscriptions.push(iced::time::every(Duration::from_secs(2)).map(|_| Message::SysInfoTick));
+        }
+        // So 2FA codes and their countdown stay current
+        if self.mode == Mode::Totp {
+            subscriptions.push(iced::time::every(Duration::from_secs(1)).map(|_| Message::TotpTick));
+        }
+        Subscription::batch(subscriptions)
     }
 
     fn render_item(&self, index: usize, item: &Item) -> Element<'static, Message> {
@@ -498,7 +1416,9 @@ impl WLaunch {
             text(icon_text).size(16).into()
         };
 
-        let name = text(item.name.clone()).size(14);
+        let (rule_color, rule_badges) = row_style(&self.config.row_rules, item);
+        let warning_color = item.metadata.warning.then(|| theme::palette().danger);
+        let name = text(item.name.clone()).size(14).color_maybe(warning_color.or(rule_color));
 
         let description = item
             .description
@@ -515,11 +1435,45 @@ impl WLaunch {
 
         let mut row_content: Row<'static, Message> = row![icon_element, name].spacing(10).align_y(iced::Alignment::Center);
 
+        for label in rule_badges {
+            row_content = row_content.push(
+                container(text(label.to_string()).size(10).color_maybe(rule_color))
+                    .padding([1, 6])
+                    .style(theme::badge),
+            );
+        }
+        if self.mode == Mode::Apps {
+            if let Some(label) = Self::main_search_badge(&item.item_type) {
+                row_content = row_content.push(badge(label.to_string()));
+            }
+        }
+        if item.item_type == ItemType::Todo {
+            let priority = item.metadata.priority.iter().map(|p| format!("!{}", p));
+            let due = item.metadata.due.iter().cloned();
+            let recurrence = item.metadata.recurrence.iter().map(|r| format!("↻ {}", r));
+            let project = item.metadata.project.iter().map(|p| format!("@{}", p));
+            let tags = item.metadata.tags.iter().map(|t| format!("#{}", t));
+            for chip in priority.chain(due).chain(recurrence).chain(project).chain(tags) {
+                row_content = row_content.push(badge(chip));
+            }
+        }
+
         if let Some(desc) = description {
             row_content = row_content.push(horizontal_space()).push(desc);
         }
 
-        let btn = button(row_content)
+        let mut content: Column<'static, Message> = column![row_content].spacing(6);
+        if let Some(&fraction) = self.progress.get(&item.id) {
+            // Unknown totals sweep the bar instead of filling it
+            let value = if fraction < 0.0 { self.progress_phase } else { fraction };
+            content = content.push(
+                progress_bar(0.0..=1.0, value)
+                    .height(4)
+                    .style(theme::progress_bar_style),
+            );
+        }
+
+        let btn = button(content)
             .width(Length::Fill)
             .padding([8, 12])
             .on_press(Message::ExecuteItem(index))
@@ -530,20 +1484,111 @@ impl WLaunch {
                 }
             });
 
-        btn.into()
+        // on_move rather than on_enter: keyboard scrolling slides rows under a
+        // resting pointer, which shouldn't steal the selection.
+        mouse_area(btn)
+            .on_move(move |_| Message::HoverItem(index))
+            .on_middle_press(Message::CopyItem(index))
+            .into()
     }
 
-    fn render_details(&self) -> Element<Message> {
-        if let Some(item) = self.filtered_items.get(self.selected_index) {
-            let title = text(&item.name).size(20);
-
-            let type_badge = text(format!("{:?}", item.item_type))
-                .size(12)
-                .style(theme::accent_text);
+    fn render_emoji_grid(&self) -> Element<'static, Message> {
+        let columns = self.emoji_columns();
+        let rows: Vec<Element<'static, Message>> = self
+            .filtered_items
+            .chunks(columns)
+            .enumerate()
+            .map(|(row_index, chunk)| {
+                let cells: Vec<Element<'static, Message>> = chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(column, item)| {
+                        let index = row_index * columns + column;
+                        let selected = index == self.selected_index;
+                        let cell = button(
+                            text(item.name.clone())
+                                .size(28)
+                                .center()
+                                .width(Length::Fill)
+                                .height(Length::Fill),
+                        )
+                        .width(EMOJI_CELL)
+                        .height(EMOJI_CELL)
+                        .padding(0)
+                        .on_press(Message::ExecuteItem(index))
+                        .style(move |theme, status| match status {
+                            button::Status::Hovered => theme::item_button_hover(theme),
+                            _ => theme::item_button(theme, selected),
+                        });
+                        mouse_area(cell)
+                            .on_move(move |_| Message::HoverItem(index))
+                            .on_middle_press(Message::CopyItem(index))
+                            .into()
+                    })
+                    .collect();
+                Row::with_children(cells).spacing(EMOJI_SPACING).into()
+            })
+            .collect();
 
-            let mut content = column![title, type_badge].spacing(10);
+        scrollable(Column::with_children(rows).spacing(EMOJI_SPACING))
+            .id(results_scrollable_id())
+            .height(Length::Fill)
+            .style(theme::scrollable_style)
+            .into()
+    }
+
+    /// How many emoji cells fit across the results panel.
+    fn emoji_columns(&self) -> usize {
+        // The results panel gets 6 of 10 portions after padding and spacing,
+        // less its own padding and the scrollbar
+        let width = (self.window_width - 30.0) * 0.6 - 20.0;
+        ((width + EMOJI_SPACING) / (EMOJI_CELL + EMOJI_SPACING)).floor().max(1.0) as usize
+    }
+
+    fn render_details(&self) -> Element<'_, Message> {
+        if self.mode == Mode::Ai && !self.ai_manager.conversation().is_empty() {
+            return self.render_chat();
+        }
+
+        if let Some(item) = self.filtered_items.get(self.selected_index) {
+            let title = text(&item.name).size(20);
+
+            let type_badge = text(format!("{:?}", item.item_type))
+                .size(12)
+                .style(theme::accent_text);
+
+            let mut content = column![title, type_badge].spacing(10);
+            let details = self.details_text.as_ref().filter(|details| details.item_id == item.id);
+            if let Some(details) = details {
+                let (label, selecting) = if details.selecting { ("Done", false) } else { ("Select text", true) };
+                content = content.push(
+                    row![
+                        button(text("Copy all").size(13))
+                            .padding([4, 12])
+                            .on_press(Message::CopyToClipboard(details.source.clone())),
+                        button(text(label).size(13)).padding([4, 12]).on_press(Message::DetailsSelect(selecting)),
+                    ]
+                    .spacing(8),
+                );
+            }
 
-            if let Some(desc) = &item.description {
+            // Notes, snippets and answers are shown in full, rendered
+            let source = match item.item_type {
+                ItemType::Note if self.note_editor.as_ref().is_some_and(|editor| editor.editing) => None,
+                ItemType::Note | ItemType::Snippet | ItemType::AiResponse => item.metadata.content.as_deref(),
+                _ => None,
+            };
+            if let Some(details) = details.filter(|details| details.selecting) {
+                content = content.push(
+                    text_editor(&details.content)
+                        .on_action(Message::DetailsAction)
+                        .padding(10)
+                        .size(14)
+                        .style(theme::note_editor),
+                );
+            } else if let Some(source) = source {
+                content = content.push(markdown::view(source, 14.0));
+            } else if let Some(desc) = &item.description {
                 content = content.push(
                     text(desc)
                         .size(14)
@@ -553,15 +1598,21 @@ impl WLaunch {
 
             // Add metadata based on item type
             content = self.add_metadata_to_details(content, item);
+            if let Some(actions) = self.file_actions.as_ref().filter(|actions| actions.item_id == item.id) {
+                content = content.push(Self::render_file_actions(actions));
+            }
 
             // Add action hint
             let hint = text("Press Enter to execute")
                 .size(12)
                 .style(theme::secondary_text);
 
-            content = content.push(iced::widget::vertical_space()).push(hint);
-
-            content.spacing(15).into()
+            // Long notes, answers and previews scroll; the hint stays put
+            let body = scrollable(content.spacing(15))
+                .id(details_scrollable_id())
+                .height(Length::Fill)
+                .style(theme::scrollable_style);
+            column![body, hint].spacing(15).into()
         } else {
             column![
                 text("No item selected").size(16).style(theme::secondary_text)
@@ -570,8 +1621,37 @@ impl WLaunch {
         }
     }
 
+    /// The session's AI conversation, with the answer being streamed last.
+    fn render_chat(&self) -> Element<'_, Message> {
+        let mut chat = Column::new().spacing(15);
+
+        for turn in self.ai_manager.conversation() {
+            let mut answer = turn.answer.clone();
+            if turn.streaming && !self.config.accessibility.reduced_motion() {
+                answer.push('▍');
+            }
+
+            let mut entry = column![
+                text(&turn.question).size(14).style(theme::accent_text),
+                markdown::view(&answer, 14.0),
+            ]
+            .spacing(6);
+
+            if let Some(error) = &turn.error {
+                entry = entry.push(text(error).size(12).style(theme::secondary_text));
+            }
+            chat = chat.push(entry);
+        }
+
+        scrollable(chat)
+            .id(ai_chat_scrollable_id())
+            .height(Length::Fill)
+            .style(theme::scrollable_style)
+            .into()
+    }
+
     fn add_metadata_to_details<'a>(
-        &self,
+        &'a self,
         mut content: Column<'a, Message>,
         item: &Item,
     ) -> Column<'a, Message> {
@@ -591,6 +1671,13 @@ impl WLaunch {
                             .style(theme::secondary_text),
                     );
                 }
+                if let Some(prefix) = &item.metadata.wine_prefix {
+                    content = content.push(
+                        text(format!("Wine prefix: {}", prefix.display()))
+                            .size(12)
+                            .style(theme::secondary_text),
+                    );
+                }
             }
             ItemType::Process => {
                 if let Some(pid) = item.metadata.pid {
@@ -603,7 +1690,110 @@ impl WLaunch {
                     content = content.push(text(format!("Memory: {:.1}%", mem)).size(12));
                 }
             }
-            ItemType::File | ItemType::RecentFile => {
+            ItemType::Snippet => {
+                let fresh;
+                let form = match &self.snippet_form {
+                    Some(form) if form.item_id == item.id => Some(form),
+                    _ => {
+                        fresh = SnippetForm::new(item);
+                        fresh.as_ref()
+                    }
+                };
+                if let Some(form) = form {
+                    for (index, (name, value)) in form.fields.iter().enumerate() {
+                        content = content.push(
+                            text_input(name, value)
+                                .id(snippet_field_id(index))
+                                .on_input(move |value| Message::SnippetField(index, value))
+                                .on_submit(Message::SnippetFieldSubmit(index))
+                                .padding(8)
+                                .size(14)
+                                .style(theme::search_input),
+                        );
+                    }
+                    content = content
+                        .push(text(form.expand(None)).size(12))
+                        .push(
+                            text("Enter to go to the next field, and to copy from the last one")
+                                .size(12)
+                                .style(theme::secondary_text),
+                        );
+                }
+            }
+            ItemType::NetworkShare => {
+                if let Some(login) = self.share_login.as_ref().filter(|login| login.item_id == item.id) {
+                    if !login.error.is_empty() {
+                        content = content.push(text(&login.error).size(12).style(theme::secondary_text));
+                    }
+                    content = content
+                        .push(
+                            text_input("User (DOMAIN\\user)", &login.user)
+                                .id(share_login_id("user"))
+                                .on_input(Message::ShareLoginUser)
+                                .on_submit(Message::ShareLoginSubmit)
+                                .padding(8)
+                                .size(14)
+                                .style(theme::search_input),
+                        )
+                        .push(
+                            text_input("Password", &login.password)
+                                .id(share_login_id("password"))
+                                .secure(true)
+                                .on_input(Message::ShareLoginPassword)
+                                .on_submit(Message::ShareLoginSubmit)
+                                .padding(8)
+                                .size(14)
+                                .style(theme::search_input),
+                        )
+                        .push(checkbox("Remember in the keyring", login.remember).on_toggle(Message::ShareLoginRemember).size(14))
+                        .push(text("Enter to mount").size(12).style(theme::secondary_text));
+                }
+            }
+            ItemType::Note => {
+                if let Some(editor) = self.note_editor.as_ref().filter(|editor| editor.item_id == item.id) {
+                    let (action, hint) = if editor.editing {
+                        content = content.push(
+                            text_editor(&editor.content)
+                                .on_action(Message::NoteEdited)
+                                .height(Length::Fixed(220.0))
+                                .padding(10)
+                                .size(14)
+                                .style(theme::note_editor),
+                        );
+                        let label = if editor.dirty { "Save*" } else { "Save" };
+                        (button(text(label).size(13)).on_press(Message::NoteSave), "Ctrl+S to save")
+                    } else {
+                        (button(text("Edit").size(13)).on_press(Message::NoteEdit), "Ctrl+E to edit")
+                    };
+                    content = content.push(
+                        row![
+                            action.padding([4, 12]),
+                            button(text("Delete").size(13)).padding([4, 12]).on_press(Message::NoteDelete),
+                            text(hint).size(12).style(theme::secondary_text),
+                        ]
+                        .spacing(8)
+                        .align_y(iced::Alignment::Center),
+                    );
+                }
+            }
+            ItemType::GrepMatch => {
+                if let Some(preview) = &item.metadata.content {
+                    content = content.push(
+                        container(text(preview.clone()).size(12).font(iced::Font::MONOSPACE))
+                            .padding(8)
+                            .width(Length::Fill)
+                            .style(theme::search_container),
+                    );
+                }
+            }
+            ItemType::Todo => {
+                content = content.push(
+                    text("Ctrl+E to edit, Alt+Up/Down to move")
+                        .size(12)
+                        .style(theme::secondary_text),
+                );
+            }
+            ItemType::File | ItemType::RecentFile | ItemType::Folder => {
                 if let Some(path) = &item.metadata.path {
                     content = content.push(
                         text(format!("Path: {}", path.display()))
@@ -614,6 +1804,52 @@ impl WLaunch {
                 if let Some(size) = item.metadata.size {
                     content = content.push(text(format!("Size: {} bytes", size)).size(12));
                 }
+                // The text is in the editor while it's being selected
+                let selecting = self.details_text.as_ref().is_some_and(|details| details.item_id == item.id && details.selecting);
+                if let Some(preview) = self
+                    .file_preview
+                    .as_ref()
+                    .filter(|(id, preview)| *id == item.id && !(selecting && matches!(preview, FilePreview::Text(_))))
+                    .and_then(|(_, preview)| Self::render_preview(preview))
+                {
+                    content = content.push(preview);
+                }
+                content = content.push(
+                    text("Ctrl+D to drag into another app, Alt+Enter to copy, move, rename or trash")
+                        .size(12)
+                        .style(theme::secondary_text),
+                );
+            }
+            ItemType::Emoji => {
+                content = content.push(text(item.name.clone()).size(64));
+                if EmojiManager::supports_tones(&item.name) {
+                    let mut tones = row![].spacing(4);
+                    for tone in std::iter::once(None).chain((0..emoji::SKIN_TONES.len()).map(Some)) {
+                        tones = tones.push(
+                            button(text(EmojiManager::with_tone(&item.name, tone)).size(20))
+                                .padding(4)
+                                .on_press(Message::EmojiTone(tone))
+                                .style(|theme, status| match status {
+                                    button::Status::Hovered => theme::item_button_hover(theme),
+                                    _ => theme::item_button(theme, false),
+                                }),
+                        );
+                    }
+                    content = content.push(tones).push(
+                        text("Ctrl+0 to Ctrl+5 copy a skin tone")
+                            .size(12)
+                            .style(theme::secondary_text),
+                    );
+                }
+            }
+            ItemType::Error => {
+                if let Some(error) = &item.metadata.error {
+                    content = content.push(
+                        text(error.clone())
+                            .size(12)
+                            .style(theme::secondary_text),
+                    );
+                }
             }
             ItemType::WifiNetwork => {
                 if let Some(signal) = item.metadata.signal_strength {
@@ -634,6 +1870,19 @@ impl WLaunch {
                 if let Some(ws) = &item.metadata.workspace {
                     content = content.push(text(format!("Workspace: {}", ws)).size(12));
                 }
+                if let Some(class) = &item.metadata.wm_class {
+                    content = content.push(text(format!("Class: {}", class)).size(12));
+                }
+            }
+            ItemType::Display => {
+                if let Some(brightness) = item.metadata.brightness {
+                    content = content.push(text(format!("Brightness: {}%", brightness)).size(12));
+                }
+                content = content.push(
+                    text("Type a number to set brightness, or \"contrast 70\"")
+                        .size(12)
+                        .style(theme::secondary_text),
+                );
             }
             ItemType::DockerContainer => {
                 if let Some(status) = &item.metadata.container_status {
@@ -642,6 +1891,26 @@ impl WLaunch {
                 if let Some(image) = &item.metadata.image {
                     content = content.push(text(format!("Image: {}", image)).size(12));
                 }
+                if let Some(ports) = &item.metadata.ports {
+                    content = content.push(text("Ports:").size(12));
+                    for port in ports.split(", ") {
+                        content = content.push(text(format!("  {}", port)).size(12));
+                    }
+                }
+            }
+            ItemType::WebSearch => {
+                let answer = self.wiki_answer.as_ref().filter(|answer| {
+                    item.metadata.search_engine.as_deref() == Some("wikipedia")
+                        && item.metadata.query.as_ref() == Some(&answer.term)
+                });
+                if let Some(answer) = answer {
+                    if let Some(thumbnail) = &answer.thumbnail {
+                        content = content.push(image(thumbnail.clone()).height(Length::Fixed(160.0)));
+                    }
+                    content = content
+                        .push(text(&answer.summary.title).size(16))
+                        .push(text(&answer.summary.extract).size(14));
+                }
             }
             ItemType::SshConnection => {
                 if let Some(host) = &item.metadata.host {
@@ -657,89 +1926,630 @@ impl WLaunch {
         content
     }
 
-    fn filter_items(&mut self) {
+    fn filter_items(&mut self) -> Task<Message> {
+        if self.config.kiosk() && !self.mode.available_in_kiosk() {
+            self.fetch_generation.fetch_add(1, Ordering::SeqCst);
+            self.loading = false;
+            self.filtered_items = vec![Item::new("kiosk:disabled", "Not available in kiosk mode", ItemType::Command)
+                .with_description("This launcher can search and launch, but not change anything")
+                .with_icon("changes-prevent")];
+            return Task::none();
+        }
+
+        // Modes that shell out or hit the disk are refreshed in the background
+        let fetch = match self.mode {
+            Mode::Processes => Some(self.fetch(
+                self.process_manager.clone(),
+                ProcessManager::get_items,
+                Message::ProcessesUpdated,
+            )),
+            Mode::Windows => Some(self.fetch(
+                self.windows_manager.clone(),
+                WindowsManager::get_items,
+                Message::WindowsUpdated,
+            )),
+            Mode::Wifi => Some(self.fetch(
+                self.network_manager.clone(),
+                NetworkManager::get_items,
+                Message::NetworkUpdated,
+            )),
+            Mode::Bluetooth => Some(self.fetch(
+                self.bluetooth_manager.clone(),
+                BluetoothManager::get_items,
+                Message::BluetoothUpdated,
+            )),
+            Mode::Audio => Some(self.fetch(
+                self.audio_manager.clone(),
+                AudioManager::get_items,
+                Message::AudioUpdated,
+            )),
+            Mode::Display => Some(self.fetch(
+                (self.brightness_manager.clone(), self.night_mode_manager.clone()),
+                |(brightness, night_mode), query| {
+                    let mut items = brightness.get_items(query);
+                    items.extend(night_mode.get_items(query));
+                    items
+                },
+                Message::DisplayUpdated,
+            )),
+            Mode::Docker => Some(self.fetch(
+                self.docker_manager.clone(),
+                DockerManager::get_items,
+                Message::DockerUpdated,
+            )),
+            Mode::Files => Some(self.fetch(
+                self.file_manager.clone(),
+                FileManager::get_items,
+                Message::FilesSearchResult,
+            )),
+            Mode::Grep => Some(self.fetch(self.content_search.clone(), ContentSearch::get_items, Message::GrepUpdated)),
+            Mode::Zoxide => Some(self.fetch(self.zoxide_manager.clone(), ZoxideManager::get_items, Message::ZoxideUpdated)),
+            Mode::Bitwarden => Some(self.fetch(
+                self.bitwarden_manager.clone(),
+                BitwardenManager::get_items,
+                Message::BitwardenUpdated,
+            )),
+            Mode::Tabs => Some(self.fetch(
+                self.tabs_manager.clone(),
+                TabsManager::get_items,
+                Message::TabsUpdated,
+            )),
+            Mode::Git => Some(self.fetch(
+                self.git_manager.clone(),
+                GitManager::get_items,
+                Message::GitUpdated,
+            )),
+            Mode::Tmux => Some(self.fetch(
+                self.tmux_manager.clone(),
+                TmuxManager::get_items,
+                Message::TmuxUpdated,
+            )),
+            Mode::Kubernetes => Some(self.fetch(
+                self.kubernetes_manager.clone(),
+                KubernetesManager::get_items,
+                Message::KubernetesUpdated,
+            )),
+            Mode::Vms => Some(self.fetch(self.vm_manager.clone(), VmManager::get_items, Message::VmsUpdated)),
+            Mode::Packages => Some(self.fetch(
+                self.packages_manager.clone(),
+                PackagesManager::get_items,
+                Message::PackagesUpdated,
+            )),
+            Mode::Flatpak => Some(self.fetch(self.flatpak_manager.clone(), FlatpakManager::get_items, Message::FlatpakUpdated)),
+            Mode::Logs => Some(self.fetch(self.journal_manager.clone(), JournalManager::get_items, Message::LogsUpdated)),
+            Mode::SysInfo => Some(self.fetch(self.sysinfo.clone(), SysInfo::get_items, Message::SysInfoUpdated)),
+            Mode::Disks => Some(self.fetch(self.disks_manager.clone(), DisksManager::get_items, Message::DisksUpdated)),
+            Mode::Gpg => Some(self.fetch(self.gpg_manager.clone(), GpgManager::get_items, Message::GpgUpdated)),
+            Mode::Totp => Some(self.fetch(self.totp_manager.clone(), TotpManager::get_items, Message::TotpUpdated)),
+            Mode::Shares => Some(self.fetch(self.shares_manager.clone(), SharesManager::get_items, Message::SharesUpdated)),
+            Mode::Battery => Some(self.fetch(self.battery_manager.clone(), BatteryManager::get_items, Message::BatteryUpdated)),
+            Mode::Screenshot => Some(self.fetch(
+                self.screenshot_manager.clone(),
+                ScreenshotManager::get_items,
+                Message::ScreenshotUpdated,
+            )),
+            _ => None,
+        };
+        if let Some(task) = fetch {
+            self.filtered_items = self.cached_results.get(&self.mode).cloned().unwrap_or_default();
+            return task;
+        }
+
+        // Anything still in flight belongs to a mode we just left
+        self.fetch_generation.fetch_add(1, Ordering::SeqCst);
+        self.loading = false;
+
         match self.mode {
             Mode::Apps => {
                 let query = self.mode_query.to_lowercase();
                 if query.is_empty() {
-                    self.filtered_items = self.all_items.clone();
-                } else {
-                    let mut items: Vec<(Item, i64)> = self
+                    // Boosted SSH hosts, snippets and apps come first
+                    let (mut items, rest): (Vec<Item>, Vec<Item>) = self
                         .all_items
                         .iter()
-                        .filter_map(|item| {
-                            let score = item.fuzzy_score(&query);
-                            if score > 0 {
-                                Some((item.clone(), score))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-
-                    items.sort_by(|a, b| b.1.cmp(&a.1));
-                    self.filtered_items = items.into_iter().map(|(item, _)| item).collect();
+                        .cloned()
+                        .partition(|item| self.is_boosted(item));
+                    if self.context.is_some() {
+                        let extra = self.main_search_items().into_iter().filter(|item| self.is_boosted(item));
+                        items.splice(0..0, extra);
+                    }
+                    items.extend(rest);
+                    self.filtered_items = items;
+                } else {
+                    let extra = self.main_search_items();
+                    let mut items = rank_items(self.all_items.iter().chain(&extra), &query, |item| self.is_boosted(item));
+                    if self.context.as_ref().is_some_and(|rule| rule.boosts("web")) {
+                        items.splice(0..0, self.websearch_manager.get_items(&self.mode_query).into_iter().take(1));
+                    }
+                    self.filtered_items = WindowsManager::with_running_instances(items, &self.open_windows);
+                }
+                if self.progress.contains_key(INDEXING_ID) {
+                    self.filtered_items.insert(
+                        0,
+                        Item::new(INDEXING_ID, "Indexing applications...", ItemType::Command)
+                            .with_description("Results will update when done")
+                            .with_icon("view-refresh"),
+                    );
                 }
             }
             Mode::Clipboard => {
                 self.filtered_items = self.clipboard_manager.get_items(&self.mode_query);
             }
-            Mode::Processes => {
-                self.filtered_items = self.process_manager.get_items(&self.mode_query);
-            }
-            Mode::Windows => {
-                self.filtered_items = self.windows_manager.get_items(&self.mode_query);
-            }
-            Mode::Wifi => {
-                self.filtered_items = self.network_manager.get_items(&self.mode_query);
-            }
-            Mode::Bluetooth => {
-                self.filtered_items = self.bluetooth_manager.get_items(&self.mode_query);
-            }
-            Mode::Audio => {
-                self.filtered_items = self.audio_manager.get_items(&self.mode_query);
-            }
             Mode::Notes => {
+                self.notes_manager.reload_if_changed();
                 self.filtered_items = self.notes_manager.get_items(&self.mode_query);
             }
             Mode::Todos => {
+                self.todos_manager.reload_if_changed();
                 self.filtered_items = self.todos_manager.get_items(&self.mode_query);
+                if let Some(sync) = self.todos_manager.start_sync() {
+                    return Task::perform(sync, |result| Message::TodosSynced(result.map_err(|e| e.to_string())));
+                }
             }
             Mode::Snippets => {
+                self.snippets_manager.reload_if_changed();
                 self.filtered_items = self.snippets_manager.get_items(&self.mode_query);
             }
             Mode::Ssh => {
                 self.filtered_items = self.ssh_manager.get_items(&self.mode_query);
             }
-            Mode::Docker => {
-                self.filtered_items = self.docker_manager.get_items(&self.mode_query);
-            }
             Mode::Timer => {
                 self.filtered_items = self.timer_manager.get_items(&self.mode_query);
             }
+            Mode::Focus => {
+                self.filtered_items = self.focus_manager.get_items(&self.mode_query);
+            }
+            Mode::Browse => {
+                self.filtered_items = self.directory_browser.get_items(&self.mode_query);
+            }
+            Mode::Run => {
+                self.filtered_items = self.shell_manager.get_items(&self.mode_query);
+            }
+            Mode::Idle => {
+                self.filtered_items = self.idle_manager.get_items(&self.mode_query);
+            }
+            Mode::Session => {
+                self.filtered_items = self.session_manager.get_items(&self.mode_query);
+            }
             Mode::Emoji => {
                 self.filtered_items = self.emoji_manager.get_items(&self.mode_query);
             }
-            Mode::Files => {
-                self.filtered_items = self.file_manager.get_items(&self.mode_query);
-            }
             Mode::RecentFiles => {
                 self.filtered_items = self.recent_files_manager.get_items(&self.mode_query);
             }
-            Mode::Bitwarden => {
-                self.filtered_items = self.bitwarden_manager.get_items(&self.mode_query);
-            }
             Mode::Ai => {
                 self.filtered_items = self.ai_manager.get_items(&self.mode_query);
+                if let Some(answer) = self.ai_manager.last_answer() {
+                    let mut item = Item::new("ai:copy", "Copy Answer", ItemType::AiResponse)
+                        .with_description("Copy the last answer to the clipboard")
+                        .with_icon("edit-copy");
+                    item.metadata.content = Some(answer.to_string());
+                    self.filtered_items.push(item);
+                }
             }
             Mode::WebSearch => {
                 self.filtered_items = self.websearch_manager.get_items(&self.mode_query);
+                return self.wiki_answer_task();
             }
             Mode::Calculator => {
-                self.filtered_items = self.calculator.get_items(&self.mode_query);
+                self.filtered_items = self.calculator.get_items(&self.mode_query, self.converter.rates());
+                if self.calculator.uses_currency(&self.mode_query, self.converter.rates()) {
+                    if let Some(url) = self.converter.refresh_rates() {
+                        return Task::perform(converter::currency::Rates::fetch(url), |result| {
+                            Message::CurrencyRatesUpdated(result.map_err(|e| e.to_string()))
+                        });
+                    }
+                }
             }
             Mode::Converter => {
                 self.filtered_items = self.converter.get_items(&self.mode_query);
+                if let Some(url) = self.converter.rates_refresh(&self.mode_query) {
+                    return Task::perform(converter::currency::Rates::fetch(url), |result| {
+                        Message::CurrencyRatesUpdated(result.map_err(|e| e.to_string()))
+                    });
+                }
+            }
+            Mode::DateCalc => {
+                self.filtered_items = self.date_calculator.get_items(&self.mode_query);
+            }
+            Mode::Hash => {
+                let clipboard = if self.mode_query.is_empty() && !self.config.kiosk() {
+                    self.clipboard_manager.get_text()
+                } else {
+                    None
+                };
+                self.filtered_items = self.crypto_tools.get_items(&self.mode_query, clipboard);
+            }
+            Mode::Generator => {
+                self.filtered_items = self.generator.get_items(&self.mode_query);
+            }
+            Mode::Unicode => {
+                self.filtered_items = self.unicode_search.get_items(&self.mode_query);
+            }
+            Mode::Capture => {
+                self.filtered_items = self.quick_capture.get_items(&self.mode_query);
+            }
+            Mode::Bookmarks => {
+                self.filtered_items = self.bookmarks_manager.get_items(&self.mode_query);
+            }
+            Mode::Projects => {
+                self.filtered_items = self.projects_manager.get_items(&self.mode_query);
+            }
+            Mode::Maps => {
+                self.filtered_items = self.websearch_manager.map_items(&self.mode_query);
+            }
+            Mode::Doctor => {
+                self.filtered_items = self.doctor.get_items(&self.mode_query);
+            }
+            Mode::Stats => {
+                self.filtered_items = self.insights.get_items(&self.mode_query, self.config.insights());
+            }
+            Mode::WorldClock => {
+                self.filtered_items = self.world_clock.get_items(&self.mode_query);
+            }
+            Mode::Wine => {
+                self.filtered_items = self.wine_manager.get_items(&self.mode_query, &self.all_items);
+            }
+            Mode::Theme => {
+                self.filtered_items = self.theme_items(&self.mode_query);
+            }
+            Mode::Profile => {
+                // Read each time, profiles may have been added by another instance
+                self.filtered_items = ProfileManager::new().get_items(&self.mode_query);
+            }
+            _ => {}
+        }
+        Task::none()
+    }
+
+    /// Runs `get_items` on the blocking pool once typing pauses. Results are
+    /// dropped if another fetch was started in the meantime.
+    fn fetch<M: Send + 'static>(
+        &mut self,
+        manager: M,
+        get_items: fn(&M, &str) -> Vec<Item>,
+        on_done: fn(Vec<Item>) -> Message,
+    ) -> Task<Message> {
+        let generation = self.fetch_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let current = self.fetch_generation.clone();
+        let query = self.mode_query.clone();
+        let feature = self.config.insights().then(|| format!("{:?}", self.mode));
+        self.loading = true;
+
+        Task::perform(
+            async move {
+                tokio::time::sleep(FETCH_DEBOUNCE).await;
+                if current.load(Ordering::SeqCst) != generation {
+                    return None;
+                }
+                let items = tokio::task::spawn_blocking(move || {
+                    let started = Instant::now();
+                    let items = get_items(&manager, &query);
+                    if let Some(feature) = feature {
+                        let failed = items.iter().any(|item| item.item_type == ItemType::Error);
+                        Insights::record(&feature, started.elapsed(), failed);
+                    }
+                    items
+                })
+                .await
+                .ok()?;
+                (current.load(Ordering::SeqCst) == generation).then_some(items)
+            },
+            |items| items,
+        )
+        .and_then(move |items| Task::done(on_done(items)))
+    }
+
+    fn apply_fetched(&mut self, mode: Mode, items: Vec<Item>) {
+        if self.mode == mode {
+            self.filtered_items = items.clone();
+            self.loading = false;
+            if self.selected_index >= self.filtered_items.len() {
+                self.selected_index = self.filtered_items.len().saturating_sub(1);
+            }
+        }
+        self.cached_results.insert(mode, items);
+    }
+
+    /// Looks up the instant answer for a `wiki <term>` search once typing
+    /// pauses, unless it is already known.
+    fn wiki_answer_task(&mut self) -> Task<Message> {
+        let Some(term) = self.wiki_term() else {
+            return Task::none();
+        };
+        if self.apply_wiki_answer() {
+            return Task::none();
+        }
+
+        let generation = self.wiki_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let current = self.wiki_generation.clone();
+        Task::perform(
+            async move {
+                tokio::time::sleep(websearch::wikipedia::DEBOUNCE).await;
+                if current.load(Ordering::SeqCst) != generation {
+                    return None;
+                }
+                let result = websearch::wikipedia::fetch(term.clone()).await;
+                Some(Message::WikiAnswer(term, result.map_err(|e| e.to_string())))
+            },
+            |message| message,
+        )
+        .and_then(Task::done)
+    }
+
+    /// The term of the Wikipedia search being shown, if any.
+    fn wiki_term(&self) -> Option<String> {
+        self.filtered_items
+            .first()
+            .filter(|item| item.metadata.search_engine.as_deref() == Some("wikipedia"))
+            .and_then(|item| item.metadata.query.clone())
+    }
+
+    /// Points the Wikipedia search at the answer's article, so Enter opens
+    /// it directly; returns whether the answer is for the current term.
+    fn apply_wiki_answer(&mut self) -> bool {
+        let Some(answer) = self.wiki_answer.as_ref().filter(|answer| Some(&answer.term) == self.wiki_term().as_ref()) else {
+            return false;
+        };
+        for item in &mut self.filtered_items {
+            item.metadata.url = Some(answer.summary.url.clone());
+        }
+        true
+    }
+
+    fn theme_items(&self, query: &str) -> Vec<Item> {
+        let query = query.to_lowercase();
+        let current = self.config.theme.preset.as_deref().unwrap_or("dark");
+
+        theme::Palette::PRESETS
+            .iter()
+            .filter(|(name, _)| query.is_empty() || name.contains(&query))
+            .map(|(name, _)| {
+                Item::new(format!("theme:{}", name), *name, ItemType::Theme)
+                    .with_description(if *name == current {
+                        "Current theme"
+                    } else {
+                        "Press Enter to apply"
+                    })
+                    .with_icon("preferences-desktop-theme")
+            })
+            .collect()
+    }
+
+    /// Entities other than applications the prefix-less search matches,
+    /// per `main_search` in the config.
+    fn main_search_items(&self) -> Vec<Item> {
+        let mut items = Vec::new();
+        if self.config.kiosk() {
+            return items;
+        }
+        if self.config.main_search.ssh() {
+            items.extend(self.ssh_manager.get_items(""));
+        }
+        if self.config.main_search.snippets() {
+            items.extend(self.snippets_manager.pinned_items());
+        }
+        if self.config.main_search.idle() {
+            items.extend(self.idle_manager.get_items(""));
+        }
+        if self.config.main_search.bookmarks() {
+            items.extend(self.bookmarks_manager.all_items());
+        }
+        items
+    }
+
+    /// Whether the context rule favors `item` in the main search.
+    fn is_boosted(&self, item: &Item) -> bool {
+        let Some(rule) = &self.context else {
+            return false;
+        };
+        match item.item_type {
+            ItemType::SshConnection => rule.boosts("ssh"),
+            ItemType::Snippet => rule.boosts("snippets"),
+            ItemType::WebSearch => rule.boosts("web"),
+            _ => rule.boosts(&item.name),
+        }
+    }
+
+    /// Short label marking non-application results in the main search.
+    fn main_search_badge(item_type: &ItemType) -> Option<&'static str> {
+        match item_type {
+            ItemType::SshConnection => Some("SSH"),
+            ItemType::Snippet => Some("Snippet"),
+            ItemType::IdleAction => Some("System"),
+            ItemType::Bookmark => Some("Bookmark"),
+            _ => None,
+        }
+    }
+
+    /// Points the note editor at the selected note, saving edits to the note
+    /// it leaves.
+    fn sync_note_editor(&mut self) {
+        let selected = self
+            .filtered_items
+            .get(self.selected_index)
+            .filter(|item| item.item_type == ItemType::Note)
+            .map(|item| (item.id.clone(), item.metadata.content.clone().unwrap_or_default()));
+        if self.note_editor.as_ref().map(|editor| &editor.item_id) == selected.as_ref().map(|(id, _)| id) {
+            return;
+        }
+        if self.save_note_editor() {
+            let _ = self.filter_items();
+        }
+        self.note_editor = selected.map(|(item_id, content)| NoteEditor {
+            item_id,
+            content: text_editor::Content::with_text(&content),
+            editing: false,
+            dirty: false,
+        });
+    }
+
+    /// The text shown for `item` that "Copy all" copies: a note, snippet or
+    /// answer, a text file's first lines, or a description long enough to
+    /// wrap.
+    fn details_source<'a>(item: &'a Item, file_preview: &'a Option<(String, FilePreview)>) -> Option<&'a str> {
+        let source = match item.item_type {
+            ItemType::Note | ItemType::Snippet | ItemType::AiResponse => item.metadata.content.as_deref(),
+            ItemType::File | ItemType::RecentFile => match file_preview {
+                Some((id, FilePreview::Text(lines))) if *id == item.id => Some(lines.as_str()),
+                _ => None,
+            },
+            _ => item
+                .description
+                .as_deref()
+                .filter(|desc| desc.contains('\n') || desc.chars().count() > LONG_DESCRIPTION),
+        };
+        source.filter(|text| !text.trim().is_empty())
+    }
+
+    /// Keeps the selectable text in step with the selected item, which may
+    /// change under it (a preview loading, an answer streaming in).
+    fn sync_details_text(&mut self) {
+        let selected = self
+            .filtered_items
+            .get(self.selected_index)
+            .and_then(|item| Some((item.id.as_str(), Self::details_source(item, &self.file_preview)?)));
+        DetailsText::sync(&mut self.details_text, selected);
+    }
+
+    /// Saves unsaved edits; returns whether there were any.
+    fn save_note_editor(&mut self) -> bool {
+        let Some(editor) = self.note_editor.as_mut().filter(|editor| editor.dirty) else {
+            return false;
+        };
+        editor.dirty = false;
+        let text = editor.content.text();
+        let text = text.strip_suffix('\n').unwrap_or(&text);
+        self.notes_manager.update_note(editor.item_id.trim_start_matches("note:"), text);
+        true
+    }
+
+    /// The placeholder form of the selected snippet, started afresh when the
+    /// selection moved to another snippet.
+    fn selected_snippet_form(&mut self) -> Option<&mut SnippetForm> {
+        let item = self.filtered_items.get(self.selected_index)?;
+        if item.item_type != ItemType::Snippet {
+            return None;
+        }
+        if self.snippet_form.as_ref().map(|form| &form.item_id) != Some(&item.id) {
+            self.snippet_form = SnippetForm::new(item);
+        }
+        self.snippet_form.as_mut()
+    }
+
+    /// Mounts the share with the typed login, opening it and closing on
+    /// success; otherwise shows why and asks for the password again.
+    fn submit_share_login(&mut self) -> Task<Message> {
+        let Some(login) = &mut self.share_login else {
+            return Task::none();
+        };
+        if login.user.is_empty() {
+            return text_input::focus(share_login_id("user"));
+        }
+        if login.password.is_empty() {
+            return text_input::focus(share_login_id("password"));
+        }
+        match self.shares_manager.mount(&login.uri, Some((&login.user, &login.password))) {
+            Ok(()) => {
+                if login.remember {
+                    self.shares_manager.store_login(&login.uri, &login.user, &login.password);
+                }
+                self.shares_manager.open(&login.uri);
+                self.share_login = None;
+                window::get_latest().and_then(window::close)
+            }
+            Err(MountError::NeedsLogin(error) | MountError::Failed(error)) => {
+                login.error = error;
+                login.password.clear();
+                text_input::focus(share_login_id("password"))
+            }
+        }
+    }
+
+    fn copy_snippet_form(&mut self) -> Task<Message> {
+        match self.snippet_form.take() {
+            Some(form) => {
+                let clipboard = self.clipboard_manager.get_text();
+                self.paste(&form.expand(clipboard.as_deref()))
+            }
+            None => window::get_latest().and_then(window::close),
+        }
+    }
+
+    /// Copies `text` and closes, typing it into the previously focused window
+    /// as well when `paste_on_select` is set.
+    fn paste(&mut self, text: &str) -> Task<Message> {
+        let _ = self.clipboard_manager.copy(text);
+        let close = window::get_latest().and_then(window::close);
+        if !self.config.paste_on_select() {
+            return close;
+        }
+        match ClipboardManager::type_into_focused(text) {
+            Ok(()) => close,
+            Err(e) => Task::done(Message::ShowNotification(e.to_string())).chain(close),
+        }
+    }
+
+    /// Moves the app's next new window to `workspace` from a detached
+    /// `wlaunch place-window`, since the launcher exits before it appears.
+    fn place_on_workspace(&self, app: &Item, workspace: &str) {
+        let windows = self.windows_manager.list_windows("");
+        let known = WindowsManager::windows_for_app(app, &windows)
+            .iter()
+            .filter_map(|window| window.metadata.window_id)
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let classes = WindowsManager::app_classes(app).join(",");
+        let spawned = std::env::current_exe().and_then(|exe| {
+            Command::new(exe)
+                .args(["place-window", workspace, &classes, &known])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+        });
+        if let Err(e) = spawned {
+            log::error!("Failed to watch for {}'s window: {}", app.name, e);
+        }
+    }
+
+    /// Runs an app or script; `workspace` overrides the app's configured
+    /// one.
+    fn launch(&self, item: &Item, workspace: Option<&str>) {
+        if let Some(exec) = &item.exec {
+            // Clean up exec string (remove %f, %F, %u, %U, etc.)
+            let exec_clean = exec
+                .replace("%f", "")
+                .replace("%F", "")
+                .replace("%u", "")
+                .replace("%U", "")
+                .replace("%c", "")
+                .replace("%k", "")
+                .replace("%i", "")
+                .trim()
+                .to_string();
+
+            let app_override = self.config.app_override(item).cloned().unwrap_or_default();
+            if let Some(workspace) = workspace.or(app_override.workspace.as_deref()) {
+                self.place_on_workspace(item, workspace);
+            }
+            let exec_clean = std::iter::once(exec_clean)
+                .chain(app_override.args)
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            if item.metadata.terminal {
+                let _ = Command::new("x-terminal-emulator")
+                    .arg("-e")
+                    .arg(&exec_clean)
+                    .envs(&app_override.env)
+                    .spawn();
+            } else {
+                let _ = Command::new("sh")
+                    .arg("-c")
+                    .arg(&exec_clean)
+                    .envs(&app_override.env)
+                    .spawn();
             }
         }
     }
@@ -753,30 +2563,12 @@ impl WLaunch {
     }
 
     fn execute_item(&mut self, item: &Item) -> Task<Message> {
+        if self.config.kiosk() && !item.item_type.is_read_only() {
+            return Task::done(Message::ShowNotification("Disabled in kiosk mode".to_string()));
+        }
         match item.item_type {
             ItemType::Application | ItemType::Script => {
-                if let Some(exec) = &item.exec {
-                    // Clean up exec string (remove %f, %F, %u, %U, etc.)
-                    let exec_clean = exec
-                        .replace("%f", "")
-                        .replace("%F", "")
-                        .replace("%u", "")
-                        .replace("%U", "")
-                        .replace("%c", "")
-                        .replace("%k", "")
-                        .replace("%i", "")
-                        .trim()
-                        .to_string();
-
-                    if item.metadata.terminal {
-                        let _ = Command::new("x-terminal-emulator")
-                            .arg("-e")
-                            .arg(&exec_clean)
-                            .spawn();
-                    } else {
-                        let _ = Command::new("sh").arg("-c").arg(&exec_clean).spawn();
-                    }
-                }
+                self.launch(item, None);
                 window::get_latest().and_then(window::close)
             }
             ItemType::Window => {
@@ -785,12 +2577,17 @@ impl WLaunch {
                 }
                 window::get_latest().and_then(window::close)
             }
+            ItemType::WindowGroup | ItemType::WindowAction => {
+                if self.windows_manager.execute_action(&item.id) {
+                    return window::get_latest().and_then(window::close);
+                }
+                self.filter_items()
+            }
             ItemType::Process => {
                 if let Some(pid) = item.metadata.pid {
                     self.process_manager.kill_process(pid);
-                    self.filter_items();
                 }
-                Task::none()
+                self.filter_items()
             }
             ItemType::WifiNetwork => {
                 if let Some(ssid) = &item.metadata.ssid {
@@ -800,8 +2597,7 @@ impl WLaunch {
             }
             ItemType::WifiAction => {
                 self.network_manager.execute_action(&item.id);
-                self.filter_items();
-                Task::none()
+                self.filter_items()
             }
             ItemType::BluetoothDevice => {
                 if let Some(mac) = &item.metadata.mac_address {
@@ -811,8 +2607,7 @@ impl WLaunch {
             }
             ItemType::BluetoothAction => {
                 self.bluetooth_manager.execute_action(&item.id);
-                self.filter_items();
-                Task::none()
+                self.filter_items()
             }
             ItemType::AudioSink => {
                 if let Some(sink_id) = &item.metadata.sink_id {
@@ -822,8 +2617,25 @@ impl WLaunch {
             }
             ItemType::AudioAction => {
                 self.audio_manager.execute_action(&item.id, &self.mode_query);
-                self.filter_items();
-                Task::none()
+                self.filter_items()
+            }
+            ItemType::Display => Task::none(),
+            ItemType::DisplayAction if item.id.starts_with("night:") => {
+                self.night_mode_manager.execute_action(&item.id);
+                // Night mode may have switched the theme preset
+                self.config = Config::load().unwrap_or_default();
+                theme::apply_config(&self.config);
+                self.filter_items()
+            }
+            ItemType::DisplayAction => {
+                self.brightness_manager.execute_action(&item.id);
+                self.filter_items()
+            }
+            ItemType::GrepMatch => {
+                if self.content_search.open(item) {
+                    return window::get_latest().and_then(window::close);
+                }
+                Task::done(Message::ShowNotification(format!("Couldn't open {}", item.description.clone().unwrap_or_default())))
             }
             ItemType::File | ItemType::RecentFile => {
                 if let Some(path) = &item.metadata.path {
@@ -832,12 +2644,55 @@ impl WLaunch {
                 }
                 window::get_latest().and_then(window::close)
             }
-            ItemType::ClipboardEntry => {
-                if let Some(content) = &item.metadata.clipboard_content {
-                    let _ = self.clipboard_manager.copy(content);
+            ItemType::Folder => match &item.metadata.path {
+                Some(path) => self.set_query(format!("cd {}", DirectoryBrowser::query_path(path))),
+                None => Task::none(),
+            },
+            ItemType::ShellAction => match ShellManager::capture_target(&item.id) {
+                Some((target, command)) => {
+                    self.loading = true;
+                    let command = command.to_string();
+                    Task::perform(ShellManager::capture(command.clone()), move |result| {
+                        Message::CommandCaptured(target, command.clone(), result)
+                    })
+                }
+                None if self.shell_manager.run_in_terminal(&item.id) => window::get_latest().and_then(window::close),
+                None => Task::none(),
+            },
+            ItemType::Place => {
+                if let Some(uri) = &item.metadata.uri {
+                    if let Err(e) = Command::new("xdg-open").arg(uri).spawn() {
+                        log::error!("Failed to open {}: {}", uri, e);
+                    }
                 }
                 window::get_latest().and_then(window::close)
             }
+            ItemType::FolderAction => {
+                if self.directory_browser.execute_action(&item.id) {
+                    return window::get_latest().and_then(window::close);
+                }
+                Task::none()
+            }
+            ItemType::ClipboardEntry => match &item.metadata.clipboard_content {
+                Some(content) => self.paste(content),
+                None => window::get_latest().and_then(window::close),
+            },
+            ItemType::ClipboardAction => match item.id.strip_prefix("clipboard:merge:") {
+                Some(count) => match self.clipboard_manager.merged(count.parse().unwrap_or(0)) {
+                    Some(merged) => self.paste(&merged),
+                    None => Task::done(Message::ShowNotification("The clipboard history is empty".to_string())),
+                },
+                None => match self.clipboard_manager.export(&item.id) {
+                    Ok(path) => {
+                        let _ = notify_rust::Notification::new()
+                            .summary("Clipboard history exported")
+                            .body(&path.display().to_string())
+                            .show();
+                        window::get_latest().and_then(window::close)
+                    }
+                    Err(e) => Task::done(Message::ShowNotification(format!("Export failed: {}", e))),
+                },
+            },
             ItemType::Note => {
                 if let Some(content) = &item.metadata.content {
                     let _ = self.clipboard_manager.copy(content);
@@ -846,29 +2701,61 @@ impl WLaunch {
             }
             ItemType::NoteAction => {
                 self.notes_manager.execute_action(&item.id, &self.mode_query);
-                self.filter_items();
-                Task::none()
+                self.filter_items()
             }
             ItemType::Snippet => {
-                if let Some(content) = &item.metadata.content {
-                    let _ = self.clipboard_manager.copy(content);
+                // Snippets with placeholders are filled in from the details panel first
+                if let Some(form) = self.selected_snippet_form() {
+                    return match form.fields.iter().position(|(_, value)| value.is_empty()) {
+                        Some(empty) => text_input::focus(snippet_field_id(empty)),
+                        None => self.copy_snippet_form(),
+                    };
                 }
-                window::get_latest().and_then(window::close)
+                match &item.metadata.content {
+                    Some(content) => {
+                        let clipboard = self.clipboard_manager.get_text();
+                        self.paste(&snippets::expand(content, &[], clipboard.as_deref()))
+                    }
+                    None => window::get_latest().and_then(window::close),
+                }
+            }
+            ItemType::SnippetAction if item.id.starts_with("snippet:import:") => {
+                let path = Config::expand_home(item.id.trim_start_matches("snippet:import:"));
+                let message = match self.snippets_manager.import(&path) {
+                    Ok(0) => "No new snippets found".to_string(),
+                    Ok(count) => format!("Imported {} snippets", count),
+                    Err(e) => format!("Import failed: {}", e),
+                };
+                self.search_query = "snip ".to_string();
+                self.mode_query.clear();
+                self.selected_index = 0;
+                Task::done(Message::ShowNotification(message)).chain(self.filter_items())
             }
             ItemType::SnippetAction => {
                 self.snippets_manager.execute_action(&item.id, &self.mode_query);
-                self.filter_items();
-                Task::none()
+                self.filter_items()
             }
             ItemType::Todo => {
                 self.todos_manager.toggle_todo(&item.id);
-                self.filter_items();
-                Task::none()
+                self.filter_items()
+            }
+            ItemType::TodoAction if item.id.starts_with("todo:edit:") => {
+                self.todos_manager.execute_action(&item.id, &self.mode_query);
+                self.search_query = "todo ".to_string();
+                self.mode_query.clear();
+                self.selected_index = 0;
+                self.filter_items()
+            }
+            ItemType::TodoAction if item.id.starts_with("todo:filter:") => {
+                self.search_query = format!("todo {} ", item.name);
+                self.mode = Mode::Todos;
+                self.mode_query = item.name.clone();
+                self.selected_index = 0;
+                self.filter_items()
             }
             ItemType::TodoAction => {
                 self.todos_manager.execute_action(&item.id, &self.mode_query);
-                self.filter_items();
-                Task::none()
+                self.filter_items()
             }
             ItemType::SshConnection => {
                 if let Some(host) = &item.metadata.host {
@@ -883,34 +2770,259 @@ impl WLaunch {
             }
             ItemType::SshAction => {
                 self.ssh_manager.execute_action(&item.id, &self.mode_query);
-                self.filter_items();
+                self.filter_items()
+            }
+            ItemType::VirtualMachine => {
+                if self.vm_manager.activate(item) {
+                    return window::get_latest().and_then(window::close);
+                }
+                self.filter_items()
+            }
+            ItemType::VmAction => {
+                if self.vm_manager.execute_action(&item.id) {
+                    return window::get_latest().and_then(window::close);
+                }
+                self.filter_items()
+            }
+            ItemType::Package => {
+                if self.packages_manager.activate(item) {
+                    return window::get_latest().and_then(window::close);
+                }
                 Task::none()
             }
+            ItemType::PackageAction => {
+                if self.packages_manager.execute_action(&item.id) {
+                    return window::get_latest().and_then(window::close);
+                }
+                Task::none()
+            }
+            ItemType::GpgKey | ItemType::GpgAction => {
+                // gpg waits for pinentry while decrypting or signing
+                self.loading = true;
+                let clipboard = self.clipboard_manager.get_text();
+                let manager = self.gpg_manager.clone();
+                let id = item.id.clone();
+                let query = self.mode_query.clone();
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || manager.execute_action(&id, &query, clipboard.as_deref()))
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()))
+                    },
+                    Message::GpgDone,
+                )
+            }
+            ItemType::TotpCode => match self.totp_manager.code(&item.id["totp:".len()..]) {
+                Some(code) => self.paste(&code),
+                None => Task::done(Message::ShowNotification(format!("No secret for {} in the keyring", item.name))),
+            },
+            ItemType::TotpAction => match self.totp_manager.execute_action(&item.id) {
+                Ok(()) => {
+                    self.search_query = "2fa ".to_string();
+                    self.mode = Mode::Totp;
+                    self.mode_query.clear();
+                    self.selected_index = 0;
+                    self.filter_items()
+                }
+                Err(error) => Task::done(Message::ShowNotification(error)),
+            },
+            ItemType::NetworkShare => {
+                let uri = &item.id["share:".len()..];
+                match self.shares_manager.mount(uri, None) {
+                    Ok(()) => {
+                        self.shares_manager.open(uri);
+                        window::get_latest().and_then(window::close)
+                    }
+                    Err(MountError::NeedsLogin(error)) => {
+                        self.share_login = Some(ShareLogin {
+                            item_id: item.id.clone(),
+                            uri: uri.to_string(),
+                            error,
+                            ..Default::default()
+                        });
+                        text_input::focus(share_login_id("user"))
+                    }
+                    Err(MountError::Failed(error)) => Task::done(Message::ShowNotification(error)),
+                }
+            }
+            ItemType::ShareAction => {
+                self.search_query = format!("share {} ", &item.id["share:server:".len()..]);
+                self.mode = Mode::Shares;
+                self.mode_query = self.search_query["share ".len()..].to_string();
+                self.selected_index = 0;
+                self.filter_items()
+            }
+            ItemType::LogAction if item.id.starts_with("logs:unit:") => {
+                self.search_query = format!("logs unit {} ", &item.id["logs:unit:".len()..]);
+                self.mode = Mode::Logs;
+                self.mode_query = self.search_query["logs ".len()..].to_string();
+                self.selected_index = 0;
+                self.filter_items()
+            }
+            ItemType::LogEntry | ItemType::LogAction => {
+                if self.journal_manager.execute_action(&item.id) {
+                    return window::get_latest().and_then(window::close);
+                }
+                Task::none()
+            }
+            ItemType::Disk => {
+                if self.disks_manager.activate(item) {
+                    return window::get_latest().and_then(window::close);
+                }
+                self.filter_items()
+            }
+            ItemType::DiskAction => {
+                self.disks_manager.execute_action(&item.id);
+                self.filter_items()
+            }
+            ItemType::PowerProfile => {
+                self.battery_manager.execute_action(&item.id);
+                self.filter_items()
+            }
+            ItemType::Flatpak => {
+                if self.flatpak_manager.activate(item) {
+                    return window::get_latest().and_then(window::close);
+                }
+                Task::none()
+            }
+            ItemType::FlatpakAction => {
+                if self.flatpak_manager.execute_action(&item.id) {
+                    return window::get_latest().and_then(window::close);
+                }
+                Task::none()
+            }
+            ItemType::TmuxSession => {
+                self.tmux_manager.attach(item);
+                window::get_latest().and_then(window::close)
+            }
+            ItemType::TmuxAction => {
+                if self.tmux_manager.execute_action(&item.id) {
+                    return window::get_latest().and_then(window::close);
+                }
+                self.search_query = "tmux ".to_string();
+                self.mode_query.clear();
+                self.selected_index = 0;
+                self.filter_items()
+            }
             ItemType::DockerContainer => {
                 if let Some(container_id) = &item.metadata.container_id {
                     self.docker_manager.toggle_container(container_id);
-                    self.filter_items();
                 }
-                Task::none()
+                self.filter_items()
             }
-            ItemType::DockerAction => {
-                self.docker_manager.execute_action(&item.id);
-                self.filter_items();
-                Task::none()
+            ItemType::DockerAction if item.id.starts_with("docker:list:") => {
+                self.search_query = format!("docker {} ", item.id.trim_start_matches("docker:list:"));
+                self.mode_query = self.search_query[7..].to_string();
+                self.selected_index = 0;
+                self.filter_items()
             }
-            ItemType::Emoji => {
-                let _ = self.clipboard_manager.copy(&item.name);
+            ItemType::DockerAction | ItemType::DockerCompose => {
+                if self.docker_manager.execute_action(&item.id) {
+                    return window::get_latest().and_then(window::close);
+                }
+                self.filter_items()
+            }
+            ItemType::DockerImage => {
+                self.docker_manager.run_image(item);
+                window::get_latest().and_then(window::close)
+            }
+            ItemType::DockerVolume => {
+                if let Some(content) = &item.metadata.content {
+                    let _ = self.clipboard_manager.copy(content);
+                }
                 window::get_latest().and_then(window::close)
             }
+            ItemType::KubeAction if item.id.starts_with("k8s:list:") => {
+                self.search_query = format!("k8s {} ", item.id.trim_start_matches("k8s:list:"));
+                self.mode_query = self.search_query[4..].to_string();
+                self.selected_index = 0;
+                self.filter_items()
+            }
+            ItemType::KubePod | ItemType::KubeAction => {
+                if self.kubernetes_manager.execute_action(&item.id) {
+                    return window::get_latest().and_then(window::close);
+                }
+                self.search_query = "k8s ".to_string();
+                self.mode_query.clear();
+                self.selected_index = 0;
+                self.filter_items()
+            }
+            ItemType::Emoji => {
+                self.emoji_manager.record_use(&item.name);
+                self.paste(&item.name)
+            }
             ItemType::Timer => {
                 self.timer_manager.execute_action(&item.id);
-                self.filter_items();
-                Task::none()
+                self.filter_items()
             }
             ItemType::TimerAction => {
                 self.timer_manager.execute_action(&item.id);
-                self.filter_items();
-                Task::none()
+                self.filter_items()
+            }
+            ItemType::IdleAction => match self.idle_manager.execute_action(&item.id) {
+                Ok(summary) => {
+                    if let Some(summary) = summary {
+                        let _ = notify_rust::Notification::new().summary(&summary).show();
+                    }
+                    window::get_latest().and_then(window::close)
+                }
+                Err(error) => Task::done(Message::ShowNotification(error)),
+            },
+            ItemType::ScreenshotAction => match self.screenshot_manager.execute_action(&item.id) {
+                Ok(()) => window::get_latest().and_then(window::close),
+                Err(e) => Task::done(Message::ShowNotification(e.to_string())),
+            },
+            ItemType::SessionAction => {
+                if let Some(name) = item.id.strip_prefix("session:save:") {
+                    let windows = self.windows_manager.list_windows("");
+                    return match self.session_manager.save(name, &windows, &self.all_items) {
+                        Ok(count) => {
+                            let _ = notify_rust::Notification::new()
+                                .summary(&format!("Session \"{}\" saved", name))
+                                .body(&format!("{} apps", count))
+                                .show();
+                            window::get_latest().and_then(window::close)
+                        }
+                        Err(e) => Task::done(Message::ShowNotification(e.to_string())),
+                    };
+                }
+                let name = item.id.strip_prefix("session:restore:").unwrap_or_default();
+                for entry in self.session_manager.session(name) {
+                    match self.all_items.iter().find(|app| app.id == entry.app) {
+                        Some(app) => self.launch(app, Some(&entry.workspace)),
+                        None => log::warn!("{} from session {} is no longer installed", entry.app, name),
+                    }
+                }
+                window::get_latest().and_then(window::close)
+            }
+            ItemType::FocusAction => {
+                // pkexec waits for the password prompt
+                self.loading = true;
+                let manager = self.focus_manager.clone();
+                let id = item.id.clone();
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || manager.execute_action(&id))
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()))
+                    },
+                    Message::FocusChanged,
+                )
+            }
+            ItemType::Calculator if item.id == "calc:eval" => {
+                if !Calculator::is_assignment(&self.mode_query) {
+                    if let Some(content) = &item.metadata.content {
+                        let _ = self.clipboard_manager.copy(content);
+                    }
+                }
+                self.calculator.commit(&self.mode_query, self.converter.rates());
+
+                // Stay open on the history so the next line can use ans
+                self.search_query = "= ".to_string();
+                self.mode = Mode::Calculator;
+                self.mode_query.clear();
+                self.selected_index = 0;
+                self.filter_items()
             }
             ItemType::Calculator => {
                 if let Some(content) = &item.metadata.content {
@@ -918,15 +3030,64 @@ impl WLaunch {
                 }
                 window::get_latest().and_then(window::close)
             }
-            ItemType::Converter => {
+            ItemType::Converter
+            | ItemType::WorldClock
+            | ItemType::DateCalc
+            | ItemType::CryptoTool
+            | ItemType::HealthCheck
+            | ItemType::SysInfo
+            | ItemType::Battery
+            | ItemType::Generator
+            | ItemType::UnicodeChar => {
                 if let Some(content) = &item.metadata.content {
                     let _ = self.clipboard_manager.copy(content);
                 }
                 window::get_latest().and_then(window::close)
             }
-            ItemType::WebSearch => {
+            ItemType::Capture => {
+                let text = item.metadata.content.as_deref().unwrap_or_default();
+                let close = window::get_latest().and_then(window::close);
+                match item.id.as_str() {
+                    "capture:note" => self.notes_manager.add_note(text),
+                    "capture:todo" => self.todos_manager.add_todo(text),
+                    _ => {
+                        if let Err(e) = self.quick_capture.append(text) {
+                            return Task::done(Message::ShowNotification(format!("Capture failed: {}", e))).chain(close);
+                        }
+                    }
+                }
+                close
+            }
+            ItemType::GitRepo => {
+                if let Some(path) = &item.metadata.path {
+                    self.git_manager.open_in_editor(path);
+                }
+                window::get_latest().and_then(window::close)
+            }
+            ItemType::GitAction => {
+                if let Some(content) = &item.metadata.content {
+                    let _ = self.clipboard_manager.copy(content);
+                } else if let Some(url) = &item.metadata.url {
+                    self.websearch_manager.open(url, None, false);
+                } else if let Some(path) = &item.metadata.path {
+                    self.git_manager.open_in_terminal(path);
+                }
+                window::get_latest().and_then(window::close)
+            }
+            ItemType::Project => {
+                ProjectsManager::open(item);
+                window::get_latest().and_then(window::close)
+            }
+            ItemType::BrowserTab => {
+                if let Err(e) = self.tabs_manager.focus_tab(&item.id) {
+                    log::error!("Failed to switch to tab: {}", e);
+                }
+                window::get_latest().and_then(window::close)
+            }
+            ItemType::WebSearch | ItemType::Bookmark => {
                 if let Some(url) = &item.metadata.url {
-                    let _ = Command::new("xdg-open").arg(url).spawn();
+                    let private = item.id.starts_with("websearch:private:");
+                    self.websearch_manager.open(url, item.metadata.search_engine.as_deref(), private);
                 }
                 window::get_latest().and_then(window::close)
             }
@@ -938,20 +3099,33 @@ impl WLaunch {
             }
             ItemType::BitwardenAction => {
                 self.bitwarden_manager.execute_action(&item.id);
-                self.filter_items();
-                Task::none()
+                self.filter_items()
+            }
+            ItemType::AiQuery if item.id.starts_with("ai:preset:") => {
+                if self.ai_manager.is_streaming() {
+                    return Task::none();
+                }
+                let Some(text) = self.clipboard_manager.get_text().filter(|t| !t.trim().is_empty()) else {
+                    return Task::done(Message::ShowNotification("Clipboard is empty".to_string()));
+                };
+                let Some(stream) = self.ai_manager.run_preset(&item.id, &text) else {
+                    return Task::none();
+                };
+                self.copy_ai_answer = true;
+                Task::run(stream, Message::AiResponse)
             }
             ItemType::AiQuery => {
-                // Trigger AI query
-                let query = self.mode_query.clone();
-                let ai = self.ai_manager.clone();
-                Task::perform(
-                    async move { ai.query(&query).await },
-                    |result| match result {
-                        Ok(response) => Message::AiResponse(response),
-                        Err(_) => Message::AiResponse("Error querying AI".to_string()),
-                    },
-                )
+                if !item.id.starts_with("ai:query:") || self.ai_manager.is_streaming() {
+                    return Task::none();
+                }
+                let stream = self.ai_manager.ask(&self.mode_query);
+
+                // Clear the question so a follow-up can be typed right away
+                self.search_query = "ai ".to_string();
+                self.mode_query.clear();
+                let refresh = self.filter_items();
+
+                Task::batch([refresh, Task::run(stream, Message::AiResponse)])
             }
             ItemType::AiResponse => {
                 if let Some(content) = &item.metadata.content {
@@ -959,15 +3133,94 @@ impl WLaunch {
                 }
                 window::get_latest().and_then(window::close)
             }
+            ItemType::WinePrefix => {
+                if let Some(prefix) = &item.metadata.wine_prefix {
+                    let _ = Command::new("xdg-open").arg(prefix.join("drive_c")).spawn();
+                }
+                window::get_latest().and_then(window::close)
+            }
+            ItemType::WineAction => {
+                self.wine_manager.execute_action(&item.id);
+                self.filter_items()
+            }
+            ItemType::Error => {
+                if item.id.starts_with("error:copy:") {
+                    if let Some(error) = &item.metadata.content {
+                        let _ = self.clipboard_manager.copy(error);
+                    }
+                    return window::get_latest().and_then(window::close);
+                }
+                // Retry: drop the cached failure and fetch again
+                self.cached_results.remove(&self.mode);
+                self.filter_items()
+            }
+            ItemType::Theme => {
+                if let Some(name) = item.id.strip_prefix("theme:") {
+                    self.config.theme.preset = Some(name.to_string());
+                    theme::apply_config(&self.config);
+                    // Only the preset is written, keeping the rest of config.json as is
+                    if let Err(e) = settings::set("theme.preset", &serde_json::Value::from(name).to_string()) {
+                        log::error!("Failed to save theme {}: {}", name, e);
+                    }
+                }
+                self.filter_items()
+            }
+            ItemType::Profile => {
+                let profiles = ProfileManager::new();
+                if let Some(name) = item.id.strip_prefix("profile:").filter(|name| !profiles.is_active(name)) {
+                    if let Err(e) = profiles.switch(name) {
+                        log::error!("Failed to switch to profile {}: {}", name, e);
+                        return Task::none();
+                    }
+                }
+                window::get_latest().and_then(window::close)
+            }
             _ => Task::none(),
         }
     }
 
     fn handle_key(&mut self, key: keyboard::Key, modifiers: keyboard::Modifiers) -> Task<Message> {
         match key.as_ref() {
+            keyboard::Key::Named(keyboard::key::Named::Escape) if self.file_actions.is_some() => {
+                self.file_actions = None;
+                text_input::focus(text_input::Id::new("search"))
+            }
             keyboard::Key::Named(keyboard::key::Named::Escape) => {
+                self.save_note_editor();
                 window::get_latest().and_then(window::close)
             }
+            keyboard::Key::Character("s") if modifiers.command() && self.note_editor.is_some() => {
+                self.save_note_editor();
+                self.filter_items()
+            }
+            // Alt+Up/Down reorders todos
+            keyboard::Key::Named(keyboard::key::Named::ArrowDown) if modifiers.alt() && self.mode == Mode::Todos => {
+                self.move_selected_todo(1)
+            }
+            keyboard::Key::Named(keyboard::key::Named::ArrowUp) if modifiers.alt() && self.mode == Mode::Todos => {
+                self.move_selected_todo(-1)
+            }
+            // The action panel takes the arrows until a name or folder is typed
+            keyboard::Key::Named(keyboard::key::Named::ArrowDown)
+                if self.selected_file_actions().is_some_and(|actions| actions.input.is_none()) =>
+            {
+                self.move_file_action(1)
+            }
+            keyboard::Key::Named(keyboard::key::Named::ArrowUp)
+                if self.selected_file_actions().is_some_and(|actions| actions.input.is_none()) =>
+            {
+                self.move_file_action(-1)
+            }
+            // The emoji grid moves by rows, and Tab steps through cells
+            keyboard::Key::Named(keyboard::key::Named::ArrowDown) if self.mode == Mode::Emoji => {
+                self.move_in_grid(self.emoji_columns() as isize)
+            }
+            keyboard::Key::Named(keyboard::key::Named::ArrowUp) if self.mode == Mode::Emoji => {
+                self.move_in_grid(-(self.emoji_columns() as isize))
+            }
+            keyboard::Key::Named(keyboard::key::Named::Tab) if self.mode == Mode::Emoji => {
+                self.move_in_grid(if modifiers.shift() { -1 } else { 1 })
+            }
             keyboard::Key::Named(keyboard::key::Named::ArrowDown) => {
                 if !self.filtered_items.is_empty() {
                     self.selected_index = (self.selected_index + 1) % self.filtered_items.len();
@@ -984,6 +3237,7 @@ impl WLaunch {
                 }
                 self.scroll_to_selected()
             }
+            keyboard::Key::Named(keyboard::key::Named::Enter) if modifiers.alt() => self.open_file_actions(),
             keyboard::Key::Named(keyboard::key::Named::Enter) => self.execute_selected(),
             keyboard::Key::Character(ref c) if modifiers.command() => {
                 let ch = c.to_string();
@@ -1004,6 +3258,19 @@ impl WLaunch {
                         }
                     }
                     self.scroll_to_selected()
+                // Ctrl+D = drag the selected file into another app
+                } else if ch == "d" {
+                    self.drag_selected()
+                // Ctrl+E = edit the selected todo in the search field
+                } else if ch == "e" && self.mode == Mode::Todos {
+                    self.edit_selected_todo()
+                // Ctrl+E = open the editor for the selected note
+                } else if ch == "e" && self.note_editor.is_some() {
+                    Task::done(Message::NoteEdit)
+                // Ctrl+0..5 = copy the selected emoji in a skin tone
+                } else if self.mode == Mode::Emoji && matches!(ch.as_str(), "0" | "1" | "2" | "3" | "4" | "5") {
+                    let tone = ch.parse::<usize>().ok().filter(|&t| t > 0).map(|t| t - 1);
+                    Task::done(Message::EmojiTone(tone))
                 } else {
                     Task::none()
                 }
@@ -1012,10 +3279,226 @@ impl WLaunch {
         }
     }
 
+    /// Opens the action panel of the selected file or folder.
+    fn open_file_actions(&mut self) -> Task<Message> {
+        let Some(item) = self.filtered_items.get(self.selected_index) else {
+            return Task::none();
+        };
+        match (&item.item_type, &item.metadata.path) {
+            (ItemType::File | ItemType::RecentFile | ItemType::Folder, Some(path)) if !self.config.kiosk() => {
+                self.file_actions = Some(FileActions::new(&item.id, path));
+            }
+            _ => {}
+        }
+        Task::none()
+    }
+
+    /// The action panel, if it belongs to the selected item; hovering
+    /// another row hides it.
+    fn selected_file_actions(&self) -> Option<&FileActions> {
+        let selected = self.filtered_items.get(self.selected_index)?;
+        self.file_actions.as_ref().filter(|actions| actions.item_id == selected.id)
+    }
+
+    fn move_file_action(&mut self, delta: isize) -> Task<Message> {
+        if let Some(actions) = &mut self.file_actions {
+            let len = FILE_ACTIONS.len() as isize;
+            actions.selected = (actions.selected as isize + delta).rem_euclid(len) as usize;
+        }
+        Task::none()
+    }
+
+    fn run_file_action(&mut self, index: usize) -> Task<Message> {
+        let Some(actions) = &mut self.file_actions else {
+            return Task::none();
+        };
+        actions.selected = index;
+        let path = actions.path.clone();
+        let result = match FILE_ACTIONS.get(index).map(|(id, _)| *id) {
+            Some("copy-path") => {
+                let _ = self.clipboard_manager.copy(&path.display().to_string());
+                Ok(None)
+            }
+            Some("copy-file") => FileManager::copy_file(&path).map(|()| None),
+            Some("move") => {
+                actions.start_input(FileInput::Move);
+                return text_input::focus(file_input_id());
+            }
+            Some("rename") => {
+                actions.start_input(FileInput::Rename);
+                return text_input::focus(file_input_id());
+            }
+            Some("trash") => FileManager::trash(&path).map(|()| Some(format!("Moved {} to the trash", path.display()))),
+            _ => return Task::none(),
+        };
+        self.file_actions = None;
+        match result {
+            Ok(None) => window::get_latest().and_then(window::close),
+            Ok(Some(summary)) => {
+                let _ = notify_rust::Notification::new().summary(&summary).show();
+                self.filter_items()
+            }
+            Err(e) => Task::done(Message::ShowNotification(e.to_string())),
+        }
+    }
+
+    /// Renames or moves the file to what was typed, staying open on the
+    /// refreshed results; shows why it failed otherwise.
+    fn submit_file_input(&mut self) -> Task<Message> {
+        let Some(actions) = &mut self.file_actions else {
+            return Task::none();
+        };
+        let result = match &actions.input {
+            Some((FileInput::Rename, name)) => FileManager::rename(&actions.path, name),
+            Some((FileInput::Move, folder)) => FileManager::move_to(&actions.path, folder),
+            None => return Task::none(),
+        };
+        match result {
+            Ok(target) => {
+                self.file_actions = None;
+                let _ = notify_rust::Notification::new().summary(&format!("Now at {}", target.display())).show();
+                Task::batch([self.filter_items(), text_input::focus(text_input::Id::new("search"))])
+            }
+            Err(e) => {
+                actions.error = e.to_string();
+                Task::none()
+            }
+        }
+    }
+
+    fn render_file_actions(actions: &FileActions) -> Element<'_, Message> {
+        let mut panel = column![].spacing(4);
+        for (index, (id, label)) in FILE_ACTIONS.iter().enumerate() {
+            let selected = index == actions.selected;
+            panel = panel.push(
+                button(text(*label).size(14))
+                    .width(Length::Fill)
+                    .padding([6, 10])
+                    .on_press(Message::FileAction(index))
+                    .style(move |theme, status| match status {
+                        button::Status::Hovered => theme::item_button_hover(theme),
+                        _ => theme::item_button(theme, selected),
+                    }),
+            );
+            let input = actions.input.as_ref().filter(|(kind, _)| match kind {
+                FileInput::Rename => *id == "rename",
+                FileInput::Move => *id == "move",
+            });
+            if let Some((kind, value)) = input {
+                let placeholder = if *kind == FileInput::Rename { "New name" } else { "Folder" };
+                panel = panel.push(
+                    text_input(placeholder, value)
+                        .id(file_input_id())
+                        .on_input(Message::FileInputChanged)
+                        .on_submit(Message::FileInputSubmit)
+                        .padding(8)
+                        .size(14)
+                        .style(theme::search_input),
+                );
+                for folder in &actions.folders {
+                    let name = folder.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                    panel = panel.push(
+                        button(text(format!("{}/", name)).size(12))
+                            .width(Length::Fill)
+                            .padding([4, 20])
+                            .on_press(Message::FileFolderChosen(folder.clone()))
+                            .style(|theme, status| match status {
+                                button::Status::Hovered => theme::item_button_hover(theme),
+                                _ => theme::item_button(theme, false),
+                            }),
+                    );
+                }
+            }
+        }
+        if !actions.error.is_empty() {
+            panel = panel.push(text(&actions.error).size(12).style(theme::secondary_text));
+        }
+        let hint = match &actions.input {
+            Some((FileInput::Rename, _)) => "Enter to rename, Escape to cancel",
+            Some((FileInput::Move, _)) => "Pick a folder or type its path, Enter to move there",
+            None => "Up/Down and Enter to pick an action, Escape to cancel",
+        };
+        panel.push(text(hint).size(12).style(theme::secondary_text)).into()
+    }
+
+    fn drag_selected(&self) -> Task<Message> {
+        let Some(path) = self
+            .filtered_items
+            .get(self.selected_index)
+            .filter(|item| matches!(item.item_type, ItemType::File | ItemType::RecentFile | ItemType::Folder))
+            .and_then(|item| item.metadata.path.clone())
+        else {
+            return Task::none();
+        };
+
+        match FileManager::drag(&path, self.config.drag_tool.as_deref()) {
+            Ok(()) => window::get_latest().and_then(window::close),
+            Err(e) => Task::done(Message::ShowNotification(e.to_string())),
+        }
+    }
+
+    fn move_selected_todo(&mut self, delta: isize) -> Task<Message> {
+        let Some(item) = self.filtered_items.get(self.selected_index).filter(|i| i.item_type == ItemType::Todo) else {
+            return Task::none();
+        };
+        let id = item.id.clone();
+        if !self.todos_manager.move_todo(&id, delta) {
+            return Task::none();
+        }
+        let _ = self.filter_items();
+        // Follow the todo to its new row
+        if let Some(index) = self.filtered_items.iter().position(|i| i.id == id) {
+            self.selected_index = index;
+        }
+        self.scroll_to_selected()
+    }
+
+    /// Replaces the search text, e.g. to drill into a folder, with the
+    /// cursor at its end.
+    fn set_query(&mut self, query: String) -> Task<Message> {
+        let (mode, mode_query) = Mode::from_query(&query);
+        self.search_query = query;
+        self.mode = mode;
+        self.mode_query = mode_query;
+        self.selected_index = 0;
+        Task::batch([
+            self.filter_items(),
+            text_input::move_cursor_to_end(text_input::Id::new("search")),
+        ])
+    }
+
+    fn edit_selected_todo(&mut self) -> Task<Message> {
+        let Some(item) = self.filtered_items.get(self.selected_index).filter(|i| i.item_type == ItemType::Todo) else {
+            return Task::none();
+        };
+        let Some(query) = self.todos_manager.edit_query(&item.id) else {
+            return Task::none();
+        };
+        self.search_query = format!("todo {}", query);
+        self.mode_query = query;
+        self.selected_index = 0;
+        Task::batch([
+            self.filter_items(),
+            text_input::move_cursor_to_end(text_input::Id::new("search")),
+        ])
+    }
+
+    /// Moves the grid selection by `delta` cells, stopping at the ends.
+    fn move_in_grid(&mut self, delta: isize) -> Task<Message> {
+        if let Some(last) = self.filtered_items.len().checked_sub(1) {
+            self.selected_index = self.selected_index.saturating_add_signed(delta).min(last);
+        }
+        self.scroll_to_selected()
+    }
+
     fn scroll_to_selected(&self) -> Task<Message> {
         // Each item is approximately 42px (40px height + 2px spacing)
         const ITEM_HEIGHT: f32 = 42.0;
-        let offset = self.selected_index as f32 * ITEM_HEIGHT;
+        let offset = if self.mode == Mode::Emoji {
+            (self.selected_index / self.emoji_columns()) as f32 * (EMOJI_CELL + EMOJI_SPACING)
+        } else {
+            self.selected_index as f32 * ITEM_HEIGHT
+        };
         scrollable::scroll_to(
             results_scrollable_id(),
             scrollable::AbsoluteOffset { x: 0.0, y: offset },
@@ -1047,27 +3530,63 @@ impl WLaunch {
         match item_type {
             ItemType::Application => "[A]",
             ItemType::Script => "[#]",
-            ItemType::Window => "[W]",
+            ItemType::Window | ItemType::WindowGroup | ItemType::WindowAction | ItemType::SessionAction => "[W]",
             ItemType::WifiNetwork | ItemType::WifiAction => "[~]",
             ItemType::BluetoothDevice | ItemType::BluetoothAction => "[B]",
             ItemType::AudioSink | ItemType::AudioAction => "[S]",
-            ItemType::File | ItemType::Folder => "[F]",
+            ItemType::Display | ItemType::DisplayAction => "[*]",
+            ItemType::File | ItemType::Folder | ItemType::FolderAction | ItemType::Place => "[F]",
+            ItemType::GrepMatch => "[rg]",
             ItemType::RecentFile => "[R]",
-            ItemType::ClipboardEntry => "[C]",
+            ItemType::Project => "[P]",
+            ItemType::GitRepo | ItemType::GitAction => "[G]",
+            ItemType::ClipboardEntry | ItemType::ClipboardAction => "[C]",
             ItemType::Note | ItemType::NoteAction => "[N]",
             ItemType::Snippet | ItemType::SnippetAction => "[<]",
             ItemType::Todo | ItemType::TodoAction => "[T]",
             ItemType::SshConnection | ItemType::SshAction => "[$]",
-            ItemType::DockerContainer | ItemType::DockerAction => "[D]",
+            ItemType::NetworkShare | ItemType::ShareAction => "[sh]",
+            ItemType::TmuxSession | ItemType::TmuxAction => "[|]",
+            ItemType::VirtualMachine | ItemType::VmAction => "[VM]",
+            ItemType::Package | ItemType::PackageAction => "[pk]",
+            ItemType::Flatpak | ItemType::FlatpakAction => "[fp]",
+            ItemType::LogEntry | ItemType::LogAction => "[L]",
+            ItemType::SysInfo => "[i]",
+            ItemType::Disk | ItemType::DiskAction => "[dk]",
+            ItemType::Battery | ItemType::PowerProfile => "[bt]",
+            ItemType::DockerContainer
+            | ItemType::DockerAction
+            | ItemType::DockerImage
+            | ItemType::DockerVolume
+            | ItemType::DockerCompose => "[D]",
+            ItemType::KubePod | ItemType::KubeAction => "[k8]",
             ItemType::Process => "[P]",
             ItemType::Emoji => "[:)]",
             ItemType::Timer | ItemType::TimerAction => "[O]",
+            ItemType::FocusAction => "[fo]",
+            ItemType::IdleAction => "[id]",
+            ItemType::ScreenshotAction => "[sc]",
             ItemType::Calculator => "[=]",
             ItemType::Converter => "[>]",
+            ItemType::WorldClock => "[T]",
+            ItemType::DateCalc => "[31]",
+            ItemType::CryptoTool => "[#]",
+            ItemType::HealthCheck => "[+]",
+            ItemType::Generator => "[*]",
+            ItemType::UnicodeChar => "[U]",
+            ItemType::Capture => "[+]",
             ItemType::WebSearch => "[?]",
+            ItemType::Bookmark => "[*]",
+            ItemType::BrowserTab => "[W]",
             ItemType::BitwardenItem | ItemType::BitwardenAction => "[K]",
+            ItemType::GpgKey | ItemType::GpgAction => "[gp]",
+            ItemType::TotpCode | ItemType::TotpAction => "[2f]",
             ItemType::AiQuery | ItemType::AiResponse => "[AI]",
-            ItemType::Command => "[>]",
+            ItemType::WinePrefix | ItemType::WineAction => "[Wn]",
+            ItemType::Theme => "[%]",
+            ItemType::Profile => "[Pf]",
+            ItemType::Command | ItemType::ShellAction => "[>]",
+            ItemType::Error => "[!]",
         }
     }
 }
@@ -1077,3 +3596,240 @@ impl Default for WLaunch {
         Self::new().0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::converter::currency::Rates;
+    use std::path::Path;
+
+    /// Compares `actual` with the golden file `tests/snapshots/<name>.snap`.
+    /// Run with `UPDATE_SNAPSHOTS=1` to accept an intended change, then
+    /// review the diff.
+    fn assert_snapshot(name: &str, actual: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots").join(format!("{}.snap", name));
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("no snapshot at {}; run with UPDATE_SNAPSHOTS=1", path.display()));
+        assert!(
+            expected == actual,
+            "snapshot {} changed; rerun with UPDATE_SNAPSHOTS=1 if intended\n--- expected\n{}\n--- actual\n{}",
+            name,
+            expected,
+            actual
+        );
+    }
+
+    fn render(items: &[Item]) -> String {
+        items
+            .iter()
+            .map(|item| match &item.description {
+                Some(description) => format!("  {:?} {} | {}\n", item.item_type, item.name, description),
+                None => format!("  {:?} {}\n", item.item_type, item.name),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_row_rules() {
+        let rules: Vec<RowRule> = serde_json::from_str(
+            r##"[
+                { "types": ["Process"], "cpu_above": 80, "color": "#e01b24" },
+                { "types": ["SshConnection", "DockerContainer"], "pattern": "prod", "badge": "PROD", "color": "#e5a50a" },
+                { "pattern": "^db-", "badge": "DB" }
+            ]"##,
+        )
+        .unwrap();
+
+        let mut process = Item::new("process:1", "ffmpeg", ItemType::Process);
+        process.metadata.cpu = Some(95.0);
+        assert_eq!(row_style(&rules, &process), (Color::parse("#e01b24"), vec![]));
+        process.metadata.cpu = Some(12.0);
+        assert_eq!(row_style(&rules, &process), (None, vec![]));
+
+        let host = Item::new("ssh:db-prod", "db-prod", ItemType::SshConnection).with_description("admin@10.0.0.5");
+        assert_eq!(row_style(&rules, &host), (Color::parse("#e5a50a"), vec!["PROD", "DB"]));
+        let container = Item::new("docker:web", "web", ItemType::DockerContainer).with_description("nginx | Up | production");
+        assert_eq!(row_style(&rules, &container).1, ["PROD"]);
+        let app = Item::new("app:prod", "Prod Monitor", ItemType::Application);
+        assert_eq!(row_style(&rules, &app), (None, vec![]));
+    }
+
+    #[test]
+    fn test_kiosk_modes() {
+        for mode in [
+            Mode::Apps,
+            Mode::Files,
+            Mode::Calculator,
+            Mode::WebSearch,
+            Mode::Bookmarks,
+        ] {
+            assert!(mode.available_in_kiosk(), "{:?} should stay available", mode);
+        }
+        for mode in [
+            Mode::Processes,
+            Mode::Clipboard,
+            Mode::Bitwarden,
+            Mode::Totp,
+            Mode::Gpg,
+            Mode::Notes,
+            Mode::Snippets,
+            Mode::Todos,
+            Mode::Run,
+            Mode::Ssh,
+            Mode::Tmux,
+            Mode::Packages,
+            Mode::Flatpak,
+            Mode::Docker,
+            Mode::Wifi,
+            Mode::Disks,
+            Mode::Focus,
+            Mode::Ai,
+            Mode::Browse,
+            Mode::Theme,
+            Mode::Profile,
+        ] {
+            assert!(!mode.available_in_kiosk(), "{:?} should be disabled", mode);
+        }
+    }
+
+    #[test]
+    fn test_details_text() {
+        let mut details = None;
+        DetailsText::sync(&mut details, Some(("note:a", "first line\nsecond line")));
+        let text = details.as_mut().unwrap();
+        // Copy all copies the whole source
+        assert_eq!(text.source, "first line\nsecond line");
+
+        // Select text: the editor selects and copies but ignores edits
+        text.selecting = true;
+        text.perform(text_editor::Action::SelectAll);
+        assert_eq!(text.content.selection().as_deref(), Some("first line\nsecond line"));
+        text.perform(text_editor::Action::Edit(text_editor::Edit::Insert('x')));
+        assert_eq!(text.content.text().trim_end(), "first line\nsecond line");
+
+        // Unchanged text keeps the selection, new text replaces the editor
+        DetailsText::sync(&mut details, Some(("note:a", "first line\nsecond line")));
+        let text = details.as_ref().unwrap();
+        assert!(text.selecting);
+        assert!(text.content.selection().is_some());
+        DetailsText::sync(&mut details, Some(("note:a", "first line\nsecond line, streamed")));
+        let text = details.as_ref().unwrap();
+        assert!(text.selecting);
+        assert_eq!(text.source, "first line\nsecond line, streamed");
+        assert_eq!(text.content.selection(), None);
+
+        // Another item starts over, no text clears it
+        DetailsText::sync(&mut details, Some(("note:b", "other")));
+        assert!(!details.as_ref().unwrap().selecting);
+        DetailsText::sync(&mut details, None);
+        assert!(details.is_none());
+    }
+
+    #[test]
+    fn test_mode_from_query() {
+        let queries = [
+            "", "firefox", "w term", "ps fire", "wifi", "vol 50", "cb", "note add milk", "todo add pay rent #home",
+            "agenda", "ssh prod", "tmux", "k8s ctx", "docker web", "e smile", "f report.pdf", "r", "bw github",
+            "ai why is the sky blue", "? hello", "g rust iced", "gh wlaunch", "yt lofi", "= 2^10", "=2+2", "calc",
+            "2 + 2", "sqrt(16)", "x = 5", "foo=bar", "255 in hex", "0x1f", "10 km to mi", "100 f in c", "5 usd to eur",
+            "today + 3 weeks", "date 2026-12-24", "time tokyo", "3pm in berlin", "hash hello", "gen uuid",
+            "u arrow", "qc buy milk", "bm rust", "tab docs", "wiki rust", "proj", "git wlaunch", "map berlin",
+            "route airport", "wine", "theme dark", "rust !gh", "!yt cats", "doctor", "unknownprefix foo",
+        ];
+        let snapshot: String = queries
+            .iter()
+            .map(|query| {
+                let (mode, remainder) = Mode::from_query(query);
+                format!("{:?} => {:?} {:?}\n", query, mode, remainder)
+            })
+            .collect();
+        assert_snapshot("mode_from_query", &snapshot);
+    }
+
+    /// Config with the given number locale, so snapshots don't depend on
+    /// the environment's LANG.
+    fn with_locale(locale: &str) -> Config {
+        let mut config = Config::default();
+        config.numbers.locale = Some(locale.to_string());
+        config
+    }
+
+    #[test]
+    fn test_calculator_items() {
+        let calc = Calculator::new(&with_locale("en_US"));
+        let rates = Rates::builtin();
+        let queries = ["2 + 2", "2 + 3 * 4", "(1 + 2) / 3", "2^10", "10 / 3", "sqrt(16)", "255 in hex", "0x1f", "1 / 0", "2 +"];
+        let snapshot: String = queries
+            .iter()
+            .map(|query| format!("> {}\n{}", query, render(&calc.get_items(query, &rates))))
+            .collect();
+        assert_snapshot("calculator", &snapshot);
+    }
+
+    #[test]
+    fn test_converter_items() {
+        let converter = Converter::new(&with_locale("en_US"));
+        let queries = ["10 km to mi", "100 f in c", "1 gb to mb", "5 kg in lb", "3 h to min", "1 parsec to m", "10 m"];
+        let snapshot: String = queries
+            .iter()
+            .map(|query| format!("> {}\n{}", query, render(&converter.get_items(query))))
+            .collect();
+        assert_snapshot("converter", &snapshot);
+    }
+
+    #[test]
+    fn test_localized_numbers() {
+        let rates = Rates::builtin();
+        let mut snapshot = String::new();
+        for (locale, queries) in [
+            ("en_US", ["1,234.5 * 2", "3.14 * 2", "2^20", "1,5 + 1", "1,234 m to ft"]),
+            ("de_DE", ["1.234,5 * 2", "3,14 * 2", "2^20", "1.5 + 1", "1.234 m to ft"]),
+            ("fr_FR", ["1\u{202f}234,5 * 2", "3,14 * 2", "2^20", "0,1 + 0,2", "2,5 km to mi"]),
+        ] {
+            let config = with_locale(locale);
+            let calc = Calculator::new(&config);
+            let converter = Converter::new(&config);
+            for query in queries {
+                let mut items = calc.get_items(query, &rates);
+                items.extend(converter.get_items(query));
+                snapshot.push_str(&format!("> [{}] {}\n{}", locale, query, render(&items)));
+            }
+        }
+        assert_snapshot("localized_numbers", &snapshot);
+    }
+
+    #[test]
+    fn test_ranking() {
+        let app = |name: &str, description: &str, keywords: &[&str]| {
+            Item::new(format!("app:{}", name), name, ItemType::Application)
+                .with_description(description)
+                .with_keywords(keywords.iter().map(|k| k.to_string()).collect())
+        };
+        let candidates = vec![
+            app("Firefox", "Web Browser", &["internet", "www"]),
+            app("Files", "Access and organize files", &["folder", "manager", "explorer"]),
+            app("Terminal", "Use the command line", &["shell", "prompt", "command"]),
+            app("Text Editor", "Edit text files", &["notepad", "write", "plain"]),
+            app("Settings", "Configure the system", &["preferences", "control"]),
+            app("Thunderbird", "Read and write mail", &["email", "mail"]),
+            app("Calculator", "Perform arithmetic", &["math", "calc"]),
+            Item::new("ssh:sshconfig:prod-db", "prod-db", ItemType::SshConnection).with_description("root@10.0.0.5:22"),
+            Item::new("snippet:sig", "sig", ItemType::Snippet).with_description("Email signature"),
+        ];
+
+        let mut snapshot = String::new();
+        for query in ["f", "fi", "te", "ed", "mail", "set", "db", "calc", "zzz"] {
+            let items = rank_items(&candidates, query, |_| false);
+            snapshot.push_str(&format!("> {}\n{}", query, render(&items)));
+        }
+        // A context rule boosting SSH lifts hosts above better app matches
+        let items = rank_items(&candidates, "d", |item| item.item_type == ItemType::SshConnection);
+        snapshot.push_str(&format!("> d (ssh boosted)\n{}", render(&items)));
+        assert_snapshot("ranking", &snapshot);
+    }
+}